@@ -0,0 +1,210 @@
+//! # pysysaudit
+//!
+//! Python bindings for [`sysaudit`]: run a local audit from Python and work
+//! with the report as Python objects, instead of shelling out to the CLI
+//! and parsing its output.
+//!
+//! ```python
+//! import pysysaudit
+//!
+//! report = pysysaudit.LocalScanner(sections="software,updates").scan()
+//! print(report.host_name, len(report.software))
+//! for finding in report.findings():
+//!     print(finding["severity"], finding["title"])
+//! ```
+//!
+//! Sections come back as plain `dict`s and `list`s with the same keys as
+//! the JSON report. Scans release the GIL while they run.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::time::Duration;
+use sysaudit::{BlockingScanner, Finding, RedactionPolicy, ScanOptions};
+
+create_exception!(
+    pysysaudit,
+    ScanError,
+    PyException,
+    "A scan failed, or a report could not be read."
+);
+
+/// Scanner for this machine.
+#[pyclass(name = "LocalScanner", module = "pysysaudit", frozen)]
+struct PyLocalScanner {
+    options: ScanOptions,
+}
+
+#[pymethods]
+impl PyLocalScanner {
+    /// `sections`: comma-separated list as for `sysaudit all --only`;
+    /// `timeout`: seconds each collector may run; `anonymize`: hash user
+    /// names, host names, MAC and IP addresses with `salt`.
+    #[new]
+    #[pyo3(signature = (sections=None, timeout=None, anonymize=false, salt=None))]
+    fn new(
+        sections: Option<&str>,
+        timeout: Option<f64>,
+        anonymize: bool,
+        salt: Option<String>,
+    ) -> PyResult<Self> {
+        let mut options = ScanOptions::new();
+        if let Some(sections) = sections {
+            options = options
+                .only(sections)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        if let Some(timeout) = timeout {
+            let timeout = Duration::try_from_secs_f64(timeout)
+                .map_err(|e| PyValueError::new_err(format!("invalid timeout: {}", e)))?;
+            options = options.timeout(timeout);
+        }
+        if anonymize {
+            options = options.redaction(RedactionPolicy::hash_all().salt(salt.unwrap_or_default()));
+        }
+        Ok(PyLocalScanner { options })
+    }
+
+    /// Run the scan, blocking until it completes.
+    fn scan(&self, py: Python<'_>) -> PyResult<PyAuditReport> {
+        let scanner = sysaudit::LocalScanner::with_options(self.options.clone());
+        let report = py
+            .allow_threads(|| scanner.scan_blocking())
+            .map_err(|e| ScanError::new_err(e.to_string()))?;
+        Ok(PyAuditReport { inner: report })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("LocalScanner(sections={:?})", self.options.selected())
+    }
+}
+
+/// Audit report of one host.
+#[pyclass(name = "AuditReport", module = "pysysaudit", frozen)]
+struct PyAuditReport {
+    inner: sysaudit::AuditReport,
+}
+
+#[pymethods]
+impl PyAuditReport {
+    /// Read a stored report of any supported schema version.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        sysaudit::AuditReport::from_json_versioned(json)
+            .map(|inner| PyAuditReport { inner })
+            .map_err(|e| ScanError::new_err(e.to_string()))
+    }
+
+    /// The report as a JSON document.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.inner).map_err(|e| ScanError::new_err(e.to_string()))
+    }
+
+    /// The whole report as a `dict`.
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner)
+    }
+
+    /// Findings derived from the report, as `dict`s.
+    fn findings(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &Finding::collect(&self.inner))
+    }
+
+    #[getter]
+    fn schema_version(&self) -> u32 {
+        self.inner.schema_version
+    }
+
+    #[getter]
+    fn host_name(&self) -> &str {
+        &self.inner.system.host_name
+    }
+
+    /// Time the report was produced, as an RFC 3339 string.
+    #[getter]
+    fn timestamp(&self) -> String {
+        self.inner.timestamp.to_rfc3339()
+    }
+
+    #[getter]
+    fn system(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.system)
+    }
+
+    #[getter]
+    fn software(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.software)
+    }
+
+    #[getter]
+    fn industrial(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.industrial)
+    }
+
+    #[getter]
+    fn updates(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.updates)
+    }
+
+    #[getter]
+    fn services(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.services)
+    }
+
+    /// Security settings, or `None` if they were not collected.
+    #[getter]
+    fn security(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.security)
+    }
+
+    #[getter]
+    fn warnings(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.warnings)
+    }
+
+    /// Audit score with its domain breakdown, or `None`.
+    #[getter]
+    fn score(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.score)
+    }
+
+    /// Sections added by custom collectors.
+    #[getter]
+    fn extensions(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.extensions)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AuditReport(host_name={:?}, software={}, timestamp={:?})",
+            self.inner.system.host_name,
+            self.inner.software.len(),
+            self.inner.timestamp.to_rfc3339()
+        )
+    }
+}
+
+/// Scan this machine with the default options.
+#[pyfunction]
+#[pyo3(signature = (sections=None))]
+fn scan(py: Python<'_>, sections: Option<&str>) -> PyResult<PyAuditReport> {
+    PyLocalScanner::new(sections, None, false, None)?.scan(py)
+}
+
+/// `value` as Python objects, going through its JSON form so the keys match
+/// the report documents.
+fn to_python(py: Python<'_>, value: &impl Serialize) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| ScanError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+#[pymodule]
+fn pysysaudit(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyLocalScanner>()?;
+    m.add_class::<PyAuditReport>()?;
+    m.add_function(wrap_pyfunction!(scan, m)?)?;
+    m.add("ScanError", m.py().get_type::<ScanError>())?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
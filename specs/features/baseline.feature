@@ -0,0 +1,25 @@
+Feature: Baseline Management
+  As a sysaudit user
+  I want to approve a report as a baseline
+  So that I can detect deviations from a validated state
+
+  Scenario: Save and load a baseline
+    Given a baseline store in a temporary directory
+    When the report is saved as baseline "line3"
+    Then baseline "line3" is listed
+    And baseline "line3" loads with the saved host name
+
+  Scenario: Host matches its baseline
+    Given the report approved as a baseline
+    When the unchanged host is checked against the baseline
+    Then no deviations are reported
+
+  Scenario: Host deviates from its baseline
+    Given the report approved as a baseline
+    When a host with extra software is checked against the baseline
+    Then a deviation names the extra software
+
+  Scenario: Unknown baseline
+    Given a baseline store in a temporary directory
+    When baseline "missing" is loaded
+    Then loading the baseline fails
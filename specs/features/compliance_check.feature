@@ -0,0 +1,22 @@
+Feature: Compliance Check
+  As a sysaudit user
+  I want to evaluate a report against a policy
+  So that I can gate hosts on compliance rules
+
+  Scenario: Policy passes
+    Given a policy forbidding software published by "TeamViewer"
+    When the policy is evaluated against the report
+    Then the policy passes
+    And the compliance score is 100
+
+  Scenario: Policy fails
+    Given a policy requiring BitLocker on the system drive
+    When the policy is evaluated against the report
+    Then the policy fails
+    And rule "bitlocker" is listed as a failure
+
+  Scenario: CIS hardening pack
+    Given the built-in CIS policy
+    When the policy is evaluated against the report
+    Then every CIS rule has a result
+    And the compliance score is between 0 and 100
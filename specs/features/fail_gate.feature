@@ -0,0 +1,29 @@
+Feature: Fail Gate
+  As a sysaudit user
+  I want scans to fail on configured conditions
+  So that CI and fleet jobs can act on the exit code
+
+  Scenario: Industrial software fails the gate
+    Given the fail condition "industrial"
+    When the condition is checked against the report
+    Then the condition is met
+    And the exit code is 10
+
+  Scenario: Recent updates pass the gate
+    Given the fail condition "missing-updates>365"
+    When the condition is checked against the report
+    Then the condition is not met
+
+  Scenario: Stale updates fail the gate
+    Given the fail condition "missing-updates>30"
+    When the condition is checked against the report
+    Then the condition is met
+    And the exit code is 11
+
+  Scenario: Exit codes per condition
+    Given every fail condition
+    Then the exit codes are 10, 11, 12, 13 and 14
+
+  Scenario: Unknown condition
+    Given the fail condition text "disk-full"
+    Then parsing the condition fails
@@ -0,0 +1,25 @@
+Feature: Report Diff
+  As a sysaudit user
+  I want to compare two reports of the same host
+  So that I can see what changed between scans
+
+  Scenario: Unchanged host
+    Given two scans of an unchanged host
+    When the scans are diffed
+    Then the diff is empty
+
+  Scenario: Software installed
+    Given a later scan with "Notepad++" installed
+    When the scans are diffed
+    Then the diff lists "Notepad++" as added
+    And the inventory is reported as changed
+
+  Scenario: Software upgraded
+    Given a later scan with Firefox upgraded to "128.0"
+    When the scans are diffed
+    Then the diff lists Firefox as changed from "115.0.2" to "128.0"
+
+  Scenario: Software removed
+    Given a later scan without Firefox
+    When the scans are diffed
+    Then the diff lists Firefox as removed
@@ -23,3 +23,69 @@ fn output_formats_feature() {}
 
 #[scenario(path = "features/error_handling.feature")]
 fn error_handling_feature() {}
+
+// `#[scenario]` binds only the first scenario of a feature unless `name`
+// selects one, so features with several scenarios bind each by name.
+
+#[scenario(path = "features/report_diff.feature", name = "Unchanged host")]
+fn report_diff_unchanged_host() {}
+
+#[scenario(path = "features/report_diff.feature", name = "Software installed")]
+fn report_diff_software_installed() {}
+
+#[scenario(path = "features/report_diff.feature", name = "Software upgraded")]
+fn report_diff_software_upgraded() {}
+
+#[scenario(path = "features/report_diff.feature", name = "Software removed")]
+fn report_diff_software_removed() {}
+
+#[scenario(path = "features/baseline.feature", name = "Save and load a baseline")]
+fn baseline_save_and_load_a_baseline() {}
+
+#[scenario(path = "features/baseline.feature", name = "Host matches its baseline")]
+fn baseline_host_matches_its_baseline() {}
+
+#[scenario(
+    path = "features/baseline.feature",
+    name = "Host deviates from its baseline"
+)]
+fn baseline_host_deviates_from_its_baseline() {}
+
+#[scenario(path = "features/baseline.feature", name = "Unknown baseline")]
+fn baseline_unknown_baseline() {}
+
+#[scenario(path = "features/compliance_check.feature", name = "Policy passes")]
+fn compliance_check_policy_passes() {}
+
+#[scenario(path = "features/compliance_check.feature", name = "Policy fails")]
+fn compliance_check_policy_fails() {}
+
+#[scenario(
+    path = "features/compliance_check.feature",
+    name = "CIS hardening pack"
+)]
+fn compliance_check_cis_hardening_pack() {}
+
+#[scenario(
+    path = "features/fail_gate.feature",
+    name = "Industrial software fails the gate"
+)]
+fn fail_gate_industrial_software_fails_the_gate() {}
+
+#[scenario(
+    path = "features/fail_gate.feature",
+    name = "Recent updates pass the gate"
+)]
+fn fail_gate_recent_updates_pass_the_gate() {}
+
+#[scenario(
+    path = "features/fail_gate.feature",
+    name = "Stale updates fail the gate"
+)]
+fn fail_gate_stale_updates_fail_the_gate() {}
+
+#[scenario(path = "features/fail_gate.feature", name = "Exit codes per condition")]
+fn fail_gate_exit_codes_per_condition() {}
+
+#[scenario(path = "features/fail_gate.feature", name = "Unknown condition")]
+fn fail_gate_unknown_condition() {}
@@ -0,0 +1,116 @@
+use super::fixture_report;
+use rstest_bdd_macros::{given, when, then};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use sysaudit::{Baseline, BaselineStore, Error, Finding};
+
+thread_local! {
+    static STORE: RefCell<Option<BaselineStore>> = const { RefCell::new(None) };
+    static BASELINE: RefCell<Option<Baseline>> = const { RefCell::new(None) };
+    static DEVIATIONS: RefCell<Vec<Finding>> = const { RefCell::new(Vec::new()) };
+    static LOADED: RefCell<Option<Result<Baseline, Error>>> = const { RefCell::new(None) };
+}
+
+const EXTRA: &str = "TeamViewer";
+
+/// Scenarios run in parallel; each store gets its own directory.
+static STORES: AtomicUsize = AtomicUsize::new(0);
+
+fn with_store<T>(f: impl FnOnce(&BaselineStore) -> T) -> T {
+    STORE.with(|s| f(s.borrow().as_ref().expect("a baseline store was given")))
+}
+
+fn check(change: impl FnOnce(&mut sysaudit::AuditReport)) {
+    let mut current = fixture_report();
+    change(&mut current);
+    let deviations = BASELINE.with(|b| {
+        b.borrow()
+            .as_ref()
+            .expect("a baseline was given")
+            .check(&current)
+    });
+    DEVIATIONS.with(|d| *d.borrow_mut() = deviations);
+}
+
+#[given("a baseline store in a temporary directory")]
+fn given_baseline_store() {
+    let dir = std::env::temp_dir().join(format!(
+        "sysaudit-specs-baselines-{}-{}",
+        std::process::id(),
+        STORES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    STORE.with(|s| *s.borrow_mut() = Some(BaselineStore::new(dir)));
+}
+
+#[when("the report is saved as baseline \"line3\"")]
+fn when_saved_line3() {
+    with_store(|store| store.save("line3", &fixture_report())).unwrap();
+}
+
+#[then("baseline \"line3\" is listed")]
+fn then_line3_listed() {
+    assert_eq!(with_store(|store| store.list()).unwrap(), ["line3"]);
+}
+
+#[then("baseline \"line3\" loads with the saved host name")]
+fn then_line3_loads() {
+    let baseline = with_store(|store| store.load("line3")).unwrap();
+    assert_eq!(baseline.name, "line3");
+    assert_eq!(
+        baseline.report.system.host_name,
+        fixture_report().system.host_name
+    );
+    let _ = std::fs::remove_dir_all(with_store(|store| store.dir().to_path_buf()));
+}
+
+#[given("the report approved as a baseline")]
+fn given_approved_baseline() {
+    BASELINE.with(|b| *b.borrow_mut() = Some(Baseline::new("approved", fixture_report())));
+}
+
+#[when("the unchanged host is checked against the baseline")]
+fn when_unchanged_checked() {
+    check(|_| {});
+}
+
+#[when("a host with extra software is checked against the baseline")]
+fn when_extra_software_checked() {
+    check(|report| {
+        let mut extra = report.software[0].clone();
+        extra.name = EXTRA.to_string();
+        report.software.push(extra);
+    });
+}
+
+#[then("no deviations are reported")]
+fn then_no_deviations() {
+    DEVIATIONS.with(|d| assert!(d.borrow().is_empty(), "{:?}", d.borrow()));
+}
+
+#[then("a deviation names the extra software")]
+fn then_deviation_names_extra() {
+    DEVIATIONS.with(|d| {
+        assert!(
+            d.borrow().iter().any(|f| f.title.contains(EXTRA)),
+            "{:?}",
+            d.borrow()
+        )
+    });
+}
+
+#[when("baseline \"missing\" is loaded")]
+fn when_missing_loaded() {
+    let loaded = with_store(|store| store.load("missing"));
+    LOADED.with(|l| *l.borrow_mut() = Some(loaded));
+}
+
+#[then("loading the baseline fails")]
+fn then_loading_fails() {
+    LOADED.with(|l| {
+        assert!(matches!(
+            l.borrow().as_ref().expect("a baseline was loaded"),
+            Err(Error::General(_))
+        ))
+    });
+}
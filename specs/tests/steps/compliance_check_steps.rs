@@ -0,0 +1,88 @@
+use super::fixture_report;
+use rstest_bdd_macros::{given, when, then};
+use std::cell::RefCell;
+use sysaudit::compliance::{Check, Rule};
+use sysaudit::{ComplianceReport, Policy, Severity};
+
+thread_local! {
+    static POLICY: RefCell<Option<Policy>> = const { RefCell::new(None) };
+    static RESULT: RefCell<Option<ComplianceReport>> = const { RefCell::new(None) };
+}
+
+fn given_policy(id: &str, check: Check) {
+    let policy = Policy {
+        name: "specs".to_string(),
+        rules: vec![Rule {
+            id: id.to_string(),
+            title: None,
+            severity: Severity::High,
+            check,
+        }],
+    };
+    POLICY.with(|p| *p.borrow_mut() = Some(policy));
+}
+
+fn with_result<T>(f: impl FnOnce(&ComplianceReport) -> T) -> T {
+    RESULT.with(|r| f(r.borrow().as_ref().expect("the policy was evaluated")))
+}
+
+#[given("a policy forbidding software published by \"TeamViewer\"")]
+fn given_forbid_teamviewer() {
+    given_policy(
+        "no-teamviewer",
+        Check::ForbidPublisher("TeamViewer".to_string()),
+    );
+}
+
+#[given("a policy requiring BitLocker on the system drive")]
+fn given_require_bitlocker() {
+    given_policy("bitlocker", Check::Bitlocker(true));
+}
+
+#[given("the built-in CIS policy")]
+fn given_cis_policy() {
+    POLICY.with(|p| *p.borrow_mut() = Some(Policy::cis()));
+}
+
+#[when("the policy is evaluated against the report")]
+fn when_policy_evaluated() {
+    let result = POLICY.with(|p| {
+        p.borrow()
+            .as_ref()
+            .expect("a policy was given")
+            .evaluate(&fixture_report())
+    });
+    RESULT.with(|r| *r.borrow_mut() = Some(result));
+}
+
+#[then("the policy passes")]
+fn then_policy_passes() {
+    assert!(with_result(ComplianceReport::passed));
+}
+
+#[then("the policy fails")]
+fn then_policy_fails() {
+    assert!(!with_result(ComplianceReport::passed));
+}
+
+#[then("the compliance score is 100")]
+fn then_score_100() {
+    assert_eq!(with_result(|r| r.score), Some(100));
+}
+
+#[then("rule \"bitlocker\" is listed as a failure")]
+fn then_bitlocker_failure() {
+    assert!(with_result(|r| r.failures().any(|f| f.id == "bitlocker")));
+}
+
+#[then("every CIS rule has a result")]
+fn then_every_cis_rule() {
+    let rules = Policy::cis().rules.len();
+    assert_eq!(with_result(|r| r.results.len()), rules);
+}
+
+#[then("the compliance score is between 0 and 100")]
+fn then_score_in_range() {
+    let score = with_result(|r| r.score).expect("the report has security data");
+    assert!(score <= 100);
+}
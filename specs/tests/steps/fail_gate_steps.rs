@@ -0,0 +1,100 @@
+use super::fixture_report;
+use rstest_bdd_macros::{given, when, then};
+use std::cell::RefCell;
+use sysaudit::gate::Violation;
+use sysaudit::{Error, FailCondition};
+
+thread_local! {
+    static CONDITION: RefCell<Option<FailCondition>> = const { RefCell::new(None) };
+    static VIOLATION: RefCell<Option<Violation>> = const { RefCell::new(None) };
+    static PARSED: RefCell<Option<Result<FailCondition, Error>>> = const { RefCell::new(None) };
+}
+
+fn given_condition(text: &str) {
+    let condition = text.parse().unwrap();
+    CONDITION.with(|c| *c.borrow_mut() = Some(condition));
+}
+
+fn condition() -> FailCondition {
+    CONDITION.with(|c| c.borrow().expect("a fail condition was given"))
+}
+
+fn exit_code_is(code: i32) {
+    let violation = VIOLATION
+        .with(|v| v.borrow().clone())
+        .expect("the condition is met");
+    assert_eq!(violation.condition.exit_code(), code);
+}
+
+#[given("the fail condition \"industrial\"")]
+fn given_industrial() {
+    given_condition("industrial");
+}
+
+#[given("the fail condition \"missing-updates>365\"")]
+fn given_missing_updates_365() {
+    given_condition("missing-updates>365");
+}
+
+#[given("the fail condition \"missing-updates>30\"")]
+fn given_missing_updates_30() {
+    given_condition("missing-updates>30");
+}
+
+#[when("the condition is checked against the report")]
+fn when_condition_checked() {
+    let violation = condition().check(&fixture_report());
+    VIOLATION.with(|v| *v.borrow_mut() = violation);
+}
+
+#[then("the condition is met")]
+fn then_condition_met() {
+    VIOLATION.with(|v| assert!(v.borrow().is_some()));
+}
+
+#[then("the condition is not met")]
+fn then_condition_not_met() {
+    VIOLATION.with(|v| assert!(v.borrow().is_none(), "{:?}", v.borrow()));
+}
+
+#[then("the exit code is 10")]
+fn then_exit_code_10() {
+    exit_code_is(10);
+}
+
+#[then("the exit code is 11")]
+fn then_exit_code_11() {
+    exit_code_is(11);
+}
+
+#[given("every fail condition")]
+fn given_every_condition() {}
+
+#[then("the exit codes are 10, 11, 12, 13 and 14")]
+fn then_exit_codes() {
+    let codes: Vec<i32> = FailCondition::NAMES
+        .iter()
+        .map(|name| {
+            name.replace('N', "1")
+                .parse::<FailCondition>()
+                .unwrap()
+                .exit_code()
+        })
+        .collect();
+    assert_eq!(codes, [10, 11, 12, 13, 14]);
+}
+
+#[given("the fail condition text \"disk-full\"")]
+fn given_disk_full() {
+    PARSED.with(|p| *p.borrow_mut() = Some("disk-full".parse()));
+}
+
+#[then("parsing the condition fails")]
+fn then_parsing_fails() {
+    PARSED.with(|p| {
+        assert!(matches!(
+            p.borrow().as_ref().expect("a condition was parsed"),
+            Err(Error::General(_))
+        ))
+    });
+}
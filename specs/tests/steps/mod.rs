@@ -6,3 +6,17 @@ pub mod updates_steps;
 pub mod cli_steps;
 pub mod output_steps;
 pub mod error_steps;
+pub mod report_diff_steps;
+pub mod baseline_steps;
+pub mod compliance_check_steps;
+pub mod fail_gate_steps;
+
+/// The report fixture shared with the `sysaudit` unit tests: an HMI with
+/// Firefox, FactoryTalk View and one update from January 2024, scanned on
+/// 2024-06-01.
+pub fn fixture_report() -> sysaudit::AuditReport {
+    sysaudit::AuditReport::from_json_versioned(include_str!(
+        "../../../sysaudit/tests/fixtures/report.json"
+    ))
+    .unwrap()
+}
@@ -0,0 +1,110 @@
+use super::fixture_report;
+use rstest_bdd_macros::{given, when, then};
+use std::cell::RefCell;
+use sysaudit::AuditReport;
+use sysaudit::report::{ChangeKind, ItemChange, ReportDiff, diff};
+
+thread_local! {
+    static SCANS: RefCell<Option<(AuditReport, AuditReport)>> = const { RefCell::new(None) };
+    static DIFF: RefCell<Option<ReportDiff>> = const { RefCell::new(None) };
+}
+
+const FIREFOX: &str = "Mozilla Firefox (x64 en-US)";
+
+/// Store the fixture as the earlier scan and `change` applied to it as the
+/// later one.
+fn scans(change: impl FnOnce(&mut AuditReport)) {
+    let old = fixture_report();
+    let mut new = old.clone();
+    change(&mut new);
+    SCANS.with(|s| *s.borrow_mut() = Some((old, new)));
+}
+
+fn software_change(name: &str) -> ItemChange {
+    DIFF.with(|d| {
+        d.borrow()
+            .as_ref()
+            .expect("scans were diffed")
+            .software
+            .iter()
+            .find(|c| c.name == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("{} is not in the diff", name))
+    })
+}
+
+#[given("two scans of an unchanged host")]
+fn given_unchanged_host() {
+    scans(|_| {});
+}
+
+#[given("a later scan with \"Notepad++\" installed")]
+fn given_notepad_installed() {
+    scans(|report| {
+        let mut notepad = report.software[0].clone();
+        notepad.name = "Notepad++".to_string();
+        notepad.version = Some("8.6.9".to_string());
+        report.software.push(notepad);
+    });
+}
+
+#[given("a later scan with Firefox upgraded to \"128.0\"")]
+fn given_firefox_upgraded() {
+    scans(|report| {
+        for sw in report.software.iter_mut().filter(|sw| sw.name == FIREFOX) {
+            sw.version = Some("128.0".to_string());
+        }
+    });
+}
+
+#[given("a later scan without Firefox")]
+fn given_firefox_removed() {
+    scans(|report| report.software.retain(|sw| sw.name != FIREFOX));
+}
+
+#[when("the scans are diffed")]
+fn when_scans_diffed() {
+    let changes = SCANS.with(|s| {
+        let scans = s.borrow();
+        let (old, new) = scans.as_ref().expect("two scans were given");
+        diff(old, new)
+    });
+    DIFF.with(|d| *d.borrow_mut() = Some(changes));
+}
+
+#[then("the diff is empty")]
+fn then_diff_empty() {
+    DIFF.with(|d| assert!(d.borrow().as_ref().expect("scans were diffed").is_empty()));
+}
+
+#[then("the diff lists \"Notepad++\" as added")]
+fn then_notepad_added() {
+    let change = software_change("Notepad++");
+    assert_eq!(change.kind, ChangeKind::Added);
+    assert_eq!(change.new_version.as_deref(), Some("8.6.9"));
+}
+
+#[then("the inventory is reported as changed")]
+fn then_inventory_changed() {
+    DIFF.with(|d| {
+        assert!(
+            d.borrow()
+                .as_ref()
+                .expect("scans were diffed")
+                .inventory_changed()
+        )
+    });
+}
+
+#[then("the diff lists Firefox as changed from \"115.0.2\" to \"128.0\"")]
+fn then_firefox_changed() {
+    let change = software_change(FIREFOX);
+    assert_eq!(change.kind, ChangeKind::Changed);
+    assert_eq!(change.old_version.as_deref(), Some("115.0.2"));
+    assert_eq!(change.new_version.as_deref(), Some("128.0"));
+}
+
+#[then("the diff lists Firefox as removed")]
+fn then_firefox_removed() {
+    assert_eq!(software_change(FIREFOX).kind, ChangeKind::Removed);
+}
@@ -0,0 +1,65 @@
+//! `sysaudit-agent`: the native collector pushed to remote hosts.
+//!
+//! `RemoteScanner` in agent mode copies this binary to the target, runs
+//! `sysaudit-agent --json --sections <list>` and reads the report from
+//! stdout, as gzipped `SYSAUDIT-CHUNK` lines like the PowerShell payload
+//! writes, since WinRM truncates large output streams. Build it with a
+//! static CRT so it runs on hosts without the VC++ redistributable:
+//!
+//! ```text
+//! RUSTFLAGS="-C target-feature=+crt-static" cargo build --release --bin sysaudit-agent
+//! ```
+
+use clap::Parser;
+use std::process::ExitCode;
+use std::time::Duration;
+use sysaudit::remote::payload::encode_output;
+use sysaudit::{BlockingScanner, LocalScanner, ScanOptions};
+
+/// Collect a full audit report of this host.
+#[derive(Parser)]
+#[command(name = "sysaudit-agent", version)]
+struct Args {
+    /// Write the report to stdout as gzipped JSON chunk lines
+    #[arg(long)]
+    json: bool,
+
+    /// Sections to collect, as in `sysaudit all --only`
+    #[arg(long, default_value = "system,software,industrial,updates")]
+    sections: String,
+
+    /// Seconds each collector may run before its section is left empty
+    #[arg(long, default_value_t = 120)]
+    collector_timeout: u64,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if !args.json {
+        eprintln!("sysaudit-agent: only --json output is supported");
+        return ExitCode::from(2);
+    }
+
+    match collect(&args.sections, Duration::from_secs(args.collector_timeout)) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("sysaudit-agent: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn collect(
+    sections: &str,
+    collector_timeout: Duration,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let options = ScanOptions::new()
+        .timeout(collector_timeout)
+        .only(sections)?;
+    let scanner = LocalScanner::with_options(options);
+    let report = scanner.scan_blocking()?;
+    Ok(encode_output(&serde_json::to_string(&report)?)?)
+}
@@ -0,0 +1,258 @@
+//! `sysaudit serve-api`: on-demand scans over an authenticated HTTPS API.
+//!
+//! Unlike `serve`, which hands out a cached report of this host, every
+//! `POST` runs a fresh scan, so a web dashboard can use sysaudit as its
+//! collection backend. With an inventory, hosts listed in it can be
+//! scanned through this server over WinRM; no other host is reachable.
+//! Every endpoint except `/health` requires `Authorization: Bearer <token>`.
+//!
+//! | Request | Body |
+//! |:---|:---|
+//! | `GET /health` | `{"status":"ok"}` (no authentication) |
+//! | `POST /scan` | Report of this host |
+//! | `GET /hosts` | Inventory hosts available for remote scans |
+//! | `POST /hosts/<host>/scan` | Report of an inventory host |
+//!
+//! Scans take `?only=` and `?skip=` section lists, as in `sysaudit all`.
+
+use secrecy::SecretString;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use sysaudit::fleet::{FleetScanner, FleetTarget};
+use sysaudit::serve::{authorized, check_token, json_response, serve_https};
+use sysaudit::{AuthMethod, BlockingScanner, LocalScanner, ScanOptions};
+use tiny_http::{Method, Request, Response};
+
+/// Hosts that may be scanned through the API and how to reach them.
+pub struct RemoteHosts {
+    /// Inventory hosts
+    pub targets: Vec<FleetTarget>,
+    /// Username for hosts that do not set their own
+    pub username: Option<String>,
+    /// Password for `username`
+    pub password: Option<SecretString>,
+    /// WinRM authentication
+    pub auth: AuthMethod,
+    /// WinRM port
+    pub port: u16,
+    /// Connect over HTTPS
+    pub use_https: bool,
+}
+
+/// HTTPS API running scans on request.
+pub struct ApiServer {
+    /// Address to listen on
+    pub listen: String,
+    /// PEM certificate chain
+    pub cert: PathBuf,
+    /// PEM private key
+    pub key: PathBuf,
+    /// Bearer token clients must present
+    pub token: SecretString,
+    /// Hosts available for proxied scans, if any
+    pub remote: Option<RemoteHosts>,
+    /// Threads handling requests
+    pub workers: usize,
+}
+
+type JsonResponse = Response<std::io::Cursor<Vec<u8>>>;
+
+/// What a request asks for.
+#[derive(Debug, PartialEq, Eq)]
+enum Route<'a> {
+    Health,
+    ScanLocal,
+    Hosts,
+    ScanRemote(&'a str),
+}
+
+impl<'a> Route<'a> {
+    fn parse(method: &Method, path: &'a str) -> Result<Self, (u16, &'static str)> {
+        let segments: Vec<&str> = path
+            .trim_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        let (route, expected) = match segments.as_slice() {
+            ["health"] => (Route::Health, Method::Get),
+            ["scan"] => (Route::ScanLocal, Method::Post),
+            ["hosts"] => (Route::Hosts, Method::Get),
+            ["hosts", host, "scan"] => (Route::ScanRemote(host), Method::Post),
+            _ => return Err((404, "not found")),
+        };
+        if *method != expected {
+            return Err((405, "method not allowed"));
+        }
+        Ok(route)
+    }
+}
+
+impl ApiServer {
+    /// Listen and serve until the process exits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`sysaudit::Error::Io`] if the certificate or key cannot be
+    /// read, or [`sysaudit::Error::General`] if the token is empty or the
+    /// listener cannot be started.
+    pub fn run(self) -> Result<(), sysaudit::Error> {
+        check_token(&self.token)?;
+        let runtime = tokio::runtime::Runtime::new()?;
+        // Local scans are serialized; concurrent requests wait for the
+        // running one instead of competing for WMI.
+        let local = Mutex::new(());
+        let (listen, cert, key, workers) = (
+            self.listen.clone(),
+            self.cert.clone(),
+            self.key.clone(),
+            self.workers,
+        );
+        serve_https(&listen, &cert, &key, workers, move |request| {
+            self.handle(request, &runtime, &local)
+        })
+    }
+
+    fn handle(&self, request: Request, runtime: &tokio::runtime::Runtime, local: &Mutex<()>) {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let response = match Route::parse(request.method(), path) {
+            Ok(Route::Health) => json_response(200, r#"{"status":"ok"}"#),
+            Ok(route) => {
+                let bearer = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Authorization"))
+                    .map(|h| h.value.as_str().to_string());
+                if authorized(bearer.as_deref(), &self.token) {
+                    self.respond(route, query, runtime, local)
+                } else {
+                    tracing::warn!(
+                        remote = ?request.remote_addr(),
+                        path = %path,
+                        "Rejected unauthenticated request"
+                    );
+                    error_response(401, "unauthorized")
+                }
+            }
+            Err((status, message)) => error_response(status, message),
+        };
+
+        if let Err(e) = request.respond(response) {
+            tracing::debug!(error = %e, "Failed to send response");
+        }
+    }
+
+    fn respond(
+        &self,
+        route: Route<'_>,
+        query: &str,
+        runtime: &tokio::runtime::Runtime,
+        local: &Mutex<()>,
+    ) -> JsonResponse {
+        let options = match scan_options(query) {
+            Ok(options) => options,
+            Err(e) => return error_response(400, &e.to_string()),
+        };
+        match route {
+            Route::Health => json_response(200, r#"{"status":"ok"}"#),
+            Route::ScanLocal => {
+                let _running = local.lock().unwrap_or_else(|e| e.into_inner());
+                tracing::info!("Scanning this host for API request");
+                match LocalScanner::with_options(options).scan_blocking() {
+                    Ok(report) => body_response(serde_json::to_string(&report)),
+                    Err(e) => {
+                        tracing::error!(error = %e, "Local scan failed");
+                        error_response(500, &e.to_string())
+                    }
+                }
+            }
+            Route::Hosts => {
+                let hosts: Vec<_> = self
+                    .remote
+                    .iter()
+                    .flat_map(|remote| &remote.targets)
+                    .map(|t| serde_json::json!({ "host": t.host, "site": t.site, "tags": t.tags }))
+                    .collect();
+                body_response(serde_json::to_string(&hosts))
+            }
+            Route::ScanRemote(host) => self.scan_remote(host, options, runtime),
+        }
+    }
+
+    fn scan_remote(
+        &self,
+        host: &str,
+        options: ScanOptions,
+        runtime: &tokio::runtime::Runtime,
+    ) -> JsonResponse {
+        let Some(remote) = &self.remote else {
+            return error_response(404, "remote scans are not configured (start with --hosts)");
+        };
+        // Only inventory hosts: the API must not become a way to reach
+        // arbitrary machines with the configured credentials.
+        let Some(target) = remote
+            .targets
+            .iter()
+            .find(|t| t.host.eq_ignore_ascii_case(host))
+        else {
+            return error_response(404, &format!("host '{}' is not in the inventory", host));
+        };
+
+        tracing::info!(host = %target.host, "Scanning remote host for API request");
+        let scanner = FleetScanner::builder()
+            .targets(vec![target.clone()])
+            .maybe_username(remote.username.clone())
+            .maybe_password(remote.password.clone())
+            .auth(remote.auth)
+            .port(remote.port)
+            .use_https(remote.use_https)
+            .options(options)
+            .concurrency(1)
+            .build();
+        let result = runtime
+            .block_on(scanner.scan())
+            .into_iter()
+            .next()
+            .map(|host| host.result);
+        match result {
+            Some(Ok(report)) => body_response(serde_json::to_string(&report)),
+            Some(Err(e)) => {
+                tracing::error!(host = %target.host, error = %e, "Remote scan failed");
+                let body = serde_json::json!({ "error": e.to_string(), "kind": e.kind() });
+                json_response(502, &body.to_string())
+            }
+            None => error_response(500, "no result"),
+        }
+    }
+}
+
+/// Scan options from `only=` and `skip=` query parameters.
+fn scan_options(query: &str) -> Result<ScanOptions, sysaudit::Error> {
+    let mut options = ScanOptions::new();
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = value.replace("%2C", ",").replace("%2c", ",");
+        options = match name {
+            "only" => options.only(&value)?,
+            "skip" => options.skip(&value)?,
+            _ => {
+                return Err(sysaudit::Error::General(format!(
+                    "unknown query parameter '{}'",
+                    name
+                )));
+            }
+        };
+    }
+    Ok(options)
+}
+
+fn body_response(body: serde_json::Result<String>) -> JsonResponse {
+    match body {
+        Ok(body) => json_response(200, &body),
+        Err(e) => error_response(500, &e.to_string()),
+    }
+}
+
+fn error_response(status: u16, message: &str) -> JsonResponse {
+    json_response(status, &serde_json::json!({ "error": message }).to_string())
+}
@@ -0,0 +1,123 @@
+//! Log output: `--verbose`, `--quiet`, `--log-level` and `--log-file`.
+//!
+//! Events go to stderr and, with `--log-file`, to a file that rolls over
+//! daily (`<name>.<YYYY-MM-DD>`). Without any flag, `RUST_LOG` is honored;
+//! otherwise debug builds log at debug and release builds only errors.
+
+use clap::Args;
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::prelude::*;
+
+/// Target of events for the `--log-file` only, such as errors the caller
+/// already printed to stderr.
+pub const FILE_ONLY: &str = "log_file";
+
+/// Logging flags, accepted by every subcommand.
+#[derive(Args)]
+pub struct LogArgs {
+    /// More log output: -v info, -vv debug, -vvv trace
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// No log output on the console; errors still print, and the
+    /// --log-file still gets it
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log level, overriding -v: error, warn, info, debug, trace
+    #[arg(long, global = true, value_parser = ["error", "warn", "info", "debug", "trace"])]
+    log_level: Option<String>,
+
+    /// Also write logs to this file, rolled over daily
+    #[arg(long, global = true)]
+    log_file: Option<PathBuf>,
+}
+
+impl LogArgs {
+    /// Level from the flags, if any was given.
+    fn level(&self) -> Option<&str> {
+        if let Some(level) = &self.log_level {
+            return Some(level);
+        }
+        match self.verbose {
+            0 => None,
+            1 => Some("info"),
+            2 => Some("debug"),
+            _ => Some("trace"),
+        }
+    }
+
+    /// Console filter: the flags, then `RUST_LOG`, then the build default.
+    fn console_filter(&self) -> EnvFilter {
+        if self.quiet {
+            return EnvFilter::new("off");
+        }
+        match self.level() {
+            Some(level) => EnvFilter::new(level),
+            None => EnvFilter::try_from_default_env().unwrap_or_else(|_| default_filter()),
+        }
+    }
+
+    /// Install the subscriber. Keep the returned guard alive until exit so
+    /// buffered file output is flushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`sysaudit::Error::Io`] if the log directory cannot be
+    /// created.
+    pub fn init(&self) -> Result<Option<WorkerGuard>, sysaudit::Error> {
+        let console = tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(false)
+            .with_filter(self.console_filter())
+            .with_filter(filter_fn(|meta| meta.target() != FILE_ONLY));
+
+        let (file, guard) = match &self.log_file {
+            Some(path) => {
+                let (dir, name) = split_log_path(path);
+                std::fs::create_dir_all(dir)?;
+                let appender = tracing_appender::rolling::daily(dir, name);
+                let (writer, guard) = tracing_appender::non_blocking(appender);
+                // The file is for troubleshooting: info by default, even
+                // when the console is quiet.
+                let level = self.level().unwrap_or("info");
+                let layer = tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_ansi(false)
+                    .with_filter(EnvFilter::new(level));
+                (Some(layer), Some(guard))
+            }
+            None => (None, None),
+        };
+
+        tracing_subscriber::registry()
+            .with(console)
+            .with(file)
+            .init();
+        Ok(guard)
+    }
+}
+
+/// Debug builds show debug and above, release builds errors only.
+fn default_filter() -> EnvFilter {
+    if cfg!(debug_assertions) {
+        EnvFilter::new("debug")
+    } else {
+        EnvFilter::new("error")
+    }
+}
+
+/// Directory and file name of a `--log-file` path.
+fn split_log_path(path: &Path) -> (&Path, &std::ffi::OsStr) {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .unwrap_or(std::ffi::OsStr::new("sysaudit.log"));
+    (dir, name)
+}
@@ -3,28 +3,965 @@
 //! A command-line tool to audit Windows system configuration,
 //! installed software, and Windows Update patches.
 
-use clap::{Parser, Subcommand};
+#[cfg(feature = "api")]
+mod api;
+mod logging;
+mod service;
+
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use secrecy::SecretString;
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::time::Duration;
+use sysaudit::allowlist::Allowlist;
+use sysaudit::fleet::{FleetScanner, FleetState};
+use sysaudit::gate;
+use sysaudit::history::{HistoryDb, HistoryMetric};
+use sysaudit::inventory::Inventory;
+use sysaudit::privileges;
+use sysaudit::query::ReportQuery;
+use sysaudit::report::{ReportDiff, diff};
+use sysaudit::score::ScoreWeights;
+use sysaudit::serve::ReportServer;
+use sysaudit::updates;
+use sysaudit::vuln::VulnDatabase;
+use sysaudit::watch::{ChangeWatcher, ReportHistory, SETTLE_TIME, parse_interval};
 use sysaudit::{
-    IndustrialScanner, SoftwareScanner, SystemInfo, Vendor, WindowsUpdate,
-    output::{ConsoleFormatter, CsvExporter},
+    AgentUpload, AuditReport, AuditScore, AuthMethod, BaselineStore, BlockingScanner,
+    CancellationToken, CredentialSource, ExecutionMode, FailCondition, Finding, FleetReport,
+    IndustrialScanner, LocalScan, LocalScanner, NetworkOptions, Policy, RedactionPolicy,
+    RemoteScanner, RetryPolicy, ScanOptions, Scanner, Sections, SoftwareLifecycle, SoftwareScanner,
+    SystemInfo, UpdateFilter, UpdateKind, Vendor, WindowsService, WindowsUpdate,
+    output::{
+        ColorChoice, ColumnSpec, ConsoleFormatter, CsvDialect, CsvExporter, EVENT_LOG_NAME,
+        ElasticAuth, ElasticMode, ElasticSink, EventLogPublisher, GelfMode, GelfSink,
+        GelfTransport, HtmlFormatter, MarkdownFormatter, REPORT_SCHEMA, ReportBundle,
+        ServiceNowAuth, ServiceNowExporter, ServiceNowMapping, ServiceNowSink, SiemFormatter,
+        SplunkMode, SplunkSink, SqliteExporter, StixFormatter, SyslogSink, SyslogTransport,
+        TemplateRenderer, WebhookSink, XlsxExporter, XmlFormatter, YamlFormatter, load_signing_key,
+        load_verifying_key, sign_report, validate_report, verify_report,
+    },
 };
 
 #[derive(Parser)]
 #[command(name = "sysaudit")]
 #[command(author, version, about = "Windows System & Software Auditor")]
+#[command(after_help = "Examples:
+  sysaudit all --output audits/
+  sysaudit industrial --vendors citect,rockwell
+  sysaudit fleet --hosts plant.yaml --auth kerberos
+  sysaudit diff old.json new.json
+  sysaudit query \"software[?contains(name, 'Citect')].version\" report.json
+  sysaudit render report.json --format html -o report.html
+  sysaudit all --format json -o host.json --fail-on industrial,missing-updates>30
+
+Tab completion: sysaudit completions <bash|zsh|fish|powershell|elvish>")]
 struct Cli {
+    /// Disable colored table output (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// CSV dialect flags shared by every command that writes CSV.
+#[derive(Args)]
+struct CsvArgs {
+    /// CSV delimiter
+    #[arg(long, default_value = "comma", ignore_case = true, value_parser = delimiter_values())]
+    delimiter: String,
+
+    /// Write a UTF-8 byte order mark (needed by Excel)
+    #[arg(long)]
+    bom: bool,
+
+    /// Use CRLF line endings
+    #[arg(long)]
+    crlf: bool,
+}
+
+/// `--delimiter` choices; the characters themselves are accepted too.
+fn delimiter_values() -> PossibleValuesParser {
+    PossibleValuesParser::new([
+        PossibleValue::new("comma").alias(","),
+        PossibleValue::new("semicolon").alias(";"),
+        PossibleValue::new("tab").alias("\\t"),
+        PossibleValue::new("pipe").alias("|"),
+    ])
+}
+
+/// `--vendors` choices, from the library's vendor list.
+fn vendor_values() -> PossibleValuesParser {
+    PossibleValuesParser::new(Vendor::NAMES.map(|name| match name {
+        "rockwell" => PossibleValue::new(name).alias("allen-bradley"),
+        _ => PossibleValue::new(name),
+    }))
+}
+
+/// The `--columns` selection, which only table and CSV output honour.
+fn column_spec(format: &str, columns: Option<&str>) -> Result<Option<ColumnSpec>, sysaudit::Error> {
+    match (format, columns) {
+        (_, None) => Ok(None),
+        ("table" | "csv", Some(columns)) => Ok(Some(ColumnSpec::parse(columns))),
+        (format, Some(_)) => Err(sysaudit::Error::General(format!(
+            "--columns applies to table and csv output, not {}",
+            format
+        ))),
+    }
+}
+
+impl CsvArgs {
+    fn dialect(&self) -> Result<CsvDialect, sysaudit::Error> {
+        Ok(CsvDialect::new()
+            .delimiter(CsvDialect::parse_delimiter(&self.delimiter)?)
+            .bom(self.bom)
+            .crlf(self.crlf))
+    }
+}
+
+/// Flags for the `all` command.
+#[derive(Args)]
+struct AllArgs {
+    /// Output format
+    #[arg(long, default_value = "table", value_parser = ["table", "json", "yaml", "xml", "markdown", "html", "cef", "leef", "stix"])]
+    format: String,
+
+    /// Write a report bundle (per-section CSVs + report.json) into a
+    /// timestamped folder under this directory, to a `.zip` archive, or
+    /// just the JSON report to a `.json` file
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Excel workbook output (one sheet per section)
+    #[arg(long)]
+    xlsx: Option<PathBuf>,
+
+    /// SQLite database to append this scan to
+    #[arg(long)]
+    sqlite: Option<PathBuf>,
+
+    /// Write this host's Autopilot registration CSV for the Intune import
+    /// (collects the autopilot section)
+    #[arg(long, value_name = "FILE")]
+    autopilot_csv: Option<PathBuf>,
+
+    /// Group Tag column of --autopilot-csv
+    #[arg(long, requires = "autopilot_csv")]
+    group_tag: Option<String>,
+
+    /// Render the report through a template instead of --format
+    #[arg(long)]
+    template: Option<PathBuf>,
+
+    /// Forward findings and a scan summary to a syslog collector (host:port)
+    #[arg(long)]
+    syslog: Option<String>,
+
+    /// Syslog transport
+    #[arg(long, default_value = "udp", requires = "syslog", value_parser = ["udp", "tcp", "tls"])]
+    syslog_proto: String,
+
+    /// Send findings and a scan summary to a Graylog GELF input (host:port)
+    #[arg(long)]
+    gelf: Option<String>,
+
+    /// GELF transport
+    #[arg(long, default_value = "udp", requires = "gelf", value_parser = ["udp", "tcp", "tls"])]
+    gelf_proto: String,
+
+    /// Also send one GELF message per system/software/industrial/update entry
+    #[arg(long, requires = "gelf")]
+    gelf_items: bool,
+
+    /// Index the report into Elasticsearch/OpenSearch (cluster URL)
+    #[arg(long)]
+    elastic: Option<String>,
+
+    /// Elasticsearch index name
+    #[arg(long, default_value = "sysaudit", requires = "elastic")]
+    elastic_index: String,
+
+    /// Index one document per system/software/industrial/update entry
+    #[arg(long, requires = "elastic")]
+    elastic_items: bool,
+
+    /// Elasticsearch API key
+    #[arg(long, env = "SYSAUDIT_ELASTIC_API_KEY", hide_env_values = true)]
+    elastic_api_key: Option<String>,
+
+    /// Elasticsearch basic-auth user (password from SYSAUDIT_ELASTIC_PASSWORD)
+    #[arg(long, conflicts_with = "elastic_api_key")]
+    elastic_user: Option<String>,
+
+    #[arg(
+        long,
+        env = "SYSAUDIT_ELASTIC_PASSWORD",
+        hide = true,
+        requires = "elastic_user"
+    )]
+    elastic_password: Option<String>,
+
+    /// Send the report to a Splunk HTTP Event Collector (base URL)
+    #[arg(long, requires = "splunk_token")]
+    splunk: Option<String>,
+
+    /// Splunk HEC token
+    #[arg(long, env = "SYSAUDIT_SPLUNK_TOKEN", hide_env_values = true)]
+    splunk_token: Option<String>,
+
+    /// Splunk index (defaults to the token's index)
+    #[arg(long, requires = "splunk")]
+    splunk_index: Option<String>,
+
+    /// Send one event per entry (sourcetypes sysaudit:software, ...)
+    #[arg(long, requires = "splunk")]
+    splunk_items: bool,
+
+    /// Write ServiceNow CMDB import-set payloads (computer and software
+    /// instances) as JSON files into this directory
+    #[arg(long)]
+    servicenow_dir: Option<PathBuf>,
+
+    /// Post CMDB import sets to a ServiceNow instance (instance URL)
+    #[arg(long)]
+    servicenow: Option<String>,
+
+    /// ServiceNow basic-auth user (password from SYSAUDIT_SERVICENOW_PASSWORD)
+    #[arg(long, requires = "servicenow", conflicts_with = "servicenow_token")]
+    servicenow_user: Option<String>,
+
+    #[arg(
+        long,
+        env = "SYSAUDIT_SERVICENOW_PASSWORD",
+        hide = true,
+        requires = "servicenow_user"
+    )]
+    servicenow_password: Option<String>,
+
+    /// ServiceNow OAuth access token
+    #[arg(long, env = "SYSAUDIT_SERVICENOW_TOKEN", hide_env_values = true)]
+    servicenow_token: Option<String>,
+
+    /// YAML file mapping staging tables and columns to report fields
+    #[arg(long)]
+    servicenow_mapping: Option<PathBuf>,
+
+    /// Write a summary and findings to the SysAudit Windows Event Log
+    /// (registers the event source on first use; requires administrator)
+    #[arg(long)]
+    eventlog: bool,
+
+    /// Sign report.json in the --output bundle with this ed25519 PEM key
+    #[arg(long, requires = "output")]
+    sign_key: Option<PathBuf>,
+
+    /// Audit score weights (e.g. patching=40,hardening=30,industrial=0)
+    #[arg(long)]
+    score_weights: Option<String>,
+
+    /// Earlier report of this host, to show the score trend
+    #[arg(long)]
+    previous: Option<PathBuf>,
+
+    /// Collect only these sections: system, software, industrial, updates, services, security, update_health, licensing, event_logs, autopilot, containers, all
+    #[arg(long)]
+    only: Option<String>,
+
+    /// Skip these sections: software, industrial, updates, services, security, update_health, licensing, event_logs, autopilot, containers
+    #[arg(long)]
+    skip: Option<String>,
+
+    /// Hash user names, host names, MAC and IP addresses in the report
+    #[arg(long)]
+    anonymize: bool,
+
+    /// Secret salt for --anonymize; reuse it to keep pseudonyms stable across scans
+    #[arg(
+        long,
+        env = "SYSAUDIT_ANONYMIZE_SALT",
+        hide_env_values = true,
+        requires = "anonymize"
+    )]
+    anonymize_salt: Option<String>,
+
+    /// Report this asset ID instead of the one derived from the BIOS
+    /// serial, MAC addresses or machine GUID
+    #[arg(long, env = "SYSAUDIT_ASSET_ID")]
+    asset_id: Option<String>,
+
+    /// Print how long each collector took (to stderr)
+    #[arg(long)]
+    timings: bool,
+
+    /// Exit nonzero when a condition is met: industrial, missing-updates>N,
+    /// eol-os, eol-software, score<N (repeatable or comma-separated)
+    #[arg(long, value_delimiter = ',', value_parser = str::parse::<FailCondition>)]
+    fail_on: Vec<FailCondition>,
+
+    #[command(flatten)]
+    csv: CsvArgs,
+}
+
+impl AllArgs {
+    fn scan_options(&self) -> Result<ScanOptions, sysaudit::Error> {
+        let mut options = ScanOptions::new();
+        if let Some(only) = &self.only {
+            options = options.only(only)?;
+        }
+        if let Some(skip) = &self.skip {
+            options = options.skip(skip)?;
+        }
+        if let Some(weights) = &self.score_weights {
+            options = options.score_weights(ScoreWeights::parse(weights)?);
+        }
+        if self.anonymize {
+            let salt = self.anonymize_salt.clone().unwrap_or_default();
+            options = options.redaction(RedactionPolicy::hash_all().salt(salt));
+        }
+        if let Some(id) = &self.asset_id {
+            options = options.asset_id(id.clone());
+        }
+        if self.autopilot_csv.is_some() {
+            options = options.autopilot(true);
+        }
+        Ok(options)
+    }
+
+    fn splunk_sink(&self) -> Option<SplunkSink> {
+        let url = self.splunk.as_ref()?;
+        let token = SecretString::from(self.splunk_token.clone().unwrap_or_default());
+        let mut sink = SplunkSink::new(url.clone(), token);
+        if let Some(index) = &self.splunk_index {
+            sink = sink.index(index.clone());
+        }
+        if self.splunk_items {
+            sink = sink.mode(SplunkMode::Items);
+        }
+        Some(sink)
+    }
+
+    fn servicenow_mapping(&self) -> Result<ServiceNowMapping, sysaudit::Error> {
+        match &self.servicenow_mapping {
+            Some(path) => ServiceNowMapping::load(path),
+            None => Ok(ServiceNowMapping::default()),
+        }
+    }
+
+    fn servicenow_sink(&self) -> Result<Option<ServiceNowSink>, sysaudit::Error> {
+        let Some(url) = &self.servicenow else {
+            return Ok(None);
+        };
+        let auth = match (&self.servicenow_token, &self.servicenow_user) {
+            (Some(token), _) => ServiceNowAuth::Bearer(SecretString::from(token.clone())),
+            (None, Some(user)) => ServiceNowAuth::Basic {
+                username: user.clone(),
+                password: SecretString::from(self.servicenow_password.clone().unwrap_or_default()),
+            },
+            (None, None) => {
+                return Err(sysaudit::Error::General(
+                    "--servicenow needs --servicenow-user or --servicenow-token".to_string(),
+                ));
+            }
+        };
+        Ok(Some(
+            ServiceNowSink::new(url.clone(), auth).mapping(self.servicenow_mapping()?),
+        ))
+    }
+
+    fn elastic_sink(&self) -> Option<ElasticSink> {
+        let url = self.elastic.as_ref()?;
+        let auth = match (&self.elastic_api_key, &self.elastic_user) {
+            (Some(key), _) => ElasticAuth::ApiKey(SecretString::from(key.clone())),
+            (None, Some(user)) => ElasticAuth::Basic {
+                username: user.clone(),
+                password: SecretString::from(self.elastic_password.clone().unwrap_or_default()),
+            },
+            (None, None) => ElasticAuth::None,
+        };
+        let mode = if self.elastic_items {
+            ElasticMode::Items
+        } else {
+            ElasticMode::Report
+        };
+        Some(
+            ElasticSink::new(url.clone())
+                .index(self.elastic_index.clone())
+                .auth(auth)
+                .mode(mode),
+        )
+    }
+
+    fn syslog_sink(&self) -> Result<Option<SyslogSink>, sysaudit::Error> {
+        self.syslog
+            .as_ref()
+            .map(|addr| {
+                SyslogTransport::parse(&self.syslog_proto)
+                    .map(|proto| SyslogSink::new(addr.clone()).transport(proto))
+            })
+            .transpose()
+    }
+
+    fn gelf_sink(&self) -> Result<Option<GelfSink>, sysaudit::Error> {
+        let mode = if self.gelf_items {
+            GelfMode::Items
+        } else {
+            GelfMode::Summary
+        };
+        self.gelf
+            .as_ref()
+            .map(|addr| {
+                GelfTransport::parse(&self.gelf_proto)
+                    .map(|proto| GelfSink::new(addr.clone()).transport(proto).mode(mode))
+            })
+            .transpose()
+    }
+}
+
+/// WinRM connection flags shared by `remote` and `fleet`.
+#[derive(Args)]
+struct WinrmArgs {
+    /// WinRM authentication
+    #[arg(long, default_value = "basic", value_parser = ["basic", "ntlm", "negotiate", "kerberos"])]
+    auth: String,
+
+    /// Connect over HTTPS
+    #[arg(long)]
+    https: bool,
+
+    /// WinRM port (default: 5985, or 5986 with --https)
+    #[arg(long)]
+    port: Option<u16>,
+
+    /// PEM bundle of extra trusted CA certificates for --https
+    #[arg(long, requires = "https")]
+    ca_bundle: Option<PathBuf>,
+
+    /// Accept any TLS certificate (self-signed lab listeners only)
+    #[arg(long, requires = "https")]
+    insecure: bool,
+
+    /// Authenticate with this client certificate (.pfx, or PEM key and
+    /// chain) instead of a password; the listener must map it to an account
+    #[arg(long, requires = "https", value_name = "PFX")]
+    client_cert: Option<PathBuf>,
+
+    /// Passphrase of --client-cert
+    #[arg(
+        long,
+        env = "SYSAUDIT_CLIENT_CERT_PASSWORD",
+        hide_env_values = true,
+        requires = "client_cert"
+    )]
+    client_cert_password: Option<String>,
+
+    /// Per-attempt timeout in seconds
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+
+    /// Retries per host after a network failure or timeout
+    #[arg(long, default_value_t = 2)]
+    retries: u32,
+
+    /// Delay before the first retry in seconds (doubles on each retry)
+    #[arg(long, default_value_t = 2)]
+    retry_backoff: u64,
+
+    /// Push this sysaudit-agent.exe to each host instead of running the PowerShell payload
+    #[arg(long)]
+    agent: Option<PathBuf>,
+
+    /// Copy the agent over the WinRM session instead of the ADMIN$ share
+    #[arg(long, requires = "agent")]
+    agent_via_session: bool,
+}
+
+impl WinrmArgs {
+    /// `--port`, or the WinRM default for the transport.
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(if self.https { 5986 } else { 5985 })
+    }
+
+    /// `--client-cert` and its passphrase.
+    fn client_cert(&self) -> Option<(PathBuf, SecretString)> {
+        let passphrase = self.client_cert_password.clone().unwrap_or_default();
+        self.client_cert
+            .clone()
+            .map(|path| (path, SecretString::from(passphrase)))
+    }
+
+    fn retry(&self) -> RetryPolicy {
+        RetryPolicy::new()
+            .max_attempts(self.retries.saturating_add(1))
+            .initial_backoff(Duration::from_secs(self.retry_backoff))
+    }
+
+    fn mode(&self) -> ExecutionMode {
+        match &self.agent {
+            Some(binary) => ExecutionMode::Agent {
+                binary: binary.clone(),
+                upload: if self.agent_via_session {
+                    AgentUpload::Session
+                } else {
+                    AgentUpload::AdminShare
+                },
+            },
+            None => ExecutionMode::Payload,
+        }
+    }
+}
+
+/// Flags for the `remote` command.
+#[derive(Args)]
+struct RemoteArgs {
+    /// Host name or IP address to audit
+    #[arg(long)]
+    host: String,
+
+    /// Account to connect as (e.g. PLANT\audit)
+    #[arg(short, long, visible_alias = "username")]
+    user: Option<String>,
+
+    /// Password for --user
+    #[arg(long, env = "SYSAUDIT_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Read the password from the first line of stdin
+    #[arg(long, conflicts_with_all = ["password", "cred", "password_from"])]
+    password_stdin: bool,
+
+    /// Windows Credential Manager entry holding the account and password
+    #[arg(long, conflicts_with_all = ["password", "password_from"])]
+    cred: Option<String>,
+
+    /// Where to get the password instead: env:<VAR>, credman:<target> or prompt
+    #[arg(long, conflicts_with = "password")]
+    password_from: Option<String>,
+
+    #[command(flatten)]
+    winrm: WinrmArgs,
+
+    /// Sections to collect: system, software, industrial, updates, services, security, update_health, licensing, event_logs, autopilot, containers, all
+    #[arg(long, visible_alias = "only", default_value = "all")]
+    sections: String,
+
+    /// Sections to leave out of --sections
+    #[arg(long)]
+    skip: Option<String>,
+
+    /// Print progress (connecting, executing, ...) to stderr
+    #[arg(long)]
+    progress: bool,
+
+    /// Print the scan duration and bytes sent and received (to stderr)
+    #[arg(long)]
+    timings: bool,
+
+    /// Output format
+    #[arg(long, default_value = "table", value_parser = ["table", "json", "yaml", "xml", "html", "cef", "leef", "stix"])]
+    format: String,
+
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// Flags for the `fleet` command.
+#[derive(Args)]
+struct FleetArgs {
+    /// Inventory file (.yaml or .csv) listing the hosts to audit
+    #[arg(long, visible_alias = "inventory")]
+    hosts: PathBuf,
+
+    /// Maximum number of hosts scanned at once
+    #[arg(long, default_value_t = 8)]
+    concurrency: usize,
+
+    /// Start at most this many hosts per minute, whatever the concurrency
+    #[arg(long, value_name = "HOSTS_PER_MINUTE", value_parser = clap::value_parser!(u32).range(1..))]
+    max_rate: Option<u32>,
+
+    /// State file recording each finished host; rerunning with the same
+    /// file skips completed hosts and retries failed ones
+    #[arg(long, visible_alias = "state", value_name = "FILE")]
+    resume: Option<PathBuf>,
+
+    /// Username for hosts that do not set their own
+    #[arg(short, long)]
+    username: Option<String>,
+
+    /// Password for --username
+    #[arg(long, env = "SYSAUDIT_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Where to get the password instead: env:<VAR>, credman:<target> or prompt
+    #[arg(long, conflicts_with = "password")]
+    password_from: Option<String>,
+
+    #[command(flatten)]
+    winrm: WinrmArgs,
+
+    /// Sections to collect: system, software, industrial, updates, services, security, update_health, licensing, event_logs, autopilot, containers, all
+    #[arg(
+        long,
+        visible_alias = "only",
+        default_value = "software,industrial,updates"
+    )]
+    sections: String,
+
+    /// Sections to leave out of --sections
+    #[arg(long)]
+    skip: Option<String>,
+
+    /// Print each host's progress (connecting, executing, ...) to stderr
+    #[arg(long)]
+    progress: bool,
+
+    /// Directory to write one `<host>.json` report per scanned host
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format (the fleet report with rollups)
+    #[arg(long, value_enum, default_value_t = FleetFormat::Table)]
+    format: FleetFormat,
+
+    /// Directory to write the host list and rollups as CSV files
+    #[arg(long)]
+    csv_dir: Option<PathBuf>,
+
+    /// Write the scanned hosts' Autopilot registration CSV for the Intune
+    /// import (collects the autopilot section)
+    #[arg(long, value_name = "FILE")]
+    autopilot_csv: Option<PathBuf>,
+
+    /// Group Tag column of --autopilot-csv
+    #[arg(long, requires = "autopilot_csv")]
+    group_tag: Option<String>,
+
+    #[command(flatten)]
+    csv: CsvArgs,
+}
+
+/// Flags for the `serve-api` command.
+#[cfg(feature = "api")]
+#[derive(Args)]
+struct ServeApiArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "0.0.0.0:8444")]
+    listen: String,
+
+    /// PEM certificate chain
+    #[arg(long)]
+    cert: PathBuf,
+
+    /// PEM private key
+    #[arg(long)]
+    key: PathBuf,
+
+    /// Bearer token clients must send
+    #[arg(long, env = "SYSAUDIT_API_TOKEN", hide_env_values = true)]
+    token: String,
+
+    /// Inventory (.yaml or .csv) of hosts that may be scanned through
+    /// the API over WinRM
+    #[arg(long)]
+    hosts: Option<PathBuf>,
+
+    /// Username for inventory hosts that do not set their own
+    #[arg(short, long, requires = "hosts")]
+    username: Option<String>,
+
+    /// Password for --username
+    #[arg(long, env = "SYSAUDIT_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// WinRM authentication
+    #[arg(long, default_value = "basic", value_parser = ["basic", "ntlm", "negotiate", "kerberos"])]
+    auth: String,
+
+    /// Connect to inventory hosts over HTTPS
+    #[arg(long)]
+    https: bool,
+
+    /// WinRM port (default: 5985, or 5986 with --https)
+    #[arg(long)]
+    port: Option<u16>,
+
+    /// Threads handling requests (and so scans running at once)
+    #[arg(long, default_value_t = 4)]
+    workers: usize,
+}
+
+/// Flags for the `watch` command.
+#[derive(Args)]
+struct WatchArgs {
+    /// Time between scans (e.g. 30m, 1h, 1d)
+    #[arg(long, default_value = "1h", value_parser = parse_interval)]
+    interval: Duration,
+
+    /// Directory for the timestamped reports and change diffs
+    #[arg(long, default_value = "history")]
+    output_dir: PathBuf,
+
+    /// When software or industrial inventory changes: `exec <command>` or
+    /// `webhook <url>` (repeatable)
+    #[arg(long, num_args = 2, value_names = ["ACTION", "TARGET"])]
+    on_change: Vec<String>,
+
+    /// Also rescan as soon as software is installed or removed (Uninstall
+    /// key notifications and Windows Installer events); --interval stays
+    /// the fallback
+    #[arg(long)]
+    watch_changes: bool,
+}
+
+/// Output format of `fleet`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FleetFormat {
+    /// Per-host table and rollups
+    Table,
+    /// The fleet report as JSON
+    Json,
+}
+
+/// What `watch` does when the inventory changes.
+enum ChangeAction {
+    /// Run a shell command with SYSAUDIT_HOST, SYSAUDIT_REPORT and
+    /// SYSAUDIT_DIFF set
+    Exec(String),
+    /// Post the changes as JSON
+    Webhook(WebhookSink),
+}
+
+impl ChangeAction {
+    /// Parse the `--on-change ACTION TARGET` pairs.
+    fn parse_all(values: &[String]) -> Result<Vec<ChangeAction>, sysaudit::Error> {
+        values
+            .chunks(2)
+            .map(|pair| match pair {
+                [action, command] if action == "exec" => Ok(ChangeAction::Exec(command.clone())),
+                [action, url] if action == "webhook" => {
+                    Ok(ChangeAction::Webhook(WebhookSink::new(url.as_str())))
+                }
+                _ => Err(sysaudit::Error::General(format!(
+                    "invalid --on-change '{}': use exec <command> or webhook <url>",
+                    pair.join(" ")
+                ))),
+            })
+            .collect()
+    }
+
+    fn run(
+        &self,
+        changes: &ReportDiff,
+        report: &std::path::Path,
+        diff: &std::path::Path,
+    ) -> Result<(), sysaudit::Error> {
+        match self {
+            ChangeAction::Exec(command) => {
+                let (shell, flag) = if cfg!(windows) {
+                    ("cmd", "/C")
+                } else {
+                    ("sh", "-c")
+                };
+                let status = std::process::Command::new(shell)
+                    .args([flag, command])
+                    .env("SYSAUDIT_HOST", &changes.host)
+                    .env("SYSAUDIT_REPORT", report)
+                    .env("SYSAUDIT_DIFF", diff)
+                    .status()?;
+                if !status.success() {
+                    return Err(sysaudit::Error::General(format!(
+                        "'{}' exited with {}",
+                        command, status
+                    )));
+                }
+                Ok(())
+            }
+            ChangeAction::Webhook(sink) => sink.send(changes),
+        }
+    }
+}
+
+/// Flags for the `baseline` command.
+#[derive(Args)]
+struct BaselineArgs {
+    /// Directory holding the baselines (default: %ProgramData%\sysaudit\baselines)
+    #[arg(long, env = "SYSAUDIT_BASELINE_DIR", global = true)]
+    dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: BaselineCommand,
+}
+
+impl BaselineArgs {
+    fn store(&self) -> BaselineStore {
+        let dir = self.dir.clone().unwrap_or_else(|| {
+            std::env::var_os("ProgramData")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+                .join("sysaudit")
+                .join("baselines")
+        });
+        BaselineStore::new(dir)
+    }
+}
+
+#[derive(Subcommand)]
+enum BaselineCommand {
+    /// Approve this host's current state (or a stored report) as a baseline
+    Save {
+        /// Baseline name
+        name: String,
+
+        /// Save this report JSON file instead of scanning the host
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+
+    /// Compare this host (or a stored report) against a baseline
+    Check {
+        /// Baseline name
+        name: String,
+
+        /// Check this report JSON file instead of scanning the host
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json"])]
+        format: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// List saved baselines
+    List,
+}
+
+/// Flags for the `history` command.
+#[derive(Args)]
+struct HistoryArgs {
+    /// History database, as written by `all --sqlite` (default:
+    /// %ProgramData%\sysaudit\history.db)
+    #[arg(long, env = "SYSAUDIT_HISTORY_DB", global = true)]
+    db: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: HistoryCommand,
+}
+
+impl HistoryArgs {
+    fn open(&self) -> Result<HistoryDb, sysaudit::Error> {
+        let path = self.db.clone().unwrap_or_else(|| {
+            std::env::var_os("ProgramData")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+                .join("sysaudit")
+                .join("history.db")
+        });
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        HistoryDb::open(path)
+    }
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+    /// Record a scan of this host (or a stored report)
+    Record {
+        /// Record this report JSON file instead of scanning the host
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+
+    /// Show a metric over time for a host name or asset ID
+    Show {
+        /// Host name or asset ID
+        #[arg(long)]
+        host: String,
+
+        /// Metric to show
+        #[arg(long, default_value = "software_count", value_parser = ["software_count", "industrial_count", "update_count", "patch_age", "score"])]
+        metric: String,
+
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json"])]
+        format: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Delete scans older than an age (e.g. 90d), keeping each asset's
+    /// latest scan
+    Prune {
+        /// Age of the oldest scans kept (e.g. 30d, 12h)
+        #[arg(long)]
+        older_than: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServiceCommand {
+    /// Register and start the scheduled-scan service (run as administrator)
+    Install {
+        /// Schedule YAML: interval, sections and sinks
+        #[arg(long)]
+        config: PathBuf,
+    },
+
+    /// Stop and remove the service
+    Uninstall,
+
+    /// Run the scheduled scans (started by the service control manager)
+    Run {
+        /// Schedule YAML: interval, sections and sinks
+        #[arg(long)]
+        config: PathBuf,
+
+        /// Run in the foreground instead of as a service
+        #[arg(long)]
+        console: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Display system information
     System {
-        /// Output format: table, json
-        #[arg(short, long, default_value = "table")]
+        /// Output format
+        #[arg(short, long, default_value = "table", value_parser = ["table", "json", "yaml", "xml", "csv", "markdown"])]
         format: String,
+
+        /// Write to this file instead of stdout (csv: default system.csv)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// One row per connected adapter, without link-local and loopback
+        /// addresses
+        #[arg(long)]
+        compact_network: bool,
+
+        /// Leave out IPv6 addresses
+        #[arg(long)]
+        no_ipv6: bool,
+
+        /// Look up the reverse-DNS name of each address
+        #[arg(long)]
+        reverse_dns: bool,
+
+        #[command(flatten)]
+        csv: CsvArgs,
     },
 
     /// List installed software
@@ -33,106 +970,592 @@ enum Commands {
         #[arg(short, long)]
         filter: Option<String>,
 
-        /// Output format: table, json, csv
-        #[arg(long, default_value = "table")]
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json", "yaml", "xml", "csv", "markdown"])]
         format: String,
 
-        /// Output file for csv format
+        /// Write to this file instead of stdout (csv: default <command>.csv)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Columns for table/csv output, in order (e.g. name,version,publisher)
+        #[arg(long, conflicts_with_all = ["with_vulns", "compare_allowlist"])]
+        columns: Option<String>,
+
+        /// List known vulnerabilities of the installed software (table, json)
+        #[arg(long, requires = "vuln_db")]
+        with_vulns: bool,
+
+        /// Offline NVD 2.0 feed or OSV file/directory for --with-vulns
+        #[arg(long, env = "SYSAUDIT_VULN_DB")]
+        vuln_db: Option<PathBuf>,
+
+        /// End-of-life dataset adding to or replacing bundled products
+        #[arg(long, env = "SYSAUDIT_EOL_DATA")]
+        eol_data: Option<PathBuf>,
+
+        /// Classify software against an approved list (CSV: name,version,status);
+        /// exits non-zero on unapproved or unknown items (table, json)
+        #[arg(long, conflicts_with = "with_vulns")]
+        compare_allowlist: Option<PathBuf>,
+
+        #[command(flatten)]
+        csv: CsvArgs,
     },
 
-    /// Detect industrial software
+    /// Detect industrial software and whether its services are running
     Industrial {
-        /// Comma-separated vendor list (citect,rockwell,abb,siemens,schneider,digifort)
-        #[arg(short, long)]
-        vendors: Option<String>,
+        /// Vendors to detect, comma-separated (default: all)
+        #[arg(long, value_delimiter = ',', ignore_case = true, value_parser = vendor_values())]
+        vendors: Vec<String>,
 
-        /// Output format: table, json, csv
-        #[arg(long, default_value = "table")]
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json", "yaml", "xml", "csv", "markdown"])]
         format: String,
 
-        /// Output file for csv format
+        /// Write to this file instead of stdout (csv: default <command>.csv)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Columns for table/csv output, in order (e.g. vendor,product,version)
+        #[arg(long)]
+        columns: Option<String>,
+
+        #[command(flatten)]
+        csv: CsvArgs,
     },
 
     /// List Windows Updates / Hotfixes
     Updates {
-        /// Output format: table, json, csv
-        #[arg(long, default_value = "table")]
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json", "yaml", "xml", "csv", "markdown"])]
         format: String,
 
-        /// Output file for csv format
+        /// Write to this file instead of stdout (csv: default <command>.csv)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Columns for table/csv output, in order (e.g. hotfix_id,installed_on)
+        #[arg(long)]
+        columns: Option<String>,
+
+        /// Only updates installed on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only these KB numbers (e.g. KB5034441; repeatable or comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        kb: Vec<String>,
+
+        /// Only security updates or only other updates
+        #[arg(long = "type", value_parser = ["security", "other"])]
+        kind: Option<String>,
+
+        #[command(flatten)]
+        csv: CsvArgs,
     },
 
     /// Run full audit
-    All {
-        /// Output file (CSV)
+    All(AllArgs),
+
+    /// Audit one remote host over WinRM
+    Remote(RemoteArgs),
+
+    /// Audit many remote hosts over WinRM
+    Fleet(FleetArgs),
+
+    /// Serve this host's report over authenticated HTTPS
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "0.0.0.0:8443")]
+        listen: String,
+
+        /// PEM certificate chain
+        #[arg(long)]
+        cert: PathBuf,
+
+        /// PEM private key
+        #[arg(long)]
+        key: PathBuf,
+
+        /// Bearer token clients must send
+        #[arg(long, env = "SYSAUDIT_SERVE_TOKEN", hide_env_values = true)]
+        token: String,
+
+        /// Seconds a collected report is reused (0 scans on every request)
+        #[arg(long, default_value_t = 300)]
+        cache_ttl: u64,
+
+        /// Also keep each section on disk for this many seconds, so restarts
+        /// and expired reports reuse unchanged registry/WMI results
+        #[arg(long)]
+        disk_cache: Option<u64>,
+    },
+
+    /// Run scans on demand over an authenticated HTTPS JSON API
+    #[cfg(feature = "api")]
+    ServeApi(ServeApiArgs),
+
+    /// Print the JSON Schema for stored reports
+    Schema,
+
+    /// Validate a stored report against the JSON Schema
+    Validate {
+        /// Report JSON file
+        file: PathBuf,
+    },
+
+    /// Save approved baselines and check hosts against them
+    Baseline(BaselineArgs),
+
+    /// Record scans and show trends (software count, patch age, ...) per host
+    History(HistoryArgs),
+
+    /// Re-scan periodically, keep timestamped reports and alert when
+    /// software or industrial inventory changes
+    Watch(WatchArgs),
+
+    /// Evaluate a compliance policy; exits non-zero if any rule fails
+    Check {
+        /// Policy YAML file
+        #[arg(long, required_unless_present = "cis")]
+        policy: Option<PathBuf>,
+
+        /// Use the built-in CIS hardening checks instead of a policy file
+        #[arg(long, conflicts_with = "policy")]
+        cis: bool,
+
+        /// Pass when the weighted score reaches this percentage, even if
+        /// some rules fail
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        min_score: Option<u8>,
+
+        /// Check this report JSON file instead of scanning the host
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json"])]
+        format: String,
+
+        /// Write to this file instead of stdout
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
+
+    /// Show what changed between two stored reports of a host
+    Diff {
+        /// Older report JSON file
+        old: PathBuf,
+
+        /// Newer report JSON file
+        new: PathBuf,
+
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json", "html"])]
+        format: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Combine partial reports of one host into a single report
+    ///
+    /// Each section comes from the newest report that has it; differing
+    /// system values are listed on stderr.
+    Merge {
+        /// Report JSON files of the same host
+        #[arg(required = true, num_args = 2..)]
+        reports: Vec<PathBuf>,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Extract values from a report with a JMESPath expression
+    ///
+    /// Scans this host unless a saved report is given, e.g.
+    /// `sysaudit query "software[?contains(name, 'Citect')].version"`.
+    Query {
+        /// JMESPath expression over the JSON report
+        expression: String,
+
+        /// Saved report JSON file (default: scan this host)
+        report: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, default_value = "json", value_parser = ["json", "table"])]
+        format: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Re-render a saved JSON report without rescanning
+    ///
+    /// e.g. `sysaudit render audits/hmi01.json --format html -o hmi01.html`.
+    Render {
+        /// Saved report JSON file
+        report: PathBuf,
+
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json", "yaml", "xml", "html", "cef", "leef", "stix", "csv", "xlsx"])]
+        format: String,
+
+        /// Write to this file instead of stdout (csv: a directory, default
+        /// the working directory; xlsx: required)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// PowerShell: `sysaudit completions powershell | Out-String | Invoke-Expression`
+    /// (add it to $PROFILE to keep it); bash: `source <(sysaudit completions bash)`.
+    Completions {
+        /// Target shell
+        shell: Shell,
+    },
+
+    /// Verify a report against its detached signature
+    Verify {
+        /// Report JSON file
+        report: PathBuf,
+
+        /// Signature file (base64)
+        signature: PathBuf,
+
+        /// Signer's ed25519 public key (PEM)
+        #[arg(long)]
+        key: PathBuf,
+    },
 }
 
 fn main() {
-    // Initialize structured logging.
-    // Debug builds: show DEBUG and above.
-    // Release builds: show ERROR and above only.
-    let filter = if cfg!(debug_assertions) {
-        tracing_subscriber::EnvFilter::new("debug")
-    } else {
-        tracing_subscriber::EnvFilter::new("error")
+    let cli = Cli::parse();
+
+    // Structured logging to stderr and, with --log-file, a daily file.
+    // The guard flushes the file on exit.
+    let log_guard = match cli.log.init() {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("Error: cannot open log file: {}", e);
+            std::process::exit(1);
+        }
     };
 
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .with_env_filter(filter)
-        .with_target(false)
-        .init();
-
-    let cli = Cli::parse();
+    // Color only when writing to a terminal and not disabled.
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if cli.no_color || no_color_env || !std::io::stdout().is_terminal() {
+        ConsoleFormatter::set_color(ColorChoice::Never);
+    }
 
+    // Nonzero without an error: a met `all --fail-on` condition.
+    let mut exit_code = 0;
     let result = match cli.command {
-        Commands::System { format } => cmd_system(&format),
+        Commands::System {
+            format,
+            output,
+            compact_network,
+            no_ipv6,
+            reverse_dns,
+            csv,
+        } => {
+            let network = if compact_network {
+                NetworkOptions::compact()
+            } else {
+                NetworkOptions::new()
+            };
+            csv.dialect().and_then(|dialect| {
+                cmd_system(
+                    &format,
+                    output.as_deref(),
+                    network.ipv6(!no_ipv6).reverse_dns(reverse_dns),
+                    &dialect,
+                )
+            })
+        }
+        Commands::Software {
+            filter,
+            format,
+            with_vulns: true,
+            vuln_db: Some(vuln_db),
+            eol_data,
+            output,
+            ..
+        } => cmd_software_vulns(
+            filter.as_deref(),
+            &vuln_db,
+            eol_data.as_deref(),
+            &format,
+            output.as_deref(),
+        ),
+        Commands::Software {
+            filter,
+            format,
+            eol_data,
+            output,
+            compare_allowlist: Some(allowlist),
+            ..
+        } => cmd_software_allowlist(
+            filter.as_deref(),
+            &allowlist,
+            eol_data.as_deref(),
+            &format,
+            output.as_deref(),
+        ),
         Commands::Software {
             filter,
             format,
             output,
-        } => cmd_software(filter.as_deref(), &format, output.as_deref()),
+            columns,
+            eol_data,
+            csv,
+            ..
+        } => csv.dialect().and_then(|dialect| {
+            cmd_software(
+                filter.as_deref(),
+                eol_data.as_deref(),
+                &format,
+                output.as_deref(),
+                columns.as_deref(),
+                &dialect,
+            )
+        }),
         Commands::Industrial {
             vendors,
             format,
             output,
-        } => cmd_industrial(vendors.as_deref(), &format, output.as_deref()),
-        Commands::Updates { format, output } => cmd_updates(&format, output.as_deref()),
-        Commands::All { output } => cmd_all(output.as_deref()),
+            columns,
+            csv,
+        } => csv.dialect().and_then(|dialect| {
+            cmd_industrial(
+                &vendors,
+                &format,
+                output.as_deref(),
+                columns.as_deref(),
+                &dialect,
+            )
+        }),
+        Commands::Updates {
+            format,
+            output,
+            columns,
+            since,
+            kb,
+            kind,
+            csv,
+        } => update_filter(since.as_deref(), &kb, kind.as_deref()).and_then(|filter| {
+            csv.dialect().and_then(|dialect| {
+                cmd_updates(
+                    &filter,
+                    &format,
+                    output.as_deref(),
+                    columns.as_deref(),
+                    &dialect,
+                )
+            })
+        }),
+        Commands::All(args) => cmd_all(&args).map(|code| exit_code = code),
+        Commands::Remote(args) => cmd_remote(&args),
+        Commands::Fleet(args) => cmd_fleet(&args),
+        Commands::Verify {
+            report,
+            signature,
+            key,
+        } => cmd_verify(&report, &signature, &key),
+        Commands::Serve {
+            listen,
+            cert,
+            key,
+            token,
+            cache_ttl,
+            disk_cache,
+        } => {
+            let mut options = ScanOptions::new();
+            if let Some(ttl) = disk_cache {
+                options = options.cache(Duration::from_secs(ttl));
+            }
+            ReportServer::builder()
+                .listen(listen)
+                .cert(cert)
+                .key(key)
+                .token(SecretString::from(token))
+                .cache_ttl(Duration::from_secs(cache_ttl))
+                .options(options)
+                .build()
+                .run()
+        }
+        #[cfg(feature = "api")]
+        Commands::ServeApi(args) => cmd_serve_api(args),
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "sysaudit",
+                &mut std::io::stdout(),
+            );
+            Ok(())
+        }
+        Commands::Schema => {
+            print!("{}", REPORT_SCHEMA);
+            Ok(())
+        }
+        Commands::Validate { file } => cmd_validate(&file),
+        Commands::Diff {
+            old,
+            new,
+            format,
+            output,
+        } => cmd_diff(&old, &new, &format, output.as_deref()),
+        Commands::Merge { reports, output } => cmd_merge(&reports, output.as_deref()),
+        Commands::Query {
+            expression,
+            report,
+            format,
+            output,
+        } => cmd_query(&expression, report.as_deref(), &format, output.as_deref()),
+        Commands::Render {
+            report,
+            format,
+            output,
+            csv,
+        } => cmd_render(&report, &format, output.as_deref(), &csv),
+        Commands::Baseline(args) => cmd_baseline(&args),
+        Commands::History(args) => cmd_history(&args),
+        Commands::Watch(args) => cmd_watch(&args),
+        Commands::Service { command } => match command {
+            ServiceCommand::Install { config } => service::install(&config),
+            ServiceCommand::Uninstall => service::uninstall(),
+            ServiceCommand::Run { config, console } => service::run(&config, console),
+        },
+        Commands::Check {
+            policy,
+            cis,
+            min_score,
+            report,
+            format,
+            output,
+        } => {
+            let policy = match policy {
+                Some(path) if !cis => Policy::load(path),
+                _ => Ok(Policy::cis()),
+            };
+            policy.and_then(|policy| {
+                cmd_check(
+                    &policy,
+                    report.as_deref(),
+                    min_score,
+                    &format,
+                    output.as_deref(),
+                )
+            })
+        }
     };
 
-    if let Err(e) = result {
-        tracing::error!(error = %e, "Command failed");
-        std::process::exit(1);
+    if let Err(e) = &result {
+        // Printed even with `--quiet`, which silences console logging.
+        eprintln!("Error: {}", e);
+        tracing::error!(target: logging::FILE_ONLY, error = %e, "Command failed");
+        exit_code = 1;
+    }
+    if exit_code != 0 {
+        // `exit` skips destructors: flush the log file first.
+        drop(log_guard);
+        std::process::exit(exit_code);
+    }
+}
+
+/// Destination of a command's formatted output: stdout, or the file given
+/// with `--output`.
+struct Output<'a> {
+    path: Option<&'a std::path::Path>,
+}
+
+impl<'a> Output<'a> {
+    /// Output to `path`, or stdout when `None`. Tables written to a file
+    /// are never colored.
+    fn new(path: Option<&'a std::path::Path>) -> Self {
+        if path.is_some() {
+            ConsoleFormatter::set_color(ColorChoice::Never);
+        }
+        Output { path }
+    }
+
+    /// Write `text`, ending it with a newline.
+    fn write(&self, text: impl Into<String>) -> Result<(), sysaudit::Error> {
+        let mut text = text.into();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        match self.path {
+            Some(path) => {
+                std::fs::write(path, text)?;
+                eprintln!("Written to {}", path.display());
+            }
+            None => print!("{}", text),
+        }
+        Ok(())
+    }
+
+    /// CSV file to write: `--output`, or `default` in the working directory.
+    fn csv_path(&self, default: &'a str) -> &'a std::path::Path {
+        self.path.unwrap_or(std::path::Path::new(default))
     }
 }
 
-fn cmd_system(format: &str) -> Result<(), sysaudit::Error> {
-    let info = SystemInfo::collect()?;
+fn cmd_system(
+    format: &str,
+    output: Option<&std::path::Path>,
+    network: NetworkOptions,
+    dialect: &CsvDialect,
+) -> Result<(), sysaudit::Error> {
+    let out = Output::new(output);
+    let info = SystemInfo::collect_with(network)?;
 
     match format {
-        "json" => println!("{}", serde_json::to_string_pretty(&info)?),
-        _ => println!("{}", ConsoleFormatter::format_system_info(&info)),
+        "json" => out.write(serde_json::to_string_pretty(&info)?),
+        "yaml" => out.write(YamlFormatter::format(&info)?),
+        "xml" => out.write(XmlFormatter::format_system_info(&info)),
+        "csv" => {
+            let path = out.csv_path("system.csv");
+            CsvExporter::export_system_with(&info, path, dialect)?;
+            println!("Exported to {}", path.display());
+            Ok(())
+        }
+        "markdown" => out.write(MarkdownFormatter::format_system_info(&info)),
+        _ => out.write(ConsoleFormatter::format_system_info(&info)),
     }
+}
 
-    Ok(())
+/// Software scanner evaluating end of life against `eol_data`, if given.
+fn software_scanner(
+    eol_data: Option<&std::path::Path>,
+) -> Result<SoftwareScanner, sysaudit::Error> {
+    let scanner = SoftwareScanner::new();
+    Ok(match eol_data {
+        Some(path) => scanner.lifecycle(SoftwareLifecycle::load(path)?),
+        None => scanner,
+    })
 }
 
 fn cmd_software(
     filter: Option<&str>,
+    eol_data: Option<&std::path::Path>,
     format: &str,
     output: Option<&std::path::Path>,
+    columns: Option<&str>,
+    dialect: &CsvDialect,
 ) -> Result<(), sysaudit::Error> {
-    let mut software = SoftwareScanner::new().scan()?;
+    let columns = column_spec(format, columns)?;
+    let out = Output::new(output);
+    let mut software = software_scanner(eol_data)?.scan()?;
 
     // Apply filter
     if let Some(f) = filter {
@@ -141,98 +1564,994 @@ fn cmd_software(
     }
 
     match format {
-        "json" => println!("{}", serde_json::to_string_pretty(&software)?),
+        "json" => out.write(serde_json::to_string_pretty(&software)?),
+        "yaml" => out.write(YamlFormatter::format(&software)?),
+        "xml" => out.write(XmlFormatter::format_software(&software)),
         "csv" => {
-            let path = output.unwrap_or(std::path::Path::new("software.csv"));
-            CsvExporter::export_software(&software, path)?;
+            let path = out.csv_path("software.csv");
+            match &columns {
+                Some(spec) => CsvExporter::export_columns_with(&software, spec, path, dialect)?,
+                None => CsvExporter::export_software_with(&software, path, dialect)?,
+            }
             println!("Exported {} items to {}", software.len(), path.display());
+            Ok(())
         }
-        _ => println!("{}", ConsoleFormatter::format_software(&software)),
+        "markdown" => out.write(MarkdownFormatter::format_software(&software)),
+        _ => match &columns {
+            Some(spec) => out.write(ConsoleFormatter::format_columns(&software, spec)?),
+            None => out.write(ConsoleFormatter::format_software(&software)),
+        },
     }
+}
 
-    Ok(())
+fn cmd_software_vulns(
+    filter: Option<&str>,
+    vuln_db: &std::path::Path,
+    eol_data: Option<&std::path::Path>,
+    format: &str,
+    output: Option<&std::path::Path>,
+) -> Result<(), sysaudit::Error> {
+    let out = Output::new(output);
+    let db = VulnDatabase::load(vuln_db)?;
+    let mut software = software_scanner(eol_data)?.scan()?;
+    if let Some(f) = filter {
+        let f_lower = f.to_lowercase();
+        software.retain(|sw| sw.name.to_lowercase().contains(&f_lower));
+    }
+    let vulnerable = db.annotate(software);
+
+    match format {
+        "json" => out.write(serde_json::to_string_pretty(&vulnerable)?),
+        "table" => {
+            let findings: Vec<Finding> = vulnerable.iter().flat_map(|v| v.findings()).collect();
+            out.write(ConsoleFormatter::format_findings(&findings))
+        }
+        other => Err(sysaudit::Error::General(format!(
+            "--with-vulns supports table and json output, not '{}'",
+            other
+        ))),
+    }
+}
+
+fn cmd_software_allowlist(
+    filter: Option<&str>,
+    allowlist: &std::path::Path,
+    eol_data: Option<&std::path::Path>,
+    format: &str,
+    output: Option<&std::path::Path>,
+) -> Result<(), sysaudit::Error> {
+    let out = Output::new(output);
+    let allowlist = Allowlist::load(allowlist)?;
+    let mut software = software_scanner(eol_data)?.scan()?;
+    if let Some(f) = filter {
+        let f_lower = f.to_lowercase();
+        software.retain(|sw| sw.name.to_lowercase().contains(&f_lower));
+    }
+    let classified = allowlist.classify_all(software);
+
+    match format {
+        "json" => out.write(serde_json::to_string_pretty(&classified)?)?,
+        "table" => out.write(ConsoleFormatter::format_allowlist(&classified))?,
+        other => {
+            return Err(sysaudit::Error::General(format!(
+                "--compare-allowlist supports table and json output, not '{}'",
+                other
+            )));
+        }
+    }
+
+    match classified.iter().filter(|c| c.is_violation()).count() {
+        0 => Ok(()),
+        violations => Err(sysaudit::Error::General(format!(
+            "{} of {} items violate the allowlist",
+            violations,
+            classified.len()
+        ))),
+    }
 }
 
 fn cmd_industrial(
-    vendors: Option<&str>,
+    vendors: &[String],
     format: &str,
     output: Option<&std::path::Path>,
+    columns: Option<&str>,
+    dialect: &CsvDialect,
 ) -> Result<(), sysaudit::Error> {
-    let scanner = if let Some(v) = vendors {
-        let vendor_list: Vec<Vendor> = v
-            .split(',')
-            .filter_map(|s| match s.trim().to_lowercase().as_str() {
-                "citect" => Some(Vendor::Citect),
-                "rockwell" | "allen-bradley" => Some(Vendor::Rockwell),
-                "abb" => Some(Vendor::ABB),
-                "siemens" => Some(Vendor::Siemens),
-                "schneider" => Some(Vendor::SchneiderElectric),
-                "digifort" => Some(Vendor::Digifort),
-                _ => None,
-            })
-            .collect();
-        IndustrialScanner::with_vendors(vendor_list)
-    } else {
+    let columns = column_spec(format, columns)?;
+    let out = Output::new(output);
+    let scanner = if vendors.is_empty() {
         IndustrialScanner::all_vendors()
+    } else {
+        IndustrialScanner::with_vendors(vendors.iter().filter_map(|v| Vendor::parse(v)).collect())
     };
 
-    let industrial = scanner.scan()?;
+    let mut industrial = scanner.scan()?;
+    // Running, stopped or not a service, from the services in each install folder
+    let services = WindowsService::collect_all();
+    for sw in &mut industrial {
+        sw.correlate(&services);
+    }
 
     match format {
-        "json" => println!("{}", serde_json::to_string_pretty(&industrial)?),
+        "json" => out.write(serde_json::to_string_pretty(&industrial)?),
+        "yaml" => out.write(YamlFormatter::format(&industrial)?),
+        "xml" => out.write(XmlFormatter::format_industrial(&industrial)),
         "csv" => {
-            let path = output.unwrap_or(std::path::Path::new("industrial.csv"));
-            CsvExporter::export_industrial(&industrial, path)?;
+            let path = out.csv_path("industrial.csv");
+            match &columns {
+                Some(spec) => CsvExporter::export_columns_with(&industrial, spec, path, dialect)?,
+                None => CsvExporter::export_industrial_with(&industrial, path, dialect)?,
+            }
             println!("Exported {} items to {}", industrial.len(), path.display());
+            Ok(())
         }
-        _ => println!("{}", ConsoleFormatter::format_industrial(&industrial)),
+        "markdown" => out.write(MarkdownFormatter::format_industrial(&industrial)),
+        _ => match &columns {
+            Some(spec) => out.write(ConsoleFormatter::format_columns(&industrial, spec)?),
+            None => out.write(ConsoleFormatter::format_industrial(&industrial)),
+        },
     }
+}
 
-    Ok(())
+/// Library filter from the `updates` command's `--since`, `--kb` and
+/// `--type` flags.
+fn update_filter(
+    since: Option<&str>,
+    kbs: &[String],
+    kind: Option<&str>,
+) -> Result<UpdateFilter, sysaudit::Error> {
+    let mut filter = UpdateFilter::new();
+    if let Some(since) = since {
+        filter = filter.since(updates::parse_date(since)?);
+    }
+    for kb in kbs {
+        filter = filter.kb(kb);
+    }
+    if let Some(kind) = kind.and_then(UpdateKind::parse) {
+        filter = filter.kind(kind);
+    }
+    Ok(filter)
 }
 
-fn cmd_updates(format: &str, output: Option<&std::path::Path>) -> Result<(), sysaudit::Error> {
-    let updates = WindowsUpdate::collect_all();
+fn cmd_updates(
+    filter: &UpdateFilter,
+    format: &str,
+    output: Option<&std::path::Path>,
+    columns: Option<&str>,
+    dialect: &CsvDialect,
+) -> Result<(), sysaudit::Error> {
+    let columns = column_spec(format, columns)?;
+    let out = Output::new(output);
+    let updates = match WindowsUpdate::try_collect_all() {
+        Ok(updates) => filter.apply(updates),
+        Err(e) => {
+            eprintln!("Warning: could not query Windows Updates: {}", e);
+            Vec::new()
+        }
+    };
 
     match format {
-        "json" => println!("{}", serde_json::to_string_pretty(&updates)?),
+        "json" => out.write(serde_json::to_string_pretty(&updates)?),
+        "yaml" => out.write(YamlFormatter::format(&updates)?),
+        "xml" => out.write(XmlFormatter::format_updates(&updates)),
         "csv" => {
-            let path = output.unwrap_or(std::path::Path::new("updates.csv"));
-            CsvExporter::export_updates(&updates, path)?;
+            let path = out.csv_path("updates.csv");
+            match &columns {
+                Some(spec) => CsvExporter::export_columns_with(&updates, spec, path, dialect)?,
+                None => CsvExporter::export_updates_with(&updates, path, dialect)?,
+            }
             println!("Exported {} items to {}", updates.len(), path.display());
+            Ok(())
+        }
+        "markdown" => out.write(MarkdownFormatter::format_updates(&updates)),
+        _ => match &columns {
+            Some(spec) => out.write(ConsoleFormatter::format_columns(&updates, spec)?),
+            None => out.write(ConsoleFormatter::format_updates(&updates)),
+        },
+    }
+}
+
+/// Run the full audit. Returns the `--fail-on` exit code.
+fn cmd_all(args: &AllArgs) -> Result<i32, sysaudit::Error> {
+    let dialect = args.csv.dialect()?;
+    let syslog = args.syslog_sink()?;
+    let gelf = args.gelf_sink()?;
+    let servicenow = args.servicenow_sink()?;
+    let format = args.format.as_str();
+    let template = args.template.as_deref();
+
+    if format == "table" && template.is_none() {
+        println!("Running full system audit...\n");
+    }
+
+    let options = args.scan_options()?;
+    if privileges::is_elevated().ok() == Some(false) {
+        for warning in privileges::admin_warnings(options.selected()) {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+    let scanner = LocalScanner::with_options(options);
+    let runtime = tokio::runtime::Runtime::new()?;
+    let scan = runtime.block_on(async {
+        // Ctrl+C stops the collectors at their next cancellation check.
+        let cancel = CancellationToken::new();
+        let interrupt = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                interrupt.cancel();
+            }
+        });
+        scanner.collect(&cancel).await
+    });
+    let LocalScan {
+        system,
+        software,
+        industrial,
+        mut report,
+    } = scan.map_err(|e| sysaudit::Error::General(format!("scan failed: {}", e)))?;
+    if let Some(path) = &args.previous {
+        let previous = AuditReport::from_json_versioned(&std::fs::read_to_string(path)?)?;
+        let previous_score = previous
+            .score
+            .clone()
+            .unwrap_or_else(|| AuditScore::compute(&previous));
+        report.score = report
+            .score
+            .take()
+            .map(|score| score.with_previous(&previous_score));
+    }
+    if let Some(metrics) = report.metrics.as_ref().filter(|_| args.timings) {
+        eprintln!("{}", ConsoleFormatter::format_metrics(metrics));
+    }
+
+    if let Some(path) = template {
+        print!("{}", TemplateRenderer::render(&report, path)?);
+    } else {
+        match format {
+            "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+            "yaml" => print!("{}", YamlFormatter::format(&report)?),
+            "xml" => print!("{}", XmlFormatter::format_report(&report)?),
+            "cef" => print!(
+                "{}",
+                SiemFormatter::format_cef(&report, &Finding::collect(&report))
+            ),
+            "leef" => print!(
+                "{}",
+                SiemFormatter::format_leef(&report, &Finding::collect(&report))
+            ),
+            "stix" => println!("{}", StixFormatter::format(&report)?),
+            "html" => print!("{}", HtmlFormatter::format_summary(&report)),
+            "markdown" => {
+                println!("# Audit Report: {}\n", system.computer_name);
+                println!("{}", MarkdownFormatter::format_system_info(&system));
+                println!("{}", MarkdownFormatter::format_software(&software));
+                if !industrial.is_empty() {
+                    println!("{}", MarkdownFormatter::format_industrial(&industrial));
+                }
+                println!("{}", MarkdownFormatter::format_updates(&report.updates));
+                if let Some(metadata) = &report.metadata {
+                    println!("{}", MarkdownFormatter::format_metadata(metadata));
+                }
+            }
+            _ => {
+                println!("{}\n", ConsoleFormatter::format_system_info(&system));
+                println!("{}\n", ConsoleFormatter::format_software(&software));
+                if !industrial.is_empty() {
+                    println!("{}\n", ConsoleFormatter::format_industrial(&industrial));
+                }
+                println!("{}\n", ConsoleFormatter::format_updates(&report.updates));
+                if let Some(score) = &report.score {
+                    println!("{}\n", ConsoleFormatter::format_score(score));
+                }
+                println!("{}\n", ConsoleFormatter::format_summary(&report));
+            }
+        }
+    }
+
+    // Export the report bundle (or the JSON report alone) if requested
+    if let Some(path) = &args.output {
+        let signature = match &args.sign_key {
+            Some(key_path) => Some(sign_report(&report, &load_signing_key(key_path)?)?),
+            None => None,
+        };
+        let mut bundle =
+            ReportBundle::new(&system, &software, &industrial, &report.updates, &report);
+        if let Some(signature) = &signature {
+            bundle = bundle.signature(signature.clone());
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => {
+                std::fs::write(path, serde_json::to_vec_pretty(&report)?)?;
+                if let Some(signature) = &signature {
+                    let mut sig_path = path.clone().into_os_string();
+                    sig_path.push(".sig");
+                    std::fs::write(&sig_path, signature)?;
+                }
+                eprintln!("Exported to {}", path.display());
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("zip") => {
+                bundle.export_zip(path, &dialect)?;
+                eprintln!("Exported to {}", path.display());
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => {
+                return Err(sysaudit::Error::General(
+                    "`all --output` now writes a report bundle; pass a directory, a .zip or a \
+                     .json path (use `software --output` for a single CSV)"
+                        .to_string(),
+                ));
+            }
+            _ => {
+                let dir = bundle.export_dir(path, &dialect)?;
+                eprintln!("Exported to {}", dir.display());
+            }
+        }
+    }
+
+    // Export to Excel if requested
+    if let Some(path) = &args.xlsx {
+        XlsxExporter::export_report(&report, path)?;
+        eprintln!("Exported to {}", path.display());
+    }
+
+    // Append to SQLite if requested
+    if let Some(path) = &args.sqlite {
+        SqliteExporter::export_report(&report, path)?;
+        eprintln!("Appended scan to {}", path.display());
+    }
+
+    // Write the Autopilot import CSV if requested
+    if let Some(path) = &args.autopilot_csv {
+        if CsvExporter::export_autopilot([&report], path, args.group_tag.as_deref())? == 0 {
+            eprintln!("Warning: no hardware hash collected (run as administrator)");
+        }
+        eprintln!("Exported to {}", path.display());
+    }
+
+    // Forward to syslog if requested
+    if let Some(sink) = &syslog {
+        let sent = sink.send(&report, &Finding::collect(&report))?;
+        eprintln!("Sent {} syslog messages", sent);
+    }
+
+    // Send to Graylog if requested
+    if let Some(sink) = &gelf {
+        let sent = sink.send(&report, &Finding::collect(&report))?;
+        eprintln!("Sent {} GELF messages", sent);
+    }
+
+    // Index into Elasticsearch if requested
+    if let Some(sink) = args.elastic_sink() {
+        let indexed = sink.send(&report)?;
+        eprintln!("Indexed {} documents into {}", indexed, args.elastic_index);
+    }
+
+    // Send to Splunk HEC if requested
+    if let Some(sink) = args.splunk_sink() {
+        let sent = sink.send(&report)?;
+        eprintln!("Sent {} events to Splunk", sent);
+    }
+
+    // Write or post ServiceNow import sets if requested
+    if let Some(dir) = &args.servicenow_dir {
+        let files = ServiceNowExporter::new()
+            .mapping(args.servicenow_mapping()?)
+            .export_report(&report, dir)?;
+        eprintln!(
+            "Wrote {} ServiceNow import sets to {}",
+            files.len(),
+            dir.display()
+        );
+    }
+    if let Some(sink) = &servicenow {
+        let posted = sink.send(&report)?;
+        eprintln!("Posted {} records to ServiceNow", posted);
+    }
+
+    // Publish to the Windows Event Log if requested
+    if args.eventlog {
+        let written = EventLogPublisher::publish(&report, &Finding::collect(&report))?;
+        eprintln!(
+            "Wrote {} events to the {} event log",
+            written, EVENT_LOG_NAME
+        );
+    }
+
+    Ok(fail_on(&args.fail_on, &report))
+}
+
+/// Exit code of the first `--fail-on` condition the report meets (0 when
+/// none is met), after listing every met condition on stderr.
+fn fail_on(conditions: &[FailCondition], report: &AuditReport) -> i32 {
+    let violations = gate::evaluate(conditions, report);
+    for violation in &violations {
+        eprintln!(
+            "Fail condition '{}' met: {}",
+            violation.condition, violation.detail
+        );
+    }
+    violations
+        .first()
+        .map_or(0, |first| first.condition.exit_code())
+}
+
+/// Scan options from the `--sections` and `--skip` flags of `remote` and
+/// `fleet`.
+fn fleet_options(sections: &str, skip: Option<&str>) -> Result<ScanOptions, sysaudit::Error> {
+    let options = ScanOptions::new().only(sections)?;
+    match skip {
+        Some(skip) => options.skip(skip),
+        None => Ok(options),
+    }
+}
+
+fn cmd_remote(args: &RemoteArgs) -> Result<(), sysaudit::Error> {
+    let out = Output::new(args.output.as_deref());
+    let password = if args.password_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Some(line.trim_end_matches(['\r', '\n']).to_string())
+    } else {
+        args.password.clone()
+    };
+    let credential_source = match (&args.cred, &args.password_from) {
+        (Some(target), _) => Some(CredentialSource::CredentialManager(target.clone())),
+        (None, Some(spec)) => Some(CredentialSource::parse(spec)?),
+        (None, None) => None,
+    };
+    let (events, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let scanner = RemoteScanner::builder()
+        .host(args.host.clone())
+        .maybe_username(args.user.clone())
+        .maybe_password(password.map(SecretString::from))
+        .maybe_credential_source(credential_source)
+        .auth(AuthMethod::parse(&args.winrm.auth)?)
+        .port(args.winrm.port())
+        .use_https(args.winrm.https)
+        .skip_cert_verify(args.winrm.insecure)
+        .maybe_ca_bundle(args.winrm.ca_bundle.clone())
+        .maybe_client_cert(args.winrm.client_cert())
+        .timeout(Duration::from_secs(args.winrm.timeout))
+        .retry(args.winrm.retry())
+        .options(fleet_options(&args.sections, args.skip.as_deref())?)
+        .mode(args.winrm.mode())
+        .maybe_events(args.progress.then_some(events))
+        .build();
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let report = runtime.block_on(async move {
+        let printer = tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                eprintln!("{}", event);
+            }
+        });
+        let report = scanner.audit().await;
+        drop(scanner);
+        let _ = printer.await;
+        report
+    });
+    let report = report
+        .map_err(|e| sysaudit::Error::General(format!("scan of {} failed: {}", args.host, e)))?;
+    if let Some(metrics) = report.metrics.as_ref().filter(|_| args.timings) {
+        eprintln!("{}", ConsoleFormatter::format_metrics(metrics));
+    }
+
+    match args.format.as_str() {
+        "json" => out.write(serde_json::to_string_pretty(&report)?),
+        "yaml" => out.write(YamlFormatter::format(&report)?),
+        "xml" => out.write(XmlFormatter::format_report(&report)?),
+        "html" => out.write(HtmlFormatter::format_summary(&report)),
+        "cef" => out.write(SiemFormatter::format_cef(
+            &report,
+            &Finding::collect(&report),
+        )),
+        "leef" => out.write(SiemFormatter::format_leef(
+            &report,
+            &Finding::collect(&report),
+        )),
+        "stix" => out.write(StixFormatter::format(&report)?),
+        _ => out.write(ConsoleFormatter::format_summary(&report)),
+    }
+}
+
+fn cmd_fleet(args: &FleetArgs) -> Result<(), sysaudit::Error> {
+    let dialect = args.csv.dialect()?;
+    let targets = Inventory::load(&args.hosts)?.targets()?;
+    if let Some(path) = &args.resume {
+        let completed = FleetState::open(path)?.completed().count();
+        if completed > 0 {
+            eprintln!(
+                "Resuming from {}: {} hosts already completed",
+                path.display(),
+                completed
+            );
+        }
+    }
+    let mut options = fleet_options(&args.sections, args.skip.as_deref())?;
+    if args.autopilot_csv.is_some() {
+        options = options.autopilot(true);
+    }
+    let (events, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let scanner = FleetScanner::builder()
+        .targets(targets)
+        .maybe_username(args.username.clone())
+        .maybe_password(args.password.clone().map(SecretString::from))
+        .maybe_credential_source(
+            args.password_from
+                .as_deref()
+                .map(CredentialSource::parse)
+                .transpose()?,
+        )
+        .auth(AuthMethod::parse(&args.winrm.auth)?)
+        .port(args.winrm.port())
+        .use_https(args.winrm.https)
+        .skip_cert_verify(args.winrm.insecure)
+        .maybe_ca_bundle(args.winrm.ca_bundle.clone())
+        .maybe_client_cert(args.winrm.client_cert())
+        .timeout(Duration::from_secs(args.winrm.timeout))
+        .retry(args.winrm.retry())
+        .options(options)
+        .mode(args.winrm.mode())
+        .concurrency(args.concurrency)
+        .maybe_start_interval(
+            args.max_rate
+                .map(|rate| Duration::from_secs_f64(60.0 / f64::from(rate))),
+        )
+        .maybe_state_file(args.resume.clone())
+        .maybe_events(args.progress.then_some(events))
+        .build();
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let results = runtime.block_on(async move {
+        let printer = tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                eprintln!("{}", event);
+            }
+        });
+        // Ctrl+C stops the run but still reports the hosts already scanned.
+        let cancel = CancellationToken::new();
+        let interrupt = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                eprintln!("Interrupted, cancelling remaining hosts...");
+                interrupt.cancel();
+            }
+        });
+        let results = scanner.scan_with_cancel(&cancel).await;
+        // Dropping the scanner closes the channel and ends the printer.
+        drop(scanner);
+        let _ = printer.await;
+        results
+    });
+    if args.format == FleetFormat::Table {
+        println!("{}\n", ConsoleFormatter::format_fleet(&results));
+    }
+
+    if let Some(dir) = &args.output {
+        std::fs::create_dir_all(dir)?;
+        for host in &results {
+            if let Ok(report) = &host.result {
+                let path = dir.join(format!("{}.json", host.host));
+                std::fs::write(&path, serde_json::to_string_pretty(report)?)?;
+            }
+        }
+        eprintln!("Exported to {}", dir.display());
+    }
+
+    let fleet = FleetReport::from(results);
+    match args.format {
+        FleetFormat::Json => println!("{}", serde_json::to_string_pretty(&fleet)?),
+        FleetFormat::Table => println!("{}", ConsoleFormatter::format_fleet_report(&fleet)),
+    }
+
+    if let Some(dir) = &args.csv_dir {
+        CsvExporter::export_fleet(&fleet, dir, &dialect)?;
+        eprintln!("Exported to {}", dir.display());
+    }
+
+    if let Some(path) = &args.autopilot_csv {
+        let devices = CsvExporter::export_autopilot(
+            fleet.hosts.iter().filter_map(|host| host.report.as_ref()),
+            path,
+            args.group_tag.as_deref(),
+        )?;
+        eprintln!("Exported {} devices to {}", devices, path.display());
+    }
+
+    let failed = fleet.failed();
+    if failed == fleet.hosts.len() && failed > 0 {
+        return Err(sysaudit::Error::General(format!(
+            "all {} hosts failed",
+            failed
+        )));
+    }
+    Ok(())
+}
+
+fn cmd_validate(path: &std::path::Path) -> Result<(), sysaudit::Error> {
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let violations = validate_report(&json)?;
+
+    if violations.is_empty() {
+        println!("Valid: {}", path.display());
+        return Ok(());
+    }
+
+    for violation in &violations {
+        println!("{}", violation);
+    }
+    Err(sysaudit::Error::General(format!(
+        "{} schema violation(s) in {}",
+        violations.len(),
+        path.display()
+    )))
+}
+
+fn cmd_diff(
+    old: &std::path::Path,
+    new: &std::path::Path,
+    format: &str,
+    output: Option<&std::path::Path>,
+) -> Result<(), sysaudit::Error> {
+    let out = Output::new(output);
+    let old = AuditReport::from_json_versioned(&std::fs::read_to_string(old)?)?;
+    let new = AuditReport::from_json_versioned(&std::fs::read_to_string(new)?)?;
+    let changes = diff(&old, &new);
+
+    match format {
+        "json" => out.write(serde_json::to_string_pretty(&changes)?),
+        "html" => out.write(HtmlFormatter::format_diff(&changes)),
+        _ => out.write(ConsoleFormatter::format_diff(&changes)),
+    }
+}
+
+fn cmd_merge(paths: &[PathBuf], output: Option<&std::path::Path>) -> Result<(), sysaudit::Error> {
+    let reports = paths
+        .iter()
+        .map(|path| AuditReport::from_json_versioned(&std::fs::read_to_string(path)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let merged = AuditReport::merge(&reports)?;
+
+    for source in &merged.sources {
+        eprintln!("{}: {}", source.collector, paths[source.index].display());
+    }
+    for conflict in &merged.conflicts {
+        eprintln!(
+            "Conflict: {} kept {}, discarded {}",
+            conflict.field,
+            conflict.kept.as_deref().unwrap_or("-"),
+            conflict.discarded.join(", ")
+        );
+    }
+    Output::new(output).write(serde_json::to_string_pretty(&merged.report)?)
+}
+
+fn cmd_query(
+    expression: &str,
+    report: Option<&std::path::Path>,
+    format: &str,
+    output: Option<&std::path::Path>,
+) -> Result<(), sysaudit::Error> {
+    let out = Output::new(output);
+    // Compile first, so a typo fails before a full scan.
+    let query = ReportQuery::new(expression)?;
+    let report = match report {
+        Some(path) => AuditReport::from_json_versioned(&std::fs::read_to_string(path)?)?,
+        None => local_report()?,
+    };
+    let result = query.run(&report)?;
+
+    match format {
+        "table" => out.write(ConsoleFormatter::format_value(&result)),
+        _ => out.write(serde_json::to_string_pretty(&result)?),
+    }
+}
+
+fn cmd_render(
+    path: &std::path::Path,
+    format: &str,
+    output: Option<&std::path::Path>,
+    csv: &CsvArgs,
+) -> Result<(), sysaudit::Error> {
+    let report = AuditReport::from_json_versioned(&std::fs::read_to_string(path)?)?;
+    let out = Output::new(output);
+
+    match format {
+        "json" => out.write(serde_json::to_string_pretty(&report)?),
+        "yaml" => out.write(YamlFormatter::format(&report)?),
+        "xml" => out.write(XmlFormatter::format_report(&report)?),
+        "html" => out.write(HtmlFormatter::format_summary(&report)),
+        "cef" => out.write(SiemFormatter::format_cef(
+            &report,
+            &Finding::collect(&report),
+        )),
+        "leef" => out.write(SiemFormatter::format_leef(
+            &report,
+            &Finding::collect(&report),
+        )),
+        "stix" => out.write(StixFormatter::format(&report)?),
+        "csv" => {
+            let dir = out.csv_path(".");
+            let files = CsvExporter::export_report(&report, dir, &csv.dialect()?)?;
+            for file in &files {
+                eprintln!("Written to {}", file.display());
+            }
+            Ok(())
+        }
+        "xlsx" => {
+            let path = output.ok_or_else(|| {
+                sysaudit::Error::General("--format xlsx needs --output <file.xlsx>".to_string())
+            })?;
+            XlsxExporter::export_report(&report, path)?;
+            eprintln!("Written to {}", path.display());
+            Ok(())
+        }
+        _ => out.write(ConsoleFormatter::format_report(&report)),
+    }
+}
+
+fn cmd_baseline(args: &BaselineArgs) -> Result<(), sysaudit::Error> {
+    let store = args.store();
+
+    match &args.command {
+        BaselineCommand::Save { name, report } => {
+            let report = match report {
+                Some(path) => AuditReport::from_json_versioned(&std::fs::read_to_string(path)?)?,
+                None => local_report()?,
+            };
+            let path = store.save(name, &report)?;
+            println!("Baseline '{}' saved to {}", name, path.display());
+        }
+        BaselineCommand::Check {
+            name,
+            report,
+            format,
+            output,
+        } => {
+            let out = Output::new(output.as_deref());
+            let baseline = store.load(name)?;
+            let report = match report {
+                Some(path) => AuditReport::from_json_versioned(&std::fs::read_to_string(path)?)?,
+                None => local_report()?,
+            };
+            let deviations = baseline.check(&report);
+
+            match format.as_str() {
+                "json" => out.write(serde_json::to_string_pretty(&deviations)?)?,
+                _ if deviations.is_empty() => out.write(format!(
+                    "{} matches baseline '{}'",
+                    report.system.host_name, name
+                ))?,
+                _ => out.write(ConsoleFormatter::format_findings(&deviations))?,
+            }
+            if !deviations.is_empty() {
+                return Err(sysaudit::Error::General(format!(
+                    "{} deviation(s) from baseline '{}'",
+                    deviations.len(),
+                    name
+                )));
+            }
+        }
+        BaselineCommand::List => {
+            for name in store.list()? {
+                println!("{}", name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_history(args: &HistoryArgs) -> Result<(), sysaudit::Error> {
+    let mut db = args.open()?;
+
+    match &args.command {
+        HistoryCommand::Record { report } => {
+            let report = match report {
+                Some(path) => AuditReport::from_json_versioned(&std::fs::read_to_string(path)?)?,
+                None => local_report()?,
+            };
+            db.record(&report)?;
+            println!(
+                "Recorded scan of {} at {}",
+                report.system.host_name,
+                report.timestamp.format("%Y-%m-%d %H:%M")
+            );
+        }
+        HistoryCommand::Show {
+            host,
+            metric,
+            format,
+            output,
+        } => {
+            let out = Output::new(output.as_deref());
+            let metric = HistoryMetric::parse(metric)?;
+            let points = db.trend(host, metric)?;
+            if points.is_empty() {
+                return Err(sysaudit::Error::General(format!(
+                    "no recorded scans for '{}'",
+                    host
+                )));
+            }
+
+            match format.as_str() {
+                "json" => out.write(serde_json::to_string_pretty(&points)?)?,
+                _ => {
+                    let mut lines = vec![format!("{} {}", host, metric)];
+                    let mut previous: Option<i64> = None;
+                    for point in &points {
+                        let value = point.value.map_or("-".to_string(), |v| v.to_string());
+                        let change = match (previous, point.value) {
+                            (Some(before), Some(now)) if now != before => {
+                                format!("  ({:+})", now - before)
+                            }
+                            _ => String::new(),
+                        };
+                        lines.push(format!(
+                            "{}  {:<16} {:>8}{}",
+                            point.scanned_at.format("%Y-%m-%d %H:%M"),
+                            point.host_name,
+                            value,
+                            change
+                        ));
+                        previous = point.value.or(previous);
+                    }
+                    out.write(lines.join("\n"))?;
+                }
+            }
+        }
+        HistoryCommand::Prune { older_than } => {
+            let age = chrono::Duration::from_std(parse_interval(older_than)?)
+                .map_err(|e| sysaudit::Error::General(e.to_string()))?;
+            let pruned = db.prune(chrono::Utc::now() - age)?;
+            println!("Pruned {} scans older than {}", pruned, older_than);
         }
-        _ => println!("{}", ConsoleFormatter::format_updates(&updates)),
     }
 
     Ok(())
 }
 
-fn cmd_all(output: Option<&std::path::Path>) -> Result<(), sysaudit::Error> {
-    println!("Running full system audit...\n");
+#[cfg(feature = "api")]
+fn cmd_serve_api(args: ServeApiArgs) -> Result<(), sysaudit::Error> {
+    let remote = match &args.hosts {
+        Some(path) => Some(api::RemoteHosts {
+            targets: Inventory::load(path)?.targets()?,
+            username: args.username,
+            password: args.password.map(SecretString::from),
+            auth: AuthMethod::parse(&args.auth)?,
+            port: args.port.unwrap_or(if args.https { 5986 } else { 5985 }),
+            use_https: args.https,
+        }),
+        None => None,
+    };
+    api::ApiServer {
+        listen: args.listen,
+        cert: args.cert,
+        key: args.key,
+        token: SecretString::from(args.token),
+        remote,
+        workers: args.workers,
+    }
+    .run()
+}
+
+fn cmd_watch(args: &WatchArgs) -> Result<(), sysaudit::Error> {
+    let actions = ChangeAction::parse_all(&args.on_change)?;
+    let history = ReportHistory::new(&args.output_dir);
+    // The sender stays alive, so without a watcher the wait is a plain
+    // sleep of one interval.
+    let (notify, changes) = std::sync::mpsc::channel();
+    let _watcher = if args.watch_changes {
+        let notify = notify.clone();
+        Some(ChangeWatcher::start(move |trigger| {
+            notify.send(trigger).is_ok()
+        })?)
+    } else {
+        None
+    };
+    eprintln!(
+        "Watching this host every {:?}{}, reports in {} (Ctrl+C to stop)",
+        args.interval,
+        if args.watch_changes {
+            " and on software changes"
+        } else {
+            ""
+        },
+        history.dir().display()
+    );
+
+    loop {
+        // A failed scan is reported and retried at the next interval.
+        if let Err(e) = watch_scan(&history, &actions) {
+            tracing::error!(error = %e, "Scan failed");
+            eprintln!("Scan failed: {}", e);
+        }
+        if let Ok(trigger) = changes.recv_timeout(args.interval) {
+            eprintln!("{}, rescanning once changes settle", trigger);
+            // One installation touches many keys: scan once it is done.
+            while changes.recv_timeout(SETTLE_TIME).is_ok() {}
+        }
+    }
+}
 
-    // System info
-    let system = SystemInfo::collect()?;
-    println!("{}\n", ConsoleFormatter::format_system_info(&system));
+/// One `watch` round: scan, store, and alert on inventory changes.
+fn watch_scan(history: &ReportHistory, actions: &[ChangeAction]) -> Result<(), sysaudit::Error> {
+    let report = local_report()?;
+    let previous = history.latest(&report.system.host_name)?;
+    let path = history.save(&report)?;
+    println!("Saved {}", path.display());
 
-    // Software
-    let software = SoftwareScanner::new().scan()?;
-    println!("{}\n", ConsoleFormatter::format_software(&software));
+    let Some(previous) = previous else {
+        return Ok(());
+    };
+    let changes = diff(&previous, &report);
+    if !changes.inventory_changed() {
+        return Ok(());
+    }
 
-    // Industrial
-    let industrial = IndustrialScanner::all_vendors().scan()?;
-    if !industrial.is_empty() {
-        println!("{}\n", ConsoleFormatter::format_industrial(&industrial));
+    let diff_path = history.save_diff(&report, &changes)?;
+    println!("{}\n", ConsoleFormatter::format_diff(&changes));
+    for action in actions {
+        // One failing alert must not keep the others from running.
+        if let Err(e) = action.run(&changes, &path, &diff_path) {
+            tracing::error!(error = %e, "Change alert failed");
+            eprintln!("Change alert failed: {}", e);
+        }
     }
+    Ok(())
+}
+
+fn cmd_check(
+    policy: &Policy,
+    report: Option<&std::path::Path>,
+    min_score: Option<u8>,
+    format: &str,
+    output: Option<&std::path::Path>,
+) -> Result<(), sysaudit::Error> {
+    let out = Output::new(output);
+    let report = match report {
+        Some(path) => AuditReport::from_json_versioned(&std::fs::read_to_string(path)?)?,
+        None => local_report()?,
+    };
+    let result = policy.evaluate(&report);
 
-    // Updates
-    let updates = WindowsUpdate::collect_all();
-    println!("{}\n", ConsoleFormatter::format_updates(&updates));
+    match format {
+        "json" => out.write(serde_json::to_string_pretty(&result)?)?,
+        _ => out.write(ConsoleFormatter::format_compliance(&result))?,
+    }
 
-    // Export to CSV if requested
-    if let Some(path) = output {
-        CsvExporter::export_software(&software, path)?;
-        println!("Exported to {}", path.display());
+    match min_score {
+        Some(min) if result.score.unwrap_or(0) < min => Err(sysaudit::Error::General(format!(
+            "score {}% is below the required {}%",
+            result.score.unwrap_or(0),
+            min
+        ))),
+        Some(_) => Ok(()),
+        None if result.passed() => Ok(()),
+        None => Err(sysaudit::Error::General(format!(
+            "{} of {} policy rules not satisfied",
+            result.failures().count(),
+            result.results.len()
+        ))),
     }
+}
+
+/// Collect every section of this host's report.
+fn local_report() -> Result<AuditReport, sysaudit::Error> {
+    LocalScanner::with_options(ScanOptions::new().sections(Sections::all()))
+        .scan_blocking()
+        .map_err(|e| sysaudit::Error::General(format!("scan failed: {}", e)))
+}
+
+fn cmd_verify(
+    report: &std::path::Path,
+    signature: &std::path::Path,
+    key: &std::path::Path,
+) -> Result<(), sysaudit::Error> {
+    let json = std::fs::read(report)?;
+    let signature = std::fs::read_to_string(signature)?;
+    let key = load_verifying_key(key)?;
 
+    verify_report(&json, &signature, &key)?;
+    println!("Signature OK: {}", report.display());
     Ok(())
 }
@@ -0,0 +1,327 @@
+//! `sysaudit service`: scheduled scans as a Windows service.
+//!
+//! `install` registers `sysaudit service run --config <file>` with the
+//! service control manager (automatic start, LocalSystem), `run` is what
+//! the SCM starts, and `uninstall` stops and removes the service. Each
+//! round scans this host and ships the report to the sinks of the
+//! [`ScheduleConfig`]; a failed scan or sink is logged and retried at the
+//! next interval. With `watch_changes`, a software installation or
+//! removal starts a round early.
+
+use secrecy::SecretString;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use sysaudit::history::HistoryDb;
+use sysaudit::output::{
+    ElasticAuth, ElasticMode, ElasticSink, GelfMode, GelfSink, GelfTransport, SyslogSink,
+    SyslogTransport,
+};
+use sysaudit::schedule::ScheduleConfig;
+use sysaudit::watch::{ChangeTrigger, ChangeWatcher, ReportHistory, SETTLE_TIME};
+use sysaudit::{BlockingScanner, Finding, LocalScanner};
+use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+
+/// Name the service is registered under.
+pub const SERVICE_NAME: &str = "SysAudit";
+
+const DISPLAY_NAME: &str = "SysAudit scheduled audit";
+
+const DESCRIPTION: &str =
+    "Scans this host on a schedule and ships the audit report to the configured sinks.";
+
+/// Configuration file of the running service, set before dispatching.
+static CONFIG: OnceLock<PathBuf> = OnceLock::new();
+
+/// What ends the wait between two rounds early.
+enum Wake {
+    /// The SCM asked the service to stop
+    Stop,
+    /// Software was installed or removed
+    Change(ChangeTrigger),
+}
+
+/// Register the service to run with `config`, starting automatically.
+///
+/// # Errors
+///
+/// Returns [`sysaudit::Error::General`] if the configuration is invalid or
+/// the service cannot be created (e.g. without administrator rights).
+pub fn install(config: &Path) -> Result<(), sysaudit::Error> {
+    // Validate now rather than failing silently at service start.
+    ScheduleConfig::load(config)?;
+    let config = std::fs::canonicalize(config)?;
+
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .map_err(service_error)?;
+    let info = ServiceInfo {
+        name: OsString::from(SERVICE_NAME),
+        display_name: OsString::from(DISPLAY_NAME),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: std::env::current_exe()?,
+        launch_arguments: vec![
+            OsString::from("service"),
+            OsString::from("run"),
+            OsString::from("--config"),
+            config.clone().into_os_string(),
+        ],
+        dependencies: vec![],
+        // LocalSystem, so every section can be read.
+        account_name: None,
+        account_password: None,
+    };
+    let service = manager
+        .create_service(&info, ServiceAccess::CHANGE_CONFIG | ServiceAccess::START)
+        .map_err(service_error)?;
+    service
+        .set_description(DESCRIPTION)
+        .map_err(service_error)?;
+    service.start::<&str>(&[]).map_err(service_error)?;
+    println!(
+        "Installed and started service '{}' with {}",
+        SERVICE_NAME,
+        config.display()
+    );
+    Ok(())
+}
+
+/// Stop the service if it runs and remove it.
+///
+/// # Errors
+///
+/// Returns [`sysaudit::Error::General`] if the service is not installed or
+/// cannot be removed.
+pub fn uninstall() -> Result<(), sysaudit::Error> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .map_err(service_error)?;
+    let service = manager
+        .open_service(
+            SERVICE_NAME,
+            ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+        )
+        .map_err(service_error)?;
+    if service.query_status().map_err(service_error)?.current_state != ServiceState::Stopped {
+        service.stop().map_err(service_error)?;
+    }
+    service.delete().map_err(service_error)?;
+    println!("Removed service '{}'", SERVICE_NAME);
+    Ok(())
+}
+
+/// Run under the service control manager, or in the foreground with
+/// `console` (Ctrl+C to stop) to try a configuration.
+///
+/// # Errors
+///
+/// Returns [`sysaudit::Error::General`] if the configuration is invalid or
+/// the process was not started by the service control manager.
+pub fn run(config: &Path, console: bool) -> Result<(), sysaudit::Error> {
+    let schedule = ScheduleConfig::load(config)?;
+    if console {
+        let (wake, woken) = mpsc::channel();
+        return run_schedule(&schedule, &wake, &woken);
+    }
+    let _ = CONFIG.set(config.to_path_buf());
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main).map_err(|e| {
+        sysaudit::Error::General(format!(
+            "not started by the service control manager ({}); use `sysaudit service install` \
+             or `sysaudit service run --console`",
+            e
+        ))
+    })
+}
+
+define_windows_service!(ffi_service_main, service_main);
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(e) = run_service() {
+        tracing::error!(error = %e, "Service failed");
+    }
+}
+
+/// Report the service as running until the SCM asks it to stop.
+fn run_service() -> Result<(), sysaudit::Error> {
+    let schedule = match CONFIG.get() {
+        Some(path) => ScheduleConfig::load(path)?,
+        None => {
+            return Err(sysaudit::Error::General(
+                "service started without --config".to_string(),
+            ));
+        }
+    };
+
+    let (wake, woken) = mpsc::channel();
+    let stop = wake.clone();
+    let handler = move |control| match control {
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            let _ = stop.send(Wake::Stop);
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+    let status = service_control_handler::register(SERVICE_NAME, handler).map_err(service_error)?;
+    let report_state = |state, accept| {
+        status.set_service_status(ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: accept,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        })
+    };
+
+    report_state(
+        ServiceState::Running,
+        ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+    )
+    .map_err(service_error)?;
+    let result = run_schedule(&schedule, &wake, &woken);
+    report_state(ServiceState::Stopped, ServiceControlAccept::empty()).map_err(service_error)?;
+    result
+}
+
+/// Scan and ship every interval, and after each software change when the
+/// schedule watches for them, until `woken` receives [`Wake::Stop`].
+fn run_schedule(
+    schedule: &ScheduleConfig,
+    wake: &mpsc::Sender<Wake>,
+    woken: &mpsc::Receiver<Wake>,
+) -> Result<(), sysaudit::Error> {
+    let interval = schedule.interval()?;
+    let scanner = LocalScanner::with_options(schedule.scan_options()?);
+    let _watcher = if schedule.watch_changes {
+        let wake = wake.clone();
+        Some(ChangeWatcher::start(move |trigger| {
+            wake.send(Wake::Change(trigger)).is_ok()
+        })?)
+    } else {
+        None
+    };
+    tracing::info!(
+        interval = ?interval,
+        watch_changes = schedule.watch_changes,
+        "Scheduled scans started"
+    );
+    loop {
+        if let Err(e) = scan_and_ship(&scanner, schedule) {
+            tracing::error!(error = %e, "Scheduled scan failed");
+        }
+        match woken.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            Ok(Wake::Change(trigger)) => {
+                tracing::info!(%trigger, "Software change detected");
+                if !settle(woken) {
+                    break;
+                }
+            }
+            _ => break,
+        }
+    }
+    tracing::info!("Scheduled scans stopped");
+    Ok(())
+}
+
+/// Wait until [`SETTLE_TIME`] passes without another change, so one
+/// installation starts one round. False when the service is stopping.
+fn settle(woken: &mpsc::Receiver<Wake>) -> bool {
+    loop {
+        match woken.recv_timeout(SETTLE_TIME) {
+            Ok(Wake::Change(_)) => continue,
+            Err(RecvTimeoutError::Timeout) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// One round: scan and send the report to every configured sink. A
+/// failing sink does not keep the others from receiving the report.
+fn scan_and_ship(scanner: &LocalScanner, schedule: &ScheduleConfig) -> Result<(), sysaudit::Error> {
+    let report = scanner
+        .scan_blocking()
+        .map_err(|e| sysaudit::Error::General(e.to_string()))?;
+
+    if let Some(dir) = &schedule.output_dir {
+        match ReportHistory::new(dir).save(&report) {
+            Ok(path) => tracing::info!(path = %path.display(), "Report written"),
+            Err(e) => tracing::error!(error = %e, "Could not write report"),
+        }
+    }
+    if let Some(path) = &schedule.history_db {
+        match HistoryDb::open(path).and_then(|mut db| db.record(&report)) {
+            Ok(()) => tracing::info!(path = %path.display(), "Scan recorded in history"),
+            Err(e) => tracing::error!(error = %e, "Could not record scan in history"),
+        }
+    }
+    if let Some(target) = &schedule.elastic {
+        let auth = match target
+            .api_key_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+        {
+            Some(key) => ElasticAuth::ApiKey(SecretString::from(key)),
+            None => ElasticAuth::None,
+        };
+        let mode = if target.items {
+            ElasticMode::Items
+        } else {
+            ElasticMode::Report
+        };
+        let sink = ElasticSink::new(target.url.clone())
+            .index(target.index.clone())
+            .auth(auth)
+            .mode(mode);
+        match sink.send(&report) {
+            Ok(indexed) => tracing::info!(indexed, "Report indexed into Elasticsearch"),
+            Err(e) => tracing::error!(error = %e, "Could not index report"),
+        }
+    }
+    if let Some(target) = &schedule.syslog {
+        let sent = SyslogTransport::parse(&target.protocol).and_then(|transport| {
+            SyslogSink::new(target.address.clone())
+                .transport(transport)
+                .send(&report, &Finding::collect(&report))
+        });
+        match sent {
+            Ok(sent) => tracing::info!(sent, "Report forwarded to syslog"),
+            Err(e) => tracing::error!(error = %e, "Could not forward report to syslog"),
+        }
+    }
+    if let Some(target) = &schedule.gelf {
+        let mode = if target.items {
+            GelfMode::Items
+        } else {
+            GelfMode::Summary
+        };
+        let sent = GelfTransport::parse(&target.protocol).and_then(|transport| {
+            GelfSink::new(target.address.clone())
+                .transport(transport)
+                .mode(mode)
+                .send(&report, &Finding::collect(&report))
+        });
+        match sent {
+            Ok(sent) => tracing::info!(sent, "Report sent to Graylog"),
+            Err(e) => tracing::error!(error = %e, "Could not send report to Graylog"),
+        }
+    }
+    Ok(())
+}
+
+fn service_error(e: windows_service::Error) -> sysaudit::Error {
+    sysaudit::Error::General(format!("service control manager: {}", e))
+}
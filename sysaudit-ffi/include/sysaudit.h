@@ -0,0 +1,40 @@
+/*
+ * sysaudit.h - C ABI of the Windows system auditor (sysaudit-ffi).
+ *
+ * Link against sysaudit_ffi.dll (import library sysaudit_ffi.dll.lib) or the
+ * static sysaudit_ffi.lib. All strings are UTF-8 and NUL-terminated.
+ */
+#ifndef SYSAUDIT_H
+#define SYSAUDIT_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Scan this machine and return the report as JSON (the `sysaudit all`
+ * report.json document). `sections` is a comma-separated list such as
+ * "software,updates", or NULL for the defaults. Blocks until done.
+ *
+ * Returns NULL on failure; call sysaudit_last_error() for the reason.
+ * Free the result with sysaudit_string_free().
+ */
+char *sysaudit_scan_json(const char *sections);
+
+/*
+ * Message of the last failed call on this thread, or NULL. Valid until the
+ * next call into the library on the same thread; do not free it.
+ */
+const char *sysaudit_last_error(void);
+
+/* Release a string returned by sysaudit_scan_json(). NULL is ignored. */
+void sysaudit_string_free(char *s);
+
+/* Library version, e.g. "0.1.0". Static; do not free it. */
+const char *sysaudit_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SYSAUDIT_H */
@@ -0,0 +1,144 @@
+//! # sysaudit-ffi
+//!
+//! Stable C ABI over [`sysaudit`], for callers in C, C#, Python (`ctypes`)
+//! or any language that can load a DLL. The whole report crosses the
+//! boundary as one UTF-8 JSON document, the same one `sysaudit all` writes,
+//! so no Rust types leak into the ABI. The declarations are in
+//! `include/sysaudit.h`.
+//!
+//! Every function is safe to call from any thread. Strings returned by
+//! [`sysaudit_scan_json`] are owned by the caller and must be released with
+//! [`sysaudit_string_free`]; on failure it returns `NULL` and
+//! [`sysaudit_last_error`] describes why.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::AssertUnwindSafe;
+use sysaudit::{BlockingScanner, LocalScanner, ScanOptions};
+
+thread_local! {
+    /// Message of the last failed call on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Scan this machine and return the report as a JSON string.
+///
+/// `sections` is a comma-separated list as accepted by `sysaudit all
+/// --only` (e.g. `"software,updates"`), or `NULL` for the default
+/// sections. Blocks until the scan completes.
+///
+/// Returns `NULL` on failure; see [`sysaudit_last_error`].
+///
+/// # Safety
+///
+/// `sections` must be `NULL` or point to a NUL-terminated string that
+/// stays valid for the duration of the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysaudit_scan_json(sections: *const c_char) -> *mut c_char {
+    let sections = if sections.is_null() {
+        None
+    } else {
+        // SAFETY: non-null, and the caller guarantees a valid C string.
+        Some(unsafe { CStr::from_ptr(sections) })
+    };
+    // Unwinding across `extern "C"` aborts the host process; report a panic
+    // as an error instead.
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| scan_json(sections)))
+        .unwrap_or_else(|_| Err("scan panicked".to_string()));
+    match result.and_then(|json| CString::new(json).map_err(|e| e.to_string())) {
+        Ok(json) => {
+            set_last_error(None);
+            json.into_raw()
+        }
+        Err(message) => {
+            set_last_error(Some(message));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Message of the last failed call on the calling thread, or `NULL` if it
+/// succeeded.
+///
+/// The pointer stays valid until the next call into this library on the
+/// same thread; it must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn sysaudit_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Release a string returned by [`sysaudit_scan_json`]. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `s` must be `NULL` or a pointer returned by this library that has not
+/// been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysaudit_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the caller guarantees `s` came from `CString::into_raw`
+        // in this library and is freed only once.
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Library version, e.g. `"0.1.0"`. The string is static; do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn sysaudit_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Run a local scan of `sections` and serialize the report.
+fn scan_json(sections: Option<&CStr>) -> Result<String, String> {
+    let mut options = ScanOptions::new();
+    if let Some(sections) = sections {
+        let sections = sections
+            .to_str()
+            .map_err(|_| "sections is not valid UTF-8".to_string())?;
+        options = options.only(sections).map_err(|e| e.to_string())?;
+    }
+    let report = LocalScanner::with_options(options)
+        .scan_blocking()
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&report).map_err(|e| e.to_string())
+}
+
+fn set_last_error(message: Option<String>) {
+    // Messages come from Display impls and contain no NUL bytes; drop any
+    // that would.
+    let message = message.and_then(|m| CString::new(m).ok());
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_sections_sets_last_error() {
+        // SAFETY: a valid NUL-terminated literal.
+        let json = unsafe { sysaudit_scan_json(c"registry".as_ptr()) };
+        assert!(json.is_null());
+
+        let error = sysaudit_last_error();
+        assert!(!error.is_null());
+        // SAFETY: non-null pointers from `sysaudit_last_error` are valid C
+        // strings until the next call on this thread.
+        let message = unsafe { CStr::from_ptr(error) }.to_str().unwrap();
+        assert!(message.contains("registry"));
+
+        // SAFETY: NULL is accepted.
+        unsafe { sysaudit_string_free(std::ptr::null_mut()) };
+    }
+
+    #[test]
+    fn test_version() {
+        // SAFETY: `sysaudit_version` returns a static C string.
+        let version = unsafe { CStr::from_ptr(sysaudit_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+}
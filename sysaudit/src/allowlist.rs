@@ -0,0 +1,370 @@
+//! Approved-software lists.
+//!
+//! An [`Allowlist`] is loaded from a CSV file with the columns
+//! `name,version,status` (only `name` is required):
+//!
+//! ```csv
+//! name,version,status
+//! AVEVA Citect SCADA,>=8.2 <9,approved
+//! Microsoft Visual C++ *,,approved
+//! Mozilla Firefox*,>=115,approved
+//! TeamViewer*,,blocked
+//! ```
+//!
+//! Names match case-insensitively; `*` matches any run of characters.
+//! Versions are constraints separated by spaces, all of which must hold:
+//! `8.2` (exactly), `8.2.*` (that release line), or a comparison
+//! (`>=8.2`, `>8`, `<=9.1`, `<9`, `=8.2`). An empty version allows any.
+//! `status` is `approved` (the default) or `blocked`.
+//!
+//! Each installed item is then [approved](AllowStatus::Approved),
+//! [unapproved](AllowStatus::Unapproved) (blocked, or listed but not at
+//! an allowed version) or [unknown](AllowStatus::Unknown) (not listed).
+
+use crate::software::compare_versions;
+use crate::{Error, Software};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Classification of an installed item against an [`Allowlist`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowStatus {
+    /// Listed as approved at this version
+    Approved,
+    /// Blocked, or listed at other versions only
+    Unapproved,
+    /// Not on the list
+    Unknown,
+}
+
+impl std::fmt::Display for AllowStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AllowStatus::Approved => write!(f, "approved"),
+            AllowStatus::Unapproved => write!(f, "unapproved"),
+            AllowStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// An installed item and its classification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassifiedSoftware {
+    /// The installed item
+    #[serde(flatten)]
+    pub software: Software,
+    /// Classification
+    pub status: AllowStatus,
+    /// Why the item got its status, e.g. the rule it matched
+    pub reason: String,
+}
+
+impl ClassifiedSoftware {
+    /// Whether the item breaks the allowlist (unapproved or unknown).
+    pub fn is_violation(&self) -> bool {
+        self.status != AllowStatus::Approved
+    }
+}
+
+/// Approved and blocked software, by name and version.
+#[derive(Debug, Clone, Default)]
+pub struct Allowlist {
+    entries: Vec<Entry>,
+}
+
+/// One row of the list.
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    version: String,
+    constraints: Vec<Constraint>,
+    blocked: bool,
+}
+
+/// A single version constraint.
+#[derive(Debug, Clone)]
+enum Constraint {
+    /// Compares as `Ordering` (or, with the flag, also equal) to the version
+    Compare(Ordering, bool, String),
+    /// Starts with these dotted segments (`8.2.*`)
+    Prefix(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+}
+
+impl Allowlist {
+    /// Parse a CSV list with a header row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Csv`] for malformed rows, or [`Error::General`]
+    /// for an empty name, an invalid version constraint or an unknown
+    /// status.
+    pub fn from_csv(csv: &str) -> Result<Self, Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(csv.as_bytes());
+
+        let mut entries = Vec::new();
+        for row in reader.deserialize() {
+            let row: CsvRow = row?;
+            if row.name.is_empty() {
+                return Err(Error::General("allowlist row without a name".to_string()));
+            }
+            let version = row.version.unwrap_or_default();
+            let blocked = match row.status.as_deref().map(str::to_ascii_lowercase) {
+                None => false,
+                Some(status) => match status.as_str() {
+                    "" | "approved" | "allowed" => false,
+                    "blocked" | "denied" => true,
+                    _ => {
+                        return Err(Error::General(format!(
+                            "unknown status '{}' for '{}': use approved or blocked",
+                            status, row.name
+                        )));
+                    }
+                },
+            };
+            entries.push(Entry {
+                constraints: parse_constraints(&version)?,
+                name: row.name,
+                version,
+                blocked,
+            });
+        }
+        Ok(Allowlist { entries })
+    }
+
+    /// Load a CSV list from a file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SoftwareScanner;
+    /// use sysaudit::allowlist::Allowlist;
+    ///
+    /// let allowlist = Allowlist::load("approved.csv").unwrap();
+    /// let classified = allowlist.classify_all(SoftwareScanner::new().scan().unwrap());
+    /// for item in classified.iter().filter(|c| c.is_violation()) {
+    ///     println!("{}: {} ({})", item.status, item.software.name, item.reason);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, otherwise as
+    /// [`from_csv`](Self::from_csv).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_csv(&std::fs::read_to_string(path)?)
+    }
+
+    /// Number of rules.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the list has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Classify one installed item, with the reason for its status.
+    ///
+    /// A matching `blocked` rule wins over `approved` ones.
+    pub fn classify(&self, name: &str, version: Option<&str>) -> (AllowStatus, String) {
+        let listed: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|e| name_matches(&e.name, name))
+            .collect();
+        if listed.is_empty() {
+            return (AllowStatus::Unknown, "not on the list".to_string());
+        }
+        let allows = |e: &&&Entry| e.constraints.iter().all(|c| c.allows(version));
+        if let Some(rule) = listed.iter().filter(|e| e.blocked).find(allows) {
+            return (
+                AllowStatus::Unapproved,
+                format!("blocked by '{}'", rule.describe()),
+            );
+        }
+        if let Some(rule) = listed.iter().filter(|e| !e.blocked).find(allows) {
+            return (
+                AllowStatus::Approved,
+                format!("approved by '{}'", rule.describe()),
+            );
+        }
+        let allowed: Vec<String> = listed
+            .iter()
+            .filter(|e| !e.blocked)
+            .map(|e| e.version.clone())
+            .collect();
+        if allowed.is_empty() {
+            // Only blocked at other versions: neither approved nor blocked.
+            return (AllowStatus::Unknown, "not on the list".to_string());
+        }
+        (
+            AllowStatus::Unapproved,
+            format!(
+                "version {} not allowed ({})",
+                version.unwrap_or("unknown"),
+                allowed.join("; ")
+            ),
+        )
+    }
+
+    /// Classify every installed item, keeping their order.
+    pub fn classify_all(&self, software: Vec<Software>) -> Vec<ClassifiedSoftware> {
+        software
+            .into_iter()
+            .map(|software| {
+                let (status, reason) = self.classify(&software.name, software.version.as_deref());
+                ClassifiedSoftware {
+                    software,
+                    status,
+                    reason,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Entry {
+    fn describe(&self) -> String {
+        if self.version.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.name, self.version)
+        }
+    }
+}
+
+impl Constraint {
+    /// Whether `version` satisfies the constraint; an unknown version
+    /// satisfies none.
+    fn allows(&self, version: Option<&str>) -> bool {
+        let Some(version) = version else {
+            return false;
+        };
+        match self {
+            Constraint::Compare(ordering, or_equal, bound) => {
+                let actual = compare_versions(version, bound);
+                actual == *ordering || (*or_equal && actual == Ordering::Equal)
+            }
+            Constraint::Prefix(prefix) => {
+                let segments = prefix.split('.').count();
+                let head: Vec<&str> = version.split('.').take(segments).collect();
+                head.len() == segments
+                    && compare_versions(&head.join("."), prefix) == Ordering::Equal
+            }
+        }
+    }
+}
+
+/// Parse space-separated constraints; empty or `*` allows any version.
+fn parse_constraints(text: &str) -> Result<Vec<Constraint>, Error> {
+    text.split_whitespace()
+        .filter(|c| *c != "*")
+        .map(|c| {
+            let (ordering, or_equal, bound) = if let Some(v) = c.strip_prefix(">=") {
+                (Ordering::Greater, true, v)
+            } else if let Some(v) = c.strip_prefix("<=") {
+                (Ordering::Less, true, v)
+            } else if let Some(v) = c.strip_prefix('>') {
+                (Ordering::Greater, false, v)
+            } else if let Some(v) = c.strip_prefix('<') {
+                (Ordering::Less, false, v)
+            } else if let Some(prefix) = c.strip_suffix(".*") {
+                return Ok(Constraint::Prefix(prefix.to_string()));
+            } else {
+                (Ordering::Equal, true, c.strip_prefix('=').unwrap_or(c))
+            };
+            if bound.is_empty() || bound.contains('*') {
+                return Err(Error::General(format!(
+                    "invalid version constraint '{}'",
+                    c
+                )));
+            }
+            Ok(Constraint::Compare(ordering, or_equal, bound.to_string()))
+        })
+        .collect()
+}
+
+/// Case-insensitive name match where `*` stands for any characters.
+fn name_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST: &str = "name,version,status\n\
+        AVEVA Citect SCADA,>=8.2 <9,approved\n\
+        Microsoft Visual C++ *,,\n\
+        Mozilla Firefox*,115.*,approved\n\
+        TeamViewer*,,blocked\n";
+
+    #[test]
+    fn test_classify() {
+        let list = Allowlist::from_csv(LIST).unwrap();
+        assert_eq!(list.len(), 4);
+        let status = |name: &str, version: &str| list.classify(name, Some(version)).0;
+
+        assert_eq!(status("AVEVA Citect SCADA", "8.2.1"), AllowStatus::Approved);
+        assert_eq!(status("aveva citect scada", "9.0"), AllowStatus::Unapproved);
+        assert_eq!(
+            status(
+                "Microsoft Visual C++ 2015-2022 Redistributable (x64)",
+                "14.38"
+            ),
+            AllowStatus::Approved
+        );
+        assert_eq!(
+            status("Mozilla Firefox (x64 en-US)", "115.6.0"),
+            AllowStatus::Approved
+        );
+        assert_eq!(
+            status("Mozilla Firefox (x64 en-US)", "121.0"),
+            AllowStatus::Unapproved
+        );
+        assert_eq!(status("TeamViewer 15", "15.50"), AllowStatus::Unapproved);
+        assert_eq!(status("7-Zip 23.01", "23.01"), AllowStatus::Unknown);
+        assert_eq!(
+            list.classify("AVEVA Citect SCADA", None).0,
+            AllowStatus::Unapproved
+        );
+    }
+
+    #[test]
+    fn test_invalid_list() {
+        assert!(Allowlist::from_csv("name,version\nFoo,>=\n").is_err());
+        assert!(Allowlist::from_csv("name,status\nFoo,maybe\n").is_err());
+        assert!(Allowlist::from_csv("version\n1.0\n").is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! Stable asset identifier.
+//!
+//! Host names change when a machine is reimaged or renamed, which breaks
+//! the history of a host across reports. Each report therefore carries an
+//! `asset_id` derived from the hardware:
+//!
+//! 1. The BIOS serial number, trimmed and upper-cased, unless it is empty
+//!    or a vendor placeholder ("To Be Filled By O.E.M.", "Default string",
+//!    "0", ...). It survives reimaging and NIC swaps.
+//! 2. Otherwise the set of the host's MAC addresses: upper-cased,
+//!    colon-separated, sorted and deduplicated, leaving out all-zero and
+//!    locally administered (virtual or randomized) addresses. It survives
+//!    reimaging, not adapter replacements.
+//! 3. Otherwise the Windows `MachineGuid`, lower-cased without braces.
+//!    Reimaging assigns a new one.
+//!
+//! The first available source is written as `bios:<value>`, `mac:<a>,<b>`
+//! or `guid:<value>`, hashed with SHA-256, and the first 16 bytes of the
+//! digest are formatted like a UUID. The ID is prefixed with `v1-` so a
+//! future change of the algorithm cannot collide with it. The remote
+//! payload's `Get-AssetId` computes the same ID; keep the two in sync.
+//!
+//! Where the hardware does not tell hosts apart (cloned VMs with a copied
+//! serial, for example), set the ID yourself with
+//! [`ScanOptions::asset_id`](crate::ScanOptions::asset_id).
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Serial numbers vendors ship in place of a real one.
+const PLACEHOLDER_SERIALS: &[&str] = &[
+    "0",
+    "00000000",
+    "0123456789",
+    "123456789",
+    "DEFAULT STRING",
+    "NONE",
+    "NOT APPLICABLE",
+    "NOT SPECIFIED",
+    "O.E.M.",
+    "SYSTEM SERIAL NUMBER",
+    "TO BE FILLED BY O.E.M.",
+];
+
+/// Hardware identifiers an asset ID is derived from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetFingerprint {
+    /// `MachineGuid` from `HKLM\SOFTWARE\Microsoft\Cryptography`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_guid: Option<String>,
+    /// BIOS serial number (`Win32_BIOS.SerialNumber`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bios_serial: Option<String>,
+    /// MAC addresses of the host's network adapters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mac_addresses: Vec<String>,
+}
+
+impl AssetFingerprint {
+    /// Asset ID from the first usable identifier, as described in the
+    /// [module documentation](self); `None` when there is none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::asset::AssetFingerprint;
+    ///
+    /// let fingerprint = AssetFingerprint {
+    ///     bios_serial: Some("5CG1234XYZ".into()),
+    ///     ..Default::default()
+    /// };
+    /// assert!(fingerprint.asset_id().unwrap().starts_with("v1-"));
+    /// ```
+    pub fn asset_id(&self) -> Option<String> {
+        let key = self
+            .serial()
+            .map(|serial| format!("bios:{}", serial))
+            .or_else(|| {
+                let macs = self.macs();
+                (!macs.is_empty()).then(|| format!("mac:{}", macs.join(",")))
+            })
+            .or_else(|| self.guid().map(|guid| format!("guid:{}", guid)))?;
+        let digest = Sha256::digest(key.as_bytes());
+        let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+        Some(format!(
+            "v1-{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        ))
+    }
+
+    fn serial(&self) -> Option<String> {
+        let serial = self.bios_serial.as_deref()?.trim().to_uppercase();
+        (!serial.is_empty() && !PLACEHOLDER_SERIALS.contains(&serial.as_str())).then_some(serial)
+    }
+
+    fn macs(&self) -> Vec<String> {
+        let mut macs: Vec<String> = self
+            .mac_addresses
+            .iter()
+            .filter_map(|mac| {
+                let octets: Vec<u8> = mac
+                    .split([':', '-'])
+                    .map(|octet| u8::from_str_radix(octet.trim(), 16).ok())
+                    .collect::<Option<_>>()?;
+                let universal =
+                    octets.len() == 6 && octets.iter().any(|&b| b != 0) && octets[0] & 0x02 == 0;
+                universal.then(|| {
+                    octets
+                        .iter()
+                        .map(|b| format!("{:02X}", b))
+                        .collect::<Vec<_>>()
+                        .join(":")
+                })
+            })
+            .collect();
+        macs.sort();
+        macs.dedup();
+        macs
+    }
+
+    fn guid(&self) -> Option<String> {
+        let guid = self.machine_guid.as_deref()?;
+        let guid = guid.trim().trim_matches(['{', '}']).to_lowercase();
+        (!guid.is_empty()).then_some(guid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_id_sources() {
+        let hardware = AssetFingerprint {
+            machine_guid: Some("{6F1C9A2E-0000-4B1A-9C3D-1234567890AB}".into()),
+            bios_serial: Some(" 5cg1234xyz ".into()),
+            mac_addresses: vec!["00-1A-2B-3C-4D-5E".into()],
+        };
+        let id = hardware.asset_id().unwrap();
+        assert_eq!(id.len(), "v1-".len() + 36);
+        // Reimaging changes the GUID, not the serial
+        let reimaged = AssetFingerprint {
+            machine_guid: Some("b0f1d5a4-1111-4c2d-8e3f-abcdefabcdef".into()),
+            bios_serial: Some("5CG1234XYZ".into()),
+            mac_addresses: vec![],
+        };
+        assert_eq!(reimaged.asset_id(), Some(id.clone()));
+
+        // Placeholder serial: the MAC set, in any order and format
+        let by_mac = |macs: &[&str]| AssetFingerprint {
+            machine_guid: Some("6f1c9a2e".into()),
+            bios_serial: Some("To Be Filled By O.E.M.".into()),
+            mac_addresses: macs.iter().map(|m| m.to_string()).collect(),
+        };
+        let a = by_mac(&[
+            "00:1A:2B:3C:4D:5E",
+            "00:50:56:aa:bb:cc",
+            "02:00:4C:4F:4F:50",
+        ]);
+        let b = by_mac(&["00-50-56-AA-BB-CC", "00:1a:2b:3c:4d:5e"]);
+        assert_eq!(a.asset_id(), b.asset_id());
+        assert_ne!(a.asset_id(), Some(id));
+
+        // Only the GUID left
+        let guid = by_mac(&["00:00:00:00:00:00"]);
+        assert!(guid.asset_id().is_some());
+        assert_ne!(guid.asset_id(), a.asset_id());
+        assert_eq!(AssetFingerprint::default().asset_id(), None);
+    }
+}
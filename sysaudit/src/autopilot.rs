@@ -0,0 +1,152 @@
+//! Windows Autopilot registration data.
+//!
+//! Registering an existing device with Autopilot takes its serial number
+//! and its 4K hardware hash, the `DeviceHardwareData` that the MDM bridge
+//! WMI provider exposes in `root\cimv2\mdm\dmmap` (the same source as
+//! `Get-WindowsAutopilotInfo.ps1`). Reading the hash needs administrator
+//! rights. [`CsvExporter::export_autopilot`](crate::output::CsvExporter::export_autopilot)
+//! writes the collected devices in the Intune import format.
+
+use serde::{Deserialize, Serialize};
+#[cfg(all(windows, feature = "local"))]
+use wmi::WMIConnection;
+
+/// WMI namespace of the MDM bridge provider.
+pub const MDM_NAMESPACE: &str = r"root\cimv2\mdm\dmmap";
+
+/// Autopilot registration data of a host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Autopilot {
+    /// BIOS serial number
+    #[serde(default)]
+    pub serial_number: Option<String>,
+    /// System manufacturer
+    #[serde(default)]
+    pub manufacturer: Option<String>,
+    /// System model
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Base64 hardware hash (`DeviceHardwareData`)
+    #[serde(default)]
+    pub hardware_hash: Option<String>,
+}
+
+impl Autopilot {
+    /// Whether the host can be registered: Autopilot needs both the serial
+    /// number and the hardware hash.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::Autopilot;
+    ///
+    /// let autopilot = Autopilot {
+    ///     serial_number: Some("5CG1234XYZ".into()),
+    ///     hardware_hash: None,
+    ///     ..Default::default()
+    /// };
+    /// assert!(!autopilot.is_complete());
+    /// ```
+    pub fn is_complete(&self) -> bool {
+        self.serial_number.is_some() && self.hardware_hash.is_some()
+    }
+}
+
+/// A non-empty, trimmed WMI string.
+#[cfg(all(windows, feature = "local"))]
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// WMI result struct for Win32_BIOS.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Win32Bios {
+    serial_number: Option<String>,
+}
+
+/// WMI result struct for Win32_ComputerSystem.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Win32ComputerSystem {
+    manufacturer: Option<String>,
+    model: Option<String>,
+}
+
+/// WMI result struct for MDM_DevDetail_Ext01.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct MdmDevDetailExt01 {
+    device_hardware_data: Option<String>,
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl Autopilot {
+    /// Collect the serial number, model and hardware hash (READ-ONLY).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::Autopilot;
+    ///
+    /// let autopilot = Autopilot::collect().unwrap();
+    /// println!("{:?}: {}", autopilot.serial_number, autopilot.is_complete());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if COM cannot be
+    /// initialized or a class cannot be queried; the MDM bridge refuses
+    /// non-elevated callers.
+    #[tracing::instrument]
+    pub fn collect() -> Result<Self, crate::Error> {
+        crate::WmiContext::with_current(Self::collect_with_wmi)
+    }
+
+    /// [`collect`](Self::collect) over an open WMI context.
+    ///
+    /// # Errors
+    ///
+    /// As [`collect`](Self::collect).
+    pub fn collect_with_wmi(wmi: &crate::WmiContext) -> Result<Self, crate::Error> {
+        tracing::info!("Collecting Autopilot registration data");
+        let mdm = wmi.namespace(MDM_NAMESPACE)?;
+        Self::query(wmi.cimv2()?, &mdm)
+    }
+
+    /// Query `cimv2` for the serial number and model and `mdm` (a
+    /// connection to [`MDM_NAMESPACE`]) for the hash. Shared by the local
+    /// and the remote (DCOM) collectors.
+    pub(crate) fn query(cimv2: &WMIConnection, mdm: &WMIConnection) -> Result<Self, crate::Error> {
+        let bios = cimv2
+            .raw_query::<Win32Bios>("SELECT SerialNumber FROM Win32_BIOS")?
+            .into_iter()
+            .next();
+        let system = cimv2
+            .raw_query::<Win32ComputerSystem>(
+                "SELECT Manufacturer, Model FROM Win32_ComputerSystem",
+            )?
+            .into_iter()
+            .next();
+        let detail = mdm
+            .raw_query::<MdmDevDetailExt01>(
+                "SELECT DeviceHardwareData FROM MDM_DevDetail_Ext01 \
+                 WHERE InstanceID='Ext' AND ParentID='./DevDetail'",
+            )?
+            .into_iter()
+            .next();
+
+        let (manufacturer, model) = system.map_or((None, None), |s| (s.manufacturer, s.model));
+        Ok(Autopilot {
+            serial_number: non_empty(bios.and_then(|b| b.serial_number)),
+            manufacturer: non_empty(manufacturer),
+            model: non_empty(model),
+            hardware_hash: non_empty(detail.and_then(|d| d.device_hardware_data)),
+        })
+    }
+}
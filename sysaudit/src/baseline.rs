@@ -0,0 +1,305 @@
+//! Approved baselines and deviation checks.
+//!
+//! A [`Baseline`] is a named, approved [`AuditReport`] kept in a
+//! [`BaselineStore`] directory (one `<name>.json` file each). Checking a
+//! host against it diffs the current report with the approved one and
+//! turns every change into a [`Finding`] whose severity reflects how much
+//! the change matters on a validated system: industrial software changes
+//! are critical, OS and software changes high.
+
+use crate::Error;
+use crate::findings::{Finding, Severity};
+use crate::report::{AuditReport, ChangeKind, FieldChange, ItemChange, diff};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+/// An approved report stored under a name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Name the baseline was saved under
+    pub name: String,
+    /// Time the baseline was approved (saved)
+    pub approved: DateTime<Utc>,
+    /// The approved report
+    pub report: AuditReport,
+}
+
+impl Baseline {
+    /// Approve `report` as baseline `name`.
+    pub fn new(name: impl Into<String>, report: AuditReport) -> Self {
+        Baseline {
+            name: name.into(),
+            approved: Utc::now(),
+            report,
+        }
+    }
+
+    /// Deviations of `current` from this baseline, most severe first.
+    ///
+    /// An empty result means the host still matches the approved state.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::baseline::BaselineStore;
+    /// use sysaudit::{LocalScanner, Scanner};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let baseline = BaselineStore::new("baselines").load("line3-validated")?;
+    /// let current = LocalScanner::new().audit().await?;
+    /// for deviation in baseline.check(&current) {
+    ///     println!("[{}] {}", deviation.severity, deviation.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check(&self, current: &AuditReport) -> Vec<Finding> {
+        let changes = diff(&self.report, current);
+        let mut deviations: Vec<Finding> = changes
+            .system
+            .iter()
+            .map(|change| self.system_deviation(change))
+            .collect();
+        for (category, items) in [
+            ("software", &changes.software),
+            ("industrial", &changes.industrial),
+            ("updates", &changes.updates),
+        ] {
+            deviations.extend(items.iter().map(|item| self.item_deviation(category, item)));
+        }
+        deviations.sort_by_key(|deviation| Reverse(deviation.severity));
+        deviations
+    }
+
+    fn system_deviation(&self, change: &FieldChange) -> Finding {
+        let severity = match change.field.as_str() {
+            "os_name" | "os_version" | "host_name" => Severity::High,
+            _ => Severity::Medium,
+        };
+        let old = change.old.as_deref().unwrap_or("-");
+        let new = change.new.as_deref().unwrap_or("-");
+        Finding {
+            id: "baseline-system-changed".to_string(),
+            category: "baseline".to_string(),
+            severity,
+            title: format!("{} changed from {} to {}", change.field, old, new),
+            attributes: vec![
+                ("baseline".to_string(), self.name.clone()),
+                ("field".to_string(), change.field.clone()),
+                ("expected".to_string(), old.to_string()),
+                ("actual".to_string(), new.to_string()),
+            ],
+        }
+    }
+
+    fn item_deviation(&self, category: &str, item: &ItemChange) -> Finding {
+        let severity = match (category, item.kind) {
+            ("industrial", _) => Severity::Critical,
+            ("updates", ChangeKind::Added) => Severity::Low,
+            ("updates", _) => Severity::High,
+            (_, ChangeKind::Changed) => Severity::Medium,
+            _ => Severity::High,
+        };
+        let title = match item.kind {
+            ChangeKind::Added => format!("Not in baseline: {}", item.name),
+            ChangeKind::Removed => format!("Missing from baseline: {}", item.name),
+            ChangeKind::Changed => format!(
+                "{} version {} differs from baseline {}",
+                item.name,
+                item.new_version.as_deref().unwrap_or("-"),
+                item.old_version.as_deref().unwrap_or("-")
+            ),
+        };
+        Finding {
+            id: format!("baseline-{}-{}", category, item.kind),
+            category: "baseline".to_string(),
+            severity,
+            title,
+            attributes: vec![
+                ("baseline".to_string(), self.name.clone()),
+                ("section".to_string(), category.to_string()),
+                ("name".to_string(), item.name.clone()),
+                (
+                    "expected".to_string(),
+                    item.old_version.clone().unwrap_or_default(),
+                ),
+                (
+                    "actual".to_string(),
+                    item.new_version.clone().unwrap_or_default(),
+                ),
+            ],
+        }
+    }
+}
+
+/// Directory of named baselines.
+#[derive(Debug, Clone)]
+pub struct BaselineStore {
+    dir: PathBuf,
+}
+
+impl BaselineStore {
+    /// Store rooted at `dir`. The directory is created on first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        BaselineStore { dir: dir.into() }
+    }
+
+    /// Directory the baselines are kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Save `report` as the approved baseline `name`, replacing any
+    /// previous baseline of that name. Returns the file written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if `name` is not a plain file name, or
+    /// [`Error::Io`] if the file cannot be written.
+    pub fn save(&self, name: &str, report: &AuditReport) -> Result<PathBuf, Error> {
+        let path = self.path(name)?;
+        std::fs::create_dir_all(&self.dir)?;
+        let baseline = Baseline::new(name, report.clone());
+        std::fs::write(&path, serde_json::to_string_pretty(&baseline)?)?;
+        Ok(path)
+    }
+
+    /// Load baseline `name`. Its report is upgraded to the current schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if no baseline of that name exists, or
+    /// [`Error::Json`] if the file is not a valid baseline.
+    pub fn load(&self, name: &str) -> Result<Baseline, Error> {
+        let path = self.path(name)?;
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            Error::General(format!(
+                "cannot read baseline '{}' ({}): {}",
+                name,
+                path.display(),
+                e
+            ))
+        })?;
+        // Upgrade the report separately so baselines saved by older
+        // releases keep loading.
+        let stored: StoredBaseline = serde_json::from_str(&json)?;
+        Ok(Baseline {
+            name: stored.name,
+            approved: stored.approved,
+            report: AuditReport::from_json_versioned(&stored.report.to_string())?,
+        })
+    }
+
+    /// Names of the stored baselines, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the directory exists but cannot be read.
+    pub fn list(&self) -> Result<Vec<String>, Error> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.push(stem.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// File backing baseline `name`.
+    fn path(&self, name: &str) -> Result<PathBuf, Error> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !name.starts_with('.');
+        if !valid {
+            return Err(Error::General(format!(
+                "invalid baseline name '{}': use letters, digits, '-', '_' and '.'",
+                name
+            )));
+        }
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
+
+/// A baseline file before its report is upgraded.
+#[derive(Deserialize)]
+struct StoredBaseline {
+    name: String,
+    approved: DateTime<Utc>,
+    report: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sysaudit_common::{IndustrialSoftwareDto, SystemInfoDto};
+
+    fn report(industrial: &[&str]) -> AuditReport {
+        AuditReport {
+            schema_version: crate::REPORT_SCHEMA_VERSION,
+            industrial: industrial
+                .iter()
+                .map(|version| IndustrialSoftwareDto {
+                    vendor: "AVEVA".into(),
+                    product: "Citect SCADA".into(),
+                    version: Some(version.to_string()),
+                    install_path: None,
+                })
+                .collect(),
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Enterprise LTSC".into(),
+                os_version: "21H2".into(),
+                host_name: "SCADA01".into(),
+                cpu_info: "Intel Xeon".into(),
+                cpu_physical_cores: Some(8),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        }
+    }
+
+    #[test]
+    fn test_check_against_baseline() {
+        let baseline = Baseline::new("line3", report(&["8.1"]));
+        assert!(baseline.check(&report(&["8.1"])).is_empty());
+
+        let deviations = baseline.check(&report(&["8.2"]));
+        assert_eq!(deviations.len(), 1);
+        assert_eq!(deviations[0].severity, Severity::Critical);
+        assert_eq!(deviations[0].id, "baseline-industrial-changed");
+        assert!(
+            deviations[0]
+                .title
+                .contains("8.2 differs from baseline 8.1")
+        );
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("sysaudit-baselines-{}", std::process::id()));
+        let store = BaselineStore::new(&dir);
+        store.save("line3", &report(&["8.1"])).unwrap();
+
+        let baseline = store.load("line3").unwrap();
+        assert_eq!(baseline.name, "line3");
+        assert_eq!(baseline.report.industrial.len(), 1);
+        assert_eq!(store.list().unwrap(), vec!["line3".to_string()]);
+        assert!(store.save("../etc", &report(&[])).is_err());
+        assert!(store.load("missing").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
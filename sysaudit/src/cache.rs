@@ -0,0 +1,193 @@
+//! On-disk cache of collector results.
+//!
+//! Enabled with [`ScanOptions::cache`](crate::ScanOptions::cache): each
+//! collector's section is stored as `<collector>.json` in the cache
+//! directory and reused while younger than its TTL, so repeated scans
+//! within the window skip the registry and WMI. Sections collected with
+//! warnings are never cached. Only local collection is cached.
+
+use crate::Error;
+#[cfg(any(all(windows, feature = "local"), test))]
+use crate::options::Collector;
+#[cfg(any(all(windows, feature = "local"), test))]
+use chrono::{DateTime, Utc};
+#[cfg(any(all(windows, feature = "local"), test))]
+use serde::de::DeserializeOwned;
+#[cfg(any(all(windows, feature = "local"), test))]
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+#[cfg(any(all(windows, feature = "local"), test))]
+use std::time::Duration;
+
+/// Default cache directory: `%LOCALAPPDATA%\sysaudit\cache`, or the
+/// temporary directory when `LOCALAPPDATA` is not set.
+pub fn default_dir() -> PathBuf {
+    std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sysaudit")
+        .join("cache")
+}
+
+/// Delete every cached section in `dir`, returning how many were removed.
+///
+/// # Errors
+///
+/// Returns [`Error::Io`] if the directory cannot be listed or a file
+/// cannot be removed. A missing directory is not an error.
+pub fn clear(dir: impl AsRef<Path>) -> Result<usize, Error> {
+    let entries = match std::fs::read_dir(dir.as_ref()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Stored section with what it was collected for.
+#[derive(Serialize, Deserialize)]
+#[cfg(any(all(windows, feature = "local"), test))]
+struct Entry<T> {
+    /// Library version that wrote the entry; other versions ignore it
+    version: String,
+    /// Collector settings the section depends on
+    key: String,
+    stored: DateTime<Utc>,
+    data: T,
+}
+
+/// Where one collector's section is cached and for how long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) struct CacheSlot {
+    path: PathBuf,
+    key: String,
+    ttl: Duration,
+}
+
+#[cfg(any(all(windows, feature = "local"), test))]
+impl CacheSlot {
+    /// Slot for `collector` in `dir`; `key` describes the settings the
+    /// section depends on, so a change of settings misses the cache.
+    pub(crate) fn new(dir: &Path, collector: Collector, key: &str, ttl: Duration) -> Self {
+        CacheSlot {
+            path: dir.join(format!("{}.json", collector)),
+            key: key.to_string(),
+            ttl,
+        }
+    }
+
+    /// The cached section, if present, fresh and written for this key.
+    fn load<T: DeserializeOwned>(&self, now: DateTime<Utc>) -> Option<T> {
+        let text = std::fs::read_to_string(&self.path).ok()?;
+        let entry: Entry<T> = match serde_json::from_str(&text) {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::debug!(path = %self.path.display(), error = %e, "Ignoring unreadable cache entry");
+                return None;
+            }
+        };
+        let age = (now - entry.stored).to_std().ok()?;
+        (entry.version == env!("CARGO_PKG_VERSION") && entry.key == self.key && age < self.ttl)
+            .then_some(entry.data)
+    }
+
+    /// Write `data`; failures only cost a cache miss next time.
+    fn store<T: Serialize>(&self, data: &T, now: DateTime<Utc>) {
+        let entry = Entry {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            key: self.key.clone(),
+            stored: now,
+            data,
+        };
+        // Write then rename, so a concurrent reader never sees half a file.
+        let tmp = self.path.with_extension("json.tmp");
+        let written = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| {
+                let json = serde_json::to_vec(&entry).map_err(std::io::Error::other)?;
+                std::fs::write(&tmp, json)
+            })
+            .and_then(|()| std::fs::rename(&tmp, &self.path));
+        if let Err(e) = written {
+            tracing::warn!(path = %self.path.display(), error = %e, "Could not write cache entry");
+        }
+    }
+}
+
+/// A fresh cached section from `slot`, or the result of `collect`, which is
+/// cached when it completed without warnings.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn cached<T: Serialize + DeserializeOwned>(
+    slot: Option<&CacheSlot>,
+    collect: impl FnOnce() -> Result<(T, Vec<String>), Error>,
+) -> Result<(T, Vec<String>), Error> {
+    let Some(slot) = slot else {
+        return collect();
+    };
+    if let Some(data) = slot.load(Utc::now()) {
+        tracing::debug!(path = %slot.path.display(), "Using cached section");
+        return Ok((data, Vec::new()));
+    }
+    let (data, warnings) = collect()?;
+    if warnings.is_empty() {
+        slot.store(&data, Utc::now());
+    }
+    Ok((data, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_reuses_fresh_entry() {
+        let dir = std::env::temp_dir().join(format!("sysaudit-cache-{}", std::process::id()));
+        let slot = CacheSlot::new(&dir, Collector::Updates, "", Duration::from_secs(60));
+
+        let first = cached(Some(&slot), || Ok((vec![1, 2], Vec::new()))).unwrap();
+        assert_eq!(first.0, vec![1, 2]);
+        let second = cached(Some(&slot), || Ok((vec![3], Vec::new()))).unwrap();
+        assert_eq!(second.0, vec![1, 2]);
+
+        // Another key, or an expired entry, collects again.
+        let other = CacheSlot::new(
+            &dir,
+            Collector::Updates,
+            "user=false",
+            Duration::from_secs(60),
+        );
+        assert_eq!(
+            cached(Some(&other), || Ok((vec![3], Vec::new())))
+                .unwrap()
+                .0,
+            vec![3]
+        );
+        let later = Utc::now() + chrono::Duration::seconds(120);
+        assert!(other.load::<Vec<i32>>(later).is_none());
+
+        assert_eq!(clear(&dir).unwrap(), 1);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn test_warnings_not_cached() {
+        let dir = std::env::temp_dir().join(format!("sysaudit-cache-warn-{}", std::process::id()));
+        let slot = CacheSlot::new(&dir, Collector::Services, "", Duration::from_secs(60));
+
+        cached(Some(&slot), || Ok((vec![1], vec!["partial".to_string()]))).unwrap();
+        assert!(slot.load::<Vec<i32>>(Utc::now()).is_none());
+        assert_eq!(clear(&dir).unwrap(), 0);
+        let _ = std::fs::remove_dir(&dir);
+    }
+}
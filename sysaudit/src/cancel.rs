@@ -0,0 +1,22 @@
+//! Cancellation checks shared by the registry collectors.
+//!
+//! [`SoftwareScanner`](crate::SoftwareScanner) and
+//! [`IndustrialScanner`](crate::IndustrialScanner) take an optional
+//! [`CancellationToken`] and poll it between hives and keys.
+
+use crate::Error;
+use tokio_util::sync::CancellationToken;
+
+/// Whether an optional token has been triggered.
+pub(crate) fn is_cancelled(cancel: Option<&CancellationToken>) -> bool {
+    cancel.is_some_and(CancellationToken::is_cancelled)
+}
+
+/// [`Error::Cancelled`] once an optional token has been triggered.
+pub(crate) fn check(cancel: Option<&CancellationToken>) -> Result<(), Error> {
+    if is_cancelled(cancel) {
+        Err(Error::Cancelled)
+    } else {
+        Ok(())
+    }
+}
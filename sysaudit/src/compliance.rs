@@ -0,0 +1,806 @@
+//! Policy rules evaluated against a report.
+//!
+//! A [`Policy`] is a list of rules, usually loaded from YAML:
+//!
+//! ```yaml
+//! name: Plant floor
+//! rules:
+//!   - id: no-teamviewer
+//!     severity: high
+//!     forbid_publisher: TeamViewer
+//!   - id: supported-build
+//!     min_os_build: 19045
+//!   - id: disk-encryption
+//!     severity: critical
+//!     bitlocker: true
+//!   - id: patching
+//!     max_update_age_days: 90
+//! ```
+//!
+//! Each rule holds exactly one check (see [`Check`]). Name, publisher and
+//! service matches are case-insensitive; software and publisher checks
+//! match substrings. [`Policy::evaluate`] returns a [`ComplianceReport`]
+//! with a pass, fail or unknown result per rule and a severity-weighted
+//! score.
+//!
+//! [`Policy::cis`] is a built-in hardening pack mapped to CIS Microsoft
+//! Windows benchmark items (password and lockout policy, UAC, firewall,
+//! SMBv1, Remote Desktop NLA). Rule titles quote the benchmark
+//! recommendations, whose numbering differs between benchmark releases.
+
+#[cfg(feature = "yaml")]
+use crate::Error;
+use crate::findings::{Finding, Severity};
+use crate::lifecycle::os_build;
+use crate::report::AuditReport;
+use crate::security::{PasswordPolicy, SecurityPosture};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "yaml")]
+use std::path::Path;
+
+/// A set of rules a host must satisfy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Policy {
+    /// Policy name, shown in results
+    #[serde(default)]
+    pub name: String,
+    /// Rules in evaluation order
+    pub rules: Vec<Rule>,
+}
+
+/// One policy rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    /// Stable identifier (e.g. "no-teamviewer")
+    pub id: String,
+    /// Human-readable summary; derived from the check when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Severity of a failure
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// What the rule checks
+    #[serde(flatten)]
+    pub check: Check,
+}
+
+/// Severity of rules that do not set one.
+fn default_severity() -> Severity {
+    Severity::Medium
+}
+
+/// The condition a [`Rule`] checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// No installed software whose publisher contains this text
+    ForbidPublisher(String),
+    /// No installed software whose name contains this text
+    ForbidSoftware(String),
+    /// Some installed software's name contains this text
+    RequireSoftware(String),
+    /// The OS build number is at least this
+    MinOsBuild(u32),
+    /// The newest update is at most this many days older than the scan
+    MaxUpdateAgeDays(i64),
+    /// BitLocker protects the system drive (`true`) or not (`false`)
+    Bitlocker(bool),
+    /// The service with this name is running
+    RequireServiceRunning(String),
+    /// The service with this name is not running
+    ForbidServiceRunning(String),
+    /// User Account Control is on (`true`) or off (`false`)
+    Uac(bool),
+    /// Administrators are prompted for consent or credentials on the
+    /// secure desktop
+    UacSecurePrompt(bool),
+    /// The SMBv1 server is enabled (`true`) or disabled (`false`)
+    Smb1Server(bool),
+    /// The SMBv1 client driver is enabled (`true`) or disabled (`false`)
+    Smb1Client(bool),
+    /// Windows Firewall is on for this profile: "domain", "private",
+    /// "public" or "all"
+    Firewall(String),
+    /// Passwords have at least this many characters
+    MinPasswordLength(u32),
+    /// At least this many previous passwords are remembered
+    PasswordHistory(u32),
+    /// Passwords expire within this many days
+    MaxPasswordAgeDays(u32),
+    /// Passwords can be changed only after this many days
+    MinPasswordAgeDays(u32),
+    /// Accounts lock out after at most this many failed logons
+    LockoutThreshold(u32),
+    /// Remote Desktop requires Network Level Authentication (or is off)
+    RdpNla(bool),
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::ForbidPublisher(p) => write!(f, "No software published by {}", p),
+            Check::ForbidSoftware(n) => write!(f, "{} is not installed", n),
+            Check::RequireSoftware(n) => write!(f, "{} is installed", n),
+            Check::MinOsBuild(b) => write!(f, "OS build is {} or later", b),
+            Check::MaxUpdateAgeDays(d) => {
+                write!(f, "An update was installed in the last {} days", d)
+            }
+            Check::Bitlocker(true) => write!(f, "BitLocker protects the system drive"),
+            Check::Bitlocker(false) => write!(f, "BitLocker is off on the system drive"),
+            Check::RequireServiceRunning(s) => write!(f, "Service {} is running", s),
+            Check::ForbidServiceRunning(s) => write!(f, "Service {} is not running", s),
+            Check::Uac(true) => write!(f, "User Account Control is on"),
+            Check::Uac(false) => write!(f, "User Account Control is off"),
+            Check::UacSecurePrompt(true) => {
+                write!(f, "Administrators are prompted on the secure desktop")
+            }
+            Check::UacSecurePrompt(false) => {
+                write!(f, "Administrators are not prompted on the secure desktop")
+            }
+            Check::Smb1Server(true) => write!(f, "SMBv1 server is enabled"),
+            Check::Smb1Server(false) => write!(f, "SMBv1 server is disabled"),
+            Check::Smb1Client(true) => write!(f, "SMBv1 client is enabled"),
+            Check::Smb1Client(false) => write!(f, "SMBv1 client is disabled"),
+            Check::Firewall(p) => write!(f, "Windows Firewall is on ({} profile)", p),
+            Check::MinPasswordLength(n) => write!(f, "Passwords have {} or more characters", n),
+            Check::PasswordHistory(n) => write!(f, "{} or more passwords are remembered", n),
+            Check::MaxPasswordAgeDays(d) => write!(f, "Passwords expire within {} days", d),
+            Check::MinPasswordAgeDays(d) => {
+                write!(f, "Passwords can be changed after {} or more days", d)
+            }
+            Check::LockoutThreshold(n) => {
+                write!(f, "Accounts lock out after {} or fewer failed logons", n)
+            }
+            Check::RdpNla(true) => write!(f, "Remote Desktop requires NLA"),
+            Check::RdpNla(false) => write!(f, "Remote Desktop does not require NLA"),
+        }
+    }
+}
+
+/// Outcome of one rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleStatus {
+    /// The host satisfies the rule
+    Pass,
+    /// The host violates the rule
+    Fail,
+    /// The report lacks the data the rule needs (section not collected)
+    Unknown,
+}
+
+impl std::fmt::Display for RuleStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleStatus::Pass => write!(f, "PASS"),
+            RuleStatus::Fail => write!(f, "FAIL"),
+            RuleStatus::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+/// Result of evaluating one rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleResult {
+    /// Rule ID
+    pub id: String,
+    /// Rule title
+    pub title: String,
+    /// Rule severity
+    pub severity: Severity,
+    /// Outcome
+    pub status: RuleStatus,
+    /// What was found
+    pub detail: String,
+}
+
+/// Results of a policy for one host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    /// Policy name
+    pub policy: String,
+    /// Host name from the report
+    pub host: String,
+    /// One result per rule, in policy order
+    pub results: Vec<RuleResult>,
+    /// Severity-weighted percentage (0-100) of the evaluated rules that
+    /// passed; `None` when no rule could be evaluated
+    #[serde(default)]
+    pub score: Option<u8>,
+}
+
+impl ComplianceReport {
+    /// Whether every rule passed. Rules that could not be evaluated count
+    /// as failures, so missing data never passes a gate.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.status == RuleStatus::Pass)
+    }
+
+    /// Rules that did not pass.
+    pub fn failures(&self) -> impl Iterator<Item = &RuleResult> {
+        self.results.iter().filter(|r| r.status != RuleStatus::Pass)
+    }
+
+    /// One finding per rule for SIEM and alerting outputs. Passed rules
+    /// are [`Severity::Info`]; the others carry the rule's severity.
+    pub fn findings(&self) -> Vec<Finding> {
+        self.results
+            .iter()
+            .map(|result| Finding {
+                id: format!("compliance-{}", result.id),
+                category: "compliance".to_string(),
+                severity: if result.status == RuleStatus::Pass {
+                    Severity::Info
+                } else {
+                    result.severity
+                },
+                title: format!("{}: {}", result.status, result.title),
+                attributes: vec![
+                    ("policy".to_string(), self.policy.clone()),
+                    ("rule".to_string(), result.id.clone()),
+                    ("status".to_string(), result.status.to_string()),
+                    ("detail".to_string(), result.detail.clone()),
+                ],
+            })
+            .collect()
+    }
+}
+
+impl Policy {
+    /// Built-in hardening checks mapped to CIS Microsoft Windows benchmark
+    /// recommendations (Level 1). Evaluating it against a report with a
+    /// `security` section gives a scored hardening report.
+    ///
+    /// The password policy is only collected by local scans; remote
+    /// reports leave those rules unknown.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::compliance::Policy;
+    /// use sysaudit::{LocalScanner, ScanOptions, Scanner};
+    ///
+    /// # async fn example() -> Result<(), sysaudit::ScanError> {
+    /// let report = LocalScanner::with_options(ScanOptions::new().security(true)).audit().await?;
+    /// let result = Policy::cis().evaluate(&report);
+    /// println!("Hardening score: {}%", result.score.unwrap_or(0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cis() -> Self {
+        let rule = |id: &str, severity, check, title: &str| Rule {
+            id: format!("cis-{}", id),
+            title: Some(title.to_string()),
+            severity,
+            check,
+        };
+        Policy {
+            name: "CIS Windows hardening".to_string(),
+            rules: vec![
+                rule(
+                    "password-history",
+                    Severity::Medium,
+                    Check::PasswordHistory(24),
+                    "Ensure 'Enforce password history' is set to '24 or more password(s)'",
+                ),
+                rule(
+                    "max-password-age",
+                    Severity::Low,
+                    Check::MaxPasswordAgeDays(365),
+                    "Ensure 'Maximum password age' is set to '365 or fewer days, but not 0'",
+                ),
+                rule(
+                    "min-password-age",
+                    Severity::Low,
+                    Check::MinPasswordAgeDays(1),
+                    "Ensure 'Minimum password age' is set to '1 or more day(s)'",
+                ),
+                rule(
+                    "min-password-length",
+                    Severity::High,
+                    Check::MinPasswordLength(14),
+                    "Ensure 'Minimum password length' is set to '14 or more character(s)'",
+                ),
+                rule(
+                    "lockout-threshold",
+                    Severity::Medium,
+                    Check::LockoutThreshold(5),
+                    "Ensure 'Account lockout threshold' is set to '5 or fewer invalid logon attempt(s), but not 0'",
+                ),
+                rule(
+                    "uac-admin-approval",
+                    Severity::High,
+                    Check::Uac(true),
+                    "Ensure 'User Account Control: Run all administrators in Admin Approval Mode' is set to 'Enabled'",
+                ),
+                rule(
+                    "uac-admin-prompt",
+                    Severity::Medium,
+                    Check::UacSecurePrompt(true),
+                    "Ensure 'User Account Control: Behavior of the elevation prompt for administrators in Admin Approval Mode' is set to 'Prompt for consent on the secure desktop' or higher",
+                ),
+                rule(
+                    "firewall-domain",
+                    Severity::Medium,
+                    Check::Firewall("domain".to_string()),
+                    "Ensure 'Windows Firewall: Domain: Firewall state' is set to 'On (recommended)'",
+                ),
+                rule(
+                    "firewall-private",
+                    Severity::Medium,
+                    Check::Firewall("private".to_string()),
+                    "Ensure 'Windows Firewall: Private: Firewall state' is set to 'On (recommended)'",
+                ),
+                rule(
+                    "firewall-public",
+                    Severity::High,
+                    Check::Firewall("public".to_string()),
+                    "Ensure 'Windows Firewall: Public: Firewall state' is set to 'On (recommended)'",
+                ),
+                rule(
+                    "smb1-client",
+                    Severity::High,
+                    Check::Smb1Client(false),
+                    "Ensure 'Configure SMB v1 client driver' is set to 'Enabled: Disable driver (recommended)'",
+                ),
+                rule(
+                    "smb1-server",
+                    Severity::High,
+                    Check::Smb1Server(false),
+                    "Ensure 'Configure SMB v1 server' is set to 'Disabled'",
+                ),
+                rule(
+                    "rdp-nla",
+                    Severity::High,
+                    Check::RdpNla(true),
+                    "Ensure 'Require user authentication for remote connections by using Network Level Authentication' is set to 'Enabled'",
+                ),
+            ],
+        }
+    }
+
+    /// Parse a policy from YAML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Yaml`] if the document is not a valid policy, or
+    /// [`Error::General`] if two rules share an ID.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        let policy: Policy = serde_yaml::from_str(yaml)?;
+        for (i, rule) in policy.rules.iter().enumerate() {
+            if policy.rules[..i].iter().any(|r| r.id == rule.id) {
+                return Err(Error::General(format!("duplicate rule id '{}'", rule.id)));
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Load a policy from a YAML file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::compliance::Policy;
+    /// use sysaudit::AuditReport;
+    ///
+    /// let policy = Policy::load("policy.yaml").unwrap();
+    /// let json = std::fs::read_to_string("report.json").unwrap();
+    /// let result = policy.evaluate(&AuditReport::from_json_versioned(&json).unwrap());
+    /// for failure in result.failures() {
+    ///     println!("[{}] {}: {}", failure.severity, failure.title, failure.detail);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, otherwise as
+    /// [`from_yaml`](Self::from_yaml).
+    #[cfg(feature = "yaml")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_yaml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Evaluate every rule against `report`.
+    pub fn evaluate(&self, report: &AuditReport) -> ComplianceReport {
+        let results: Vec<RuleResult> = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let (status, detail) = check(&rule.check, report);
+                RuleResult {
+                    id: rule.id.clone(),
+                    title: rule.title.clone().unwrap_or_else(|| rule.check.to_string()),
+                    severity: rule.severity,
+                    status,
+                    detail,
+                }
+            })
+            .collect();
+        ComplianceReport {
+            policy: self.name.clone(),
+            host: report.system.host_name.clone(),
+            score: score(&results),
+            results,
+        }
+    }
+}
+
+/// Passed share of the evaluated rules, each weighted by its severity
+/// level. Unknown rules are left out.
+fn score(results: &[RuleResult]) -> Option<u8> {
+    let (passed, total) = results
+        .iter()
+        .filter(|r| r.status != RuleStatus::Unknown)
+        .fold((0u32, 0u32), |(passed, total), r| {
+            let weight = u32::from(r.severity.level());
+            if r.status == RuleStatus::Pass {
+                (passed + weight, total + weight)
+            } else {
+                (passed, total + weight)
+            }
+        });
+    // At most 100, so the conversion cannot fail.
+    (total > 0).then(|| u8::try_from((passed * 100 + total / 2) / total).unwrap_or(100))
+}
+
+/// Evaluate one check.
+fn check(check: &Check, report: &AuditReport) -> (RuleStatus, String) {
+    let contains =
+        |haystack: &str, needle: &str| haystack.to_lowercase().contains(&needle.to_lowercase());
+
+    match check {
+        Check::ForbidPublisher(_) | Check::ForbidSoftware(_) | Check::RequireSoftware(_)
+            if report.software.is_empty() =>
+        {
+            unknown("no software inventory in report")
+        }
+        Check::ForbidPublisher(publisher) => {
+            let found: Vec<&str> = report
+                .software
+                .iter()
+                .filter(|sw| sw.vendor.as_deref().is_some_and(|v| contains(v, publisher)))
+                .map(|sw| sw.name.as_str())
+                .collect();
+            forbidden(&found)
+        }
+        Check::ForbidSoftware(name) => {
+            let found: Vec<&str> = report
+                .software
+                .iter()
+                .filter(|sw| contains(&sw.name, name))
+                .map(|sw| sw.name.as_str())
+                .collect();
+            forbidden(&found)
+        }
+        Check::RequireSoftware(name) => {
+            match report.software.iter().find(|sw| contains(&sw.name, name)) {
+                Some(sw) => pass(format!(
+                    "installed: {} {}",
+                    sw.name,
+                    sw.version.as_deref().unwrap_or("")
+                )),
+                None => fail("not installed"),
+            }
+        }
+        Check::MinOsBuild(min) => {
+            match os_build(&report.system.os_name, &report.system.os_version) {
+                Some(build) if build >= *min => pass(format!("build {}", build)),
+                Some(build) => fail(format!("build {}", build)),
+                None => unknown(format!(
+                    "cannot read a build number from '{}'",
+                    report.system.os_version
+                )),
+            }
+        }
+        Check::MaxUpdateAgeDays(max) => {
+            match report.updates.iter().filter_map(|u| u.installed_on).max() {
+                Some(latest) => {
+                    let age = (report.timestamp.date_naive() - latest).num_days();
+                    let detail = format!("latest update {} ({} days)", latest, age);
+                    if age <= *max {
+                        pass(detail)
+                    } else {
+                        fail(detail)
+                    }
+                }
+                None => fail("no dated updates recorded"),
+            }
+        }
+        Check::Bitlocker(expected) => match &report.security {
+            Some(security) if security.bitlocker.is_empty() => {
+                unknown("no BitLocker volumes in report")
+            }
+            Some(security) => {
+                let enabled = security.bitlocker_enabled();
+                let detail = if enabled {
+                    "protected"
+                } else {
+                    "not protected"
+                };
+                if enabled == *expected {
+                    pass(detail)
+                } else {
+                    fail(detail)
+                }
+            }
+            None => unknown("no security section in report"),
+        },
+        Check::RequireServiceRunning(_) | Check::ForbidServiceRunning(_)
+            if report.services.is_empty() =>
+        {
+            unknown("no services in report")
+        }
+        Check::RequireServiceRunning(name) | Check::ForbidServiceRunning(name) => {
+            let state = report
+                .services
+                .iter()
+                .find(|s| s.name.eq_ignore_ascii_case(name))
+                .map(|s| s.state.as_deref().unwrap_or("Unknown"));
+            let running = state.is_some_and(|s| s.eq_ignore_ascii_case("Running"));
+            let detail = state.map_or("not installed".to_string(), |s| s.to_lowercase());
+            if running == matches!(check, Check::RequireServiceRunning(_)) {
+                pass(detail)
+            } else {
+                fail(detail)
+            }
+        }
+        Check::Uac(expected) => {
+            setting(security(report, |s| s.uac_enabled), *expected, "on", "off")
+        }
+        Check::UacSecurePrompt(expected) => match security(report, |s| s.uac_admin_prompt) {
+            // 1: credentials, 2: consent, both on the secure desktop
+            Some(value) => compare(
+                matches!(value, 1 | 2) == *expected,
+                format!("ConsentPromptBehaviorAdmin is {}", value),
+            ),
+            None => unknown(NOT_REPORTED),
+        },
+        Check::Smb1Server(expected) => setting(
+            security(report, |s| s.smb1_server),
+            *expected,
+            "enabled",
+            "disabled",
+        ),
+        Check::Smb1Client(expected) => setting(
+            security(report, |s| s.smb1_client),
+            *expected,
+            "enabled",
+            "disabled",
+        ),
+        Check::Firewall(profile) => {
+            let profiles: Vec<_> = report
+                .security
+                .iter()
+                .flat_map(|s| &s.firewall)
+                .filter(|p| {
+                    profile.eq_ignore_ascii_case("all") || p.name.eq_ignore_ascii_case(profile)
+                })
+                .collect();
+            let off: Vec<&str> = profiles
+                .iter()
+                .filter(|p| p.enabled == Some(false))
+                .map(|p| p.name.as_str())
+                .collect();
+            let unread = profiles.iter().any(|p| p.enabled.is_none());
+            if !off.is_empty() {
+                fail(format!("off: {}", off.join(", ")))
+            } else if profiles.is_empty() || unread {
+                unknown(NOT_REPORTED)
+            } else {
+                pass("on")
+            }
+        }
+        Check::MinPasswordLength(min) => password(report, |p| {
+            compare(p.min_length >= *min, format!("{} characters", p.min_length))
+        }),
+        Check::PasswordHistory(min) => password(report, |p| {
+            compare(p.history >= *min, format!("{} remembered", p.history))
+        }),
+        Check::MaxPasswordAgeDays(max) => password(report, |p| match p.max_age_days {
+            Some(days) => compare(days > 0 && days <= *max, format!("{} days", days)),
+            None => fail("passwords never expire"),
+        }),
+        Check::MinPasswordAgeDays(min) => password(report, |p| {
+            compare(p.min_age_days >= *min, format!("{} days", p.min_age_days))
+        }),
+        Check::LockoutThreshold(max) => password(report, |p| match p.lockout_threshold {
+            0 => fail("accounts never lock out"),
+            n => compare(n <= *max, format!("{} failed logons", n)),
+        }),
+        Check::RdpNla(expected) => {
+            if *expected && security(report, |s| s.rdp_enabled) == Some(false) {
+                pass("Remote Desktop is off")
+            } else {
+                setting(
+                    security(report, |s| s.rdp_nla),
+                    *expected,
+                    "required",
+                    "not required",
+                )
+            }
+        }
+    }
+}
+
+/// Detail of a rule whose setting the report lacks.
+const NOT_REPORTED: &str = "not in report";
+
+/// A setting from the report's security section.
+fn security<T>(report: &AuditReport, get: impl FnOnce(&SecurityPosture) -> Option<T>) -> Option<T> {
+    report.security.as_ref().and_then(get)
+}
+
+/// Evaluate a password policy check, unknown when the policy is missing.
+fn password(
+    report: &AuditReport,
+    check: impl FnOnce(&PasswordPolicy) -> (RuleStatus, String),
+) -> (RuleStatus, String) {
+    match report
+        .security
+        .as_ref()
+        .and_then(|s| s.password_policy.as_ref())
+    {
+        Some(policy) => check(policy),
+        None => unknown(NOT_REPORTED),
+    }
+}
+
+/// Compare an on/off setting with the expected state.
+fn setting(actual: Option<bool>, expected: bool, on: &str, off: &str) -> (RuleStatus, String) {
+    match actual {
+        Some(actual) => compare(actual == expected, if actual { on } else { off }),
+        None => unknown(NOT_REPORTED),
+    }
+}
+
+fn compare(ok: bool, detail: impl Into<String>) -> (RuleStatus, String) {
+    if ok { pass(detail) } else { fail(detail) }
+}
+
+/// Result of a "none of these" check.
+fn forbidden(found: &[&str]) -> (RuleStatus, String) {
+    if found.is_empty() {
+        pass("none found")
+    } else {
+        fail(format!("found: {}", found.join(", ")))
+    }
+}
+
+fn pass(detail: impl Into<String>) -> (RuleStatus, String) {
+    (RuleStatus::Pass, detail.into())
+}
+
+fn fail(detail: impl Into<String>) -> (RuleStatus, String) {
+    (RuleStatus::Fail, detail.into())
+}
+
+fn unknown(detail: impl Into<String>) -> (RuleStatus, String) {
+    (RuleStatus::Unknown, detail.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use sysaudit_common::{SoftwareDto, SystemInfoDto};
+
+    fn report() -> AuditReport {
+        AuditReport {
+            schema_version: crate::REPORT_SCHEMA_VERSION,
+            software: vec![SoftwareDto {
+                name: "TeamViewer 15".into(),
+                version: Some("15.50".into()),
+                vendor: Some("TeamViewer Germany GmbH".into()),
+                install_date: None,
+            }],
+            updates: vec![crate::WindowsUpdate {
+                hotfix_id: "KB5034441".into(),
+                description: None,
+                installed_on: NaiveDate::from_ymd_opt(2024, 1, 10),
+                installed_by: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Enterprise".into(),
+                os_version: "10.0.19044".into(),
+                host_name: "HMI01".into(),
+                cpu_info: "Intel i5".into(),
+                cpu_physical_cores: Some(4),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_evaluate_policy() {
+        let policy = Policy::from_yaml(
+            r#"
+name: Plant floor
+rules:
+  - id: no-teamviewer
+    severity: high
+    forbid_publisher: teamviewer
+  - id: supported-build
+    min_os_build: 19045
+  - id: patching
+    title: Patched monthly
+    max_update_age_days: 30
+  - id: disk-encryption
+    severity: critical
+    bitlocker: true
+"#,
+        )
+        .unwrap();
+
+        let result = policy.evaluate(&report());
+        let statuses: Vec<RuleStatus> = result.results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                RuleStatus::Fail,
+                RuleStatus::Fail,
+                RuleStatus::Pass,
+                RuleStatus::Unknown
+            ]
+        );
+        assert_eq!(result.results[0].severity, Severity::High);
+        assert_eq!(result.results[0].detail, "found: TeamViewer 15");
+        assert_eq!(result.results[1].title, "OS build is 19045 or later");
+        assert_eq!(result.results[2].title, "Patched monthly");
+        assert!(!result.passed());
+        assert_eq!(result.failures().count(), 3);
+        assert_eq!(result.findings()[2].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_cis_score() {
+        let mut report = report();
+        report.security = Some(SecurityPosture {
+            uac_enabled: Some(true),
+            uac_admin_prompt: Some(5),
+            smb1_server: Some(false),
+            smb1_client: Some(false),
+            firewall: ["domain", "private", "public"]
+                .iter()
+                .map(|name| crate::security::FirewallProfile {
+                    name: name.to_string(),
+                    enabled: Some(*name != "public"),
+                })
+                .collect(),
+            rdp_enabled: Some(false),
+            ..Default::default()
+        });
+
+        let result = Policy::cis().evaluate(&report);
+        let status = |id: &str| result.results.iter().find(|r| r.id == id).map(|r| r.status);
+        assert_eq!(status("cis-uac-admin-approval"), Some(RuleStatus::Pass));
+        assert_eq!(status("cis-uac-admin-prompt"), Some(RuleStatus::Fail));
+        assert_eq!(status("cis-firewall-public"), Some(RuleStatus::Fail));
+        assert_eq!(status("cis-rdp-nla"), Some(RuleStatus::Pass));
+        assert_eq!(status("cis-min-password-length"), Some(RuleStatus::Unknown));
+        // 42 of 55 severity-weighted points; password rules are unknown.
+        assert_eq!(result.score, Some(76));
+        assert_eq!(result.findings()[0].id, "compliance-cis-password-history");
+
+        report.security = None;
+        assert_eq!(Policy::cis().evaluate(&report).score, None);
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_policy_rejects_bad_rules() {
+        assert!(Policy::from_yaml("rules:\n  - id: x\n    unknown_check: 1\n").is_err());
+        assert!(
+            Policy::from_yaml(
+                "rules:\n  - id: x\n    min_os_build: 1\n  - id: x\n    min_os_build: 2\n"
+            )
+            .is_err()
+        );
+    }
+}
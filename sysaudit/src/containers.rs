@@ -0,0 +1,395 @@
+//! WSL and container runtime module.
+//!
+//! Reports whether the Windows Subsystem for Linux is installed (the inbox
+//! optional feature or the Store/MSI package), the distributions registered
+//! in the loaded user hives, and Docker Desktop, Docker Engine and
+//! containerd installations. A Linux userland or a container host on an
+//! engineering workstation is software the Windows inventory does not see.
+//!
+//! Read from the registry only: the Uninstall entries give the WSL package
+//! and Docker Desktop versions, the services give the inbox feature, Docker
+//! Engine and containerd (whose static binaries carry no version), and each
+//! user's `Lxss` key lists their distributions.
+
+use serde::{Deserialize, Serialize};
+#[cfg(all(windows, feature = "local"))]
+use windows_registry::{Key, LOCAL_MACHINE, USERS};
+
+/// Registry key of the installed services.
+#[cfg(any(all(windows, feature = "local"), test))]
+const SERVICES_KEY: &str = r"SYSTEM\CurrentControlSet\Services";
+
+/// Registry key of the (64-bit) Uninstall entries.
+#[cfg(any(all(windows, feature = "local"), test))]
+const UNINSTALL_KEY: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall";
+
+/// Per-user registry key of the WSL distributions, one `{GUID}` subkey each.
+#[cfg(any(all(windows, feature = "local"), test))]
+const LXSS_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Lxss";
+
+/// Uninstall display name of the Store/MSI WSL package.
+#[cfg(any(all(windows, feature = "local"), test))]
+const WSL_PACKAGE: &str = "Windows Subsystem for Linux";
+
+/// Registry hive a path is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) enum Hive {
+    /// `HKEY_LOCAL_MACHINE`
+    LocalMachine,
+    /// `HKEY_USERS`
+    Users,
+}
+
+/// WSL and container runtimes of a host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Containers {
+    /// WSL, if installed
+    #[serde(default)]
+    pub wsl: Option<Wsl>,
+    /// Installed container runtimes
+    #[serde(default)]
+    pub runtimes: Vec<ContainerRuntime>,
+}
+
+/// The Windows Subsystem for Linux.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wsl {
+    /// Whether the inbox optional feature is enabled (`LxssManager`)
+    #[serde(default)]
+    pub inbox: bool,
+    /// Version of the Store/MSI package (e.g., "2.3.26.0"), if installed
+    #[serde(default)]
+    pub package_version: Option<String>,
+    /// Distributions registered by the users whose hives are loaded
+    #[serde(default)]
+    pub distributions: Vec<WslDistribution>,
+}
+
+/// One registered WSL distribution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WslDistribution {
+    /// Distribution name (e.g., "Ubuntu-22.04")
+    pub name: String,
+    /// WSL version it runs under (1 or 2)
+    #[serde(default)]
+    pub wsl_version: Option<u32>,
+    /// Whether it is the user's default distribution
+    #[serde(default)]
+    pub default: bool,
+    /// Folder holding its file system
+    #[serde(default)]
+    pub base_path: Option<String>,
+    /// SID of the user who registered it
+    #[serde(default)]
+    pub user_sid: Option<String>,
+}
+
+/// An installed container runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerRuntime {
+    /// Which runtime
+    pub kind: ContainerRuntimeKind,
+    /// Installed version, when the registry records one
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Install folder or service binary
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Kind of container runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerRuntimeKind {
+    /// Docker Desktop (WSL 2 or Hyper-V backend)
+    DockerDesktop,
+    /// Docker Engine / Mirantis Container Runtime (`docker` service)
+    DockerEngine,
+    /// Standalone containerd (`containerd` service)
+    Containerd,
+}
+
+impl std::fmt::Display for ContainerRuntimeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerRuntimeKind::DockerDesktop => write!(f, "Docker Desktop"),
+            ContainerRuntimeKind::DockerEngine => write!(f, "Docker Engine"),
+            ContainerRuntimeKind::Containerd => write!(f, "containerd"),
+        }
+    }
+}
+
+impl Containers {
+    /// Whether neither WSL nor a container runtime is installed.
+    pub fn is_empty(&self) -> bool {
+        self.wsl.is_none() && self.runtimes.is_empty()
+    }
+
+    /// Build the section from registry reads. Shared by the local and the
+    /// remote (DCOM) collectors.
+    #[cfg(any(all(windows, feature = "local"), test))]
+    pub(crate) fn from_registry(
+        subkeys: impl Fn(Hive, &str) -> Vec<String>,
+        string: impl Fn(Hive, &str, &str) -> Option<String>,
+        dword: impl Fn(Hive, &str, &str) -> Option<u32>,
+    ) -> Self {
+        let machine_string = |path: &str, value: &str| string(Hive::LocalMachine, path, value);
+        let service = |name: &str| {
+            let path = format!(r"{}\{}", SERVICES_KEY, name);
+            dword(Hive::LocalMachine, &path, "Start")
+                .map(|_| machine_string(&path, "ImagePath").map(|p| service_binary(&p)))
+        };
+
+        let mut package_version = None;
+        let mut runtimes = Vec::new();
+        for entry in subkeys(Hive::LocalMachine, UNINSTALL_KEY) {
+            let path = format!(r"{}\{}", UNINSTALL_KEY, entry);
+            let Some(name) = machine_string(&path, "DisplayName") else {
+                continue;
+            };
+            let version = machine_string(&path, "DisplayVersion");
+            if name.trim().eq_ignore_ascii_case(WSL_PACKAGE) {
+                package_version = version;
+            } else if name.to_lowercase().starts_with("docker desktop") {
+                runtimes.push(ContainerRuntime {
+                    kind: ContainerRuntimeKind::DockerDesktop,
+                    version,
+                    path: machine_string(&path, "InstallLocation"),
+                });
+            }
+        }
+        for (name, kind) in [
+            ("docker", ContainerRuntimeKind::DockerEngine),
+            ("containerd", ContainerRuntimeKind::Containerd),
+        ] {
+            if let Some(path) = service(name) {
+                runtimes.push(ContainerRuntime {
+                    kind,
+                    version: None,
+                    path,
+                });
+            }
+        }
+
+        let mut distributions = Vec::new();
+        // Per-user hives; the `_Classes` keys and the built-in accounts
+        // hold no distributions
+        for sid in subkeys(Hive::Users, "")
+            .into_iter()
+            .filter(|sid| sid.starts_with("S-1-5-21-") && !sid.ends_with("_Classes"))
+        {
+            let lxss = format!(r"{}\{}", sid, LXSS_KEY);
+            let default = string(Hive::Users, &lxss, "DefaultDistribution");
+            for id in subkeys(Hive::Users, &lxss) {
+                let path = format!(r"{}\{}", lxss, id);
+                let Some(name) = string(Hive::Users, &path, "DistributionName") else {
+                    continue;
+                };
+                distributions.push(WslDistribution {
+                    name,
+                    wsl_version: dword(Hive::Users, &path, "Version"),
+                    default: default
+                        .as_deref()
+                        .is_some_and(|d| d.eq_ignore_ascii_case(&id)),
+                    base_path: string(Hive::Users, &path, "BasePath")
+                        .map(|p| p.trim_start_matches(r"\\?\").to_string()),
+                    user_sid: Some(sid.clone()),
+                });
+            }
+        }
+
+        let inbox = service("LxssManager").is_some();
+        // The Store package runs WSLService; MSI installs also leave an
+        // Uninstall entry with the version
+        let packaged = package_version.is_some() || service("WSLService").is_some();
+        let wsl = (inbox || packaged || !distributions.is_empty()).then_some(Wsl {
+            inbox,
+            package_version,
+            distributions,
+        });
+        Containers { wsl, runtimes }
+    }
+}
+
+/// The binary of a service's `ImagePath`, without quotes and arguments.
+#[cfg(any(all(windows, feature = "local"), test))]
+fn service_binary(image_path: &str) -> String {
+    let image_path = image_path.trim();
+    match image_path.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().unwrap_or(rest).to_string(),
+        None => image_path
+            .split(" -")
+            .next()
+            .unwrap_or(image_path)
+            .trim()
+            .to_string(),
+    }
+}
+
+/// The predefined key of a hive.
+#[cfg(all(windows, feature = "local"))]
+fn root(hive: Hive) -> &'static Key {
+    match hive {
+        Hive::LocalMachine => LOCAL_MACHINE,
+        Hive::Users => USERS,
+    }
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl Containers {
+    /// Collect WSL and the container runtimes (READ-ONLY). Distributions of
+    /// other users are only visible to administrators.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::Containers;
+    ///
+    /// let containers = Containers::collect().unwrap();
+    /// for distribution in containers.wsl.iter().flat_map(|wsl| &wsl.distributions) {
+    ///     println!("{} (WSL {:?})", distribution.name, distribution.wsl_version);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Registry`](crate::Error::Registry) if the services
+    /// key cannot be opened.
+    #[tracing::instrument]
+    pub fn collect() -> Result<Self, crate::Error> {
+        tracing::info!("Collecting WSL and container runtimes");
+        LOCAL_MACHINE.open(SERVICES_KEY)?;
+        Ok(Self::from_registry(
+            |hive, path| {
+                root(hive)
+                    .open(path)
+                    .and_then(|key| Ok(key.keys()?.collect()))
+                    .unwrap_or_default()
+            },
+            |hive, path, value| {
+                root(hive)
+                    .open(path)
+                    .ok()?
+                    .get_string(value)
+                    .ok()
+                    .filter(|s| !s.is_empty())
+            },
+            |hive, path, value| root(hive).open(path).ok()?.get_u32(value).ok(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SID: &str = "S-1-5-21-1004336348-1177238915-682003330-1001";
+
+    fn subkeys(hive: Hive, path: &str) -> Vec<String> {
+        let keys: &[&str] = match (hive, path) {
+            (Hive::LocalMachine, UNINSTALL_KEY) => &["{WSL}", "Docker Desktop", "7-Zip"],
+            (Hive::Users, "") => &[
+                "S-1-5-18",
+                SID,
+                "S-1-5-21-1004336348-1177238915-682003330-1001_Classes",
+            ],
+            (Hive::Users, path) if path == format!(r"{}\{}", SID, LXSS_KEY) => &["{a1}", "{b2}"],
+            _ => &[],
+        };
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    fn string(hive: Hive, path: &str, value: &str) -> Option<String> {
+        let lxss = format!(r"{}\{}", SID, LXSS_KEY);
+        let value = match (hive, path.strip_prefix(&lxss), path, value) {
+            (Hive::Users, Some(""), _, "DefaultDistribution") => "{b2}",
+            (Hive::Users, Some(r"\{a1}"), _, "DistributionName") => "Debian",
+            (Hive::Users, Some(r"\{b2}"), _, "DistributionName") => "Ubuntu-22.04",
+            (Hive::Users, Some(r"\{b2}"), _, "BasePath") => {
+                r"\\?\C:\Users\alice\AppData\Local\Packages\Ubuntu\LocalState"
+            }
+            (Hive::LocalMachine, _, path, "DisplayName") if path.ends_with("{WSL}") => {
+                "Windows Subsystem for Linux"
+            }
+            (Hive::LocalMachine, _, path, "DisplayVersion") if path.ends_with("{WSL}") => {
+                "2.3.26.0"
+            }
+            (Hive::LocalMachine, _, path, "DisplayName") if path.ends_with("Docker Desktop") => {
+                "Docker Desktop"
+            }
+            (Hive::LocalMachine, _, path, "DisplayVersion") if path.ends_with("Docker Desktop") => {
+                "4.34.2"
+            }
+            (
+                Hive::LocalMachine,
+                _,
+                r"SYSTEM\CurrentControlSet\Services\containerd",
+                "ImagePath",
+            ) => r#""C:\Program Files\containerd\containerd.exe" --run-service"#,
+            _ => return None,
+        };
+        Some(value.to_string())
+    }
+
+    fn dword(hive: Hive, path: &str, value: &str) -> Option<u32> {
+        match (hive, path, value) {
+            (Hive::LocalMachine, r"SYSTEM\CurrentControlSet\Services\containerd", "Start") => {
+                Some(2)
+            }
+            (Hive::Users, path, "Version") if path.ends_with("{b2}") => Some(2),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_from_registry() {
+        let containers = Containers::from_registry(subkeys, string, dword);
+        let wsl = containers.wsl.as_ref().unwrap();
+        assert!(!wsl.inbox);
+        assert_eq!(wsl.package_version.as_deref(), Some("2.3.26.0"));
+        assert_eq!(wsl.distributions.len(), 2);
+        let ubuntu = &wsl.distributions[1];
+        assert_eq!(ubuntu.name, "Ubuntu-22.04");
+        assert_eq!(ubuntu.wsl_version, Some(2));
+        assert!(ubuntu.default && !wsl.distributions[0].default);
+        assert_eq!(
+            ubuntu.base_path.as_deref(),
+            Some(r"C:\Users\alice\AppData\Local\Packages\Ubuntu\LocalState")
+        );
+        assert_eq!(ubuntu.user_sid.as_deref(), Some(SID));
+
+        let kinds: Vec<ContainerRuntimeKind> = containers.runtimes.iter().map(|r| r.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ContainerRuntimeKind::DockerDesktop,
+                ContainerRuntimeKind::Containerd
+            ]
+        );
+        assert_eq!(containers.runtimes[0].version.as_deref(), Some("4.34.2"));
+        assert_eq!(
+            containers.runtimes[1].path.as_deref(),
+            Some(r"C:\Program Files\containerd\containerd.exe")
+        );
+    }
+
+    #[test]
+    fn test_service_binary() {
+        assert_eq!(
+            service_binary(r"C:\Program Files\Docker\dockerd.exe --run-service"),
+            r"C:\Program Files\Docker\dockerd.exe"
+        );
+        assert_eq!(
+            service_binary(r#""C:\Program Files\containerd\containerd.exe""#),
+            r"C:\Program Files\containerd\containerd.exe"
+        );
+    }
+
+    #[test]
+    fn test_nothing_installed() {
+        let containers =
+            Containers::from_registry(|_, _| Vec::new(), |_, _, _| None, |_, _, _| None);
+        assert!(containers.is_empty());
+    }
+}
@@ -10,6 +10,7 @@ pub enum Error {
     Registry(#[from] windows_result::Error),
 
     /// WMI query error
+    #[cfg(all(windows, feature = "local"))]
     #[error("WMI error: {0}")]
     Wmi(#[from] wmi::WMIError),
 
@@ -25,10 +26,54 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// YAML serialization error
+    #[cfg(feature = "yaml")]
+    #[error("YAML error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    /// Excel workbook error
+    #[cfg(feature = "xlsx")]
+    #[error("XLSX error: {0}")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+
+    /// SQLite database error
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    /// Template rendering error
+    #[cfg(feature = "template")]
+    #[error("Template error: {0}")]
+    Template(#[from] minijinja::Error),
+
+    /// ZIP archive error
+    #[cfg(feature = "zip")]
+    #[error("ZIP error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    /// Report signing or verification error
+    #[cfg(feature = "signing")]
+    #[error("Signature error: {0}")]
+    Signature(String),
+
+    /// HTTP sink error
+    #[cfg(any(feature = "elastic", feature = "splunk", feature = "servicenow"))]
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// TLS error
+    #[cfg(any(feature = "syslog-tls", feature = "gelf-tls"))]
+    #[error("TLS error: {0}")]
+    Tls(#[from] native_tls::Error),
+
     /// Date parsing error
     #[error("Date parse error: {0}")]
     DateParse(String),
 
+    /// The operation was cancelled through its cancellation token
+    #[error("operation cancelled")]
+    Cancelled,
+
     /// General error with message
     #[error("{0}")]
     General(String),
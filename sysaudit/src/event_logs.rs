@@ -0,0 +1,351 @@
+//! Windows event log configuration module.
+//!
+//! Reports the maximum size and retention of the Application, Security,
+//! Setup and System logs, and whether Windows Event Forwarding sends them
+//! to a collector. A 20 MB Security log on a busy SCADA server overwrites
+//! its oldest events within hours; [`EventLogs::issues`] flags logs below
+//! the CIS benchmark sizes. Group Policy settings take precedence over the
+//! logs' own settings, as they do for the event log service.
+
+use serde::{Deserialize, Serialize};
+#[cfg(all(windows, feature = "local"))]
+use windows_registry::LOCAL_MACHINE;
+
+/// Logs whose configuration is reported.
+pub const AUDITED_LOGS: &[&str] = &["Application", "Security", "Setup", "System"];
+
+/// Registry key holding the classic logs' own settings.
+#[cfg(any(all(windows, feature = "local"), test))]
+const EVENTLOG_KEY: &str = r"SYSTEM\CurrentControlSet\Services\EventLog";
+
+/// Registry key holding the settings of channels without a classic key
+/// (Setup).
+#[cfg(any(all(windows, feature = "local"), test))]
+const CHANNELS_KEY: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\WINEVT\Channels";
+
+/// Registry key holding the Group Policy event log settings.
+#[cfg(any(all(windows, feature = "local"), test))]
+const POLICY_KEY: &str = r"SOFTWARE\Policies\Microsoft\Windows\EventLog";
+
+/// Registry key listing the Windows Event Forwarding subscription managers
+/// as values "1", "2", ...
+#[cfg(any(all(windows, feature = "local"), test))]
+const SUBSCRIPTION_MANAGER_KEY: &str =
+    r"SOFTWARE\Policies\Microsoft\Windows\EventLog\EventForwarding\SubscriptionManager";
+
+/// `Retention` value of a log that is never overwritten.
+#[cfg(any(all(windows, feature = "local"), test))]
+const RETAIN_ALL: u32 = u32::MAX;
+
+/// Event log configuration of a host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventLogs {
+    /// Configuration of each audited log
+    #[serde(default)]
+    pub logs: Vec<EventLog>,
+    /// Windows Event Forwarding subscription managers (collector URLs);
+    /// empty when forwarding is not configured
+    #[serde(default)]
+    pub subscription_managers: Vec<String>,
+}
+
+/// One event log's configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventLog {
+    /// Log name (e.g., "Security")
+    pub name: String,
+    /// Maximum log size in KB, if set
+    #[serde(default)]
+    pub max_size_kb: Option<u32>,
+    /// What happens when the log is full, if set
+    #[serde(default)]
+    pub retention: Option<LogRetention>,
+    /// Whether Group Policy sets the size or retention
+    #[serde(default)]
+    pub policy: bool,
+}
+
+/// What the event log service does when a log reaches its maximum size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRetention {
+    /// Overwrite the oldest events
+    Overwrite,
+    /// Save the full log to an archive file and start a new one
+    Archive,
+    /// Stop recording until the log is cleared
+    DoNotOverwrite,
+}
+
+impl std::fmt::Display for LogRetention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogRetention::Overwrite => write!(f, "Overwrite"),
+            LogRetention::Archive => write!(f, "Archive"),
+            LogRetention::DoNotOverwrite => write!(f, "Do not overwrite"),
+        }
+    }
+}
+
+impl EventLog {
+    /// Smallest maximum size the CIS benchmark accepts for this log, in KB.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::event_logs::EventLog;
+    ///
+    /// assert_eq!(EventLog::minimum_kb("Security"), 196_608);
+    /// assert_eq!(EventLog::minimum_kb("System"), 32_768);
+    /// ```
+    pub fn minimum_kb(name: &str) -> u32 {
+        if name.eq_ignore_ascii_case("Security") {
+            196_608
+        } else {
+            32_768
+        }
+    }
+}
+
+impl EventLogs {
+    /// Log settings and forwarding policy, read through `dword(key, value)`
+    /// and `string(key, value)` under `HKEY_LOCAL_MACHINE`. Shared by the
+    /// local and the remote (`StdRegProv`) collectors.
+    #[cfg(any(all(windows, feature = "local"), test))]
+    pub(crate) fn from_registry(
+        dword: impl Fn(&str, &str) -> Option<u32>,
+        string: impl Fn(&str, &str) -> Option<String>,
+    ) -> Self {
+        let logs = AUDITED_LOGS
+            .iter()
+            .map(|&name| {
+                let local = if name == "Setup" {
+                    format!(r"{}\{}", CHANNELS_KEY, name)
+                } else {
+                    format!(r"{}\{}", EVENTLOG_KEY, name)
+                };
+                let policy = format!(r"{}\{}", POLICY_KEY, name);
+
+                // Group Policy gives the size in KB and its flags as "0"/"1"
+                // strings; the log's own key gives bytes and DWORDs.
+                let policy_flag = |value: &str| string(&policy, value).map(|s| s.trim() == "1");
+                let policy_size = dword(&policy, "MaxSize");
+                let policy_retain = policy_flag("Retention");
+                let policy_archive = policy_flag("AutoBackupLogFiles");
+
+                let max_size_kb =
+                    policy_size.or_else(|| dword(&local, "MaxSize").map(|b| b / 1024));
+                let retain =
+                    policy_retain.or_else(|| dword(&local, "Retention").map(|r| r == RETAIN_ALL));
+                let archive =
+                    policy_archive.or_else(|| dword(&local, "AutoBackupLogFiles").map(|a| a != 0));
+                let retention = match (retain, archive) {
+                    (_, Some(true)) => Some(LogRetention::Archive),
+                    (Some(true), _) => Some(LogRetention::DoNotOverwrite),
+                    (Some(false), _) => Some(LogRetention::Overwrite),
+                    (None, _) => None,
+                };
+
+                EventLog {
+                    name: name.to_string(),
+                    max_size_kb,
+                    retention,
+                    policy: policy_size.is_some()
+                        || policy_retain.is_some()
+                        || policy_archive.is_some(),
+                }
+            })
+            .collect();
+
+        let subscription_managers = (1..)
+            .map_while(|i| string(SUBSCRIPTION_MANAGER_KEY, &i.to_string()))
+            .map(|entry| subscription_server(&entry))
+            .filter(|server| !server.is_empty())
+            .collect();
+
+        EventLogs {
+            logs,
+            subscription_managers,
+        }
+    }
+
+    /// The log with this name, if reported.
+    pub fn log(&self, name: &str) -> Option<&EventLog> {
+        self.logs.iter().find(|l| l.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether Windows Event Forwarding sends events to a collector.
+    pub fn forwarded(&self) -> bool {
+        !self.subscription_managers.is_empty()
+    }
+
+    /// Logs too small for the CIS benchmark or that stop recording when
+    /// full; empty when every log is configured well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::event_logs::{EventLog, EventLogs, LogRetention};
+    ///
+    /// let logs = EventLogs {
+    ///     logs: vec![EventLog {
+    ///         name: "Security".into(),
+    ///         max_size_kb: Some(20_480),
+    ///         retention: Some(LogRetention::Overwrite),
+    ///         policy: false,
+    ///     }],
+    ///     subscription_managers: vec![],
+    /// };
+    /// assert_eq!(logs.issues().len(), 1);
+    /// ```
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for log in &self.logs {
+            let minimum = EventLog::minimum_kb(&log.name);
+            if let Some(size) = log.max_size_kb.filter(|&size| size < minimum) {
+                issues.push(format!(
+                    "{} event log maximum size is {} KB (at least {} KB recommended)",
+                    log.name, size, minimum
+                ));
+            }
+            if log.retention == Some(LogRetention::DoNotOverwrite) {
+                issues.push(format!(
+                    "{} event log stops recording when full (no overwrite, no archive)",
+                    log.name
+                ));
+            }
+        }
+        issues
+    }
+}
+
+/// The `Server=` URL of a subscription manager entry such as
+/// `Server=http://wec.plant.local:5985/wsman/SubscriptionManager/WEC,Refresh=60`,
+/// or the whole entry when it names no server.
+#[cfg(any(all(windows, feature = "local"), test))]
+fn subscription_server(entry: &str) -> String {
+    entry
+        .split(',')
+        .find_map(|part| {
+            let (key, value) = part.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("Server")
+                .then(|| value.trim().to_string())
+        })
+        .unwrap_or_else(|| entry.trim().to_string())
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl EventLogs {
+    /// Collect the event log configuration (READ-ONLY).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::EventLogs;
+    ///
+    /// let logs = EventLogs::collect().unwrap();
+    /// for log in &logs.logs {
+    ///     println!("{}: {:?} KB", log.name, log.max_size_kb);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Registry`](crate::Error::Registry) if the event log
+    /// service key cannot be opened.
+    #[tracing::instrument]
+    pub fn collect() -> Result<Self, crate::Error> {
+        tracing::info!("Collecting event log configuration");
+        LOCAL_MACHINE.open(EVENTLOG_KEY)?;
+        Ok(Self::from_registry(
+            |path, value| LOCAL_MACHINE.open(path).ok()?.get_u32(value).ok(),
+            |path, value| {
+                LOCAL_MACHINE
+                    .open(path)
+                    .ok()?
+                    .get_string(value)
+                    .ok()
+                    .filter(|s| !s.is_empty())
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_registry_policy_wins() {
+        let logs = EventLogs::from_registry(
+            |path, value| match (path, value) {
+                (r"SYSTEM\CurrentControlSet\Services\EventLog\Security", "MaxSize") => {
+                    Some(20_971_520)
+                }
+                (r"SYSTEM\CurrentControlSet\Services\EventLog\Security", "Retention") => Some(0),
+                (r"SYSTEM\CurrentControlSet\Services\EventLog\System", "MaxSize") => {
+                    Some(20_971_520)
+                }
+                (r"SYSTEM\CurrentControlSet\Services\EventLog\System", "Retention") => {
+                    Some(RETAIN_ALL)
+                }
+                (r"SOFTWARE\Policies\Microsoft\Windows\EventLog\Application", "MaxSize") => {
+                    Some(32_768)
+                }
+                _ => None,
+            },
+            |path, value| match (path, value) {
+                (r"SOFTWARE\Policies\Microsoft\Windows\EventLog\Application", "Retention") => {
+                    Some("0".into())
+                }
+                (SUBSCRIPTION_MANAGER_KEY, "1") => Some(
+                    "Server=http://wec.plant.local:5985/wsman/SubscriptionManager/WEC,Refresh=60"
+                        .into(),
+                ),
+                _ => None,
+            },
+        );
+
+        let application = logs.log("Application").unwrap();
+        assert_eq!(application.max_size_kb, Some(32_768));
+        assert_eq!(application.retention, Some(LogRetention::Overwrite));
+        assert!(application.policy);
+        let security = logs.log("security").unwrap();
+        assert_eq!(security.max_size_kb, Some(20_480));
+        assert!(!security.policy);
+        assert_eq!(logs.log("Setup").unwrap().max_size_kb, None);
+        assert_eq!(
+            logs.subscription_managers,
+            ["http://wec.plant.local:5985/wsman/SubscriptionManager/WEC"]
+        );
+        assert!(logs.forwarded());
+
+        let issues = logs.issues();
+        assert_eq!(issues.len(), 3, "{:?}", issues);
+        assert!(issues[0].starts_with("Security event log maximum size is 20480 KB"));
+        assert!(issues[1].starts_with("System event log maximum size"));
+        assert!(issues[2].contains("stops recording"));
+    }
+
+    #[test]
+    fn test_archive_retention() {
+        let logs = EventLogs::from_registry(
+            |path, value| {
+                (path == r"SYSTEM\CurrentControlSet\Services\EventLog\Security"
+                    && value == "Retention")
+                    .then_some(RETAIN_ALL)
+            },
+            |path, value| {
+                (path == r"SOFTWARE\Policies\Microsoft\Windows\EventLog\Security"
+                    && value == "AutoBackupLogFiles")
+                    .then(|| "1".to_string())
+            },
+        );
+        let security = logs.log("Security").unwrap();
+        assert_eq!(security.retention, Some(LogRetention::Archive));
+        assert!(security.policy);
+        assert!(logs.issues().is_empty());
+        assert!(!logs.forwarded());
+    }
+}
@@ -0,0 +1,377 @@
+//! Antivirus exclusions.
+//!
+//! Automation vendors publish the antivirus exclusions their products need:
+//! without them real-time scanning locks project and runtime files and the
+//! HMI or historian crashes, while exclusions that are too broad leave the
+//! host unprotected. This module reads the Microsoft Defender exclusion
+//! lists (via WMI, which needs administrator rights) and the antivirus
+//! products registered with Windows Security Center, flags exclusions that
+//! cover far more than any product needs and lists the detected industrial
+//! products whose install folder is not excluded.
+//!
+//! Exclusions of third-party products are not readable through a common
+//! interface; when one is registered, missing Defender exclusions are not
+//! reported, since Defender is then passive.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use sysaudit_common::IndustrialSoftwareDto;
+#[cfg(all(windows, feature = "local"))]
+use wmi::WMIConnection;
+
+/// WMI namespace of `MSFT_MpPreference`.
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const DEFENDER_NAMESPACE: &str = r"root\Microsoft\Windows\Defender";
+
+/// WMI namespace of `AntiVirusProduct` (workstations only).
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const SECURITY_CENTER_NAMESPACE: &str = r"root\SecurityCenter2";
+
+/// Folders no product needs excluded as a whole, relative to the drive.
+const BROAD_FOLDERS: &[&str] = &[
+    r"\windows",
+    r"\windows\system32",
+    r"\windows\syswow64",
+    r"\windows\temp",
+    r"\program files",
+    r"\program files (x86)",
+    r"\programdata",
+    r"\users",
+];
+
+/// Per-user and temporary folders, by environment variable.
+const BROAD_VARIABLES: &[&str] = &[
+    "%temp%",
+    "%tmp%",
+    "%userprofile%",
+    "%appdata%",
+    "%localappdata%",
+];
+
+/// Executable and script extensions malware arrives as.
+const BROAD_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "sys", "com", "scr", "msi", "ps1", "bat", "cmd", "vbs", "js", "hta",
+];
+
+/// Interpreters and system binaries attackers run their payloads through.
+const BROAD_PROCESSES: &[&str] = &[
+    "powershell.exe",
+    "pwsh.exe",
+    "cmd.exe",
+    "wscript.exe",
+    "cscript.exe",
+    "mshta.exe",
+    "rundll32.exe",
+    "regsvr32.exe",
+    "svchost.exe",
+    "explorer.exe",
+];
+
+/// Microsoft Defender exclusion lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvExclusions {
+    /// Excluded files and folders, as configured (may contain environment
+    /// variables and wildcards)
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Excluded file extensions
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Processes whose file activity is excluded
+    #[serde(default)]
+    pub processes: Vec<String>,
+}
+
+impl AvExclusions {
+    /// Exclusions that cover far more than any product needs: drive roots,
+    /// system, program and user folders, executable extensions and script
+    /// interpreters, each with the reason.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::exclusions::AvExclusions;
+    ///
+    /// let exclusions = AvExclusions {
+    ///     paths: vec![
+    ///         r"C:\".into(),
+    ///         r"C:\Program Files\Schneider Electric\Citect SCADA 2018".into(),
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(exclusions.broad(), vec![r"path C:\ excludes a whole drive".to_string()]);
+    /// ```
+    pub fn broad(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for path in &self.paths {
+            let normalized = normalize(path);
+            let relative = normalized
+                .get(2..)
+                .filter(|_| normalized.as_bytes().get(1) == Some(&b':'))
+                .unwrap_or(&normalized);
+            if matches!(relative.trim_start_matches('\\'), "" | "*") {
+                issues.push(format!("path {} excludes a whole drive", path));
+            } else if BROAD_FOLDERS.contains(&relative) {
+                issues.push(format!("path {} excludes a system or shared folder", path));
+            } else if BROAD_VARIABLES.iter().any(|v| normalized.starts_with(v))
+                || relative.contains(r"\appdata\local\temp")
+            {
+                issues.push(format!("path {} excludes user or temporary files", path));
+            }
+        }
+        for extension in &self.extensions {
+            let normalized = extension
+                .trim_start_matches('*')
+                .trim_start_matches('.')
+                .to_lowercase();
+            if BROAD_EXTENSIONS.contains(&normalized.as_str()) {
+                issues.push(format!(
+                    "extension {} excludes every executable or script of that type",
+                    extension
+                ));
+            }
+        }
+        for process in &self.processes {
+            let normalized = normalize(process);
+            let name = normalized.rsplit('\\').next().unwrap_or_default();
+            if BROAD_PROCESSES.contains(&name) {
+                issues.push(format!(
+                    "process {} excludes everything that interpreter or system binary runs",
+                    process
+                ));
+            }
+        }
+        issues
+    }
+
+    /// Whether a path exclusion covers `path` (the path itself or a parent
+    /// folder; `*` and `?` match within one folder name).
+    pub fn covers(&self, path: &Path) -> bool {
+        let path = normalize(&path.to_string_lossy());
+        let target: Vec<&str> = path.split('\\').collect();
+        self.paths.iter().any(|exclusion| {
+            let exclusion = normalize(exclusion);
+            let pattern: Vec<&str> = exclusion.split('\\').collect();
+            pattern.len() <= target.len()
+                && pattern
+                    .iter()
+                    .zip(&target)
+                    .all(|(pattern, name)| wildcard(pattern, name))
+        })
+    }
+
+    /// Detected industrial products whose install folder no path exclusion
+    /// covers. Products without a known install path are left out.
+    pub fn missing<'a>(
+        &self,
+        industrial: &'a [IndustrialSoftwareDto],
+    ) -> Vec<&'a IndustrialSoftwareDto> {
+        industrial
+            .iter()
+            .filter(|sw| sw.install_path.as_deref().is_some_and(|p| !self.covers(p)))
+            .collect()
+    }
+}
+
+/// Whether a Security Center product name is Microsoft Defender.
+pub fn is_defender(product: &str) -> bool {
+    product.to_lowercase().contains("defender")
+}
+
+/// Lowercase, with the common system variables expanded and no trailing
+/// separator.
+fn normalize(path: &str) -> String {
+    let mut normalized = path.trim().replace('/', "\\").to_lowercase();
+    for (variable, value) in [
+        ("%systemdrive%", "c:"),
+        ("%systemroot%", r"c:\windows"),
+        ("%windir%", r"c:\windows"),
+        ("%programfiles%", r"c:\program files"),
+        ("%programfiles(x86)%", r"c:\program files (x86)"),
+        ("%programdata%", r"c:\programdata"),
+    ] {
+        if let Some(rest) = normalized.strip_prefix(variable) {
+            normalized = format!("{}{}", value, rest);
+        }
+    }
+    while normalized.len() > 2 && normalized.ends_with('\\') {
+        normalized.pop();
+    }
+    normalized
+}
+
+/// Match one folder name against a pattern with `*` and `?`.
+fn wildcard(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => {
+            pattern.len() == name.len()
+                && pattern
+                    .chars()
+                    .zip(name.chars())
+                    .all(|(p, c)| p == '?' || p == c)
+        }
+        Some((head, tail)) => {
+            name.is_char_boundary(head.len())
+                && wildcard(head, &name[..head.len()])
+                && (head.len()..=name.len())
+                    .filter(|&i| name.is_char_boundary(i))
+                    .any(|i| wildcard(tail, &name[i..]))
+        }
+    }
+}
+
+/// WMI result struct for MSFT_MpPreference.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename = "MSFT_MpPreference")]
+#[serde(rename_all = "PascalCase")]
+struct MsftMpPreference {
+    exclusion_path: Option<Vec<String>>,
+    exclusion_extension: Option<Vec<String>>,
+    exclusion_process: Option<Vec<String>>,
+}
+
+/// WMI result struct for AntiVirusProduct.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename = "AntiVirusProduct")]
+#[serde(rename_all = "camelCase")]
+struct AntiVirusProduct {
+    display_name: Option<String>,
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl AvExclusions {
+    /// Query `MSFT_MpPreference` on an open (local or remote) WMI
+    /// connection to [`DEFENDER_NAMESPACE`]. `None` when Defender has no
+    /// preferences there.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if the query fails, or
+    /// [`Error::General`](crate::Error::General) if the account may not read
+    /// the exclusions (Defender then reports "N/A: Must be an administrator").
+    pub(crate) fn query(wmi_con: &WMIConnection) -> Result<Option<Self>, crate::Error> {
+        let results: Vec<MsftMpPreference> = wmi_con.query()?;
+        let Some(preference) = results.into_iter().next() else {
+            return Ok(None);
+        };
+        let exclusions = AvExclusions {
+            paths: preference.exclusion_path.unwrap_or_default(),
+            extensions: preference.exclusion_extension.unwrap_or_default(),
+            processes: preference.exclusion_process.unwrap_or_default(),
+        };
+        if [
+            &exclusions.paths,
+            &exclusions.extensions,
+            &exclusions.processes,
+        ]
+        .iter()
+        .any(|list| list.iter().any(|e| e.starts_with("N/A")))
+        {
+            return Err(crate::Error::General(
+                "reading Defender exclusions requires administrator rights".to_string(),
+            ));
+        }
+        Ok(Some(exclusions))
+    }
+}
+
+/// Antivirus products registered with Security Center, from an open WMI
+/// connection to [`SECURITY_CENTER_NAMESPACE`].
+#[cfg(all(windows, feature = "local"))]
+pub(crate) fn query_antivirus(wmi_con: &WMIConnection) -> Result<Vec<String>, crate::Error> {
+    let results: Vec<AntiVirusProduct> = wmi_con.query()?;
+    let mut products: Vec<String> = results.into_iter().filter_map(|r| r.display_name).collect();
+    products.sort();
+    products.dedup();
+    Ok(products)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_broad() {
+        let exclusions = AvExclusions {
+            paths: vec![
+                r"D:\".into(),
+                r"%ProgramFiles%\".into(),
+                r"C:\Windows\Temp".into(),
+                r"%TEMP%\setup".into(),
+                r"C:\Users\operator\AppData\Local\Temp\".into(),
+                r"C:\ProgramData\AVEVA".into(),
+            ],
+            extensions: vec![".exe".into(), "*.ps1".into(), "mdb".into()],
+            processes: vec![
+                r"C:\Windows\System32\WindowsPowerShell\v1.0\powershell.exe".into(),
+                "Citect32.exe".into(),
+            ],
+        };
+        let broad = exclusions.broad();
+        assert_eq!(broad.len(), 8);
+        assert!(broad[0].contains("whole drive"));
+        assert!(broad[1].contains("system or shared folder"));
+        assert!(broad[2].contains("system or shared folder"));
+        assert!(broad[3].contains("user or temporary"));
+        assert!(broad[4].contains("user or temporary"));
+        assert!(broad[5].starts_with("extension .exe"));
+        assert!(broad[7].contains("powershell.exe"));
+        assert!(AvExclusions::default().broad().is_empty());
+    }
+
+    #[test]
+    fn test_covers() {
+        let exclusions = AvExclusions {
+            paths: vec![
+                r"%ProgramFiles(x86)%\Schneider Electric\".into(),
+                r"C:\Program Files\Rockwell*\FactoryTalk ?iew".into(),
+            ],
+            ..Default::default()
+        };
+        let covered = |path: &str| exclusions.covers(Path::new(path));
+        assert!(covered(
+            r"C:\Program Files (x86)\Schneider Electric\Citect SCADA 2018\Bin"
+        ));
+        assert!(covered(
+            r"c:\program files\Rockwell Software\FactoryTalk View\"
+        ));
+        assert!(!covered(r"C:\Program Files\Rockwell Software"));
+        assert!(!covered(r"C:\Program Files (x86)\Schneider"));
+    }
+
+    #[test]
+    fn test_missing() {
+        let product = |name: &str, path: Option<&str>| IndustrialSoftwareDto {
+            vendor: "Vendor".into(),
+            product: name.into(),
+            version: None,
+            install_path: path.map(PathBuf::from),
+        };
+        let industrial = [
+            product(
+                "Citect SCADA",
+                Some(r"C:\Program Files (x86)\AVEVA\Citect SCADA"),
+            ),
+            product(
+                "KEPServerEX",
+                Some(r"C:\Program Files (x86)\Kepware\KEPServerEX 6"),
+            ),
+            product("Unknown", None),
+        ];
+        let exclusions = AvExclusions {
+            paths: vec![r"C:\Program Files (x86)\AVEVA".into()],
+            ..Default::default()
+        };
+        let missing: Vec<&str> = exclusions
+            .missing(&industrial)
+            .iter()
+            .map(|sw| sw.product.as_str())
+            .collect();
+        assert_eq!(missing, ["KEPServerEX"]);
+        assert!(is_defender("Windows Defender"));
+        assert!(!is_defender("Sophos Intercept X"));
+    }
+}
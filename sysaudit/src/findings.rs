@@ -0,0 +1,492 @@
+//! Notable findings derived from an audit report.
+//!
+//! Findings are the event-shaped view of a report used by SIEM and alerting
+//! outputs: one entry per thing an operator may need to act on.
+
+use crate::AuditReport;
+use crate::exclusions::is_defender;
+use crate::lifecycle::{SoftwareLifecycle, SupportStatus};
+use crate::servicing::ServicingStatus;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Days without a recorded update before patching is flagged as stale.
+pub const STALE_PATCH_DAYS: i64 = 90;
+
+/// Finding severity, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    /// Informational
+    #[serde(alias = "info")]
+    Info,
+    /// Low
+    #[serde(alias = "low")]
+    Low,
+    /// Medium
+    #[serde(alias = "medium")]
+    Medium,
+    /// High
+    #[serde(alias = "high")]
+    High,
+    /// Critical
+    #[serde(alias = "critical")]
+    Critical,
+}
+
+impl Severity {
+    /// Severity on the 0-10 scale used by CEF and LEEF.
+    pub fn level(self) -> u8 {
+        match self {
+            Severity::Info => 1,
+            Severity::Low => 3,
+            Severity::Medium => 5,
+            Severity::High => 8,
+            Severity::Critical => 10,
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Info => write!(f, "Info"),
+            Severity::Low => write!(f, "Low"),
+            Severity::Medium => write!(f, "Medium"),
+            Severity::High => write!(f, "High"),
+            Severity::Critical => write!(f, "Critical"),
+        }
+    }
+}
+
+/// A single notable finding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    /// Stable identifier for the kind of finding (e.g. "industrial-detected")
+    pub id: String,
+    /// Category (e.g. "industrial", "patching")
+    pub category: String,
+    /// Severity
+    pub severity: Severity,
+    /// Human-readable summary
+    pub title: String,
+    /// Additional attributes as key/value pairs
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Finding {
+    /// Derive findings from a report.
+    ///
+    /// Currently reports:
+    /// - each detected industrial application, with its
+    ///   [`RuntimeState`](crate::RuntimeState) when services were collected
+    /// - installed software past (or within six months of) its vendor end
+    ///   of life, per [`SoftwareLifecycle::bundled`]
+    /// - hosts with no recorded Windows updates
+    /// - hosts whose latest update is older than [`STALE_PATCH_DAYS`]
+    /// - hosts whose OS build and installed cumulative updates disagree,
+    ///   per [`ServicingStatus`]
+    /// - each problem with the Windows Update agents, per
+    ///   [`UpdateHealth::issues`](crate::UpdateHealth::issues)
+    /// - each license that is not activated, per
+    ///   [`Licensing::issues`](crate::Licensing::issues)
+    /// - each Defender exclusion that is too broad, per
+    ///   [`AvExclusions::broad`](crate::exclusions::AvExclusions::broad), and
+    ///   each detected industrial product whose install folder Defender does
+    ///   not exclude, per
+    ///   [`AvExclusions::missing`](crate::exclusions::AvExclusions::missing)
+    ///   (only while Defender is the active antivirus)
+    /// - each event log that is too small or stops recording when full, per
+    ///   [`EventLogs::issues`](crate::EventLogs::issues), and hosts that do
+    ///   not forward events to a collector
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{Finding, LocalScanner, Scanner};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// for finding in Finding::collect(&report) {
+    ///     println!("[{}] {}", finding.severity, finding.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn collect(report: &AuditReport) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = report
+            .industrial
+            .iter()
+            .map(|sw| {
+                let mut attributes = vec![
+                    ("vendor".to_string(), sw.vendor.clone()),
+                    ("product".to_string(), sw.product.clone()),
+                    (
+                        "version".to_string(),
+                        sw.version.clone().unwrap_or_default(),
+                    ),
+                    (
+                        "path".to_string(),
+                        sw.install_path
+                            .as_ref()
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_default(),
+                    ),
+                ];
+                if let Some(state) = report.runtime_state(sw) {
+                    attributes.push(("runtime_state".to_string(), format!("{:?}", state)));
+                }
+                Finding {
+                    id: "industrial-detected".to_string(),
+                    category: "industrial".to_string(),
+                    severity: Severity::Medium,
+                    title: format!("Industrial software detected: {} {}", sw.vendor, sw.product),
+                    attributes,
+                }
+            })
+            .collect();
+
+        let today = report.timestamp.date_naive();
+        let lifecycle = SoftwareLifecycle::bundled();
+        for sw in &report.software {
+            let Some(support) = lifecycle.lookup(&sw.name, sw.version.as_deref(), today) else {
+                continue;
+            };
+            let severity = match support.status {
+                SupportStatus::Supported => continue,
+                SupportStatus::EndingSoon => Severity::Low,
+                SupportStatus::EndOfLife => Severity::High,
+            };
+            let eol = support.eol.map(|d| d.to_string()).unwrap_or_default();
+            findings.push(Finding {
+                id: "software-end-of-life".to_string(),
+                category: "obsolescence".to_string(),
+                severity,
+                title: match support.status {
+                    SupportStatus::EndingSoon => {
+                        format!("Vendor support for {} ends {}", sw.name, eol)
+                    }
+                    _ => format!("{} is past vendor end of life", sw.name),
+                },
+                attributes: vec![
+                    ("name".to_string(), sw.name.clone()),
+                    (
+                        "version".to_string(),
+                        sw.version.clone().unwrap_or_default(),
+                    ),
+                    ("product".to_string(), support.product),
+                    ("cycle".to_string(), support.cycle),
+                    ("eol".to_string(), eol),
+                ],
+            });
+        }
+
+        let latest: Option<NaiveDate> = report.updates.iter().filter_map(|u| u.installed_on).max();
+
+        match latest {
+            None => findings.push(Finding {
+                id: "patches-none".to_string(),
+                category: "patching".to_string(),
+                severity: Severity::High,
+                title: "No installed Windows updates recorded".to_string(),
+                attributes: vec![("updates".to_string(), report.updates.len().to_string())],
+            }),
+            Some(date) if (today - date).num_days() > STALE_PATCH_DAYS => findings.push(Finding {
+                id: "patches-stale".to_string(),
+                category: "patching".to_string(),
+                severity: Severity::High,
+                title: format!(
+                    "No Windows updates installed in {} days",
+                    (today - date).num_days()
+                ),
+                attributes: vec![("last_update".to_string(), date.to_string())],
+            }),
+            Some(_) => {}
+        }
+
+        // An empty update list is already reported as `patches-none`.
+        let servicing = report
+            .os_build
+            .as_deref()
+            .filter(|_| !report.updates.is_empty())
+            .map(|build| ServicingStatus::evaluate(build, &report.updates));
+        let kb = match &servicing {
+            Some(ServicingStatus::MissingUpdate { kb, .. })
+            | Some(ServicingStatus::BuildBehind { kb, .. }) => Some(kb.clone()),
+            _ => None,
+        };
+        if let (Some(status), Some(kb)) = (servicing, kb) {
+            findings.push(Finding {
+                id: "servicing-inconsistent".to_string(),
+                category: "patching".to_string(),
+                severity: Severity::High,
+                title: format!("OS build and installed updates disagree: {}", status),
+                attributes: vec![
+                    (
+                        "build".to_string(),
+                        report.os_build.clone().unwrap_or_default(),
+                    ),
+                    ("kb".to_string(), kb),
+                ],
+            });
+        }
+
+        if let Some(health) = &report.update_health {
+            for issue in health.issues(report.timestamp) {
+                findings.push(Finding {
+                    id: "update-agent-unhealthy".to_string(),
+                    category: "patching".to_string(),
+                    severity: Severity::Medium,
+                    title: issue.clone(),
+                    attributes: vec![("issue".to_string(), issue)],
+                });
+            }
+        }
+
+        if let Some(licensing) = &report.licensing {
+            for issue in licensing.issues() {
+                findings.push(Finding {
+                    id: "license-not-activated".to_string(),
+                    category: "licensing".to_string(),
+                    severity: Severity::Medium,
+                    title: issue.clone(),
+                    attributes: vec![("issue".to_string(), issue)],
+                });
+            }
+        }
+
+        let exclusions = report.security.as_ref().and_then(|security| {
+            let defender_active = security.antivirus.iter().all(|p| is_defender(p));
+            Some((security.defender_exclusions.as_ref()?, defender_active))
+        });
+        if let Some((exclusions, defender_active)) = exclusions {
+            for issue in exclusions.broad() {
+                findings.push(Finding {
+                    id: "av-exclusion-broad".to_string(),
+                    category: "security".to_string(),
+                    severity: Severity::High,
+                    title: format!("Antivirus exclusion too broad: {}", issue),
+                    attributes: vec![("issue".to_string(), issue)],
+                });
+            }
+            // Another product is doing the scanning; its exclusions are not read.
+            let missing = if defender_active {
+                exclusions.missing(&report.industrial)
+            } else {
+                Vec::new()
+            };
+            for sw in missing {
+                let path = sw
+                    .install_path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default();
+                findings.push(Finding {
+                    id: "av-exclusion-missing".to_string(),
+                    category: "security".to_string(),
+                    severity: Severity::Low,
+                    title: format!(
+                        "No antivirus exclusion for {} {} ({})",
+                        sw.vendor, sw.product, path
+                    ),
+                    attributes: vec![
+                        ("vendor".to_string(), sw.vendor.clone()),
+                        ("product".to_string(), sw.product.clone()),
+                        ("path".to_string(), path),
+                    ],
+                });
+            }
+        }
+
+        if let Some(logs) = &report.event_logs {
+            for issue in logs.issues() {
+                findings.push(Finding {
+                    id: "event-log-misconfigured".to_string(),
+                    category: "logging".to_string(),
+                    severity: Severity::Medium,
+                    title: issue.clone(),
+                    attributes: vec![("issue".to_string(), issue)],
+                });
+            }
+            if !logs.forwarded() {
+                findings.push(Finding {
+                    id: "event-forwarding-missing".to_string(),
+                    category: "logging".to_string(),
+                    severity: Severity::Low,
+                    title: "Events are not forwarded to a collector (no WEF subscription manager)"
+                        .to_string(),
+                    attributes: vec![],
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exclusions::AvExclusions;
+    use crate::{EventLog, EventLogs, LogRetention, SecurityPosture, WindowsUpdate};
+    use chrono::{TimeZone, Utc};
+    use sysaudit_common::{IndustrialSoftwareDto, SoftwareDto, SystemInfoDto};
+
+    fn sample_report(updates: Vec<WindowsUpdate>) -> AuditReport {
+        AuditReport {
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "Rockwell".into(),
+                product: "FactoryTalk View".into(),
+                version: Some("13.0".into()),
+                install_path: None,
+            }],
+            updates,
+            timestamp: Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 IoT".into(),
+                os_version: "21H2".into(),
+                host_name: "HMI-01".into(),
+                cpu_info: "Intel Atom".into(),
+                cpu_physical_cores: Some(4),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        }
+    }
+
+    fn update(y: i32, m: u32, d: u32) -> WindowsUpdate {
+        WindowsUpdate {
+            hotfix_id: "KB5034441".into(),
+            description: None,
+            installed_on: NaiveDate::from_ymd_opt(y, m, d),
+            installed_by: None,
+        }
+    }
+
+    #[test]
+    fn test_findings_industrial_and_no_updates() {
+        let findings = Finding::collect(&sample_report(vec![]));
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].id, "industrial-detected");
+        assert_eq!(findings[1].id, "patches-none");
+    }
+
+    #[test]
+    fn test_findings_stale_patches() {
+        let stale = Finding::collect(&sample_report(vec![update(2024, 1, 1)]));
+        assert!(stale.iter().any(|f| f.id == "patches-stale"));
+
+        let recent = Finding::collect(&sample_report(vec![update(2024, 5, 20)]));
+        assert!(recent.iter().all(|f| f.category != "patching"));
+    }
+
+    #[test]
+    fn test_findings_software_end_of_life() {
+        let mut report = sample_report(vec![update(2024, 5, 20)]);
+        report.software = [
+            ("Python 3.7.9 (64-bit)", "3.7.9150.0"),
+            ("Node.js", "20.11.0"),
+        ]
+        .into_iter()
+        .map(|(name, version)| SoftwareDto {
+            name: name.into(),
+            version: Some(version.into()),
+            vendor: None,
+            install_date: None,
+        })
+        .collect();
+
+        let eol: Vec<_> = Finding::collect(&report)
+            .into_iter()
+            .filter(|f| f.id == "software-end-of-life")
+            .collect();
+        assert_eq!(eol.len(), 1);
+        assert_eq!(eol[0].severity, Severity::High);
+        assert!(
+            eol[0]
+                .attributes
+                .contains(&("cycle".to_string(), "3.7".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_findings_servicing_inconsistent() {
+        let mut report = sample_report(vec![update(2024, 5, 20)]);
+        report.os_build = Some("19045.4412".into());
+        let servicing: Vec<_> = Finding::collect(&report)
+            .into_iter()
+            .filter(|f| f.id == "servicing-inconsistent")
+            .collect();
+        assert_eq!(servicing.len(), 1);
+        assert!(
+            servicing[0]
+                .attributes
+                .contains(&("kb".to_string(), "KB5037768".to_string()))
+        );
+
+        report.updates[0].hotfix_id = "KB5037768".into();
+        assert!(
+            Finding::collect(&report)
+                .iter()
+                .all(|f| f.id != "servicing-inconsistent")
+        );
+    }
+
+    #[test]
+    fn test_findings_av_exclusions() {
+        let mut report = sample_report(vec![update(2024, 5, 20)]);
+        report.industrial[0].install_path =
+            Some(r"C:\Program Files (x86)\Rockwell Software\RSView Enterprise".into());
+        report.security = Some(SecurityPosture {
+            defender_exclusions: Some(AvExclusions {
+                paths: vec![r"C:\Windows".into()],
+                ..Default::default()
+            }),
+            antivirus: vec!["Windows Defender".into()],
+            ..Default::default()
+        });
+        let ids = |report: &AuditReport| -> Vec<String> {
+            Finding::collect(report)
+                .into_iter()
+                .filter(|f| f.category == "security")
+                .map(|f| f.id)
+                .collect()
+        };
+        assert_eq!(ids(&report), ["av-exclusion-broad", "av-exclusion-missing"]);
+
+        // With another product active, Defender's missing exclusions do not matter
+        let security = report.security.as_mut().unwrap();
+        security.antivirus.push("Sophos Intercept X".into());
+        assert_eq!(ids(&report), ["av-exclusion-broad"]);
+    }
+
+    #[test]
+    fn test_findings_event_logs() {
+        let mut report = sample_report(vec![update(2024, 5, 20)]);
+        report.event_logs = Some(EventLogs {
+            logs: vec![EventLog {
+                name: "Security".into(),
+                max_size_kb: Some(20_480),
+                retention: Some(LogRetention::Overwrite),
+                policy: false,
+            }],
+            subscription_managers: vec![],
+        });
+        let findings: Vec<Finding> = Finding::collect(&report)
+            .into_iter()
+            .filter(|f| f.category == "logging")
+            .collect();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].id, "event-log-misconfigured");
+        assert!(findings[0].title.contains("20480 KB"));
+        assert_eq!(findings[1].id, "event-forwarding-missing");
+    }
+
+    #[test]
+    fn test_severity_order() {
+        assert!(Severity::Critical > Severity::High);
+        assert_eq!(Severity::High.level(), 8);
+    }
+}
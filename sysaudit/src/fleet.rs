@@ -0,0 +1,570 @@
+//! Concurrent auditing of many remote hosts.
+//!
+//! [`FleetScanner`] runs one [`RemoteScanner`] per target, at most
+//! `concurrency` at a time, and reports success or failure per host.
+//! With a state file, every finished host is recorded as it completes, so
+//! an interrupted run can be resumed (see [`FleetState`]).
+
+mod state;
+
+pub use state::{FleetState, HostRecord};
+
+use bon::Builder;
+use chrono::Utc;
+use secrecy::SecretString;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::inventory::{Inventory, Transport};
+use crate::remote::progress::emit;
+use crate::report::AuditReport;
+use crate::scanner::{ScanError, Scanner};
+use crate::{
+    AuthMethod, ClientCertificate, CredentialSource, Error, EventSender, ExecutionMode,
+    RemoteScanner, RetryPolicy, ScanOptions, ScanStage, Sections,
+};
+
+/// A host to audit, with optional per-host overrides.
+#[derive(Debug, Clone)]
+pub struct FleetTarget {
+    /// Hostname or IP address
+    pub host: String,
+    /// Port overriding the fleet-wide one
+    pub port: Option<u16>,
+    /// Transport overriding the fleet-wide one
+    pub transport: Option<Transport>,
+    /// Username overriding the fleet-wide one
+    pub username: Option<String>,
+    /// Password overriding the fleet-wide one
+    pub password: Option<SecretString>,
+    /// Site the host belongs to
+    pub site: Option<String>,
+    /// Grouping tags
+    pub tags: Vec<String>,
+}
+
+impl FleetTarget {
+    /// Target using the fleet-wide settings.
+    pub fn new(host: impl Into<String>) -> Self {
+        FleetTarget {
+            host: host.into(),
+            port: None,
+            transport: None,
+            username: None,
+            password: None,
+            site: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Parse a YAML list of hosts.
+    ///
+    /// Entries are hostnames or maps with `host` and an optional
+    /// `username`; passwords never come from the file. See
+    /// [`Inventory`](crate::inventory::Inventory) for the full format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::fleet::FleetTarget;
+    ///
+    /// let targets = FleetTarget::from_yaml("- hmi01\n- host: hmi02\n  username: PLANT\\audit\n").unwrap();
+    /// assert_eq!(targets[1].username.as_deref(), Some("PLANT\\audit"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Yaml`] if the document is not a list of targets, or
+    /// [`Error::General`] if a referenced credential has no password set.
+    pub fn from_yaml(yaml: &str) -> Result<Vec<Self>, Error> {
+        Inventory::from_yaml(yaml)?.targets()
+    }
+}
+
+/// Outcome of scanning one host.
+#[derive(Debug)]
+pub struct HostResult {
+    /// Target hostname or IP address
+    pub host: String,
+    /// Site from the inventory
+    pub site: Option<String>,
+    /// Tags from the inventory
+    pub tags: Vec<String>,
+    /// The host's report, or why it could not be collected
+    pub result: Result<AuditReport, ScanError>,
+}
+
+/// Audits a list of hosts over WinRM with bounded concurrency.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::fleet::{FleetScanner, FleetTarget};
+/// use sysaudit::AuthMethod;
+///
+/// # async fn example() {
+/// let results = FleetScanner::builder()
+///     .targets(vec![FleetTarget::new("hmi01"), FleetTarget::new("hmi02")])
+///     .auth(AuthMethod::Kerberos)
+///     .concurrency(16)
+///     .build()
+///     .scan()
+///     .await;
+///
+/// for host in &results {
+///     match &host.result {
+///         Ok(report) => println!("{}: {} packages", host.host, report.software.len()),
+///         Err(e) => println!("{}: {}", host.host, e),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Builder)]
+pub struct FleetScanner {
+    /// Hosts to audit.
+    targets: Vec<FleetTarget>,
+
+    /// Username shared by targets without their own.
+    #[builder(into)]
+    username: Option<String>,
+
+    /// Password shared by targets without their own.
+    password: Option<SecretString>,
+
+    /// Where to fetch the shared password when none is set. Resolved once
+    /// per run, so a prompt is shown only once.
+    credential_source: Option<CredentialSource>,
+
+    /// Authentication method (default: Basic).
+    #[builder(default)]
+    auth: AuthMethod,
+
+    /// WinRM port (default: 5985).
+    #[builder(default = 5985)]
+    port: u16,
+
+    /// Use HTTPS instead of HTTP.
+    #[builder(default = false)]
+    use_https: bool,
+
+    /// Skip TLS certificate verification (for self-signed certs).
+    #[builder(default = false)]
+    skip_cert_verify: bool,
+
+    /// PEM bundle of additional trusted CA certificates for HTTPS.
+    #[builder(into)]
+    ca_bundle: Option<PathBuf>,
+
+    /// Client certificate (`.pfx` file and passphrase) presented to every
+    /// WinRM HTTPS host instead of a username and password.
+    #[builder(with = |path: impl Into<PathBuf>, passphrase: impl Into<SecretString>| {
+        ClientCertificate::new(path, passphrase)
+    })]
+    client_cert: Option<ClientCertificate>,
+
+    /// Timeout for each scan attempt.
+    #[builder(default = Duration::from_secs(30))]
+    timeout: Duration,
+
+    /// Per-host retry policy for network failures and timeouts.
+    #[builder(default)]
+    retry: RetryPolicy,
+
+    /// What to collect on every host (default: software, industrial and
+    /// updates).
+    #[builder(into, default)]
+    options: ScanOptions,
+
+    /// Payload or pushed agent for WinRM hosts (default: payload).
+    #[builder(default)]
+    mode: ExecutionMode,
+
+    /// Maximum number of hosts scanned at once (default: 8).
+    #[builder(default = 8)]
+    concurrency: usize,
+
+    /// Channel receiving every host's progress events, plus a
+    /// [`ScanStage::Done`] when each host finishes.
+    events: Option<EventSender>,
+
+    /// State file recording every finished host. Hosts completed in an
+    /// earlier run with the same file are not scanned again; failed hosts
+    /// are retried.
+    #[builder(into)]
+    state_file: Option<PathBuf>,
+
+    /// Minimum delay between two hosts starting, whatever the concurrency,
+    /// to spread the load on slow or fragile networks.
+    start_interval: Option<Duration>,
+}
+
+impl<S: fleet_scanner_builder::State> FleetScannerBuilder<S> {
+    /// Report sections to collect.
+    #[deprecated(note = "use `options`, which also takes `Sections`")]
+    pub fn sections(
+        self,
+        sections: Sections,
+    ) -> FleetScannerBuilder<fleet_scanner_builder::SetOptions<S>>
+    where
+        S::Options: fleet_scanner_builder::IsUnset,
+    {
+        self.options(sections)
+    }
+}
+
+impl FleetScanner {
+    /// Scan every target, returning one result per target in input order.
+    ///
+    /// A failing host never aborts the run: transient failures are retried
+    /// per the [`RetryPolicy`], and the final error is kept in the host's
+    /// [`HostResult`] (see [`ScanError::kind`] to tell auth, network and
+    /// execution failures apart).
+    ///
+    /// With a [`state_file`](FleetScannerBuilder::state_file), hosts
+    /// completed in an earlier run keep their recorded report instead of
+    /// being scanned again.
+    pub async fn scan(&self) -> Vec<HostResult> {
+        self.scan_with_cancel(&CancellationToken::new()).await
+    }
+
+    /// Like [`scan`](Self::scan), but stops once `cancel` is triggered:
+    /// running hosts are abandoned and hosts not yet started are skipped,
+    /// all reported as [`ScanError::Cancelled`]. Finished hosts keep their
+    /// results.
+    pub async fn scan_with_cancel(&self, cancel: &CancellationToken) -> Vec<HostResult> {
+        let concurrency = self.concurrency.max(1);
+        tracing::info!(
+            hosts = self.targets.len(),
+            concurrency,
+            "Starting fleet scan"
+        );
+
+        let shared = match self.shared_credentials().await {
+            Ok(shared) => shared,
+            Err(message) => return self.fail_all(&message),
+        };
+        let mut state = match self.state_file.clone().map(FleetState::open).transpose() {
+            Ok(state) => state,
+            Err(e) => return self.fail_all(&format!("fleet state: {}", e)),
+        };
+
+        let mut tasks = JoinSet::new();
+        let mut results: Vec<Option<HostResult>> = Vec::new();
+        results.resize_with(self.targets.len(), || None);
+        if let Some(state) = &state {
+            for (index, target) in self.targets.iter().enumerate() {
+                if let Some(report) = state.get(&target.host).and_then(|r| r.report.clone()) {
+                    results[index] = Some(HostResult::new(target, Ok(report)));
+                }
+            }
+            tracing::info!(
+                path = %state.path().display(),
+                completed = results.iter().flatten().count(),
+                "Resuming fleet scan"
+            );
+        }
+        let pending: Vec<usize> = (0..self.targets.len())
+            .filter(|&index| results[index].is_none())
+            .collect();
+        let mut queue = pending.into_iter();
+        let mut next_start = Instant::now();
+
+        loop {
+            while tasks.len() < concurrency && !cancel.is_cancelled() {
+                let Some(index) = queue.next() else {
+                    break;
+                };
+                let scanner = self.scanner_for(&self.targets[index], &shared);
+                let cancel = cancel.clone();
+                // Each host waits for its turn in its own task, so finished
+                // hosts are still collected while the next start is delayed.
+                let start_at = next_start.max(Instant::now());
+                next_start = start_at + self.start_interval.unwrap_or_default();
+                tasks.spawn(async move {
+                    let started = Utc::now();
+                    if cancel
+                        .run_until_cancelled(tokio::time::sleep_until(start_at))
+                        .await
+                        .is_none()
+                    {
+                        return (index, started, Err(ScanError::Cancelled));
+                    }
+                    (index, Utc::now(), scanner.scan(&cancel).await)
+                });
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            let (index, started, result) = match joined {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    // The slot stays empty and is reported below.
+                    tracing::error!(error = %e, "Fleet scan task failed");
+                    continue;
+                }
+            };
+
+            let target = &self.targets[index];
+            emit(
+                self.events.as_ref(),
+                &target.host,
+                ScanStage::Done { ok: result.is_ok() },
+            );
+            match &result {
+                Ok(_) => tracing::info!(host = %target.host, "Host scanned"),
+                Err(e) => tracing::warn!(host = %target.host, error = %e, "Host scan failed"),
+            }
+            // Cancelled hosts did not finish and are scanned on resume.
+            let recorded = match state.as_mut() {
+                Some(state) if !matches!(result, Err(ScanError::Cancelled)) => {
+                    state.append(HostRecord::new(&target.host, started, &result))
+                }
+                _ => Ok(()),
+            };
+            if let Err(e) = recorded {
+                tracing::warn!(host = %target.host, error = %e, "Could not record host in fleet state");
+            }
+            results[index] = Some(HostResult::new(target, result));
+        }
+
+        results
+            .into_iter()
+            .zip(&self.targets)
+            .map(|(result, target)| {
+                result.unwrap_or_else(|| {
+                    let error = if cancel.is_cancelled() {
+                        ScanError::Cancelled
+                    } else {
+                        ScanError::RemoteExecution {
+                            host: target.host.clone(),
+                            message: "scan task failed".to_string(),
+                        }
+                    };
+                    HostResult::new(target, Err(error))
+                })
+            })
+            .collect()
+    }
+
+    /// One failure per target, for errors that prevent the whole run.
+    fn fail_all(&self, message: &str) -> Vec<HostResult> {
+        self.targets
+            .iter()
+            .map(|target| HostResult::new(target, Err(ScanError::Local(message.to_string()))))
+            .collect()
+    }
+
+    /// The fleet-wide username and password, resolving the credential
+    /// source if no password was given.
+    async fn shared_credentials(&self) -> Result<Credentials, String> {
+        let Some(source) = self
+            .credential_source
+            .clone()
+            .filter(|_| self.password.is_none())
+        else {
+            return Ok((self.username.clone(), self.password.clone()));
+        };
+
+        let username = self.username.clone();
+        let resolved = tokio::task::spawn_blocking(move || source.resolve(username.as_deref()))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("credentials: {}", e))?;
+        Ok((resolved.username, Some(resolved.password)))
+    }
+
+    /// Port for a host without its own: the fleet port when the host uses
+    /// the fleet's scheme, otherwise the standard port of its scheme.
+    fn default_port(&self, use_https: bool) -> u16 {
+        match (use_https, self.use_https) {
+            (true, false) => 5986,
+            (false, true) => 5985,
+            _ => self.port,
+        }
+    }
+
+    fn scanner_for(&self, target: &FleetTarget, shared: &Credentials) -> HostScanner {
+        let transport = target.transport.unwrap_or(if self.use_https {
+            Transport::WinRmHttps
+        } else {
+            Transport::WinRm
+        });
+        let use_https = transport == Transport::WinRmHttps;
+        let port = target.port.unwrap_or_else(|| self.default_port(use_https));
+
+        if transport == Transport::Wmi {
+            #[cfg(feature = "local")]
+            return HostScanner::Wmi(
+                crate::WmiRemoteScanner::builder()
+                    .host(target.host.clone())
+                    .timeout(self.timeout)
+                    .options(self.options.clone())
+                    .build(),
+            );
+            #[cfg(not(feature = "local"))]
+            return HostScanner::Unsupported(target.host.clone());
+        }
+
+        HostScanner::WinRm(
+            RemoteScanner::builder()
+                .host(target.host.clone())
+                .maybe_username(target.username.clone().or_else(|| shared.0.clone()))
+                .maybe_password(target.password.clone().or_else(|| shared.1.clone()))
+                .auth(self.auth)
+                .port(port)
+                .use_https(use_https)
+                .skip_cert_verify(self.skip_cert_verify)
+                .maybe_ca_bundle(self.ca_bundle.clone())
+                .timeout(self.timeout)
+                .retry(self.retry)
+                .options(self.options.clone())
+                .mode(self.mode.clone())
+                .maybe_events(self.events.clone())
+                .build()
+                .with_client_cert(self.client_cert.clone()),
+        )
+    }
+}
+
+impl HostResult {
+    fn new(target: &FleetTarget, result: Result<AuditReport, ScanError>) -> Self {
+        HostResult {
+            host: target.host.clone(),
+            site: target.site.clone(),
+            tags: target.tags.clone(),
+            result,
+        }
+    }
+}
+
+/// Username and password shared by targets without their own.
+type Credentials = (Option<String>, Option<SecretString>);
+
+/// The scanner chosen for a target's transport.
+enum HostScanner {
+    WinRm(RemoteScanner),
+    #[cfg(feature = "local")]
+    Wmi(crate::WmiRemoteScanner),
+    #[cfg(not(feature = "local"))]
+    Unsupported(String),
+}
+
+impl HostScanner {
+    async fn scan(&self, cancel: &CancellationToken) -> Result<AuditReport, ScanError> {
+        match self {
+            HostScanner::WinRm(scanner) => scanner.scan_with_cancel(cancel).await,
+            #[cfg(feature = "local")]
+            HostScanner::Wmi(scanner) => scanner.scan_with_cancel(cancel).await,
+            #[cfg(not(feature = "local"))]
+            HostScanner::Unsupported(host) => Err(ScanError::RemoteConnection {
+                host: host.clone(),
+                message: "the wmi transport requires the `local` feature".to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets_from_yaml() {
+        let yaml = "- hmi01\n- host: 10.0.0.12\n  username: PLANT\\audit\n- host: hmi03\n";
+        let targets = FleetTarget::from_yaml(yaml).unwrap();
+
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0].host, "hmi01");
+        assert!(targets[0].username.is_none());
+        assert_eq!(targets[1].host, "10.0.0.12");
+        assert_eq!(targets[1].username.as_deref(), Some("PLANT\\audit"));
+        assert!(targets[2].username.is_none());
+        assert!(FleetTarget::from_yaml("host: hmi01").is_err());
+    }
+
+    #[test]
+    fn test_default_port_follows_host_transport() {
+        let http = FleetScanner::builder().targets(Vec::new()).build();
+        assert_eq!(http.default_port(false), 5985);
+        assert_eq!(http.default_port(true), 5986);
+
+        let https = FleetScanner::builder()
+            .targets(Vec::new())
+            .use_https(true)
+            .port(5986)
+            .build();
+        assert_eq!(https.default_port(true), 5986);
+        assert_eq!(https.default_port(false), 5985);
+    }
+
+    #[tokio::test]
+    async fn test_empty_fleet() {
+        let results = FleetScanner::builder()
+            .targets(Vec::new())
+            .build()
+            .scan()
+            .await;
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_fleet_skips_hosts() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let results = FleetScanner::builder()
+            .targets(FleetTarget::from_yaml("- hmi01\n- hmi02\n").unwrap())
+            .build()
+            .scan_with_cancel(&cancel)
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(
+            results
+                .iter()
+                .all(|r| matches!(r.result, Err(ScanError::Cancelled)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resumed_fleet_skips_completed_hosts() {
+        let path =
+            std::env::temp_dir().join(format!("sysaudit-resume-{}.state", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let report =
+            AuditReport::from_json_versioned(include_str!("../tests/fixtures/report.json"))
+                .unwrap();
+        let mut state = FleetState::open(&path).unwrap();
+        state
+            .append(HostRecord::new("hmi01", Utc::now(), &Ok(report)))
+            .unwrap();
+        state
+            .append(HostRecord::new(
+                "hmi02",
+                Utc::now(),
+                &Err(ScanError::Timeout(Duration::from_secs(30))),
+            ))
+            .unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let results = FleetScanner::builder()
+            .targets(FleetTarget::from_yaml("- HMI01\n- hmi02\n").unwrap())
+            .state_file(&path)
+            .build()
+            .scan_with_cancel(&cancel)
+            .await;
+
+        assert!(results[0].result.is_ok());
+        // Failed hosts are scanned again
+        assert!(matches!(results[1].result, Err(ScanError::Cancelled)));
+        // and cancelled ones are not recorded
+        let state = FleetState::open(&path).unwrap();
+        assert_eq!(state.records().count(), 2);
+        assert!(!state.get("hmi02").unwrap().completed());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,252 @@
+//! Run state of a fleet scan, for resuming interrupted runs.
+//!
+//! Every host that finishes is appended to the state file as one JSON line,
+//! with its start and end time and either its report or its error. A run
+//! given the same file skips the hosts completed in it and reuses their
+//! reports; failed hosts are scanned again. Appending one line per host
+//! keeps the file valid however the run ends: a line cut off by a crash is
+//! dropped from the file on the next load, so later records start on a
+//! line of their own.
+
+use crate::Error;
+use crate::report::AuditReport;
+use crate::scanner::{ScanError, ScanErrorKind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Outcome of one host in a fleet run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostRecord {
+    /// Target hostname or IP address
+    pub host: String,
+    /// When the host's scan started
+    pub started: DateTime<Utc>,
+    /// When it finished
+    pub finished: DateTime<Utc>,
+    /// Class of the failure, for failed hosts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ScanErrorKind>,
+    /// Failure message, for failed hosts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The host's report, for completed hosts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<AuditReport>,
+}
+
+impl HostRecord {
+    /// Record a finished scan.
+    pub fn new(
+        host: impl Into<String>,
+        started: DateTime<Utc>,
+        result: &Result<AuditReport, ScanError>,
+    ) -> Self {
+        let (report, error_kind, error) = match result {
+            Ok(report) => (Some(report.clone()), None, None),
+            Err(e) => (None, Some(e.kind()), Some(e.to_string())),
+        };
+        HostRecord {
+            host: host.into(),
+            started,
+            finished: Utc::now(),
+            error_kind,
+            error,
+            report,
+        }
+    }
+
+    /// Whether the host was scanned successfully.
+    pub fn completed(&self) -> bool {
+        self.report.is_some()
+    }
+}
+
+/// A fleet run's state file and the latest record of each host in it.
+#[derive(Debug)]
+pub struct FleetState {
+    path: PathBuf,
+    records: BTreeMap<String, HostRecord>,
+}
+
+impl FleetState {
+    /// Load a state file, or start an empty state if it does not exist yet.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::fleet::FleetState;
+    ///
+    /// let state = FleetState::open("run1.state").unwrap();
+    /// println!("{} hosts already completed", state.completed().count());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file exists but cannot be read or a
+    /// partial last line cannot be cut off, or [`Error::General`] if a line
+    /// other than the last is not a host record (the file is not a state
+    /// file).
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let mut records = BTreeMap::new();
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        // An interrupted write leaves a last line without its newline; cut
+        // it off so the next append starts a new line.
+        let complete = text.rfind('\n').map_or(0, |i| i + 1);
+        if complete < text.len() {
+            tracing::warn!(path = %path.display(), "Dropping partial last line of state file");
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(&path)?
+                .set_len(complete as u64)?;
+        }
+
+        let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+        for (i, line) in lines.iter().enumerate() {
+            match serde_json::from_str::<HostRecord>(line) {
+                Ok(record) => {
+                    records.insert(record.host.to_lowercase(), record);
+                }
+                // An interrupted write leaves at most the last line partial
+                Err(e) if i + 1 == lines.len() => {
+                    tracing::warn!(path = %path.display(), error = %e, "Ignoring truncated last line of state file");
+                }
+                Err(e) => {
+                    return Err(Error::General(format!(
+                        "{} line {} is not a fleet state record: {}",
+                        path.display(),
+                        i + 1,
+                        e
+                    )));
+                }
+            }
+        }
+
+        Ok(FleetState { path, records })
+    }
+
+    /// Path of the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Latest record of a host, if it finished in an earlier run.
+    pub fn get(&self, host: &str) -> Option<&HostRecord> {
+        self.records.get(&host.to_lowercase())
+    }
+
+    /// Latest record of every host, sorted by host.
+    pub fn records(&self) -> impl Iterator<Item = &HostRecord> {
+        self.records.values()
+    }
+
+    /// Records of the hosts completed successfully.
+    pub fn completed(&self) -> impl Iterator<Item = &HostRecord> {
+        self.records().filter(|record| record.completed())
+    }
+
+    /// Append a finished host to the file and the state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be written.
+    pub fn append(&mut self, record: HostRecord) -> Result<(), Error> {
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        self.records.insert(record.host.to_lowercase(), record);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(host: &str, error: Option<&str>) -> HostRecord {
+        let report =
+            AuditReport::from_json_versioned(include_str!("../../tests/fixtures/report.json"))
+                .unwrap();
+        let result = match error {
+            Some(message) => Err(ScanError::Local(message.into())),
+            None => Ok(report),
+        };
+        HostRecord::new(host, Utc::now(), &result)
+    }
+
+    #[test]
+    fn test_append_after_truncated_line() {
+        let path = std::env::temp_dir().join(format!(
+            "sysaudit-fleet-truncated-{}.state",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut state = FleetState::open(&path).unwrap();
+        state.append(record("hmi01", None)).unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"{\"host\":\"hmi02\",\"sta").unwrap();
+        drop(file);
+
+        let mut state = FleetState::open(&path).unwrap();
+        state
+            .append(record("hmi03", Some("access denied")))
+            .unwrap();
+
+        let state = FleetState::open(&path).unwrap();
+        assert!(state.get("hmi01").unwrap().completed());
+        assert!(state.get("hmi02").is_none());
+        assert!(!state.get("hmi03").unwrap().completed());
+        assert!(std::fs::read_to_string(&path).unwrap().ends_with('\n'));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_state_roundtrip_and_resume() {
+        let path =
+            std::env::temp_dir().join(format!("sysaudit-fleet-{}.state", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut state = FleetState::open(&path).unwrap();
+        assert_eq!(state.records().count(), 0);
+        state.append(record("hmi01", None)).unwrap();
+        state
+            .append(record("hmi02", Some("access denied")))
+            .unwrap();
+        // A crash in the middle of the next write
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"{\"host\":\"hmi03\",\"sta").unwrap();
+        drop(file);
+
+        let state = FleetState::open(&path).unwrap();
+        assert!(state.get("HMI01").unwrap().completed());
+        let failed = state.get("hmi02").unwrap();
+        assert!(!failed.completed());
+        assert_eq!(failed.error_kind, Some(ScanErrorKind::Local));
+        assert!(state.get("hmi03").is_none());
+        assert_eq!(state.completed().count(), 1);
+
+        std::fs::write(&path, "not a record\n{}\n").unwrap();
+        assert!(FleetState::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
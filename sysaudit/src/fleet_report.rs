@@ -0,0 +1,577 @@
+//! Multi-host report and cross-host rollups.
+//!
+//! [`FleetReport`] keeps every host's [`AuditReport`] (or the error that
+//! prevented one) together with rollups computed across the fleet:
+//! software name/version counts, hosts per OS build, an industrial
+//! host × product matrix and the patch currency distribution. It also
+//! flags hosts that look cloned from one image: sharing a machine SID, or
+//! installed at the same second.
+
+use crate::findings::STALE_PATCH_DAYS;
+use crate::metrics::TransferMetrics;
+use crate::report::AuditReport;
+use crate::scanner::ScanErrorKind;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One host in a fleet report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetHost {
+    /// Hostname or IP address as listed in the inventory
+    pub host: String,
+    /// Site from the inventory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site: Option<String>,
+    /// Tags from the inventory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The host's report, if the scan succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<AuditReport>,
+    /// Why the scan failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Class of the failure (auth, network, execution, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ScanErrorKind>,
+}
+
+/// Number of hosts with a given software name and version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoftwareCount {
+    /// Display name
+    pub name: String,
+    /// Version, if recorded
+    pub version: Option<String>,
+    /// Hosts with this name and version installed
+    pub hosts: usize,
+}
+
+/// Hosts running a given OS release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsBuildCount {
+    /// OS name
+    pub os_name: String,
+    /// OS version / build
+    pub os_version: String,
+    /// Hosts on this release
+    pub hosts: Vec<String>,
+}
+
+/// Industrial products per host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndustrialMatrix {
+    /// Column labels (`Vendor Product`), sorted
+    pub products: Vec<String>,
+    /// One row per scanned host with at least one product
+    pub rows: Vec<IndustrialRow>,
+}
+
+/// A host's row in the [`IndustrialMatrix`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndustrialRow {
+    /// Hostname
+    pub host: String,
+    /// Per product column: `Some(version)` when installed (empty version
+    /// when unknown), `None` otherwise
+    pub versions: Vec<Option<String>>,
+}
+
+/// Age of a host's most recent Windows update, relative to its scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchCurrency {
+    /// Updated within 30 days
+    Current,
+    /// Updated within [`STALE_PATCH_DAYS`]
+    Recent,
+    /// Last update older than [`STALE_PATCH_DAYS`], within 180 days
+    Stale,
+    /// Last update older than 180 days
+    Outdated,
+    /// No dated updates recorded
+    Unknown,
+}
+
+impl PatchCurrency {
+    /// Classify a report by its newest dated update.
+    pub fn of(report: &AuditReport) -> Self {
+        let Some(latest) = report.updates.iter().filter_map(|u| u.installed_on).max() else {
+            return PatchCurrency::Unknown;
+        };
+        match (report.timestamp.date_naive() - latest).num_days() {
+            days if days <= 30 => PatchCurrency::Current,
+            days if days <= STALE_PATCH_DAYS => PatchCurrency::Recent,
+            days if days <= 180 => PatchCurrency::Stale,
+            _ => PatchCurrency::Outdated,
+        }
+    }
+}
+
+impl std::fmt::Display for PatchCurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchCurrency::Current => write!(f, "0-30 days"),
+            PatchCurrency::Recent => write!(f, "31-{} days", STALE_PATCH_DAYS),
+            PatchCurrency::Stale => write!(f, "{}-180 days", STALE_PATCH_DAYS + 1),
+            PatchCurrency::Outdated => write!(f, "over 180 days"),
+            PatchCurrency::Unknown => write!(f, "no updates"),
+        }
+    }
+}
+
+/// Hosts in a [`PatchCurrency`] bucket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchCurrencyCount {
+    /// Bucket
+    pub currency: PatchCurrency,
+    /// Number of hosts
+    pub hosts: usize,
+}
+
+/// What a group of hosts has in common that suggests they were cloned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneEvidence {
+    /// Same machine SID; clones not generalized with Sysprep keep the
+    /// image's SID, which trips up domain trust and WSUS
+    SharedSid,
+    /// Windows installed at the same second, which a fresh install
+    /// practically never repeats
+    SameInstallTime,
+}
+
+impl std::fmt::Display for CloneEvidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloneEvidence::SharedSid => write!(f, "shared machine SID"),
+            CloneEvidence::SameInstallTime => write!(f, "identical install time"),
+        }
+    }
+}
+
+/// Hosts that look cloned from the same image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloneGroup {
+    /// What the hosts share
+    pub evidence: CloneEvidence,
+    /// The shared machine SID or install time (RFC 3339)
+    pub value: String,
+    /// Hosts in the group, at least two
+    pub hosts: Vec<String>,
+}
+
+/// Reports from many hosts with cross-host rollups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetReport {
+    /// Time the fleet report was assembled
+    pub generated: DateTime<Utc>,
+    /// Every host, scanned or failed, in inventory order
+    pub hosts: Vec<FleetHost>,
+    /// Software name/version counts, most widespread first
+    pub software: Vec<SoftwareCount>,
+    /// Hosts per OS release, largest group first
+    pub os_builds: Vec<OsBuildCount>,
+    /// Industrial products per host
+    pub industrial: IndustrialMatrix,
+    /// Hosts per patch currency bucket
+    pub patch_currency: Vec<PatchCurrencyCount>,
+    /// Hosts that look cloned, shared SIDs first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clones: Vec<CloneGroup>,
+    /// Network footprint summed over the remotely scanned hosts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer: Option<TransferMetrics>,
+}
+
+impl FleetReport {
+    /// Merge per-host results and compute the rollups.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::fleet_report::{FleetHost, FleetReport};
+    /// use sysaudit::AuditReport;
+    ///
+    /// let json = std::fs::read_to_string("fleet/hmi01.json").unwrap();
+    /// let report = FleetReport::new(vec![FleetHost {
+    ///     host: "hmi01".into(),
+    ///     site: None,
+    ///     tags: Vec::new(),
+    ///     report: Some(AuditReport::from_json_versioned(&json).unwrap()),
+    ///     error: None,
+    ///     error_kind: None,
+    /// }]);
+    /// println!("{} distinct packages", report.software.len());
+    /// ```
+    pub fn new(hosts: Vec<FleetHost>) -> Self {
+        let scanned: Vec<(&str, &AuditReport)> = hosts
+            .iter()
+            .filter_map(|h| h.report.as_ref().map(|r| (h.host.as_str(), r)))
+            .collect();
+
+        FleetReport {
+            generated: Utc::now(),
+            software: software_counts(&scanned),
+            os_builds: os_builds(&scanned),
+            industrial: industrial_matrix(&scanned),
+            patch_currency: patch_currency(&scanned),
+            clones: clones(&scanned),
+            transfer: scanned
+                .iter()
+                .filter_map(|(_, r)| r.metrics.as_ref()?.transfer)
+                .reduce(|total, host| total + host),
+            hosts,
+        }
+    }
+
+    /// Number of hosts that could not be scanned.
+    pub fn failed(&self) -> usize {
+        self.hosts.iter().filter(|h| h.report.is_none()).count()
+    }
+
+    /// Failed hosts per error kind, most common first.
+    pub fn failures_by_kind(&self) -> Vec<(ScanErrorKind, usize)> {
+        let mut counts: BTreeMap<ScanErrorKind, usize> = BTreeMap::new();
+        for kind in self.hosts.iter().filter_map(|h| h.error_kind) {
+            *counts.entry(kind).or_default() += 1;
+        }
+        let mut result: Vec<_> = counts.into_iter().collect();
+        result.sort_by_key(|&(_, count)| Reverse(count));
+        result
+    }
+}
+
+#[cfg(feature = "remote")]
+impl From<Vec<crate::fleet::HostResult>> for FleetReport {
+    fn from(results: Vec<crate::fleet::HostResult>) -> Self {
+        FleetReport::new(
+            results
+                .into_iter()
+                .map(|r| {
+                    let (report, error, error_kind) = match r.result {
+                        Ok(report) => (Some(report), None, None),
+                        Err(e) => (None, Some(e.to_string()), Some(e.kind())),
+                    };
+                    FleetHost {
+                        host: r.host,
+                        site: r.site,
+                        tags: r.tags,
+                        report,
+                        error,
+                        error_kind,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+fn software_counts(scanned: &[(&str, &AuditReport)]) -> Vec<SoftwareCount> {
+    let mut counts: BTreeMap<(&str, Option<&str>), BTreeSet<&str>> = BTreeMap::new();
+    for (host, report) in scanned {
+        for sw in &report.software {
+            counts
+                .entry((sw.name.as_str(), sw.version.as_deref()))
+                .or_default()
+                .insert(*host);
+        }
+    }
+
+    let mut result: Vec<SoftwareCount> = counts
+        .into_iter()
+        .map(|((name, version), hosts)| SoftwareCount {
+            name: name.to_string(),
+            version: version.map(String::from),
+            hosts: hosts.len(),
+        })
+        .collect();
+    result.sort_by_key(|count| Reverse(count.hosts));
+    result
+}
+
+fn os_builds(scanned: &[(&str, &AuditReport)]) -> Vec<OsBuildCount> {
+    let mut builds: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+    for (host, report) in scanned {
+        builds
+            .entry((
+                report.system.os_name.as_str(),
+                report.system.os_version.as_str(),
+            ))
+            .or_default()
+            .push(host.to_string());
+    }
+
+    let mut result: Vec<OsBuildCount> = builds
+        .into_iter()
+        .map(|((os_name, os_version), hosts)| OsBuildCount {
+            os_name: os_name.to_string(),
+            os_version: os_version.to_string(),
+            hosts,
+        })
+        .collect();
+    result.sort_by_key(|count| Reverse(count.hosts.len()));
+    result
+}
+
+fn industrial_matrix(scanned: &[(&str, &AuditReport)]) -> IndustrialMatrix {
+    let label = |vendor: &str, product: &str| format!("{} {}", vendor, product);
+    let products: Vec<String> = scanned
+        .iter()
+        .flat_map(|(_, r)| r.industrial.iter().map(|i| label(&i.vendor, &i.product)))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let rows = scanned
+        .iter()
+        .filter(|(_, r)| !r.industrial.is_empty())
+        .map(|(host, report)| IndustrialRow {
+            host: host.to_string(),
+            versions: products
+                .iter()
+                .map(|product| {
+                    report
+                        .industrial
+                        .iter()
+                        .find(|i| label(&i.vendor, &i.product) == *product)
+                        .map(|i| i.version.clone().unwrap_or_default())
+                })
+                .collect(),
+        })
+        .collect();
+
+    IndustrialMatrix { products, rows }
+}
+
+fn patch_currency(scanned: &[(&str, &AuditReport)]) -> Vec<PatchCurrencyCount> {
+    let mut counts: BTreeMap<PatchCurrency, usize> = BTreeMap::new();
+    for (_, report) in scanned {
+        *counts.entry(PatchCurrency::of(report)).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(currency, hosts)| PatchCurrencyCount { currency, hosts })
+        .collect()
+}
+
+fn clones(scanned: &[(&str, &AuditReport)]) -> Vec<CloneGroup> {
+    let mut groups: BTreeMap<(CloneEvidence, String), BTreeSet<&str>> = BTreeMap::new();
+    for (host, report) in scanned {
+        let Some(identity) = &report.identity else {
+            continue;
+        };
+        if let Some(sid) = &identity.machine_sid {
+            groups
+                .entry((CloneEvidence::SharedSid, sid.to_uppercase()))
+                .or_default()
+                .insert(*host);
+        }
+        if let Some(installed) = identity.installed_on {
+            groups
+                .entry((CloneEvidence::SameInstallTime, installed.to_rfc3339()))
+                .or_default()
+                .insert(*host);
+        }
+    }
+
+    let mut result: Vec<CloneGroup> = groups
+        .into_iter()
+        .filter(|(_, hosts)| hosts.len() > 1)
+        .map(|((evidence, value), hosts)| CloneGroup {
+            evidence,
+            value,
+            hosts: hosts.into_iter().map(String::from).collect(),
+        })
+        .collect();
+    result.sort_by(|a, b| {
+        a.evidence
+            .cmp(&b.evidence)
+            .then(b.hosts.len().cmp(&a.hosts.len()))
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HostIdentity;
+    use crate::WindowsUpdate;
+    use chrono::{NaiveDate, TimeZone};
+    use sysaudit_common::{IndustrialSoftwareDto, SoftwareDto, SystemInfoDto};
+
+    fn host(name: &str, os_version: &str, software: &[(&str, &str)], updated: u32) -> FleetHost {
+        let report = AuditReport {
+            software: software
+                .iter()
+                .map(|(n, v)| SoftwareDto {
+                    name: n.to_string(),
+                    version: Some(v.to_string()),
+                    vendor: None,
+                    install_date: None,
+                })
+                .collect(),
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "Rockwell".into(),
+                product: "FactoryTalk View".into(),
+                version: Some("13.0".into()),
+                install_path: None,
+            }],
+            updates: vec![WindowsUpdate {
+                hotfix_id: "KB5034441".into(),
+                description: None,
+                installed_on: NaiveDate::from_ymd_opt(2026, updated, 1),
+                installed_by: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2026, 6, 15, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Enterprise".into(),
+                os_version: os_version.into(),
+                host_name: name.into(),
+                cpu_info: String::new(),
+                cpu_physical_cores: None,
+                memory_total_bytes: 0,
+                memory_used_bytes: 0,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        };
+        FleetHost {
+            host: name.into(),
+            site: None,
+            tags: Vec::new(),
+            report: Some(report),
+            error: None,
+            error_kind: None,
+        }
+    }
+
+    #[test]
+    fn test_rollups() {
+        let fleet = FleetReport::new(vec![
+            host(
+                "hmi01",
+                "19045",
+                &[("7-Zip", "23.01"), ("Notepad++", "8.6")],
+                6,
+            ),
+            host("hmi02", "19045", &[("7-Zip", "23.01")], 4),
+            host("hist01", "17763", &[("7-Zip", "19.00")], 1),
+            FleetHost {
+                host: "hmi03".into(),
+                site: None,
+                tags: Vec::new(),
+                report: None,
+                error: Some("connection refused".into()),
+                error_kind: Some(ScanErrorKind::Network),
+            },
+        ]);
+
+        assert_eq!(fleet.failed(), 1);
+        assert_eq!(fleet.failures_by_kind(), vec![(ScanErrorKind::Network, 1)]);
+        assert_eq!(
+            fleet.software[0],
+            SoftwareCount {
+                name: "7-Zip".into(),
+                version: Some("23.01".into()),
+                hosts: 2,
+            }
+        );
+        assert_eq!(fleet.software.len(), 3);
+        assert_eq!(fleet.os_builds[0].hosts, vec!["hmi01", "hmi02"]);
+        assert_eq!(fleet.industrial.products, vec!["Rockwell FactoryTalk View"]);
+        assert_eq!(fleet.industrial.rows.len(), 3);
+        assert_eq!(
+            fleet.patch_currency,
+            vec![
+                PatchCurrencyCount {
+                    currency: PatchCurrency::Current,
+                    hosts: 1
+                },
+                PatchCurrencyCount {
+                    currency: PatchCurrency::Recent,
+                    hosts: 1
+                },
+                PatchCurrencyCount {
+                    currency: PatchCurrency::Stale,
+                    hosts: 1
+                },
+            ]
+        );
+        assert!(fleet.clones.is_empty());
+        assert!(fleet.transfer.is_none());
+    }
+
+    #[test]
+    fn test_transfer_total() {
+        let remote = |name: &str, sent: u64, received: u64| {
+            let mut host = host(name, "19045", &[], 6);
+            if let Some(report) = &mut host.report {
+                report.metrics = Some(crate::ScanMetrics::new().transfer(TransferMetrics {
+                    bytes_sent: sent,
+                    bytes_received: received,
+                    requests: 6,
+                    ..Default::default()
+                }));
+            }
+            host
+        };
+        let fleet = FleetReport::new(vec![
+            remote("hmi01", 40_000, 250_000),
+            remote("hmi02", 40_000, 150_000),
+            host("hist01", "17763", &[], 1),
+        ]);
+
+        let transfer = fleet.transfer.unwrap();
+        assert_eq!(transfer.bytes_sent, 80_000);
+        assert_eq!(transfer.requests, 12);
+        assert_eq!(transfer.total_bytes(), 480_000);
+    }
+
+    #[test]
+    fn test_clones() {
+        let cloned = |name: &str, sid: &str, installed: u32| {
+            let mut host = host(name, "19045", &[], 6);
+            if let Some(report) = &mut host.report {
+                report.identity = Some(HostIdentity {
+                    machine_sid: Some(sid.into()),
+                    installed_on: Utc.timestamp_opt(installed.into(), 0).single(),
+                    ..Default::default()
+                });
+            }
+            host
+        };
+        let image = "S-1-5-21-1004336348-1177238915-682003330";
+        let fleet = FleetReport::new(vec![
+            cloned("hmi01", image, 1_700_000_000),
+            cloned("hmi02", &image.to_lowercase(), 1_700_000_000),
+            cloned("hmi03", image, 1_700_000_600),
+            cloned("hist01", "S-1-5-21-1-2-3", 1_700_000_600),
+            host("eng01", "19045", &[], 6),
+        ]);
+
+        assert_eq!(
+            fleet.clones,
+            vec![
+                CloneGroup {
+                    evidence: CloneEvidence::SharedSid,
+                    value: image.into(),
+                    hosts: vec!["hmi01".into(), "hmi02".into(), "hmi03".into()],
+                },
+                CloneGroup {
+                    evidence: CloneEvidence::SameInstallTime,
+                    value: "2023-11-14T22:13:20+00:00".into(),
+                    hosts: vec!["hmi01".into(), "hmi02".into()],
+                },
+                CloneGroup {
+                    evidence: CloneEvidence::SameInstallTime,
+                    value: "2023-11-14T22:23:20+00:00".into(),
+                    hosts: vec!["hist01".into(), "hmi03".into()],
+                },
+            ]
+        );
+    }
+}
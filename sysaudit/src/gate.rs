@@ -0,0 +1,248 @@
+//! Pass/fail conditions for running sysaudit from pipelines.
+//!
+//! A [`FailCondition`] is checked against a finished report; every
+//! condition has its own process exit code, so a deployment script can
+//! tell "industrial software present" from "patches overdue" without
+//! parsing output:
+//!
+//! | Condition | Met when | Exit code |
+//! |:---|:---|:---|
+//! | `industrial` | Any industrial software was detected | 10 |
+//! | `missing-updates>N` | The newest update is more than N days old, or none is dated | 11 |
+//! | `eol-os` | The OS release is out of support | 12 |
+//! | `eol-software` | An installed product is past vendor end of life | 13 |
+//! | `score<N` | The audit score is below N | 14 |
+
+use crate::Error;
+use crate::lifecycle::{OsSupport, SoftwareLifecycle, SupportStatus};
+use crate::report::AuditReport;
+use crate::score::AuditScore;
+use std::fmt;
+use std::str::FromStr;
+
+/// A condition that fails an automated scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailCondition {
+    /// Industrial software is installed
+    Industrial,
+    /// No update installed within this many days
+    MissingUpdates(i64),
+    /// The OS release is end of life
+    EolOs,
+    /// Some installed software is end of life
+    EolSoftware,
+    /// The audit score is below this value
+    ScoreBelow(u8),
+}
+
+/// A condition met by a report, with what triggered it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The condition that was met
+    pub condition: FailCondition,
+    /// Human-readable reason
+    pub detail: String,
+}
+
+impl FailCondition {
+    /// Names accepted by [`parse`](str::parse), `N` standing for a number.
+    pub const NAMES: [&'static str; 5] = [
+        "industrial",
+        "missing-updates>N",
+        "eol-os",
+        "eol-software",
+        "score<N",
+    ];
+
+    /// Process exit code reported when the condition is met.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FailCondition::Industrial => 10,
+            FailCondition::MissingUpdates(_) => 11,
+            FailCondition::EolOs => 12,
+            FailCondition::EolSoftware => 13,
+            FailCondition::ScoreBelow(_) => 14,
+        }
+    }
+
+    /// Check the condition against a report.
+    ///
+    /// Returns the violation if the condition is met, `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::gate::FailCondition;
+    /// use sysaudit::AuditReport;
+    ///
+    /// # fn example(report: &AuditReport) -> Result<(), sysaudit::Error> {
+    /// let condition: FailCondition = "missing-updates>30".parse()?;
+    /// if let Some(violation) = condition.check(report) {
+    ///     eprintln!("{}", violation.detail);
+    ///     std::process::exit(condition.exit_code());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check(self, report: &AuditReport) -> Option<Violation> {
+        let today = report.timestamp.date_naive();
+        let detail = match self {
+            FailCondition::Industrial => {
+                let first = report.industrial.first()?;
+                format!(
+                    "{} industrial product(s) detected, e.g. {} {}",
+                    report.industrial.len(),
+                    first.vendor,
+                    first.product
+                )
+            }
+            FailCondition::MissingUpdates(max) => {
+                match report.updates.iter().filter_map(|u| u.installed_on).max() {
+                    Some(latest) => {
+                        let age = (today - latest).num_days();
+                        if age <= max {
+                            return None;
+                        }
+                        format!("latest update {} is {} days old", latest, age)
+                    }
+                    None => "no dated updates recorded".to_string(),
+                }
+            }
+            FailCondition::EolOs => {
+                let support =
+                    OsSupport::evaluate(&report.system.os_name, &report.system.os_version, today);
+                if !support.is_end_of_life() {
+                    return None;
+                }
+                format!("{}: {}", report.system.os_name, support)
+            }
+            FailCondition::EolSoftware => {
+                let lifecycle = SoftwareLifecycle::bundled();
+                let eol: Vec<&str> = report
+                    .software
+                    .iter()
+                    .filter(|sw| {
+                        lifecycle
+                            .lookup(&sw.name, sw.version.as_deref(), today)
+                            .is_some_and(|s| s.status == SupportStatus::EndOfLife)
+                    })
+                    .map(|sw| sw.name.as_str())
+                    .collect();
+                if eol.is_empty() {
+                    return None;
+                }
+                format!("past end of life: {}", eol.join(", "))
+            }
+            FailCondition::ScoreBelow(min) => {
+                // A report no domain could be scored for counts as 0.
+                let overall = match &report.score {
+                    Some(score) => score.overall,
+                    None => AuditScore::compute(report).overall,
+                }
+                .unwrap_or(0);
+                if overall >= min {
+                    return None;
+                }
+                format!("audit score {}% is below {}%", overall, min)
+            }
+        };
+        Some(Violation {
+            condition: self,
+            detail,
+        })
+    }
+}
+
+/// Conditions met by `report`, in the order given.
+pub fn evaluate(conditions: &[FailCondition], report: &AuditReport) -> Vec<Violation> {
+    conditions.iter().filter_map(|c| c.check(report)).collect()
+}
+
+impl FromStr for FailCondition {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let text = text.trim();
+        let invalid = || {
+            Error::General(format!(
+                "unknown fail condition '{}': use {}",
+                text,
+                FailCondition::NAMES.join(", ")
+            ))
+        };
+        if let Some(days) = text.strip_prefix("missing-updates>") {
+            return days
+                .trim()
+                .parse()
+                .map(FailCondition::MissingUpdates)
+                .map_err(|_| invalid());
+        }
+        if let Some(min) = text.strip_prefix("score<") {
+            return min
+                .trim()
+                .parse()
+                .map(FailCondition::ScoreBelow)
+                .map_err(|_| invalid());
+        }
+        match text.to_ascii_lowercase().as_str() {
+            "industrial" => Ok(FailCondition::Industrial),
+            "eol-os" => Ok(FailCondition::EolOs),
+            "eol-software" => Ok(FailCondition::EolSoftware),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for FailCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailCondition::Industrial => write!(f, "industrial"),
+            FailCondition::MissingUpdates(days) => write!(f, "missing-updates>{}", days),
+            FailCondition::EolOs => write!(f, "eol-os"),
+            FailCondition::EolSoftware => write!(f, "eol-software"),
+            FailCondition::ScoreBelow(min) => write!(f, "score<{}", min),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_condition() {
+        let parsed: FailCondition = "missing-updates>30".parse().unwrap();
+        assert_eq!(parsed, FailCondition::MissingUpdates(30));
+        assert_eq!(parsed.to_string(), "missing-updates>30");
+        assert_eq!(
+            "score<70".parse::<FailCondition>().unwrap(),
+            FailCondition::ScoreBelow(70)
+        );
+        assert_eq!(
+            "EOL-OS".parse::<FailCondition>().unwrap(),
+            FailCondition::EolOs
+        );
+        assert!("missing-updates>soon".parse::<FailCondition>().is_err());
+        assert!("reboot-pending".parse::<FailCondition>().is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let report =
+            AuditReport::from_json_versioned(include_str!("../tests/fixtures/report.json"))
+                .unwrap();
+        let conditions = [
+            FailCondition::EolOs,
+            FailCondition::MissingUpdates(200),
+            FailCondition::MissingUpdates(30),
+            FailCondition::Industrial,
+        ];
+
+        let violations = evaluate(&conditions, &report);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].condition, FailCondition::MissingUpdates(30));
+        assert_eq!(violations[0].condition.exit_code(), 11);
+        assert!(violations[0].detail.contains("143 days"));
+        assert_eq!(violations[1].condition.exit_code(), 10);
+    }
+}
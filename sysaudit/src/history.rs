@@ -0,0 +1,302 @@
+//! Historical trends from the SQLite inventory database.
+//!
+//! Every scan exported with [`SqliteExporter`] or recorded with
+//! [`HistoryDb::record`] leaves one `history` row, keyed by the report's
+//! asset ID (the host name for reports without one) and scan time, so a
+//! renamed or reimaged host keeps its trend. [`HistoryDb::trend`] reads one
+//! metric over time ("patch age got worse after the outage") and
+//! [`HistoryDb::prune`] drops old scans from every table.
+
+use crate::output::{SCAN_TABLES, SqliteExporter};
+use crate::{AuditReport, Error};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A per-scan value tracked in the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryMetric {
+    /// Installed programs
+    SoftwareCount,
+    /// Detected industrial products
+    IndustrialCount,
+    /// Installed updates
+    UpdateCount,
+    /// Days between the newest dated update and the scan
+    PatchAge,
+    /// Overall audit score (0-100)
+    Score,
+}
+
+impl HistoryMetric {
+    /// Every metric, in display order.
+    pub const ALL: [HistoryMetric; 5] = [
+        HistoryMetric::SoftwareCount,
+        HistoryMetric::IndustrialCount,
+        HistoryMetric::UpdateCount,
+        HistoryMetric::PatchAge,
+        HistoryMetric::Score,
+    ];
+
+    /// Parse a metric name (`software_count`, `industrial_count`,
+    /// `update_count`, `patch_age`, `score`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown metric.
+    pub fn parse(name: &str) -> Result<Self, Error> {
+        Self::ALL
+            .into_iter()
+            .find(|metric| metric.to_string() == name.trim().to_lowercase())
+            .ok_or_else(|| {
+                Error::General(format!(
+                    "unknown history metric '{}' (expected software_count, industrial_count, \
+                     update_count, patch_age or score)",
+                    name
+                ))
+            })
+    }
+
+    fn column(self) -> &'static str {
+        match self {
+            HistoryMetric::SoftwareCount => "software_count",
+            HistoryMetric::IndustrialCount => "industrial_count",
+            HistoryMetric::UpdateCount => "update_count",
+            HistoryMetric::PatchAge => "patch_age_days",
+            HistoryMetric::Score => "score",
+        }
+    }
+}
+
+impl std::fmt::Display for HistoryMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryMetric::SoftwareCount => write!(f, "software_count"),
+            HistoryMetric::IndustrialCount => write!(f, "industrial_count"),
+            HistoryMetric::UpdateCount => write!(f, "update_count"),
+            HistoryMetric::PatchAge => write!(f, "patch_age"),
+            HistoryMetric::Score => write!(f, "score"),
+        }
+    }
+}
+
+/// One scan's value of a metric.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    /// Time of the scan
+    pub scanned_at: DateTime<Utc>,
+    /// Asset the scan belongs to
+    pub asset_id: String,
+    /// Host name at the time of the scan
+    pub host_name: String,
+    /// Value, `None` when the scan could not measure it (no dated
+    /// updates, no score)
+    pub value: Option<i64>,
+}
+
+/// SQLite database holding the scan history.
+pub struct HistoryDb {
+    conn: Connection,
+}
+
+impl HistoryDb {
+    /// Open a history database, creating it if needed. Databases written
+    /// by [`SqliteExporter`] are history databases.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Sqlite`] if the database cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let conn = Connection::open(path)?;
+        SqliteExporter::create_schema(&conn)?;
+        Ok(HistoryDb { conn })
+    }
+
+    /// Record a scan, replacing an earlier recording of the same scan.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Sqlite`] if the database cannot be written.
+    pub fn record(&mut self, report: &AuditReport) -> Result<(), Error> {
+        SqliteExporter::write_report(&mut self.conn, report)
+    }
+
+    /// A metric over time for a host, oldest scan first. `host` matches an
+    /// asset ID or a host name (case-insensitive); a host name reused by
+    /// several assets returns all of their scans.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::history::{HistoryDb, HistoryMetric};
+    ///
+    /// # fn example() -> Result<(), sysaudit::Error> {
+    /// let db = HistoryDb::open("inventory.db")?;
+    /// for point in db.trend("HMI-01", HistoryMetric::PatchAge)? {
+    ///     println!("{} {:?}", point.scanned_at, point.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Sqlite`] if the database cannot be read.
+    pub fn trend(&self, host: &str, metric: HistoryMetric) -> Result<Vec<TrendPoint>, Error> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT scanned_at, asset_id, host_name, {} FROM history \
+             WHERE asset_id = ?1 OR host_name = ?1 COLLATE NOCASE ORDER BY scanned_at",
+            metric.column()
+        ))?;
+        let rows = stmt
+            .query_map(params![host], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<(String, String, String, Option<i64>)>, _>>()?;
+
+        rows.into_iter()
+            .map(|(scanned_at, asset_id, host_name, value)| {
+                Ok(TrendPoint {
+                    scanned_at: DateTime::parse_from_rfc3339(&scanned_at)
+                        .map_err(|e| Error::DateParse(format!("{}: {}", scanned_at, e)))?
+                        .with_timezone(&Utc),
+                    asset_id,
+                    host_name,
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    /// Delete scans older than `before` from every table, keeping each
+    /// asset's latest scan however old it is. Returns the number of scans
+    /// deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Sqlite`] if the database cannot be written.
+    pub fn prune(&mut self, before: DateTime<Utc>) -> Result<usize, Error> {
+        let tx = self.conn.transaction()?;
+        let expired = {
+            let mut stmt = tx.prepare(
+                "SELECT asset_id, host_name, scanned_at FROM history AS h \
+                 WHERE scanned_at < ?1 \
+                 AND scanned_at < (SELECT MAX(scanned_at) FROM history WHERE asset_id = h.asset_id)",
+            )?;
+            stmt.query_map(params![before.to_rfc3339()], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<Vec<(String, String, String)>, _>>()?
+        };
+
+        for (asset_id, host_name, scanned_at) in &expired {
+            for table in SCAN_TABLES {
+                tx.execute(
+                    &format!(
+                        "DELETE FROM {} WHERE host_name = ?1 AND scanned_at = ?2",
+                        table
+                    ),
+                    params![host_name, scanned_at],
+                )?;
+            }
+            tx.execute(
+                "DELETE FROM history WHERE asset_id = ?1 AND scanned_at = ?2",
+                params![asset_id, scanned_at],
+            )?;
+        }
+        tx.commit()?;
+
+        tracing::debug!(count = expired.len(), before = %before, "History pruned");
+        Ok(expired.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WindowsUpdate;
+    use crate::report::test_system;
+    use chrono::{NaiveDate, TimeZone};
+    use sysaudit_common::{SoftwareDto, SystemInfoDto};
+
+    fn sample_report(host: &str, day: u32, software: usize) -> AuditReport {
+        AuditReport {
+            software: (0..software)
+                .map(|i| SoftwareDto {
+                    name: format!("App {}", i),
+                    version: None,
+                    vendor: None,
+                    install_date: None,
+                })
+                .collect(),
+            updates: vec![WindowsUpdate {
+                hotfix_id: "KB5034441".into(),
+                description: None,
+                installed_on: NaiveDate::from_ymd_opt(2024, 1, 1),
+                installed_by: None,
+            }],
+            asset_id: Some("v1-0001".into()),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                host_name: host.into(),
+                ..test_system()
+            })
+        }
+    }
+
+    #[test]
+    fn test_metric_parse() {
+        assert_eq!(
+            HistoryMetric::parse("patch_age").unwrap(),
+            HistoryMetric::PatchAge
+        );
+        assert_eq!(
+            HistoryMetric::parse("Software_Count").unwrap(),
+            HistoryMetric::SoftwareCount
+        );
+        assert!(HistoryMetric::parse("uptime").is_err());
+    }
+
+    #[test]
+    fn test_trend_follows_asset_across_renames() {
+        let dir = std::env::temp_dir().join(format!("sysaudit-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut db = HistoryDb::open(dir.join("history.db")).unwrap();
+        db.record(&sample_report("HMI-01", 5, 10)).unwrap();
+        db.record(&sample_report("HMI-01", 10, 12)).unwrap();
+        db.record(&sample_report("HMI-01-NEW", 20, 15)).unwrap();
+
+        let software = db.trend("hmi-01", HistoryMetric::SoftwareCount).unwrap();
+        assert_eq!(software.len(), 2);
+        let by_asset = db.trend("v1-0001", HistoryMetric::SoftwareCount).unwrap();
+        let values: Vec<Option<i64>> = by_asset.iter().map(|p| p.value).collect();
+        assert_eq!(values, [Some(10), Some(12), Some(15)]);
+        assert_eq!(by_asset[2].host_name, "HMI-01-NEW");
+
+        let patch_age = db.trend("v1-0001", HistoryMetric::PatchAge).unwrap();
+        assert_eq!(patch_age[0].value, Some(4));
+        assert_eq!(
+            db.trend("v1-0001", HistoryMetric::Score).unwrap()[0].value,
+            None
+        );
+
+        // The latest scan survives however old it is
+        let pruned = db
+            .prune(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+            .unwrap();
+        assert_eq!(pruned, 2);
+        let left = db.trend("v1-0001", HistoryMetric::SoftwareCount).unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].value, Some(15));
+
+        drop(db);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,11 +1,26 @@
 //! Industrial software detection module.
 //!
-//! Provides read-only detection of industrial automation software.
+//! Provides read-only detection of industrial automation software, of the
+//! device connectivity tooling (serial device servers, fieldbus gateways)
+//! that links a host to field devices, and of the data middleware (OPC
+//! routers, tunnellers, MQTT brokers, Node-RED) that integrates them.
 
-use crate::Error;
+#[cfg(any(all(windows, feature = "local"), test))]
+use crate::Software;
+use crate::WindowsService;
+#[cfg(all(windows, feature = "local"))]
+use crate::software::ERROR_FILE_NOT_FOUND;
+#[cfg(all(windows, feature = "local"))]
+use crate::{Error, cancel};
+#[cfg(all(windows, feature = "local"))]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+#[cfg(all(windows, feature = "local"))]
 use windows_registry::{CURRENT_USER, Key, LOCAL_MACHINE};
+#[cfg(all(windows, feature = "local"))]
+use windows_result::HRESULT;
 
 /// Industrial software vendor.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,10 +37,56 @@ pub enum Vendor {
     Siemens,
     /// Schneider Electric
     SchneiderElectric,
+    /// Phoenix Contact (PC Worx, PLCnext Engineer)
+    PhoenixContact,
+    /// WAGO (e!COCKPIT, WAGO CODESYS)
+    Wago,
+    /// Festo (Festo Automation Suite)
+    Festo,
+    /// Moxa (NPort tools, drivers and virtual COM ports)
+    Moxa,
+    /// HMS Networks (Anybus, IXXAT)
+    Hms,
+    /// inray OPC Router
+    Inray,
+    /// Skkynet Cogent DataHub
+    Skkynet,
+    /// PTC Kepware (KEPServerEX, LinkMaster)
+    Kepware,
+    /// HiveMQ MQTT broker
+    HiveMq,
+    /// Eclipse Mosquitto MQTT broker
+    Mosquitto,
+    /// Node-RED
+    NodeRed,
     /// Other vendor
     Other(String),
 }
 
+/// Kind of product a [`Vendor`] is detected for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndustrialCategory {
+    /// SCADA, HMI, engineering and video supervision software
+    Automation,
+    /// Serial device servers, fieldbus gateways and their drivers
+    DeviceConnectivity,
+    /// OPC routers and tunnellers, MQTT brokers and flow engines
+    Middleware,
+}
+
+/// Former name of [`IndustrialCategory`].
+pub type VendorCategory = IndustrialCategory;
+
+impl std::fmt::Display for IndustrialCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndustrialCategory::Automation => write!(f, "Automation"),
+            IndustrialCategory::DeviceConnectivity => write!(f, "Device Connectivity"),
+            IndustrialCategory::Middleware => write!(f, "Middleware"),
+        }
+    }
+}
+
 impl std::fmt::Display for Vendor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -35,6 +96,17 @@ impl std::fmt::Display for Vendor {
             Vendor::Rockwell => write!(f, "Rockwell"),
             Vendor::Siemens => write!(f, "Siemens"),
             Vendor::SchneiderElectric => write!(f, "Schneider Electric"),
+            Vendor::PhoenixContact => write!(f, "Phoenix Contact"),
+            Vendor::Wago => write!(f, "WAGO"),
+            Vendor::Festo => write!(f, "Festo"),
+            Vendor::Moxa => write!(f, "Moxa"),
+            Vendor::Hms => write!(f, "HMS"),
+            Vendor::Inray => write!(f, "inray"),
+            Vendor::Skkynet => write!(f, "Skkynet"),
+            Vendor::Kepware => write!(f, "Kepware"),
+            Vendor::HiveMq => write!(f, "HiveMQ"),
+            Vendor::Mosquitto => write!(f, "Mosquitto"),
+            Vendor::NodeRed => write!(f, "Node-RED"),
             Vendor::Other(name) => write!(f, "{}", name),
         }
     }
@@ -51,11 +123,169 @@ pub struct IndustrialSoftware {
     pub version: Option<String>,
     /// Installation path
     pub install_path: Option<PathBuf>,
+    /// Whether the product runs as a service, once correlated with the
+    /// installed services (see [`IndustrialSoftware::correlate`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_state: Option<RuntimeState>,
+}
+
+/// Whether an industrial product is active on the host, judged by the
+/// Windows services whose binary lives in its install folder.
+///
+/// Installed-but-stopped SCADA components and active nodes call for
+/// different decommissioning plans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuntimeState {
+    /// At least one of its services is running
+    Running,
+    /// It has services, none of them running
+    Stopped,
+    /// No service runs from its install folder
+    NotInstalledAsService,
+}
+
+impl std::fmt::Display for RuntimeState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuntimeState::Running => write!(f, "Running"),
+            RuntimeState::Stopped => write!(f, "Stopped"),
+            RuntimeState::NotInstalledAsService => write!(f, "Not a service"),
+        }
+    }
+}
+
+impl RuntimeState {
+    /// State of the product installed at `install_path`, from the services
+    /// whose binary lives under it. `None` when `services` is empty (not
+    /// collected).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use sysaudit::{RuntimeState, WindowsService};
+    ///
+    /// let service = WindowsService {
+    ///     name: "CtApiSvc".into(),
+    ///     display_name: None,
+    ///     state: Some("Running".into()),
+    ///     start_mode: Some("Auto".into()),
+    ///     account: None,
+    ///     path: Some(r#""C:\Program Files\AVEVA\Citect SCADA\Bin\CtApiSvc.exe" -s"#.into()),
+    /// };
+    /// let install = Path::new(r"C:\Program Files\AVEVA\Citect SCADA");
+    /// assert_eq!(
+    ///     RuntimeState::of(install, &[service]),
+    ///     Some(RuntimeState::Running)
+    /// );
+    /// ```
+    pub fn of(install_path: &Path, services: &[WindowsService]) -> Option<Self> {
+        if services.is_empty() {
+            return None;
+        }
+        let folder = path_key(install_path).replace('/', "\\");
+        let mut states = services
+            .iter()
+            .filter(|service| {
+                service.path.as_deref().is_some_and(|command| {
+                    let binary = command.trim_start().trim_start_matches('"');
+                    let binary = binary.replace('/', "\\").to_lowercase();
+                    binary
+                        .strip_prefix(&folder)
+                        .is_some_and(|rest| rest.starts_with('\\'))
+                })
+            })
+            .map(|service| service.state.as_deref() == Some("Running"))
+            .peekable();
+        Some(if states.peek().is_none() {
+            RuntimeState::NotInstalledAsService
+        } else if states.any(|running| running) {
+            RuntimeState::Running
+        } else {
+            RuntimeState::Stopped
+        })
+    }
+}
+
+impl IndustrialSoftware {
+    /// Set [`runtime_state`](Self::runtime_state) from the installed
+    /// services; left `None` without an install path or services.
+    pub fn correlate(&mut self, services: &[WindowsService]) {
+        self.runtime_state = self
+            .install_path
+            .as_deref()
+            .and_then(|path| RuntimeState::of(path, services));
+    }
 }
 
 /// Scanner for industrial software.
 pub struct IndustrialScanner {
     vendors: Vec<Vendor>,
+    cancel: Option<CancellationToken>,
+}
+
+impl Vendor {
+    /// Short names of the known vendors, as accepted by [`Vendor::parse`].
+    pub const NAMES: [&'static str; 17] = [
+        "citect",
+        "digifort",
+        "abb",
+        "rockwell",
+        "siemens",
+        "schneider",
+        "phoenix",
+        "wago",
+        "festo",
+        "moxa",
+        "hms",
+        "inray",
+        "skkynet",
+        "kepware",
+        "hivemq",
+        "mosquitto",
+        "node-red",
+    ];
+
+    /// Vendor for a short name, case-insensitive (`allen-bradley` is an
+    /// alias of `rockwell`, `phoenix-contact` of `phoenix`, `anybus` and
+    /// `ixxat` of `hms`, `opc-router` of `inray`, `datahub` of `skkynet`,
+    /// `nodered` of `node-red`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "citect" => Some(Vendor::Citect),
+            "digifort" => Some(Vendor::Digifort),
+            "abb" => Some(Vendor::ABB),
+            "rockwell" | "allen-bradley" => Some(Vendor::Rockwell),
+            "siemens" => Some(Vendor::Siemens),
+            "schneider" => Some(Vendor::SchneiderElectric),
+            "phoenix" | "phoenix-contact" => Some(Vendor::PhoenixContact),
+            "wago" => Some(Vendor::Wago),
+            "festo" => Some(Vendor::Festo),
+            "moxa" => Some(Vendor::Moxa),
+            "hms" | "anybus" | "ixxat" => Some(Vendor::Hms),
+            "inray" | "opc-router" => Some(Vendor::Inray),
+            "skkynet" | "datahub" => Some(Vendor::Skkynet),
+            "kepware" => Some(Vendor::Kepware),
+            "hivemq" => Some(Vendor::HiveMq),
+            "mosquitto" => Some(Vendor::Mosquitto),
+            "node-red" | "nodered" => Some(Vendor::NodeRed),
+            _ => None,
+        }
+    }
+
+    /// Kind of product detected for this vendor.
+    pub fn category(&self) -> IndustrialCategory {
+        match self {
+            Vendor::Moxa | Vendor::Hms => IndustrialCategory::DeviceConnectivity,
+            Vendor::Inray
+            | Vendor::Skkynet
+            | Vendor::Kepware
+            | Vendor::HiveMq
+            | Vendor::Mosquitto
+            | Vendor::NodeRed => IndustrialCategory::Middleware,
+            _ => IndustrialCategory::Automation,
+        }
+    }
 }
 
 impl Default for IndustrialScanner {
@@ -75,15 +305,60 @@ impl IndustrialScanner {
                 Vendor::Rockwell,
                 Vendor::Siemens,
                 Vendor::SchneiderElectric,
+                Vendor::PhoenixContact,
+                Vendor::Wago,
+                Vendor::Festo,
+                Vendor::Moxa,
+                Vendor::Hms,
+                Vendor::Inray,
+                Vendor::Skkynet,
+                Vendor::Kepware,
+                Vendor::HiveMq,
+                Vendor::Mosquitto,
+                Vendor::NodeRed,
             ],
+            cancel: None,
         }
     }
 
+    /// Create scanner for the vendors of one category.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::{IndustrialScanner, IndustrialCategory};
+    ///
+    /// let scanner = IndustrialScanner::category(IndustrialCategory::DeviceConnectivity);
+    /// ```
+    pub fn category(category: IndustrialCategory) -> Self {
+        let mut scanner = Self::all_vendors();
+        scanner.vendors.retain(|v| v.category() == category);
+        scanner
+    }
+
     /// Create scanner for specific vendors.
     pub fn with_vendors(vendors: Vec<Vendor>) -> Self {
-        IndustrialScanner { vendors }
+        IndustrialScanner {
+            vendors,
+            cancel: None,
+        }
+    }
+
+    /// Stop between registry keys once `cancel` is triggered.
+    pub fn cancel_token(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
     }
 
+    /// Vendors this scanner classifies.
+    #[cfg(all(windows, feature = "local"))]
+    pub(crate) fn vendors(&self) -> &[Vendor] {
+        &self.vendors
+    }
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl IndustrialScanner {
     /// Scan for industrial software (READ-ONLY).
     ///
     /// # Example
@@ -100,41 +375,120 @@ impl IndustrialScanner {
     ///
     /// # Errors
     ///
-    /// Returns [`Error`] if the Windows registry cannot be opened.
+    /// Returns [`Error`] if the Windows registry cannot be opened, or
+    /// [`Error::Cancelled`] if the scan was cancelled.
     pub fn scan(&self) -> Result<Vec<IndustrialSoftware>, Error> {
+        self.scan_with_warnings().map(|(industrial, _)| industrial)
+    }
+
+    /// [`scan`](Self::scan), also describing registry keys that exist but
+    /// could not be read.
+    #[tracing::instrument(skip(self), fields(items = tracing::field::Empty))]
+    pub(crate) fn scan_with_warnings(
+        &self,
+    ) -> Result<(Vec<IndustrialSoftware>, Vec<String>), Error> {
         tracing::info!(
             "Scanning for industrial software (vendors: {:?})",
             self.vendors
         );
+        let mut warnings = Vec::new();
+        let mut result = self.scan_vendor_keys(&mut warnings)?;
+
+        // Also scan standard Uninstall keys for industrial patterns
+        result.extend(self.scan_uninstall_keys(&mut warnings));
+        cancel::check(self.cancel.as_ref())?;
+
+        Ok((finish(result), warnings))
+    }
+
+    /// Like [`scan`](Self::scan), but classifies an already collected
+    /// software list instead of walking the Uninstall keys again.
+    ///
+    /// Only the vendor-specific registry keys are read, so a full audit
+    /// enumerates the Uninstall hives once. Pass the result of a
+    /// [`SoftwareScanner`](crate::SoftwareScanner) that includes the
+    /// 32-bit view to find everything [`scan`](Self::scan) would; per-user
+    /// entries in the list are classified as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{IndustrialScanner, SoftwareScanner};
+    ///
+    /// let software = SoftwareScanner::new().scan().unwrap();
+    /// let industrial = IndustrialScanner::all_vendors().classify_from(&software).unwrap();
+    /// println!("{} of {} items are industrial", industrial.len(), software.len());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`] if the scan was cancelled.
+    pub fn classify_from(&self, software: &[Software]) -> Result<Vec<IndustrialSoftware>, Error> {
+        self.classify_from_with_warnings(software)
+            .map(|(industrial, _)| industrial)
+    }
+
+    /// [`classify_from`](Self::classify_from), also describing vendor
+    /// registry keys that exist but could not be read.
+    #[tracing::instrument(skip_all, fields(items = tracing::field::Empty))]
+    pub(crate) fn classify_from_with_warnings(
+        &self,
+        software: &[Software],
+    ) -> Result<(Vec<IndustrialSoftware>, Vec<String>), Error> {
+        tracing::info!(
+            "Classifying {} software entries (vendors: {:?})",
+            software.len(),
+            self.vendors
+        );
+        let mut warnings = Vec::new();
+        let mut result = self.scan_vendor_keys(&mut warnings)?;
+        result.extend(classify_software(software, &self.vendors));
+        cancel::check(self.cancel.as_ref())?;
+
+        Ok((finish(result), warnings))
+    }
+
+    /// Products found under the vendors' own registry keys.
+    fn scan_vendor_keys(
+        &self,
+        warnings: &mut Vec<String>,
+    ) -> Result<Vec<IndustrialSoftware>, Error> {
         let mut result = Vec::new();
 
         for vendor in &self.vendors {
+            cancel::check(self.cancel.as_ref())?;
             match vendor {
-                Vendor::Citect => result.extend(self.scan_citect()),
-                Vendor::Digifort => result.extend(self.scan_digifort()),
+                Vendor::Citect => result.extend(self.scan_citect(warnings)),
+                Vendor::Digifort => result.extend(self.scan_digifort(warnings)),
                 Vendor::ABB => result.extend(self.scan_abb()),
-                Vendor::Rockwell => result.extend(self.scan_rockwell()),
+                Vendor::Rockwell => result.extend(self.scan_rockwell(warnings)),
                 Vendor::Siemens => result.extend(self.scan_siemens()),
-                Vendor::SchneiderElectric => result.extend(self.scan_schneider()),
-                Vendor::Other(_) => {}
+                Vendor::SchneiderElectric => result.extend(self.scan_schneider(warnings)),
+                Vendor::Moxa => result.extend(self.scan_moxa(warnings)),
+                Vendor::NodeRed => result.extend(self.scan_node_red(warnings)),
+                // The rest register only in the Uninstall keys
+                Vendor::PhoenixContact
+                | Vendor::Wago
+                | Vendor::Festo
+                | Vendor::Hms
+                | Vendor::Inray
+                | Vendor::Skkynet
+                | Vendor::Kepware
+                | Vendor::HiveMq
+                | Vendor::Mosquitto
+                | Vendor::Other(_) => {}
             }
         }
 
-        // Also scan standard Uninstall keys for industrial patterns
-        result.extend(self.scan_uninstall_keys());
-
-        // Remove duplicates by product name
-        result.sort_by(|a, b| a.product.cmp(&b.product));
-        result.dedup_by(|a, b| a.product == b.product);
-
         Ok(result)
     }
 
-    fn scan_citect(&self) -> Vec<IndustrialSoftware> {
+    fn scan_citect(&self, warnings: &mut Vec<String>) -> Vec<IndustrialSoftware> {
         let mut result = Vec::new();
 
         // Check Citect SCADA Installs
-        if let Ok(key) = LOCAL_MACHINE.open(r"SOFTWARE\WOW6432Node\Citect\SCADA Installs") {
+        let path = r"SOFTWARE\WOW6432Node\Citect\SCADA Installs";
+        if let Some(key) = open_key(LOCAL_MACHINE, path, warnings) {
             for version in key.keys().into_iter().flatten() {
                 if let Ok(subkey) = key.open(&version) {
                     let install_path = subkey.get_string("DefaultINIPath").ok().map(PathBuf::from);
@@ -144,6 +498,7 @@ impl IndustrialScanner {
                         product: format!("AVEVA Plant SCADA {}", version),
                         version: Some(version),
                         install_path,
+                        runtime_state: None,
                     });
                 }
             }
@@ -152,19 +507,20 @@ impl IndustrialScanner {
         result
     }
 
-    fn scan_digifort(&self) -> Vec<IndustrialSoftware> {
+    fn scan_digifort(&self, warnings: &mut Vec<String>) -> Vec<IndustrialSoftware> {
         let mut result = Vec::new();
 
         for (root, name) in [
             (&LOCAL_MACHINE, r"SOFTWARE\Digifort"),
             (&CURRENT_USER, r"Software\Digifort"),
         ] {
-            if root.open(name).is_ok() {
+            if open_key(root, name, warnings).is_some() {
                 result.push(IndustrialSoftware {
                     vendor: Vendor::Digifort,
                     product: "Digifort VMS".to_string(),
                     version: None,
                     install_path: None,
+                    runtime_state: None,
                 });
                 break;
             }
@@ -178,17 +534,19 @@ impl IndustrialScanner {
         Vec::new()
     }
 
-    fn scan_rockwell(&self) -> Vec<IndustrialSoftware> {
+    fn scan_rockwell(&self, warnings: &mut Vec<String>) -> Vec<IndustrialSoftware> {
         let mut result = Vec::new();
 
         // Check Rockwell Software registry
-        if let Ok(key) = LOCAL_MACHINE.open(r"SOFTWARE\Wow6432Node\Rockwell Software") {
+        let path = r"SOFTWARE\Wow6432Node\Rockwell Software";
+        if let Some(key) = open_key(LOCAL_MACHINE, path, warnings) {
             for subkey_name in key.keys().into_iter().flatten() {
                 result.push(IndustrialSoftware {
                     vendor: Vendor::Rockwell,
                     product: subkey_name.clone(),
                     version: None,
                     install_path: None,
+                    runtime_state: None,
                 });
             }
         }
@@ -201,16 +559,17 @@ impl IndustrialScanner {
         Vec::new()
     }
 
-    fn scan_schneider(&self) -> Vec<IndustrialSoftware> {
+    fn scan_schneider(&self, warnings: &mut Vec<String>) -> Vec<IndustrialSoftware> {
         let mut result = Vec::new();
 
-        if let Ok(key) = CURRENT_USER.open(r"Software\Schneider Electric") {
+        if let Some(key) = open_key(CURRENT_USER, r"Software\Schneider Electric", warnings) {
             for subkey_name in key.keys().into_iter().flatten() {
                 result.push(IndustrialSoftware {
                     vendor: Vendor::SchneiderElectric,
                     product: subkey_name.clone(),
                     version: None,
                     install_path: None,
+                    runtime_state: None,
                 });
             }
         }
@@ -218,7 +577,51 @@ impl IndustrialScanner {
         result
     }
 
-    fn scan_uninstall_keys(&self) -> Vec<IndustrialSoftware> {
+    fn scan_moxa(&self, warnings: &mut Vec<String>) -> Vec<IndustrialSoftware> {
+        // COM ports mapped to NPort device servers by the Real COM driver
+        let path = r"HARDWARE\DEVICEMAP\SERIALCOMM";
+        let Some(key) = open_key(LOCAL_MACHINE, path, warnings) else {
+            return Vec::new();
+        };
+        let Some(values) = readable(path, key.values(), warnings) else {
+            return Vec::new();
+        };
+        values
+            .filter_map(|(device, _)| {
+                let port = key.get_string(&device).ok()?;
+                virtual_com_port(&device, &port)
+            })
+            .collect()
+    }
+
+    fn scan_node_red(&self, warnings: &mut Vec<String>) -> Vec<IndustrialSoftware> {
+        // Node-RED is installed with `npm install -g`, into each user's
+        // roaming npm prefix rather than the Uninstall keys
+        let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        let users = format!(r"{}\Users", drive);
+        let profiles = match std::fs::read_dir(&users) {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!(path = %users, error = %e, "Could not list user profiles");
+                    warnings.push(format!("could not list {}: {}", users, e));
+                }
+                return Vec::new();
+            }
+        };
+        profiles
+            .flatten()
+            .filter_map(|profile| {
+                let path = profile
+                    .path()
+                    .join(r"AppData\Roaming\npm\node_modules\node-red");
+                let package = std::fs::read_to_string(path.join("package.json")).ok()?;
+                node_red_install(&package, path)
+            })
+            .collect()
+    }
+
+    fn scan_uninstall_keys(&self, warnings: &mut Vec<String>) -> Vec<IndustrialSoftware> {
         let mut result = Vec::new();
 
         let paths = [
@@ -227,16 +630,16 @@ impl IndustrialScanner {
         ];
 
         for path in paths {
-            if let Ok(key) = LOCAL_MACHINE.open(path) {
-                for subkey_name in key.keys().into_iter().flatten() {
-                    if let Ok(subkey) = key.open(&subkey_name) {
-                        if let Ok(name) = subkey.get_string("DisplayName") {
-                            if let Some(sw) = self.match_industrial(&name, &subkey) {
-                                result.push(sw);
-                            }
-                        }
+            if let Some(key) = open_key(LOCAL_MACHINE, path, warnings) {
+                let subkey_names: Vec<String> = key.keys().into_iter().flatten().collect();
+                result.par_extend(subkey_names.par_iter().filter_map(|subkey_name| {
+                    if cancel::is_cancelled(self.cancel.as_ref()) {
+                        return None;
                     }
-                }
+                    let subkey = key.open(subkey_name).ok()?;
+                    let name = subkey.get_string("DisplayName").ok()?;
+                    self.match_industrial(&name, &subkey)
+                }));
             }
         }
 
@@ -255,8 +658,184 @@ impl IndustrialScanner {
     }
 }
 
+/// The key at `path` under `root`, or `None` when it is absent; a key that
+/// exists but cannot be read is described in `warnings`.
+#[cfg(all(windows, feature = "local"))]
+fn open_key(root: &Key, path: &str, warnings: &mut Vec<String>) -> Option<Key> {
+    readable(path, root.open(path), warnings)
+}
+
+/// The result of a read of `path`, or `None` when the key is absent or the
+/// read failed, in which case the failure is described in `warnings`.
+#[cfg(all(windows, feature = "local"))]
+fn readable<T>(
+    path: &str,
+    read: windows_result::Result<T>,
+    warnings: &mut Vec<String>,
+) -> Option<T> {
+    match read {
+        Ok(value) => Some(value),
+        Err(e) if e.code() == HRESULT::from_win32(ERROR_FILE_NOT_FOUND) => None,
+        Err(e) => {
+            tracing::warn!(path, error = %e, "Could not read industrial software key");
+            warnings.push(format!("could not read {}: {}", path, e));
+            None
+        }
+    }
+}
+
+/// Merge duplicate detections, recording the count on the span.
+#[cfg(all(windows, feature = "local"))]
+fn finish(result: Vec<IndustrialSoftware>) -> Vec<IndustrialSoftware> {
+    let result = merge_industrial(result);
+    tracing::Span::current().record("items", result.len());
+    result
+}
+
+/// Merge detections of the same install, sorted by product and version.
+///
+/// Two entries are one install when vendor and normalized product name
+/// match and their versions and install paths agree wherever both are
+/// known; the merged entry keeps what either detection found. Side-by-side
+/// versions of a product stay separate.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn merge_industrial(found: Vec<IndustrialSoftware>) -> Vec<IndustrialSoftware> {
+    let mut merged: Vec<IndustrialSoftware> = Vec::with_capacity(found.len());
+    for sw in found {
+        match merged.iter_mut().find(|m| same_install(m, &sw)) {
+            Some(existing) => {
+                if existing.version.is_none() {
+                    existing.version = sw.version;
+                }
+                if existing.install_path.is_none() {
+                    existing.install_path = sw.install_path;
+                }
+            }
+            None => merged.push(sw),
+        }
+    }
+    merged.sort_by(|a, b| {
+        a.product
+            .cmp(&b.product)
+            .then_with(|| a.version.cmp(&b.version))
+    });
+    merged
+}
+
+#[cfg(any(all(windows, feature = "local"), test))]
+fn same_install(a: &IndustrialSoftware, b: &IndustrialSoftware) -> bool {
+    fn agree<T>(a: Option<T>, b: Option<T>, eq: impl Fn(T, T) -> bool) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => eq(a, b),
+            _ => true,
+        }
+    }
+    a.vendor == b.vendor
+        && product_key(&a.product, a.version.as_deref())
+            == product_key(&b.product, b.version.as_deref())
+        && agree(a.version.as_deref(), b.version.as_deref(), |a, b| {
+            a.eq_ignore_ascii_case(b)
+        })
+        && agree(
+            a.install_path.as_deref(),
+            b.install_path.as_deref(),
+            |a, b| path_key(a) == path_key(b),
+        )
+}
+
+/// Product name without case, punctuation, architecture tags or the
+/// version itself, so "Studio 5000 (x64) 33.00" matches "Studio 5000".
+#[cfg(any(all(windows, feature = "local"), test))]
+fn product_key(product: &str, version: Option<&str>) -> String {
+    const ARCH: &[&str] = &["x64", "x86", "64", "32", "bit", "64bit", "32bit"];
+    let version = version.map(str::to_lowercase);
+    product
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '.')
+        .filter(|token| {
+            !token.is_empty() && !ARCH.contains(token) && Some(*token) != version.as_deref()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy()
+        .trim_end_matches(['\\', '/'])
+        .to_lowercase()
+}
+
+/// Device name prefixes of the Moxa NPort Real COM driver in
+/// `HKLM\HARDWARE\DEVICEMAP\SERIALCOMM`.
+#[cfg(any(all(windows, feature = "local"), test))]
+const MOXA_REAL_COM_DEVICES: &[&str] = &[r"\device\npdrv", r"\device\npser"];
+
+/// Entry for a serial port whose device is an NPort virtual COM port.
+///
+/// The remote payload's `Get-IndustrialSoftware` matches the same device
+/// names; keep the two in sync.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn virtual_com_port(device: &str, port: &str) -> Option<IndustrialSoftware> {
+    let device_lower = device.to_lowercase();
+    if !MOXA_REAL_COM_DEVICES
+        .iter()
+        .any(|prefix| device_lower.starts_with(prefix))
+    {
+        return None;
+    }
+    Some(IndustrialSoftware {
+        vendor: Vendor::Moxa,
+        product: format!("NPort Real COM {} ({})", port.trim(), device),
+        version: None,
+        install_path: None,
+        runtime_state: None,
+    })
+}
+
+/// Entry for a Node-RED install from its `package.json`.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn node_red_install(package_json: &str, path: PathBuf) -> Option<IndustrialSoftware> {
+    let package: serde_json::Value = serde_json::from_str(package_json).ok()?;
+    if package.get("name")?.as_str()? != "node-red" {
+        return None;
+    }
+    Some(IndustrialSoftware {
+        vendor: Vendor::NodeRed,
+        product: "Node-RED".to_string(),
+        version: package
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        install_path: Some(path),
+        runtime_state: None,
+    })
+}
+
+/// Industrial products among installed software entries.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn classify_software(
+    software: &[Software],
+    vendors: &[Vendor],
+) -> Vec<IndustrialSoftware> {
+    software
+        .iter()
+        .filter_map(|sw| {
+            classify_industrial(
+                &sw.name,
+                sw.version.clone(),
+                sw.install_location.clone(),
+                vendors,
+            )
+        })
+        .collect()
+}
+
 /// Pure classification logic for industrial software (fully testable).
-fn classify_industrial(
+///
+/// The remote payload's `Get-IndustrialVendor` mirrors these patterns; keep
+/// the two in sync.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn classify_industrial(
     name: &str,
     version: Option<String>,
     install_path: Option<PathBuf>,
@@ -311,6 +890,79 @@ fn classify_industrial(
         } else {
             None
         }
+    } else if name_lower.contains("pc worx") || name_lower.contains("plcnext engineer") {
+        if vendors.contains(&Vendor::PhoenixContact) {
+            Some(Vendor::PhoenixContact)
+        } else {
+            None
+        }
+    } else if name_lower.contains("e!cockpit")
+        || (name_lower.contains("wago") && name_lower.contains("codesys"))
+    {
+        if vendors.contains(&Vendor::Wago) {
+            Some(Vendor::Wago)
+        } else {
+            None
+        }
+    } else if name_lower.contains("festo automation suite") {
+        if vendors.contains(&Vendor::Festo) {
+            Some(Vendor::Festo)
+        } else {
+            None
+        }
+    } else if name_lower.contains("moxa") || name_lower.starts_with("nport") {
+        if vendors.contains(&Vendor::Moxa) {
+            Some(Vendor::Moxa)
+        } else {
+            None
+        }
+    } else if name_lower.contains("anybus") || name_lower.contains("ixxat") {
+        if vendors.contains(&Vendor::Hms) {
+            Some(Vendor::Hms)
+        } else {
+            None
+        }
+    } else if name_lower.contains("opc router") {
+        if vendors.contains(&Vendor::Inray) {
+            Some(Vendor::Inray)
+        } else {
+            None
+        }
+    } else if name_lower.contains("datahub")
+        && (name_lower.contains("cogent") || name_lower.contains("skkynet"))
+    {
+        if vendors.contains(&Vendor::Skkynet) {
+            Some(Vendor::Skkynet)
+        } else {
+            None
+        }
+    } else if name_lower.contains("kepware")
+        || name_lower.contains("kepserverex")
+        || name_lower.contains("linkmaster")
+    {
+        if vendors.contains(&Vendor::Kepware) {
+            Some(Vendor::Kepware)
+        } else {
+            None
+        }
+    } else if name_lower.contains("hivemq") {
+        if vendors.contains(&Vendor::HiveMq) {
+            Some(Vendor::HiveMq)
+        } else {
+            None
+        }
+    } else if name_lower.contains("mosquitto") {
+        if vendors.contains(&Vendor::Mosquitto) {
+            Some(Vendor::Mosquitto)
+        } else {
+            None
+        }
+    } else if name_lower.contains("node-red") {
+        if vendors.contains(&Vendor::NodeRed) {
+            Some(Vendor::NodeRed)
+        } else {
+            None
+        }
     } else {
         None
     }?;
@@ -320,6 +972,7 @@ fn classify_industrial(
         product: name.to_string(),
         version,
         install_path,
+        runtime_state: None,
     })
 }
 
@@ -335,6 +988,17 @@ mod tests {
             Vendor::Rockwell,
             Vendor::Siemens,
             Vendor::SchneiderElectric,
+            Vendor::PhoenixContact,
+            Vendor::Wago,
+            Vendor::Festo,
+            Vendor::Moxa,
+            Vendor::Hms,
+            Vendor::Inray,
+            Vendor::Skkynet,
+            Vendor::Kepware,
+            Vendor::HiveMq,
+            Vendor::Mosquitto,
+            Vendor::NodeRed,
         ]
     }
 
@@ -346,10 +1010,22 @@ mod tests {
         assert_eq!(Vendor::Other("Custom".into()).to_string(), "Custom");
     }
 
+    #[test]
+    fn test_vendor_parse() {
+        assert_eq!(Vendor::parse("Allen-Bradley"), Some(Vendor::Rockwell));
+        assert_eq!(Vendor::parse("schneider"), Some(Vendor::SchneiderElectric));
+        assert_eq!(Vendor::parse("honeywell"), None);
+        let parsed: Vec<Vendor> = Vendor::NAMES
+            .iter()
+            .filter_map(|n| Vendor::parse(n))
+            .collect();
+        assert_eq!(parsed, all_vendors());
+    }
+
     #[test]
     fn test_all_vendors_constructor() {
         let scanner = IndustrialScanner::all_vendors();
-        assert_eq!(scanner.vendors.len(), 6);
+        assert_eq!(scanner.vendors.len(), 17);
     }
 
     #[test]
@@ -424,6 +1100,82 @@ mod tests {
         assert_eq!(result.unwrap().vendor, Vendor::SchneiderElectric);
     }
 
+    #[test]
+    fn test_classify_phoenix_wago_festo() {
+        let v = all_vendors();
+        for (name, vendor) in [
+            ("PC WORX 6.30", Vendor::PhoenixContact),
+            ("PLCnext Engineer 2024.0", Vendor::PhoenixContact),
+            ("WAGO e!COCKPIT", Vendor::Wago),
+            ("CODESYS V3.5 SP19 WAGO", Vendor::Wago),
+            ("Festo Automation Suite", Vendor::Festo),
+        ] {
+            let result = classify_industrial(name, None, None, &v);
+            assert_eq!(result.map(|sw| sw.vendor), Some(vendor), "{}", name);
+        }
+        // Plain CODESYS and WAGO drivers belong to no vendor
+        assert!(classify_industrial("CODESYS V3.5 SP19", None, None, &v).is_none());
+        assert!(classify_industrial("WAGO USB Driver", None, None, &v).is_none());
+    }
+
+    #[test]
+    fn test_classify_device_connectivity() {
+        let v = all_vendors();
+        for (name, vendor) in [
+            ("NPort Administration Suite", Vendor::Moxa),
+            ("MOXA NPort Windows Driver Manager", Vendor::Moxa),
+            ("Anybus Configuration Manager", Vendor::Hms),
+            ("IXXAT VCI 4", Vendor::Hms),
+        ] {
+            let result = classify_industrial(name, None, None, &v);
+            assert_eq!(result.map(|sw| sw.vendor), Some(vendor), "{}", name);
+        }
+        assert_eq!(
+            Vendor::Hms.category(),
+            IndustrialCategory::DeviceConnectivity
+        );
+        assert_eq!(Vendor::Siemens.category(), IndustrialCategory::Automation);
+        assert_eq!(
+            IndustrialScanner::category(IndustrialCategory::DeviceConnectivity).vendors,
+            vec![Vendor::Moxa, Vendor::Hms]
+        );
+    }
+
+    #[test]
+    fn test_classify_middleware() {
+        let v = all_vendors();
+        for (name, vendor) in [
+            ("OPC Router 5", Vendor::Inray),
+            ("Cogent DataHub", Vendor::Skkynet),
+            ("Kepware LinkMaster 3.0", Vendor::Kepware),
+            ("HiveMQ Edge", Vendor::HiveMq),
+            ("Eclipse Mosquitto", Vendor::Mosquitto),
+            ("Node-RED", Vendor::NodeRed),
+        ] {
+            assert_eq!(vendor.category(), IndustrialCategory::Middleware);
+            let result = classify_industrial(name, None, None, &v);
+            assert_eq!(result.map(|sw| sw.vendor), Some(vendor), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_node_red_install() {
+        let path = PathBuf::from(r"C:\Users\ops\AppData\Roaming\npm\node_modules\node-red");
+        let sw = node_red_install(r#"{"name":"node-red","version":"3.1.9"}"#, path).unwrap();
+        assert_eq!(sw.vendor, Vendor::NodeRed);
+        assert_eq!(sw.version.as_deref(), Some("3.1.9"));
+        assert!(node_red_install(r#"{"name":"express"}"#, PathBuf::new()).is_none());
+        assert!(node_red_install("not json", PathBuf::new()).is_none());
+    }
+
+    #[test]
+    fn test_virtual_com_port() {
+        let port = virtual_com_port(r"\Device\Npdrv0", "COM5").unwrap();
+        assert_eq!(port.vendor, Vendor::Moxa);
+        assert_eq!(port.product, r"NPort Real COM COM5 (\Device\Npdrv0)");
+        assert!(virtual_com_port(r"\Device\Serial0", "COM1").is_none());
+    }
+
     #[test]
     fn test_classify_unrecognized_no_match() {
         let v = all_vendors();
@@ -439,6 +1191,125 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_classify_software_list() {
+        let software: Vec<Software> = [
+            ("Studio 5000 Logix Designer", "33.00"),
+            ("7-Zip 23.01", "23.01"),
+            ("SIMATIC WinCC", "8.0"),
+        ]
+        .into_iter()
+        .map(|(name, version)| Software {
+            name: name.into(),
+            version: Some(version.into()),
+            publisher: None,
+            install_date: None,
+            install_date_source: crate::InstallDateSource::Unknown,
+            install_location: None,
+            source: crate::RegistrySource::LocalMachine32,
+            support_status: None,
+            eol: None,
+        })
+        .collect();
+
+        let found = classify_software(&software, &all_vendors());
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].vendor, Vendor::Rockwell);
+        assert_eq!(found[0].version.as_deref(), Some("33.00"));
+        assert_eq!(found[1].vendor, Vendor::Siemens);
+
+        assert!(classify_software(&software, &[Vendor::Citect]).is_empty());
+    }
+
+    #[test]
+    fn test_merge_industrial() {
+        let entry = |product: &str, version: Option<&str>, path: Option<&str>| IndustrialSoftware {
+            vendor: Vendor::Citect,
+            product: product.into(),
+            version: version.map(Into::into),
+            install_path: path.map(PathBuf::from),
+            runtime_state: None,
+        };
+        let merged = merge_industrial(vec![
+            // Side-by-side versions stay separate
+            entry("Citect SCADA 2018", Some("8.10"), None),
+            entry(
+                "Citect SCADA 2018 (x64)",
+                Some("8.20"),
+                Some(r"C:\Program Files (x86)\Citect"),
+            ),
+            // Same install seen twice: the evidence is combined
+            entry("Citect SCADA 2018", None, Some(r"C:\Program Files\Citect\")),
+            entry(
+                "citect scada 2018 8.10",
+                Some("8.10"),
+                Some(r"c:\program files\citect"),
+            ),
+            // Same version in another folder is another install
+            entry("Citect SCADA 2018", Some("8.20"), Some(r"D:\Citect")),
+        ]);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].product, "Citect SCADA 2018");
+        assert_eq!(merged[0].version.as_deref(), Some("8.10"));
+        assert_eq!(
+            merged[0].install_path.as_deref(),
+            Some(Path::new(r"C:\Program Files\Citect\"))
+        );
+        assert_eq!(merged[1].version.as_deref(), Some("8.20"));
+        assert_eq!(
+            merged[1].install_path.as_deref(),
+            Some(Path::new(r"D:\Citect"))
+        );
+        assert_eq!(merged[2].product, "Citect SCADA 2018 (x64)");
+        assert_eq!(merged[2].version.as_deref(), Some("8.20"));
+    }
+
+    #[test]
+    fn test_runtime_state() {
+        let service = |state: &str, path: &str| WindowsService {
+            name: "svc".into(),
+            display_name: None,
+            state: Some(state.into()),
+            start_mode: None,
+            account: None,
+            path: Some(path.into()),
+        };
+        let services = [
+            service(
+                "Stopped",
+                r#""C:\Program Files\Citect\Bin\Citect32.exe" -service"#,
+            ),
+            service(
+                "Running",
+                r"C:\Program Files\Kepware\KEPServerEX 6\server_runtime.exe",
+            ),
+            service(
+                "Stopped",
+                r"C:\Program Files\Kepware\KEPServerEX 6\server_config.exe",
+            ),
+            service("Running", r"C:\Program Files\Citect2\Bin\Other.exe"),
+        ];
+        let state = |path: &str| RuntimeState::of(Path::new(path), &services);
+        assert_eq!(
+            state(r"c:\program files\citect\"),
+            Some(RuntimeState::Stopped)
+        );
+        assert_eq!(
+            state(r"C:\Program Files\Kepware\KEPServerEX 6"),
+            Some(RuntimeState::Running)
+        );
+        assert_eq!(
+            state(r"C:\Program Files\Siemens"),
+            Some(RuntimeState::NotInstalledAsService)
+        );
+        assert_eq!(RuntimeState::of(Path::new(r"C:\Citect"), &[]), None);
+
+        let mut sw = classify_industrial("Citect SCADA", None, None, &all_vendors()).unwrap();
+        sw.correlate(&services);
+        assert_eq!(sw.runtime_state, None);
+    }
+
     #[test]
     fn test_classify_preserves_metadata() {
         let v = all_vendors();
@@ -450,4 +1321,31 @@ mod tests {
         assert_eq!(sw.install_path, Some(path));
         assert_eq!(sw.product, "Citect SCADA");
     }
+
+    #[cfg(all(windows, feature = "local"))]
+    #[test]
+    fn test_unreadable_key_warns() {
+        let error = |code| {
+            Err::<(), _>(windows_result::Error::from_hresult(HRESULT::from_win32(
+                code,
+            )))
+        };
+        let mut warnings = Vec::new();
+        assert_eq!(
+            readable(
+                r"SOFTWARE\Digifort",
+                error(ERROR_FILE_NOT_FOUND),
+                &mut warnings
+            ),
+            None
+        );
+        assert!(warnings.is_empty());
+
+        // ERROR_ACCESS_DENIED
+        let path = r"SOFTWARE\Wow6432Node\Rockwell Software";
+        assert_eq!(readable(path, error(5), &mut warnings), None);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(path));
+        assert_eq!(readable(path, Ok(()), &mut warnings), Some(()));
+    }
 }
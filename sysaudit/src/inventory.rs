@@ -0,0 +1,425 @@
+//! Fleet inventory files.
+//!
+//! An inventory lists the hosts to audit with their connection settings,
+//! a reference to a named credential and a site/tags grouping that is
+//! carried into the fleet results. YAML inventories may declare the
+//! credentials; passwords are read from environment variables or the
+//! Windows Credential Manager, never from the file.
+//!
+//! ```yaml
+//! credentials:
+//!   plant-admin:
+//!     username: PLANT\audit
+//!     password_env: PLANT_AUDIT_PASSWORD
+//!   historian:
+//!     credential_manager: sysaudit/historian
+//! hosts:
+//!   - host: hmi01.plant.local
+//!     site: north
+//!     tags: [line1, hmi]
+//!     credential: plant-admin
+//!   - host: 10.20.0.15
+//!     transport: winrm-https
+//!     port: 5986
+//!   - host: hist01
+//!     credential: historian
+//!   - hmi02
+//! ```
+//!
+//! CSV inventories use the columns `host,port,transport,credential,username,site,tags`
+//! (only `host` is required; tags are separated by `;`).
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::fleet::FleetTarget;
+use crate::{CredentialSource, Error};
+
+/// How a host is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Transport {
+    /// WinRM over HTTP
+    #[serde(rename = "winrm", alias = "http")]
+    WinRm,
+    /// WinRM over HTTPS
+    #[serde(rename = "winrm-https", alias = "https")]
+    WinRmHttps,
+    /// DCOM/WMI (see [`WmiRemoteScanner`](crate::WmiRemoteScanner)), as
+    /// the current Windows account; a host may not set a credential
+    #[serde(rename = "wmi", alias = "dcom")]
+    Wmi,
+}
+
+impl Transport {
+    /// Parse a transport name (`winrm`, `winrm-https`, `wmi`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown name.
+    pub fn parse(name: &str) -> Result<Self, Error> {
+        match name.trim().to_lowercase().as_str() {
+            "winrm" | "http" => Ok(Transport::WinRm),
+            "winrm-https" | "https" => Ok(Transport::WinRmHttps),
+            "wmi" | "dcom" => Ok(Transport::Wmi),
+            other => Err(Error::General(format!(
+                "unsupported transport '{}' (use winrm, winrm-https or wmi)",
+                other
+            ))),
+        }
+    }
+}
+
+/// A named credential. The password comes from the Credential Manager
+/// entry `credential_manager`, from `password_env`, or from
+/// `SYSAUDIT_CRED_<NAME>` when neither is set.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CredentialRef {
+    /// Account name (`DOMAIN\user` or `user`)
+    pub username: Option<String>,
+    /// Environment variable holding the password
+    pub password_env: Option<String>,
+    /// Generic Credential Manager target holding the account
+    pub credential_manager: Option<String>,
+}
+
+/// One inventory entry.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "HostEntry")]
+pub struct InventoryHost {
+    /// Hostname or IP address
+    pub host: String,
+    /// Port override
+    pub port: Option<u16>,
+    /// Transport override
+    pub transport: Option<Transport>,
+    /// Name of a credential to use
+    pub credential: Option<String>,
+    /// Username, when no credential is referenced
+    pub username: Option<String>,
+    /// Site (plant, building, ...)
+    pub site: Option<String>,
+    /// Free-form grouping tags (line, cell, role, ...)
+    pub tags: Vec<String>,
+}
+
+/// A bare hostname or a full entry.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HostEntry {
+    Host(String),
+    Detailed {
+        host: String,
+        port: Option<u16>,
+        transport: Option<Transport>,
+        credential: Option<String>,
+        username: Option<String>,
+        site: Option<String>,
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+}
+
+impl From<HostEntry> for InventoryHost {
+    fn from(entry: HostEntry) -> Self {
+        match entry {
+            HostEntry::Host(host) => InventoryHost {
+                host,
+                port: None,
+                transport: None,
+                credential: None,
+                username: None,
+                site: None,
+                tags: Vec::new(),
+            },
+            HostEntry::Detailed {
+                host,
+                port,
+                transport,
+                credential,
+                username,
+                site,
+                tags,
+            } => InventoryHost {
+                host,
+                port,
+                transport,
+                credential,
+                username,
+                site,
+                tags,
+            },
+        }
+    }
+}
+
+/// A YAML document: either a plain host list or hosts plus credentials.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Document {
+    List(Vec<InventoryHost>),
+    Full {
+        #[serde(default)]
+        credentials: HashMap<String, CredentialRef>,
+        hosts: Vec<InventoryHost>,
+    },
+}
+
+/// CSV row; every column but `host` is optional.
+#[derive(Deserialize)]
+struct CsvRow {
+    host: String,
+    port: Option<u16>,
+    transport: Option<String>,
+    credential: Option<String>,
+    username: Option<String>,
+    site: Option<String>,
+    tags: Option<String>,
+}
+
+/// Hosts to audit and the credentials they reference.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    /// Hosts in file order
+    pub hosts: Vec<InventoryHost>,
+    /// Named credentials
+    pub credentials: HashMap<String, CredentialRef>,
+}
+
+impl Inventory {
+    /// Load an inventory, choosing CSV or YAML by file extension.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::inventory::Inventory;
+    ///
+    /// let inventory = Inventory::load("plant.yaml").unwrap();
+    /// let targets = inventory.targets().unwrap();
+    /// println!("{} hosts", targets.len());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, or a parse error
+    /// if it is not a valid inventory.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::from_csv(&text),
+            _ => Self::from_yaml(&text),
+        }
+    }
+
+    /// Parse a YAML inventory (a host list, or `credentials` + `hosts`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Yaml`] if the document is not a valid inventory,
+    /// or [`Error::General`] if a `wmi` host sets a credential.
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        let inventory = match serde_yaml::from_str(yaml)? {
+            Document::List(hosts) => Inventory {
+                hosts,
+                credentials: HashMap::new(),
+            },
+            Document::Full { credentials, hosts } => Inventory { hosts, credentials },
+        };
+        inventory.validate()?;
+        Ok(inventory)
+    }
+
+    /// Parse a CSV inventory with a header row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Csv`] for malformed rows, or [`Error::General`]
+    /// for an unknown transport or a `wmi` host with a credential.
+    pub fn from_csv(csv: &str) -> Result<Self, Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(csv.as_bytes());
+
+        let mut hosts = Vec::new();
+        for row in reader.deserialize() {
+            let row: CsvRow = row?;
+            hosts.push(InventoryHost {
+                host: row.host,
+                port: row.port,
+                transport: row.transport.as_deref().map(Transport::parse).transpose()?,
+                credential: row.credential,
+                username: row.username,
+                site: row.site,
+                tags: row
+                    .tags
+                    .map(|t| {
+                        t.split(';')
+                            .map(str::trim)
+                            .filter(|t| !t.is_empty())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            });
+        }
+
+        let inventory = Inventory {
+            hosts,
+            credentials: HashMap::new(),
+        };
+        inventory.validate()?;
+        Ok(inventory)
+    }
+
+    /// Reject credentials on `wmi` hosts: the DCOM scanner connects as the
+    /// current Windows account and would silently ignore them.
+    fn validate(&self) -> Result<(), Error> {
+        for host in &self.hosts {
+            if host.transport == Some(Transport::Wmi)
+                && (host.credential.is_some() || host.username.is_some())
+            {
+                return Err(Error::General(format!(
+                    "{}: the wmi transport scans as the current Windows account; \
+                     remove its credential and username",
+                    host.host
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve every host into a fleet target, reading referenced
+    /// passwords from the environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if a referenced credential's password
+    /// variable is not set or its Credential Manager entry does not exist.
+    pub fn targets(&self) -> Result<Vec<FleetTarget>, Error> {
+        self.hosts
+            .iter()
+            .map(|host| {
+                let mut target = FleetTarget::new(&host.host);
+                target.port = host.port;
+                target.transport = host.transport;
+                target.username = host.username.clone();
+                target.site = host.site.clone();
+                target.tags = host.tags.clone();
+
+                if let Some(name) = &host.credential {
+                    let credential = self.credentials.get(name).cloned().unwrap_or_default();
+                    let source = match credential.credential_manager {
+                        Some(entry) => CredentialSource::CredentialManager(entry),
+                        None => CredentialSource::Env(
+                            credential
+                                .password_env
+                                .unwrap_or_else(|| default_password_env(name)),
+                        ),
+                    };
+                    let resolved = source
+                        .resolve(credential.username.as_deref())
+                        .map_err(|e| {
+                            Error::General(format!(
+                                "credential '{}' for {}: {}",
+                                name, host.host, e
+                            ))
+                        })?;
+                    target.username = resolved.username.or(target.username);
+                    target.password = Some(resolved.password);
+                }
+
+                Ok(target)
+            })
+            .collect()
+    }
+}
+
+/// `SYSAUDIT_CRED_<NAME>`, with the name upper-cased and non-alphanumerics
+/// replaced by `_`.
+fn default_password_env(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("SYSAUDIT_CRED_{}", name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_inventory() {
+        let yaml = r"
+credentials:
+  plant-admin:
+    username: PLANT\audit
+    password_env: SYSAUDIT_TEST_INVENTORY_PW
+hosts:
+  - host: hmi01
+    site: north
+    tags: [line1, hmi]
+    credential: plant-admin
+  - host: 10.20.0.15
+    transport: winrm-https
+    port: 5986
+  - hist01
+  - host: plc01
+    transport: dcom
+";
+        let inventory = Inventory::from_yaml(yaml).unwrap();
+        assert_eq!(inventory.hosts.len(), 4);
+        assert_eq!(inventory.hosts[0].tags, vec!["line1", "hmi"]);
+        assert_eq!(inventory.hosts[1].transport, Some(Transport::WinRmHttps));
+        assert_eq!(inventory.hosts[2].host, "hist01");
+        assert_eq!(inventory.hosts[3].transport, Some(Transport::Wmi));
+
+        assert!(inventory.targets().is_err());
+
+        // SAFETY: no other test reads or writes this variable.
+        unsafe { std::env::set_var("SYSAUDIT_TEST_INVENTORY_PW", "secret") };
+        let targets = inventory.targets().unwrap();
+        assert_eq!(targets[0].username.as_deref(), Some("PLANT\\audit"));
+        assert!(targets[0].password.is_some());
+        assert_eq!(targets[0].site.as_deref(), Some("north"));
+        assert_eq!(targets[1].port, Some(5986));
+        assert!(targets[2].password.is_none());
+
+        assert!(
+            Inventory::from_yaml("- host: plc01\n  transport: wmi\n  credential: plant-admin\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_csv_inventory() {
+        let csv = "host,port,transport,credential,username,site,tags\n\
+                   hmi01,,wmi,,,north,line1; hmi\n\
+                   10.20.0.15,5986,winrm-https,,audit,south,\n";
+        let inventory = Inventory::from_csv(csv).unwrap();
+        assert_eq!(inventory.hosts.len(), 2);
+        assert_eq!(inventory.hosts[0].transport, Some(Transport::Wmi));
+        assert_eq!(inventory.hosts[0].tags, vec!["line1", "hmi"]);
+        assert_eq!(inventory.hosts[1].port, Some(5986));
+        assert!(inventory.hosts[1].tags.is_empty());
+
+        assert!(Inventory::from_csv("host,transport\nhmi01,telnet\n").is_err());
+        let err = Inventory::from_csv("host,transport,username\nhmi01,wmi,audit\n").unwrap_err();
+        assert!(err.to_string().contains("hmi01: the wmi transport"));
+    }
+
+    #[test]
+    fn test_default_password_env() {
+        assert_eq!(
+            default_password_env("plant-admin"),
+            "SYSAUDIT_CRED_PLANT_ADMIN"
+        );
+    }
+}
@@ -7,6 +7,15 @@
 //! - Installed software (from Windows Registry)
 //! - Industrial software detection (Citect, ABB, Rockwell, etc.)
 //! - Windows Updates (via WMI)
+//! - Windows services (via WMI)
+//! - Security settings: BitLocker status (via WMI), UAC, SMBv1, firewall,
+//!   Remote Desktop, password policy and Defender exclusions
+//! - Windows Update agent health: wuauserv, BITS, WSUS policy and the SCCM
+//!   client
+//! - Windows edition, license channels and activation status (via WMI)
+//! - Event log sizes, retention and Windows Event Forwarding
+//! - Visual C++, OPC Core Components and .NET redistributables, classified
+//!   from the installed software
 //!
 //! ## Example
 //!
@@ -24,38 +33,120 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Other platforms
+//!
+//! The live collectors and [`LocalScanner`] need Windows. On Linux and
+//! macOS the crate still builds with the report types, output formats,
+//! diff and compliance checks, and [`MockScanner`] serves reports from JSON
+//! fixtures in place of a real scan.
 
+pub mod allowlist;
+pub mod asset;
+pub mod autopilot;
+pub mod baseline;
+pub mod cache;
+#[cfg(all(feature = "local", windows))]
+mod cancel;
+pub mod compliance;
+pub mod containers;
 pub mod error;
-#[cfg(feature = "local")]
+pub mod findings;
+#[cfg(feature = "remote")]
+pub mod fleet;
+pub mod fleet_report;
+pub mod gate;
+#[cfg(all(feature = "local", feature = "sqlite"))]
+pub mod history;
+pub mod lifecycle;
+#[cfg(all(feature = "local", windows))]
 pub mod local;
+pub mod metadata;
+pub mod metrics;
+pub mod mock;
+pub mod options;
+pub mod plugin;
+pub mod privileges;
+#[cfg(feature = "query")]
+pub mod query;
+pub mod redact;
+pub mod redistributables;
 #[cfg(feature = "remote")]
 pub mod remote;
+pub mod report;
 pub mod scanner;
+pub mod schedule;
+pub mod score;
+#[cfg(all(feature = "serve", windows))]
+pub mod serve;
+pub mod servicing;
 
-#[cfg(feature = "local")]
+pub mod event_logs;
+pub mod exclusions;
 pub mod industrial;
-#[cfg(feature = "local")]
+#[cfg(feature = "remote")]
+pub mod inventory;
+pub mod licensing;
 pub mod output;
-#[cfg(feature = "local")]
+pub mod security;
+pub mod services;
 pub mod software;
-#[cfg(feature = "local")]
 pub mod system;
-#[cfg(feature = "local")]
+pub mod update_health;
 pub mod updates;
+#[cfg(feature = "vuln")]
+pub mod vuln;
+pub mod watch;
+#[cfg(all(feature = "local", windows))]
+pub mod wmi_context;
 
+pub use asset::AssetFingerprint;
+pub use baseline::{Baseline, BaselineStore};
+pub use compliance::{ComplianceReport, Policy};
 pub use error::Error;
-pub use scanner::{ScanError, Scanner};
+pub use findings::{Finding, Severity};
+pub use fleet_report::FleetReport;
+pub use gate::FailCondition;
+pub use lifecycle::{OsSupport, SoftwareLifecycle, SupportStatus};
+pub use metadata::{ScanMetadata, ScannerKind};
+pub use metrics::{CollectorMetrics, ScanMetrics, TransferMetrics};
+pub use mock::MockScanner;
+pub use options::{Collector, ScanOptions, Sections};
+pub use plugin::CustomCollector;
+pub use redact::{Redaction, RedactionPolicy};
+pub use redistributables::{Redistributable, RedistributableKind};
+pub use report::{AuditReport, REPORT_SCHEMA_VERSION, ScanWarning};
+pub use scanner::{BlockingScanner, ScanError, ScanErrorKind, ScanItem, ScanStream, Scanner};
+pub use score::AuditScore;
+pub use servicing::ServicingStatus;
+pub use tokio_util::sync::CancellationToken;
 
-#[cfg(feature = "local")]
-pub use local::LocalScanner;
+#[cfg(all(feature = "local", windows))]
+pub use local::{LocalScan, LocalScanner};
+#[cfg(all(feature = "remote", feature = "local"))]
+pub use remote::WmiRemoteScanner;
 #[cfg(feature = "remote")]
-pub use remote::RemoteScanner;
+pub use remote::{
+    AgentUpload, AuthMethod, ClientCertificate, CredentialSource, EventSender, ExecutionMode,
+    RemoteScanner, ResolvedCredential, RetryPolicy, ScanEvent, ScanStage,
+};
 
-#[cfg(feature = "local")]
-pub use industrial::{IndustrialScanner, IndustrialSoftware, Vendor};
-#[cfg(feature = "local")]
-pub use software::{RegistrySource, Software, SoftwareScanner};
-#[cfg(feature = "local")]
-pub use system::{NetworkInterface, SystemInfo};
-#[cfg(feature = "local")]
-pub use updates::WindowsUpdate;
+pub use autopilot::Autopilot;
+pub use containers::{ContainerRuntime, ContainerRuntimeKind, Containers, Wsl, WslDistribution};
+pub use event_logs::{EventLog, EventLogs, LogRetention};
+pub use industrial::{
+    IndustrialCategory, IndustrialScanner, IndustrialSoftware, RuntimeState, Vendor, VendorCategory,
+};
+pub use licensing::{License, LicenseChannel, LicenseStatus, Licensing};
+pub use security::{BitLockerVolume, FirewallProfile, PasswordPolicy, SecurityPosture};
+pub use services::WindowsService;
+#[cfg(all(feature = "local", windows))]
+pub use software::SoftwareIter;
+pub use software::{InstallDateSource, RegistrySource, Software, SoftwareScanner};
+pub use system::{
+    HostIdentity, InterfaceAddress, NetworkInterface, NetworkOptions, ReverseDns, SystemInfo,
+};
+pub use update_health::{SccmClient, UpdateHealth};
+pub use updates::{UpdateFilter, UpdateKind, WindowsUpdate};
+#[cfg(all(feature = "local", windows))]
+pub use wmi_context::WmiContext;
@@ -0,0 +1,408 @@
+//! Windows licensing module.
+//!
+//! Reports the Windows edition and every installed license that has a
+//! product key (Windows and, on Office 2013 and later, Office alike): its
+//! channel (retail, OEM or volume), activation status and the last five
+//! characters of the key, as `slmgr /dlv` shows them. Read from the
+//! `SoftwareLicensingProduct` WMI class; license true-ups start here.
+
+use serde::{Deserialize, Serialize};
+#[cfg(all(windows, feature = "local"))]
+use windows_registry::LOCAL_MACHINE;
+#[cfg(all(windows, feature = "local"))]
+use wmi::WMIConnection;
+
+/// `ApplicationID` of Windows licenses in `SoftwareLicensingProduct`.
+pub const WINDOWS_APPLICATION_ID: &str = "55c92734-d682-4d71-983e-d6ec3f16059f";
+
+/// Registry key holding the `EditionID` value.
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const EDITION_KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion";
+
+/// Edition and installed licenses of a host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Licensing {
+    /// Windows edition (`EditionID`, e.g., "Professional", "EnterpriseS")
+    #[serde(default)]
+    pub edition: Option<String>,
+    /// Installed licenses with a product key
+    #[serde(default)]
+    pub licenses: Vec<License>,
+}
+
+/// One installed license.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct License {
+    /// License name (e.g., "Windows(R), Professional edition")
+    pub name: String,
+    /// License description (e.g., "Windows(R) Operating System, OEM_DM
+    /// channel")
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Licensed application; [`WINDOWS_APPLICATION_ID`] for Windows
+    #[serde(default)]
+    pub application_id: Option<String>,
+    /// Distribution channel
+    #[serde(default)]
+    pub channel: Option<LicenseChannel>,
+    /// Channel as Windows reports it (e.g., "OEM:DM", "Volume:GVLK");
+    /// not available before Windows 8
+    #[serde(default)]
+    pub key_channel: Option<String>,
+    /// Activation status
+    pub status: LicenseStatus,
+    /// Last five characters of the product key
+    #[serde(default)]
+    pub partial_product_key: Option<String>,
+}
+
+/// Channel a license was sold through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseChannel {
+    /// Boxed or online retail license
+    Retail,
+    /// Preinstalled by the hardware vendor
+    Oem,
+    /// Volume license (KMS or MAK)
+    Volume,
+}
+
+impl LicenseChannel {
+    /// Channel from `ProductKeyChannel` ("OEM:DM", "Volume:MAK", ...) or,
+    /// before Windows 8, from the description's "..., VOLUME_KMSCLIENT
+    /// channel" suffix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::licensing::LicenseChannel;
+    ///
+    /// assert_eq!(LicenseChannel::parse(Some("OEM:DM"), None), Some(LicenseChannel::Oem));
+    /// assert_eq!(
+    ///     LicenseChannel::parse(None, Some("Windows(R) 7, VOLUME_MAK channel")),
+    ///     Some(LicenseChannel::Volume)
+    /// );
+    /// ```
+    pub fn parse(key_channel: Option<&str>, description: Option<&str>) -> Option<Self> {
+        let channel = key_channel.map(str::to_string).or_else(|| {
+            let description = description?.trim();
+            let channel = description
+                .strip_suffix(" channel")?
+                .rsplit(',')
+                .next()?
+                .trim();
+            Some(channel.to_string())
+        })?;
+        let channel = channel.to_uppercase();
+        if channel.starts_with("RETAIL") {
+            Some(LicenseChannel::Retail)
+        } else if channel.starts_with("OEM") {
+            Some(LicenseChannel::Oem)
+        } else if channel.starts_with("VOLUME") {
+            Some(LicenseChannel::Volume)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for LicenseChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LicenseChannel::Retail => write!(f, "Retail"),
+            LicenseChannel::Oem => write!(f, "OEM"),
+            LicenseChannel::Volume => write!(f, "Volume"),
+        }
+    }
+}
+
+/// Activation status (`SoftwareLicensingProduct.LicenseStatus`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseStatus {
+    /// Not activated and out of grace
+    Unlicensed,
+    /// Activated
+    Licensed,
+    /// Within the initial grace period after installation
+    InitialGrace,
+    /// Within the grace period after a KMS activation expired or the
+    /// hardware changed too much
+    AdditionalGrace,
+    /// Within the grace period after failing genuine validation
+    NonGenuineGrace,
+    /// Out of grace: Windows nags and limits personalization
+    Notification,
+    /// Within an extended grace period
+    ExtendedGrace,
+    /// A status this version does not know
+    Unknown,
+}
+
+impl LicenseStatus {
+    /// Status from its `LicenseStatus` value.
+    pub fn from_code(code: u32) -> Self {
+        match code {
+            0 => LicenseStatus::Unlicensed,
+            1 => LicenseStatus::Licensed,
+            2 => LicenseStatus::InitialGrace,
+            3 => LicenseStatus::AdditionalGrace,
+            4 => LicenseStatus::NonGenuineGrace,
+            5 => LicenseStatus::Notification,
+            6 => LicenseStatus::ExtendedGrace,
+            _ => LicenseStatus::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for LicenseStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LicenseStatus::Unlicensed => write!(f, "unlicensed"),
+            LicenseStatus::Licensed => write!(f, "licensed"),
+            LicenseStatus::InitialGrace => write!(f, "initial grace period"),
+            LicenseStatus::AdditionalGrace => write!(f, "additional grace period"),
+            LicenseStatus::NonGenuineGrace => write!(f, "non-genuine grace period"),
+            LicenseStatus::Notification => write!(f, "notification"),
+            LicenseStatus::ExtendedGrace => write!(f, "extended grace period"),
+            LicenseStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl License {
+    /// Whether this is a Windows license.
+    pub fn is_windows(&self) -> bool {
+        self.application_id
+            .as_deref()
+            .is_some_and(|id| id.eq_ignore_ascii_case(WINDOWS_APPLICATION_ID))
+    }
+}
+
+impl Licensing {
+    /// The Windows license in effect: the activated one when there are
+    /// several, otherwise the first.
+    pub fn windows(&self) -> Option<&License> {
+        let mut windows = self.licenses.iter().filter(|l| l.is_windows());
+        let first = windows.clone().next();
+        windows
+            .find(|l| l.status == LicenseStatus::Licensed)
+            .or(first)
+    }
+
+    /// What is not activated; empty when every license is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::licensing::{License, LicenseStatus, Licensing, WINDOWS_APPLICATION_ID};
+    ///
+    /// let licensing = Licensing {
+    ///     edition: Some("Professional".into()),
+    ///     licenses: vec![License {
+    ///         name: "Windows(R), Professional edition".into(),
+    ///         description: None,
+    ///         application_id: Some(WINDOWS_APPLICATION_ID.into()),
+    ///         channel: None,
+    ///         key_channel: None,
+    ///         status: LicenseStatus::Notification,
+    ///         partial_product_key: Some("3V66T".into()),
+    ///     }],
+    /// };
+    /// assert_eq!(licensing.issues().len(), 1);
+    /// ```
+    pub fn issues(&self) -> Vec<String> {
+        let windows = self.windows();
+        self.licenses
+            .iter()
+            // Only the Windows license in effect counts; the others are
+            // leftovers of edition upgrades.
+            .filter(|l| !l.is_windows() || windows.is_some_and(|w| std::ptr::eq(w, *l)))
+            .filter(|l| l.status != LicenseStatus::Licensed)
+            .map(|l| format!("{} is not activated ({})", l.name, l.status))
+            .collect()
+    }
+}
+
+/// WMI result struct for SoftwareLicensingProduct.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct SoftwareLicensingProduct {
+    name: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "ApplicationID")]
+    application_id: Option<String>,
+    #[serde(default)]
+    product_key_channel: Option<String>,
+    license_status: Option<u32>,
+    partial_product_key: Option<String>,
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl Licensing {
+    /// Collect the edition and installed licenses (READ-ONLY).
+    ///
+    /// The query takes a few seconds on most hosts.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::Licensing;
+    ///
+    /// let licensing = Licensing::collect().unwrap();
+    /// if let Some(windows) = licensing.windows() {
+    ///     println!("{}: {}", windows.name, windows.status);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if COM cannot be
+    /// initialized or `SoftwareLicensingProduct` cannot be queried.
+    #[tracing::instrument]
+    pub fn collect() -> Result<Self, crate::Error> {
+        crate::WmiContext::with_current(Self::collect_with_wmi)
+    }
+
+    /// [`collect`](Self::collect) over an open WMI context.
+    ///
+    /// # Errors
+    ///
+    /// As [`collect`](Self::collect).
+    pub fn collect_with_wmi(wmi: &crate::WmiContext) -> Result<Self, crate::Error> {
+        tracing::info!("Collecting licensing status");
+        let edition = LOCAL_MACHINE
+            .open(EDITION_KEY)
+            .and_then(|key| key.get_string("EditionID"))
+            .ok()
+            .filter(|s| !s.is_empty());
+        Self::query(wmi.cimv2()?, edition)
+    }
+
+    /// Query the licenses over `wmi_con`. Shared by the local and the
+    /// remote (DCOM) collectors.
+    pub(crate) fn query(
+        wmi_con: &WMIConnection,
+        edition: Option<String>,
+    ) -> Result<Self, crate::Error> {
+        const QUERY: &str = "SELECT Name, Description, ApplicationID, ProductKeyChannel, \
+                             LicenseStatus, PartialProductKey FROM SoftwareLicensingProduct \
+                             WHERE PartialProductKey IS NOT NULL";
+        // Windows 7 has no ProductKeyChannel; the description names the
+        // channel there.
+        const LEGACY_QUERY: &str = "SELECT Name, Description, ApplicationID, LicenseStatus, \
+                                    PartialProductKey FROM SoftwareLicensingProduct \
+                                    WHERE PartialProductKey IS NOT NULL";
+        let products = match wmi_con.raw_query::<SoftwareLicensingProduct>(QUERY) {
+            Ok(products) => products,
+            Err(e) => {
+                tracing::debug!(error = %e, "Retrying license query without ProductKeyChannel");
+                wmi_con.raw_query::<SoftwareLicensingProduct>(LEGACY_QUERY)?
+            }
+        };
+
+        let licenses = products
+            .into_iter()
+            .map(|p| License {
+                channel: LicenseChannel::parse(
+                    p.product_key_channel.as_deref(),
+                    p.description.as_deref(),
+                ),
+                name: p.name.unwrap_or_default(),
+                description: p.description,
+                application_id: p.application_id,
+                key_channel: p.product_key_channel.filter(|s| !s.is_empty()),
+                status: p
+                    .license_status
+                    .map_or(LicenseStatus::Unknown, LicenseStatus::from_code),
+                partial_product_key: p.partial_product_key.filter(|s| !s.is_empty()),
+            })
+            .collect();
+        Ok(Licensing { edition, licenses })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license(name: &str, windows: bool, status: LicenseStatus) -> License {
+        License {
+            name: name.into(),
+            description: None,
+            application_id: Some(if windows {
+                WINDOWS_APPLICATION_ID.to_uppercase()
+            } else {
+                "0ff1ce15-a989-479d-af46-f275c6370663".into()
+            }),
+            channel: None,
+            key_channel: None,
+            status,
+            partial_product_key: None,
+        }
+    }
+
+    #[test]
+    fn test_channel_parse() {
+        assert_eq!(
+            LicenseChannel::parse(Some("Volume:GVLK"), Some("..., OEM_DM channel")),
+            Some(LicenseChannel::Volume)
+        );
+        assert_eq!(
+            LicenseChannel::parse(None, Some("Windows(R) Operating System, RETAIL channel")),
+            Some(LicenseChannel::Retail)
+        );
+        assert_eq!(
+            LicenseChannel::parse(None, Some("Windows(R), TIMEBASED_EVAL channel")),
+            None
+        );
+        assert_eq!(LicenseChannel::parse(None, None), None);
+        assert_eq!(LicenseStatus::from_code(5), LicenseStatus::Notification);
+        assert_eq!(LicenseStatus::from_code(42), LicenseStatus::Unknown);
+    }
+
+    #[test]
+    fn test_windows_and_issues() {
+        let licensing = Licensing {
+            edition: Some("Enterprise".into()),
+            licenses: vec![
+                // Left over from the edition the host shipped with
+                license(
+                    "Windows(R), Professional edition",
+                    true,
+                    LicenseStatus::Unlicensed,
+                ),
+                license(
+                    "Windows(R), Enterprise edition",
+                    true,
+                    LicenseStatus::Licensed,
+                ),
+                license(
+                    "Office 19, Office19ProPlus2019VL_MAK_AE",
+                    false,
+                    LicenseStatus::InitialGrace,
+                ),
+            ],
+        };
+        assert_eq!(
+            licensing.windows().map(|l| l.name.as_str()),
+            Some("Windows(R), Enterprise edition")
+        );
+        assert_eq!(
+            licensing.issues(),
+            ["Office 19, Office19ProPlus2019VL_MAK_AE is not activated (initial grace period)"]
+        );
+
+        let unactivated = Licensing {
+            edition: None,
+            licenses: vec![license(
+                "Windows(R), Professional edition",
+                true,
+                LicenseStatus::Notification,
+            )],
+        };
+        assert_eq!(unactivated.issues().len(), 1);
+    }
+}
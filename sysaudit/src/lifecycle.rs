@@ -0,0 +1,237 @@
+//! Windows lifecycle (end-of-support) lookup.
+//!
+//! Dates are the general servicing end dates for the Home/Pro editions of
+//! each client release and the extended support end for server releases.
+//! Enterprise LTSC/LTSB and IoT Enterprise LTSC editions, recognized from
+//! the OS name, use their own fixed lifecycle. Other Enterprise editions
+//! are serviced longer than Home/Pro; treat the result as a conservative
+//! hint, not a licensing statement.
+//!
+//! Installed runtimes and applications are covered by
+//! [`SoftwareLifecycle`].
+
+mod products;
+
+pub use products::{SoftwareLifecycle, SoftwareSupport, SupportStatus};
+
+use chrono::NaiveDate;
+
+/// Days before end of support at which a release counts as ending soon.
+pub const ENDING_SOON_DAYS: i64 = 180;
+
+/// A date as `(year, month, day)`.
+type Ymd = (i32, u32, u32);
+
+/// `(build, client end of support, server end of support)`.
+const WINDOWS_BUILDS: &[(u32, Option<Ymd>, Option<Ymd>)] = &[
+    (7601, Some((2020, 1, 14)), Some((2020, 1, 14))),
+    (9200, Some((2016, 1, 12)), Some((2023, 10, 10))),
+    (9600, Some((2023, 1, 10)), Some((2023, 10, 10))),
+    (10240, Some((2017, 5, 9)), None),
+    (14393, Some((2018, 4, 10)), Some((2027, 1, 12))),
+    (17763, Some((2020, 11, 10)), Some((2029, 1, 9))),
+    (19041, Some((2021, 12, 14)), None),
+    (19042, Some((2022, 5, 10)), None),
+    (19043, Some((2022, 12, 13)), None),
+    (19044, Some((2023, 6, 13)), None),
+    (19045, Some((2025, 10, 14)), None),
+    (20348, None, Some((2031, 10, 14))),
+    (22000, Some((2023, 10, 10)), None),
+    (22621, Some((2024, 10, 8)), None),
+    (22631, Some((2025, 11, 11)), None),
+    (26100, Some((2026, 10, 13)), Some((2034, 11, 10))),
+    (26200, Some((2027, 10, 12)), None),
+];
+
+/// `(build, Enterprise LTSC end of support, IoT Enterprise LTSC end of
+/// support)`.
+const LTSC_BUILDS: &[(u32, Ymd, Ymd)] = &[
+    (10240, (2025, 10, 14), (2025, 10, 14)),
+    (14393, (2026, 10, 13), (2026, 10, 13)),
+    (17763, (2029, 1, 9), (2029, 1, 9)),
+    (19044, (2027, 1, 12), (2032, 1, 13)),
+    (26100, (2029, 10, 9), (2034, 10, 10)),
+];
+
+/// Display versions (`22H2`, ...) mapped to builds, for reports that carry
+/// no build number.
+const DISPLAY_VERSIONS: &[(&str, &str, u32)] = &[
+    ("Windows 10", "21H2", 19044),
+    ("Windows 10", "22H2", 19045),
+    ("Windows 11", "21H2", 22000),
+    ("Windows 11", "22H2", 22621),
+    ("Windows 11", "23H2", 22631),
+    ("Windows 11", "24H2", 26100),
+    ("Windows 11", "25H2", 26200),
+];
+
+/// Support status of an operating system release on a given date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsSupport {
+    /// In support until the given date
+    Supported(NaiveDate),
+    /// In support, but ending within [`ENDING_SOON_DAYS`]
+    EndingSoon(NaiveDate),
+    /// Out of support since the given date
+    EndOfLife(NaiveDate),
+    /// Release not recognized
+    Unknown,
+}
+
+impl OsSupport {
+    /// Evaluate an OS name and version (or build number) on a date.
+    ///
+    /// `version` may be a build (`22631.3007`, `10 (19045)`) or a display
+    /// version (`23H2`); server editions are recognized from the name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use sysaudit::lifecycle::OsSupport;
+    ///
+    /// let on = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    /// let status = OsSupport::evaluate("Windows 7 Professional", "6.1.7601", on);
+    /// assert!(status.is_end_of_life());
+    /// ```
+    pub fn evaluate(os_name: &str, version: &str, on: NaiveDate) -> Self {
+        let Some(end) = end_of_support(os_name, version) else {
+            return OsSupport::Unknown;
+        };
+        let remaining = (end - on).num_days();
+        if remaining < 0 {
+            OsSupport::EndOfLife(end)
+        } else if remaining <= ENDING_SOON_DAYS {
+            OsSupport::EndingSoon(end)
+        } else {
+            OsSupport::Supported(end)
+        }
+    }
+
+    /// Whether the release is out of support.
+    pub fn is_end_of_life(self) -> bool {
+        matches!(self, OsSupport::EndOfLife(_))
+    }
+}
+
+impl std::fmt::Display for OsSupport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OsSupport::Supported(end) => write!(f, "Supported until {}", end),
+            OsSupport::EndingSoon(end) => write!(f, "Support ends {}", end),
+            OsSupport::EndOfLife(end) => write!(f, "END OF LIFE since {}", end),
+            OsSupport::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// End-of-support date for an OS release, if known.
+pub fn end_of_support(os_name: &str, version: &str) -> Option<NaiveDate> {
+    let server = os_name.contains("Server");
+    let ltsc = !server && (os_name.contains("LTSC") || os_name.contains("LTSB"));
+    let build = os_build(os_name, version)?;
+
+    let (y, m, d) = if ltsc {
+        let (_, enterprise, iot) = LTSC_BUILDS.iter().find(|(b, _, _)| *b == build)?;
+        if os_name.contains("IoT") {
+            *iot
+        } else {
+            *enterprise
+        }
+    } else {
+        let (_, client, server_end) = WINDOWS_BUILDS.iter().find(|(b, _, _)| *b == build)?;
+        if server { (*server_end)? } else { (*client)? }
+    };
+    NaiveDate::from_ymd_opt(y, m, d)
+}
+
+/// NT build number of an OS release, from a version string such as
+/// `10.0.19045` or a display version such as `22H2`.
+pub fn os_build(os_name: &str, version: &str) -> Option<u32> {
+    build_number(version).or_else(|| {
+        DISPLAY_VERSIONS
+            .iter()
+            .find(|(name, display, _)| {
+                os_name.contains(name) && version.eq_ignore_ascii_case(display)
+            })
+            .map(|(_, _, build)| *build)
+    })
+}
+
+/// First number in `version` that looks like an NT build (7600 or later).
+fn build_number(version: &str) -> Option<u32> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse::<u32>().ok())
+        .find(|n| (7600..100_000).contains(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_build_and_display_version() {
+        assert_eq!(
+            end_of_support("Windows 11 Pro", "22631.3007"),
+            Some(date(2025, 11, 11))
+        );
+        assert_eq!(
+            end_of_support("Windows 11 Pro", "23H2"),
+            Some(date(2025, 11, 11))
+        );
+        assert_eq!(
+            end_of_support("Windows Server 2019 Standard", "10 (17763)"),
+            Some(date(2029, 1, 9))
+        );
+        assert_eq!(end_of_support("Windows 11 Pro", "Unknown"), None);
+    }
+
+    #[test]
+    fn test_ltsc_editions() {
+        assert_eq!(
+            end_of_support("Windows 10 Enterprise LTSC 2019", "10.0.17763"),
+            Some(date(2029, 1, 9))
+        );
+        assert_eq!(
+            end_of_support("Windows 10 Enterprise LTSB 2016", "10.0.14393"),
+            Some(date(2026, 10, 13))
+        );
+        assert_eq!(
+            end_of_support("Windows 10 Enterprise LTSC 2021", "10.0.19044"),
+            Some(date(2027, 1, 12))
+        );
+        assert_eq!(
+            end_of_support("Windows 10 IoT Enterprise LTSC 2021", "10.0.19044"),
+            Some(date(2032, 1, 13))
+        );
+        assert_eq!(
+            end_of_support("Windows 11 IoT Enterprise LTSC 2024", "10.0.26100"),
+            Some(date(2034, 10, 10))
+        );
+        // Not an LTSC build.
+        assert_eq!(
+            end_of_support("Windows 10 Enterprise LTSC", "10.0.19045"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let on = date(2025, 6, 1);
+        assert_eq!(
+            OsSupport::evaluate("Windows 10 Pro", "22H2", on),
+            OsSupport::EndingSoon(date(2025, 10, 14))
+        );
+        assert!(OsSupport::evaluate("Windows 7", "7601", on).is_end_of_life());
+        assert_eq!(
+            OsSupport::evaluate("Windows Server 2022", "20348", on),
+            OsSupport::Supported(date(2031, 10, 14))
+        );
+        assert_eq!(OsSupport::evaluate("Linux", "6.1", on), OsSupport::Unknown);
+    }
+}
@@ -0,0 +1,337 @@
+//! End-of-life lookup for common runtimes, databases and applications.
+//!
+//! The bundled dataset is derived from [endoflife.date](https://endoflife.date)
+//! and covers the products most often found obsolete on audited hosts
+//! (.NET, Java, Python, Node.js, SQL Server, MySQL, PostgreSQL, Office,
+//! Visual C++ runtimes, Flash). A site file in the same format can add
+//! products or replace bundled ones:
+//!
+//! ```json
+//! {"products": [{"product": "acme-hmi", "match": ["Acme HMI"],
+//!   "exclude": ["Viewer"],
+//!   "cycles": [{"cycle": "7", "eol": "2025-12-31"}, {"cycle": "6", "eol": true}]}]}
+//! ```
+//!
+//! `eol` is a date, `true` (out of support, date unknown) or `false`.
+
+use super::ENDING_SOON_DAYS;
+use crate::Error;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Dataset compiled into the library.
+const BUNDLED: &str = include_str!("products.json");
+
+/// Support status of an installed product on a given date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportStatus {
+    /// In support
+    Supported,
+    /// In support, but ending within [`ENDING_SOON_DAYS`]
+    EndingSoon,
+    /// Out of support
+    EndOfLife,
+}
+
+impl std::fmt::Display for SupportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SupportStatus::Supported => write!(f, "Supported"),
+            SupportStatus::EndingSoon => write!(f, "Ending soon"),
+            SupportStatus::EndOfLife => write!(f, "End of life"),
+        }
+    }
+}
+
+/// Release cycle an installed product was matched to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SoftwareSupport {
+    /// Dataset product (e.g. "python")
+    pub product: String,
+    /// Release cycle (e.g. "3.8")
+    pub cycle: String,
+    /// End of support, when the dataset has a date
+    pub eol: Option<NaiveDate>,
+    /// Status on the evaluation date
+    pub status: SupportStatus,
+}
+
+/// Product release cycles with their end-of-life dates.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SoftwareLifecycle {
+    products: Vec<Product>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Product {
+    product: String,
+    #[serde(rename = "match")]
+    patterns: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    cycles: Vec<Cycle>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Cycle {
+    cycle: String,
+    eol: Eol,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+enum Eol {
+    Date(NaiveDate),
+    Flag(bool),
+}
+
+impl SoftwareLifecycle {
+    /// The dataset bundled with this release.
+    pub fn bundled() -> &'static Self {
+        static BUNDLED_DATA: OnceLock<SoftwareLifecycle> = OnceLock::new();
+        BUNDLED_DATA.get_or_init(|| {
+            SoftwareLifecycle::from_json(BUNDLED).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Bundled lifecycle dataset is invalid");
+                SoftwareLifecycle::default()
+            })
+        })
+    }
+
+    /// Load a site file on top of the bundled dataset; its products replace
+    /// bundled products of the same name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SoftwareScanner;
+    /// use sysaudit::lifecycle::SoftwareLifecycle;
+    ///
+    /// let lifecycle = SoftwareLifecycle::load("eol.json").unwrap();
+    /// let software = SoftwareScanner::new().lifecycle(lifecycle).scan().unwrap();
+    /// for sw in software.iter().filter(|sw| sw.support_status.is_some()) {
+    ///     println!("{}: {:?}", sw.name, sw.eol);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, or
+    /// [`Error::General`] if it is not a lifecycle dataset.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let site = Self::from_json(&std::fs::read_to_string(path)?)
+            .map_err(|e| Error::General(format!("{}: {}", path.display(), e)))?;
+        let mut lifecycle = Self::bundled().clone();
+        lifecycle.merge(site);
+        Ok(lifecycle)
+    }
+
+    /// Parse a dataset held in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if the text is not a lifecycle dataset.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::General(format!("invalid lifecycle dataset: {}", e)))
+    }
+
+    /// Add the products of `other`, replacing those with the same name.
+    pub fn merge(&mut self, other: SoftwareLifecycle) {
+        for product in other.products {
+            match self
+                .products
+                .iter_mut()
+                .find(|p| p.product == product.product)
+            {
+                Some(existing) => *existing = product,
+                None => self.products.push(product),
+            }
+        }
+    }
+
+    /// Number of products covered.
+    pub fn len(&self) -> usize {
+        self.products.len()
+    }
+
+    /// Whether no products are covered.
+    pub fn is_empty(&self) -> bool {
+        self.products.is_empty()
+    }
+
+    /// Support status of the package `name` at `version` on a date.
+    ///
+    /// The release cycle is taken from the version (`3.8.10` is cycle
+    /// `3.8`), else from the name (`Microsoft SQL Server 2014`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use sysaudit::lifecycle::{SoftwareLifecycle, SupportStatus};
+    ///
+    /// let on = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    /// let support = SoftwareLifecycle::bundled()
+    ///     .lookup("Python 3.8.10 (64-bit)", Some("3.8.10150.0"), on)
+    ///     .unwrap();
+    /// assert_eq!(support.status, SupportStatus::EndOfLife);
+    /// ```
+    pub fn lookup(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        on: NaiveDate,
+    ) -> Option<SoftwareSupport> {
+        let lower = name.to_lowercase();
+        let product = self.products.iter().find(|p| {
+            p.patterns.iter().any(|m| lower.contains(&m.to_lowercase()))
+                && !p.exclude.iter().any(|x| lower.contains(&x.to_lowercase()))
+        })?;
+
+        let cycle = version
+            .and_then(|v| {
+                longest_match(&product.cycles, |c| {
+                    v.starts_with(c) && boundary(v, c.len())
+                })
+            })
+            .or_else(|| longest_match(&product.cycles, |c| contains_word(name, c)))
+            .or_else(|| product.cycles.iter().find(|c| c.cycle == "*"))?;
+
+        let (eol, status) = match cycle.eol {
+            Eol::Date(end) => {
+                let remaining = (end - on).num_days();
+                let status = if remaining < 0 {
+                    SupportStatus::EndOfLife
+                } else if remaining <= ENDING_SOON_DAYS {
+                    SupportStatus::EndingSoon
+                } else {
+                    SupportStatus::Supported
+                };
+                (Some(end), status)
+            }
+            Eol::Flag(true) => (None, SupportStatus::EndOfLife),
+            Eol::Flag(false) => (None, SupportStatus::Supported),
+        };
+        Some(SoftwareSupport {
+            product: product.product.clone(),
+            cycle: cycle.cycle.clone(),
+            eol,
+            status,
+        })
+    }
+}
+
+/// Longest cycle satisfying `pred`, so `3.10` wins over `3.1`.
+fn longest_match(cycles: &[Cycle], pred: impl Fn(&str) -> bool) -> Option<&Cycle> {
+    cycles
+        .iter()
+        .filter(|c| c.cycle != "*" && pred(&c.cycle))
+        .max_by_key(|c| c.cycle.len())
+}
+
+/// Whether `text` does not continue with a digit at byte `at`.
+fn boundary(text: &str, at: usize) -> bool {
+    !text[at..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Whether `word` occurs in `text` not inside a larger number or word
+/// (`17` in `JDK 17.0.9`, but not `9` in `17.0.9`).
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(at, _)| {
+        let before = text[..at].chars().next_back();
+        let after = text[at + word.len()..].chars().next();
+        before.is_none_or(|c| !c.is_alphanumeric() && c != '.')
+            && after.is_none_or(|c| !c.is_alphanumeric())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_bundled_lookup() {
+        let data = SoftwareLifecycle::bundled();
+        assert!(!data.is_empty());
+        let on = date(2025, 6, 1);
+
+        let python = data
+            .lookup("Python 3.10.11 (64-bit)", Some("3.10.11150.0"), on)
+            .unwrap();
+        assert_eq!(
+            (python.cycle.as_str(), python.status),
+            ("3.10", SupportStatus::Supported)
+        );
+
+        let sql = data
+            .lookup(
+                "Microsoft SQL Server 2008 R2 (64-bit)",
+                Some("10.50.6000.34"),
+                on,
+            )
+            .unwrap();
+        assert_eq!(
+            (sql.cycle.as_str(), sql.status),
+            ("2008 R2", SupportStatus::EndOfLife)
+        );
+
+        let java = data
+            .lookup(
+                "Java(TM) SE Development Kit 17.0.9 (64-bit)",
+                Some("17.0.9.0"),
+                on,
+            )
+            .unwrap();
+        assert_eq!(java.eol, Some(date(2027, 10, 31)));
+
+        let node = data.lookup("Node.js", Some("23.1.0"), on).unwrap();
+        assert_eq!((node.eol, node.status), (None, SupportStatus::EndOfLife));
+
+        assert!(
+            data.lookup("Java Auto Updater", Some("2.8.391.13"), on)
+                .is_none()
+        );
+        assert!(
+            data.lookup(
+                "Microsoft SQL Server Management Studio - 19.1",
+                Some("16.200"),
+                on
+            )
+            .is_none()
+        );
+        assert!(
+            data.lookup("7-Zip 23.01 (x64)", Some("23.01"), on)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_merge_replaces_product() {
+        let site = SoftwareLifecycle::from_json(
+            r#"{"products": [
+                {"product": "python", "match": ["Python "], "cycles": [{"cycle": "3.8", "eol": false}]},
+                {"product": "acme-hmi", "match": ["Acme HMI"], "cycles": [{"cycle": "*", "eol": "2025-12-31"}]}
+            ]}"#,
+        )
+        .unwrap();
+        let mut data = SoftwareLifecycle::bundled().clone();
+        let bundled = data.len();
+        data.merge(site);
+        assert_eq!(data.len(), bundled + 1);
+
+        let on = date(2025, 9, 1);
+        let python = data.lookup("Python 3.8.10", Some("3.8.10"), on).unwrap();
+        assert_eq!(python.status, SupportStatus::Supported);
+        let hmi = data.lookup("Acme HMI Runtime", None, on).unwrap();
+        assert_eq!(hmi.status, SupportStatus::EndingSoon);
+        assert!(SoftwareLifecycle::from_json("{}").is_err());
+    }
+}
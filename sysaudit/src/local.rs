@@ -1,87 +1,841 @@
-use crate::scanner::{ScanError, Scanner};
-use crate::{IndustrialScanner, SoftwareScanner, SystemInfo};
-use sysaudit_common::{
-    IndustrialSoftwareDto, IpVersion, NetworkInterfaceDto, SoftwareDto, SysauditReport,
-    SystemInfoDto,
+use crate::cache::cached;
+use crate::metadata::{ScanMetadata, ScannerKind};
+use crate::metrics::ScanMetrics;
+use crate::options::{Collector, ScanOptions};
+use crate::plugin::{CustomCollector, collect_all};
+use crate::privileges::{admin_warnings, is_elevated};
+use crate::report::{AuditReport, ScanWarning};
+use crate::scanner::{ScanError, ScanItem, ScanStream, Scanner};
+use crate::score::AuditScore;
+use crate::{
+    Autopilot, Containers, Error, EventLogs, IndustrialScanner, IndustrialSoftware, Licensing,
+    SecurityPosture, Software, SoftwareScanner, SystemInfo, UpdateHealth, WindowsService,
+    WindowsUpdate,
 };
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 /// Collects system data from the local machine.
 ///
 /// Wraps existing `SystemInfo::collect()`, `SoftwareScanner::scan()`,
-/// `IndustrialScanner::scan()`, and `WindowsUpdate::collect_all()`.
-pub struct LocalScanner;
+/// `IndustrialScanner::classify_from()` (or `scan()` when the software
+/// list cannot be reused), `WindowsUpdate::collect_all()`,
+/// `WindowsService::collect_all()`, `SecurityPosture::collect()`,
+/// `UpdateHealth::collect()`, `Licensing::collect()`,
+/// `EventLogs::collect()`, `Autopilot::collect()` and
+/// `Containers::collect()`,
+/// running only the collectors selected in its [`ScanOptions`].
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::{LocalScanner, ScanOptions, Scanner};
+///
+/// # async fn example() -> Result<(), sysaudit::ScanError> {
+/// let options = ScanOptions::new().updates(false).services(true);
+/// let report = LocalScanner::with_options(options).audit().await?;
+/// println!("{} services", report.services.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct LocalScanner {
+    options: ScanOptions,
+    collectors: Vec<Arc<dyn CustomCollector>>,
+}
 
-impl Scanner for LocalScanner {
-    #[tracing::instrument(skip(self))]
-    async fn scan(&self) -> Result<SysauditReport, ScanError> {
-        let system_info = SystemInfo::collect()?;
-        let software = SoftwareScanner::new().scan()?;
-        let industrial = IndustrialScanner::default().scan()?;
-        // let updates = WindowsUpdate::collect_all(); // Currently not mapped to SysauditReport in DTO, skip for now.
-
-        // Map sysaudit structures to the DTOs expected by sysaudit-common
-        let system_dto = SystemInfoDto {
-            os_name: system_info.os_name,
-            os_version: system_info.os_version,
-            host_name: system_info.computer_name,
-            cpu_info: system_info.cpu_info,
-            cpu_physical_cores: system_info.cpu_cores_physical.map(|c| c as u32),
-            memory_total_bytes: system_info.memory_total,
-            memory_used_bytes: system_info.memory_used,
-            manufacturer: system_info.manufacturer,
-            model: system_info.model,
-            network_interfaces: system_info
-                .network_interfaces
-                .into_iter()
-                .map(|iface| {
-                    let ip_version = if iface.ip_address.is_ipv4() {
-                        IpVersion::IPv4
+/// The default scanner, so code written when `LocalScanner` was a unit
+/// struct (`LocalScanner.scan()`) still builds.
+#[deprecated(note = "use `LocalScanner::new()` or `LocalScanner::with_options`")]
+#[allow(non_upper_case_globals)]
+pub const LocalScanner: LocalScanner = LocalScanner::new();
+
+impl std::fmt::Debug for LocalScanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalScanner")
+            .field("options", &self.options)
+            .field(
+                "collectors",
+                &self.collectors.iter().map(|c| c.name()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl LocalScanner {
+    /// Scanner collecting software, industrial software and updates.
+    pub const fn new() -> Self {
+        LocalScanner {
+            options: ScanOptions::new(),
+            collectors: Vec::new(),
+        }
+    }
+
+    /// Scanner collecting what `options` selects.
+    pub fn with_options(options: ScanOptions) -> Self {
+        LocalScanner {
+            options,
+            collectors: Vec::new(),
+        }
+    }
+
+    /// Also run `collector`, adding its section to the report's
+    /// [`extensions`](AuditReport::extensions). Custom collectors run one
+    /// after another, alongside the built-in ones, within the
+    /// [`Collector::Custom`] time budget.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{CustomCollector, Error, LocalScanner, Scanner};
+    ///
+    /// struct Line;
+    ///
+    /// impl CustomCollector for Line {
+    ///     fn name(&self) -> &str {
+    ///         "production_line"
+    ///     }
+    ///
+    ///     fn collect(&self) -> Result<serde_json::Value, Error> {
+    ///         Ok(serde_json::json!("Line 3"))
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<(), sysaudit::ScanError> {
+    /// let report = LocalScanner::new().with_collector(Line).audit().await?;
+    /// println!("{}", report.extensions["production_line"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_collector(mut self, collector: impl CustomCollector + 'static) -> Self {
+        self.collectors.push(Arc::new(collector));
+        self
+    }
+}
+
+/// What a [`LocalScanner`] collected: the report, and the full system,
+/// software and industrial records its sections were built from, as taken
+/// by the table, Markdown and XML formatters and by
+/// [`ReportBundle`](crate::output::ReportBundle).
+#[derive(Debug, Clone)]
+pub struct LocalScan {
+    /// Host information, redacted like the report
+    pub system: SystemInfo,
+    /// Installed programs, redacted like the report
+    pub software: Vec<Software>,
+    /// Detected industrial software, redacted like the report
+    pub industrial: Vec<IndustrialSoftware>,
+    /// The finished report
+    pub report: AuditReport,
+}
+
+impl LocalScanner {
+    /// Run the scan like [`Scanner::scan_with_cancel`], keeping the
+    /// collected records alongside the report.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{CancellationToken, LocalScanner, output::ConsoleFormatter};
+    ///
+    /// # async fn example() -> Result<(), sysaudit::ScanError> {
+    /// let scan = LocalScanner::new().collect(&CancellationToken::new()).await?;
+    /// println!("{}", ConsoleFormatter::format_software(&scan.software));
+    /// println!("{}", ConsoleFormatter::format_summary(&scan.report));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As [`Scanner::scan_with_cancel`].
+    #[tracing::instrument(skip(self, cancel))]
+    pub async fn collect(&self, cancel: &CancellationToken) -> Result<LocalScan, ScanError> {
+        let started = Instant::now();
+        let sections = self.options.selected();
+        let elevated = check_elevation();
+        let budget = |collector| self.options.timeout_for(collector);
+        // One child token per collector, so a timed-out collector can be
+        // stopped without cancelling the others.
+        let software_cancel = cancel.child_token();
+        let industrial_cancel = cancel.child_token();
+        let software_scanner = SoftwareScanner::new()
+            .include_user_installs(self.options.has_user_installs())
+            .include_32bit(self.options.has_32bit())
+            .cancel_token(software_cancel.clone());
+        let industrial_scanner =
+            IndustrialScanner::default().cancel_token(industrial_cancel.clone());
+        let slot = |collector, key: &str| self.options.cache_slot(collector, key);
+        let system_slot = slot(Collector::System, "");
+        let software_slot = slot(
+            Collector::Software,
+            &format!(
+                "user={},32bit={}",
+                self.options.has_user_installs(),
+                self.options.has_32bit()
+            ),
+        );
+        let industrial_slot = slot(Collector::Industrial, "");
+        let updates_slot = slot(Collector::Updates, "");
+        let services_slot = slot(Collector::Services, "");
+        let security_slot = slot(Collector::Security, "");
+        let update_health_slot = slot(Collector::UpdateHealth, "");
+        let licensing_slot = slot(Collector::Licensing, "");
+        let event_logs_slot = slot(Collector::EventLogs, "");
+        let autopilot_slot = slot(Collector::Autopilot, "");
+        let containers_slot = slot(Collector::Containers, "");
+
+        // The collectors are independent registry/WMI reads: run them side by
+        // side on the blocking pool.
+        let system = blocking(
+            Collector::System,
+            budget(Collector::System),
+            cancel.child_token(),
+            move || {
+                cached(system_slot.as_ref(), || {
+                    SystemInfo::collect().map(|info| (info, Vec::new()))
+                })
+                .map(|(info, _)| info)
+            },
+        );
+        let software = blocking(
+            Collector::Software,
+            budget(Collector::Software),
+            software_cancel,
+            move || {
+                if sections.has_software() {
+                    cached(software_slot.as_ref(), || {
+                        software_scanner.scan_with_warnings()
+                    })
+                } else {
+                    Ok(Default::default())
+                }
+            },
+        );
+        let industrial = |shared: Option<Vec<Software>>| {
+            blocking(
+                Collector::Industrial,
+                budget(Collector::Industrial),
+                industrial_cancel,
+                move || {
+                    if sections.has_industrial() {
+                        cached(industrial_slot.as_ref(), || match shared {
+                            Some(software) => {
+                                industrial_scanner.classify_from_with_warnings(&software)
+                            }
+                            None => industrial_scanner.scan_with_warnings(),
+                        })
                     } else {
-                        IpVersion::IPv6
-                    };
-
-                    NetworkInterfaceDto {
-                        name: iface.name,
-                        ip_address: iface.ip_address.to_string(),
-                        ip_version,
-                        mac_address: iface.mac_address,
+                        Ok(Default::default())
                     }
+                },
+            )
+        };
+        // With the 32-bit view included the software list covers every
+        // Uninstall key the industrial scan reads, so classify that list
+        // instead of walking the hives twice.
+        let share_walk =
+            sections.has_software() && sections.has_industrial() && self.options.has_32bit();
+        let installed = async move {
+            if share_walk {
+                let software = software.await;
+                let shared = match &software.1 {
+                    Ok((list, _)) => Some(list.clone()),
+                    Err(_) => None,
+                };
+                (software, industrial(shared).await)
+            } else {
+                tokio::join!(software, industrial(None))
+            }
+        };
+        let updates = blocking(
+            Collector::Updates,
+            budget(Collector::Updates),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_updates() {
+                    cached(updates_slot.as_ref(), || {
+                        Ok(or_warning(
+                            Collector::Updates,
+                            WindowsUpdate::try_collect_all(),
+                        ))
+                    })?
+                } else {
+                    Default::default()
                 })
-                .collect(),
+            },
+        );
+        let services = blocking(
+            Collector::Services,
+            budget(Collector::Services),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_services() {
+                    cached(services_slot.as_ref(), || {
+                        Ok(or_warning(
+                            Collector::Services,
+                            WindowsService::try_collect(),
+                        ))
+                    })?
+                } else {
+                    Default::default()
+                })
+            },
+        );
+        let security = blocking(
+            Collector::Security,
+            budget(Collector::Security),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_security() {
+                    cached(security_slot.as_ref(), || {
+                        Ok(match SecurityPosture::collect_with_warnings() {
+                            Ok((posture, warnings)) => (Some(posture), warnings),
+                            Err(e) => or_warning(Collector::Security, Err(e)),
+                        })
+                    })?
+                } else {
+                    Default::default()
+                })
+            },
+        );
+        let update_health = blocking(
+            Collector::UpdateHealth,
+            budget(Collector::UpdateHealth),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_update_health() {
+                    cached(update_health_slot.as_ref(), || {
+                        Ok(match UpdateHealth::collect_with_warnings() {
+                            Ok((health, warnings)) => (Some(health), warnings),
+                            Err(e) => or_warning(Collector::UpdateHealth, Err(e)),
+                        })
+                    })?
+                } else {
+                    Default::default()
+                })
+            },
+        );
+        let licensing = blocking(
+            Collector::Licensing,
+            budget(Collector::Licensing),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_licensing() {
+                    cached(licensing_slot.as_ref(), || {
+                        Ok(or_warning(
+                            Collector::Licensing,
+                            Licensing::collect().map(Some),
+                        ))
+                    })?
+                } else {
+                    Default::default()
+                })
+            },
+        );
+        let event_logs = blocking(
+            Collector::EventLogs,
+            budget(Collector::EventLogs),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_event_logs() {
+                    cached(event_logs_slot.as_ref(), || {
+                        Ok(or_warning(
+                            Collector::EventLogs,
+                            EventLogs::collect().map(Some),
+                        ))
+                    })?
+                } else {
+                    Default::default()
+                })
+            },
+        );
+        let autopilot = blocking(
+            Collector::Autopilot,
+            budget(Collector::Autopilot),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_autopilot() {
+                    cached(autopilot_slot.as_ref(), || {
+                        Ok(or_warning(
+                            Collector::Autopilot,
+                            Autopilot::collect().map(Some),
+                        ))
+                    })?
+                } else {
+                    Default::default()
+                })
+            },
+        );
+        let containers = blocking(
+            Collector::Containers,
+            budget(Collector::Containers),
+            cancel.child_token(),
+            move || {
+                Ok(if sections.has_containers() {
+                    cached(containers_slot.as_ref(), || {
+                        Ok(or_warning(
+                            Collector::Containers,
+                            Containers::collect().map(Some),
+                        ))
+                    })?
+                } else {
+                    Default::default()
+                })
+            },
+        );
+        let has_custom = !self.collectors.is_empty();
+        let collectors = self.collectors.clone();
+        let custom = blocking(
+            Collector::Custom,
+            budget(Collector::Custom),
+            cancel.child_token(),
+            move || Ok(collect_all(&collectors)),
+        );
+        let collect = async {
+            tokio::join!(
+                system,
+                installed,
+                updates,
+                services,
+                security,
+                update_health,
+                licensing,
+                event_logs,
+                autopilot,
+                containers,
+                custom
+            )
         };
 
-        let software_dto = software
+        // Collectors still running finish at their next cancellation check.
+        let (
+            (system_time, system_info),
+            ((software_time, software), (industrial_time, industrial)),
+            (updates_time, updates),
+            (services_time, services),
+            (security_time, security),
+            (update_health_time, update_health),
+            (licensing_time, licensing),
+            (event_logs_time, event_logs),
+            (autopilot_time, autopilot),
+            (containers_time, containers),
+            (custom_time, custom),
+        ) = cancel
+            .run_until_cancelled(collect)
+            .await
+            .ok_or(ScanError::Cancelled)?;
+
+        let mut metrics = ScanMetrics::new();
+        metrics.record(Collector::System, system_time);
+        for (collector, ran, time) in [
+            (Collector::Software, sections.has_software(), software_time),
+            (
+                Collector::Industrial,
+                sections.has_industrial(),
+                industrial_time,
+            ),
+            (Collector::Updates, sections.has_updates(), updates_time),
+            (Collector::Services, sections.has_services(), services_time),
+            (Collector::Security, sections.has_security(), security_time),
+            (
+                Collector::UpdateHealth,
+                sections.has_update_health(),
+                update_health_time,
+            ),
+            (
+                Collector::Licensing,
+                sections.has_licensing(),
+                licensing_time,
+            ),
+            (
+                Collector::EventLogs,
+                sections.has_event_logs(),
+                event_logs_time,
+            ),
+            (
+                Collector::Autopilot,
+                sections.has_autopilot(),
+                autopilot_time,
+            ),
+            (
+                Collector::Containers,
+                sections.has_containers(),
+                containers_time,
+            ),
+            (Collector::Custom, has_custom, custom_time),
+        ] {
+            if ran {
+                metrics.record(collector, time);
+            }
+        }
+
+        let mut warnings = if elevated == Some(false) {
+            admin_warnings(sections)
+        } else {
+            Vec::new()
+        };
+        let mut system = system_info?;
+        let mut software = optional(Collector::Software, software, &mut warnings)?;
+        let mut industrial = optional(Collector::Industrial, industrial, &mut warnings)?;
+        let mut report = AuditReport::from_local(
+            system.clone(),
+            software.clone(),
+            industrial.clone(),
+            optional(Collector::Updates, updates, &mut warnings)?,
+        );
+        report.services = optional(Collector::Services, services, &mut warnings)?;
+        report.security = optional(Collector::Security, security, &mut warnings)?;
+        report.update_health = optional(Collector::UpdateHealth, update_health, &mut warnings)?;
+        report.licensing = optional(Collector::Licensing, licensing, &mut warnings)?;
+        report.event_logs = optional(Collector::EventLogs, event_logs, &mut warnings)?;
+        report.autopilot = optional(Collector::Autopilot, autopilot, &mut warnings)?;
+        report.containers = optional(Collector::Containers, containers, &mut warnings)?;
+        report.extensions = optional(Collector::Custom, custom, &mut warnings)?;
+        report.warnings = warnings;
+        report.elevated = elevated;
+        report.score = Some(AuditScore::compute_with(
+            &report,
+            &self.options.effective_score_weights(),
+        ));
+        report.metrics = Some(metrics.total(started.elapsed()).count(&report));
+        report.metadata =
+            Some(ScanMetadata::new(ScannerKind::Local, started, &self.options).elevated(elevated));
+        self.options.apply_asset_id(&mut report);
+        self.options.redact(&mut report);
+        if let Some(policy) = self.options.redaction_policy() {
+            policy.apply_system_info(&mut system);
+            for sw in &mut software {
+                policy.apply_software(sw);
+            }
+            for sw in &mut industrial {
+                policy.apply_industrial(sw);
+            }
+        }
+        Ok(LocalScan {
+            system,
+            software,
+            industrial,
+            report,
+        })
+    }
+}
+
+impl Scanner for LocalScanner {
+    async fn audit(&self) -> Result<AuditReport, ScanError> {
+        self.scan_with_cancel(&CancellationToken::new()).await
+    }
+
+    async fn scan_with_cancel(&self, cancel: &CancellationToken) -> Result<AuditReport, ScanError> {
+        self.collect(cancel).await.map(|scan| scan.report)
+    }
+
+    /// Collectors run one after another on a dedicated thread, so no async
+    /// runtime needs to drive them; software entries are sent as registry
+    /// keys are read. Timeouts and the on-disk cache do not apply.
+    fn scan_stream(&self) -> ScanStream<'_> {
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let options = self.options.clone();
+        let collectors = self.collectors.clone();
+        let spawned = std::thread::Builder::new()
+            .name("sysaudit-scan".into())
+            .spawn(move || stream_local(&options, &collectors, &tx));
+        match spawned {
+            Ok(_) => ScanStream::from_channel(rx),
+            Err(e) => ScanStream::from_error(ScanError::Local(e.to_string())),
+        }
+    }
+}
+
+/// Items buffered between the collector thread and the stream's reader.
+const STREAM_BUFFER: usize = 256;
+
+/// Collect what `options` selects, sending each item to `tx`. Stops early
+/// once the stream is dropped.
+fn stream_local(
+    options: &ScanOptions,
+    collectors: &[Arc<dyn CustomCollector>],
+    tx: &mpsc::Sender<Result<ScanItem, ScanError>>,
+) {
+    let sections = options.selected();
+    let redaction = options.redaction_policy();
+    let send = |item| tx.blocking_send(item).is_ok();
+    let emit = |items: Vec<ScanItem>| items.into_iter().all(|item| send(Ok(item)));
+    let warn = |collector, messages: Vec<String>| {
+        messages
             .into_iter()
-            .map(|sw| {
-                let install_date = sw
-                    .install_date
-                    .and_then(|d| d.and_hms_opt(0, 0, 0))
-                    .map(|d| d.and_utc());
-                SoftwareDto {
-                    name: sw.name,
-                    version: sw.version,
-                    vendor: sw.publisher,
-                    install_date,
+            .all(|m| send(Ok(ScanItem::Warning(ScanWarning::new(collector, m)))))
+    };
+
+    match SystemInfo::collect() {
+        Ok(info) => {
+            let mut system = info.into();
+            if let Some(policy) = redaction {
+                policy.apply_system(&mut system);
+            }
+            if !send(Ok(ScanItem::System(Box::new(system)))) {
+                return;
+            }
+            if check_elevation() == Some(false)
+                && !admin_warnings(sections)
+                    .into_iter()
+                    .all(|w| send(Ok(ScanItem::Warning(w))))
+            {
+                return;
+            }
+        }
+        Err(e) => {
+            send(Err(e.into()));
+            return;
+        }
+    }
+
+    if sections.has_software() {
+        let scanner = SoftwareScanner::new()
+            .include_user_installs(options.has_user_installs())
+            .include_32bit(options.has_32bit());
+        for sw in scanner.scan_iter() {
+            let item = match sw {
+                Ok(sw) => ScanItem::Software(sw.into()),
+                Err(e) => ScanItem::Warning(ScanWarning::new(Collector::Software, e.to_string())),
+            };
+            if !send(Ok(item)) {
+                return;
+            }
+        }
+    }
+    if sections.has_industrial() {
+        match IndustrialScanner::default().scan() {
+            Ok(found) => {
+                if !emit(
+                    found
+                        .into_iter()
+                        .map(|sw| ScanItem::Industrial(sw.into()))
+                        .collect(),
+                ) {
+                    return;
                 }
-            })
+            }
+            Err(e) => {
+                send(Err(e.into()));
+                return;
+            }
+        }
+    }
+    if sections.has_updates() {
+        let (updates, warnings) = or_warning(Collector::Updates, WindowsUpdate::try_collect_all());
+        let updates = updates.into_iter().map(|mut update| {
+            if let Some(policy) = redaction {
+                policy.apply_update(&mut update);
+            }
+            ScanItem::Update(update)
+        });
+        if !emit(updates.collect()) || !warn(Collector::Updates, warnings) {
+            return;
+        }
+    }
+    if sections.has_services() {
+        let (services, warnings) = or_warning(Collector::Services, WindowsService::try_collect());
+        let services = services.into_iter().map(|mut service| {
+            if let Some(policy) = redaction {
+                policy.apply_service(&mut service);
+            }
+            ScanItem::Service(service)
+        });
+        if !emit(services.collect()) || !warn(Collector::Services, warnings) {
+            return;
+        }
+    }
+    if sections.has_security() {
+        let (security, warnings) = match SecurityPosture::collect_with_warnings() {
+            Ok((posture, warnings)) => (Some(posture), warnings),
+            Err(e) => or_warning(Collector::Security, Err(e)),
+        };
+        let items = security
+            .map(|posture| ScanItem::Security(Box::new(posture)))
+            .into_iter()
             .collect();
-
-        let industrial_dto = industrial
+        if !emit(items) || !warn(Collector::Security, warnings) {
+            return;
+        }
+    }
+    if sections.has_update_health() {
+        let (health, warnings) = match UpdateHealth::collect_with_warnings() {
+            Ok((health, warnings)) => (Some(health), warnings),
+            Err(e) => or_warning(Collector::UpdateHealth, Err(e)),
+        };
+        let items = health
+            .map(|health| ScanItem::UpdateHealth(Box::new(health)))
+            .into_iter()
+            .collect();
+        if !emit(items) || !warn(Collector::UpdateHealth, warnings) {
+            return;
+        }
+    }
+    if sections.has_licensing() {
+        let (licensing, warnings) =
+            or_warning(Collector::Licensing, Licensing::collect().map(Some));
+        let items = licensing
+            .map(|licensing| ScanItem::Licensing(Box::new(licensing)))
+            .into_iter()
+            .collect();
+        if !emit(items) || !warn(Collector::Licensing, warnings) {
+            return;
+        }
+    }
+    if sections.has_event_logs() {
+        let (logs, warnings) = or_warning(Collector::EventLogs, EventLogs::collect().map(Some));
+        let items = logs
+            .map(|logs| ScanItem::EventLogs(Box::new(logs)))
+            .into_iter()
+            .collect();
+        if !emit(items) || !warn(Collector::EventLogs, warnings) {
+            return;
+        }
+    }
+    if sections.has_autopilot() {
+        let (autopilot, warnings) =
+            or_warning(Collector::Autopilot, Autopilot::collect().map(Some));
+        let items = autopilot
+            .map(|autopilot| ScanItem::Autopilot(Box::new(autopilot)))
             .into_iter()
-            .map(|sw| IndustrialSoftwareDto {
-                vendor: sw.vendor.to_string(),
-                product: sw.product,
-                version: sw.version,
-                install_path: sw.install_path,
-            })
             .collect();
+        if !emit(items) || !warn(Collector::Autopilot, warnings) {
+            return;
+        }
+    }
+    if sections.has_containers() {
+        let (containers, warnings) =
+            or_warning(Collector::Containers, Containers::collect().map(Some));
+        let items = containers
+            .map(|containers| ScanItem::Containers(Box::new(containers)))
+            .into_iter()
+            .collect();
+        if !emit(items) || !warn(Collector::Containers, warnings) {
+            return;
+        }
+    }
+    if !collectors.is_empty() {
+        let (sections, warnings) = collect_all(collectors);
+        let items = sections
+            .into_iter()
+            .map(|(section, value)| ScanItem::Extension(section, value))
+            .collect();
+        // The last section: nothing left to stop early.
+        let _ = emit(items) && warn(Collector::Custom, warnings);
+    }
+}
 
-        Ok(SysauditReport {
-            system: system_dto,
-            software: software_dto,
-            industrial: industrial_dto,
-            timestamp: chrono::Utc::now(),
-        })
+/// Run a collector on tokio's blocking pool within its time budget,
+/// returning how long it took alongside its result.
+///
+/// The collector does not start if `cancel` already fired. When the budget
+/// runs out, `cancel` is triggered so registry scans stop at their next
+/// check, and [`ScanError::Timeout`] is returned. A collector blocked in a
+/// WMI call is abandoned rather than stopped: it holds its blocking-pool
+/// thread until the call returns, and its result is dropped.
+async fn blocking<T, F>(
+    collector: Collector,
+    budget: Option<Duration>,
+    cancel: CancellationToken,
+    collect: F,
+) -> (Duration, Result<T, ScanError>)
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    let started = Instant::now();
+    let result = run_blocking(collector, budget, cancel, collect).await;
+    (started.elapsed(), result)
+}
+
+/// [`blocking`] without the timing.
+async fn run_blocking<T, F>(
+    collector: Collector,
+    budget: Option<Duration>,
+    cancel: CancellationToken,
+    collect: F,
+) -> Result<T, ScanError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    // The blocking pool does not inherit the scan's span.
+    let span = tracing::info_span!("collector", %collector);
+    let token = cancel.clone();
+    let task = tokio::task::spawn_blocking(move || {
+        if token.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        span.in_scope(collect)
+    });
+    let joined = match budget {
+        Some(budget) => match tokio::time::timeout(budget, task).await {
+            Ok(joined) => joined,
+            Err(_) => {
+                tracing::warn!(%collector, ?budget, "Collector timed out; abandoning its thread");
+                cancel.cancel();
+                return Err(ScanError::Timeout(budget));
+            }
+        },
+        None => task.await,
+    };
+    joined
+        .map_err(|e| ScanError::Local(e.to_string()))?
+        .map_err(ScanError::from)
+}
+
+/// Unwrap an optional collector's section, recording its warnings and
+/// degrading a timeout into an empty section.
+fn optional<T: Default>(
+    collector: Collector,
+    result: Result<(T, Vec<String>), ScanError>,
+    warnings: &mut Vec<ScanWarning>,
+) -> Result<T, ScanError> {
+    match result {
+        Ok((section, messages)) => {
+            warnings.extend(messages.into_iter().map(|m| ScanWarning::new(collector, m)));
+            Ok(section)
+        }
+        Err(ScanError::Timeout(budget)) => {
+            tracing::warn!(%collector, ?budget, "Collector timed out, leaving its section empty");
+            warnings.push(ScanWarning::new(
+                collector,
+                format!("timed out after {:?}", budget),
+            ));
+            Ok(T::default())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether the process runs elevated, or `None` if that cannot be told.
+/// Logs a warning up front when it does not.
+fn check_elevation() -> Option<bool> {
+    match is_elevated() {
+        Ok(elevated) => {
+            if !elevated {
+                tracing::warn!("Not running as administrator; some sections will be incomplete");
+            }
+            Some(elevated)
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "Could not determine whether the process is elevated");
+            None
+        }
+    }
+}
+
+/// An empty section and a warning in place of a failed WMI query.
+fn or_warning<T: Default>(collector: Collector, result: Result<T, Error>) -> (T, Vec<String>) {
+    match result {
+        Ok(section) => (section, Vec::new()),
+        Err(e) => {
+            tracing::warn!(%collector, error = %e, "Collector failed, leaving its section empty");
+            (T::default(), vec![format!("query failed: {}", e)])
+        }
     }
 }
 
@@ -92,8 +846,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_local_scanner_produces_report() {
-        let scanner = LocalScanner;
-        let report = scanner.scan().await;
+        let scanner = LocalScanner::new();
+        let report = scanner.audit().await;
         assert!(
             report.is_ok(),
             "LocalScanner should succeed on a Windows machine"
@@ -108,4 +862,23 @@ mod tests {
             "os_name should not be empty"
         );
     }
+
+    #[tokio::test]
+    async fn test_local_scanner_stream_starts_with_system() {
+        let options = ScanOptions::new().updates(false);
+        let scanner = LocalScanner::with_options(options);
+        let mut items = scanner.scan_stream();
+        assert!(matches!(items.next().await, Some(Ok(ScanItem::System(_)))));
+        while let Some(item) = items.next().await {
+            assert!(!matches!(item, Ok(ScanItem::Update(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_scanner_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = LocalScanner::new().scan_with_cancel(&cancel).await;
+        assert!(matches!(result, Err(ScanError::Cancelled)));
+    }
 }
@@ -0,0 +1,175 @@
+//! Audit-trail header of a report.
+//!
+//! [`ScanMetadata`] records which release produced a report, how the
+//! host was scanned, by whom, with which options and for how long, so a
+//! stored report can be traced back to the run that made it. Scanners
+//! attach it to [`AuditReport::metadata`](crate::AuditReport::metadata).
+
+use crate::options::ScanOptions;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Instant;
+
+/// How a report was collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScannerKind {
+    /// On the audited host itself
+    Local,
+    /// Over WinRM or DCOM from another machine
+    Remote,
+}
+
+impl fmt::Display for ScannerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScannerKind::Local => write!(f, "local"),
+            ScannerKind::Remote => write!(f, "remote"),
+        }
+    }
+}
+
+/// Who produced a report, how and when.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Instant;
+/// use sysaudit::ScanOptions;
+/// use sysaudit::metadata::{ScanMetadata, ScannerKind};
+///
+/// let started = Instant::now();
+/// // ... scan ...
+/// let metadata = ScanMetadata::new(ScannerKind::Local, started, &ScanOptions::new());
+/// assert_eq!(metadata.options["sections"], "software,industrial,updates");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanMetadata {
+    /// sysaudit release that produced the report
+    pub tool_version: String,
+    /// Local or remote scan
+    pub scanner: ScannerKind,
+    /// When collection started
+    pub started: DateTime<Utc>,
+    /// When collection finished
+    pub finished: DateTime<Utc>,
+    /// Wall-clock time of the scan, in milliseconds
+    pub duration_ms: u64,
+    /// Account that ran the scan (for remote scans, the account used to
+    /// connect), if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Whether the scan ran with administrator rights, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevated: Option<bool>,
+    /// Scan options in effect, by name
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
+}
+
+impl ScanMetadata {
+    /// Metadata of a scan that started at `started` and finishes now,
+    /// run by the current user.
+    pub fn new(scanner: ScannerKind, started: Instant, options: &ScanOptions) -> Self {
+        let elapsed = started.elapsed();
+        let finished = Utc::now();
+        ScanMetadata {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            scanner,
+            started: finished - chrono::Duration::from_std(elapsed).unwrap_or_default(),
+            finished,
+            duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            user: current_user(),
+            elevated: None,
+            options: options.summary(),
+        }
+    }
+
+    /// Record the account that ran the scan.
+    pub fn user(mut self, user: Option<String>) -> Self {
+        self.user = user;
+        self
+    }
+
+    /// Record whether the scan ran elevated.
+    pub fn elevated(mut self, elevated: Option<bool>) -> Self {
+        self.elevated = elevated;
+        self
+    }
+
+    /// Labelled values for a two-column table, such as the System sheet of
+    /// a workbook.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let elevated = match self.elevated {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "unknown",
+        };
+        vec![
+            ("Tool Version", self.tool_version.clone()),
+            ("Scanner", self.scanner.to_string()),
+            ("Scan Started", self.started.to_rfc3339()),
+            ("Scan Finished", self.finished.to_rfc3339()),
+            ("Scan Duration (ms)", self.duration_ms.to_string()),
+            ("Scanned By", self.user.clone().unwrap_or_default()),
+            ("Elevated", elevated.to_string()),
+            ("Scan Options", self.options_text()),
+        ]
+    }
+
+    /// Options as `name=value` pairs, e.g. for a one-line log entry.
+    pub fn options_text(&self) -> String {
+        self.options
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// `DOMAIN\user` of this process, from the environment.
+pub fn current_user() -> Option<String> {
+    let user = std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .ok()
+        .filter(|u| !u.is_empty())?;
+    match std::env::var("USERDOMAIN") {
+        Ok(domain) if !domain.is_empty() => Some(format!(r"{}\{}", domain, user)),
+        _ => Some(user),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Collector;
+    use std::time::Duration;
+
+    #[test]
+    fn test_metadata() {
+        let options = ScanOptions::new()
+            .services(true)
+            .include_32bit(false)
+            .collector_timeout(Collector::Updates, Duration::from_secs(120));
+        let started = Instant::now() - Duration::from_secs(2);
+
+        let metadata = ScanMetadata::new(ScannerKind::Remote, started, &options)
+            .user(Some(r"PLANT\audit".to_string()))
+            .elevated(Some(true));
+        assert!(metadata.duration_ms >= 2000);
+        assert!(metadata.finished - metadata.started >= chrono::Duration::seconds(2));
+        assert_eq!(metadata.tool_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            metadata.options["sections"],
+            "software,industrial,updates,services"
+        );
+        assert_eq!(metadata.options["include_32bit"], "false");
+        assert_eq!(metadata.options["timeout.updates"], "120s");
+
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["scanner"], "remote");
+        assert_eq!(json["user"], r"PLANT\audit");
+    }
+}
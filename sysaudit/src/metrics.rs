@@ -0,0 +1,254 @@
+//! Scan telemetry.
+//!
+//! [`ScanMetrics`] records how long each collector ran and how much it
+//! found, so slow collectors can be traced to the hosts they are slow on.
+//! Local scans attach it to
+//! [`AuditReport::metrics`](crate::AuditReport::metrics); the same numbers
+//! are logged as `tracing` events at the end of a scan, and each collector
+//! runs in a `collector` span. Remote scans add the [`TransferMetrics`]
+//! of their WinRM connection, for sites that pay for every megabyte.
+
+use crate::AuditReport;
+use crate::options::Collector;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Timing and counts of one collector.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectorMetrics {
+    /// The collector
+    pub collector: Collector,
+    /// Wall-clock time it ran, in milliseconds
+    pub duration_ms: u64,
+    /// Entries it returned (1 for the system and security sections)
+    pub items: usize,
+    /// Warnings it raised
+    pub warnings: usize,
+}
+
+/// Network footprint of a remote scan.
+///
+/// Byte counts cover HTTP message bodies, including authentication
+/// rounds and retries; HTTP headers and TLS framing are not counted
+/// (`requests` gives an idea of that overhead). Agents copied over the
+/// `ADMIN$` share travel over SMB and are not counted either.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferMetrics {
+    /// Bytes sent to the host
+    pub bytes_sent: u64,
+    /// Bytes received from the host
+    pub bytes_received: u64,
+    /// HTTP requests made
+    pub requests: u64,
+    /// Size of the PowerShell payload or agent binary run on the host
+    pub payload_bytes: u64,
+    /// Size of the resulting report as JSON
+    pub report_bytes: u64,
+}
+
+impl TransferMetrics {
+    /// Bytes sent and received.
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes_sent.saturating_add(self.bytes_received)
+    }
+}
+
+impl std::ops::Add for TransferMetrics {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        TransferMetrics {
+            bytes_sent: self.bytes_sent.saturating_add(other.bytes_sent),
+            bytes_received: self.bytes_received.saturating_add(other.bytes_received),
+            requests: self.requests.saturating_add(other.requests),
+            payload_bytes: self.payload_bytes.saturating_add(other.payload_bytes),
+            report_bytes: self.report_bytes.saturating_add(other.report_bytes),
+        }
+    }
+}
+
+/// Per-collector telemetry of one scan.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::Collector;
+/// use sysaudit::metrics::ScanMetrics;
+///
+/// let mut metrics = ScanMetrics::new();
+/// let names = metrics.time(Collector::Software, || vec!["7-Zip", "Notepad++"]);
+/// assert_eq!(names.len(), 2);
+/// assert_eq!(metrics.collectors[0].collector, Collector::Software);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanMetrics {
+    /// Wall-clock time of the whole scan, in milliseconds
+    pub duration_ms: u64,
+    /// Collectors in the order they were recorded
+    pub collectors: Vec<CollectorMetrics>,
+    /// Network footprint, for remote scans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer: Option<TransferMetrics>,
+}
+
+impl ScanMetrics {
+    /// Empty metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `collect`, recording its duration under `collector`.
+    pub fn time<T>(&mut self, collector: Collector, collect: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = collect();
+        self.record(collector, started.elapsed());
+        result
+    }
+
+    /// Record that `collector` ran for `duration`. Recording a collector
+    /// again adds to its duration.
+    pub fn record(&mut self, collector: Collector, duration: Duration) {
+        let duration_ms = millis(duration);
+        match self
+            .collectors
+            .iter_mut()
+            .find(|m| m.collector == collector)
+        {
+            Some(metrics) => metrics.duration_ms += duration_ms,
+            None => self.collectors.push(CollectorMetrics {
+                collector,
+                duration_ms,
+                items: 0,
+                warnings: 0,
+            }),
+        }
+    }
+
+    /// Set the duration of the whole scan.
+    pub fn total(mut self, duration: Duration) -> Self {
+        self.duration_ms = millis(duration);
+        self
+    }
+
+    /// Attach the network footprint of a remote scan.
+    pub fn transfer(mut self, transfer: TransferMetrics) -> Self {
+        self.transfer = Some(transfer);
+        self
+    }
+
+    /// Fill in item and warning counts of the recorded collectors from the
+    /// finished `report`, and log one `tracing` event per collector.
+    pub fn count(mut self, report: &AuditReport) -> Self {
+        for metrics in &mut self.collectors {
+            metrics.items = items(report, metrics.collector);
+            metrics.warnings = report
+                .warnings
+                .iter()
+                .filter(|w| w.collector == metrics.collector)
+                .count();
+            tracing::info!(
+                collector = %metrics.collector,
+                duration_ms = metrics.duration_ms,
+                items = metrics.items,
+                warnings = metrics.warnings,
+                "Collector finished"
+            );
+        }
+        self
+    }
+
+    /// The collector that ran longest.
+    pub fn slowest(&self) -> Option<&CollectorMetrics> {
+        self.collectors.iter().max_by_key(|m| m.duration_ms)
+    }
+}
+
+/// Entries `collector` contributed to `report`.
+fn items(report: &AuditReport, collector: Collector) -> usize {
+    match collector {
+        Collector::System => 1,
+        Collector::Software => report.software.len(),
+        Collector::Industrial => report.industrial.len(),
+        Collector::Updates => report.updates.len(),
+        Collector::Services => report.services.len(),
+        Collector::Security => usize::from(report.security.is_some()),
+        Collector::UpdateHealth => usize::from(report.update_health.is_some()),
+        Collector::Licensing => report.licensing.as_ref().map_or(0, |l| l.licenses.len()),
+        Collector::EventLogs => report.event_logs.as_ref().map_or(0, |l| l.logs.len()),
+        Collector::Autopilot => usize::from(report.autopilot.is_some()),
+        Collector::Containers => report.containers.as_ref().map_or(0, |c| {
+            c.runtimes.len() + c.wsl.as_ref().map_or(0, |wsl| wsl.distributions.len())
+        }),
+        Collector::Custom => report.extensions.len(),
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ScanWarning;
+    use chrono::Utc;
+    use sysaudit_common::{SoftwareDto, SystemInfoDto};
+
+    #[test]
+    fn test_record_and_count() {
+        let mut report = AuditReport::from(sysaudit_common::SysauditReport {
+            system: SystemInfoDto {
+                os_name: "Windows 10".to_string(),
+                os_version: "10.0".to_string(),
+                host_name: "HMI-01".to_string(),
+                cpu_info: "CPU".to_string(),
+                cpu_physical_cores: None,
+                memory_total_bytes: 0,
+                memory_used_bytes: 0,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            },
+            software: vec![SoftwareDto {
+                name: "7-Zip".to_string(),
+                version: None,
+                vendor: None,
+                install_date: None,
+            }],
+            industrial: vec![],
+            timestamp: Utc::now(),
+        });
+        report
+            .warnings
+            .push(ScanWarning::new(Collector::Updates, "query failed"));
+
+        let mut metrics = ScanMetrics::new();
+        metrics.record(Collector::Software, Duration::from_millis(1200));
+        metrics.record(Collector::Updates, Duration::from_millis(300));
+        metrics.record(Collector::Software, Duration::from_millis(50));
+        let metrics = metrics.total(Duration::from_secs(2)).count(&report);
+
+        assert_eq!(metrics.duration_ms, 2000);
+        assert_eq!(
+            metrics.collectors,
+            vec![
+                CollectorMetrics {
+                    collector: Collector::Software,
+                    duration_ms: 1250,
+                    items: 1,
+                    warnings: 0,
+                },
+                CollectorMetrics {
+                    collector: Collector::Updates,
+                    duration_ms: 300,
+                    items: 0,
+                    warnings: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            metrics.slowest().map(|m| m.collector),
+            Some(Collector::Software)
+        );
+    }
+}
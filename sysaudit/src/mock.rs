@@ -0,0 +1,108 @@
+//! Fixture-backed scanner for development and tests.
+//!
+//! [`MockScanner`] implements [`Scanner`] by returning a stored report, so
+//! code built on scan results (output, diff, compliance, findings) can be
+//! exercised without Windows or a live host. Fixtures are the JSON documents
+//! `sysaudit all --format json` writes, of any schema version, or the
+//! `sysaudit-common` [`SysauditReport`](sysaudit_common::SysauditReport)
+//! wire format.
+
+use crate::Error;
+use crate::report::AuditReport;
+use crate::scanner::{ScanError, Scanner};
+use std::path::Path;
+
+/// Scanner returning a fixed report.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::{BlockingScanner, MockScanner};
+///
+/// let scanner = MockScanner::from_file("tests/fixtures/report.json").unwrap();
+/// let report = scanner.scan_blocking().unwrap();
+/// println!("{}: {} programs", report.system.host_name, report.software.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockScanner {
+    report: AuditReport,
+}
+
+impl MockScanner {
+    /// Scanner returning `report`.
+    pub fn new(report: AuditReport) -> Self {
+        MockScanner { report }
+    }
+
+    /// Scanner returning the report in `json`, upgraded to the current
+    /// schema version.
+    ///
+    /// # Errors
+    ///
+    /// As [`AuditReport::from_json_versioned`].
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        AuditReport::from_json_versioned(json).map(Self::new)
+    }
+
+    /// Scanner returning the report stored at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, otherwise as
+    /// [`from_json`](Self::from_json).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// The report every scan returns.
+    pub fn report(&self) -> &AuditReport {
+        &self.report
+    }
+}
+
+impl Scanner for MockScanner {
+    async fn audit(&self) -> Result<AuditReport, ScanError> {
+        Ok(self.report.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::BlockingScanner;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/report.json");
+
+    #[test]
+    fn test_scan_returns_fixture() {
+        let scanner = MockScanner::from_json(FIXTURE).unwrap();
+        let report = scanner.scan_blocking().unwrap();
+
+        assert_eq!(report.system.host_name, "PLANT-HMI-01");
+        assert_eq!(report.software.len(), 2);
+        assert_eq!(report.updates.len(), 1);
+        assert_eq!(report.services.len(), 1);
+        assert!(report.security.is_some());
+    }
+
+    #[test]
+    fn test_from_json_upgrades_legacy_report() {
+        // Version 1 documents have only the DTO sections.
+        let mut legacy: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        let object = legacy.as_object_mut().unwrap();
+        object.retain(|key, _| {
+            ["system", "software", "industrial", "timestamp"].contains(&key.as_str())
+        });
+        let scanner = MockScanner::from_json(&legacy.to_string()).unwrap();
+
+        assert_eq!(
+            scanner.report().schema_version,
+            crate::REPORT_SCHEMA_VERSION
+        );
+        assert!(scanner.report().updates.is_empty());
+        assert!(matches!(
+            MockScanner::from_file("missing.json"),
+            Err(Error::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,754 @@
+//! Choosing what a scan collects.
+//!
+//! [`ScanOptions`] is accepted by [`LocalScanner`](crate::LocalScanner) and
+//! the remote scanners: which [`Sections`] to collect plus per-collector
+//! settings such as time budgets, cache lifetimes and redaction.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::Error;
+#[cfg(all(windows, feature = "local"))]
+use crate::cache::CacheSlot;
+use crate::redact::RedactionPolicy;
+#[cfg(all(windows, any(feature = "local", feature = "remote")))]
+use crate::report::AuditReport;
+use crate::score::ScoreWeights;
+
+/// One of the collectors a scan runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Collector {
+    /// System information (OS, hardware, network)
+    System,
+    /// Installed software
+    Software,
+    /// Industrial software
+    Industrial,
+    /// Windows updates
+    Updates,
+    /// Windows services
+    Services,
+    /// Security settings (BitLocker)
+    Security,
+    /// Windows Update, BITS and SCCM client health
+    UpdateHealth,
+    /// Windows edition, license channels and activation
+    Licensing,
+    /// Event log sizes, retention and forwarding
+    EventLogs,
+    /// Autopilot hardware hash, serial number and model
+    Autopilot,
+    /// WSL distributions and container runtimes
+    Containers,
+    /// Custom collectors registered on the scanner
+    Custom,
+}
+
+impl std::fmt::Display for Collector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Collector::System => write!(f, "system"),
+            Collector::Software => write!(f, "software"),
+            Collector::Industrial => write!(f, "industrial"),
+            Collector::Updates => write!(f, "updates"),
+            Collector::Services => write!(f, "services"),
+            Collector::Security => write!(f, "security"),
+            Collector::UpdateHealth => write!(f, "update_health"),
+            Collector::Licensing => write!(f, "licensing"),
+            Collector::EventLogs => write!(f, "event_logs"),
+            Collector::Autopilot => write!(f, "autopilot"),
+            Collector::Containers => write!(f, "containers"),
+            Collector::Custom => write!(f, "custom"),
+        }
+    }
+}
+
+/// Report sections a scan collects.
+///
+/// System information is always collected; the other sections can be
+/// switched off (their arrays are then empty in the report). The default
+/// collects software and industrial software, as earlier releases did.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::Sections;
+///
+/// let health_check = Sections::system_only().updates(true);
+/// assert!(!health_check.has_software());
+/// assert_eq!(Sections::parse("software,services").unwrap(), Sections::system_only().software(true).services(true));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sections {
+    software: bool,
+    industrial: bool,
+    updates: bool,
+    services: bool,
+    security: bool,
+    update_health: bool,
+    licensing: bool,
+    event_logs: bool,
+    autopilot: bool,
+    containers: bool,
+}
+
+impl Default for Sections {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sections {
+    /// System, software and industrial software.
+    pub const fn new() -> Self {
+        Sections {
+            software: true,
+            industrial: true,
+            updates: false,
+            services: false,
+            security: false,
+            update_health: false,
+            licensing: false,
+            event_logs: false,
+            autopilot: false,
+            containers: false,
+        }
+    }
+
+    /// System information only.
+    pub const fn system_only() -> Self {
+        Sections {
+            software: false,
+            industrial: false,
+            updates: false,
+            services: false,
+            security: false,
+            update_health: false,
+            licensing: false,
+            event_logs: false,
+            autopilot: false,
+            containers: false,
+        }
+    }
+
+    /// Every section.
+    pub const fn all() -> Self {
+        Sections {
+            software: true,
+            industrial: true,
+            updates: true,
+            services: true,
+            security: true,
+            update_health: true,
+            licensing: true,
+            event_logs: true,
+            autopilot: true,
+            containers: true,
+        }
+    }
+
+    /// Collect installed software.
+    pub const fn software(mut self, enabled: bool) -> Self {
+        self.software = enabled;
+        self
+    }
+
+    /// Collect industrial software.
+    pub const fn industrial(mut self, enabled: bool) -> Self {
+        self.industrial = enabled;
+        self
+    }
+
+    /// Collect installed Windows updates.
+    pub const fn updates(mut self, enabled: bool) -> Self {
+        self.updates = enabled;
+        self
+    }
+
+    /// Collect Windows services.
+    pub const fn services(mut self, enabled: bool) -> Self {
+        self.services = enabled;
+        self
+    }
+
+    /// Collect security settings.
+    pub const fn security(mut self, enabled: bool) -> Self {
+        self.security = enabled;
+        self
+    }
+
+    /// Collect Windows Update agent health.
+    pub const fn update_health(mut self, enabled: bool) -> Self {
+        self.update_health = enabled;
+        self
+    }
+
+    /// Collect the Windows edition, license channels and activation status.
+    pub const fn licensing(mut self, enabled: bool) -> Self {
+        self.licensing = enabled;
+        self
+    }
+
+    /// Collect event log sizes, retention and forwarding.
+    pub const fn event_logs(mut self, enabled: bool) -> Self {
+        self.event_logs = enabled;
+        self
+    }
+
+    /// Collect the Autopilot registration data (needs administrator
+    /// rights for the hardware hash).
+    pub const fn autopilot(mut self, enabled: bool) -> Self {
+        self.autopilot = enabled;
+        self
+    }
+
+    /// Collect WSL distributions and container runtimes.
+    pub const fn containers(mut self, enabled: bool) -> Self {
+        self.containers = enabled;
+        self
+    }
+
+    /// Whether installed software is collected.
+    pub fn has_software(&self) -> bool {
+        self.software
+    }
+
+    /// Whether industrial software is collected.
+    pub fn has_industrial(&self) -> bool {
+        self.industrial
+    }
+
+    /// Whether Windows updates are collected.
+    pub fn has_updates(&self) -> bool {
+        self.updates
+    }
+
+    /// Whether Windows services are collected.
+    pub fn has_services(&self) -> bool {
+        self.services
+    }
+
+    /// Whether security settings are collected.
+    pub fn has_security(&self) -> bool {
+        self.security
+    }
+
+    /// Whether Windows Update agent health is collected.
+    pub fn has_update_health(&self) -> bool {
+        self.update_health
+    }
+
+    /// Whether licensing and activation status is collected.
+    pub fn has_licensing(&self) -> bool {
+        self.licensing
+    }
+
+    /// Whether the event log configuration is collected.
+    pub fn has_event_logs(&self) -> bool {
+        self.event_logs
+    }
+
+    /// Whether the Autopilot registration data is collected.
+    pub fn has_autopilot(&self) -> bool {
+        self.autopilot
+    }
+
+    /// Whether WSL and the container runtimes are collected.
+    pub fn has_containers(&self) -> bool {
+        self.containers
+    }
+
+    /// Parse a comma-separated list of section names (`system`,
+    /// `software`, `industrial`, `updates`, `services`, `security`,
+    /// `update_health`, `licensing`, `event_logs`, `autopilot`, `containers`,
+    /// or `all`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown section name.
+    pub fn parse(list: &str) -> Result<Self, Error> {
+        let mut sections = Self::system_only();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            sections = match name.to_lowercase().as_str() {
+                "system" => sections,
+                "software" => sections.software(true),
+                "industrial" => sections.industrial(true),
+                "updates" => sections.updates(true),
+                "services" => sections.services(true),
+                "security" => sections.security(true),
+                "update_health" | "update-health" => sections.update_health(true),
+                "licensing" => sections.licensing(true),
+                "event_logs" | "event-logs" => sections.event_logs(true),
+                "autopilot" => sections.autopilot(true),
+                "containers" => sections.containers(true),
+                "all" => Self::all(),
+                other => {
+                    return Err(Error::General(format!(
+                        "unknown section '{}' (use system, software, industrial, updates, services, security, update_health, licensing, event_logs, autopilot, containers or all)",
+                        other
+                    )));
+                }
+            };
+        }
+        Ok(sections)
+    }
+
+    /// Section names as the payload expects them.
+    pub(crate) fn names(&self) -> Vec<&'static str> {
+        [
+            (self.software, "software"),
+            (self.industrial, "industrial"),
+            (self.updates, "updates"),
+            (self.services, "services"),
+            (self.security, "security"),
+            (self.update_health, "update_health"),
+            (self.licensing, "licensing"),
+            (self.event_logs, "event_logs"),
+            (self.autopilot, "autopilot"),
+            (self.containers, "containers"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, name)| name)
+        .collect()
+    }
+}
+
+/// Which collectors a scan runs and how they are configured.
+///
+/// System information is always collected. The default collects
+/// everything [`LocalScanner`](crate::LocalScanner) always has: software,
+/// industrial software and Windows updates.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::ScanOptions;
+///
+/// // CLI-style selection: everything but the slow update query.
+/// let options = ScanOptions::new().skip("updates").unwrap();
+/// assert!(!options.selected().has_updates());
+///
+/// let quick = ScanOptions::new().only("software").unwrap().include_user_installs(false);
+/// assert!(!quick.selected().has_industrial());
+/// ```
+///
+/// Cap slow collectors so one hung WMI repository cannot stall a scan:
+///
+/// ```
+/// use std::time::Duration;
+/// use sysaudit::{Collector, ScanOptions};
+///
+/// let options = ScanOptions::new()
+///     .timeout(Duration::from_secs(60))
+///     .collector_timeout(Collector::Updates, Duration::from_secs(20));
+/// assert_eq!(options.timeout_for(Collector::Updates), Some(Duration::from_secs(20)));
+/// ```
+///
+/// Reuse results of repeated scans for ten minutes, and the installed
+/// software for an hour:
+///
+/// ```
+/// use std::time::Duration;
+/// use sysaudit::{Collector, ScanOptions};
+///
+/// let options = ScanOptions::new()
+///     .cache(Duration::from_secs(600))
+///     .collector_cache(Collector::Software, Duration::from_secs(3600));
+/// assert_eq!(options.cache_ttl_for(Collector::Updates), Some(Duration::from_secs(600)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    sections: Sections,
+    include_user_installs: bool,
+    include_32bit: bool,
+    timeout: Option<Duration>,
+    collector_timeouts: Vec<(Collector, Duration)>,
+    score_weights: ScoreWeights,
+    cache_ttl: Option<Duration>,
+    collector_cache_ttls: Vec<(Collector, Duration)>,
+    cache_dir: Option<PathBuf>,
+    redaction: Option<RedactionPolicy>,
+    asset_id: Option<String>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Sections> for ScanOptions {
+    fn from(sections: Sections) -> Self {
+        Self::new().sections(sections)
+    }
+}
+
+impl ScanOptions {
+    /// Software, industrial software and updates with default settings.
+    pub const fn new() -> Self {
+        ScanOptions {
+            sections: Sections::new().updates(true),
+            include_user_installs: true,
+            include_32bit: true,
+            timeout: None,
+            collector_timeouts: Vec::new(),
+            score_weights: ScoreWeights::DEFAULT,
+            cache_ttl: None,
+            collector_cache_ttls: Vec::new(),
+            cache_dir: None,
+            redaction: None,
+            asset_id: None,
+        }
+    }
+
+    /// Collect exactly these sections.
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Collect installed software.
+    pub fn software(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.software(enabled);
+        self
+    }
+
+    /// Collect industrial software.
+    pub fn industrial(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.industrial(enabled);
+        self
+    }
+
+    /// Collect installed Windows updates.
+    pub fn updates(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.updates(enabled);
+        self
+    }
+
+    /// Collect Windows services.
+    pub fn services(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.services(enabled);
+        self
+    }
+
+    /// Collect security settings.
+    pub fn security(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.security(enabled);
+        self
+    }
+
+    /// Collect Windows Update agent health.
+    pub fn update_health(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.update_health(enabled);
+        self
+    }
+
+    /// Collect the Windows edition, license channels and activation status.
+    pub fn licensing(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.licensing(enabled);
+        self
+    }
+
+    /// Collect event log sizes, retention and forwarding.
+    pub fn event_logs(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.event_logs(enabled);
+        self
+    }
+
+    /// Collect the Autopilot registration data.
+    pub fn autopilot(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.autopilot(enabled);
+        self
+    }
+
+    /// Collect WSL distributions and container runtimes.
+    pub fn containers(mut self, enabled: bool) -> Self {
+        self.sections = self.sections.containers(enabled);
+        self
+    }
+
+    /// Include per-user (HKCU) installations in the software inventory
+    /// (local scans only).
+    pub fn include_user_installs(mut self, include: bool) -> Self {
+        self.include_user_installs = include;
+        self
+    }
+
+    /// Include 32-bit software on 64-bit Windows (local scans only).
+    pub fn include_32bit(mut self, include: bool) -> Self {
+        self.include_32bit = include;
+        self
+    }
+
+    /// Collect only the sections in a comma-separated list (see
+    /// [`Sections::parse`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown section name.
+    pub fn only(self, list: &str) -> Result<Self, Error> {
+        Ok(self.sections(Sections::parse(list)?))
+    }
+
+    /// Stop collecting the sections in a comma-separated list.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown section name, or for
+    /// `system`, which is always collected.
+    pub fn skip(mut self, list: &str) -> Result<Self, Error> {
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if name.eq_ignore_ascii_case("system") {
+                return Err(Error::General(
+                    "the system section is always collected and cannot be skipped".to_string(),
+                ));
+            }
+            let skipped = Sections::parse(name)?;
+            self.sections = Sections {
+                software: self.sections.software && !skipped.software,
+                industrial: self.sections.industrial && !skipped.industrial,
+                updates: self.sections.updates && !skipped.updates,
+                services: self.sections.services && !skipped.services,
+                security: self.sections.security && !skipped.security,
+                update_health: self.sections.update_health && !skipped.update_health,
+                licensing: self.sections.licensing && !skipped.licensing,
+                event_logs: self.sections.event_logs && !skipped.event_logs,
+                autopilot: self.sections.autopilot && !skipped.autopilot,
+                containers: self.sections.containers && !skipped.containers,
+            };
+        }
+        Ok(self)
+    }
+
+    /// The sections that will be collected.
+    pub fn selected(&self) -> Sections {
+        self.sections
+    }
+
+    /// Whether per-user installations are inventoried.
+    pub fn has_user_installs(&self) -> bool {
+        self.include_user_installs
+    }
+
+    /// Whether 32-bit software is inventoried.
+    pub fn has_32bit(&self) -> bool {
+        self.include_32bit
+    }
+
+    /// Give up on any collector still running after `timeout` (default: no
+    /// limit). A timed-out section is left empty; system information is
+    /// required, so its timeout fails the scan.
+    ///
+    /// Registry collectors stop at their next cancellation check, but a
+    /// collector blocked in a WMI call cannot be interrupted: its thread
+    /// stays busy in the background until the call returns.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Time budget for one collector, overriding [`timeout`](Self::timeout).
+    pub fn collector_timeout(mut self, collector: Collector, timeout: Duration) -> Self {
+        self.collector_timeouts.retain(|(c, _)| *c != collector);
+        self.collector_timeouts.push((collector, timeout));
+        self
+    }
+
+    /// The time budget of `collector`, if it has one.
+    pub fn timeout_for(&self, collector: Collector) -> Option<Duration> {
+        self.collector_timeouts
+            .iter()
+            .find(|(c, _)| *c == collector)
+            .map(|(_, timeout)| *timeout)
+            .or(self.timeout)
+    }
+
+    /// Reuse each collector's results from an on-disk cache for `ttl`
+    /// (default: no caching). See [`crate::cache`].
+    pub fn cache(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Cache lifetime for one collector, overriding [`cache`](Self::cache);
+    /// a zero `ttl` disables caching for it.
+    pub fn collector_cache(mut self, collector: Collector, ttl: Duration) -> Self {
+        self.collector_cache_ttls.retain(|(c, _)| *c != collector);
+        self.collector_cache_ttls.push((collector, ttl));
+        self
+    }
+
+    /// Keep the cache in `dir` instead of [`cache::default_dir`](crate::cache::default_dir).
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Cache lifetime for one collector, if its results are cached.
+    pub fn cache_ttl_for(&self, collector: Collector) -> Option<Duration> {
+        self.collector_cache_ttls
+            .iter()
+            .find(|(c, _)| *c == collector)
+            .map(|(_, ttl)| *ttl)
+            .or(self.cache_ttl)
+            .filter(|ttl| !ttl.is_zero())
+    }
+
+    /// Cache slot for `collector`, whose results depend on `key`.
+    #[cfg(all(windows, feature = "local"))]
+    pub(crate) fn cache_slot(&self, collector: Collector, key: &str) -> Option<CacheSlot> {
+        let ttl = self.cache_ttl_for(collector)?;
+        let dir = self
+            .cache_dir
+            .clone()
+            .unwrap_or_else(crate::cache::default_dir);
+        Some(CacheSlot::new(&dir, collector, key, ttl))
+    }
+
+    /// Weights for the report's [`AuditScore`](crate::score::AuditScore).
+    pub fn score_weights(mut self, weights: ScoreWeights) -> Self {
+        self.score_weights = weights;
+        self
+    }
+
+    /// Score weights for a scan with these options: patching does not
+    /// count when updates are not collected.
+    pub fn effective_score_weights(&self) -> ScoreWeights {
+        let mut weights = self.score_weights;
+        if !self.sections.has_updates() {
+            weights.patching = 0;
+        }
+        weights
+    }
+
+    /// Hash or drop identifying values in the produced report (default:
+    /// keep everything).
+    pub fn redaction(mut self, policy: RedactionPolicy) -> Self {
+        self.redaction = Some(policy);
+        self
+    }
+
+    /// The redaction policy, if one is set.
+    pub fn redaction_policy(&self) -> Option<&RedactionPolicy> {
+        self.redaction.as_ref()
+    }
+
+    /// Report `id` as the asset ID instead of the one derived from the
+    /// hardware (see [`asset`](crate::asset)), e.g. the tag from the site's
+    /// asset register.
+    pub fn asset_id(mut self, id: impl Into<String>) -> Self {
+        self.asset_id = Some(id.into());
+        self
+    }
+
+    /// The asset ID override, if one is set.
+    pub fn asset_id_override(&self) -> Option<&str> {
+        self.asset_id.as_deref()
+    }
+
+    /// The options as name/value pairs, for a report's
+    /// [`ScanMetadata`](crate::metadata::ScanMetadata). Unset timeouts and
+    /// cache lifetimes are left out.
+    pub fn summary(&self) -> BTreeMap<String, String> {
+        let seconds = |d: &Duration| format!("{}s", d.as_secs());
+        let names = self.sections.names();
+        let mut summary = BTreeMap::from([
+            (
+                "sections".to_string(),
+                if names.is_empty() {
+                    "system".to_string()
+                } else {
+                    names.join(",")
+                },
+            ),
+            (
+                "include_user_installs".to_string(),
+                self.include_user_installs.to_string(),
+            ),
+            ("include_32bit".to_string(), self.include_32bit.to_string()),
+        ]);
+        if let Some(timeout) = &self.timeout {
+            summary.insert("timeout".to_string(), seconds(timeout));
+        }
+        for (collector, timeout) in &self.collector_timeouts {
+            summary.insert(format!("timeout.{}", collector), seconds(timeout));
+        }
+        if let Some(ttl) = &self.cache_ttl {
+            summary.insert("cache".to_string(), seconds(ttl));
+        }
+        for (collector, ttl) in &self.collector_cache_ttls {
+            summary.insert(format!("cache.{}", collector), seconds(ttl));
+        }
+        if self.redaction.is_some() {
+            summary.insert("redaction".to_string(), "on".to_string());
+        }
+        if let Some(id) = &self.asset_id {
+            summary.insert("asset_id".to_string(), id.clone());
+        }
+        summary
+    }
+
+    /// Set the asset ID override, if any, on a finished report.
+    #[cfg(all(windows, any(feature = "local", feature = "remote")))]
+    pub(crate) fn apply_asset_id(&self, report: &mut AuditReport) {
+        if let Some(id) = &self.asset_id {
+            report.asset_id = Some(id.clone());
+        }
+    }
+
+    /// Apply the redaction policy, if any, to a finished report.
+    #[cfg(all(windows, any(feature = "local", feature = "remote")))]
+    pub(crate) fn redact(&self, report: &mut AuditReport) {
+        if let Some(policy) = &self.redaction {
+            policy.apply(report);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_and_skip() {
+        let options = ScanOptions::new().skip("updates, industrial").unwrap();
+        assert_eq!(options.selected(), Sections::system_only().software(true));
+
+        let options = ScanOptions::new()
+            .only("services")
+            .unwrap()
+            .skip("all")
+            .unwrap();
+        assert_eq!(options.selected(), Sections::system_only());
+
+        assert!(ScanOptions::new().skip("system").is_err());
+        assert!(ScanOptions::new().only("registry").is_err());
+        assert_eq!(
+            ScanOptions::from(Sections::new()).selected(),
+            Sections::new()
+        );
+    }
+
+    #[test]
+    fn test_collector_timeouts() {
+        let options = ScanOptions::new()
+            .collector_timeout(Collector::Updates, Duration::from_secs(5))
+            .timeout(Duration::from_secs(60))
+            .collector_timeout(Collector::Updates, Duration::from_secs(20));
+
+        assert_eq!(
+            options.timeout_for(Collector::Updates),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            options.timeout_for(Collector::System),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(ScanOptions::new().timeout_for(Collector::Services), None);
+        assert_eq!(Collector::Industrial.to_string(), "industrial");
+    }
+}
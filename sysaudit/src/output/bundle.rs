@@ -0,0 +1,261 @@
+//! Full-report bundle export.
+//!
+//! A bundle holds one CSV per section plus the complete JSON report:
+//!
+//! ```text
+//! sysaudit_<host>_<YYYYMMDD_HHMMSS>/
+//!   system.csv
+//!   software.csv
+//!   industrial.csv
+//!   updates.csv
+//!   report.json
+//!   report.json.sig   (only when a signature is attached)
+//! ```
+
+use super::csv_output::{CsvDialect, CsvExporter};
+use crate::{AuditReport, Error, IndustrialSoftware, Software, SystemInfo, WindowsUpdate};
+use std::path::{Path, PathBuf};
+
+/// `sysaudit_<host>_<YYYYMMDD_HHMMSS>` for a report.
+pub(crate) fn report_name(report: &AuditReport) -> String {
+    format!(
+        "sysaudit_{}_{}",
+        file_safe(&report.system.host_name),
+        report.timestamp.format("%Y%m%d_%H%M%S")
+    )
+}
+
+/// Host name reduced to characters safe in file names.
+pub(crate) fn file_safe(host: &str) -> String {
+    host.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Everything collected by a full audit, ready to be written as a bundle.
+pub struct ReportBundle<'a> {
+    system: &'a SystemInfo,
+    software: &'a [Software],
+    industrial: &'a [IndustrialSoftware],
+    updates: &'a [WindowsUpdate],
+    report: &'a AuditReport,
+    signature: Option<String>,
+}
+
+impl<'a> ReportBundle<'a> {
+    /// Create a bundle over collected data and the report built from it.
+    pub fn new(
+        system: &'a SystemInfo,
+        software: &'a [Software],
+        industrial: &'a [IndustrialSoftware],
+        updates: &'a [WindowsUpdate],
+        report: &'a AuditReport,
+    ) -> Self {
+        ReportBundle {
+            system,
+            software,
+            industrial,
+            updates,
+            report,
+            signature: None,
+        }
+    }
+
+    /// Attach a detached signature, written as `report.json.sig`.
+    pub fn signature(mut self, signature: String) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Bundle name: `sysaudit_<host>_<YYYYMMDD_HHMMSS>` from the report timestamp.
+    pub fn name(&self) -> String {
+        report_name(self.report)
+    }
+
+    /// Write the bundle into a new timestamped directory under `parent`.
+    ///
+    /// Returns the path of the created directory.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{AuditReport, SystemInfo, output::{CsvDialect, ReportBundle}};
+    /// use std::path::Path;
+    ///
+    /// let info = SystemInfo::collect().unwrap();
+    /// let report = AuditReport::from_local(info.clone(), vec![], vec![], vec![]);
+    /// let bundle = ReportBundle::new(&info, &[], &[], &[], &report);
+    /// let dir = bundle.export_dir(Path::new("audits"), &CsvDialect::default()).unwrap();
+    /// println!("Written to {}", dir.display());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the directory or any file cannot be written.
+    pub fn export_dir(&self, parent: &Path, dialect: &CsvDialect) -> Result<PathBuf, Error> {
+        let dir = parent.join(self.name());
+        std::fs::create_dir_all(&dir)?;
+
+        for (name, bytes) in self.entries(dialect)? {
+            std::fs::write(dir.join(name), bytes)?;
+        }
+
+        tracing::debug!(path = %dir.display(), "Report bundle written");
+        Ok(dir)
+    }
+
+    /// Write the bundle as a ZIP archive at `path`.
+    ///
+    /// Files are stored under a top-level folder named after [`ReportBundle::name`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the archive cannot be created or written.
+    #[cfg(feature = "zip")]
+    pub fn export_zip(&self, path: &Path, dialect: &CsvDialect) -> Result<(), Error> {
+        use std::io::Write;
+        use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+        let folder = self.name();
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(std::fs::File::create(path)?);
+
+        for (name, bytes) in self.entries(dialect)? {
+            zip.start_file(format!("{}/{}", folder, name), options)?;
+            zip.write_all(&bytes)?;
+        }
+
+        zip.finish()?;
+        tracing::debug!(path = %path.display(), "Report bundle archived");
+        Ok(())
+    }
+
+    /// Render every bundle file in memory as `(file name, contents)`.
+    fn entries(&self, dialect: &CsvDialect) -> Result<Vec<(&'static str, Vec<u8>)>, Error> {
+        let mut system = dialect.writer_for(Vec::new())?;
+        CsvExporter::write_system(&mut system, self.system)?;
+
+        let mut software = dialect.writer_for(Vec::new())?;
+        CsvExporter::write_software(&mut software, self.software)?;
+
+        let mut industrial = dialect.writer_for(Vec::new())?;
+        CsvExporter::write_industrial(&mut industrial, self.industrial)?;
+
+        let mut updates = dialect.writer_for(Vec::new())?;
+        CsvExporter::write_updates(&mut updates, self.updates)?;
+
+        let mut entries = vec![
+            ("system.csv", into_bytes(system)?),
+            ("software.csv", into_bytes(software)?),
+            ("industrial.csv", into_bytes(industrial)?),
+            ("updates.csv", into_bytes(updates)?),
+            ("report.json", serde_json::to_vec_pretty(self.report)?),
+        ];
+        if let Some(signature) = &self.signature {
+            entries.push(("report.json.sig", signature.clone().into_bytes()));
+        }
+        Ok(entries)
+    }
+}
+
+fn into_bytes(wtr: csv::Writer<Vec<u8>>) -> Result<Vec<u8>, Error> {
+    wtr.into_inner().map_err(|e| Error::Io(e.into_error()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RegistrySource, Vendor};
+    use chrono::{TimeZone, Utc};
+
+    fn sample_system() -> SystemInfo {
+        SystemInfo {
+            os_name: "Windows 11 Pro".into(),
+            os_version: "23H2".into(),
+            build_number: "22631".into(),
+            computer_name: "TEST PC".into(),
+            domain: None,
+            identity: Default::default(),
+            fingerprint: Default::default(),
+            cpu_info: "Intel i7".into(),
+            network_interfaces: vec![],
+            manufacturer: None,
+            model: None,
+            cpu_cores_physical: Some(8),
+            cpu_cores_logical: Some(16),
+            cpu_frequency_mhz: 3000,
+            memory_total: 1000,
+            memory_used: 500,
+            memory_free: 500,
+        }
+    }
+
+    #[test]
+    fn test_export_dir_writes_all_files() {
+        let system = sample_system();
+        let software = vec![Software {
+            name: "TestApp".into(),
+            version: Some("1.0".into()),
+            publisher: None,
+            install_date: None,
+            install_date_source: crate::InstallDateSource::Unknown,
+            install_location: None,
+            source: RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
+        }];
+        let industrial = vec![IndustrialSoftware {
+            vendor: Vendor::Siemens,
+            product: "TIA Portal".into(),
+            version: None,
+            install_path: None,
+            runtime_state: None,
+        }];
+        let mut report =
+            AuditReport::from_local(system.clone(), software.clone(), industrial.clone(), vec![]);
+        report.timestamp = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+
+        let bundle = ReportBundle::new(&system, &software, &industrial, &[], &report);
+        assert_eq!(bundle.name(), "sysaudit_TEST_PC_20240301_123000");
+
+        let parent = std::env::temp_dir().join("sysaudit_test_bundle");
+        let dir = bundle.export_dir(&parent, &CsvDialect::default()).unwrap();
+
+        for name in [
+            "system.csv",
+            "software.csv",
+            "industrial.csv",
+            "updates.csv",
+            "report.json",
+        ] {
+            assert!(dir.join(name).is_file(), "missing {}", name);
+        }
+
+        let software_csv = std::fs::read_to_string(dir.join("software.csv")).unwrap();
+        assert!(software_csv.contains("TestApp,1.0"));
+        let json = std::fs::read_to_string(dir.join("report.json")).unwrap();
+        let parsed: AuditReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.industrial[0].product, "TIA Portal");
+
+        std::fs::remove_dir_all(&parent).ok();
+    }
+
+    #[test]
+    fn test_system_csv_rows() {
+        let system = sample_system();
+        let report = AuditReport::from_local(system.clone(), vec![], vec![], vec![]);
+        let bundle = ReportBundle::new(&system, &[], &[], &[], &report);
+
+        let entries = bundle.entries(&CsvDialect::default()).unwrap();
+        let (_, bytes) = entries.iter().find(|(n, _)| *n == "system.csv").unwrap();
+        let csv = String::from_utf8(bytes.clone()).unwrap();
+        assert!(csv.starts_with("Field,Value\n"));
+        assert!(csv.contains("Computer Name,TEST PC\n"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Column selection for tabular output.
+
+use crate::{Error, IndustrialSoftware, Software, WindowsUpdate};
+
+/// A row type that can be rendered as selectable columns.
+pub trait Tabular {
+    /// Available columns as `(key, header)` pairs in default order.
+    const COLUMNS: &'static [(&'static str, &'static str)];
+
+    /// Value for the column with the given key, if present.
+    fn value(&self, key: &str) -> Option<String>;
+}
+
+/// Ordered list of columns chosen by the user (e.g. `name,version,publisher`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    keys: Vec<String>,
+}
+
+impl ColumnSpec {
+    /// Parse a comma-separated column list.
+    ///
+    /// Keys are case-insensitive and `-` is accepted in place of `_`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::output::ColumnSpec;
+    ///
+    /// let spec = ColumnSpec::parse("Name, install-date");
+    /// assert_eq!(spec.keys(), ["name", "install_date"]);
+    /// ```
+    pub fn parse(spec: &str) -> Self {
+        let keys = spec
+            .split(',')
+            .map(|k| k.trim().to_lowercase().replace('-', "_"))
+            .filter(|k| !k.is_empty())
+            .collect();
+        ColumnSpec { keys }
+    }
+
+    /// Selected column keys, in output order.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Resolve the selection against a row type's columns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if a key is not a column of `T` or no
+    /// columns were selected.
+    pub fn resolve<T: Tabular>(&self) -> Result<Vec<(&'static str, &'static str)>, Error> {
+        if self.keys.is_empty() {
+            return Err(Error::General("no columns selected".to_string()));
+        }
+
+        self.keys
+            .iter()
+            .map(|key| {
+                T::COLUMNS
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .copied()
+                    .ok_or_else(|| {
+                        let available: Vec<&str> = T::COLUMNS.iter().map(|(k, _)| *k).collect();
+                        Error::General(format!(
+                            "unknown column '{}' (available: {})",
+                            key,
+                            available.join(", ")
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Tabular for Software {
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("name", "Name"),
+        ("version", "Version"),
+        ("publisher", "Publisher"),
+        ("install_date", "Install Date"),
+        ("install_date_source", "Date Source"),
+        ("install_location", "Install Location"),
+        ("source", "Source"),
+        ("support_status", "Support"),
+        ("eol", "EOL"),
+    ];
+
+    fn value(&self, key: &str) -> Option<String> {
+        match key {
+            "name" => Some(self.name.clone()),
+            "version" => self.version.clone(),
+            "publisher" => self.publisher.clone(),
+            "install_date" => self.install_date.map(|d| d.to_string()),
+            "install_date_source" => Some(self.install_date_source.to_string()),
+            "install_location" => self
+                .install_location
+                .as_ref()
+                .map(|p| p.display().to_string()),
+            "source" => Some(self.source.to_string()),
+            "support_status" => self.support_status.map(|s| s.to_string()),
+            "eol" => self.eol.map(|d| d.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Tabular for IndustrialSoftware {
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("vendor", "Vendor"),
+        ("product", "Product"),
+        ("version", "Version"),
+        ("install_path", "Install Path"),
+        ("runtime_state", "Runtime"),
+    ];
+
+    fn value(&self, key: &str) -> Option<String> {
+        match key {
+            "vendor" => Some(self.vendor.to_string()),
+            "product" => Some(self.product.clone()),
+            "version" => self.version.clone(),
+            "install_path" => self.install_path.as_ref().map(|p| p.display().to_string()),
+            "runtime_state" => self.runtime_state.map(|s| s.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Tabular for WindowsUpdate {
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("hotfix_id", "HotFix ID"),
+        ("description", "Description"),
+        ("installed_on", "Installed On"),
+        ("installed_by", "Installed By"),
+    ];
+
+    fn value(&self, key: &str) -> Option<String> {
+        match key {
+            "hotfix_id" => Some(self.hotfix_id.clone()),
+            "description" => self.description.clone(),
+            "installed_on" => self.installed_on.map(|d| d.to_string()),
+            "installed_by" => self.installed_by.clone(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalizes_keys() {
+        let spec = ColumnSpec::parse(" Name ,INSTALL-DATE,, source");
+        assert_eq!(spec.keys(), ["name", "install_date", "source"]);
+    }
+
+    #[test]
+    fn test_resolve_keeps_order() {
+        let spec = ColumnSpec::parse("publisher,name");
+        let columns = spec.resolve::<Software>().unwrap();
+        assert_eq!(columns, vec![("publisher", "Publisher"), ("name", "Name")]);
+    }
+
+    #[test]
+    fn test_resolve_unknown_column() {
+        let spec = ColumnSpec::parse("name,colour");
+        let err = spec.resolve::<Software>().unwrap_err();
+        assert!(err.to_string().contains("unknown column 'colour'"));
+    }
+
+    #[test]
+    fn test_resolve_empty() {
+        assert!(ColumnSpec::parse(" , ").resolve::<WindowsUpdate>().is_err());
+    }
+}
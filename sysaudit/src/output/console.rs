@@ -1,32 +1,132 @@
 //! Console output formatting.
+//!
+//! Rows that need attention are styled: an out-of-support OS in red,
+//! interfaces without a default gateway dimmed and industrial detections
+//! highlighted. Styling follows [`ColorChoice`]; by default it is only
+//! applied when stdout is a terminal.
 
-use crate::{IndustrialSoftware, Software, SystemInfo, WindowsUpdate};
-use comfy_table::{ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use super::columns::{ColumnSpec, Tabular};
+use crate::allowlist::{AllowStatus, ClassifiedSoftware};
+use crate::compliance::{ComplianceReport, RuleStatus};
+#[cfg(feature = "remote")]
+use crate::fleet::HostResult;
+use crate::fleet_report::PatchCurrency;
+use crate::metrics::{ScanMetrics, TransferMetrics};
+use crate::report::{ChangeKind, ReportDiff};
+use crate::score::AuditScore;
+use crate::{
+    AuditReport, Error, Finding, FleetReport, IndustrialSoftware, OsSupport, RuntimeState,
+    Severity, Software, SupportStatus, SystemInfo, WindowsService, WindowsUpdate,
+};
+use chrono::{NaiveDate, Utc};
+use comfy_table::{
+    Attribute, Cell, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
+    presets::UTF8_FULL,
+};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 /// Max table width in characters
 const MAX_TABLE_WIDTH: u16 = 120;
 
+/// Findings listed in the summary panel
+const SUMMARY_FINDINGS: usize = 5;
+
+/// Software rows listed in the fleet rollup
+const FLEET_TOP_SOFTWARE: usize = 20;
+
+/// Process-wide color setting, stored as a [`ColorChoice`] discriminant.
+static COLOR: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// When console tables use color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal
+    #[default]
+    Auto,
+    /// Always emit ANSI styling
+    Always,
+    /// Never emit ANSI styling
+    Never,
+}
+
 /// Console formatter for pretty output.
 pub struct ConsoleFormatter;
 
 impl ConsoleFormatter {
+    /// Choose when tables are colored. Applies to every later call.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SystemInfo;
+    /// use sysaudit::output::{ColorChoice, ConsoleFormatter};
+    ///
+    /// ConsoleFormatter::set_color(ColorChoice::Never);
+    /// println!("{}", ConsoleFormatter::format_system_info(&SystemInfo::collect().unwrap()));
+    /// ```
+    pub fn set_color(choice: ColorChoice) {
+        COLOR.store(choice as u8, Ordering::Relaxed);
+    }
+
+    /// Current color setting.
+    pub fn color() -> ColorChoice {
+        match COLOR.load(Ordering::Relaxed) {
+            1 => ColorChoice::Always,
+            2 => ColorChoice::Never,
+            _ => ColorChoice::Auto,
+        }
+    }
+
     /// Format system info as a table.
+    ///
+    /// The OS support status is evaluated against today's date.
     pub fn format_system_info(info: &SystemInfo) -> String {
+        Self::system_info(info, Utc::now().date_naive(), Self::color())
+    }
+
+    fn system_info(info: &SystemInfo, today: NaiveDate, color: ColorChoice) -> String {
         let mut output = String::new();
 
         // System info table
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_width(MAX_TABLE_WIDTH)
-            .set_header(vec!["SYSTEM INFORMATION", ""]);
+        let mut table = new_table(color);
+        table.set_header(vec!["SYSTEM INFORMATION", ""]);
 
         table.add_row(vec!["Computer Name", &info.computer_name]);
         if let Some(domain) = &info.domain {
             table.add_row(vec!["Domain", domain]);
         }
+        if let Some(fqdn) = &info.identity.fqdn {
+            table.add_row(vec!["FQDN", fqdn]);
+        }
+        if let Some(asset_id) = info.fingerprint.asset_id() {
+            table.add_row(vec!["Asset ID".to_string(), asset_id]);
+        }
+        if let Some(sid) = &info.identity.machine_sid {
+            table.add_row(vec!["Machine SID", sid]);
+        }
+        if let Some(installed) = info.identity.installed_on {
+            table.add_row(vec![
+                "Installed".to_string(),
+                installed.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            ]);
+        }
+        if !info.identity.dns_search_list.is_empty() {
+            table.add_row(vec![
+                "DNS Search List",
+                &info.identity.dns_search_list.join("\n"),
+            ]);
+        }
+        if !info.identity.reverse_dns.is_empty() {
+            let names: Vec<String> = info
+                .identity
+                .reverse_dns
+                .iter()
+                .map(|r| format!("{} -> {}", r.ip_address, r.name))
+                .collect();
+            table.add_row(vec!["Reverse DNS", &names.join("\n")]);
+        }
 
         // Manufacturer / Model
         if let (Some(man), Some(mod_)) = (&info.manufacturer, &info.model) {
@@ -37,8 +137,20 @@ impl ConsoleFormatter {
             table.add_row(vec!["Model", mod_]);
         }
 
-        table.add_row(vec!["OS", &format!("{} {}", info.os_name, info.os_version)]);
+        // OS, styled by support status
+        let support = match OsSupport::evaluate(&info.os_name, &info.build_number, today) {
+            OsSupport::Unknown => OsSupport::evaluate(&info.os_name, &info.os_version, today),
+            support => support,
+        };
+        let mut os = Cell::new(format!("{} {}", info.os_name, info.os_version));
+        if support.is_end_of_life() {
+            os = os.fg(Color::Red).add_attribute(Attribute::Bold);
+        }
+        table.add_row(vec![Cell::new("OS"), os]);
         table.add_row(vec!["Build", &info.build_number]);
+        if let Some(cell) = support_cell(support) {
+            table.add_row(vec![Cell::new("Support"), cell]);
+        }
 
         // CPU
         let cpu_cores =
@@ -73,21 +185,41 @@ impl ConsoleFormatter {
 
         // Network interfaces table
         if !info.network_interfaces.is_empty() {
-            let mut net_table = Table::new();
-            net_table
-                .load_preset(UTF8_FULL)
-                .apply_modifier(UTF8_ROUND_CORNERS)
-                .set_content_arrangement(ContentArrangement::Dynamic)
-                .set_width(MAX_TABLE_WIDTH)
-                .set_header(vec!["Interface", "IP Address", "Prefix", "MAC"]);
+            let mut net_table = new_table(color);
+            net_table.set_header(vec!["Interface", "IP Address", "Prefix", "Gateway", "MAC"]);
 
+            // Interfaces without a gateway are usually link-local or
+            // isolated; dim them so routed interfaces stand out.
             for iface in &info.network_interfaces {
-                net_table.add_row(vec![
-                    &iface.name,
-                    &iface.ip_address.to_string(),
-                    iface.subnet_mask.as_deref().unwrap_or("-"),
-                    iface.mac_address.as_deref().unwrap_or("-"),
-                ]);
+                // Grouped interfaces list one address per line
+                let cells = [
+                    iface.name.clone(),
+                    iface
+                        .ip_addresses()
+                        .map(|ip| ip.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    std::iter::once(iface.subnet_mask.clone().unwrap_or_else(|| "-".to_string()))
+                        .chain(
+                            iface
+                                .additional_addresses
+                                .iter()
+                                .map(|address| format!("/{}", address.prefix)),
+                        )
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    iface.gateway.clone().unwrap_or_else(|| "-".to_string()),
+                    iface.mac_address.clone().unwrap_or_else(|| "-".to_string()),
+                ];
+                let dim = iface.gateway.is_none();
+                net_table.add_row(cells.into_iter().map(|value| {
+                    let cell = Cell::new(value);
+                    if dim {
+                        cell.add_attribute(Attribute::Dim)
+                    } else {
+                        cell
+                    }
+                }));
             }
 
             output.push_str(&net_table.to_string());
@@ -98,55 +230,239 @@ impl ConsoleFormatter {
 
     /// Format software list as a table.
     pub fn format_software(software: &[Software]) -> String {
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_width(MAX_TABLE_WIDTH)
-            .set_header(vec![
-                "Name",
-                "Version",
-                "Publisher",
-                "Install Date",
-                "Source",
-            ]);
+        let mut table = new_table(Self::color());
+        table.set_header(vec![
+            "Name",
+            "Version",
+            "Publisher",
+            "Install Date",
+            "Source",
+            "Support",
+        ]);
 
         for sw in software {
             table.add_row(vec![
-                &sw.name,
-                sw.version.as_deref().unwrap_or("-"),
-                sw.publisher.as_deref().unwrap_or("-"),
-                &sw.install_date
-                    .map(|d| d.to_string())
-                    .unwrap_or_else(|| "-".to_string()),
-                &sw.source.to_string(),
+                Cell::new(&sw.name),
+                Cell::new(sw.version.as_deref().unwrap_or("-")),
+                Cell::new(sw.publisher.as_deref().unwrap_or("-")),
+                Cell::new(
+                    sw.install_date
+                        .map(|d| d.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Cell::new(sw.source),
+                software_support_cell(sw),
             ]);
         }
 
         format!("{}\nFound: {} items", table, software.len())
     }
 
+    /// Format software classified against an allowlist: violations first
+    /// (unapproved in red, unknown in yellow), then a count per status.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SoftwareScanner;
+    /// use sysaudit::allowlist::Allowlist;
+    /// use sysaudit::output::ConsoleFormatter;
+    ///
+    /// let allowlist = Allowlist::load("approved.csv").unwrap();
+    /// let classified = allowlist.classify_all(SoftwareScanner::new().scan().unwrap());
+    /// println!("{}", ConsoleFormatter::format_allowlist(&classified));
+    /// ```
+    pub fn format_allowlist(items: &[ClassifiedSoftware]) -> String {
+        let mut table = new_table(Self::color());
+        table.set_header(vec!["Status", "Name", "Version", "Publisher", "Reason"]);
+
+        let mut sorted: Vec<&ClassifiedSoftware> = items.iter().collect();
+        sorted.sort_by_key(|item| match item.status {
+            AllowStatus::Unapproved => 0,
+            AllowStatus::Unknown => 1,
+            AllowStatus::Approved => 2,
+        });
+        for item in sorted {
+            let status = Cell::new(item.status);
+            let status = match item.status {
+                AllowStatus::Unapproved => status.fg(Color::Red).add_attribute(Attribute::Bold),
+                AllowStatus::Unknown => status.fg(Color::Yellow),
+                AllowStatus::Approved => status.fg(Color::Green),
+            };
+            table.add_row(vec![
+                status,
+                Cell::new(&item.software.name),
+                Cell::new(item.software.version.as_deref().unwrap_or("-")),
+                Cell::new(item.software.publisher.as_deref().unwrap_or("-")),
+                Cell::new(&item.reason),
+            ]);
+        }
+
+        let count = |status| items.iter().filter(|i| i.status == status).count();
+        format!(
+            "{}\n{} approved, {} unapproved, {} unknown",
+            table,
+            count(AllowStatus::Approved),
+            count(AllowStatus::Unapproved),
+            count(AllowStatus::Unknown)
+        )
+    }
+
+    /// Format any JSON value as a table: an array of objects gets one
+    /// column per key, an object one row per key, an array of scalars one
+    /// row per value. Nested values are shown as compact JSON.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::output::{ColorChoice, ConsoleFormatter};
+    ///
+    /// ConsoleFormatter::set_color(ColorChoice::Never);
+    /// let value = serde_json::json!([{"name": "7-Zip", "version": "23.01"}]);
+    /// assert!(ConsoleFormatter::format_value(&value).contains("23.01"));
+    /// ```
+    pub fn format_value(value: &Value) -> String {
+        let rows = match value {
+            Value::Array(rows) => rows,
+            Value::Object(map) => {
+                let mut table = new_table(Self::color());
+                table.set_header(vec!["Key", "Value"]);
+                for (key, value) in map {
+                    table.add_row(vec![Cell::new(key), Cell::new(value_text(value))]);
+                }
+                return table.to_string();
+            }
+            scalar => return value_text(scalar),
+        };
+
+        let mut table = new_table(Self::color());
+        if rows.iter().all(Value::is_object) && !rows.is_empty() {
+            let mut keys: Vec<&String> = Vec::new();
+            for key in rows
+                .iter()
+                .filter_map(Value::as_object)
+                .flat_map(|m| m.keys())
+            {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+            table.set_header(keys.iter().map(|k| k.as_str()).collect::<Vec<_>>());
+            for row in rows.iter().filter_map(Value::as_object) {
+                table.add_row(
+                    keys.iter()
+                        .map(|k| Cell::new(row.get(*k).map(value_text).unwrap_or_default()))
+                        .collect::<Vec<_>>(),
+                );
+            }
+        } else {
+            table.set_header(vec!["Value"]);
+            for row in rows {
+                table.add_row(vec![Cell::new(value_text(row))]);
+            }
+        }
+        format!("{}\nFound: {} items", table, rows.len())
+    }
+
+    /// Format an audit score as a table: the overall score with its grade
+    /// and trend, then each domain with its weight and what lowered it.
+    pub fn format_score(score: &AuditScore) -> String {
+        let mut table = new_table(Self::color());
+        table.set_header(vec!["Domain", "Score", "Weight", "Reasons"]);
+        for domain in &score.domains {
+            let value = match domain.score {
+                Some(value) => score_colored(Cell::new(value), value),
+                None => Cell::new("n/a"),
+            };
+            table.add_row(vec![
+                Cell::new(domain.domain),
+                value,
+                Cell::new(domain.weight),
+                Cell::new(domain.reasons.join("\n")),
+            ]);
+        }
+        table.add_row(vec![
+            Cell::new("OVERALL").add_attribute(Attribute::Bold),
+            score_cell(score),
+            Cell::new(""),
+            Cell::new(""),
+        ]);
+        table.to_string()
+    }
+
+    /// Format scan metrics as a table: each collector's duration, item and
+    /// warning counts, then the total. The slowest collector is highlighted;
+    /// remote scans add a line with their network footprint.
+    pub fn format_metrics(metrics: &ScanMetrics) -> String {
+        let mut table = new_table(Self::color());
+        table.set_header(vec!["Collector", "Duration", "Items", "Warnings"]);
+        let slowest = metrics.slowest().map(|m| m.collector);
+        for collector in &metrics.collectors {
+            let mut duration = Cell::new(format_millis(collector.duration_ms));
+            if slowest == Some(collector.collector) {
+                duration = duration.fg(Color::Yellow).add_attribute(Attribute::Bold);
+            }
+            table.add_row(vec![
+                Cell::new(collector.collector),
+                duration,
+                Cell::new(collector.items),
+                Cell::new(collector.warnings),
+            ]);
+        }
+        table.add_row(vec![
+            Cell::new("TOTAL").add_attribute(Attribute::Bold),
+            Cell::new(format_millis(metrics.duration_ms)).add_attribute(Attribute::Bold),
+            Cell::new(""),
+            Cell::new(""),
+        ]);
+        match &metrics.transfer {
+            Some(transfer) => format!(
+                "{}\n{} (payload {}, report {})",
+                table,
+                transfer_line(transfer),
+                format_bytes(transfer.payload_bytes),
+                format_bytes(transfer.report_bytes)
+            ),
+            None => table.to_string(),
+        }
+    }
+
     /// Format industrial software as a table.
+    ///
+    /// Vendor and product are highlighted so detections stand out.
     pub fn format_industrial(software: &[IndustrialSoftware]) -> String {
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_width(MAX_TABLE_WIDTH)
-            .set_header(vec!["Vendor", "Product", "Version", "Install Path"]);
+        Self::industrial(software, Self::color())
+    }
+
+    fn industrial(software: &[IndustrialSoftware], color: ColorChoice) -> String {
+        let mut table = new_table(color);
+        let runtime = software.iter().any(|sw| sw.runtime_state.is_some());
+        let mut header = vec!["Vendor", "Product", "Version", "Install Path"];
+        if runtime {
+            header.push("Runtime");
+        }
+        table.set_header(header);
 
         for sw in software {
-            table.add_row(vec![
-                &sw.vendor.to_string(),
-                &sw.product,
-                sw.version.as_deref().unwrap_or("-"),
-                &sw.install_path
-                    .as_ref()
-                    .map(|p| p.display().to_string())
-                    .unwrap_or_else(|| "-".to_string()),
-            ]);
+            let mut row = vec![
+                Cell::new(sw.vendor.to_string())
+                    .fg(Color::Yellow)
+                    .add_attribute(Attribute::Bold),
+                Cell::new(&sw.product)
+                    .fg(Color::Yellow)
+                    .add_attribute(Attribute::Bold),
+                Cell::new(sw.version.as_deref().unwrap_or("-")),
+                Cell::new(
+                    sw.install_path
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ];
+            if runtime {
+                row.push(runtime_cell(sw.runtime_state));
+            }
+            table.add_row(row);
         }
 
         format!(
@@ -158,18 +474,13 @@ impl ConsoleFormatter {
 
     /// Format Windows updates as a table.
     pub fn format_updates(updates: &[WindowsUpdate]) -> String {
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_width(MAX_TABLE_WIDTH)
-            .set_header(vec![
-                "HotFix ID",
-                "Description",
-                "Installed On",
-                "Installed By",
-            ]);
+        let mut table = new_table(Self::color());
+        table.set_header(vec![
+            "HotFix ID",
+            "Description",
+            "Installed On",
+            "Installed By",
+        ]);
 
         for update in updates {
             table.add_row(vec![
@@ -185,6 +496,972 @@ impl ConsoleFormatter {
 
         format!("{}\nFound: {} updates", table, updates.len())
     }
+
+    /// Format a one-screen overview of a report: host, OS support status,
+    /// section counts and the most severe findings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::ConsoleFormatter};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// println!("{}", ConsoleFormatter::format_summary(&report));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_summary(report: &AuditReport) -> String {
+        Self::summary(report, Self::color())
+    }
+
+    /// Format a whole report from its stored sections: software,
+    /// industrial software, redistributables and updates, then the summary.
+    ///
+    /// Unlike the per-section formatters this needs nothing but the
+    /// report, so it also renders reports loaded from JSON.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::AuditReport;
+    /// use sysaudit::output::ConsoleFormatter;
+    ///
+    /// # fn example() -> Result<(), sysaudit::Error> {
+    /// let report = AuditReport::from_json_versioned(&std::fs::read_to_string("hmi01.json")?)?;
+    /// println!("{}", ConsoleFormatter::format_report(&report));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_report(report: &AuditReport) -> String {
+        let color = Self::color();
+
+        let mut software = new_table(color);
+        software.set_header(vec!["Name", "Version", "Publisher", "Install Date"]);
+        for sw in &report.software {
+            software.add_row(vec![
+                sw.name.as_str(),
+                sw.version.as_deref().unwrap_or("-"),
+                sw.vendor.as_deref().unwrap_or("-"),
+                &sw.install_date
+                    .map(|d| d.date_naive().to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ]);
+        }
+        let mut output = format!("{}\nFound: {} items\n\n", software, report.software.len());
+
+        if !report.industrial.is_empty() {
+            let mut industrial = new_table(color);
+            let runtime = !report.services.is_empty();
+            let mut header = vec!["Vendor", "Product", "Version", "Install Path"];
+            if runtime {
+                header.push("Runtime");
+            }
+            industrial.set_header(header);
+            for sw in &report.industrial {
+                let mut row = vec![
+                    Cell::new(&sw.vendor),
+                    Cell::new(&sw.product),
+                    Cell::new(sw.version.as_deref().unwrap_or("-")),
+                    Cell::new(
+                        sw.install_path
+                            .as_ref()
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                ];
+                if runtime {
+                    row.push(runtime_cell(report.runtime_state(sw)));
+                }
+                industrial.add_row(row);
+            }
+            output.push_str(&format!(
+                "{}\nFound: {} industrial applications\n\n",
+                industrial,
+                report.industrial.len()
+            ));
+        }
+
+        if !report.redistributables.is_empty() {
+            let mut redistributables = new_table(color);
+            redistributables.set_header(vec!["Kind", "Name", "Version", "Arch"]);
+            for redist in &report.redistributables {
+                redistributables.add_row(vec![
+                    redist.kind.to_string().as_str(),
+                    &redist.name,
+                    redist.version.as_deref().unwrap_or("-"),
+                    redist.architecture.as_deref().unwrap_or("-"),
+                ]);
+            }
+            output.push_str(&format!(
+                "{}\nFound: {} redistributables\n\n",
+                redistributables,
+                report.redistributables.len()
+            ));
+        }
+
+        output.push_str(&Self::format_updates(&report.updates));
+        output.push_str("\n\n");
+        output.push_str(&Self::summary(report, color));
+        output
+    }
+
+    fn summary(report: &AuditReport, color: ColorChoice) -> String {
+        let system = &report.system;
+        let support = OsSupport::evaluate(
+            &system.os_name,
+            &system.os_version,
+            report.timestamp.date_naive(),
+        );
+        let mut findings = Finding::collect(report);
+        findings.sort_by_key(|finding| Reverse(finding.severity));
+
+        let score = report
+            .score
+            .clone()
+            .unwrap_or_else(|| AuditScore::compute(report));
+
+        let mut table = new_table(color);
+        table.set_header(vec!["AUDIT SUMMARY", ""]);
+        table.add_row(vec!["Host", &system.host_name]);
+        if let Some(fqdn) = report.identity.as_ref().and_then(|i| i.fqdn.as_ref()) {
+            table.add_row(vec!["FQDN", fqdn]);
+        }
+        if let Some(asset_id) = &report.asset_id {
+            table.add_row(vec!["Asset ID", asset_id]);
+        }
+        table.add_row(vec![Cell::new("Score"), score_cell(&score)]);
+        table.add_row(vec![
+            "Domains",
+            &score
+                .domains
+                .iter()
+                .map(|d| match d.score {
+                    Some(value) => format!("{} {}", d.domain, value),
+                    None => format!("{} n/a", d.domain),
+                })
+                .collect::<Vec<_>>()
+                .join(" | "),
+        ]);
+
+        let mut os = Cell::new(format!("{} {}", system.os_name, system.os_version));
+        if support.is_end_of_life() {
+            os = os.fg(Color::Red).add_attribute(Attribute::Bold);
+        }
+        table.add_row(vec![Cell::new("OS"), os]);
+        if let Some(cell) = support_cell(support) {
+            table.add_row(vec![Cell::new("Support"), cell]);
+        }
+        table.add_row(vec![
+            "Scanned",
+            &report.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        ]);
+        if let Some(metadata) = &report.metadata {
+            table.add_row(vec![
+                "Scan".to_string(),
+                format!(
+                    "{} scan in {} by sysaudit {}",
+                    metadata.scanner,
+                    format_millis(metadata.duration_ms),
+                    metadata.tool_version
+                ),
+            ]);
+            if let Some(user) = &metadata.user {
+                let rights = match metadata.elevated {
+                    Some(true) => " (elevated)",
+                    Some(false) => " (standard user)",
+                    None => "",
+                };
+                table.add_row(vec!["Run By".to_string(), format!("{}{}", user, rights)]);
+            }
+            if !metadata.options.is_empty() {
+                let options: Vec<String> = metadata
+                    .options
+                    .iter()
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect();
+                table.add_row(vec!["Options".to_string(), options.join("\n")]);
+            }
+        }
+        if report.elevated == Some(false) {
+            table.add_row(vec![
+                Cell::new("Privileges"),
+                Cell::new("standard user (some sections incomplete)").fg(Color::Yellow),
+            ]);
+        }
+
+        table.add_row(vec!["Software", &report.software.len().to_string()]);
+        let mut industrial = Cell::new(report.industrial.len());
+        if !report.industrial.is_empty() {
+            industrial = industrial.fg(Color::Yellow).add_attribute(Attribute::Bold);
+        }
+        table.add_row(vec![Cell::new("Industrial"), industrial]);
+        let latest = report.updates.iter().filter_map(|u| u.installed_on).max();
+        table.add_row(vec![
+            "Updates",
+            &match latest {
+                Some(date) => format!("{} (latest {})", report.updates.len(), date),
+                None => report.updates.len().to_string(),
+            },
+        ]);
+        if let Some(health) = &report.update_health {
+            let service = |name: &str, service: Option<&WindowsService>| match service {
+                Some(s) => format!(
+                    "{}: {} ({})",
+                    name,
+                    s.state.as_deref().unwrap_or("-"),
+                    s.start_mode.as_deref().unwrap_or("-")
+                ),
+                None => format!("{}: not found", name),
+            };
+            let mut lines = vec![
+                service("Windows Update", health.windows_update.as_ref()),
+                service("BITS", health.bits.as_ref()),
+            ];
+            if let Some(server) = &health.wsus_server {
+                lines.push(format!("WSUS: {}", server));
+            }
+            if let Some(sccm) = &health.sccm {
+                lines.push(format!(
+                    "SCCM: {} site {}, policy {}",
+                    sccm.version.as_deref().unwrap_or("-"),
+                    sccm.site_code.as_deref().unwrap_or("-"),
+                    sccm.last_policy_evaluation
+                        .map(|t| t.format("%Y-%m-%d").to_string())
+                        .unwrap_or_else(|| "never".to_string())
+                ));
+            }
+            table.add_row(vec!["Update Agents".to_string(), lines.join("\n")]);
+        }
+        if let Some(licensing) = &report.licensing {
+            let mut lines: Vec<String> = licensing.edition.iter().cloned().collect();
+            lines.extend(licensing.licenses.iter().map(|license| {
+                format!(
+                    "{}: {}, {}, key *****-{}",
+                    license.name,
+                    license.status,
+                    license
+                        .channel
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    license.partial_product_key.as_deref().unwrap_or("?????")
+                )
+            }));
+            table.add_row(vec!["Licensing".to_string(), lines.join("\n")]);
+        }
+        if let Some(logs) = &report.event_logs {
+            let mut lines: Vec<String> = logs
+                .logs
+                .iter()
+                .map(|log| {
+                    format!(
+                        "{}: {}, {}{}",
+                        log.name,
+                        log.max_size_kb
+                            .map(|kb| format!("{} MB", kb / 1024))
+                            .unwrap_or_else(|| "-".to_string()),
+                        log.retention
+                            .map(|r| r.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        if log.policy { " (policy)" } else { "" }
+                    )
+                })
+                .collect();
+            lines.push(if logs.forwarded() {
+                format!("Forwarded to {}", logs.subscription_managers.join(", "))
+            } else {
+                "Not forwarded".to_string()
+            });
+            table.add_row(vec!["Event Logs".to_string(), lines.join("\n")]);
+        }
+        if let Some(autopilot) = &report.autopilot {
+            let hash = match &autopilot.hardware_hash {
+                Some(hash) => format!("hardware hash ({} chars)", hash.len()),
+                None => "no hardware hash".to_string(),
+            };
+            table.add_row(vec![
+                "Autopilot".to_string(),
+                format!(
+                    "{}, {}",
+                    autopilot
+                        .serial_number
+                        .as_deref()
+                        .unwrap_or("no serial number"),
+                    hash
+                ),
+            ]);
+        }
+        if let Some(containers) = &report.containers {
+            let mut lines = Vec::new();
+            if let Some(wsl) = &containers.wsl {
+                let mut kinds = Vec::new();
+                if wsl.inbox {
+                    kinds.push("inbox".to_string());
+                }
+                if let Some(version) = &wsl.package_version {
+                    kinds.push(format!("package {}", version));
+                }
+                lines.push(if kinds.is_empty() {
+                    "WSL".to_string()
+                } else {
+                    format!("WSL ({})", kinds.join(", "))
+                });
+                lines.extend(wsl.distributions.iter().map(|d| {
+                    format!(
+                        "  {}: WSL {}{}",
+                        d.name,
+                        d.wsl_version
+                            .map(|v| v.to_string())
+                            .unwrap_or_else(|| "?".to_string()),
+                        if d.default { " (default)" } else { "" }
+                    )
+                }));
+            }
+            lines.extend(containers.runtimes.iter().map(|runtime| {
+                format!(
+                    "{} {}",
+                    runtime.kind,
+                    runtime.version.as_deref().unwrap_or("(version unknown)")
+                )
+            }));
+            if lines.is_empty() {
+                lines.push("None installed".to_string());
+            }
+            table.add_row(vec!["WSL / Containers".to_string(), lines.join("\n")]);
+        }
+
+        let mut counts = Vec::new();
+        for severity in [
+            Severity::Critical,
+            Severity::High,
+            Severity::Medium,
+            Severity::Low,
+            Severity::Info,
+        ] {
+            let count = findings.iter().filter(|f| f.severity == severity).count();
+            if count > 0 {
+                counts.push(format!("{} {}", count, severity));
+            }
+        }
+        table.add_row(vec![
+            "Findings",
+            &if counts.is_empty() {
+                "None".to_string()
+            } else {
+                counts.join(", ")
+            },
+        ]);
+        if !report.warnings.is_empty() {
+            let warnings = report
+                .warnings
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+            table.add_row(vec![
+                Cell::new("Warnings"),
+                Cell::new(warnings).fg(Color::Yellow),
+            ]);
+        }
+
+        if findings.is_empty() {
+            return table.to_string();
+        }
+
+        let mut top = new_table(color);
+        top.set_header(vec!["Severity", "Top Findings"]);
+        for finding in findings.iter().take(SUMMARY_FINDINGS) {
+            top.add_row(vec![
+                severity_cell(finding.severity),
+                Cell::new(&finding.title),
+            ]);
+        }
+
+        let mut output = format!("{}\n{}", table, top);
+        if findings.len() > SUMMARY_FINDINGS {
+            output.push_str(&format!(
+                "\n... and {} more",
+                findings.len() - SUMMARY_FINDINGS
+            ));
+        }
+        output
+    }
+
+    /// Format findings as a table, one row per finding with its severity,
+    /// title and attributes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::output::ConsoleFormatter;
+    /// use sysaudit::Finding;
+    ///
+    /// # fn example(report: &sysaudit::AuditReport) {
+    /// println!("{}", ConsoleFormatter::format_findings(&Finding::collect(report)));
+    /// # }
+    /// ```
+    pub fn format_findings(findings: &[Finding]) -> String {
+        let mut table = new_table(Self::color());
+        table.set_header(vec!["Severity", "Finding", "Details"]);
+        for finding in findings {
+            let details: Vec<String> = finding
+                .attributes
+                .iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(key, value)| format!("{}: {}", key, value))
+                .collect();
+            table.add_row(vec![
+                severity_cell(finding.severity),
+                Cell::new(&finding.title),
+                Cell::new(details.join("\n")),
+            ]);
+        }
+        format!("{}\nFound: {} items", table, findings.len())
+    }
+
+    /// Format policy results: one row per rule, failures colored by
+    /// severity, followed by a pass/fail count.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::compliance::Policy;
+    /// use sysaudit::output::ConsoleFormatter;
+    ///
+    /// # fn example(report: &sysaudit::AuditReport) {
+    /// let result = Policy::load("policy.yaml").unwrap().evaluate(report);
+    /// println!("{}", ConsoleFormatter::format_compliance(&result));
+    /// # }
+    /// ```
+    pub fn format_compliance(report: &ComplianceReport) -> String {
+        let mut table = new_table(Self::color());
+        table.set_header(vec!["Status", "Severity", "Rule", "Detail"]);
+        for result in &report.results {
+            let status = Cell::new(result.status);
+            let status = match result.status {
+                RuleStatus::Pass => status.fg(Color::Green),
+                RuleStatus::Fail => styled_by_severity(status, result.severity),
+                RuleStatus::Unknown => status.fg(Color::Yellow),
+            };
+            table.add_row(vec![
+                status,
+                Cell::new(result.severity),
+                Cell::new(format!("{}\n{}", result.id, result.title)),
+                Cell::new(&result.detail),
+            ]);
+        }
+
+        let failed = report.failures().count();
+        let score = report
+            .score
+            .map(|score| format!(", score {}%", score))
+            .unwrap_or_default();
+        format!(
+            "{} on {}\n{}\n{} of {} rules passed{}",
+            if report.policy.is_empty() {
+                "Policy"
+            } else {
+                &report.policy
+            },
+            report.host,
+            table,
+            report.results.len() - failed,
+            report.results.len(),
+            score
+        )
+    }
+
+    /// Format per-host fleet scan results: one row per host with its OS,
+    /// section counts and findings, or the error that stopped the scan.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::fleet::{FleetScanner, FleetTarget};
+    /// use sysaudit::output::ConsoleFormatter;
+    ///
+    /// # async fn example() {
+    /// let results = FleetScanner::builder()
+    ///     .targets(vec![FleetTarget::new("hmi01")])
+    ///     .build()
+    ///     .scan()
+    ///     .await;
+    /// println!("{}", ConsoleFormatter::format_fleet(&results));
+    /// # }
+    /// ```
+    #[cfg(feature = "remote")]
+    pub fn format_fleet(results: &[HostResult]) -> String {
+        Self::fleet(results, Self::color())
+    }
+
+    #[cfg(feature = "remote")]
+    fn fleet(results: &[HostResult], color: ColorChoice) -> String {
+        let mut table = new_table(color);
+        table.set_header(vec![
+            "Host",
+            "Site / Tags",
+            "Status",
+            "OS",
+            "Software",
+            "Industrial",
+            "Findings / Error",
+        ]);
+
+        let mut failed = 0;
+        for host in results {
+            match &host.result {
+                Ok(report) => {
+                    let findings = Finding::collect(report);
+                    let worst = findings.iter().map(|f| f.severity).max();
+                    let mut os = Cell::new(format!(
+                        "{} {}",
+                        report.system.os_name, report.system.os_version
+                    ));
+                    if OsSupport::evaluate(
+                        &report.system.os_name,
+                        &report.system.os_version,
+                        report.timestamp.date_naive(),
+                    )
+                    .is_end_of_life()
+                    {
+                        os = os.fg(Color::Red).add_attribute(Attribute::Bold);
+                    }
+                    table.add_row(vec![
+                        Cell::new(&host.host),
+                        Cell::new(group_label(host)),
+                        Cell::new("OK").fg(Color::Green),
+                        os,
+                        Cell::new(report.software.len()),
+                        Cell::new(report.industrial.len()),
+                        match worst {
+                            Some(severity) => styled_by_severity(
+                                Cell::new(format!(
+                                    "{} ({} total)",
+                                    severity.to_string().to_uppercase(),
+                                    findings.len()
+                                )),
+                                severity,
+                            ),
+                            None => Cell::new("None"),
+                        },
+                    ]);
+                }
+                Err(e) => {
+                    failed += 1;
+                    table.add_row(vec![
+                        Cell::new(&host.host),
+                        Cell::new(group_label(host)),
+                        Cell::new(format!("FAILED ({})", e.kind()))
+                            .fg(Color::Red)
+                            .add_attribute(Attribute::Bold),
+                        Cell::new("-"),
+                        Cell::new("-"),
+                        Cell::new("-"),
+                        Cell::new(e).fg(Color::Red),
+                    ]);
+                }
+            }
+        }
+
+        let mut summary = format!(
+            "{}\nScanned: {} hosts, {} failed",
+            table,
+            results.len(),
+            failed
+        );
+        let transfer = results
+            .iter()
+            .filter_map(|host| host.result.as_ref().ok()?.metrics.as_ref()?.transfer)
+            .reduce(|total, host| total + host);
+        if let Some(transfer) = transfer {
+            summary.push_str(&format!("\n{}", transfer_line(&transfer)));
+        }
+        summary
+    }
+
+    /// Format the cross-host rollups of a fleet report: hosts per OS
+    /// build, the most widespread software, the industrial product matrix
+    /// and patch currency.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::FleetReport;
+    /// use sysaudit::output::ConsoleFormatter;
+    ///
+    /// # fn example(fleet: &FleetReport) {
+    /// println!("{}", ConsoleFormatter::format_fleet_report(fleet));
+    /// # }
+    /// ```
+    pub fn format_fleet_report(report: &FleetReport) -> String {
+        Self::fleet_report(report, Self::color())
+    }
+
+    fn fleet_report(report: &FleetReport, color: ColorChoice) -> String {
+        let mut sections = Vec::new();
+        let today = report.generated.date_naive();
+
+        let mut builds = new_table(color);
+        builds.set_header(vec!["OS", "Version", "Hosts", "Support"]);
+        for build in &report.os_builds {
+            let support = OsSupport::evaluate(&build.os_name, &build.os_version, today);
+            builds.add_row(vec![
+                Cell::new(&build.os_name),
+                Cell::new(&build.os_version),
+                Cell::new(build.hosts.len()),
+                support_cell(support).unwrap_or_else(|| Cell::new("-")),
+            ]);
+        }
+        sections.push(format!("OS BUILDS\n{}", builds));
+
+        let mut software = new_table(color);
+        software.set_header(vec!["Name", "Version", "Hosts"]);
+        for sw in report.software.iter().take(FLEET_TOP_SOFTWARE) {
+            software.add_row(vec![
+                Cell::new(&sw.name),
+                Cell::new(sw.version.as_deref().unwrap_or("-")),
+                Cell::new(sw.hosts),
+            ]);
+        }
+        sections.push(format!(
+            "SOFTWARE (top {} of {})\n{}",
+            FLEET_TOP_SOFTWARE.min(report.software.len()),
+            report.software.len(),
+            software
+        ));
+
+        if !report.industrial.products.is_empty() {
+            let mut matrix = new_table(color);
+            matrix.set_header(
+                std::iter::once("Host")
+                    .chain(report.industrial.products.iter().map(String::as_str)),
+            );
+            for row in &report.industrial.rows {
+                let mut cells = vec![Cell::new(&row.host)];
+                cells.extend(row.versions.iter().map(|v| {
+                    match v.as_deref() {
+                        Some(version) => {
+                            Cell::new(if version.is_empty() { "yes" } else { version })
+                                .fg(Color::Yellow)
+                        }
+                        None => Cell::new("-"),
+                    }
+                }));
+                matrix.add_row(cells);
+            }
+            sections.push(format!("INDUSTRIAL SOFTWARE\n{}", matrix));
+        }
+
+        let mut patches = new_table(color);
+        patches.set_header(vec!["Last Update", "Hosts"]);
+        for bucket in &report.patch_currency {
+            let mut label = Cell::new(bucket.currency);
+            label = match bucket.currency {
+                PatchCurrency::Current => label.fg(Color::Green),
+                PatchCurrency::Recent => label,
+                PatchCurrency::Stale => label.fg(Color::Yellow),
+                PatchCurrency::Outdated | PatchCurrency::Unknown => label.fg(Color::Red),
+            };
+            patches.add_row(vec![label, Cell::new(bucket.hosts)]);
+        }
+        sections.push(format!("PATCH CURRENCY\n{}", patches));
+
+        if !report.clones.is_empty() {
+            let mut clones = new_table(color);
+            clones.set_header(vec!["Evidence", "Value", "Hosts"]);
+            for group in &report.clones {
+                clones.add_row(vec![
+                    Cell::new(group.evidence).fg(Color::Yellow),
+                    Cell::new(&group.value),
+                    Cell::new(group.hosts.join("\n")),
+                ]);
+            }
+            sections.push(format!("POSSIBLE CLONED IMAGES\n{}", clones));
+        }
+
+        let mut hosts = format!(
+            "Hosts: {} scanned, {} failed",
+            report.hosts.len() - report.failed(),
+            report.failed()
+        );
+        let failures = report.failures_by_kind();
+        if !failures.is_empty() {
+            let kinds: Vec<String> = failures
+                .iter()
+                .map(|(kind, count)| format!("{} {}", count, kind))
+                .collect();
+            hosts.push_str(&format!(" ({})", kinds.join(", ")));
+        }
+        if let Some(transfer) = &report.transfer {
+            hosts.push_str(&format!("\n{}", transfer_line(transfer)));
+        }
+        sections.push(hosts);
+        sections.join("\n\n")
+    }
+
+    /// Format the changes between two reports: changed system fields, then
+    /// one table per section with added rows green, removed rows red and
+    /// version changes yellow.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::output::ConsoleFormatter;
+    /// use sysaudit::report::diff;
+    ///
+    /// # fn example(old: &sysaudit::AuditReport, new: &sysaudit::AuditReport) {
+    /// println!("{}", ConsoleFormatter::format_diff(&diff(old, new)));
+    /// # }
+    /// ```
+    pub fn format_diff(diff: &ReportDiff) -> String {
+        Self::diff(diff, Self::color())
+    }
+
+    fn diff(diff: &ReportDiff, color: ColorChoice) -> String {
+        let mut sections = vec![format!(
+            "{}: {} -> {}",
+            diff.host,
+            diff.old_timestamp.format("%Y-%m-%d %H:%M UTC"),
+            diff.new_timestamp.format("%Y-%m-%d %H:%M UTC")
+        )];
+        if diff.is_empty() {
+            sections.push("No changes".to_string());
+            return sections.join("\n\n");
+        }
+
+        if !diff.system.is_empty() {
+            let mut system = new_table(color);
+            system.set_header(vec!["Field", "Old", "New"]);
+            for change in &diff.system {
+                system.add_row(vec![
+                    Cell::new(&change.field),
+                    Cell::new(change.old.as_deref().unwrap_or("-")),
+                    Cell::new(change.new.as_deref().unwrap_or("-")).fg(Color::Yellow),
+                ]);
+            }
+            sections.push(format!("SYSTEM\n{}", system));
+        }
+
+        for (title, changes) in [
+            ("SOFTWARE", &diff.software),
+            ("INDUSTRIAL SOFTWARE", &diff.industrial),
+            ("UPDATES", &diff.updates),
+        ] {
+            if changes.is_empty() {
+                continue;
+            }
+            let mut table = new_table(color);
+            table.set_header(vec!["Change", "Name", "Old Version", "New Version"]);
+            for change in changes {
+                let kind = match change.kind {
+                    ChangeKind::Added => Cell::new("+ added").fg(Color::Green),
+                    ChangeKind::Removed => Cell::new("- removed").fg(Color::Red),
+                    ChangeKind::Changed => Cell::new("~ changed").fg(Color::Yellow),
+                };
+                table.add_row(vec![
+                    kind,
+                    Cell::new(&change.name),
+                    Cell::new(change.old_version.as_deref().unwrap_or("-")),
+                    Cell::new(change.new_version.as_deref().unwrap_or("-")),
+                ]);
+            }
+            sections.push(format!("{} ({})\n{}", title, changes.len(), table));
+        }
+        sections.join("\n\n")
+    }
+
+    /// Format rows as a table using a user-selected column layout.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SoftwareScanner;
+    /// use sysaudit::output::{ColumnSpec, ConsoleFormatter};
+    ///
+    /// let software = SoftwareScanner::new().scan().unwrap();
+    /// let spec = ColumnSpec::parse("name,version,install_location");
+    /// println!("{}", ConsoleFormatter::format_columns(&software, &spec).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if the spec names an unknown column.
+    pub fn format_columns<T: Tabular>(rows: &[T], columns: &ColumnSpec) -> Result<String, Error> {
+        let columns = columns.resolve::<T>()?;
+
+        let mut table = new_table(Self::color());
+        table.set_header(columns.iter().map(|(_, header)| *header));
+
+        for row in rows {
+            table.add_row(
+                columns
+                    .iter()
+                    .map(|(key, _)| row.value(key).unwrap_or_else(|| "-".to_string())),
+            );
+        }
+
+        Ok(format!("{}\nFound: {} items", table, rows.len()))
+    }
+}
+
+/// `site [tag, tag]` for a fleet row, `-` when ungrouped.
+#[cfg(feature = "remote")]
+fn group_label(host: &HostResult) -> String {
+    let site = host.site.as_deref().unwrap_or("-");
+    if host.tags.is_empty() {
+        site.to_string()
+    } else {
+        format!("{} [{}]", site, host.tags.join(", "))
+    }
+}
+
+/// Support status cell: green, yellow when ending soon, red once out of support.
+fn support_cell(support: OsSupport) -> Option<Cell> {
+    let color = match support {
+        OsSupport::Unknown => return None,
+        OsSupport::Supported(_) => Color::Green,
+        OsSupport::EndingSoon(_) => Color::Yellow,
+        OsSupport::EndOfLife(_) => Color::Red,
+    };
+    let cell = Cell::new(support).fg(color);
+    Some(if support.is_end_of_life() {
+        cell.add_attribute(Attribute::Bold)
+    } else {
+        cell
+    })
+}
+
+/// Vendor support cell for an installed package; `-` when not tracked.
+fn software_support_cell(sw: &Software) -> Cell {
+    let Some(status) = sw.support_status else {
+        return Cell::new("-");
+    };
+    let text = match sw.eol {
+        Some(eol) => format!("{} ({})", status, eol),
+        None => status.to_string(),
+    };
+    match status {
+        SupportStatus::Supported => Cell::new(text).fg(Color::Green),
+        SupportStatus::EndingSoon => Cell::new(text).fg(Color::Yellow),
+        SupportStatus::EndOfLife => Cell::new(text)
+            .fg(Color::Red)
+            .add_attribute(Attribute::Bold),
+    }
+}
+
+/// Runtime state of an industrial product; `-` when unknown.
+fn runtime_cell(state: Option<RuntimeState>) -> Cell {
+    match state {
+        Some(RuntimeState::Running) => Cell::new(RuntimeState::Running).fg(Color::Green),
+        Some(state) => Cell::new(state),
+        None => Cell::new("-"),
+    }
+}
+
+/// Overall score with grade and trend, e.g. `72/100 (C), +5 since last scan`.
+fn score_cell(score: &AuditScore) -> Cell {
+    let (Some(overall), Some(grade)) = (score.overall, score.grade()) else {
+        return Cell::new("n/a");
+    };
+    let mut text = format!("{}/100 ({})", overall, grade);
+    match score.trend {
+        Some(0) => text.push_str(", unchanged since last scan"),
+        Some(trend) => text.push_str(&format!(", {:+} since last scan", trend)),
+        None => {}
+    }
+    score_colored(Cell::new(text), overall).add_attribute(Attribute::Bold)
+}
+
+/// Color a cell green (80+), yellow (60+) or red by score.
+fn score_colored(cell: Cell, score: u8) -> Cell {
+    match score {
+        80.. => cell.fg(Color::Green),
+        60..=79 => cell.fg(Color::Yellow),
+        _ => cell.fg(Color::Red),
+    }
+}
+
+/// `1.25 s` from 1250 ms, `300 ms` below a second.
+fn format_millis(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{} ms", ms)
+    } else {
+        format!("{:.2} s", ms as f64 / 1000.0)
+    }
+}
+
+/// `812 B`, `14.20 KB`, `361 MB`.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 100.0 {
+        format!("{:.2} {}", value, UNITS[unit])
+    } else {
+        format!("{:.0} {}", value, UNITS[unit])
+    }
+}
+
+/// `Network: 40.2 KB sent, 1.31 MB received in 14 requests`.
+fn transfer_line(transfer: &TransferMetrics) -> String {
+    format!(
+        "Network: {} sent, {} received in {} requests",
+        format_bytes(transfer.bytes_sent),
+        format_bytes(transfer.bytes_received),
+        transfer.requests
+    )
+}
+
+/// Cell text of a JSON value: strings unquoted, `-` for null, other
+/// values as compact JSON.
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Severity marker cell, colored by severity.
+fn severity_cell(severity: Severity) -> Cell {
+    styled_by_severity(Cell::new(severity.to_string().to_uppercase()), severity)
+}
+
+/// Apply the color used for `severity` to a cell.
+fn styled_by_severity(cell: Cell, severity: Severity) -> Cell {
+    match severity {
+        Severity::Critical | Severity::High => cell.fg(Color::Red).add_attribute(Attribute::Bold),
+        Severity::Medium => cell.fg(Color::Yellow),
+        Severity::Low => cell.fg(Color::Cyan),
+        Severity::Info => cell,
+    }
+}
+
+/// Table with the shared preset, width and color handling.
+fn new_table(color: ColorChoice) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(MAX_TABLE_WIDTH);
+    match color {
+        ColorChoice::Auto => {}
+        ColorChoice::Always => {
+            table.enforce_styling();
+        }
+        ColorChoice::Never => {
+            table.force_no_tty();
+        }
+    }
+    table
 }
 
 #[cfg(test)]
@@ -200,8 +1477,11 @@ mod tests {
             version: Some("1.0.0".to_string()),
             publisher: Some("Test Corp".to_string()),
             install_date: NaiveDate::from_ymd_opt(2024, 1, 1),
+            install_date_source: crate::InstallDateSource::Exact,
             install_location: Some(PathBuf::from("C:\\App")),
             source: crate::RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
         };
 
         let output = ConsoleFormatter::format_software(&[sw]);
@@ -220,6 +1500,42 @@ mod tests {
         assert!(output.contains("Found: 0 updates"));
     }
 
+    #[test]
+    fn test_format_metrics() {
+        let mut metrics = ScanMetrics::new();
+        metrics.record(
+            crate::Collector::Software,
+            std::time::Duration::from_millis(1250),
+        );
+        metrics.record(
+            crate::Collector::Updates,
+            std::time::Duration::from_millis(300),
+        );
+        let output = ConsoleFormatter::format_metrics(&metrics);
+        assert!(output.contains("software"));
+        assert!(output.contains("1.25 s"));
+        assert!(output.contains("300 ms"));
+        assert!(output.contains("TOTAL"));
+        assert!(!output.contains("Network"));
+
+        let output = ConsoleFormatter::format_metrics(&metrics.transfer(TransferMetrics {
+            bytes_sent: 41_200,
+            bytes_received: 1_372_000,
+            requests: 14,
+            payload_bytes: 38_000,
+            report_bytes: 2_048_000,
+        }));
+        assert!(output.contains("Network: 40.23 KB sent, 1.31 MB received in 14 requests"));
+        assert!(output.contains("report 1.95 MB"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(812), "812 B");
+        assert_eq!(format_bytes(1536), "1.50 KB");
+        assert_eq!(format_bytes(300 * 1024 * 1024), "300 MB");
+    }
+
     #[test]
     fn test_format_system_info() {
         let info = SystemInfo {
@@ -228,6 +1544,8 @@ mod tests {
             build_number: "22631.3007".into(),
             computer_name: "TEST-PC".into(),
             domain: Some("contoso.local".into()),
+            identity: Default::default(),
+            fingerprint: Default::default(),
             cpu_info: "Intel i7-9700".into(),
             network_interfaces: vec![],
             manufacturer: Some("Dell Inc.".into()),
@@ -256,6 +1574,7 @@ mod tests {
             product: "AVEVA Plant SCADA 2023".into(),
             version: Some("8.0".into()),
             install_path: Some(PathBuf::from(r"C:\Citect")),
+            runtime_state: None,
         };
 
         let output = ConsoleFormatter::format_industrial(&[sw]);
@@ -264,4 +1583,178 @@ mod tests {
         assert!(output.contains("8.0"));
         assert!(output.contains("Found: 1 industrial"));
     }
+
+    #[test]
+    fn test_format_columns_selection() {
+        let sw = Software {
+            name: "Test App".to_string(),
+            version: None,
+            publisher: Some("Test Corp".to_string()),
+            install_date: None,
+            install_date_source: crate::InstallDateSource::Unknown,
+            install_location: Some(PathBuf::from("C:\\App")),
+            source: crate::RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
+        };
+
+        let spec = ColumnSpec::parse("install_location,name");
+        let output = ConsoleFormatter::format_columns(&[sw], &spec).unwrap();
+        assert!(output.contains("Install Location"));
+        assert!(output.contains("C:\\App"));
+        assert!(!output.contains("Publisher"));
+        assert!(!output.contains("Test Corp"));
+    }
+
+    fn eol_system() -> SystemInfo {
+        SystemInfo {
+            os_name: "Windows 7 Professional".into(),
+            os_version: "6.1".into(),
+            build_number: "7601.24544".into(),
+            computer_name: "HMI-01".into(),
+            domain: None,
+            identity: Default::default(),
+            fingerprint: Default::default(),
+            cpu_info: "Intel Atom".into(),
+            network_interfaces: vec![crate::NetworkInterface {
+                name: "Plant LAN".into(),
+                ip_address: "192.168.1.10".parse().unwrap(),
+                subnet_mask: Some("24".into()),
+                gateway: None,
+                mac_address: None,
+                additional_addresses: Vec::new(),
+            }],
+            manufacturer: None,
+            model: None,
+            cpu_cores_physical: None,
+            cpu_cores_logical: None,
+            cpu_frequency_mhz: 1600,
+            memory_total: 0,
+            memory_used: 0,
+            memory_free: 0,
+        }
+    }
+
+    #[test]
+    fn test_system_info_end_of_life_styled() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let output = ConsoleFormatter::system_info(&eol_system(), today, ColorChoice::Always);
+        assert!(output.contains("END OF LIFE since 2020-01-14"));
+        // red foreground and dim attribute escape sequences
+        assert!(output.contains("\u{1b}[38;5;9m") || output.contains("\u{1b}[31m"));
+        assert!(output.contains("\u{1b}[2m"));
+    }
+
+    #[test]
+    fn test_no_color_plain() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let output = ConsoleFormatter::system_info(&eol_system(), today, ColorChoice::Never);
+        assert!(output.contains("END OF LIFE"));
+        assert!(!output.contains('\u{1b}'));
+
+        let industrial = IndustrialSoftware {
+            vendor: crate::Vendor::Citect,
+            product: "Plant SCADA".into(),
+            version: None,
+            install_path: None,
+            runtime_state: None,
+        };
+        let output = ConsoleFormatter::industrial(&[industrial], ColorChoice::Never);
+        assert!(output.contains("Plant SCADA"));
+        assert!(!output.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_format_summary() {
+        use chrono::TimeZone;
+        use sysaudit_common::{IndustrialSoftwareDto, SystemInfoDto};
+
+        let report = AuditReport {
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "Rockwell".into(),
+                product: "FactoryTalk View".into(),
+                version: None,
+                install_path: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Pro".into(),
+                os_version: "22H2".into(),
+                host_name: "HMI-01".into(),
+                cpu_info: "Intel Atom".into(),
+                cpu_physical_cores: Some(4),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        };
+
+        let output = ConsoleFormatter::summary(&report, ColorChoice::Never);
+        assert!(output.contains("HMI-01"));
+        assert!(output.contains("END OF LIFE since 2025-10-14"));
+        assert!(output.contains("23/100 (F)"));
+        assert!(output.contains("1 High, 1 Medium"));
+        assert!(output.contains("Top Findings"));
+        // most severe first
+        let patches = output.find("No installed Windows updates").unwrap();
+        let industrial = output.find("Industrial software detected").unwrap();
+        assert!(patches < industrial);
+    }
+
+    #[test]
+    fn test_format_summary_metadata() {
+        let report =
+            AuditReport::from_json_versioned(include_str!("../../tests/fixtures/report.json"))
+                .unwrap();
+
+        let output = ConsoleFormatter::summary(&report, ColorChoice::Never);
+        assert!(output.contains("local scan in 19.25 s by sysaudit 0.1.0"));
+        assert!(output.contains(r"PLANT\audit (elevated)"));
+        assert!(output.contains("sections=software,industrial,updates,services,security"));
+    }
+
+    #[cfg(feature = "remote")]
+    #[test]
+    fn test_format_fleet_failures() {
+        use crate::ScanError;
+        use crate::fleet::HostResult;
+
+        let results = vec![HostResult {
+            host: "hmi02".into(),
+            site: Some("north".into()),
+            tags: vec!["line1".into()],
+            result: Err(ScanError::RemoteAuth {
+                host: "hmi02".into(),
+                user: "audit".into(),
+            }),
+        }];
+
+        let output = ConsoleFormatter::fleet(&results, ColorChoice::Never);
+        assert!(output.contains("hmi02"));
+        assert!(output.contains("FAILED (auth)"));
+        assert!(output.contains("north [line1]"));
+        assert!(output.contains("Scanned: 1 hosts, 1 failed"));
+    }
+
+    #[test]
+    fn test_format_fleet_report() {
+        use crate::fleet_report::FleetHost;
+
+        let fleet = FleetReport::new(vec![FleetHost {
+            host: "hmi03".into(),
+            site: None,
+            tags: Vec::new(),
+            report: None,
+            error: Some("connection refused".into()),
+            error_kind: Some(crate::ScanErrorKind::Network),
+        }]);
+
+        let output = ConsoleFormatter::fleet_report(&fleet, ColorChoice::Never);
+        assert!(output.contains("OS BUILDS"));
+        assert!(output.contains("PATCH CURRENCY"));
+        assert!(!output.contains("INDUSTRIAL SOFTWARE"));
+        assert!(output.contains("Hosts: 0 scanned, 1 failed (1 network)"));
+    }
 }
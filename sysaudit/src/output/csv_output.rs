@@ -1,25 +1,261 @@
 //! CSV export functionality.
 
-use crate::{Error, IndustrialSoftware, Software, WindowsUpdate};
-use std::path::Path;
+use super::columns::{ColumnSpec, Tabular};
+use crate::{
+    AuditReport, Error, FleetReport, IndustrialSoftware, Software, SystemInfo, WindowsUpdate,
+};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// UTF-8 byte order mark, required by Excel to detect UTF-8 CSV files.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// CSV dialect: delimiter, BOM and line endings.
+///
+/// The default is comma-separated, no BOM, `\n` line endings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvDialect {
+    delimiter: u8,
+    bom: bool,
+    crlf: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvDialect {
+    /// Create the default dialect.
+    pub fn new() -> Self {
+        CsvDialect {
+            delimiter: b',',
+            bom: false,
+            crlf: false,
+        }
+    }
+
+    /// Field delimiter byte (e.g. `b';'`).
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Emit a UTF-8 byte order mark at the start of the file.
+    pub fn bom(mut self, bom: bool) -> Self {
+        self.bom = bom;
+        self
+    }
+
+    /// Terminate records with `\r\n` instead of `\n`.
+    pub fn crlf(mut self, crlf: bool) -> Self {
+        self.crlf = crlf;
+        self
+    }
+
+    /// Parse a delimiter name (`comma`, `semicolon`, `tab`, `pipe`) or the
+    /// character itself, a tab being written `\t`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for anything else.
+    pub fn parse_delimiter(name: &str) -> Result<u8, Error> {
+        match name.trim().to_lowercase().as_str() {
+            "comma" | "," => Ok(b','),
+            "semicolon" | ";" => Ok(b';'),
+            "tab" | "\\t" => Ok(b'\t'),
+            "pipe" | "|" => Ok(b'|'),
+            other => Err(Error::General(format!(
+                "unsupported CSV delimiter '{}' (use comma, semicolon, tab or pipe)",
+                other
+            ))),
+        }
+    }
+
+    fn writer(&self, path: &Path) -> Result<csv::Writer<File>, Error> {
+        self.writer_for(File::create(path)?)
+    }
+
+    /// Build a CSV writer over any output, emitting the BOM if enabled.
+    pub(crate) fn writer_for<W: Write>(&self, mut out: W) -> Result<csv::Writer<W>, Error> {
+        if self.bom {
+            out.write_all(UTF8_BOM)?;
+        }
+
+        let terminator = if self.crlf {
+            csv::Terminator::CRLF
+        } else {
+            csv::Terminator::Any(b'\n')
+        };
+
+        Ok(csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .terminator(terminator)
+            .from_writer(out))
+    }
+}
 
 /// CSV exporter for audit data.
 pub struct CsvExporter;
 
 impl CsvExporter {
+    /// Export system info to CSV as `Field,Value` rows.
+    ///
+    /// Network interfaces are written as one `Interface <name>` row each.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_system(info: &SystemInfo, path: &Path) -> Result<(), Error> {
+        Self::export_system_with(info, path, &CsvDialect::default())
+    }
+
+    /// Like [`CsvExporter::export_system`], using the given dialect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_system_with(
+        info: &SystemInfo,
+        path: &Path,
+        dialect: &CsvDialect,
+    ) -> Result<(), Error> {
+        let mut wtr = dialect.writer(path)?;
+        Self::write_system(&mut wtr, info)
+    }
+
     /// Export software list to CSV.
     ///
     /// # Errors
     ///
     /// Returns [`Error`] if the file cannot be created or written.
     pub fn export_software(software: &[Software], path: &Path) -> Result<(), Error> {
-        let mut wtr = csv::Writer::from_path(path)?;
+        Self::export_software_with(software, path, &CsvDialect::default())
+    }
+
+    /// Like [`CsvExporter::export_software`], using the given dialect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_software_with(
+        software: &[Software],
+        path: &Path,
+        dialect: &CsvDialect,
+    ) -> Result<(), Error> {
+        let mut wtr = dialect.writer(path)?;
+        Self::write_software(&mut wtr, software)
+    }
+
+    /// Export industrial software to CSV.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_industrial(software: &[IndustrialSoftware], path: &Path) -> Result<(), Error> {
+        Self::export_industrial_with(software, path, &CsvDialect::default())
+    }
+
+    /// Like [`CsvExporter::export_industrial`], using the given dialect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_industrial_with(
+        software: &[IndustrialSoftware],
+        path: &Path,
+        dialect: &CsvDialect,
+    ) -> Result<(), Error> {
+        let mut wtr = dialect.writer(path)?;
+        Self::write_industrial(&mut wtr, software)
+    }
+
+    /// Export Windows updates to CSV.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_updates(updates: &[WindowsUpdate], path: &Path) -> Result<(), Error> {
+        Self::export_updates_with(updates, path, &CsvDialect::default())
+    }
+
+    /// Like [`CsvExporter::export_updates`], using the given dialect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_updates_with(
+        updates: &[WindowsUpdate],
+        path: &Path,
+        dialect: &CsvDialect,
+    ) -> Result<(), Error> {
+        let mut wtr = dialect.writer(path)?;
+        Self::write_updates(&mut wtr, updates)
+    }
 
+    /// Write system info rows to an existing CSV writer.
+    pub(crate) fn write_system<W: Write>(
+        wtr: &mut csv::Writer<W>,
+        info: &SystemInfo,
+    ) -> Result<(), Error> {
+        wtr.write_record(["Field", "Value"])?;
+
+        let opt = |v: &Option<String>| v.clone().unwrap_or_default();
+        let rows = [
+            ("Computer Name", info.computer_name.clone()),
+            ("Domain", opt(&info.domain)),
+            ("FQDN", opt(&info.identity.fqdn)),
+            ("DNS Search List", info.identity.dns_search_list.join(" ")),
+            ("Manufacturer", opt(&info.manufacturer)),
+            ("Model", opt(&info.model)),
+            ("OS Name", info.os_name.clone()),
+            ("OS Version", info.os_version.clone()),
+            ("Build", info.build_number.clone()),
+            ("CPU", info.cpu_info.clone()),
+            (
+                "CPU Cores (Physical)",
+                info.cpu_cores_physical
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                "CPU Cores (Logical)",
+                info.cpu_cores_logical
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("CPU Frequency (MHz)", info.cpu_frequency_mhz.to_string()),
+            ("Memory Total (bytes)", info.memory_total.to_string()),
+            ("Memory Used (bytes)", info.memory_used.to_string()),
+            ("Memory Free (bytes)", info.memory_free.to_string()),
+        ];
+        for (field, value) in &rows {
+            wtr.write_record([*field, value.as_str()])?;
+        }
+
+        for iface in &info.network_interfaces {
+            for ip in iface.ip_addresses() {
+                wtr.write_record([format!("Interface {}", iface.name), ip.to_string()])?;
+            }
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+
+    /// Write the software table to an existing CSV writer.
+    pub(crate) fn write_software<W: Write>(
+        wtr: &mut csv::Writer<W>,
+        software: &[Software],
+    ) -> Result<(), Error> {
         wtr.write_record([
             "Name",
             "Version",
             "Publisher",
             "Install Date",
+            "Install Date Source",
             "Install Location",
             "Source",
         ])?;
@@ -30,6 +266,7 @@ impl CsvExporter {
                 sw.version.as_deref().unwrap_or(""),
                 sw.publisher.as_deref().unwrap_or(""),
                 &sw.install_date.map(|d| d.to_string()).unwrap_or_default(),
+                &sw.install_date_source.to_string(),
                 &sw.install_location
                     .as_ref()
                     .map(|p| p.display().to_string())
@@ -42,14 +279,11 @@ impl CsvExporter {
         Ok(())
     }
 
-    /// Export industrial software to CSV.
-    ///
-    /// # Errors
-    ///
-    /// Returns [`Error`] if the file cannot be created or written.
-    pub fn export_industrial(software: &[IndustrialSoftware], path: &Path) -> Result<(), Error> {
-        let mut wtr = csv::Writer::from_path(path)?;
-
+    /// Write the industrial table to an existing CSV writer.
+    pub(crate) fn write_industrial<W: Write>(
+        wtr: &mut csv::Writer<W>,
+        software: &[IndustrialSoftware],
+    ) -> Result<(), Error> {
         wtr.write_record(["Vendor", "Product", "Version", "Install Path"])?;
 
         for sw in software {
@@ -68,14 +302,11 @@ impl CsvExporter {
         Ok(())
     }
 
-    /// Export Windows updates to CSV.
-    ///
-    /// # Errors
-    ///
-    /// Returns [`Error`] if the file cannot be created or written.
-    pub fn export_updates(updates: &[WindowsUpdate], path: &Path) -> Result<(), Error> {
-        let mut wtr = csv::Writer::from_path(path)?;
-
+    /// Write the updates table to an existing CSV writer.
+    pub(crate) fn write_updates<W: Write>(
+        wtr: &mut csv::Writer<W>,
+        updates: &[WindowsUpdate],
+    ) -> Result<(), Error> {
         wtr.write_record(["HotFix ID", "Description", "Installed On", "Installed By"])?;
 
         for update in updates {
@@ -93,6 +324,390 @@ impl CsvExporter {
         wtr.flush()?;
         Ok(())
     }
+
+    /// Export a saved report's sections as CSV files in `dir`:
+    /// `system.csv`, `network.csv`, `software.csv`, `industrial.csv` and
+    /// `updates.csv`.
+    ///
+    /// Works from the report alone, so a JSON report written earlier (or
+    /// on another machine) can be re-exported without rescanning.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use sysaudit::AuditReport;
+    /// use sysaudit::output::{CsvDialect, CsvExporter};
+    ///
+    /// # fn example() -> Result<(), sysaudit::Error> {
+    /// let report = AuditReport::from_json_versioned(&std::fs::read_to_string("hmi01.json")?)?;
+    /// CsvExporter::export_report(&report, Path::new("hmi01"), &CsvDialect::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the directory or any file cannot be written.
+    pub fn export_report(
+        report: &AuditReport,
+        dir: &Path,
+        dialect: &CsvDialect,
+    ) -> Result<Vec<PathBuf>, Error> {
+        std::fs::create_dir_all(dir)?;
+        let mut written = Vec::new();
+        let system = &report.system;
+
+        let path = dir.join("system.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["Field", "Value"])?;
+        let opt = |v: &Option<String>| v.clone().unwrap_or_default();
+        let rows = [
+            ("Host Name", system.host_name.clone()),
+            (
+                "FQDN",
+                opt(&report.identity.as_ref().and_then(|i| i.fqdn.clone())),
+            ),
+            ("Manufacturer", opt(&system.manufacturer)),
+            ("Model", opt(&system.model)),
+            ("OS Name", system.os_name.clone()),
+            ("OS Version", system.os_version.clone()),
+            ("CPU", system.cpu_info.clone()),
+            (
+                "CPU Cores (Physical)",
+                system
+                    .cpu_physical_cores
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                "Memory Total (bytes)",
+                system.memory_total_bytes.to_string(),
+            ),
+            ("Memory Used (bytes)", system.memory_used_bytes.to_string()),
+            ("Scan Timestamp", report.timestamp.to_rfc3339()),
+        ];
+        for (field, value) in &rows {
+            wtr.write_record([*field, value.as_str()])?;
+        }
+        if let Some(metadata) = &report.metadata {
+            for (field, value) in metadata.fields() {
+                wtr.write_record([field, value.as_str()])?;
+            }
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("network.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["Interface", "IP Address", "IP Version", "MAC"])?;
+        for iface in &system.network_interfaces {
+            wtr.write_record([
+                iface.name.as_str(),
+                &iface.ip_address,
+                &format!("{:?}", iface.ip_version),
+                iface.mac_address.as_deref().unwrap_or(""),
+            ])?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("software.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["Name", "Version", "Publisher", "Install Date"])?;
+        for sw in &report.software {
+            wtr.write_record([
+                sw.name.as_str(),
+                sw.version.as_deref().unwrap_or(""),
+                sw.vendor.as_deref().unwrap_or(""),
+                &sw.install_date
+                    .map(|d| d.date_naive().to_string())
+                    .unwrap_or_default(),
+            ])?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("industrial.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["Vendor", "Product", "Version", "Install Path"])?;
+        for sw in &report.industrial {
+            wtr.write_record([
+                sw.vendor.as_str(),
+                &sw.product,
+                sw.version.as_deref().unwrap_or(""),
+                &sw.install_path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default(),
+            ])?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("updates.csv");
+        let mut wtr = dialect.writer(&path)?;
+        Self::write_updates(&mut wtr, &report.updates)?;
+        written.push(path);
+
+        Ok(written)
+    }
+
+    /// Export a fleet report's host list and rollups as CSV files in `dir`:
+    /// `hosts.csv`, `software.csv`, `os_builds.csv`,
+    /// `industrial_matrix.csv`, `patch_currency.csv` and `clones.csv`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use sysaudit::FleetReport;
+    /// use sysaudit::output::{CsvDialect, CsvExporter};
+    ///
+    /// # fn example(fleet: &FleetReport) -> Result<(), sysaudit::Error> {
+    /// let files = CsvExporter::export_fleet(fleet, Path::new("fleet"), &CsvDialect::default())?;
+    /// println!("Wrote {} files", files.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the directory or any file cannot be written.
+    pub fn export_fleet(
+        report: &FleetReport,
+        dir: &Path,
+        dialect: &CsvDialect,
+    ) -> Result<Vec<PathBuf>, Error> {
+        std::fs::create_dir_all(dir)?;
+        let mut written = Vec::new();
+
+        let path = dir.join("hosts.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record([
+            "Host",
+            "Site",
+            "Tags",
+            "Status",
+            "OS",
+            "OS Version",
+            "Software",
+            "Industrial",
+            "Updates",
+            "Error Kind",
+            "Error",
+        ])?;
+        for host in &report.hosts {
+            let tags = host.tags.join(";");
+            let site = host.site.as_deref().unwrap_or("");
+            match &host.report {
+                Some(r) => wtr.write_record([
+                    host.host.as_str(),
+                    site,
+                    &tags,
+                    "ok",
+                    &r.system.os_name,
+                    &r.system.os_version,
+                    &r.software.len().to_string(),
+                    &r.industrial.len().to_string(),
+                    &r.updates.len().to_string(),
+                    "",
+                    "",
+                ])?,
+                None => wtr.write_record([
+                    host.host.as_str(),
+                    site,
+                    &tags,
+                    "failed",
+                    "",
+                    "",
+                    "",
+                    "",
+                    "",
+                    &host.error_kind.map(|k| k.to_string()).unwrap_or_default(),
+                    host.error.as_deref().unwrap_or(""),
+                ])?,
+            }
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("software.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["Name", "Version", "Hosts"])?;
+        for sw in &report.software {
+            wtr.write_record([
+                sw.name.as_str(),
+                sw.version.as_deref().unwrap_or(""),
+                &sw.hosts.to_string(),
+            ])?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("os_builds.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["OS", "OS Version", "Host Count", "Hosts"])?;
+        for build in &report.os_builds {
+            wtr.write_record([
+                build.os_name.as_str(),
+                &build.os_version,
+                &build.hosts.len().to_string(),
+                &build.hosts.join(";"),
+            ])?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("industrial_matrix.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(
+            std::iter::once("Host").chain(report.industrial.products.iter().map(String::as_str)),
+        )?;
+        for row in &report.industrial.rows {
+            wtr.write_record(
+                std::iter::once(row.host.as_str()).chain(row.versions.iter().map(|v| {
+                    match v.as_deref() {
+                        Some("") => "installed",
+                        Some(version) => version,
+                        None => "",
+                    }
+                })),
+            )?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("patch_currency.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["Last Update", "Hosts"])?;
+        for bucket in &report.patch_currency {
+            wtr.write_record([bucket.currency.to_string(), bucket.hosts.to_string()])?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        let path = dir.join("clones.csv");
+        let mut wtr = dialect.writer(&path)?;
+        wtr.write_record(["Evidence", "Value", "Host Count", "Hosts"])?;
+        for group in &report.clones {
+            wtr.write_record([
+                group.evidence.to_string(),
+                group.value.clone(),
+                group.hosts.len().to_string(),
+                group.hosts.join(";"),
+            ])?;
+        }
+        wtr.flush()?;
+        written.push(path);
+
+        Ok(written)
+    }
+
+    /// Export the Autopilot registration data of scanned hosts in the
+    /// Intune import format (`Device Serial Number,Windows Product
+    /// ID,Hardware Hash`, plus `Group Tag` when one is given). Hosts
+    /// without a serial number or hardware hash are skipped with a
+    /// warning. Returns the number of devices written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use sysaudit::AuditReport;
+    /// use sysaudit::output::CsvExporter;
+    ///
+    /// # fn example(reports: &[AuditReport]) -> Result<(), sysaudit::Error> {
+    /// let devices = CsvExporter::export_autopilot(reports, Path::new("autopilot.csv"), Some("Plant1"))?;
+    /// println!("{} devices ready for import", devices);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the file cannot be created or written.
+    pub fn export_autopilot<'a>(
+        reports: impl IntoIterator<Item = &'a AuditReport>,
+        path: &Path,
+        group_tag: Option<&str>,
+    ) -> Result<usize, Error> {
+        // Get-WindowsAutopilotInfo writes CRLF and Intune takes no BOM
+        let mut wtr = CsvDialect::new().crlf(true).writer(path)?;
+        let mut header = vec![
+            "Device Serial Number",
+            "Windows Product ID",
+            "Hardware Hash",
+        ];
+        if group_tag.is_some() {
+            header.push("Group Tag");
+        }
+        wtr.write_record(&header)?;
+
+        let mut devices = 0;
+        for report in reports {
+            let autopilot = report.autopilot.as_ref();
+            let (Some(serial), Some(hash)) = (
+                autopilot.and_then(|a| a.serial_number.as_deref()),
+                autopilot.and_then(|a| a.hardware_hash.as_deref()),
+            ) else {
+                tracing::warn!(
+                    host = %report.system.host_name,
+                    "No Autopilot serial number or hardware hash, host skipped"
+                );
+                continue;
+            };
+            let mut record = vec![serial, "", hash];
+            record.extend(group_tag);
+            wtr.write_record(&record)?;
+            devices += 1;
+        }
+        wtr.flush()?;
+        Ok(devices)
+    }
+
+    /// Export rows to CSV using a user-selected column layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the spec names an unknown column or the file
+    /// cannot be created or written.
+    pub fn export_columns<T: Tabular>(
+        rows: &[T],
+        columns: &ColumnSpec,
+        path: &Path,
+    ) -> Result<(), Error> {
+        Self::export_columns_with(rows, columns, path, &CsvDialect::default())
+    }
+
+    /// Like [`CsvExporter::export_columns`], using the given dialect.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the spec names an unknown column or the file
+    /// cannot be created or written.
+    pub fn export_columns_with<T: Tabular>(
+        rows: &[T],
+        columns: &ColumnSpec,
+        path: &Path,
+        dialect: &CsvDialect,
+    ) -> Result<(), Error> {
+        let columns = columns.resolve::<T>()?;
+        let mut wtr = dialect.writer(path)?;
+
+        wtr.write_record(columns.iter().map(|(_, header)| *header))?;
+
+        for row in rows {
+            wtr.write_record(
+                columns
+                    .iter()
+                    .map(|(key, _)| row.value(key).unwrap_or_default()),
+            )?;
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -114,8 +729,11 @@ mod tests {
             version: Some("1.0".into()),
             publisher: Some("Acme".into()),
             install_date: NaiveDate::from_ymd_opt(2024, 1, 15),
+            install_date_source: crate::InstallDateSource::Exact,
             install_location: Some(PathBuf::from(r"C:\App")),
             source: RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
         }];
 
         CsvExporter::export_software(&sw, &path).unwrap();
@@ -123,6 +741,7 @@ mod tests {
         assert!(content.contains("TestApp"));
         assert!(content.contains("1.0"));
         assert!(content.contains("Acme"));
+        assert!(content.contains("2024-01-15,exact"));
         std::fs::remove_file(&path).ok();
     }
 
@@ -151,6 +770,7 @@ mod tests {
             product: "Studio 5000".into(),
             version: Some("33.0".into()),
             install_path: None,
+            runtime_state: None,
         }];
 
         CsvExporter::export_industrial(&sw, &path).unwrap();
@@ -159,4 +779,105 @@ mod tests {
         assert!(content.contains("Studio 5000"));
         std::fs::remove_file(&path).ok();
     }
+
+    #[test]
+    fn test_export_columns_csv() {
+        let path = temp_csv("columns");
+        let updates = vec![WindowsUpdate {
+            hotfix_id: "KB5034441".into(),
+            description: Some("Security Update".into()),
+            installed_on: NaiveDate::from_ymd_opt(2024, 1, 15),
+            installed_by: None,
+        }];
+
+        let spec = ColumnSpec::parse("installed_on,hotfix_id");
+        CsvExporter::export_columns(&updates, &spec, &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("Installed On,HotFix ID"));
+        assert!(content.contains("2024-01-15,KB5034441"));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(CsvDialect::parse_delimiter("semicolon").unwrap(), b';');
+        assert_eq!(CsvDialect::parse_delimiter("TAB").unwrap(), b'\t');
+        assert_eq!(CsvDialect::parse_delimiter("\\t").unwrap(), b'\t');
+        assert_eq!(CsvDialect::parse_delimiter(",").unwrap(), b',');
+        assert!(CsvDialect::parse_delimiter("colon").is_err());
+    }
+
+    #[test]
+    fn test_export_with_dialect() {
+        let path = temp_csv("dialect");
+        let updates = vec![WindowsUpdate {
+            hotfix_id: "KB5034441".into(),
+            description: Some("Security Update".into()),
+            installed_on: None,
+            installed_by: None,
+        }];
+
+        let dialect = CsvDialect::new().delimiter(b';').bom(true).crlf(true);
+        CsvExporter::export_updates_with(&updates, &path, &dialect).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(UTF8_BOM));
+        let content = String::from_utf8(bytes[UTF8_BOM.len()..].to_vec()).unwrap();
+        assert!(content.starts_with("HotFix ID;Description;Installed On;Installed By\r\n"));
+        assert!(content.contains("KB5034441;Security Update;;\r\n"));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_export_report_dir() {
+        let report =
+            AuditReport::from_json_versioned(include_str!("../../tests/fixtures/report.json"))
+                .unwrap();
+        let dir = std::env::temp_dir().join("sysaudit_test_report_csv");
+
+        let files = CsvExporter::export_report(&report, &dir, &CsvDialect::default()).unwrap();
+        assert_eq!(files.len(), 5);
+        let software = std::fs::read_to_string(dir.join("software.csv")).unwrap();
+        assert!(software.starts_with("Name,Version,Publisher,Install Date"));
+        assert_eq!(software.lines().count(), report.software.len() + 1);
+        let system = std::fs::read_to_string(dir.join("system.csv")).unwrap();
+        assert!(system.contains(&report.system.host_name));
+        assert!(system.contains("Scan Duration (ms),19250"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_export_autopilot() {
+        let mut report =
+            AuditReport::from_json_versioned(include_str!("../../tests/fixtures/report.json"))
+                .unwrap();
+        let unregistered = report.clone();
+        report.autopilot = Some(crate::Autopilot {
+            serial_number: Some("5CG1234XYZ".into()),
+            manufacturer: Some("HP".into()),
+            model: Some("EliteDesk 800 G6".into()),
+            hardware_hash: Some("T0FCBQEAHAAAAAoAHgZhSgAACgCgNwAA".into()),
+        });
+        let path = temp_csv("autopilot");
+
+        let devices =
+            CsvExporter::export_autopilot([&report, &unregistered], &path, Some("Plant1")).unwrap();
+        assert_eq!(devices, 1);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "Device Serial Number,Windows Product ID,Hardware Hash,Group Tag\r\n\
+             5CG1234XYZ,,T0FCBQEAHAAAAAoAHgZhSgAACgCgNwAA,Plant1\r\n"
+        );
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_default_dialect_no_bom() {
+        let path = temp_csv("default_dialect");
+        CsvExporter::export_updates(&[], &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"HotFix ID,"));
+        assert!(!bytes.contains(&b'\r'));
+        std::fs::remove_file(&path).ok();
+    }
 }
@@ -0,0 +1,299 @@
+//! Elasticsearch / OpenSearch bulk indexing.
+
+use crate::{AuditReport, Error};
+use reqwest::blocking::Client;
+use secrecy::{ExposeSecret, SecretString};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Default index name.
+pub const DEFAULT_INDEX: &str = "sysaudit";
+
+/// Request timeout for bulk calls.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Authentication for the cluster.
+#[derive(Debug, Clone, Default)]
+pub enum ElasticAuth {
+    /// No authentication
+    #[default]
+    None,
+    /// HTTP basic authentication
+    Basic {
+        /// User name
+        username: String,
+        /// Password
+        password: SecretString,
+    },
+    /// Elasticsearch API key (base64 `id:key` as shown by the cluster)
+    ApiKey(SecretString),
+}
+
+/// What each indexed document represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ElasticMode {
+    /// One document per report
+    #[default]
+    Report,
+    /// One document per system, software, industrial and update entry
+    Items,
+}
+
+/// Sink posting reports to an Elasticsearch or OpenSearch index.
+///
+/// Documents get deterministic `_id`s derived from host, scan time and
+/// position, so re-sending the same scan overwrites rather than duplicates.
+#[derive(Debug, Clone)]
+pub struct ElasticSink {
+    url: String,
+    index: String,
+    auth: ElasticAuth,
+    mode: ElasticMode,
+}
+
+impl ElasticSink {
+    /// Create a sink for a cluster URL (e.g. `https://es.example.com:9200`).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::{ElasticAuth, ElasticMode, ElasticSink}};
+    /// use secrecy::SecretString;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// ElasticSink::new("https://es.example.com:9200")
+    ///     .index("assets-sysaudit")
+    ///     .auth(ElasticAuth::ApiKey(SecretString::from("aWQ6a2V5")))
+    ///     .mode(ElasticMode::Items)
+    ///     .send(&report)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(url: impl Into<String>) -> Self {
+        ElasticSink {
+            url: url.into().trim_end_matches('/').to_string(),
+            index: DEFAULT_INDEX.to_string(),
+            auth: ElasticAuth::default(),
+            mode: ElasticMode::default(),
+        }
+    }
+
+    /// Set the target index.
+    pub fn index(mut self, index: impl Into<String>) -> Self {
+        self.index = index.into();
+        self
+    }
+
+    /// Set the authentication method.
+    pub fn auth(mut self, auth: ElasticAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Set the document mode.
+    pub fn mode(mut self, mode: ElasticMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Index the report through the bulk API.
+    ///
+    /// Returns the number of documents indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Http`] if the request fails, or [`Error::General`]
+    /// if the cluster rejects the request or any document.
+    pub fn send(&self, report: &AuditReport) -> Result<usize, Error> {
+        let documents = self.documents(report)?;
+        let body = bulk_body(&self.index, &documents)?;
+
+        let request = Client::builder()
+            .timeout(TIMEOUT)
+            .build()?
+            .post(format!("{}/_bulk", self.url))
+            .header("Content-Type", "application/x-ndjson")
+            .body(body);
+        let request = match &self.auth {
+            ElasticAuth::None => request,
+            ElasticAuth::Basic { username, password } => {
+                request.basic_auth(username, Some(password.expose_secret()))
+            }
+            ElasticAuth::ApiKey(key) => {
+                request.header("Authorization", format!("ApiKey {}", key.expose_secret()))
+            }
+        };
+
+        let response = request.send()?;
+        let status = response.status();
+        let text = response.text()?;
+        if !status.is_success() {
+            return Err(Error::General(format!(
+                "bulk request to {} failed with {}: {}",
+                self.url, status, text
+            )));
+        }
+        check_bulk_response(&serde_json::from_str(&text)?)?;
+
+        tracing::debug!(index = %self.index, count = documents.len(), "Report indexed");
+        Ok(documents.len())
+    }
+
+    /// Build `(id, document)` pairs for the configured mode.
+    fn documents(&self, report: &AuditReport) -> Result<Vec<(String, Value)>, Error> {
+        let host = report.host_id();
+        let scan_id = format!("{}-{}", host, report.timestamp.timestamp_millis());
+        let timestamp = report.timestamp.to_rfc3339();
+
+        if self.mode == ElasticMode::Report {
+            let mut doc = serde_json::to_value(report)?;
+            doc["@timestamp"] = json!(timestamp);
+            return Ok(vec![(scan_id, doc)]);
+        }
+
+        let mut documents = Vec::new();
+        let mut push = |kind: &str, index: usize, item: Value| {
+            let mut doc = json!({
+                "@timestamp": timestamp,
+                "host": host,
+                "type": kind,
+            });
+            if let (Some(doc), Value::Object(fields)) = (doc.as_object_mut(), item) {
+                doc.extend(fields);
+            }
+            documents.push((format!("{}-{}-{}", scan_id, kind, index), doc));
+        };
+
+        let mut system = serde_json::to_value(&report.system)?;
+        // Keep the NetBIOS name when `host` is the FQDN.
+        if host == report.system.host_name {
+            if let Some(fields) = system.as_object_mut() {
+                fields.remove("host_name");
+            }
+        }
+        push("system", 0, system);
+        for (i, sw) in report.software.iter().enumerate() {
+            push("software", i, serde_json::to_value(sw)?);
+        }
+        for (i, sw) in report.industrial.iter().enumerate() {
+            push("industrial", i, serde_json::to_value(sw)?);
+        }
+        for (i, update) in report.updates.iter().enumerate() {
+            push("update", i, serde_json::to_value(update)?);
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Render an NDJSON bulk body of `index` actions.
+fn bulk_body(index: &str, documents: &[(String, Value)]) -> Result<String, Error> {
+    let mut body = String::new();
+    for (id, doc) in documents {
+        body.push_str(&serde_json::to_string(
+            &json!({ "index": { "_index": index, "_id": id } }),
+        )?);
+        body.push('\n');
+        body.push_str(&serde_json::to_string(doc)?);
+        body.push('\n');
+    }
+    Ok(body)
+}
+
+/// Fail if the bulk response reports item errors.
+fn check_bulk_response(response: &Value) -> Result<(), Error> {
+    if !response["errors"].as_bool().unwrap_or(false) {
+        return Ok(());
+    }
+
+    let failed: Vec<&Value> = response["items"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| item["index"]["error"].is_object())
+                .collect()
+        })
+        .unwrap_or_default();
+    let reason = failed
+        .first()
+        .and_then(|item| item["index"]["error"]["reason"].as_str())
+        .unwrap_or("unknown error");
+
+    Err(Error::General(format!(
+        "{} document(s) rejected by the cluster: {}",
+        failed.len(),
+        reason
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+    use sysaudit_common::SoftwareDto;
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            software: vec![SoftwareDto {
+                name: "TestApp".into(),
+                version: Some("1.0".into()),
+                vendor: None,
+                install_date: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    #[test]
+    fn test_report_mode_single_document() {
+        let docs = ElasticSink::new("http://localhost:9200/")
+            .documents(&sample_report())
+            .unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].0, "TEST-PC-1704067200000");
+        assert_eq!(docs[0].1["system"]["host_name"], "TEST-PC");
+        assert!(docs[0].1["@timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_items_mode_documents() {
+        let docs = ElasticSink::new("http://localhost:9200")
+            .mode(ElasticMode::Items)
+            .documents(&sample_report())
+            .unwrap();
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[1].0, "TEST-PC-1704067200000-software-0");
+        assert_eq!(docs[1].1["type"], "software");
+        assert_eq!(docs[1].1["host"], "TEST-PC");
+        assert_eq!(docs[1].1["name"], "TestApp");
+    }
+
+    #[test]
+    fn test_bulk_body_ndjson() {
+        let body = bulk_body("idx", &[("1".into(), json!({"a": 1}))]).unwrap();
+        assert_eq!(
+            body,
+            "{\"index\":{\"_id\":\"1\",\"_index\":\"idx\"}}\n{\"a\":1}\n"
+        );
+    }
+
+    #[test]
+    fn test_check_bulk_response_errors() {
+        assert!(check_bulk_response(&json!({"errors": false, "items": []})).is_ok());
+
+        let response = json!({
+            "errors": true,
+            "items": [
+                {"index": {"status": 201}},
+                {"index": {"status": 400, "error": {"reason": "mapper_parsing_exception"}}}
+            ]
+        });
+        let err = check_bulk_response(&response).unwrap_err();
+        assert!(err.to_string().contains("1 document(s) rejected"));
+        assert!(err.to_string().contains("mapper_parsing_exception"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Windows Event Log publishing.
+//!
+//! Writes a scan summary and each finding to the `SysAudit` event log so
+//! sites that monitor through Windows Event Forwarding can pick them up.
+//!
+//! This is the only part of the crate that writes to the system: the first
+//! publish registers the `SysAudit` log and event source under
+//! `HKLM\SYSTEM\CurrentControlSet\Services\EventLog`, which requires
+//! administrator rights. Callers must opt in explicitly.
+
+use super::siem::records;
+use crate::{AuditReport, Error, Finding, Severity};
+use windows::Win32::System::EventLog::{
+    DeregisterEventSource, EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE,
+    REPORT_EVENT_TYPE, RegisterEventSourceW, ReportEventW,
+};
+use windows::core::{HSTRING, PCWSTR};
+use windows_registry::LOCAL_MACHINE;
+
+/// Event log and source name.
+pub const EVENT_LOG_NAME: &str = "SysAudit";
+
+/// Event ID for the per-scan summary.
+pub const EVENT_ID_SUMMARY: u32 = 1000;
+
+/// Event ID for findings.
+pub const EVENT_ID_FINDING: u32 = 1001;
+
+/// Registry root for event log registrations.
+const EVENTLOG_KEY: &str = r"SYSTEM\CurrentControlSet\Services\EventLog";
+
+/// Generic message file whose every message is `%1`, shipped with .NET.
+const MESSAGE_FILE: &str =
+    r"%SystemRoot%\Microsoft.NET\Framework64\v4.0.30319\EventLogMessages.dll";
+
+/// Publisher writing findings to the `SysAudit` event log.
+pub struct EventLogPublisher;
+
+impl EventLogPublisher {
+    /// Write a summary event and one event per finding.
+    ///
+    /// Registers the event source on first use. Returns the number of
+    /// events written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{Finding, LocalScanner, Scanner, output::EventLogPublisher};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// EventLogPublisher::publish(&report, &Finding::collect(&report))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Registry`] if the source cannot be registered (usually
+    /// missing administrator rights) or an event cannot be written.
+    pub fn publish(report: &AuditReport, findings: &[Finding]) -> Result<usize, Error> {
+        Self::ensure_source()?;
+
+        // SAFETY: both arguments are valid for the duration of the call; a
+        // null server name means the local machine.
+        let handle =
+            unsafe { RegisterEventSourceW(PCWSTR::null(), &HSTRING::from(EVENT_LOG_NAME)) }?;
+
+        let mut written = 0;
+        let mut result = Ok(());
+        for finding in records(report, findings) {
+            let message = HSTRING::from(event_message(&finding));
+            let strings = [PCWSTR(message.as_ptr())];
+            let event_id = if finding.id == "scan-summary" {
+                EVENT_ID_SUMMARY
+            } else {
+                EVENT_ID_FINDING
+            };
+
+            // SAFETY: `handle` is a live event source and `strings` points at
+            // `message`, which outlives the call.
+            result = unsafe {
+                ReportEventW(
+                    handle,
+                    event_type(finding.severity),
+                    0,
+                    event_id,
+                    None,
+                    0,
+                    Some(&strings),
+                    None,
+                )
+            };
+            if result.is_err() {
+                break;
+            }
+            written += 1;
+        }
+
+        // SAFETY: `handle` came from RegisterEventSourceW above.
+        unsafe { DeregisterEventSource(handle) }?;
+        result?;
+
+        tracing::debug!(count = written, "Findings written to event log");
+        Ok(written)
+    }
+
+    /// Register the `SysAudit` log and source if they do not exist yet.
+    fn ensure_source() -> Result<(), Error> {
+        let source_path = format!(r"{}\{}\{}", EVENTLOG_KEY, EVENT_LOG_NAME, EVENT_LOG_NAME);
+        if LOCAL_MACHINE.open(&source_path).is_ok() {
+            return Ok(());
+        }
+
+        tracing::info!(log = EVENT_LOG_NAME, "Registering event log source");
+        let key = LOCAL_MACHINE.create(&source_path)?;
+        key.set_expand_string("EventMessageFile", MESSAGE_FILE)?;
+        key.set_u32("TypesSupported", 0x7)?;
+        Ok(())
+    }
+}
+
+/// Map a finding severity to an event type.
+fn event_type(severity: Severity) -> REPORT_EVENT_TYPE {
+    match severity {
+        Severity::Critical | Severity::High => EVENTLOG_ERROR_TYPE,
+        Severity::Medium | Severity::Low => EVENTLOG_WARNING_TYPE,
+        Severity::Info => EVENTLOG_INFORMATION_TYPE,
+    }
+}
+
+/// Event text: title, then id/category/severity and attributes on their own lines.
+fn event_message(finding: &Finding) -> String {
+    let mut message = format!(
+        "{}\r\n\r\nId: {}\r\nCategory: {}\r\nSeverity: {}",
+        finding.title, finding.id, finding.category, finding.severity
+    );
+    for (key, value) in &finding.attributes {
+        message.push_str(&format!("\r\n{}: {}", key, value));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_mapping() {
+        assert_eq!(event_type(Severity::High), EVENTLOG_ERROR_TYPE);
+        assert_eq!(event_type(Severity::Medium), EVENTLOG_WARNING_TYPE);
+        assert_eq!(event_type(Severity::Info), EVENTLOG_INFORMATION_TYPE);
+    }
+
+    #[test]
+    fn test_event_message() {
+        let finding = Finding {
+            id: "industrial-detected".into(),
+            category: "industrial".into(),
+            severity: Severity::Medium,
+            title: "Industrial software detected: Siemens TIA Portal".into(),
+            attributes: vec![("version".into(), "V18".into())],
+        };
+
+        let message = event_message(&finding);
+        assert!(message.starts_with("Industrial software detected: Siemens TIA Portal\r\n"));
+        assert!(message.contains("Severity: Medium"));
+        assert!(message.ends_with("version: V18"));
+    }
+}
@@ -0,0 +1,448 @@
+//! GELF 1.1 output for Graylog.
+//!
+//! Every finding, plus a per-scan summary, is sent as one GELF message with
+//! the finding id, category and attributes as additional (`_`-prefixed)
+//! fields. In [`GelfMode::Items`] the system, software, industrial and update
+//! entries follow as one message each. UDP splits messages larger than a
+//! datagram into GELF chunks; TCP and TLS delimit messages with a null byte.
+
+use super::siem::records;
+use super::syslog::syslog_severity;
+use crate::{AuditReport, Error, Finding};
+use serde_json::{Map, Value, json};
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Largest UDP datagram sent, chunk header included.
+const CHUNK_SIZE: usize = 8192;
+
+/// Size of a chunk header: magic bytes, message id, sequence number and count.
+const CHUNK_HEADER: usize = 12;
+
+/// Most chunks a GELF message may be split into.
+const MAX_CHUNKS: usize = 128;
+
+/// Magic bytes starting every chunk.
+const CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+
+/// Connect and write timeout for stream transports.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// GELF transport protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GelfTransport {
+    /// UDP, chunked when a message exceeds one datagram
+    #[default]
+    Udp,
+    /// Plain TCP, null-byte delimited
+    Tcp,
+    /// TLS, null-byte delimited
+    #[cfg(feature = "gelf-tls")]
+    Tls,
+}
+
+impl GelfTransport {
+    /// Parse a transport name (`udp`, `tcp`, `tls`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown or unsupported transport.
+    pub fn parse(name: &str) -> Result<Self, Error> {
+        match name.to_lowercase().as_str() {
+            "udp" => Ok(GelfTransport::Udp),
+            "tcp" => Ok(GelfTransport::Tcp),
+            #[cfg(feature = "gelf-tls")]
+            "tls" => Ok(GelfTransport::Tls),
+            other => Err(Error::General(format!(
+                "unsupported GELF transport '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// What the sink sends besides the findings and the scan summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GelfMode {
+    /// Findings and the scan summary only
+    #[default]
+    Summary,
+    /// Also one message per system, software, industrial and update entry
+    Items,
+}
+
+/// Sink sending findings and inventory to a Graylog GELF input.
+#[derive(Debug, Clone)]
+pub struct GelfSink {
+    address: String,
+    transport: GelfTransport,
+    mode: GelfMode,
+}
+
+impl GelfSink {
+    /// Create a sink for a `host:port` GELF input address using UDP.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{Finding, LocalScanner, Scanner, output::{GelfMode, GelfSink, GelfTransport}};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// GelfSink::new("graylog.plant.local:12201")
+    ///     .transport(GelfTransport::Tcp)
+    ///     .mode(GelfMode::Items)
+    ///     .send(&report, &Finding::collect(&report))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(address: impl Into<String>) -> Self {
+        GelfSink {
+            address: address.into(),
+            transport: GelfTransport::default(),
+            mode: GelfMode::default(),
+        }
+    }
+
+    /// Set the transport protocol.
+    pub fn transport(mut self, transport: GelfTransport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Set what is sent besides the findings.
+    pub fn mode(mut self, mode: GelfMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Send findings, a scan summary and, in [`GelfMode::Items`], the
+    /// inventory entries to the input.
+    ///
+    /// Returns the number of messages sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the address cannot be resolved, a message is too
+    /// large for UDP, or the messages cannot be delivered.
+    pub fn send(&self, report: &AuditReport, findings: &[Finding]) -> Result<usize, Error> {
+        let messages = self
+            .messages(report, findings)?
+            .iter()
+            .map(serde_json::to_vec)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let addr = self.resolve()?;
+        match self.transport {
+            GelfTransport::Udp => {
+                let bind: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+                };
+                let socket = UdpSocket::bind(bind)?;
+                let first_id = message_id_base();
+                for (i, message) in messages.iter().enumerate() {
+                    for datagram in chunks(message, first_id.wrapping_add(i as u64))? {
+                        socket.send_to(&datagram, addr)?;
+                    }
+                }
+            }
+            GelfTransport::Tcp => {
+                let mut stream = self.connect(addr)?;
+                write_delimited(&mut stream, &messages)?;
+            }
+            #[cfg(feature = "gelf-tls")]
+            GelfTransport::Tls => {
+                let domain = self
+                    .address
+                    .rsplit_once(':')
+                    .map_or(self.address.as_str(), |(host, _)| host)
+                    .trim_matches(['[', ']']);
+                let connector = native_tls::TlsConnector::new()?;
+                let mut stream = connector
+                    .connect(domain, self.connect(addr)?)
+                    .map_err(|e| {
+                        Error::General(format!("TLS handshake with {} failed: {}", domain, e))
+                    })?;
+                write_delimited(&mut stream, &messages)?;
+            }
+        }
+
+        tracing::debug!(
+            address = %self.address,
+            count = messages.len(),
+            "Messages sent to GELF input"
+        );
+        Ok(messages.len())
+    }
+
+    /// Build the GELF messages for the configured mode.
+    fn messages(&self, report: &AuditReport, findings: &[Finding]) -> Result<Vec<Value>, Error> {
+        let host = report.host_id();
+        let timestamp = report.timestamp.timestamp_millis() as f64 / 1000.0;
+        let message = |short: &str, level: u8, kind: &str| {
+            json!({
+                "version": "1.1",
+                "host": host,
+                "short_message": short,
+                "timestamp": timestamp,
+                "level": level,
+                "_app": "sysaudit",
+                "_type": kind,
+            })
+        };
+
+        let mut messages: Vec<Value> = records(report, findings)
+            .map(|finding| {
+                let mut msg = message(&finding.title, syslog_severity(finding.severity), "finding");
+                for (key, value) in &finding.attributes {
+                    msg[field_name(key)] = json!(value);
+                }
+                msg["_finding_id"] = json!(finding.id);
+                msg["_category"] = json!(finding.category);
+                msg["_severity"] = json!(finding.severity.to_string());
+                msg
+            })
+            .collect();
+
+        if self.mode == GelfMode::Summary {
+            return Ok(messages);
+        }
+
+        // Inventory entries are informational (level 6)
+        let item = |short: String, kind: &str, entry: Value| {
+            let mut msg = message(&short, 6, kind);
+            if let (Value::Object(fields), Value::Object(entry)) = (&mut msg, entry) {
+                add_fields(fields, entry);
+            }
+            msg
+        };
+        let system = &report.system;
+        messages.push(item(
+            format!("{} {}", system.os_name, system.os_version),
+            "system",
+            serde_json::to_value(system)?,
+        ));
+        for sw in &report.software {
+            let short = match &sw.version {
+                Some(version) => format!("{} {}", sw.name, version),
+                None => sw.name.clone(),
+            };
+            messages.push(item(short, "software", serde_json::to_value(sw)?));
+        }
+        for sw in &report.industrial {
+            let short = format!("{} {}", sw.vendor, sw.product);
+            messages.push(item(short, "industrial", serde_json::to_value(sw)?));
+        }
+        for update in &report.updates {
+            let short = update.hotfix_id.clone();
+            messages.push(item(short, "update", serde_json::to_value(update)?));
+        }
+        Ok(messages)
+    }
+
+    fn resolve(&self) -> Result<SocketAddr, Error> {
+        self.address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| Error::General(format!("cannot resolve GELF address {}", self.address)))
+    }
+
+    fn connect(&self, addr: SocketAddr) -> Result<TcpStream, Error> {
+        let stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Ok(stream)
+    }
+}
+
+/// Add an entry's fields as additional fields. GELF values are strings or
+/// numbers: booleans become strings, lists and objects compact JSON, and
+/// missing values are left out.
+fn add_fields(message: &mut Map<String, Value>, entry: Map<String, Value>) {
+    for (key, value) in entry {
+        let value = match value {
+            Value::Null => continue,
+            Value::String(_) | Value::Number(_) => value,
+            Value::Bool(b) => Value::String(b.to_string()),
+            other => Value::String(other.to_string()),
+        };
+        message.insert(field_name(&key), value);
+    }
+}
+
+/// Additional field name: `_` followed by word characters, dots and dashes.
+/// `_id` is reserved by Graylog.
+fn field_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name == "id" {
+        "_id_".to_string()
+    } else {
+        format!("_{}", name)
+    }
+}
+
+/// Split a message into datagrams: the message itself when it fits, GELF
+/// chunks otherwise.
+fn chunks(message: &[u8], id: u64) -> Result<Vec<Vec<u8>>, Error> {
+    if message.len() <= CHUNK_SIZE {
+        return Ok(vec![message.to_vec()]);
+    }
+    let parts: Vec<&[u8]> = message.chunks(CHUNK_SIZE - CHUNK_HEADER).collect();
+    if parts.len() > MAX_CHUNKS {
+        return Err(Error::General(format!(
+            "GELF message of {} bytes exceeds {} UDP chunks; use TCP",
+            message.len(),
+            MAX_CHUNKS
+        )));
+    }
+    let count = parts.len() as u8;
+    Ok(parts
+        .into_iter()
+        .enumerate()
+        .map(|(seq, part)| {
+            let mut datagram = Vec::with_capacity(CHUNK_HEADER + part.len());
+            datagram.extend_from_slice(&CHUNK_MAGIC);
+            datagram.extend_from_slice(&id.to_be_bytes());
+            datagram.push(seq as u8);
+            datagram.push(count);
+            datagram.extend_from_slice(part);
+            datagram
+        })
+        .collect())
+}
+
+/// Chunk message id of the first message of a send; the following
+/// messages count up from it so that concurrent senders do not collide.
+fn message_id_base() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    nanos ^ (u64::from(std::process::id()) << 32)
+}
+
+/// Write messages, each followed by a null byte.
+fn write_delimited<W: Write>(out: &mut W, messages: &[Vec<u8>]) -> Result<(), Error> {
+    for message in messages {
+        out.write_all(message)?;
+        out.write_all(&[0])?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Severity;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+    use sysaudit_common::IndustrialSoftwareDto;
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "ABB".into(),
+                product: "Automation Builder".into(),
+                version: None,
+                install_path: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    #[test]
+    fn test_finding_message() {
+        let finding = Finding {
+            id: "patches-none".into(),
+            category: "patching".into(),
+            severity: Severity::High,
+            title: "No installed Windows updates recorded".into(),
+            attributes: vec![("id".into(), "x".into()), ("last kb".into(), "y".into())],
+        };
+
+        let messages = GelfSink::new("localhost:12201")
+            .messages(&sample_report(), &[finding])
+            .unwrap();
+        assert_eq!(messages.len(), 2);
+        let msg = &messages[0];
+        assert_eq!(msg["version"], "1.1");
+        assert_eq!(msg["host"], "TEST-PC");
+        assert_eq!(msg["timestamp"], 1704067200.0);
+        assert_eq!(msg["level"], 3);
+        assert_eq!(msg["_finding_id"], "patches-none");
+        assert_eq!(msg["_id_"], "x");
+        assert_eq!(msg["_last_kb"], "y");
+        assert_eq!(messages[1]["_finding_id"], "scan-summary");
+    }
+
+    #[test]
+    fn test_item_messages() {
+        let messages = GelfSink::new("localhost:12201")
+            .mode(GelfMode::Items)
+            .messages(&sample_report(), &[])
+            .unwrap();
+        let types: Vec<&str> = messages
+            .iter()
+            .map(|m| m["_type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["finding", "system", "industrial"]);
+        assert_eq!(messages[1]["_cpu_physical_cores"], 8);
+        assert_eq!(messages[1]["_network_interfaces"], "[]");
+        assert!(messages[1].get("_manufacturer").is_none());
+        assert_eq!(messages[2]["short_message"], "ABB Automation Builder");
+    }
+
+    #[test]
+    fn test_chunks() {
+        assert_eq!(chunks(b"{}", 1).unwrap(), [b"{}".to_vec()]);
+
+        let message = vec![b'x'; CHUNK_SIZE * 2];
+        let datagrams = chunks(&message, 0x0102).unwrap();
+        assert_eq!(datagrams.len(), 3);
+        assert_eq!(
+            &datagrams[1][..12],
+            &[0x1e, 0x0f, 0, 0, 0, 0, 0, 0, 1, 2, 1, 3]
+        );
+        assert!(datagrams.iter().all(|d| d.len() <= CHUNK_SIZE));
+        let payload: usize = datagrams.iter().map(|d| d.len() - CHUNK_HEADER).sum();
+        assert_eq!(payload, message.len());
+
+        assert!(chunks(&vec![b'x'; CHUNK_SIZE * MAX_CHUNKS], 0).is_err());
+    }
+
+    #[test]
+    fn test_null_delimited() {
+        let mut out = Vec::new();
+        write_delimited(&mut out, &[b"{}".to_vec(), b"{}".to_vec()]).unwrap();
+        assert_eq!(out, b"{}\0{}\0");
+    }
+
+    #[test]
+    fn test_send_udp() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let addr = server.local_addr().unwrap().to_string();
+
+        let sent = GelfSink::new(addr).send(&sample_report(), &[]).unwrap();
+        assert_eq!(sent, 1);
+
+        let mut buf = [0u8; 2048];
+        let len = server.recv(&mut buf).unwrap();
+        let message: Value = serde_json::from_slice(&buf[..len]).unwrap();
+        assert_eq!(message["_finding_id"], "scan-summary");
+    }
+}
@@ -0,0 +1,288 @@
+//! Standalone HTML output.
+//!
+//! Produces a single self-contained page (inline CSS, no scripts) that can
+//! be mailed or attached to a change ticket.
+
+use super::xml::escape;
+use crate::metadata::ScanMetadata;
+use crate::report::{ChangeKind, ItemChange, ReportDiff};
+use crate::score::AuditScore;
+use crate::{AuditReport, Finding, Severity};
+use std::cmp::Reverse;
+
+/// Inline stylesheet shared by every page.
+const STYLE: &str = "body{font-family:Segoe UI,sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{border:1px solid #ccc;padding:4px 10px;text-align:left}\
+th{background:#f0f0f0}\
+.added{background:#e6f4ea}.removed{background:#fce8e6}.changed{background:#fef7e0}\
+.score{font-size:3em;font-weight:bold;margin:0.2em 0}\
+.good{color:#1e8e3e}.fair{color:#e37400}.poor{color:#d93025}";
+
+/// HTML formatter producing standalone pages.
+pub struct HtmlFormatter;
+
+impl HtmlFormatter {
+    /// Format the changes between two reports as an HTML page.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::output::HtmlFormatter;
+    /// use sysaudit::report::diff;
+    ///
+    /// # fn example(old: &sysaudit::AuditReport, new: &sysaudit::AuditReport) {
+    /// std::fs::write("changes.html", HtmlFormatter::format_diff(&diff(old, new))).unwrap();
+    /// # }
+    /// ```
+    pub fn format_diff(diff: &ReportDiff) -> String {
+        let title = format!("Changes on {}", escape(&diff.host));
+        let mut body = format!(
+            "<h1>{}</h1>\n<p>{} &rarr; {}</p>\n",
+            title,
+            diff.old_timestamp.format("%Y-%m-%d %H:%M UTC"),
+            diff.new_timestamp.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        if diff.is_empty() {
+            body.push_str("<p>No changes.</p>\n");
+        }
+
+        if !diff.system.is_empty() {
+            body.push_str(
+                "<h2>System</h2>\n<table>\n<tr><th>Field</th><th>Old</th><th>New</th></tr>\n",
+            );
+            for change in &diff.system {
+                body.push_str(&format!(
+                    "<tr class=\"changed\"><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape(&change.field),
+                    optional(change.old.as_deref()),
+                    optional(change.new.as_deref())
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        for (title, changes) in [
+            ("Software", &diff.software),
+            ("Industrial Software", &diff.industrial),
+            ("Updates", &diff.updates),
+        ] {
+            if !changes.is_empty() {
+                body.push_str(&change_table(title, changes));
+            }
+        }
+
+        page(&title, &body)
+    }
+
+    /// Format an executive summary of a report as an HTML page: the audit
+    /// score with its grade and trend, the per-domain scores and the most
+    /// severe findings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::HtmlFormatter};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// std::fs::write("summary.html", HtmlFormatter::format_summary(&report))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_summary(report: &AuditReport) -> String {
+        let score = report
+            .score
+            .clone()
+            .unwrap_or_else(|| AuditScore::compute(report));
+        let title = format!("Audit summary: {}", escape(&report.system.host_name));
+        let mut body = format!(
+            "<h1>{}</h1>\n<p>{} {} &middot; scanned {}</p>\n",
+            title,
+            escape(&report.system.os_name),
+            escape(&report.system.os_version),
+            report.timestamp.format("%Y-%m-%d %H:%M UTC")
+        );
+        if let Some(metadata) = &report.metadata {
+            body.push_str(&metadata_table(metadata));
+        }
+
+        match (score.overall, score.grade()) {
+            (Some(overall), Some(grade)) => {
+                body.push_str(&format!(
+                    "<p class=\"score {}\">{}/100 ({})</p>\n",
+                    score_class(overall),
+                    overall,
+                    grade
+                ));
+                if let Some(trend) = score.trend {
+                    body.push_str(&format!("<p>{:+} since the last scan</p>\n", trend));
+                }
+            }
+            _ => body.push_str("<p class=\"score\">n/a</p>\n"),
+        }
+
+        body.push_str(
+            "<table>\n<tr><th>Domain</th><th>Score</th><th>Weight</th><th>Reasons</th></tr>\n",
+        );
+        for domain in &score.domains {
+            let value = match domain.score {
+                Some(value) => format!("<td class=\"{}\">{}</td>", score_class(value), value),
+                None => "<td>n/a</td>".to_string(),
+            };
+            let reasons: Vec<String> = domain.reasons.iter().map(|r| escape(r)).collect();
+            body.push_str(&format!(
+                "<tr><td>{}</td>{}<td>{}</td><td>{}</td></tr>\n",
+                domain.domain,
+                value,
+                domain.weight,
+                reasons.join("<br>")
+            ));
+        }
+        body.push_str("</table>\n");
+
+        let mut findings = Finding::collect(report);
+        findings.retain(|f| f.severity >= Severity::Medium);
+        findings.sort_by_key(|finding| Reverse(finding.severity));
+        if !findings.is_empty() {
+            body.push_str(&format!(
+                "<h2>Findings ({})</h2>\n<table>\n<tr><th>Severity</th><th>Finding</th></tr>\n",
+                findings.len()
+            ));
+            for finding in &findings {
+                body.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td></tr>\n",
+                    finding.severity,
+                    escape(&finding.title)
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        page(&title, &body)
+    }
+}
+
+/// Table of who ran a scan, how and for how long.
+fn metadata_table(metadata: &ScanMetadata) -> String {
+    let mut table = "<table>\n".to_string();
+    for (field, value) in metadata.fields() {
+        table.push_str(&format!(
+            "<tr><th>{}</th><td>{}</td></tr>\n",
+            field,
+            escape(&value)
+        ));
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+/// CSS class for a score: good (80+), fair (60+) or poor.
+fn score_class(score: u8) -> &'static str {
+    match score {
+        80.. => "good",
+        60..=79 => "fair",
+        _ => "poor",
+    }
+}
+
+/// Section heading and table of added, removed and changed items.
+fn change_table(title: &str, changes: &[ItemChange]) -> String {
+    let mut table = format!(
+        "<h2>{} ({})</h2>\n<table>\n<tr><th>Change</th><th>Name</th><th>Old Version</th><th>New Version</th></tr>\n",
+        title,
+        changes.len()
+    );
+    for change in changes {
+        let class = match change.kind {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+        };
+        table.push_str(&format!(
+            "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            class,
+            change.kind,
+            escape(&change.name),
+            optional(change.old_version.as_deref()),
+            optional(change.new_version.as_deref())
+        ));
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+/// Escaped value, `-` when absent.
+fn optional(value: Option<&str>) -> String {
+    value.map(escape).unwrap_or_else(|| "-".to_string())
+}
+
+/// Wrap `body` in a complete document.
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        title, STYLE, body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::Utc;
+
+    #[test]
+    fn test_format_diff_escapes() {
+        let diff = ReportDiff {
+            host: "HMI01".into(),
+            old_timestamp: Utc::now(),
+            new_timestamp: Utc::now(),
+            system: vec![],
+            software: vec![ItemChange {
+                kind: ChangeKind::Added,
+                name: "Tools <x64> & more".into(),
+                old_version: None,
+                new_version: Some("1.0".into()),
+            }],
+            industrial: vec![],
+            updates: vec![],
+        };
+
+        let html = HtmlFormatter::format_diff(&diff);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2>Software (1)</h2>"));
+        assert!(html.contains("<tr class=\"added\"><td>added</td><td>Tools &lt;x64&gt; &amp; more</td><td>-</td><td>1.0</td></tr>"));
+        assert!(!html.contains("<h2>Updates"));
+    }
+
+    #[test]
+    fn test_format_summary_score() {
+        use crate::score::{DomainScore, ScoreDomain};
+        use sysaudit_common::SystemInfoDto;
+
+        let report = AuditReport {
+            score: Some(AuditScore {
+                overall: Some(84),
+                domains: vec![DomainScore {
+                    domain: ScoreDomain::Patching,
+                    score: Some(59),
+                    weight: 30,
+                    reasons: vec!["latest update installed 91 days ago".into()],
+                }],
+                trend: Some(6),
+            }),
+            ..AuditReport::empty(SystemInfoDto {
+                os_version: "24H2".into(),
+                host_name: "ENG<01>".into(),
+                ..test_system()
+            })
+        };
+
+        let html = HtmlFormatter::format_summary(&report);
+        assert!(html.contains("<h1>Audit summary: ENG&lt;01&gt;</h1>"));
+        assert!(html.contains("<p class=\"score good\">84/100 (B)</p>"));
+        assert!(html.contains("+6 since the last scan"));
+        assert!(html.contains("<td class=\"poor\">59</td>"));
+    }
+}
@@ -0,0 +1,253 @@
+//! Markdown output formatting.
+
+use crate::metadata::ScanMetadata;
+use crate::{IndustrialSoftware, Software, SystemInfo, WindowsUpdate};
+
+/// Markdown formatter producing GitHub-flavored tables.
+pub struct MarkdownFormatter;
+
+impl MarkdownFormatter {
+    /// Format system info as Markdown sections.
+    pub fn format_system_info(info: &SystemInfo) -> String {
+        let mut rows = vec![vec![
+            "Computer Name".to_string(),
+            info.computer_name.clone(),
+        ]];
+        if let Some(domain) = &info.domain {
+            rows.push(vec!["Domain".to_string(), domain.clone()]);
+        }
+        if let Some(fqdn) = &info.identity.fqdn {
+            rows.push(vec!["FQDN".to_string(), fqdn.clone()]);
+        }
+        if let Some(man) = &info.manufacturer {
+            rows.push(vec!["Manufacturer".to_string(), man.clone()]);
+        }
+        if let Some(model) = &info.model {
+            rows.push(vec!["Model".to_string(), model.clone()]);
+        }
+        rows.push(vec![
+            "OS".to_string(),
+            format!("{} {}", info.os_name, info.os_version),
+        ]);
+        rows.push(vec!["Build".to_string(), info.build_number.clone()]);
+        rows.push(vec!["CPU".to_string(), info.cpu_info.clone()]);
+        if let (Some(phys), Some(log)) = (info.cpu_cores_physical, info.cpu_cores_logical) {
+            rows.push(vec![
+                "CPU Cores".to_string(),
+                format!("{} (Phys) / {} (Log)", phys, log),
+            ]);
+        }
+        rows.push(vec![
+            "CPU Freq".to_string(),
+            format!("{} MHz", info.cpu_frequency_mhz),
+        ]);
+        rows.push(vec![
+            "Memory".to_string(),
+            format!(
+                "{:.2} GB / {:.2} GB",
+                info.memory_used as f64 / 1_073_741_824.0,
+                info.memory_total as f64 / 1_073_741_824.0
+            ),
+        ]);
+
+        let mut output = String::from("## System Information\n\n");
+        output.push_str(&table(&["Field", "Value"], &rows));
+
+        if !info.network_interfaces.is_empty() {
+            let net_rows: Vec<Vec<String>> = info
+                .network_interfaces
+                .iter()
+                .map(|iface| {
+                    vec![
+                        iface.name.clone(),
+                        iface
+                            .ip_addresses()
+                            .map(|ip| ip.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        std::iter::once(iface.prefix())
+                            .chain(iface.additional_addresses.iter().map(|a| Some(a.prefix)))
+                            .map(|prefix| prefix.map_or_else(|| "-".into(), |p| format!("/{}", p)))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        iface.mac_address.clone().unwrap_or_else(|| "-".into()),
+                    ]
+                })
+                .collect();
+
+            output.push_str("\n### Network Interfaces\n\n");
+            output.push_str(&table(
+                &["Interface", "IP Address", "Prefix", "MAC"],
+                &net_rows,
+            ));
+        }
+
+        output
+    }
+
+    /// Format software list as a Markdown table.
+    pub fn format_software(software: &[Software]) -> String {
+        let rows: Vec<Vec<String>> = software
+            .iter()
+            .map(|sw| {
+                vec![
+                    sw.name.clone(),
+                    sw.version.clone().unwrap_or_else(|| "-".into()),
+                    sw.publisher.clone().unwrap_or_else(|| "-".into()),
+                    sw.install_date
+                        .map(|d| d.to_string())
+                        .unwrap_or_else(|| "-".into()),
+                    sw.source.to_string(),
+                ]
+            })
+            .collect();
+
+        format!(
+            "## Installed Software\n\n{}\nFound: {} items\n",
+            table(
+                &["Name", "Version", "Publisher", "Install Date", "Source"],
+                &rows
+            ),
+            software.len()
+        )
+    }
+
+    /// Format industrial software as a Markdown table.
+    pub fn format_industrial(software: &[IndustrialSoftware]) -> String {
+        let rows: Vec<Vec<String>> = software
+            .iter()
+            .map(|sw| {
+                vec![
+                    sw.vendor.to_string(),
+                    sw.product.clone(),
+                    sw.version.clone().unwrap_or_else(|| "-".into()),
+                    sw.install_path
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|| "-".into()),
+                ]
+            })
+            .collect();
+
+        format!(
+            "## Industrial Software\n\n{}\nFound: {} industrial applications\n",
+            table(&["Vendor", "Product", "Version", "Install Path"], &rows),
+            software.len()
+        )
+    }
+
+    /// Format Windows updates as a Markdown table.
+    pub fn format_updates(updates: &[WindowsUpdate]) -> String {
+        let rows: Vec<Vec<String>> = updates
+            .iter()
+            .map(|update| {
+                vec![
+                    update.hotfix_id.clone(),
+                    update.description.clone().unwrap_or_else(|| "-".into()),
+                    update
+                        .installed_on
+                        .map(|d| d.to_string())
+                        .unwrap_or_else(|| "-".into()),
+                    update.installed_by.clone().unwrap_or_else(|| "-".into()),
+                ]
+            })
+            .collect();
+
+        format!(
+            "## Windows Updates\n\n{}\nFound: {} updates\n",
+            table(
+                &["HotFix ID", "Description", "Installed On", "Installed By"],
+                &rows
+            ),
+            updates.len()
+        )
+    }
+
+    /// Format a report's scan metadata as a Markdown table.
+    pub fn format_metadata(metadata: &ScanMetadata) -> String {
+        let rows: Vec<Vec<String>> = metadata
+            .fields()
+            .into_iter()
+            .map(|(field, value)| vec![field.to_string(), value])
+            .collect();
+        format!("## Scan\n\n{}", table(&["Field", "Value"], &rows))
+    }
+}
+
+/// Render a GitHub-flavored Markdown table.
+fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut output = format!("| {} |\n", headers.join(" | "));
+    output.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|c| escape_cell(c)).collect();
+        output.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+
+    output
+}
+
+/// Escape characters that would break a Markdown table cell.
+fn escape_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RegistrySource, Vendor};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_escape_cell() {
+        assert_eq!(escape_cell("a|b"), "a\\|b");
+        assert_eq!(escape_cell("line1\nline2"), "line1 line2");
+        assert_eq!(escape_cell(r"C:\App"), r"C:\\App");
+    }
+
+    #[test]
+    fn test_format_software_markdown() {
+        let sw = Software {
+            name: "Test App".to_string(),
+            version: Some("1.0.0".to_string()),
+            publisher: Some("Test Corp".to_string()),
+            install_date: NaiveDate::from_ymd_opt(2024, 1, 1),
+            install_date_source: crate::InstallDateSource::Exact,
+            install_location: None,
+            source: RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
+        };
+
+        let output = MarkdownFormatter::format_software(&[sw]);
+        assert!(output.starts_with("## Installed Software"));
+        assert!(output.contains("| Name | Version | Publisher | Install Date | Source |"));
+        assert!(output.contains("|---|---|---|---|---|"));
+        assert!(output.contains("| Test App | 1.0.0 | Test Corp | 2024-01-01 |"));
+        assert!(output.contains("Found: 1 items"));
+    }
+
+    #[test]
+    fn test_format_industrial_markdown() {
+        let sw = IndustrialSoftware {
+            vendor: Vendor::Rockwell,
+            product: "Studio 5000".into(),
+            version: None,
+            install_path: None,
+            runtime_state: None,
+        };
+
+        let output = MarkdownFormatter::format_industrial(&[sw]);
+        assert!(output.contains("| Rockwell | Studio 5000 | - | - |"));
+    }
+
+    #[test]
+    fn test_format_updates_empty_markdown() {
+        let output = MarkdownFormatter::format_updates(&[]);
+        assert!(output.contains("| HotFix ID |"));
+        assert!(output.contains("Found: 0 updates"));
+    }
+}
@@ -1,7 +1,78 @@
 //! Output formatting module.
 
+mod bundle;
+mod columns;
 mod console;
 mod csv_output;
+#[cfg(feature = "elastic")]
+mod elastic;
+#[cfg(all(feature = "eventlog", windows))]
+mod eventlog;
+mod gelf;
+mod html;
+mod markdown;
+mod schema;
+mod servicenow;
+mod siem;
+#[cfg(feature = "signing")]
+mod signing;
+#[cfg(feature = "splunk")]
+mod splunk;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "stix")]
+mod stix;
+mod syslog;
+#[cfg(feature = "template")]
+mod template;
+#[cfg(feature = "webhook")]
+mod webhook;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+mod xml;
+#[cfg(feature = "yaml")]
+mod yaml;
 
-pub use console::ConsoleFormatter;
-pub use csv_output::CsvExporter;
+pub use bundle::ReportBundle;
+pub(crate) use bundle::{file_safe, report_name};
+pub use columns::{ColumnSpec, Tabular};
+pub use console::{ColorChoice, ConsoleFormatter};
+pub use csv_output::{CsvDialect, CsvExporter};
+#[cfg(feature = "elastic")]
+pub use elastic::{ElasticAuth, ElasticMode, ElasticSink};
+#[cfg(all(feature = "eventlog", windows))]
+pub use eventlog::{EVENT_ID_FINDING, EVENT_ID_SUMMARY, EVENT_LOG_NAME, EventLogPublisher};
+pub use gelf::{GelfMode, GelfSink, GelfTransport};
+pub use html::HtmlFormatter;
+pub use markdown::MarkdownFormatter;
+#[cfg(feature = "schema")]
+pub use schema::validate_report;
+pub use schema::{REPORT_SCHEMA, report_schema};
+pub use servicenow::{
+    COMPUTER_SOURCES, ImportSet, SOFTWARE_SOURCES, ServiceNowExporter, ServiceNowMapping,
+};
+#[cfg(feature = "servicenow")]
+pub use servicenow::{ServiceNowAuth, ServiceNowSink};
+pub use siem::SiemFormatter;
+#[cfg(feature = "signing")]
+pub use signing::{
+    canonical_json, load_signing_key, load_verifying_key, sign_report, verify_report,
+};
+#[cfg(feature = "splunk")]
+pub use splunk::{SplunkMode, SplunkSink};
+#[cfg(feature = "sqlite")]
+pub(crate) use sqlite::SCAN_TABLES;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteExporter;
+#[cfg(feature = "stix")]
+pub use stix::StixFormatter;
+pub use syslog::{SyslogSink, SyslogTransport};
+#[cfg(feature = "template")]
+pub use template::TemplateRenderer;
+#[cfg(feature = "webhook")]
+pub use webhook::WebhookSink;
+#[cfg(feature = "xlsx")]
+pub use xlsx::XlsxExporter;
+pub use xml::{XML_SCHEMA_VERSION, XmlFormatter};
+#[cfg(feature = "yaml")]
+pub use yaml::YamlFormatter;
@@ -0,0 +1,302 @@
+//! JSON Schema for stored reports.
+//!
+//! The schema describes the JSON written for [`AuditReport`](crate::AuditReport) (a superset of
+//! the `sysaudit-common` DTO) and is the contract for downstream consumers.
+//! The DTOs live outside this crate, so the schema is maintained by hand in
+//! `report.schema.json`; the tests below check that freshly serialized
+//! reports still validate against it.
+
+use crate::Error;
+
+/// The report JSON Schema (draft 2020-12) as text.
+pub const REPORT_SCHEMA: &str = include_str!("report.schema.json");
+
+/// The report JSON Schema as a JSON value.
+///
+/// # Errors
+///
+/// Returns [`Error::Json`] if the bundled schema is not valid JSON.
+pub fn report_schema() -> Result<serde_json::Value, Error> {
+    Ok(serde_json::from_str(REPORT_SCHEMA)?)
+}
+
+/// Validate a stored report against [`REPORT_SCHEMA`].
+///
+/// Returns one `"<path>: <message>"` entry per violation; an empty list
+/// means the report is valid.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::output::validate_report;
+///
+/// let json: serde_json::Value =
+///     serde_json::from_str(&std::fs::read_to_string("report.json").unwrap()).unwrap();
+/// for violation in validate_report(&json).unwrap() {
+///     eprintln!("{}", violation);
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`Error`] if the bundled schema cannot be parsed or compiled.
+#[cfg(feature = "schema")]
+pub fn validate_report(report: &serde_json::Value) -> Result<Vec<String>, Error> {
+    let validator = jsonschema::options()
+        .should_validate_formats(true)
+        .build(&report_schema()?)
+        .map_err(|e| Error::General(format!("invalid report schema: {}", e)))?;
+
+    Ok(validator
+        .iter_errors(report)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            let path = if path.is_empty() { "/" } else { &path };
+            format!("{}: {}", path, e)
+        })
+        .collect())
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use crate::{AuditReport, AuditScore, Collector, ScanWarning, WindowsUpdate};
+    use chrono::{NaiveDate, Utc};
+    use sysaudit_common::{
+        IndustrialSoftwareDto, IpVersion, NetworkInterfaceDto, SoftwareDto, SystemInfoDto,
+    };
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            software: vec![SoftwareDto {
+                name: "TestApp".into(),
+                version: Some("1.0".into()),
+                vendor: None,
+                install_date: Some(Utc::now()),
+            }],
+            updates: vec![WindowsUpdate {
+                hotfix_id: "KB5034441".into(),
+                description: None,
+                installed_on: NaiveDate::from_ymd_opt(2024, 1, 15),
+                installed_by: None,
+            }],
+            ..AuditReport::empty(SystemInfoDto {
+                network_interfaces: vec![NetworkInterfaceDto {
+                    name: "Ethernet".into(),
+                    ip_address: "10.0.0.5".into(),
+                    ip_version: IpVersion::IPv4,
+                    mac_address: None,
+                }],
+                ..test_system()
+            })
+        }
+    }
+
+    #[test]
+    fn test_serialized_report_matches_schema() {
+        let json = serde_json::to_value(sample_report()).unwrap();
+        assert_eq!(validate_report(&json).unwrap(), Vec::<String>::new());
+    }
+
+    /// `sample_report` with every optional section and field set.
+    fn full_report() -> AuditReport {
+        let service = |name: &str| crate::WindowsService {
+            name: name.into(),
+            display_name: Some(name.to_uppercase()),
+            state: Some("Running".into()),
+            start_mode: Some("Auto".into()),
+            account: Some("LocalSystem".into()),
+            path: Some(format!("C:\\Windows\\{}.exe", name)),
+        };
+        let mut report = AuditReport {
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "AVEVA".into(),
+                product: "Citect SCADA".into(),
+                version: Some("8.0".into()),
+                install_path: Some("C:\\Program Files\\Citect".into()),
+            }],
+            updates: vec![WindowsUpdate {
+                hotfix_id: "KB5034441".into(),
+                description: Some("Security Update".into()),
+                installed_on: NaiveDate::from_ymd_opt(2024, 1, 15),
+                installed_by: Some("NT AUTHORITY\\SYSTEM".into()),
+            }],
+            services: vec![service("wuauserv")],
+            security: Some(crate::SecurityPosture {
+                system_drive: Some("C:".into()),
+                bitlocker: vec![crate::BitLockerVolume {
+                    drive: "C:".into(),
+                    protected: Some(true),
+                }],
+                uac_enabled: Some(true),
+                uac_admin_prompt: Some(5),
+                smb1_server: Some(false),
+                smb1_client: Some(false),
+                firewall: vec![crate::FirewallProfile {
+                    name: "Domain".into(),
+                    enabled: Some(true),
+                }],
+                password_policy: Some(crate::PasswordPolicy {
+                    min_length: 14,
+                    max_age_days: Some(60),
+                    min_age_days: 1,
+                    history: 24,
+                    lockout_threshold: 5,
+                }),
+                rdp_enabled: Some(true),
+                rdp_nla: Some(true),
+                defender_exclusions: Some(crate::exclusions::AvExclusions {
+                    paths: vec!["C:\\Citect".into()],
+                    extensions: vec![".ctz".into()],
+                    processes: vec!["citect.exe".into()],
+                }),
+                antivirus: vec!["Windows Defender".into()],
+            }),
+            update_health: Some(crate::UpdateHealth {
+                windows_update: Some(service("wuauserv")),
+                bits: Some(service("BITS")),
+                wsus_server: Some("http://wsus.plant.local:8530".into()),
+                auto_update_disabled: Some(false),
+                sccm: Some(crate::SccmClient {
+                    version: Some("5.00.9106.1000".into()),
+                    site_code: Some("PLT".into()),
+                    management_point: Some("sccm.plant.local".into()),
+                    last_policy_evaluation: Some(Utc::now()),
+                }),
+            }),
+            licensing: Some(crate::Licensing {
+                edition: Some("Professional".into()),
+                licenses: vec![crate::License {
+                    name: "Windows(R), Professional edition".into(),
+                    description: Some("RETAIL channel".into()),
+                    application_id: Some("55c92734-d682-4d71-983e-d6ec3f16059f".into()),
+                    channel: Some(crate::LicenseChannel::Retail),
+                    key_channel: Some("Retail".into()),
+                    status: crate::LicenseStatus::Licensed,
+                    partial_product_key: Some("3V66T".into()),
+                }],
+            }),
+            event_logs: Some(crate::EventLogs {
+                logs: vec![crate::EventLog {
+                    name: "Security".into(),
+                    max_size_kb: Some(196_608),
+                    retention: Some(crate::LogRetention::Overwrite),
+                    policy: true,
+                }],
+                subscription_managers: vec!["Server=http://wec.plant.local:5985".into()],
+            }),
+            autopilot: Some(crate::Autopilot {
+                serial_number: Some("ABC123".into()),
+                manufacturer: Some("Dell Inc.".into()),
+                model: Some("OptiPlex 7090".into()),
+                hardware_hash: Some("T0Fp".into()),
+            }),
+            containers: Some(crate::Containers {
+                wsl: Some(crate::Wsl {
+                    inbox: false,
+                    package_version: Some("2.0.14.0".into()),
+                    distributions: vec![crate::WslDistribution {
+                        name: "Ubuntu".into(),
+                        wsl_version: Some(2),
+                        default: true,
+                        base_path: Some("C:\\Users\\eng\\Ubuntu".into()),
+                        user_sid: Some("S-1-5-21-1-2-3-1001".into()),
+                    }],
+                }),
+                runtimes: vec![crate::ContainerRuntime {
+                    kind: crate::ContainerRuntimeKind::DockerDesktop,
+                    version: Some("4.26.1".into()),
+                    path: Some("C:\\Program Files\\Docker".into()),
+                }],
+            }),
+            redistributables: vec![crate::Redistributable {
+                kind: crate::RedistributableKind::VcRedist,
+                name: "Microsoft Visual C++ 2015-2022 Redistributable (x64)".into(),
+                version: Some("14.38.33130".into()),
+                year: Some("2015-2022".into()),
+                architecture: Some("x64".into()),
+            }],
+            warnings: vec![ScanWarning::new(Collector::Services, "access denied")],
+            metrics: Some(crate::ScanMetrics {
+                duration_ms: 1200,
+                collectors: vec![crate::CollectorMetrics {
+                    collector: Collector::Software,
+                    duration_ms: 800,
+                    items: 1,
+                    warnings: 0,
+                }],
+                transfer: Some(crate::TransferMetrics {
+                    bytes_sent: 4096,
+                    bytes_received: 65_536,
+                    requests: 6,
+                    payload_bytes: 20_000,
+                    report_bytes: 60_000,
+                }),
+            }),
+            elevated: Some(true),
+            metadata: Some(
+                crate::ScanMetadata::new(
+                    crate::ScannerKind::Local,
+                    std::time::Instant::now(),
+                    &crate::ScanOptions::new(),
+                )
+                .user(Some("PLANT\\audit".into()))
+                .elevated(Some(true)),
+            ),
+            identity: Some(crate::HostIdentity {
+                fqdn: Some("test-pc.plant.local".into()),
+                dns_suffix: Some("plant.local".into()),
+                dns_search_list: vec!["plant.local".into()],
+                reverse_dns: vec![crate::system::ReverseDns {
+                    ip_address: "10.0.0.5".parse().unwrap(),
+                    name: "test-pc.plant.local".into(),
+                }],
+                machine_sid: Some("S-1-5-21-1-2-3".into()),
+                installed_on: Some(Utc::now()),
+            }),
+            os_build: Some("22631.3007".into()),
+            asset_id: Some("v1-0001".into()),
+            ..sample_report()
+        };
+        report.system.manufacturer = Some("Dell Inc.".into());
+        report.system.model = Some("OptiPlex 7090".into());
+        report.system.network_interfaces[0].mac_address = Some("00:1A:2B:3C:4D:5E".into());
+        report.software[0].vendor = Some("Test Corp".into());
+        report
+            .extensions
+            .insert("asset_tag".into(), serde_json::json!({"tag": "PLC-0042"}));
+        report.score = Some(AuditScore {
+            trend: Some(3),
+            ..AuditScore::compute(&report)
+        });
+        report
+    }
+
+    #[test]
+    fn test_full_report_matches_schema() {
+        let json = serde_json::to_value(full_report()).unwrap();
+        assert_eq!(validate_report(&json).unwrap(), Vec::<String>::new());
+        let sections = json.as_object().unwrap();
+        let schema = report_schema().unwrap();
+        for name in schema["properties"].as_object().unwrap().keys() {
+            assert!(sections.contains_key(name), "{} not populated", name);
+        }
+    }
+
+    #[test]
+    fn test_missing_field_reported() {
+        let mut json = serde_json::to_value(sample_report()).unwrap();
+        json["system"].as_object_mut().unwrap().remove("host_name");
+        json["software"][0]["name"] = serde_json::json!(42);
+
+        let violations = validate_report(&json).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().any(|v| v.starts_with("/system:")));
+        assert!(
+            violations
+                .iter()
+                .any(|v| v.starts_with("/software/0/name:"))
+        );
+    }
+}
@@ -0,0 +1,570 @@
+//! ServiceNow CMDB import-set payloads.
+//!
+//! A report becomes two import sets: one computer record for
+//! `cmdb_ci_computer` and one record per installed program for
+//! `cmdb_ci_software_instance`. Records are written to the staging tables
+//! of a [`ServiceNowMapping`], whose transform maps load them into the CMDB;
+//! the mapping also names the staging column each report field goes to.
+//! Payloads are written as files with [`ServiceNowExporter`] or, with the
+//! `servicenow` feature, posted to the Import Set API with `ServiceNowSink`.
+
+use super::report_name;
+use crate::{AuditReport, Error};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Report fields a computer column can be mapped from.
+pub const COMPUTER_SOURCES: &[&str] = &[
+    "host_name",
+    "fqdn",
+    "os_name",
+    "os_version",
+    "os_build",
+    "cpu_info",
+    "cpu_physical_cores",
+    "memory_total_bytes",
+    "memory_mb",
+    "manufacturer",
+    "model",
+    "asset_id",
+    "ip_address",
+    "mac_address",
+    "scan_time",
+];
+
+/// Software fields a software instance column can be mapped from.
+pub const SOFTWARE_SOURCES: &[&str] = &[
+    "name",
+    "version",
+    "vendor",
+    "install_date",
+    "host_name",
+    "fqdn",
+];
+
+/// Where report fields go in ServiceNow.
+///
+/// Each column map goes from a staging table column to a source field (see
+/// [`COMPUTER_SOURCES`] and [`SOFTWARE_SOURCES`]), or to a constant written
+/// as `=value`. Columns whose source is empty in the report are left out,
+/// so a transform map does not clear values the scan could not collect.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::output::ServiceNowMapping;
+///
+/// let mapping = ServiceNowMapping::from_yaml(
+///     "computer_table: u_plant_computer\ncomputer:\n  u_name: host_name\n  u_source: =sysaudit\n",
+/// )
+/// .unwrap();
+/// assert_eq!(mapping.computer_table, "u_plant_computer");
+/// assert_eq!(mapping.software_table, "u_sysaudit_software");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceNowMapping {
+    /// Staging table loaded into `cmdb_ci_computer`
+    #[serde(default = "default_computer_table")]
+    pub computer_table: String,
+    /// Staging table loaded into `cmdb_ci_software_instance`
+    #[serde(default = "default_software_table")]
+    pub software_table: String,
+    /// Computer columns and their sources
+    #[serde(default = "default_computer_fields")]
+    pub computer: BTreeMap<String, String>,
+    /// Software instance columns and their sources
+    #[serde(default = "default_software_fields")]
+    pub software: BTreeMap<String, String>,
+}
+
+fn default_computer_table() -> String {
+    "u_sysaudit_computer".to_string()
+}
+
+fn default_software_table() -> String {
+    "u_sysaudit_software".to_string()
+}
+
+fn default_computer_fields() -> BTreeMap<String, String> {
+    fields(&[
+        ("name", "host_name"),
+        ("fqdn", "fqdn"),
+        ("os", "os_name"),
+        ("os_version", "os_version"),
+        ("cpu_name", "cpu_info"),
+        ("cpu_core_count", "cpu_physical_cores"),
+        ("ram", "memory_mb"),
+        ("manufacturer", "manufacturer"),
+        ("model_id", "model"),
+        ("serial_number", "asset_id"),
+        ("ip_address", "ip_address"),
+        ("mac_address", "mac_address"),
+        ("last_discovered", "scan_time"),
+    ])
+}
+
+fn default_software_fields() -> BTreeMap<String, String> {
+    fields(&[
+        ("name", "name"),
+        ("version", "version"),
+        ("publisher", "vendor"),
+        ("install_date", "install_date"),
+        ("installed_on", "host_name"),
+    ])
+}
+
+fn fields(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(column, source)| (column.to_string(), source.to_string()))
+        .collect()
+}
+
+impl Default for ServiceNowMapping {
+    fn default() -> Self {
+        ServiceNowMapping {
+            computer_table: default_computer_table(),
+            software_table: default_software_table(),
+            computer: default_computer_fields(),
+            software: default_software_fields(),
+        }
+    }
+}
+
+impl ServiceNowMapping {
+    /// Parse a mapping from YAML; omitted keys keep their defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Yaml`] if the document is not a valid mapping, or
+    /// [`Error::General`] if a column names an unknown source.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        let mapping: ServiceNowMapping = serde_yaml::from_str(yaml)?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    /// Load a mapping from a YAML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, otherwise as
+    /// [`from_yaml`](Self::from_yaml).
+    #[cfg(feature = "yaml")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_yaml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Check that every column maps from a known source or a constant.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] naming the first unknown source.
+    pub fn validate(&self) -> Result<(), Error> {
+        let tables = [
+            (&self.computer, COMPUTER_SOURCES),
+            (&self.software, SOFTWARE_SOURCES),
+        ];
+        for (columns, sources) in tables {
+            for (column, source) in columns {
+                if !source.starts_with('=') && !sources.contains(&source.as_str()) {
+                    return Err(Error::General(format!(
+                        "unknown ServiceNow source '{}' for column '{}' (expected one of: {})",
+                        source,
+                        column,
+                        sources.join(", ")
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records for one import-set staging table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSet {
+    /// Staging table name
+    pub table: String,
+    /// One object per row, keyed by column
+    pub records: Vec<Map<String, Value>>,
+}
+
+impl ImportSet {
+    /// Body of an `insertMultiple` request: `{"records": [...]}`.
+    pub fn body(&self) -> Value {
+        json!({ "records": self.records })
+    }
+}
+
+/// Exporter turning reports into ServiceNow import-set payloads.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceNowExporter {
+    mapping: ServiceNowMapping,
+}
+
+impl ServiceNowExporter {
+    /// Create an exporter with the default mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the table and column mapping.
+    pub fn mapping(mut self, mapping: ServiceNowMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// The computer and software import sets of a report.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if the mapping names an unknown source.
+    pub fn import_sets(&self, report: &AuditReport) -> Result<Vec<ImportSet>, Error> {
+        self.mapping.validate()?;
+        let host = report.system.host_name.clone();
+        let fqdn = report.identity.as_ref().and_then(|i| i.fqdn.clone());
+
+        let mut computer = scalars(serde_json::to_value(&report.system)?);
+        let interface = report.system.network_interfaces.first();
+        let derived = [
+            ("fqdn", fqdn.clone().map(Value::from)),
+            ("os_build", report.os_build.clone().map(Value::from)),
+            (
+                "memory_mb",
+                Some(Value::from(
+                    report.system.memory_total_bytes / (1024 * 1024),
+                )),
+            ),
+            ("asset_id", report.asset_id.clone().map(Value::from)),
+            (
+                "ip_address",
+                interface.map(|i| Value::from(i.ip_address.to_string())),
+            ),
+            (
+                "mac_address",
+                interface
+                    .and_then(|i| i.mac_address.clone())
+                    .map(Value::from),
+            ),
+            (
+                "scan_time",
+                Some(Value::from(report.timestamp.to_rfc3339())),
+            ),
+        ];
+        for (source, value) in derived {
+            if let Some(value) = value {
+                computer.insert(source.to_string(), value);
+            }
+        }
+
+        let software = report
+            .software
+            .iter()
+            .map(|sw| {
+                let mut fields = scalars(serde_json::to_value(sw)?);
+                fields.insert("host_name".to_string(), Value::from(host.clone()));
+                if let Some(fqdn) = &fqdn {
+                    fields.insert("fqdn".to_string(), Value::from(fqdn.clone()));
+                }
+                Ok(map_record(&self.mapping.software, &fields))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(vec![
+            ImportSet {
+                table: self.mapping.computer_table.clone(),
+                records: vec![map_record(&self.mapping.computer, &computer)],
+            },
+            ImportSet {
+                table: self.mapping.software_table.clone(),
+                records: software,
+            },
+        ])
+    }
+
+    /// Write each import set as `<report name>_<table>.json` into a
+    /// directory, ready for an import or a later `insertMultiple` call.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use sysaudit::AuditReport;
+    /// use sysaudit::output::{ServiceNowExporter, ServiceNowMapping};
+    ///
+    /// # fn example() -> Result<(), sysaudit::Error> {
+    /// let report = AuditReport::from_json_versioned(&std::fs::read_to_string("hmi01.json")?)?;
+    /// ServiceNowExporter::new()
+    ///     .mapping(ServiceNowMapping::load("servicenow.yaml")?)
+    ///     .export_report(&report, Path::new("cmdb"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the mapping is invalid or the directory or any
+    /// file cannot be written.
+    pub fn export_report(&self, report: &AuditReport, dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        std::fs::create_dir_all(dir)?;
+        let name = report_name(report);
+        let mut written = Vec::new();
+        for set in self.import_sets(report)? {
+            let path = dir.join(format!("{}_{}.json", name, set.table));
+            std::fs::write(&path, serde_json::to_string_pretty(&set.body())?)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// The string and number fields of a serialized entry.
+fn scalars(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(fields) => fields
+            .into_iter()
+            .filter(|(_, v)| v.is_string() || v.is_number())
+            .collect(),
+        _ => Map::new(),
+    }
+}
+
+/// One staging row: each column's constant or source value, leaving out
+/// columns whose source is empty.
+fn map_record(
+    columns: &BTreeMap<String, String>,
+    fields: &Map<String, Value>,
+) -> Map<String, Value> {
+    columns
+        .iter()
+        .filter_map(|(column, source)| {
+            let value = match source.strip_prefix('=') {
+                Some(constant) => Value::from(constant),
+                None => fields.get(source)?.clone(),
+            };
+            Some((column.clone(), value))
+        })
+        .collect()
+}
+
+#[cfg(feature = "servicenow")]
+pub use sink::{ServiceNowAuth, ServiceNowSink};
+
+#[cfg(feature = "servicenow")]
+mod sink {
+    use super::{ServiceNowExporter, ServiceNowMapping};
+    use crate::{AuditReport, Error};
+    use reqwest::blocking::Client;
+    use secrecy::{ExposeSecret, SecretString};
+    use std::time::Duration;
+
+    /// Request timeout for Import Set API calls.
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Most records posted in one `insertMultiple` request.
+    const BATCH_SIZE: usize = 200;
+
+    /// Authentication for the instance.
+    #[derive(Debug, Clone)]
+    pub enum ServiceNowAuth {
+        /// HTTP basic authentication of an integration user
+        Basic {
+            /// User name
+            username: String,
+            /// Password
+            password: SecretString,
+        },
+        /// OAuth access token
+        Bearer(SecretString),
+    }
+
+    /// Sink posting import sets to a ServiceNow instance.
+    #[derive(Debug, Clone)]
+    pub struct ServiceNowSink {
+        url: String,
+        auth: ServiceNowAuth,
+        exporter: ServiceNowExporter,
+    }
+
+    impl ServiceNowSink {
+        /// Create a sink for an instance URL (e.g.
+        /// `https://plant.service-now.com`).
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use sysaudit::{LocalScanner, Scanner};
+        /// use sysaudit::output::{ServiceNowAuth, ServiceNowMapping, ServiceNowSink};
+        /// use secrecy::SecretString;
+        ///
+        /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+        /// let report = LocalScanner::new().audit().await?;
+        /// let auth = ServiceNowAuth::Basic {
+        ///     username: "sysaudit.integration".into(),
+        ///     password: SecretString::from("secret"),
+        /// };
+        /// ServiceNowSink::new("https://plant.service-now.com", auth)
+        ///     .mapping(ServiceNowMapping::load("servicenow.yaml")?)
+        ///     .send(&report)?;
+        /// # Ok(())
+        /// # }
+        /// ```
+        pub fn new(url: impl Into<String>, auth: ServiceNowAuth) -> Self {
+            ServiceNowSink {
+                url: url.into().trim_end_matches('/').to_string(),
+                auth,
+                exporter: ServiceNowExporter::new(),
+            }
+        }
+
+        /// Set the table and column mapping.
+        pub fn mapping(mut self, mapping: ServiceNowMapping) -> Self {
+            self.exporter = self.exporter.mapping(mapping);
+            self
+        }
+
+        /// Post the report's import sets through `insertMultiple`, in
+        /// batches of up to 200 records.
+        ///
+        /// Returns the number of records posted.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`Error::Http`] if the instance is unreachable, or
+        /// [`Error::General`] if the mapping is invalid or the instance
+        /// rejects a batch.
+        pub fn send(&self, report: &AuditReport) -> Result<usize, Error> {
+            let client = Client::builder().timeout(TIMEOUT).build()?;
+            let mut posted = 0;
+            for set in self.exporter.import_sets(report)? {
+                let url = format!("{}/api/now/import/{}/insertMultiple", self.url, set.table);
+                for batch in set.records.chunks(BATCH_SIZE) {
+                    let request = client
+                        .post(&url)
+                        .header("Accept", "application/json")
+                        .json(&serde_json::json!({ "records": batch }));
+                    let request = match &self.auth {
+                        ServiceNowAuth::Basic { username, password } => {
+                            request.basic_auth(username, Some(password.expose_secret()))
+                        }
+                        ServiceNowAuth::Bearer(token) => request.bearer_auth(token.expose_secret()),
+                    };
+
+                    let response = request.send()?;
+                    let status = response.status();
+                    if !status.is_success() {
+                        let text = response.text().unwrap_or_default();
+                        return Err(Error::General(format!(
+                            "ServiceNow import into {} failed with {}: {}",
+                            set.table, status, text
+                        )));
+                    }
+                    posted += batch.len();
+                }
+                tracing::debug!(table = %set.table, count = set.records.len(), "Import set posted");
+            }
+            Ok(posted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+    use sysaudit_common::{SoftwareDto, SystemInfoDto};
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            software: vec![SoftwareDto {
+                name: "7-Zip".into(),
+                version: Some("23.01".into()),
+                vendor: None,
+                install_date: None,
+            }],
+            asset_id: Some("bios:ABC123".into()),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                memory_total_bytes: 16 * 1024 * 1024 * 1024,
+                manufacturer: Some("Dell Inc.".into()),
+                ..test_system()
+            })
+        }
+    }
+
+    #[test]
+    fn test_default_mapping() {
+        let sets = ServiceNowExporter::new()
+            .import_sets(&sample_report())
+            .unwrap();
+        assert_eq!(sets[0].table, "u_sysaudit_computer");
+        let computer = &sets[0].records[0];
+        assert_eq!(computer["name"], "TEST-PC");
+        assert_eq!(computer["ram"], 16384);
+        assert_eq!(computer["cpu_core_count"], 8);
+        assert_eq!(computer["serial_number"], "bios:ABC123");
+        assert_eq!(computer["last_discovered"], "2024-01-01T00:00:00+00:00");
+        assert!(computer.get("model_id").is_none());
+        assert!(computer.get("fqdn").is_none());
+
+        let software = &sets[1].records;
+        assert_eq!(software.len(), 1);
+        assert_eq!(software[0]["installed_on"], "TEST-PC");
+        assert_eq!(software[0]["version"], "23.01");
+        assert!(software[0].get("publisher").is_none());
+        assert_eq!(sets[1].body()["records"][0]["name"], "7-Zip");
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_custom_mapping() {
+        let mapping = ServiceNowMapping::from_yaml(
+            "computer:\n  u_host: host_name\n  u_source: =sysaudit\nsoftware:\n  u_product: name\n",
+        )
+        .unwrap();
+        let sets = ServiceNowExporter::new()
+            .mapping(mapping)
+            .import_sets(&sample_report())
+            .unwrap();
+        let computer = &sets[0].records[0];
+        assert_eq!(computer.len(), 2);
+        assert_eq!(computer["u_source"], "sysaudit");
+        assert_eq!(sets[1].records[0].len(), 1);
+
+        let err = ServiceNowMapping::from_yaml("software:\n  u_os: os_name\n").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unknown ServiceNow source 'os_name'")
+        );
+        assert!(ServiceNowMapping::from_yaml("tables: []\n").is_err());
+    }
+
+    #[test]
+    fn test_export_report() {
+        let dir = std::env::temp_dir().join(format!("sysaudit-servicenow-{}", std::process::id()));
+        let files = ServiceNowExporter::new()
+            .export_report(&sample_report(), &dir)
+            .unwrap();
+        let names: Vec<String> = files
+            .iter()
+            .map(|f| f.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "sysaudit_TEST-PC_20240101_000000_u_sysaudit_computer.json",
+                "sysaudit_TEST-PC_20240101_000000_u_sysaudit_software.json"
+            ]
+        );
+        let body: Value =
+            serde_json::from_str(&std::fs::read_to_string(&files[0]).unwrap()).unwrap();
+        assert_eq!(body["records"][0]["os"], "Windows 11 Pro");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
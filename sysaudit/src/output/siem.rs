@@ -0,0 +1,228 @@
+//! CEF and LEEF output for SIEM ingestion.
+//!
+//! Each [`Finding`] becomes one record per line. A final summary record
+//! carries the host's inventory counts so the SIEM sees every scan, even
+//! one without findings.
+
+use crate::{AuditReport, Finding, Severity};
+
+const VENDOR: &str = "sysaudit";
+const PRODUCT: &str = "sysaudit";
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// SIEM formatter producing CEF or LEEF records.
+pub struct SiemFormatter;
+
+impl SiemFormatter {
+    /// Format findings as ArcSight CEF records, one per line.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{Finding, LocalScanner, Scanner, output::SiemFormatter};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// print!("{}", SiemFormatter::format_cef(&report, &Finding::collect(&report)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_cef(report: &AuditReport, findings: &[Finding]) -> String {
+        records(report, findings)
+            .map(|record| {
+                let mut line = format!(
+                    "CEF:0|{}|{}|{}|{}|{}|{}|",
+                    cef_header(VENDOR),
+                    cef_header(PRODUCT),
+                    cef_header(VERSION),
+                    cef_header(&record.id),
+                    cef_header(&record.title),
+                    record.severity.level()
+                );
+                let mut ext = vec![
+                    (
+                        "rt".to_string(),
+                        report.timestamp.timestamp_millis().to_string(),
+                    ),
+                    ("dhost".to_string(), report.host_id().to_string()),
+                    ("cat".to_string(), record.category.clone()),
+                ];
+                if let Some(metadata) = &report.metadata {
+                    ext.push((
+                        "start".to_string(),
+                        metadata.started.timestamp_millis().to_string(),
+                    ));
+                    ext.push((
+                        "end".to_string(),
+                        metadata.finished.timestamp_millis().to_string(),
+                    ));
+                    if let Some(user) = &metadata.user {
+                        ext.push(("suser".to_string(), user.clone()));
+                    }
+                }
+                for (i, (key, value)) in record.attributes.iter().enumerate().take(6) {
+                    ext.push((format!("cs{}Label", i + 1), key.clone()));
+                    ext.push((format!("cs{}", i + 1), value.clone()));
+                }
+                let ext: Vec<String> = ext
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, cef_value(v)))
+                    .collect();
+                line.push_str(&ext.join(" "));
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// Format findings as IBM QRadar LEEF 1.0 records, one per line.
+    pub fn format_leef(report: &AuditReport, findings: &[Finding]) -> String {
+        records(report, findings)
+            .map(|record| {
+                let mut line = format!(
+                    "LEEF:1.0|{}|{}|{}|{}|",
+                    leef_header(VENDOR),
+                    leef_header(PRODUCT),
+                    leef_header(VERSION),
+                    leef_header(&record.id)
+                );
+                let mut attrs = vec![
+                    (
+                        "devTime".to_string(),
+                        report.timestamp.timestamp_millis().to_string(),
+                    ),
+                    ("identHostName".to_string(), report.host_id().to_string()),
+                    ("cat".to_string(), record.category.clone()),
+                    ("sev".to_string(), record.severity.level().to_string()),
+                    ("msg".to_string(), record.title.clone()),
+                ];
+                if let Some(user) = report.metadata.as_ref().and_then(|m| m.user.as_ref()) {
+                    attrs.push(("usrName".to_string(), user.clone()));
+                }
+                attrs.extend(record.attributes.iter().cloned());
+                let attrs: Vec<String> = attrs
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, leef_value(v)))
+                    .collect();
+                line.push_str(&attrs.join("\t"));
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+}
+
+/// Findings followed by the per-scan summary record.
+pub(crate) fn records<'a>(
+    report: &'a AuditReport,
+    findings: &'a [Finding],
+) -> impl Iterator<Item = Finding> + 'a {
+    let mut summary = Finding {
+        id: "scan-summary".to_string(),
+        category: "inventory".to_string(),
+        severity: Severity::Info,
+        title: format!("Audit completed for {}", report.system.host_name),
+        attributes: vec![
+            (
+                "os".to_string(),
+                format!("{} {}", report.system.os_name, report.system.os_version),
+            ),
+            ("software".to_string(), report.software.len().to_string()),
+            (
+                "industrial".to_string(),
+                report.industrial.len().to_string(),
+            ),
+            ("updates".to_string(), report.updates.len().to_string()),
+            ("findings".to_string(), findings.len().to_string()),
+        ],
+    };
+    if let Some(metadata) = &report.metadata {
+        summary
+            .attributes
+            .push(("scanner".to_string(), metadata.scanner.to_string()));
+        summary
+            .attributes
+            .push(("duration_ms".to_string(), metadata.duration_ms.to_string()));
+    }
+    findings.iter().cloned().chain(std::iter::once(summary))
+}
+
+/// Escape a CEF header field.
+fn cef_header(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+/// Escape a CEF extension value.
+fn cef_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+/// Escape a LEEF header field.
+fn leef_header(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// Strip characters that would split a LEEF attribute or record.
+fn leef_value(value: &str) -> String {
+    value.replace(['\t', '\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    fn sample_finding() -> Finding {
+        Finding {
+            id: "industrial-detected".into(),
+            category: "industrial".into(),
+            severity: Severity::Medium,
+            title: "Industrial software detected: AB|C".into(),
+            attributes: vec![("path".into(), r"C:\a=b".into())],
+        }
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(cef_header("a|b\\c"), "a\\|b\\\\c");
+        assert_eq!(cef_value("k=v\nx"), "k\\=v\\nx");
+        assert_eq!(leef_value("a\tb"), "a b");
+    }
+
+    #[test]
+    fn test_format_cef() {
+        let output = SiemFormatter::format_cef(&sample_report(), &[sample_finding()]);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("CEF:0|sysaudit|sysaudit|"));
+        assert!(lines[0].contains("|industrial-detected|Industrial software detected: AB\\|C|5|"));
+        assert!(lines[0].contains("dhost=TEST-PC"));
+        assert!(lines[0].contains("cs1Label=path cs1=C:\\\\a\\=b"));
+        assert!(lines[1].contains("|scan-summary|"));
+    }
+
+    #[test]
+    fn test_format_leef() {
+        let output = SiemFormatter::format_leef(&sample_report(), &[sample_finding()]);
+        let first = output.lines().next().unwrap();
+        assert!(first.starts_with("LEEF:1.0|sysaudit|sysaudit|"));
+        assert!(first.contains("|industrial-detected|devTime="));
+        assert!(first.contains("\tsev=5\t"));
+        assert!(first.contains("\tpath=C:\\a=b"));
+    }
+}
@@ -0,0 +1,190 @@
+//! Detached ed25519 signatures over audit reports.
+//!
+//! The signature covers the canonical JSON form of the report: object keys
+//! sorted, no insignificant whitespace. A report can therefore be
+//! pretty-printed or re-serialized without invalidating its signature, while
+//! any change to a value does. Signatures are stored as base64 text.
+//!
+//! Keys are read from PKCS#8 PEM files, e.g. as produced by
+//! `openssl genpkey -algorithm ed25519`.
+
+use crate::{AuditReport, Error};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde_json::Value;
+use std::path::Path;
+
+/// Canonical JSON bytes for a report document.
+///
+/// # Errors
+///
+/// Returns [`Error::Json`] if `json` is not valid JSON.
+pub fn canonical_json(json: &[u8]) -> Result<Vec<u8>, Error> {
+    let value: Value = serde_json::from_slice(json)?;
+    Ok(serde_json::to_vec(&sort_keys(value))?)
+}
+
+/// `value` with the keys of every object in sorted order, whether or not
+/// serde_json was built with `preserve_order`.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object
+                .into_iter()
+                .map(|(key, value)| (key, sort_keys(value)))
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Sign a report, returning a base64-encoded detached signature.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::{LocalScanner, Scanner, output::{load_signing_key, sign_report}};
+/// use std::path::Path;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let report = LocalScanner::new().audit().await?;
+/// let key = load_signing_key(Path::new("audit-key.pem"))?;
+/// std::fs::write("report.json.sig", sign_report(&report, &key)?)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`Error::Json`] if the report cannot be serialized.
+pub fn sign_report(report: &AuditReport, key: &SigningKey) -> Result<String, Error> {
+    let signature = key.sign(&canonical_json(&serde_json::to_vec(report)?)?);
+    Ok(STANDARD.encode(signature.to_bytes()))
+}
+
+/// Verify a detached signature against a stored JSON report.
+///
+/// # Errors
+///
+/// Returns [`Error::Signature`] if the signature is malformed or does not
+/// match, or [`Error::Json`] if the report is not valid JSON.
+pub fn verify_report(json: &[u8], signature: &str, key: &VerifyingKey) -> Result<(), Error> {
+    let bytes = STANDARD
+        .decode(signature.trim())
+        .map_err(|e| Error::Signature(format!("invalid signature encoding: {}", e)))?;
+    let signature = Signature::from_slice(&bytes)
+        .map_err(|e| Error::Signature(format!("invalid signature: {}", e)))?;
+
+    key.verify(&canonical_json(json)?, &signature)
+        .map_err(|_| Error::Signature("signature does not match report".to_string()))
+}
+
+/// Load an ed25519 private key from a PKCS#8 PEM file.
+///
+/// # Errors
+///
+/// Returns [`Error`] if the file cannot be read or is not an ed25519 key.
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, Error> {
+    let pem = std::fs::read_to_string(path)?;
+    SigningKey::from_pkcs8_pem(&pem)
+        .map_err(|e| Error::Signature(format!("invalid private key {}: {}", path.display(), e)))
+}
+
+/// Load an ed25519 public key from a PEM file.
+///
+/// # Errors
+///
+/// Returns [`Error`] if the file cannot be read or is not an ed25519 key.
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey, Error> {
+    let pem = std::fs::read_to_string(path)?;
+    VerifyingKey::from_public_key_pem(&pem)
+        .map_err(|e| Error::Signature(format!("invalid public key {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use crate::{AuditScore, Collector, ScanWarning};
+    use sysaudit_common::{IndustrialSoftwareDto, SoftwareDto};
+
+    fn sample_report() -> AuditReport {
+        AuditReport::empty(test_system())
+    }
+
+    #[test]
+    fn test_canonical_json_ignores_layout() {
+        let a = canonical_json(br#"{"b": 1, "a": [1, 2]}"#).unwrap();
+        let b = canonical_json(b"{\n  \"a\": [1,2],\n  \"b\": 1\n}").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, br#"{"a":[1,2],"b":1}"#);
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_nested_keys() {
+        let json = br#"{"z": {"y": 1, "x": [{"d": 2, "c": 3}]}, "a": null}"#;
+        assert_eq!(
+            canonical_json(json).unwrap(),
+            br#"{"a":null,"z":{"x":[{"c":3,"d":2}],"y":1}}"#
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify_pretty_report() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let report = sample_report();
+        let signature = sign_report(&report, &key).unwrap();
+
+        let stored = serde_json::to_vec_pretty(&report).unwrap();
+        verify_report(&stored, &signature, &key.verifying_key()).unwrap();
+    }
+
+    #[test]
+    fn test_sign_and_verify_populated_report() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut report = sample_report();
+        report.software = vec![SoftwareDto {
+            name: "Citect SCADA".into(),
+            version: Some("8.0".into()),
+            vendor: Some("AVEVA".into()),
+            install_date: None,
+        }];
+        report.industrial = vec![IndustrialSoftwareDto {
+            vendor: "AVEVA".into(),
+            product: "Citect SCADA".into(),
+            version: Some("8.0".into()),
+            install_path: None,
+        }];
+        report.warnings = vec![ScanWarning::new(Collector::Updates, "access denied")];
+        report
+            .extensions
+            .insert("site".into(), serde_json::json!({"zone": "b", "area": "a"}));
+        report.score = Some(AuditScore::compute(&report));
+
+        let signature = sign_report(&report, &key).unwrap();
+        let stored = serde_json::to_vec(&report).unwrap();
+        key.verifying_key()
+            .verify(
+                &canonical_json(&stored).unwrap(),
+                &Signature::from_slice(&STANDARD.decode(&signature).unwrap()).unwrap(),
+            )
+            .unwrap();
+        verify_report(&stored, &signature, &key.verifying_key()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut report = sample_report();
+        let signature = sign_report(&report, &key).unwrap();
+
+        report.system.host_name = "OTHER-PC".into();
+        let stored = serde_json::to_vec(&report).unwrap();
+        let err = verify_report(&stored, &signature, &key.verifying_key()).unwrap_err();
+        assert!(matches!(err, Error::Signature(_)));
+    }
+}
@@ -0,0 +1,283 @@
+//! Splunk HTTP Event Collector (HEC) output.
+
+use crate::{AuditReport, Error};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use secrecy::{ExposeSecret, SecretString};
+use serde_json::{Value, json};
+use std::io::Write;
+use std::time::Duration;
+
+/// Default number of retries after the first attempt.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Request timeout for HEC calls.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Delay before the first retry; doubled on each further attempt (capped at 64x).
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// What each HEC event represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplunkMode {
+    /// One `sysaudit:report` event per report
+    #[default]
+    Report,
+    /// One event per entry, with sourcetypes `sysaudit:system`,
+    /// `sysaudit:software`, `sysaudit:industrial` and `sysaudit:update`
+    Items,
+}
+
+/// Sink sending reports to a Splunk HTTP Event Collector.
+#[derive(Debug, Clone)]
+pub struct SplunkSink {
+    url: String,
+    token: SecretString,
+    index: Option<String>,
+    mode: SplunkMode,
+    gzip: bool,
+    retries: u32,
+}
+
+impl SplunkSink {
+    /// Create a sink for a HEC base URL (e.g. `https://splunk:8088`) and token.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::{SplunkMode, SplunkSink}};
+    /// use secrecy::SecretString;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// SplunkSink::new("https://splunk.example.com:8088", SecretString::from("hec-token"))
+    ///     .index("ot_assets")
+    ///     .mode(SplunkMode::Items)
+    ///     .send(&report)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(url: impl Into<String>, token: SecretString) -> Self {
+        SplunkSink {
+            url: url.into().trim_end_matches('/').to_string(),
+            token,
+            index: None,
+            mode: SplunkMode::default(),
+            gzip: true,
+            retries: DEFAULT_RETRIES,
+        }
+    }
+
+    /// Set the target index (otherwise the token's default index is used).
+    pub fn index(mut self, index: impl Into<String>) -> Self {
+        self.index = Some(index.into());
+        self
+    }
+
+    /// Set the event mode.
+    pub fn mode(mut self, mode: SplunkMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Enable or disable gzip request compression (enabled by default).
+    pub fn gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
+
+    /// Set how many times a failed request is retried.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Send the report to the collector.
+    ///
+    /// Connection failures, `429` and `5xx` responses are retried with
+    /// exponential backoff. Returns the number of events sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Http`] if the collector stays unreachable, or
+    /// [`Error::General`] if it rejects the events.
+    pub fn send(&self, report: &AuditReport) -> Result<usize, Error> {
+        let events = self.events(report)?;
+        let mut body = Vec::new();
+        for event in &events {
+            serde_json::to_writer(&mut body, event)?;
+            body.push(b'\n');
+        }
+        if self.gzip {
+            body = gzip(&body)?;
+        }
+
+        let client = Client::builder().timeout(TIMEOUT).build()?;
+        let url = format!("{}/services/collector/event", self.url);
+        let mut attempt = 0;
+        loop {
+            let mut request = client
+                .post(&url)
+                .header(
+                    "Authorization",
+                    format!("Splunk {}", self.token.expose_secret()),
+                )
+                .header("Content-Type", "application/json")
+                .body(body.clone());
+            if self.gzip {
+                request = request.header("Content-Encoding", "gzip");
+            }
+
+            let retry = match request.send() {
+                Ok(response) => {
+                    let status = response.status();
+                    if status.is_success() {
+                        break;
+                    }
+                    let text = response.text().unwrap_or_default();
+                    if !is_retryable(status) || attempt >= self.retries {
+                        return Err(Error::General(format!(
+                            "Splunk HEC at {} rejected events with {}: {}",
+                            self.url, status, text
+                        )));
+                    }
+                    format!("HTTP {}", status)
+                }
+                Err(e) if attempt >= self.retries => return Err(e.into()),
+                Err(e) => e.to_string(),
+            };
+
+            let delay = RETRY_BASE_DELAY * 2u32.pow(attempt.min(6));
+            attempt += 1;
+            tracing::warn!(attempt, reason = %retry, delay = ?delay, "Retrying Splunk HEC request");
+            std::thread::sleep(delay);
+        }
+
+        tracing::debug!(count = events.len(), "Events sent to Splunk HEC");
+        Ok(events.len())
+    }
+
+    /// Build HEC event envelopes for the configured mode.
+    fn events(&self, report: &AuditReport) -> Result<Vec<Value>, Error> {
+        let host = report.host_id();
+        let time = report.timestamp.timestamp_millis() as f64 / 1000.0;
+        let envelope = |sourcetype: &str, event: Value| {
+            let mut envelope = json!({
+                "time": time,
+                "host": host,
+                "source": "sysaudit",
+                "sourcetype": sourcetype,
+                "event": event,
+            });
+            if let Some(index) = &self.index {
+                envelope["index"] = json!(index);
+            }
+            envelope
+        };
+
+        if self.mode == SplunkMode::Report {
+            return Ok(vec![envelope(
+                "sysaudit:report",
+                serde_json::to_value(report)?,
+            )]);
+        }
+
+        let mut events = vec![envelope(
+            "sysaudit:system",
+            serde_json::to_value(&report.system)?,
+        )];
+        for sw in &report.software {
+            events.push(envelope("sysaudit:software", serde_json::to_value(sw)?));
+        }
+        for sw in &report.industrial {
+            events.push(envelope("sysaudit:industrial", serde_json::to_value(sw)?));
+        }
+        for update in &report.updates {
+            events.push(envelope("sysaudit:update", serde_json::to_value(update)?));
+        }
+        Ok(events)
+    }
+}
+
+/// Whether a response status is worth retrying.
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn gzip(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use sysaudit_common::IndustrialSoftwareDto;
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "ABB".into(),
+                product: "Automation Builder".into(),
+                version: None,
+                install_path: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    fn sink() -> SplunkSink {
+        SplunkSink::new("https://splunk:8088/", SecretString::from("token"))
+    }
+
+    #[test]
+    fn test_report_event() {
+        let events = sink().index("ot").events(&sample_report()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["sourcetype"], "sysaudit:report");
+        assert_eq!(events[0]["host"], "TEST-PC");
+        assert_eq!(events[0]["index"], "ot");
+        assert_eq!(events[0]["time"], 1704067200.0);
+        assert_eq!(events[0]["event"]["system"]["host_name"], "TEST-PC");
+    }
+
+    #[test]
+    fn test_item_events() {
+        let events = sink()
+            .mode(SplunkMode::Items)
+            .events(&sample_report())
+            .unwrap();
+        let types: Vec<&str> = events
+            .iter()
+            .map(|e| e["sourcetype"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["sysaudit:system", "sysaudit:industrial"]);
+        assert!(events[0].get("index").is_none());
+        assert_eq!(events[1]["event"]["product"], "Automation Builder");
+    }
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let compressed = gzip(b"{\"event\":1}").unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "{\"event\":1}");
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable(StatusCode::FORBIDDEN));
+    }
+}
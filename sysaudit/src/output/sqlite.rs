@@ -0,0 +1,305 @@
+//! SQLite database export.
+//!
+//! Besides the inventory tables, every export adds a row to `history`:
+//! per-scan counts and patch age keyed by asset ID and scan time, which
+//! [`HistoryDb`](crate::history::HistoryDb) reads as trends.
+
+use crate::{AuditReport, Error};
+use rusqlite::{Connection, params};
+use std::path::Path;
+
+/// Table definitions. Every row is keyed by host name + scan timestamp.
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS hosts (
+    host_name          TEXT NOT NULL,
+    scanned_at         TEXT NOT NULL,
+    os_name            TEXT NOT NULL,
+    os_version         TEXT NOT NULL,
+    cpu_info           TEXT NOT NULL,
+    cpu_physical_cores INTEGER,
+    memory_total_bytes INTEGER NOT NULL,
+    memory_used_bytes  INTEGER NOT NULL,
+    manufacturer       TEXT,
+    model              TEXT,
+    PRIMARY KEY (host_name, scanned_at)
+);
+CREATE TABLE IF NOT EXISTS software (
+    host_name    TEXT NOT NULL,
+    scanned_at   TEXT NOT NULL,
+    name         TEXT NOT NULL,
+    version      TEXT,
+    publisher    TEXT,
+    install_date TEXT
+);
+CREATE TABLE IF NOT EXISTS industrial (
+    host_name    TEXT NOT NULL,
+    scanned_at   TEXT NOT NULL,
+    vendor       TEXT NOT NULL,
+    product      TEXT NOT NULL,
+    version      TEXT,
+    install_path TEXT
+);
+CREATE TABLE IF NOT EXISTS updates (
+    host_name    TEXT NOT NULL,
+    scanned_at   TEXT NOT NULL,
+    hotfix_id    TEXT NOT NULL,
+    description  TEXT,
+    installed_on TEXT,
+    installed_by TEXT
+);
+CREATE TABLE IF NOT EXISTS scans (
+    host_name    TEXT NOT NULL,
+    scanned_at   TEXT NOT NULL,
+    tool_version TEXT NOT NULL,
+    scanner      TEXT NOT NULL,
+    started_at   TEXT NOT NULL,
+    finished_at  TEXT NOT NULL,
+    duration_ms  INTEGER NOT NULL,
+    user_name    TEXT,
+    elevated     INTEGER,
+    options      TEXT NOT NULL,
+    PRIMARY KEY (host_name, scanned_at)
+);
+CREATE TABLE IF NOT EXISTS history (
+    asset_id         TEXT NOT NULL,
+    scanned_at       TEXT NOT NULL,
+    host_name        TEXT NOT NULL,
+    software_count   INTEGER NOT NULL,
+    industrial_count INTEGER NOT NULL,
+    update_count     INTEGER NOT NULL,
+    patch_age_days   INTEGER,
+    score            INTEGER,
+    PRIMARY KEY (asset_id, scanned_at)
+);
+CREATE INDEX IF NOT EXISTS idx_software_scan ON software (host_name, scanned_at);
+CREATE INDEX IF NOT EXISTS idx_industrial_scan ON industrial (host_name, scanned_at);
+CREATE INDEX IF NOT EXISTS idx_updates_scan ON updates (host_name, scanned_at);
+CREATE INDEX IF NOT EXISTS idx_history_host ON history (host_name, scanned_at);
+"#;
+
+/// Tables cleared when the same scan is exported twice.
+pub(crate) const SCAN_TABLES: [&str; 5] = ["hosts", "scans", "software", "industrial", "updates"];
+
+/// SQLite exporter for audit reports.
+pub struct SqliteExporter;
+
+impl SqliteExporter {
+    /// Append a report to a SQLite database, creating it if needed.
+    ///
+    /// Each scan is stored under its host name and timestamp, so repeated
+    /// exports into the same file build a history. Re-exporting the same
+    /// scan replaces its rows rather than duplicating them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::SqliteExporter};
+    /// use std::path::Path;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// SqliteExporter::export_report(&report, Path::new("inventory.db"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the database cannot be opened or written.
+    pub fn export_report(report: &AuditReport, path: &Path) -> Result<(), Error> {
+        let mut conn = Connection::open(path)?;
+        Self::write_report(&mut conn, report)
+    }
+
+    /// Create the tables of a new database; existing tables are kept.
+    pub(crate) fn create_schema(conn: &Connection) -> Result<(), Error> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(())
+    }
+
+    pub(crate) fn write_report(conn: &mut Connection, report: &AuditReport) -> Result<(), Error> {
+        Self::create_schema(conn)?;
+
+        let host = &report.system.host_name;
+        let scanned_at = report.timestamp.to_rfc3339();
+        let tx = conn.transaction()?;
+
+        for table in SCAN_TABLES {
+            tx.execute(
+                &format!(
+                    "DELETE FROM {} WHERE host_name = ?1 AND scanned_at = ?2",
+                    table
+                ),
+                params![host, scanned_at],
+            )?;
+        }
+
+        let system = &report.system;
+        tx.execute(
+            "INSERT INTO hosts (host_name, scanned_at, os_name, os_version, cpu_info, \
+             cpu_physical_cores, memory_total_bytes, memory_used_bytes, manufacturer, model) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                host,
+                scanned_at,
+                system.os_name,
+                system.os_version,
+                system.cpu_info,
+                system.cpu_physical_cores,
+                system.memory_total_bytes as i64,
+                system.memory_used_bytes as i64,
+                system.manufacturer,
+                system.model,
+            ],
+        )?;
+
+        if let Some(metadata) = &report.metadata {
+            tx.execute(
+                "INSERT INTO scans (host_name, scanned_at, tool_version, scanner, started_at, \
+                 finished_at, duration_ms, user_name, elevated, options) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    host,
+                    scanned_at,
+                    metadata.tool_version,
+                    metadata.scanner.to_string(),
+                    metadata.started.to_rfc3339(),
+                    metadata.finished.to_rfc3339(),
+                    i64::try_from(metadata.duration_ms).unwrap_or(i64::MAX),
+                    metadata.user,
+                    metadata.elevated,
+                    serde_json::to_string(&metadata.options)?,
+                ],
+            )?;
+        }
+
+        for sw in &report.software {
+            tx.execute(
+                "INSERT INTO software (host_name, scanned_at, name, version, publisher, install_date) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    host,
+                    scanned_at,
+                    sw.name,
+                    sw.version,
+                    sw.vendor,
+                    sw.install_date.map(|d| d.date_naive().to_string()),
+                ],
+            )?;
+        }
+
+        for sw in &report.industrial {
+            tx.execute(
+                "INSERT INTO industrial (host_name, scanned_at, vendor, product, version, install_path) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    host,
+                    scanned_at,
+                    sw.vendor,
+                    sw.product,
+                    sw.version,
+                    sw.install_path.as_ref().map(|p| p.display().to_string()),
+                ],
+            )?;
+        }
+
+        for update in &report.updates {
+            tx.execute(
+                "INSERT INTO updates (host_name, scanned_at, hotfix_id, description, installed_on, installed_by) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    host,
+                    scanned_at,
+                    update.hotfix_id,
+                    update.description,
+                    update.installed_on.map(|d| d.to_string()),
+                    update.installed_by,
+                ],
+            )?;
+        }
+
+        // Days between the newest dated update and the scan
+        let patch_age = report
+            .updates
+            .iter()
+            .filter_map(|u| u.installed_on)
+            .max()
+            .map(|latest| (report.timestamp.date_naive() - latest).num_days());
+        tx.execute(
+            "INSERT OR REPLACE INTO history (asset_id, scanned_at, host_name, software_count, \
+             industrial_count, update_count, patch_age_days, score) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                history_key(report),
+                scanned_at,
+                host,
+                report.software.len() as i64,
+                report.industrial.len() as i64,
+                report.updates.len() as i64,
+                patch_age,
+                report.score.as_ref().and_then(|s| s.overall),
+            ],
+        )?;
+
+        tx.commit()?;
+        tracing::debug!(host = %host, scanned_at = %scanned_at, "Report written to SQLite");
+        Ok(())
+    }
+}
+
+/// Key of a report's scans in `history`: its asset ID, or the host name
+/// for reports without one.
+fn history_key(report: &AuditReport) -> &str {
+    report
+        .asset_id
+        .as_deref()
+        .unwrap_or(&report.system.host_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+    use sysaudit_common::SoftwareDto;
+
+    fn sample_report(day: u32) -> AuditReport {
+        AuditReport {
+            software: vec![SoftwareDto {
+                name: "TestApp".into(),
+                version: Some("1.0".into()),
+                vendor: Some("Acme".into()),
+                install_date: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_export_appends_scans() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        SqliteExporter::write_report(&mut conn, &sample_report(1)).unwrap();
+        SqliteExporter::write_report(&mut conn, &sample_report(2)).unwrap();
+
+        assert_eq!(count(&conn, "hosts"), 2);
+        assert_eq!(count(&conn, "software"), 2);
+        assert_eq!(count(&conn, "history"), 2);
+    }
+
+    #[test]
+    fn test_export_same_scan_replaces_rows() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        SqliteExporter::write_report(&mut conn, &sample_report(1)).unwrap();
+        SqliteExporter::write_report(&mut conn, &sample_report(1)).unwrap();
+
+        assert_eq!(count(&conn, "hosts"), 1);
+        assert_eq!(count(&conn, "software"), 1);
+        assert_eq!(count(&conn, "history"), 1);
+    }
+}
@@ -0,0 +1,317 @@
+//! STIX 2.1 bundle output for threat-intelligence platforms.
+//!
+//! A report becomes one bundle: an `infrastructure` object for the host,
+//! `software` objects for the operating system, installed programs and
+//! industrial products, `ipv4-addr`/`ipv6-addr` and `mac-addr` objects for
+//! its interfaces, an `observed-data` object referencing all of them, and
+//! a `consists-of` relationship from the host to the observation.
+//!
+//! Cyber-observable ids are the deterministic UUIDv5 ids of the STIX
+//! specification, so the same program on two hosts is one object in a
+//! platform such as MISP. The other ids derive from host and scan time:
+//! exporting the same scan twice produces the same bundle.
+
+use crate::{AuditReport, Error};
+use chrono::SecondsFormat;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeSet;
+use sysaudit_common::IpVersion;
+use uuid::Uuid;
+
+/// Namespace of STIX cyber-observable object ids.
+const SCO_NAMESPACE: Uuid = Uuid::from_u128(0x00abedb4_aa42_466c_9c01_fed23315a9b7);
+
+/// Name used to derive the namespace of this crate's domain object ids.
+const SDO_NAMESPACE_NAME: &str = "https://github.com/wends155/sysaudit/stix";
+
+/// STIX 2.1 bundle formatter.
+pub struct StixFormatter;
+
+impl StixFormatter {
+    /// Build the STIX bundle of a report.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::StixFormatter};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// let bundle = StixFormatter::bundle(&report);
+    /// println!("{} objects", bundle["objects"].as_array().unwrap().len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bundle(report: &AuditReport) -> Value {
+        let host = report.host_id();
+        let time = report
+            .timestamp
+            .to_rfc3339_opts(SecondsFormat::Millis, true);
+        let sdo_namespace = Uuid::new_v5(&Uuid::NAMESPACE_URL, SDO_NAMESPACE_NAME.as_bytes());
+        let sdo_id = |kind: &str| {
+            let name = format!("{}|{}|{}", host, time, kind);
+            format!(
+                "{}--{}",
+                kind,
+                Uuid::new_v5(&sdo_namespace, name.as_bytes())
+            )
+        };
+
+        let mut observables: Vec<Value> = Vec::new();
+        let mut seen = BTreeSet::new();
+        let mut observe = |object: Value| {
+            if seen.insert(object["id"].as_str().unwrap_or_default().to_string()) {
+                observables.push(object);
+            }
+        };
+
+        let system = &report.system;
+        observe(software(
+            &system.os_name,
+            Some(&system.os_version),
+            Some("Microsoft"),
+        ));
+        for sw in &report.software {
+            observe(software(
+                &sw.name,
+                sw.version.as_deref(),
+                sw.vendor.as_deref(),
+            ));
+        }
+        for sw in &report.industrial {
+            observe(software(
+                &sw.product,
+                sw.version.as_deref(),
+                Some(&sw.vendor),
+            ));
+        }
+        // One entry per address; `observe` drops the repeated MACs.
+        for iface in &system.network_interfaces {
+            let kind = match iface.ip_version {
+                IpVersion::IPv4 => "ipv4-addr",
+                IpVersion::IPv6 => "ipv6-addr",
+            };
+            observe(observable(kind, &[("value", iface.ip_address.clone())]));
+            if let Some(mac) = &iface.mac_address {
+                observe(observable(
+                    "mac-addr",
+                    &[("value", mac.to_lowercase().replace('-', ":"))],
+                ));
+            }
+        }
+
+        let infrastructure_id = sdo_id("infrastructure");
+        let observed_id = sdo_id("observed-data");
+        let infrastructure_type = if report.industrial.is_empty() {
+            "workstation"
+        } else {
+            "control-system"
+        };
+        let mut description = format!(
+            "{} {} ({} programs, {} industrial products)",
+            system.os_name,
+            system.os_version,
+            report.software.len(),
+            report.industrial.len()
+        );
+        if let Some(asset_id) = &report.asset_id {
+            description.push_str(&format!(", asset id {}", asset_id));
+        }
+
+        let mut objects = vec![
+            json!({
+                "type": "infrastructure",
+                "spec_version": "2.1",
+                "id": infrastructure_id,
+                "created": time,
+                "modified": time,
+                "name": host,
+                "description": description,
+                "infrastructure_types": [infrastructure_type],
+            }),
+            json!({
+                "type": "observed-data",
+                "spec_version": "2.1",
+                "id": observed_id,
+                "created": time,
+                "modified": time,
+                "first_observed": time,
+                "last_observed": time,
+                "number_observed": 1,
+                "object_refs": observables.iter().map(|o| o["id"].clone()).collect::<Vec<_>>(),
+            }),
+            json!({
+                "type": "relationship",
+                "spec_version": "2.1",
+                "id": sdo_id("relationship"),
+                "created": time,
+                "modified": time,
+                "relationship_type": "consists-of",
+                "source_ref": infrastructure_id,
+                "target_ref": observed_id,
+            }),
+        ];
+        objects.extend(observables);
+
+        json!({
+            "type": "bundle",
+            "id": sdo_id("bundle"),
+            "objects": objects,
+        })
+    }
+
+    /// Format a report as a pretty-printed STIX 2.1 bundle.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`] if serialization fails.
+    pub fn format(report: &AuditReport) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(&Self::bundle(report))?)
+    }
+}
+
+/// A `software` object; name, vendor and version make up its id.
+fn software(name: &str, version: Option<&str>, vendor: Option<&str>) -> Value {
+    let mut properties = vec![("name", name.to_string())];
+    if let Some(vendor) = vendor.filter(|v| !v.is_empty()) {
+        properties.push(("vendor", vendor.to_string()));
+    }
+    if let Some(version) = version.filter(|v| !v.is_empty()) {
+        properties.push(("version", version.to_string()));
+    }
+    observable("software", &properties)
+}
+
+/// A cyber-observable object with a deterministic id: a UUIDv5 of the
+/// canonical JSON of its id-contributing properties, all of which are
+/// passed here.
+fn observable(kind: &str, properties: &[(&str, String)]) -> Value {
+    // serde_json sorts object keys and writes no whitespace, which is the
+    // canonical form for these string-only objects
+    let contributing: Map<String, Value> = properties
+        .iter()
+        .map(|(key, value)| (key.to_string(), Value::from(value.as_str())))
+        .collect();
+    let canonical = Value::Object(contributing.clone()).to_string();
+
+    let mut object = Map::new();
+    object.insert("type".into(), json!(kind));
+    object.insert("spec_version".into(), json!("2.1"));
+    object.insert(
+        "id".into(),
+        json!(format!(
+            "{}--{}",
+            kind,
+            Uuid::new_v5(&SCO_NAMESPACE, canonical.as_bytes())
+        )),
+    );
+    object.extend(contributing);
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+    use sysaudit_common::{IndustrialSoftwareDto, NetworkInterfaceDto, SoftwareDto};
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            software: vec![
+                SoftwareDto {
+                    name: "7-Zip".into(),
+                    version: Some("23.01".into()),
+                    vendor: Some("Igor Pavlov".into()),
+                    install_date: None,
+                },
+                SoftwareDto {
+                    name: "7-Zip".into(),
+                    version: Some("23.01".into()),
+                    vendor: Some("Igor Pavlov".into()),
+                    install_date: None,
+                },
+            ],
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "ABB".into(),
+                product: "Automation Builder".into(),
+                version: None,
+                install_path: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    #[test]
+    fn test_software_id() {
+        // UUIDv5 of {"name":"Word","vendor":"Microsoft","version":"2002"}
+        let object = software("Word", Some("2002"), Some("Microsoft"));
+        assert_eq!(
+            object["id"],
+            "software--c616478f-891e-5ddb-bb5c-f6d497d2a513"
+        );
+        assert_eq!(object["vendor"], "Microsoft");
+    }
+
+    #[test]
+    fn test_bundle() {
+        let bundle = StixFormatter::bundle(&sample_report());
+        assert_eq!(bundle["type"], "bundle");
+        let objects = bundle["objects"].as_array().unwrap();
+        let types: Vec<&str> = objects
+            .iter()
+            .map(|o| o["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "infrastructure",
+                "observed-data",
+                "relationship",
+                "software",
+                "software",
+                "software"
+            ]
+        );
+
+        let infrastructure = &objects[0];
+        assert_eq!(infrastructure["name"], "TEST-PC");
+        assert_eq!(infrastructure["infrastructure_types"][0], "control-system");
+        assert_eq!(infrastructure["created"], "2024-01-01T00:00:00.000Z");
+        assert_eq!(objects[1]["object_refs"].as_array().unwrap().len(), 3);
+        assert_eq!(objects[2]["source_ref"], infrastructure["id"]);
+        assert_eq!(objects[2]["target_ref"], objects[1]["id"]);
+        assert_eq!(objects[5]["vendor"], "ABB");
+        assert!(objects[5].get("version").is_none());
+
+        // Same scan, same bundle
+        assert_eq!(StixFormatter::bundle(&sample_report()), bundle);
+    }
+
+    #[test]
+    fn test_network_observables() {
+        let mut report = sample_report();
+        let entry = |ip: &str, ip_version| NetworkInterfaceDto {
+            name: "Ethernet".into(),
+            ip_address: ip.into(),
+            ip_version,
+            mac_address: Some("00-1A-2B-3C-4D-5E".into()),
+        };
+        report.system.network_interfaces = vec![
+            entry("10.0.0.5", IpVersion::IPv4),
+            entry("fe80::1", IpVersion::IPv6),
+        ];
+
+        let bundle = StixFormatter::bundle(&report);
+        let objects = bundle["objects"].as_array().unwrap();
+        let value = |kind: &str| {
+            let matching: Vec<&Value> = objects.iter().filter(|o| o["type"] == kind).collect();
+            assert_eq!(matching.len(), 1, "{}", kind);
+            matching[0]["value"].clone()
+        };
+        assert_eq!(value("ipv4-addr"), "10.0.0.5");
+        assert_eq!(value("ipv6-addr"), "fe80::1");
+        assert_eq!(value("mac-addr"), "00:1a:2b:3c:4d:5e");
+    }
+}
@@ -0,0 +1,300 @@
+//! RFC 5424 syslog forwarding.
+//!
+//! Every finding, plus a per-scan summary, is sent as one syslog message.
+//! The finding id is used as MSGID and its attributes are carried as
+//! structured data under the `sysaudit@32473` SD-ID. UDP sends one message
+//! per datagram; TCP and TLS use octet-counting framing (RFC 6587 / 5425).
+
+use super::siem::records;
+use crate::{AuditReport, Error, Finding, Severity};
+use chrono::SecondsFormat;
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Default facility: `local0`.
+pub const DEFAULT_FACILITY: u8 = 16;
+
+/// SD-ID used for finding attributes.
+const SD_ID: &str = "sysaudit@32473";
+
+/// Application name written in every message.
+const APP_NAME: &str = "sysaudit";
+
+/// Connect and write timeout for stream transports.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Syslog transport protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyslogTransport {
+    /// UDP, one message per datagram (RFC 5426)
+    #[default]
+    Udp,
+    /// Plain TCP with octet-counting framing (RFC 6587)
+    Tcp,
+    /// TLS with octet-counting framing (RFC 5425)
+    #[cfg(feature = "syslog-tls")]
+    Tls,
+}
+
+impl SyslogTransport {
+    /// Parse a transport name (`udp`, `tcp`, `tls`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown or unsupported transport.
+    pub fn parse(name: &str) -> Result<Self, Error> {
+        match name.to_lowercase().as_str() {
+            "udp" => Ok(SyslogTransport::Udp),
+            "tcp" => Ok(SyslogTransport::Tcp),
+            #[cfg(feature = "syslog-tls")]
+            "tls" => Ok(SyslogTransport::Tls),
+            other => Err(Error::General(format!(
+                "unsupported syslog transport '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Sink forwarding findings to a syslog collector.
+#[derive(Debug, Clone)]
+pub struct SyslogSink {
+    address: String,
+    transport: SyslogTransport,
+    facility: u8,
+}
+
+impl SyslogSink {
+    /// Create a sink for a `host:port` collector address using UDP.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{Finding, LocalScanner, Scanner, output::{SyslogSink, SyslogTransport}};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// SyslogSink::new("syslog.plant.local:514")
+    ///     .transport(SyslogTransport::Tcp)
+    ///     .send(&report, &Finding::collect(&report))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(address: impl Into<String>) -> Self {
+        SyslogSink {
+            address: address.into(),
+            transport: SyslogTransport::default(),
+            facility: DEFAULT_FACILITY,
+        }
+    }
+
+    /// Set the transport protocol.
+    pub fn transport(mut self, transport: SyslogTransport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Set the syslog facility (0-23).
+    pub fn facility(mut self, facility: u8) -> Self {
+        self.facility = facility.min(23);
+        self
+    }
+
+    /// Send findings and a scan summary to the collector.
+    ///
+    /// Returns the number of messages sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the address cannot be resolved or the messages
+    /// cannot be delivered.
+    pub fn send(&self, report: &AuditReport, findings: &[Finding]) -> Result<usize, Error> {
+        let messages: Vec<String> = records(report, findings)
+            .map(|finding| self.format_message(report, &finding))
+            .collect();
+
+        let addr = self.resolve()?;
+        match self.transport {
+            SyslogTransport::Udp => {
+                let bind: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+                };
+                let socket = UdpSocket::bind(bind)?;
+                for message in &messages {
+                    socket.send_to(message.as_bytes(), addr)?;
+                }
+            }
+            SyslogTransport::Tcp => {
+                let mut stream = self.connect(addr)?;
+                write_framed(&mut stream, &messages)?;
+            }
+            #[cfg(feature = "syslog-tls")]
+            SyslogTransport::Tls => {
+                let domain = self
+                    .address
+                    .rsplit_once(':')
+                    .map_or(self.address.as_str(), |(host, _)| host)
+                    .trim_matches(['[', ']']);
+                let connector = native_tls::TlsConnector::new()?;
+                let mut stream = connector
+                    .connect(domain, self.connect(addr)?)
+                    .map_err(|e| {
+                        Error::General(format!("TLS handshake with {} failed: {}", domain, e))
+                    })?;
+                write_framed(&mut stream, &messages)?;
+            }
+        }
+
+        tracing::debug!(
+            address = %self.address,
+            count = messages.len(),
+            "Findings sent to syslog"
+        );
+        Ok(messages.len())
+    }
+
+    /// Render one RFC 5424 message.
+    fn format_message(&self, report: &AuditReport, finding: &Finding) -> String {
+        let pri = u16::from(self.facility) * 8 + u16::from(syslog_severity(finding.severity));
+
+        let mut sd = format!("[{} category=\"{}\"", SD_ID, sd_value(&finding.category));
+        for (key, value) in &finding.attributes {
+            sd.push_str(&format!(" {}=\"{}\"", sd_name(key), sd_value(value)));
+        }
+        sd.push(']');
+
+        format!(
+            "<{}>1 {} {} {} {} {} {} {}",
+            pri,
+            report
+                .timestamp
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            header_field(&report.system.host_name, 255),
+            APP_NAME,
+            std::process::id(),
+            header_field(&finding.id, 32),
+            sd,
+            finding.title
+        )
+    }
+
+    fn resolve(&self) -> Result<SocketAddr, Error> {
+        self.address.to_socket_addrs()?.next().ok_or_else(|| {
+            Error::General(format!("cannot resolve syslog address {}", self.address))
+        })
+    }
+
+    fn connect(&self, addr: SocketAddr) -> Result<TcpStream, Error> {
+        let stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Ok(stream)
+    }
+}
+
+/// Write messages with octet-counting framing.
+fn write_framed<W: Write>(out: &mut W, messages: &[String]) -> Result<(), Error> {
+    for message in messages {
+        write!(out, "{} {}", message.len(), message)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Map a finding severity to a syslog severity code.
+pub(super) fn syslog_severity(severity: Severity) -> u8 {
+    match severity {
+        Severity::Critical => 2,
+        Severity::High => 3,
+        Severity::Medium => 4,
+        Severity::Low => 5,
+        Severity::Info => 6,
+    }
+}
+
+/// Printable, space-free header field; `-` when empty.
+fn header_field(value: &str, max: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+/// SD-PARAM name: printable ASCII without `=`, space, `]` or `"`.
+fn sd_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        .take(32)
+        .collect()
+}
+
+/// Escape an SD-PARAM value.
+fn sd_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use chrono::{TimeZone, Utc};
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    #[test]
+    fn test_format_message() {
+        let finding = Finding {
+            id: "patches-none".into(),
+            category: "patching".into(),
+            severity: Severity::High,
+            title: "No installed Windows updates recorded".into(),
+            attributes: vec![("note".into(), "a \"b\" ]".into())],
+        };
+
+        let message = SyslogSink::new("localhost:514").format_message(&sample_report(), &finding);
+        assert!(message.starts_with("<131>1 2024-01-01T00:00:00.000Z TEST-PC sysaudit "));
+        assert!(message.contains(
+            " patches-none [sysaudit@32473 category=\"patching\" note=\"a \\\"b\\\" \\]\"] No installed"
+        ));
+    }
+
+    #[test]
+    fn test_octet_framing() {
+        let mut out = Vec::new();
+        write_framed(&mut out, &["<14>1 abc".to_string()]).unwrap();
+        assert_eq!(out, b"9 <14>1 abc");
+    }
+
+    #[test]
+    fn test_send_udp() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let addr = server.local_addr().unwrap().to_string();
+
+        let sent = SyslogSink::new(addr).send(&sample_report(), &[]).unwrap();
+        assert_eq!(sent, 1);
+
+        let mut buf = [0u8; 2048];
+        let len = server.recv(&mut buf).unwrap();
+        let message = String::from_utf8_lossy(&buf[..len]);
+        assert!(message.contains(" scan-summary "));
+    }
+}
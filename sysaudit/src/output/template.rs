@@ -0,0 +1,116 @@
+//! Template-based report rendering.
+//!
+//! Templates use Jinja2 syntax (via MiniJinja). The report's top-level
+//! fields are exposed directly, e.g. `{{ system.host_name }}` or
+//! `{% for sw in software %}`. Templates whose file name contains `.html`,
+//! `.htm` or `.xml` are HTML-escaped automatically.
+
+use crate::{AuditReport, Error};
+use minijinja::{AutoEscape, Environment};
+use std::path::Path;
+
+/// Renders audit reports through user-supplied templates.
+pub struct TemplateRenderer;
+
+impl TemplateRenderer {
+    /// Render a report using the template at `template_path`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::TemplateRenderer};
+    /// use std::path::Path;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// let html = TemplateRenderer::render(&report, Path::new("my_report.html.tera"))?;
+    /// std::fs::write("report.html", html)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the template cannot be read, parsed or rendered.
+    pub fn render(report: &AuditReport, template_path: &Path) -> Result<String, Error> {
+        let source = std::fs::read_to_string(template_path)?;
+        let name = template_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "report".to_string());
+
+        Self::render_str(report, &name, &source)
+    }
+
+    /// Render a report using an in-memory template.
+    ///
+    /// `name` is used in error messages and to pick the escaping mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the template cannot be parsed or rendered.
+    pub fn render_str(report: &AuditReport, name: &str, source: &str) -> Result<String, Error> {
+        let mut env = Environment::new();
+        env.set_auto_escape_callback(auto_escape_for);
+        env.add_template(name, source)?;
+        let rendered = env.get_template(name)?.render(report)?;
+        Ok(rendered)
+    }
+}
+
+/// Pick the escaping mode from the template file name.
+fn auto_escape_for(name: &str) -> AutoEscape {
+    let name = name.to_lowercase();
+    if name.contains(".html") || name.contains(".htm") || name.contains(".xml") {
+        AutoEscape::Html
+    } else {
+        AutoEscape::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use sysaudit_common::SoftwareDto;
+
+    fn sample_report() -> AuditReport {
+        AuditReport {
+            software: vec![SoftwareDto {
+                name: "AT&T <Tools>".into(),
+                version: Some("1.0".into()),
+                vendor: None,
+                install_date: None,
+            }],
+            ..AuditReport::empty(test_system())
+        }
+    }
+
+    #[test]
+    fn test_render_text_template() {
+        let output = TemplateRenderer::render_str(
+            &sample_report(),
+            "report.txt",
+            "{{ system.host_name }}: {{ software | length }} packages",
+        )
+        .unwrap();
+        assert_eq!(output, "TEST-PC: 1 packages");
+    }
+
+    #[test]
+    fn test_render_html_template_escapes() {
+        let output = TemplateRenderer::render_str(
+            &sample_report(),
+            "report.html.tera",
+            "{% for sw in software %}<li>{{ sw.name }}</li>{% endfor %}",
+        )
+        .unwrap();
+        assert_eq!(output, "<li>AT&amp;T &lt;Tools&gt;</li>");
+    }
+
+    #[test]
+    fn test_render_invalid_template() {
+        let result = TemplateRenderer::render_str(&sample_report(), "bad.txt", "{% for %}");
+        assert!(result.is_err());
+    }
+}
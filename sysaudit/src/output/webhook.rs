@@ -0,0 +1,162 @@
+//! Change alerts posted to an HTTP webhook.
+
+use crate::Error;
+use crate::report::ReportDiff;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Default number of retries after the first attempt.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Request timeout for webhook calls.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay before the first retry; doubled on each further attempt (capped at 64x).
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Sink posting report changes as JSON to a webhook URL.
+///
+/// The body is `{"event": "sysaudit.inventory_changed", "host": ..., "summary": ..., "diff": {...}}`,
+/// where `summary` is a one-line description suitable for chat
+/// integrations and `diff` the full [`ReportDiff`].
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    retries: u32,
+}
+
+impl WebhookSink {
+    /// Create a sink for a webhook URL.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::AuditReport;
+    /// use sysaudit::output::WebhookSink;
+    /// use sysaudit::report::diff;
+    ///
+    /// # fn example(old: AuditReport, new: AuditReport) -> Result<(), sysaudit::Error> {
+    /// let changes = diff(&old, &new);
+    /// if changes.inventory_changed() {
+    ///     WebhookSink::new("https://hooks.example.com/sysaudit").send(&changes)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(url: impl Into<String>) -> Self {
+        WebhookSink {
+            url: url.into(),
+            retries: DEFAULT_RETRIES,
+        }
+    }
+
+    /// Set how many times a failed request is retried.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Post the changes to the webhook.
+    ///
+    /// Connection failures, `429` and `5xx` responses are retried with
+    /// exponential backoff.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Http`] if the webhook stays unreachable, or
+    /// [`Error::General`] if it rejects the alert.
+    pub fn send(&self, diff: &ReportDiff) -> Result<(), Error> {
+        let body = serde_json::to_vec(&payload(diff)?)?;
+        let client = Client::builder().timeout(TIMEOUT).build()?;
+        let mut attempt = 0;
+        loop {
+            let request = client
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .body(body.clone());
+
+            let retry = match request.send() {
+                Ok(response) => {
+                    let status = response.status();
+                    if status.is_success() {
+                        break;
+                    }
+                    let text = response.text().unwrap_or_default();
+                    if !is_retryable(status) || attempt >= self.retries {
+                        return Err(Error::General(format!(
+                            "webhook {} rejected the alert with {}: {}",
+                            self.url, status, text
+                        )));
+                    }
+                    format!("HTTP {}", status)
+                }
+                Err(e) if attempt >= self.retries => return Err(e.into()),
+                Err(e) => e.to_string(),
+            };
+
+            let delay = RETRY_BASE_DELAY * 2u32.pow(attempt.min(6));
+            attempt += 1;
+            tracing::warn!(attempt, reason = %retry, delay = ?delay, "Retrying webhook request");
+            std::thread::sleep(delay);
+        }
+
+        tracing::debug!(url = %self.url, "Change alert sent to webhook");
+        Ok(())
+    }
+}
+
+/// Alert body for a set of changes.
+fn payload(diff: &ReportDiff) -> Result<Value, Error> {
+    Ok(json!({
+        "event": "sysaudit.inventory_changed",
+        "host": diff.host,
+        "summary": format!(
+            "{}: {} software and {} industrial change(s)",
+            diff.host,
+            diff.software.len(),
+            diff.industrial.len()
+        ),
+        "diff": serde_json::to_value(diff)?,
+    }))
+}
+
+/// Whether a response status is worth retrying.
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{ChangeKind, ItemChange};
+    use chrono::Utc;
+
+    #[test]
+    fn test_payload() {
+        let diff = ReportDiff {
+            host: "SCADA01".into(),
+            old_timestamp: Utc::now(),
+            new_timestamp: Utc::now(),
+            system: vec![],
+            software: vec![],
+            industrial: vec![ItemChange {
+                kind: ChangeKind::Changed,
+                name: "AVEVA Citect SCADA".into(),
+                old_version: Some("8.1".into()),
+                new_version: Some("8.2".into()),
+            }],
+            updates: vec![],
+        };
+
+        let body = payload(&diff).unwrap();
+        assert_eq!(body["event"], "sysaudit.inventory_changed");
+        assert_eq!(body["host"], "SCADA01");
+        assert_eq!(
+            body["summary"],
+            "SCADA01: 0 software and 1 industrial change(s)"
+        );
+        assert_eq!(body["diff"]["industrial"][0]["new_version"], "8.2");
+    }
+}
@@ -0,0 +1,234 @@
+//! Excel workbook export.
+
+use crate::{AuditReport, Error};
+use rust_xlsxwriter::{Format, Workbook};
+use std::path::Path;
+
+/// Excel exporter for audit reports.
+pub struct XlsxExporter;
+
+impl XlsxExporter {
+    /// Export a full report to an `.xlsx` workbook.
+    ///
+    /// Writes one worksheet per section (System, Network, Software,
+    /// Industrial, Updates), each with a frozen header row and autofilter.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, output::XlsxExporter};
+    /// use std::path::Path;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// XlsxExporter::export_report(&report, Path::new("audit.xlsx"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the workbook cannot be built or written.
+    pub fn export_report(report: &AuditReport, path: &Path) -> Result<(), Error> {
+        let mut workbook = Workbook::new();
+
+        let system = &report.system;
+        let system_rows = vec![
+            vec!["Host Name".to_string(), system.host_name.clone()],
+            vec!["OS Name".to_string(), system.os_name.clone()],
+            vec!["OS Version".to_string(), system.os_version.clone()],
+            vec!["CPU".to_string(), system.cpu_info.clone()],
+            vec![
+                "CPU Physical Cores".to_string(),
+                system
+                    .cpu_physical_cores
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+            ],
+            vec![
+                "Memory Total (bytes)".to_string(),
+                system.memory_total_bytes.to_string(),
+            ],
+            vec![
+                "Memory Used (bytes)".to_string(),
+                system.memory_used_bytes.to_string(),
+            ],
+            vec![
+                "Manufacturer".to_string(),
+                system.manufacturer.clone().unwrap_or_default(),
+            ],
+            vec![
+                "Model".to_string(),
+                system.model.clone().unwrap_or_default(),
+            ],
+            vec!["Scan Timestamp".to_string(), report.timestamp.to_rfc3339()],
+        ];
+        let system_rows: Vec<Vec<String>> = system_rows
+            .into_iter()
+            .chain(
+                report
+                    .metadata
+                    .iter()
+                    .flat_map(|metadata| metadata.fields())
+                    .map(|(field, value)| vec![field.to_string(), value]),
+            )
+            .collect();
+        write_sheet(&mut workbook, "System", &["Field", "Value"], &system_rows)?;
+
+        let network_rows: Vec<Vec<String>> = system
+            .network_interfaces
+            .iter()
+            .map(|iface| {
+                vec![
+                    iface.name.clone(),
+                    iface.ip_address.clone(),
+                    format!("{:?}", iface.ip_version),
+                    iface.mac_address.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        write_sheet(
+            &mut workbook,
+            "Network",
+            &["Interface", "IP Address", "IP Version", "MAC"],
+            &network_rows,
+        )?;
+
+        let software_rows: Vec<Vec<String>> = report
+            .software
+            .iter()
+            .map(|sw| {
+                vec![
+                    sw.name.clone(),
+                    sw.version.clone().unwrap_or_default(),
+                    sw.vendor.clone().unwrap_or_default(),
+                    sw.install_date
+                        .map(|d| d.date_naive().to_string())
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect();
+        write_sheet(
+            &mut workbook,
+            "Software",
+            &["Name", "Version", "Publisher", "Install Date"],
+            &software_rows,
+        )?;
+
+        let industrial_rows: Vec<Vec<String>> = report
+            .industrial
+            .iter()
+            .map(|sw| {
+                vec![
+                    sw.vendor.clone(),
+                    sw.product.clone(),
+                    sw.version.clone().unwrap_or_default(),
+                    sw.install_path
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect();
+        write_sheet(
+            &mut workbook,
+            "Industrial",
+            &["Vendor", "Product", "Version", "Install Path"],
+            &industrial_rows,
+        )?;
+
+        let update_rows: Vec<Vec<String>> = report
+            .updates
+            .iter()
+            .map(|update| {
+                vec![
+                    update.hotfix_id.clone(),
+                    update.description.clone().unwrap_or_default(),
+                    update
+                        .installed_on
+                        .map(|d| d.to_string())
+                        .unwrap_or_default(),
+                    update.installed_by.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        write_sheet(
+            &mut workbook,
+            "Updates",
+            &["HotFix ID", "Description", "Installed On", "Installed By"],
+            &update_rows,
+        )?;
+
+        workbook.save(path)?;
+        Ok(())
+    }
+}
+
+/// Add a worksheet with a bold, frozen, filterable header row.
+fn write_sheet(
+    workbook: &mut Workbook,
+    name: &str,
+    headers: &[&str],
+    rows: &[Vec<String>],
+) -> Result<(), Error> {
+    let header_format = Format::new().set_bold();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(name)?;
+
+    for (col, header) in headers.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
+
+    for (row, values) in rows.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            worksheet.write_string(row as u32 + 1, col as u16, value)?;
+        }
+    }
+
+    let last_col = headers.len().saturating_sub(1) as u16;
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofilter(0, 0, rows.len() as u32, last_col)?;
+    worksheet.autofit();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WindowsUpdate;
+    use crate::report::test_system;
+    use chrono::NaiveDate;
+    use std::path::PathBuf;
+    use sysaudit_common::SoftwareDto;
+
+    fn temp_xlsx(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sysaudit_test_{}.xlsx", name))
+    }
+
+    #[test]
+    fn test_export_report_xlsx() {
+        let path = temp_xlsx("report");
+        let report = AuditReport {
+            software: vec![SoftwareDto {
+                name: "TestApp".into(),
+                version: Some("1.0".into()),
+                vendor: Some("Acme".into()),
+                install_date: None,
+            }],
+            updates: vec![WindowsUpdate {
+                hotfix_id: "KB5034441".into(),
+                description: Some("Security Update".into()),
+                installed_on: NaiveDate::from_ymd_opt(2024, 1, 15),
+                installed_by: None,
+            }],
+            ..AuditReport::empty(test_system())
+        };
+
+        XlsxExporter::export_report(&report, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        // xlsx files are ZIP containers
+        assert!(bytes.starts_with(b"PK"));
+        std::fs::remove_file(&path).ok();
+    }
+}
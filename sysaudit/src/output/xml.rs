@@ -0,0 +1,562 @@
+//! XML output formatting.
+//!
+//! Documents follow a fixed schema so CMDB importers can rely on it. Every
+//! element listed below is always emitted; unknown values are written as
+//! empty elements. New elements are only ever appended, and any breaking
+//! change bumps `schemaVersion`.
+//!
+//! ```text
+//! <sysaudit schemaVersion="2">
+//!   <system>
+//!     <computerName/> <domain/> <osName/> <osVersion/> <buildNumber/>
+//!     <manufacturer/> <model/> <cpu/> <cpuCoresPhysical/> <cpuCoresLogical/>
+//!     <cpuFrequencyMhz/> <memoryTotalBytes/> <memoryUsedBytes/> <memoryFreeBytes/>
+//!     <networkInterfaces>
+//!       <interface>
+//!         <name/> <ipAddress/> <prefix/> <gateway/> <macAddress/> <additionalAddress/>*
+//!       </interface>*
+//!     </networkInterfaces>
+//!     <fqdn/>
+//!   </system>
+//!   <software count="N">
+//!     <package> <name/> <version/> <publisher/> <installDate/> <installLocation/> <source/> </package>*
+//!   </software>
+//!   <industrial count="N">
+//!     <application> <vendor/> <product/> <version/> <installPath/> </application>*
+//!   </industrial>
+//!   <updates count="N">
+//!     <update> <hotfixId/> <description/> <installedOn/> <installedBy/> </update>*
+//!   </updates>
+//! </sysaudit>
+//! ```
+//!
+//! `<additionalAddress>` holds each further address of an interface as
+//! `address/prefix`. Version 2 added it and `<fqdn>`.
+//!
+//! Single-section commands emit the `<sysaudit>` root with only that section.
+//! Dates use `YYYY-MM-DD`.
+//!
+//! Full reports ([`XmlFormatter::format_report`]) fill the sections above
+//! from the report, leaving elements it does not carry empty, and append
+//! every other report field after `<updates>`, in alphabetical order of its
+//! JSON name: objects become elements named after their camelCased keys,
+//! array entries `<item>` elements, and keys that are not XML names
+//! `<entry key="..."/>` elements.
+
+use crate::{AuditReport, IndustrialSoftware, Software, SystemInfo, WindowsUpdate};
+use serde_json::Value;
+use sysaudit_common::{IndustrialSoftwareDto, SoftwareDto, SystemInfoDto};
+
+/// Current XML schema version.
+pub const XML_SCHEMA_VERSION: u32 = 2;
+
+/// XML formatter following the documented `sysaudit` schema.
+pub struct XmlFormatter;
+
+impl XmlFormatter {
+    /// Format system info as an XML document.
+    pub fn format_system_info(info: &SystemInfo) -> String {
+        document(|out| write_system(out, info))
+    }
+
+    /// Format software list as an XML document.
+    pub fn format_software(software: &[Software]) -> String {
+        document(|out| write_software(out, software))
+    }
+
+    /// Format industrial software as an XML document.
+    pub fn format_industrial(software: &[IndustrialSoftware]) -> String {
+        document(|out| write_industrial(out, software))
+    }
+
+    /// Format Windows updates as an XML document.
+    pub fn format_updates(updates: &[WindowsUpdate]) -> String {
+        document(|out| write_updates(out, updates))
+    }
+
+    /// Format a full audit as a single XML document.
+    pub fn format_audit(
+        info: &SystemInfo,
+        software: &[Software],
+        industrial: &[IndustrialSoftware],
+        updates: &[WindowsUpdate],
+    ) -> String {
+        document(|out| {
+            write_system(out, info);
+            write_software(out, software);
+            write_industrial(out, industrial);
+            write_updates(out, updates);
+        })
+    }
+
+    /// Format a full [`AuditReport`], including every section beyond the
+    /// fixed schema, as a single XML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::Json`] if the report cannot be serialized.
+    pub fn format_report(report: &AuditReport) -> Result<String, crate::Error> {
+        let Value::Object(fields) = serde_json::to_value(report)? else {
+            return Err(crate::Error::General("report is not an object".to_string()));
+        };
+        Ok(document(|out| {
+            write_report_system(out, report);
+            write_software_dto(out, &report.software);
+            write_industrial_dto(out, &report.industrial);
+            write_updates(out, &report.updates);
+            for (key, value) in &fields {
+                if !matches!(
+                    key.as_str(),
+                    "system" | "software" | "industrial" | "updates"
+                ) {
+                    write_value(out, 1, key, value);
+                }
+            }
+        }))
+    }
+}
+
+fn document(body: impl FnOnce(&mut String)) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<sysaudit schemaVersion=\"{}\">\n",
+        XML_SCHEMA_VERSION
+    ));
+    body(&mut out);
+    out.push_str("</sysaudit>\n");
+    out
+}
+
+fn write_system(out: &mut String, info: &SystemInfo) {
+    out.push_str("  <system>\n");
+    element(out, 2, "computerName", Some(&info.computer_name));
+    element(out, 2, "domain", info.domain.as_deref());
+    element(out, 2, "osName", Some(&info.os_name));
+    element(out, 2, "osVersion", Some(&info.os_version));
+    element(out, 2, "buildNumber", Some(&info.build_number));
+    element(out, 2, "manufacturer", info.manufacturer.as_deref());
+    element(out, 2, "model", info.model.as_deref());
+    element(out, 2, "cpu", Some(&info.cpu_info));
+    element(
+        out,
+        2,
+        "cpuCoresPhysical",
+        info.cpu_cores_physical.map(|c| c.to_string()).as_deref(),
+    );
+    element(
+        out,
+        2,
+        "cpuCoresLogical",
+        info.cpu_cores_logical.map(|c| c.to_string()).as_deref(),
+    );
+    element(
+        out,
+        2,
+        "cpuFrequencyMhz",
+        Some(&info.cpu_frequency_mhz.to_string()),
+    );
+    element(
+        out,
+        2,
+        "memoryTotalBytes",
+        Some(&info.memory_total.to_string()),
+    );
+    element(
+        out,
+        2,
+        "memoryUsedBytes",
+        Some(&info.memory_used.to_string()),
+    );
+    element(
+        out,
+        2,
+        "memoryFreeBytes",
+        Some(&info.memory_free.to_string()),
+    );
+
+    out.push_str("    <networkInterfaces>\n");
+    for iface in &info.network_interfaces {
+        out.push_str("      <interface>\n");
+        element(out, 4, "name", Some(&iface.name));
+        element(out, 4, "ipAddress", Some(&iface.ip_address.to_string()));
+        element(out, 4, "prefix", iface.subnet_mask.as_deref());
+        element(out, 4, "gateway", iface.gateway.as_deref());
+        element(out, 4, "macAddress", iface.mac_address.as_deref());
+        for address in &iface.additional_addresses {
+            element(out, 4, "additionalAddress", Some(&address.to_string()));
+        }
+        out.push_str("      </interface>\n");
+    }
+    out.push_str("    </networkInterfaces>\n");
+    element(out, 2, "fqdn", info.identity.fqdn.as_deref());
+    out.push_str("  </system>\n");
+}
+
+/// `<system>` from a report's DTO; elements the DTO lacks stay empty.
+fn write_report_system(out: &mut String, report: &AuditReport) {
+    let info: &SystemInfoDto = &report.system;
+    out.push_str("  <system>\n");
+    element(out, 2, "computerName", Some(&info.host_name));
+    element(out, 2, "domain", None);
+    element(out, 2, "osName", Some(&info.os_name));
+    element(out, 2, "osVersion", Some(&info.os_version));
+    element(out, 2, "buildNumber", report.os_build.as_deref());
+    element(out, 2, "manufacturer", info.manufacturer.as_deref());
+    element(out, 2, "model", info.model.as_deref());
+    element(out, 2, "cpu", Some(&info.cpu_info));
+    element(
+        out,
+        2,
+        "cpuCoresPhysical",
+        info.cpu_physical_cores.map(|c| c.to_string()).as_deref(),
+    );
+    element(out, 2, "cpuCoresLogical", None);
+    element(out, 2, "cpuFrequencyMhz", None);
+    element(
+        out,
+        2,
+        "memoryTotalBytes",
+        Some(&info.memory_total_bytes.to_string()),
+    );
+    element(
+        out,
+        2,
+        "memoryUsedBytes",
+        Some(&info.memory_used_bytes.to_string()),
+    );
+    element(
+        out,
+        2,
+        "memoryFreeBytes",
+        Some(
+            &info
+                .memory_total_bytes
+                .saturating_sub(info.memory_used_bytes)
+                .to_string(),
+        ),
+    );
+
+    out.push_str("    <networkInterfaces>\n");
+    for iface in &info.network_interfaces {
+        out.push_str("      <interface>\n");
+        element(out, 4, "name", Some(&iface.name));
+        element(out, 4, "ipAddress", Some(&iface.ip_address));
+        element(out, 4, "prefix", None);
+        element(out, 4, "gateway", None);
+        element(out, 4, "macAddress", iface.mac_address.as_deref());
+        out.push_str("      </interface>\n");
+    }
+    out.push_str("    </networkInterfaces>\n");
+    element(
+        out,
+        2,
+        "fqdn",
+        report.identity.as_ref().and_then(|i| i.fqdn.as_deref()),
+    );
+    out.push_str("  </system>\n");
+}
+
+fn write_software(out: &mut String, software: &[Software]) {
+    out.push_str(&format!("  <software count=\"{}\">\n", software.len()));
+    for sw in software {
+        out.push_str("    <package>\n");
+        element(out, 3, "name", Some(&sw.name));
+        element(out, 3, "version", sw.version.as_deref());
+        element(out, 3, "publisher", sw.publisher.as_deref());
+        element(
+            out,
+            3,
+            "installDate",
+            sw.install_date.map(|d| d.to_string()).as_deref(),
+        );
+        element(
+            out,
+            3,
+            "installLocation",
+            sw.install_location
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .as_deref(),
+        );
+        element(out, 3, "source", Some(&sw.source.to_string()));
+        out.push_str("    </package>\n");
+    }
+    out.push_str("  </software>\n");
+}
+
+fn write_industrial(out: &mut String, software: &[IndustrialSoftware]) {
+    out.push_str(&format!("  <industrial count=\"{}\">\n", software.len()));
+    for sw in software {
+        out.push_str("    <application>\n");
+        element(out, 3, "vendor", Some(&sw.vendor.to_string()));
+        element(out, 3, "product", Some(&sw.product));
+        element(out, 3, "version", sw.version.as_deref());
+        element(
+            out,
+            3,
+            "installPath",
+            sw.install_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .as_deref(),
+        );
+        out.push_str("    </application>\n");
+    }
+    out.push_str("  </industrial>\n");
+}
+
+fn write_software_dto(out: &mut String, software: &[SoftwareDto]) {
+    out.push_str(&format!("  <software count=\"{}\">\n", software.len()));
+    for sw in software {
+        out.push_str("    <package>\n");
+        element(out, 3, "name", Some(&sw.name));
+        element(out, 3, "version", sw.version.as_deref());
+        element(out, 3, "publisher", sw.vendor.as_deref());
+        element(
+            out,
+            3,
+            "installDate",
+            sw.install_date
+                .map(|d| d.date_naive().to_string())
+                .as_deref(),
+        );
+        element(out, 3, "installLocation", None);
+        element(out, 3, "source", None);
+        out.push_str("    </package>\n");
+    }
+    out.push_str("  </software>\n");
+}
+
+fn write_industrial_dto(out: &mut String, software: &[IndustrialSoftwareDto]) {
+    out.push_str(&format!("  <industrial count=\"{}\">\n", software.len()));
+    for sw in software {
+        out.push_str("    <application>\n");
+        element(out, 3, "vendor", Some(&sw.vendor));
+        element(out, 3, "product", Some(&sw.product));
+        element(out, 3, "version", sw.version.as_deref());
+        element(
+            out,
+            3,
+            "installPath",
+            sw.install_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .as_deref(),
+        );
+        out.push_str("    </application>\n");
+    }
+    out.push_str("  </industrial>\n");
+}
+
+fn write_updates(out: &mut String, updates: &[WindowsUpdate]) {
+    out.push_str(&format!("  <updates count=\"{}\">\n", updates.len()));
+    for update in updates {
+        out.push_str("    <update>\n");
+        element(out, 3, "hotfixId", Some(&update.hotfix_id));
+        element(out, 3, "description", update.description.as_deref());
+        element(
+            out,
+            3,
+            "installedOn",
+            update.installed_on.map(|d| d.to_string()).as_deref(),
+        );
+        element(out, 3, "installedBy", update.installed_by.as_deref());
+        out.push_str("    </update>\n");
+    }
+    out.push_str("  </updates>\n");
+}
+
+/// Write a JSON value as an element named after `key`.
+fn write_value(out: &mut String, depth: usize, key: &str, value: &Value) {
+    let indent = "  ".repeat(depth);
+    let name = camel_case(key);
+    let (open, close) = if is_xml_name(&name) {
+        (name.clone(), name)
+    } else {
+        (
+            format!("entry key=\"{}\"", escape(key)),
+            "entry".to_string(),
+        )
+    };
+    match value {
+        Value::Null => out.push_str(&format!("{}<{}/>\n", indent, open)),
+        Value::Object(fields) => {
+            out.push_str(&format!("{}<{}>\n", indent, open));
+            for (key, value) in fields {
+                write_value(out, depth + 1, key, value);
+            }
+            out.push_str(&format!("{}</{}>\n", indent, close));
+        }
+        Value::Array(items) => {
+            out.push_str(&format!("{}<{}>\n", indent, open));
+            for item in items {
+                write_value(out, depth + 1, "item", item);
+            }
+            out.push_str(&format!("{}</{}>\n", indent, close));
+        }
+        Value::String(text) if text.is_empty() => out.push_str(&format!("{}<{}/>\n", indent, open)),
+        Value::String(text) => out.push_str(&format!(
+            "{}<{}>{}</{}>\n",
+            indent,
+            open,
+            escape(text),
+            close
+        )),
+        other => out.push_str(&format!("{}<{}>{}</{}>\n", indent, open, other, close)),
+    }
+}
+
+/// `snake_case` to `camelCase`.
+fn camel_case(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !name.is_empty() {
+            upper = true;
+        } else if upper {
+            name.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Whether `name` can be used as an element name as is.
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !name.to_ascii_lowercase().starts_with("xml")
+}
+
+/// Write a single text element at the given nesting depth.
+fn element(out: &mut String, depth: usize, name: &str, value: Option<&str>) {
+    let indent = "  ".repeat(depth);
+    match value {
+        Some(v) if !v.is_empty() => {
+            out.push_str(&format!("{}<{}>{}</{}>\n", indent, name, escape(v), name));
+        }
+        _ => out.push_str(&format!("{}<{}/>\n", indent, name)),
+    }
+}
+
+/// Escape XML special characters and drop characters XML 1.0 cannot carry.
+pub(super) fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_system;
+    use crate::{RegistrySource, Vendor};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("AT&T <Tools>"), "AT&amp;T &lt;Tools&gt;");
+        assert_eq!(escape("a\u{0001}b"), "ab");
+    }
+
+    #[test]
+    fn test_format_software_xml() {
+        let sw = Software {
+            name: "Test App".to_string(),
+            version: Some("1.0.0".to_string()),
+            publisher: None,
+            install_date: NaiveDate::from_ymd_opt(2024, 1, 1),
+            install_date_source: crate::InstallDateSource::Exact,
+            install_location: None,
+            source: RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
+        };
+
+        let output = XmlFormatter::format_software(&[sw]);
+        assert!(output.starts_with("<?xml"));
+        assert!(output.contains("<sysaudit schemaVersion=\"2\">"));
+        assert!(output.contains("<software count=\"1\">"));
+        assert!(output.contains("<name>Test App</name>"));
+        assert!(output.contains("<publisher/>"));
+        assert!(output.contains("<installDate>2024-01-01</installDate>"));
+        assert!(output.trim_end().ends_with("</sysaudit>"));
+    }
+
+    #[test]
+    fn test_format_report_xml() {
+        let mut report = AuditReport::empty(SystemInfoDto {
+            memory_used_bytes: 400,
+            ..test_system()
+        });
+        report.software = vec![SoftwareDto {
+            name: "Test App".into(),
+            version: None,
+            vendor: Some("ACME".into()),
+            install_date: None,
+        }];
+        report.warnings = vec![crate::ScanWarning::new(
+            crate::Collector::Updates,
+            "access denied",
+        )];
+        report
+            .extensions
+            .insert("site info".into(), serde_json::json!({"line_no": 3}));
+        report.identity = Some(crate::HostIdentity {
+            fqdn: Some("test-pc.plant.local".into()),
+            ..Default::default()
+        });
+
+        let output = XmlFormatter::format_report(&report).unwrap();
+        assert!(output.contains("<computerName>TEST-PC</computerName>"));
+        assert!(output.contains("<memoryFreeBytes>600</memoryFreeBytes>"));
+        assert!(output.contains(
+            "    </networkInterfaces>\n    <fqdn>test-pc.plant.local</fqdn>\n  </system>"
+        ));
+        assert!(output.contains("<publisher>ACME</publisher>"));
+        assert!(output.contains("<updates count=\"0\">"));
+        assert!(output.contains(
+            "  <warnings>\n    <item>\n      <collector>updates</collector>\n      <message>access denied</message>"
+        ));
+        assert!(output.contains("<entry key=\"site info\">\n      <lineNo>3</lineNo>"));
+        assert!(output.find("<updates").unwrap() < output.find("<warnings>").unwrap());
+        assert!(output.trim_end().ends_with("</sysaudit>"));
+    }
+
+    #[test]
+    fn test_format_industrial_xml() {
+        let sw = IndustrialSoftware {
+            vendor: Vendor::SchneiderElectric,
+            product: "EcoStruxure".into(),
+            version: None,
+            install_path: None,
+            runtime_state: None,
+        };
+
+        let output = XmlFormatter::format_industrial(&[sw]);
+        assert!(output.contains("<vendor>Schneider Electric</vendor>"));
+        assert!(output.contains("<installPath/>"));
+    }
+
+    #[test]
+    fn test_format_updates_empty_xml() {
+        let output = XmlFormatter::format_updates(&[]);
+        assert!(output.contains("<updates count=\"0\">"));
+    }
+}
@@ -0,0 +1,50 @@
+//! YAML output formatting.
+
+use crate::Error;
+use serde::Serialize;
+
+/// YAML formatter for any serializable audit data.
+pub struct YamlFormatter;
+
+impl YamlFormatter {
+    /// Serialize audit data as a YAML document.
+    ///
+    /// Field names match the JSON output.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{SoftwareScanner, output::YamlFormatter};
+    ///
+    /// let software = SoftwareScanner::new().scan().unwrap();
+    /// println!("{}", YamlFormatter::format(&software).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the value cannot be serialized.
+    pub fn format<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+        Ok(serde_yaml::to_string(value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WindowsUpdate;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_format_updates_yaml() {
+        let updates = vec![WindowsUpdate {
+            hotfix_id: "KB5034441".into(),
+            description: Some("Security Update".into()),
+            installed_on: NaiveDate::from_ymd_opt(2024, 1, 15),
+            installed_by: None,
+        }];
+
+        let output = YamlFormatter::format(&updates).unwrap();
+        assert!(output.contains("hotfix_id: KB5034441"));
+        assert!(output.contains("installed_on: 2024-01-15"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Custom collectors for site-specific data.
+//!
+//! Implement [`CustomCollector`] and register it with
+//! [`LocalScanner::with_collector`](crate::LocalScanner::with_collector) to
+//! attach data the built-in collectors do not read, such as asset tags from
+//! a local file or vendor registry keys. Each collector's section lands in
+//! [`AuditReport::extensions`](crate::AuditReport::extensions) under its
+//! [`section`](CustomCollector::section) key; a collector that fails or
+//! panics leaves its section out and adds a warning.
+
+use crate::Error;
+use serde_json::Value;
+#[cfg(any(all(windows, feature = "local"), test))]
+use std::collections::BTreeMap;
+#[cfg(any(all(windows, feature = "local"), test))]
+use std::panic::AssertUnwindSafe;
+#[cfg(any(all(windows, feature = "local"), test))]
+use std::sync::Arc;
+
+/// A collector contributing one extension section to the report.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::Error;
+/// use sysaudit::plugin::CustomCollector;
+///
+/// struct AssetTag;
+///
+/// impl CustomCollector for AssetTag {
+///     fn name(&self) -> &str {
+///         "asset_tag"
+///     }
+///
+///     fn collect(&self) -> Result<serde_json::Value, Error> {
+///         let tag = std::fs::read_to_string(r"C:\ProgramData\Plant\asset-tag.txt")?;
+///         Ok(serde_json::json!({ "tag": tag.trim() }))
+///     }
+/// }
+/// ```
+pub trait CustomCollector: Send + Sync {
+    /// Name used in logs and warnings.
+    fn name(&self) -> &str;
+
+    /// Key of the section in the report's extensions (default: the name).
+    fn section(&self) -> &str {
+        self.name()
+    }
+
+    /// Collect the section. Runs on a blocking thread, so registry and
+    /// file reads are fine.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the data cannot be read; the scan continues
+    /// without the section.
+    fn collect(&self) -> Result<Value, Error>;
+}
+
+/// Run `collectors` one after another, returning their sections and a
+/// warning for each one that failed. A later collector replaces an earlier
+/// one's section with the same key.
+#[tracing::instrument(skip_all, fields(collectors = collectors.len()))]
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn collect_all(
+    collectors: &[Arc<dyn CustomCollector>],
+) -> (BTreeMap<String, Value>, Vec<String>) {
+    let mut sections = BTreeMap::new();
+    let mut warnings = Vec::new();
+    for collector in collectors {
+        // A buggy plugin must not take the whole scan down.
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| collector.collect()))
+            .unwrap_or_else(|_| Err(Error::General("collector panicked".to_string())));
+        match result {
+            Ok(value) => {
+                sections.insert(collector.section().to_string(), value);
+            }
+            Err(e) => {
+                tracing::warn!(collector = collector.name(), error = %e, "Custom collector failed");
+                warnings.push(format!("{}: {}", collector.name(), e));
+            }
+        }
+    }
+    (sections, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Fixed(&'static str, Result<Value, &'static str>);
+
+    impl CustomCollector for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn collect(&self) -> Result<Value, Error> {
+            self.1.clone().map_err(|e| Error::General(e.to_string()))
+        }
+    }
+
+    struct Panics;
+
+    impl CustomCollector for Panics {
+        fn name(&self) -> &str {
+            "panics"
+        }
+
+        fn collect(&self) -> Result<Value, Error> {
+            panic!("plugin bug")
+        }
+    }
+
+    #[test]
+    fn test_collect_all() {
+        let collectors: Vec<Arc<dyn CustomCollector>> = vec![
+            Arc::new(Fixed("asset_tag", Ok(json!({ "tag": "PLC-0042" })))),
+            Arc::new(Fixed("registry", Err("access denied"))),
+            Arc::new(Panics),
+        ];
+        let (sections, warnings) = collect_all(&collectors);
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections["asset_tag"]["tag"], "PLC-0042");
+        assert_eq!(
+            warnings,
+            vec![
+                "registry: access denied".to_string(),
+                "panics: collector panicked".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Administrator-rights awareness.
+//!
+//! Most of a scan works as a standard user, but a few reads need an
+//! elevated process and otherwise come back empty or partial. Scanners
+//! check [`is_elevated`] up front and record what an unprivileged scan
+//! misses as [`ScanWarning`]s, so thin data is not mistaken for a clean
+//! host.
+
+use crate::options::{Collector, Sections};
+use crate::report::ScanWarning;
+
+/// What each section misses without administrator rights.
+const ADMIN_ONLY: &[(Collector, &str)] = &[
+    (
+        Collector::Security,
+        "not running as administrator: BitLocker status (Win32_EncryptableVolume) cannot be read",
+    ),
+    (
+        Collector::Services,
+        "not running as administrator: binary paths and accounts of protected services may be missing",
+    ),
+    (
+        Collector::Autopilot,
+        "not running as administrator: the hardware hash (MDM_DevDetail_Ext01) cannot be read",
+    ),
+];
+
+/// Whether the current process runs elevated (as administrator with UAC
+/// on, or as SYSTEM).
+///
+/// # Example
+///
+/// ```no_run
+/// if !sysaudit::privileges::is_elevated().unwrap_or(false) {
+///     eprintln!("Run as administrator for a complete audit");
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`Error`](crate::Error) if the process token cannot be read.
+#[cfg(windows)]
+pub fn is_elevated() -> Result<bool, crate::Error> {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{
+        GetTokenInformation, TOKEN_ELEVATION, TOKEN_QUERY, TokenElevation,
+    };
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    let mut token = HANDLE::default();
+    // SAFETY: the pseudo handle of the current process needs no closing and
+    // `token` is a valid out pointer.
+    unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) }
+        .map_err(|e| crate::Error::General(format!("could not open the process token: {}", e)))?;
+
+    let mut elevation = TOKEN_ELEVATION::default();
+    let mut size = 0u32;
+    // SAFETY: the buffer is a TOKEN_ELEVATION of the size passed, as the
+    // TokenElevation class requires.
+    let result = unsafe {
+        GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut std::ffi::c_void),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        )
+    };
+    // SAFETY: `token` was opened above and is closed exactly once.
+    let _ = unsafe { CloseHandle(token) };
+
+    result.map_err(|e| crate::Error::General(format!("could not read token elevation: {}", e)))?;
+    Ok(elevation.TokenIsElevated != 0)
+}
+
+/// Warnings for the selected `sections` that are known to be incomplete
+/// without administrator rights.
+pub fn admin_warnings(sections: Sections) -> Vec<ScanWarning> {
+    ADMIN_ONLY
+        .iter()
+        .filter(|(collector, _)| match collector {
+            Collector::Security => sections.has_security(),
+            Collector::Services => sections.has_services(),
+            Collector::Autopilot => sections.has_autopilot(),
+            _ => true,
+        })
+        .map(|(collector, message)| ScanWarning::new(*collector, *message))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_warnings_follow_sections() {
+        assert!(admin_warnings(Sections::system_only()).is_empty());
+
+        let warnings = admin_warnings(Sections::system_only().security(true));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].collector, Collector::Security);
+        assert!(warnings[0].message.contains("BitLocker"));
+    }
+}
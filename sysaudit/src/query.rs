@@ -0,0 +1,107 @@
+//! JMESPath queries over audit reports.
+//!
+//! A [`ReportQuery`] extracts part of a report without external tools,
+//! e.g. the versions of one product:
+//!
+//! ```text
+//! software[?contains(name, 'Citect')].{name: name, version: version}
+//! updates[?installed_on > '2024-01-01'].hotfix_id
+//! system.os_version
+//! ```
+//!
+//! Field names are those of the JSON report (`sysaudit schema`).
+
+use crate::Error;
+use crate::report::AuditReport;
+use serde_json::Value;
+
+/// A compiled JMESPath expression.
+pub struct ReportQuery {
+    expression: jmespath::Expression<'static>,
+}
+
+impl ReportQuery {
+    /// Compile an expression.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::query::ReportQuery;
+    /// use sysaudit::{LocalScanner, Scanner};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = ReportQuery::new("software[?contains(name, 'Citect')].version")?;
+    /// let report = LocalScanner::new().audit().await?;
+    /// println!("{}", query.run(&report)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if the expression does not parse.
+    pub fn new(expression: &str) -> Result<Self, Error> {
+        let expression = jmespath::compile(expression)
+            .map_err(|e| Error::General(format!("invalid query: {}", e)))?;
+        Ok(ReportQuery { expression })
+    }
+
+    /// Run the expression against a report.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`] if the report cannot be serialized, or
+    /// [`Error::General`] if evaluation fails (e.g. a function applied
+    /// to the wrong type).
+    pub fn run(&self, report: &AuditReport) -> Result<Value, Error> {
+        self.run_json(serde_json::to_value(report)?)
+    }
+
+    /// Run the expression against any JSON document, such as a report
+    /// read without upgrading it.
+    ///
+    /// # Errors
+    ///
+    /// As [`run`](Self::run).
+    pub fn run_json(&self, document: Value) -> Result<Value, Error> {
+        let result = self
+            .expression
+            .search(document)
+            .map_err(|e| Error::General(format!("query failed: {}", e)))?;
+        Ok(serde_json::to_value(&*result)?)
+    }
+}
+
+impl std::fmt::Debug for ReportQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReportQuery")
+            .field("expression", &self.expression.as_str())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_report() {
+        let report =
+            AuditReport::from_json_versioned(include_str!("../tests/fixtures/report.json"))
+                .unwrap();
+
+        let versions = ReportQuery::new("software[?contains(name, 'Firefox')].version")
+            .unwrap()
+            .run(&report)
+            .unwrap();
+        assert_eq!(versions, serde_json::json!(["115.0.2"]));
+
+        let host = ReportQuery::new("system.host_name")
+            .unwrap()
+            .run(&report)
+            .unwrap();
+        assert_eq!(host, Value::String(report.system.host_name.clone()));
+
+        assert!(ReportQuery::new("software[?name ==").is_err());
+    }
+}
@@ -0,0 +1,552 @@
+//! Redaction of identifying data in reports.
+//!
+//! A [`RedactionPolicy`] hashes or drops user names, host names, MAC
+//! addresses and IP addresses, so reports from different sites can be
+//! pooled into benchmark datasets. Hashed values are salted and stable: the
+//! same host gets the same pseudonym in every report made with one salt,
+//! and hashed addresses are still valid addresses.
+
+use crate::report::AuditReport;
+use crate::{WindowsService, WindowsUpdate};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use sysaudit_common::SystemInfoDto;
+
+/// Placeholder for required text fields that were dropped.
+const REDACTED: &str = "redacted";
+
+/// Service accounts built into Windows; they identify no one and are kept.
+const BUILTIN_ACCOUNTS: &[&str] = &[
+    "localsystem",
+    "nt authority\\",
+    "nt service\\",
+    ".\\localsystem",
+];
+
+/// What to do with one kind of identifying value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Redaction {
+    /// Leave the value as collected
+    #[default]
+    Keep,
+    /// Replace it with a stable salted pseudonym
+    Hash,
+    /// Remove it, or blank it where the field is required
+    Drop,
+}
+
+/// Which identifying values to hash or drop from a report.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::{Redaction, RedactionPolicy, ScanOptions};
+///
+/// let policy = RedactionPolicy::hash_all()
+///     .salt("benchmark-2026")
+///     .mac_addresses(Redaction::Drop);
+/// let options = ScanOptions::new().redaction(policy);
+/// assert!(options.redaction_policy().is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    user_names: Redaction,
+    host_names: Redaction,
+    mac_addresses: Redaction,
+    ip_addresses: Redaction,
+    salt: String,
+}
+
+impl RedactionPolicy {
+    /// Policy keeping every value.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policy hashing user names, host names, MAC and IP addresses.
+    pub fn hash_all() -> Self {
+        Self::all(Redaction::Hash)
+    }
+
+    /// Policy dropping user names, host names, MAC and IP addresses.
+    pub fn drop_all() -> Self {
+        Self::all(Redaction::Drop)
+    }
+
+    fn all(redaction: Redaction) -> Self {
+        RedactionPolicy {
+            user_names: redaction,
+            host_names: redaction,
+            mac_addresses: redaction,
+            ip_addresses: redaction,
+            salt: String::new(),
+        }
+    }
+
+    /// Service accounts, update installers and profile folders in paths.
+    pub fn user_names(mut self, redaction: Redaction) -> Self {
+        self.user_names = redaction;
+        self
+    }
+
+    /// Computer and domain names.
+    pub fn host_names(mut self, redaction: Redaction) -> Self {
+        self.host_names = redaction;
+        self
+    }
+
+    /// Network adapter MAC addresses.
+    pub fn mac_addresses(mut self, redaction: Redaction) -> Self {
+        self.mac_addresses = redaction;
+        self
+    }
+
+    /// Interface addresses and gateways.
+    pub fn ip_addresses(mut self, redaction: Redaction) -> Self {
+        self.ip_addresses = redaction;
+        self
+    }
+
+    /// Salt mixed into hashed values; keep it secret, or short values such
+    /// as IPv4 addresses can be recovered by trying them all.
+    pub fn salt(mut self, salt: impl Into<String>) -> Self {
+        self.salt = salt.into();
+        self
+    }
+
+    /// Redact `report` in place.
+    pub fn apply(&self, report: &mut AuditReport) {
+        let host_name = report.system.host_name.clone();
+        self.apply_system(&mut report.system);
+        for update in &mut report.updates {
+            self.apply_update(update);
+        }
+        for service in &mut report.services {
+            self.apply_service(service);
+        }
+        for sw in &mut report.industrial {
+            if let Some(path) = &sw.install_path {
+                sw.install_path = Some(self.profile_path(&path.to_string_lossy()).into());
+            }
+        }
+        if let Some(exclusions) = report
+            .security
+            .as_mut()
+            .and_then(|s| s.defender_exclusions.as_mut())
+        {
+            for path in exclusions.paths.iter_mut().chain(&mut exclusions.processes) {
+                *path = self.profile_path(path);
+            }
+        }
+        if let Some(metadata) = &mut report.metadata {
+            metadata.user = self.account(metadata.user.take());
+        }
+        if let Some(identity) = &mut report.identity {
+            self.apply_identity(identity);
+        }
+        if let Some(health) = &mut report.update_health {
+            for service in [&mut health.windows_update, &mut health.bits]
+                .into_iter()
+                .flatten()
+            {
+                self.apply_service(service);
+            }
+            health.wsus_server = self.optional(Kind::Host, health.wsus_server.take());
+            if let Some(sccm) = &mut health.sccm {
+                sccm.management_point = self.optional(Kind::Host, sccm.management_point.take());
+            }
+        }
+        if let Some(logs) = &mut report.event_logs {
+            // Kept in number, so a redacted report still shows forwarding.
+            for manager in &mut logs.subscription_managers {
+                *manager = self.required(Kind::Host, manager);
+            }
+        }
+        if let Some(autopilot) = &mut report.autopilot {
+            autopilot.serial_number = self.optional(Kind::Host, autopilot.serial_number.take());
+            autopilot.hardware_hash = self.optional(Kind::Host, autopilot.hardware_hash.take());
+        }
+        if let Some(wsl) = report.containers.as_mut().and_then(|c| c.wsl.as_mut()) {
+            for distribution in &mut wsl.distributions {
+                distribution.base_path = distribution
+                    .base_path
+                    .take()
+                    .map(|path| self.profile_path(&path));
+                distribution.user_sid = self.optional(Kind::User, distribution.user_sid.take());
+            }
+        }
+        // Remote collectors name the host in their warnings.
+        if !host_name.is_empty() && host_name != report.system.host_name {
+            for warning in &mut report.warnings {
+                warning.message = warning
+                    .message
+                    .replace(&host_name, &report.system.host_name);
+            }
+        }
+    }
+
+    /// Redact a report's host section.
+    pub fn apply_system(&self, system: &mut SystemInfoDto) {
+        system.host_name = self.required(Kind::Host, &system.host_name);
+        for iface in &mut system.network_interfaces {
+            iface.ip_address = self.ip_text(&iface.ip_address);
+            iface.mac_address = self.mac(iface.mac_address.take());
+        }
+    }
+
+    /// Redact collected host information, as shown by the formatters.
+    pub fn apply_system_info(&self, system: &mut crate::SystemInfo) {
+        system.computer_name = self.required(Kind::Host, &system.computer_name);
+        system.domain = self.optional(Kind::Host, system.domain.take());
+        self.apply_identity(&mut system.identity);
+        for iface in &mut system.network_interfaces {
+            iface.ip_address = self.ip(iface.ip_address);
+            for address in &mut iface.additional_addresses {
+                address.ip_address = self.ip(address.ip_address);
+            }
+            iface.gateway = match (self.ip_addresses, iface.gateway.take()) {
+                (Redaction::Drop, _) | (_, None) => None,
+                (_, Some(gateway)) => Some(self.ip_text(&gateway)),
+            };
+            iface.mac_address = self.mac(iface.mac_address.take());
+        }
+        let fingerprint = &mut system.fingerprint;
+        fingerprint.machine_guid = self.optional(Kind::Host, fingerprint.machine_guid.take());
+        fingerprint.bios_serial = self.optional(Kind::Host, fingerprint.bios_serial.take());
+        fingerprint.mac_addresses = std::mem::take(&mut fingerprint.mac_addresses)
+            .into_iter()
+            .filter_map(|mac| self.mac(Some(mac)))
+            .collect();
+    }
+
+    /// Redact a host's DNS names, the addresses they resolve from and its
+    /// machine SID. Hashing keeps equal SIDs equal, so cloned hosts still
+    /// show up in fleet reports.
+    pub fn apply_identity(&self, identity: &mut crate::HostIdentity) {
+        identity.fqdn = self.optional(Kind::Host, identity.fqdn.take());
+        identity.dns_suffix = self.optional(Kind::Host, identity.dns_suffix.take());
+        identity.dns_search_list = std::mem::take(&mut identity.dns_search_list)
+            .into_iter()
+            .filter_map(|suffix| self.optional(Kind::Host, Some(suffix)))
+            .collect();
+        identity.reverse_dns = std::mem::take(&mut identity.reverse_dns)
+            .into_iter()
+            .filter_map(|mut entry| {
+                entry.name = self.optional(Kind::Host, Some(entry.name))?;
+                entry.ip_address = self.ip(entry.ip_address);
+                Some(entry)
+            })
+            .collect();
+        identity.machine_sid = self.optional(Kind::Host, identity.machine_sid.take());
+    }
+
+    /// Redact the profile folder in an installed program's location, as
+    /// shown by the formatters.
+    pub fn apply_software(&self, software: &mut crate::Software) {
+        if let Some(path) = &software.install_location {
+            software.install_location = Some(self.profile_path(&path.to_string_lossy()).into());
+        }
+    }
+
+    /// Redact the profile folder in detected industrial software's path,
+    /// as shown by the formatters.
+    pub fn apply_industrial(&self, software: &mut crate::IndustrialSoftware) {
+        if let Some(path) = &software.install_path {
+            software.install_path = Some(self.profile_path(&path.to_string_lossy()).into());
+        }
+    }
+
+    /// Redact who installed an update.
+    pub fn apply_update(&self, update: &mut WindowsUpdate) {
+        update.installed_by = self.account(update.installed_by.take());
+    }
+
+    /// Redact a service's account and the profile folder in its path.
+    pub fn apply_service(&self, service: &mut WindowsService) {
+        service.account = self.account(service.account.take());
+        if self.user_names != Redaction::Keep {
+            service.path = service.path.take().map(|path| self.profile_path(&path));
+        }
+    }
+
+    fn required(&self, kind: Kind, value: &str) -> String {
+        match self.redaction(kind) {
+            Redaction::Keep => value.to_string(),
+            Redaction::Hash => self.pseudonym(kind, value),
+            Redaction::Drop => REDACTED.to_string(),
+        }
+    }
+
+    fn optional(&self, kind: Kind, value: Option<String>) -> Option<String> {
+        let value = value?;
+        match self.redaction(kind) {
+            Redaction::Keep => Some(value),
+            Redaction::Hash => Some(self.pseudonym(kind, &value)),
+            Redaction::Drop => None,
+        }
+    }
+
+    fn account(&self, account: Option<String>) -> Option<String> {
+        let account = account?;
+        let lower = account.to_lowercase();
+        if BUILTIN_ACCOUNTS
+            .iter()
+            .any(|builtin| lower.starts_with(builtin))
+        {
+            return Some(account);
+        }
+        self.optional(Kind::User, Some(account))
+    }
+
+    /// `path` with the user folder under `\Users\` redacted.
+    fn profile_path(&self, path: &str) -> String {
+        let lower = path.to_ascii_lowercase();
+        let Some(start) = lower.find("\\users\\").map(|i| i + "\\users\\".len()) else {
+            return path.to_string();
+        };
+        let end = path[start..]
+            .find(['\\', '"'])
+            .map_or(path.len(), |i| start + i);
+        let user = &path[start..end];
+        if user.eq_ignore_ascii_case("public") || user.eq_ignore_ascii_case("default") {
+            return path.to_string();
+        }
+        format!(
+            "{}{}{}",
+            &path[..start],
+            self.required(Kind::User, user),
+            &path[end..]
+        )
+    }
+
+    fn mac(&self, mac: Option<String>) -> Option<String> {
+        let mac = mac?;
+        match self.mac_addresses {
+            Redaction::Keep => Some(mac),
+            Redaction::Drop => None,
+            Redaction::Hash => {
+                let digest = self.digest(Kind::Mac, &mac);
+                // Locally administered, so it cannot clash with a real vendor.
+                let octets = std::iter::once(0x02).chain(digest[..5].iter().copied());
+                Some(
+                    octets
+                        .map(|b| format!("{:02X}", b))
+                        .collect::<Vec<_>>()
+                        .join(":"),
+                )
+            }
+        }
+    }
+
+    fn ip_text(&self, ip: &str) -> String {
+        match ip.parse::<IpAddr>() {
+            Ok(ip) => self.ip(ip).to_string(),
+            Err(_) => self.required(Kind::Ip, ip),
+        }
+    }
+
+    fn ip(&self, ip: IpAddr) -> IpAddr {
+        if ip.is_loopback() || ip.is_unspecified() {
+            return ip;
+        }
+        match (self.ip_addresses, ip) {
+            (Redaction::Keep, ip) => ip,
+            (Redaction::Drop, IpAddr::V4(_)) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            (Redaction::Drop, IpAddr::V6(_)) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            (Redaction::Hash, IpAddr::V4(v4)) => {
+                let d = self.digest(Kind::Ip, &v4.to_string());
+                IpAddr::V4(Ipv4Addr::new(10, d[0], d[1], d[2]))
+            }
+            (Redaction::Hash, IpAddr::V6(v6)) => {
+                let d = self.digest(Kind::Ip, &v6.to_string());
+                let mut octets = [0u8; 16];
+                octets[0] = 0xfd; // unique local range
+                octets[1..].copy_from_slice(&d[..15]);
+                IpAddr::V6(Ipv6Addr::from(octets))
+            }
+        }
+    }
+
+    fn redaction(&self, kind: Kind) -> Redaction {
+        match kind {
+            Kind::User => self.user_names,
+            Kind::Host => self.host_names,
+            Kind::Mac => self.mac_addresses,
+            Kind::Ip => self.ip_addresses,
+        }
+    }
+
+    /// `<kind>-<hex>`, e.g. `host-3fa29c01d4`.
+    fn pseudonym(&self, kind: Kind, value: &str) -> String {
+        let hex: String = self.digest(kind, value)[..5]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("{}-{}", kind.prefix(), hex)
+    }
+
+    fn digest(&self, kind: Kind, value: &str) -> [u8; 32] {
+        // Windows names are case-insensitive: HMI01 and hmi01 are one host.
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update([0]);
+        hasher.update(kind.prefix().as_bytes());
+        hasher.update([0]);
+        hasher.update(value.to_lowercase().as_bytes());
+        hasher.finalize().into()
+    }
+}
+
+/// Kind of identifying value, which keeps pseudonyms of different kinds
+/// apart.
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    User,
+    Host,
+    Mac,
+    Ip,
+}
+
+impl Kind {
+    fn prefix(self) -> &'static str {
+        match self {
+            Kind::User => "user",
+            Kind::Host => "host",
+            Kind::Mac => "mac",
+            Kind::Ip => "ip",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ScanWarning;
+    use sysaudit_common::{IndustrialSoftwareDto, IpVersion, NetworkInterfaceDto};
+
+    fn report() -> AuditReport {
+        AuditReport {
+            schema_version: crate::REPORT_SCHEMA_VERSION,
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "Node-RED".into(),
+                product: "Node-RED".into(),
+                version: Some("3.1.9".into()),
+                install_path: Some(
+                    "C:\\Users\\jsmith\\AppData\\Roaming\\npm\\node_modules\\node-red".into(),
+                ),
+            }],
+            updates: vec![WindowsUpdate {
+                hotfix_id: "KB5034441".into(),
+                description: None,
+                installed_on: None,
+                installed_by: Some("PLANT\\jsmith".into()),
+            }],
+            services: vec![WindowsService {
+                name: "HistorianSvc".into(),
+                display_name: None,
+                state: None,
+                start_mode: None,
+                account: Some("NT AUTHORITY\\LocalService".into()),
+                path: Some("C:\\Users\\jsmith\\AppData\\historian.exe".into()),
+            }],
+            warnings: vec![ScanWarning::new(
+                crate::Collector::Updates,
+                "query failed on HMI01",
+            )],
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Pro".into(),
+                os_version: "10.0.19045".into(),
+                host_name: "HMI01".into(),
+                cpu_info: "Intel i5".into(),
+                cpu_physical_cores: Some(4),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![NetworkInterfaceDto {
+                    name: "Ethernet".into(),
+                    ip_address: "192.168.10.21".into(),
+                    ip_version: IpVersion::IPv4,
+                    mac_address: Some("00:1A:2B:3C:4D:5E".into()),
+                }],
+            })
+        }
+    }
+
+    #[test]
+    fn test_hash_all_is_stable() {
+        let policy = RedactionPolicy::hash_all().salt("site-a");
+        let mut first = report();
+        policy.apply(&mut first);
+        let mut second = report();
+        second.system.host_name = "hmi01".into();
+        policy.apply(&mut second);
+
+        let system = &first.system;
+        assert!(system.host_name.starts_with("host-"));
+        assert_eq!(system.host_name, second.system.host_name);
+        let iface = &system.network_interfaces[0];
+        assert!(iface.ip_address.starts_with("10."));
+        assert!(iface.ip_address.parse::<IpAddr>().is_ok());
+        assert!(iface.mac_address.as_deref().unwrap().starts_with("02:"));
+        let user = first.updates[0].installed_by.as_deref().unwrap();
+        assert!(user.starts_with("user-"));
+        assert_eq!(
+            first.services[0].account.as_deref(),
+            Some("NT AUTHORITY\\LocalService")
+        );
+        let path = first.services[0].path.as_deref().unwrap();
+        assert!(!path.contains("jsmith"));
+        assert!(path.starts_with("C:\\Users\\user-"));
+        let install = first.industrial[0].install_path.as_deref().unwrap();
+        assert!(!install.to_string_lossy().contains("jsmith"));
+        assert!(!first.warnings[0].message.contains("HMI01"));
+
+        let mut other_salt = report();
+        RedactionPolicy::hash_all()
+            .salt("site-b")
+            .apply(&mut other_salt);
+        assert_ne!(other_salt.system.host_name, system.host_name);
+    }
+
+    #[test]
+    fn test_software_install_location() {
+        let mut software = crate::Software {
+            name: "Node-RED".into(),
+            version: None,
+            publisher: None,
+            install_date: None,
+            install_date_source: crate::InstallDateSource::Exact,
+            install_location: Some("C:\\Users\\jsmith\\AppData\\Local\\node-red".into()),
+            source: crate::RegistrySource::CurrentUser,
+            support_status: None,
+            eol: None,
+        };
+        RedactionPolicy::hash_all().apply_software(&mut software);
+        let location = software.install_location.unwrap();
+        let location = location.to_string_lossy();
+        assert!(!location.contains("jsmith"));
+        assert!(location.starts_with("C:\\Users\\user-"));
+        assert!(location.ends_with("\\AppData\\Local\\node-red"));
+    }
+
+    #[test]
+    fn test_drop_and_keep() {
+        let mut dropped = report();
+        RedactionPolicy::drop_all()
+            .ip_addresses(Redaction::Keep)
+            .apply(&mut dropped);
+        assert_eq!(dropped.system.host_name, "redacted");
+        let iface = &dropped.system.network_interfaces[0];
+        assert_eq!(iface.ip_address, "192.168.10.21");
+        assert_eq!(iface.mac_address, None);
+        assert_eq!(dropped.updates[0].installed_by, None);
+
+        let mut kept = report();
+        RedactionPolicy::new().apply(&mut kept);
+        assert_eq!(kept.system.host_name, "HMI01");
+        assert_eq!(kept.warnings[0].message, "query failed on HMI01");
+    }
+}
@@ -0,0 +1,210 @@
+//! Redistributable runtimes and shared components.
+//!
+//! Vendor support calls start with "which Visual C++ redistributables and
+//! OPC Core Components are installed". Rather than leave them buried among
+//! thousands of software entries, reports list them separately: Visual C++
+//! redistributables of every year and architecture, the OPC Foundation's
+//! Core Components, .NET (Core) and Windows Desktop runtimes, and codec
+//! packs, classified from the installed software by display name.
+
+use serde::{Deserialize, Serialize};
+use sysaudit_common::SoftwareDto;
+
+/// Kind of redistributable component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedistributableKind {
+    /// Microsoft Visual C++ redistributable
+    VcRedist,
+    /// OPC Core Components redistributable (OPC DA/AE/HDA proxies, OpcEnum)
+    OpcCoreComponents,
+    /// .NET (Core) runtime
+    DotNetRuntime,
+    /// Windows Desktop runtime (WinForms and WPF on .NET)
+    DesktopRuntime,
+    /// Audio/video codec pack
+    Codec,
+}
+
+impl std::fmt::Display for RedistributableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedistributableKind::VcRedist => write!(f, "Visual C++"),
+            RedistributableKind::OpcCoreComponents => write!(f, "OPC Core Components"),
+            RedistributableKind::DotNetRuntime => write!(f, ".NET Runtime"),
+            RedistributableKind::DesktopRuntime => write!(f, "Desktop Runtime"),
+            RedistributableKind::Codec => write!(f, "Codec"),
+        }
+    }
+}
+
+/// An installed redistributable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Redistributable {
+    /// Kind of component
+    pub kind: RedistributableKind,
+    /// Display name as installed
+    pub name: String,
+    /// Version, if recorded
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Visual C++ release year (e.g., "2010", "2015-2022")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<String>,
+    /// Architecture ("x86", "x64" or "arm64"), when the name gives it
+    #[serde(default)]
+    pub architecture: Option<String>,
+}
+
+impl Redistributable {
+    /// Classify one software entry by its display name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::redistributables::{Redistributable, RedistributableKind};
+    ///
+    /// let vc = Redistributable::classify(
+    ///     "Microsoft Visual C++ 2015-2022 Redistributable (x64) - 14.38.33130",
+    ///     Some("14.38.33130.0"),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(vc.kind, RedistributableKind::VcRedist);
+    /// assert_eq!(vc.year.as_deref(), Some("2015-2022"));
+    /// assert_eq!(vc.architecture.as_deref(), Some("x64"));
+    /// ```
+    pub fn classify(name: &str, version: Option<&str>) -> Option<Self> {
+        let lower = name.to_lowercase();
+        let kind = if lower.contains("visual c++") && lower.contains("redistributable") {
+            RedistributableKind::VcRedist
+        } else if lower.contains("opc core components") {
+            RedistributableKind::OpcCoreComponents
+        } else if lower.starts_with("microsoft windows desktop runtime") {
+            RedistributableKind::DesktopRuntime
+        } else if lower.starts_with("microsoft .net runtime")
+            || lower.starts_with("microsoft .net core runtime")
+        {
+            RedistributableKind::DotNetRuntime
+        } else if lower.contains("codec") {
+            RedistributableKind::Codec
+        } else {
+            return None;
+        };
+
+        let year = (kind == RedistributableKind::VcRedist)
+            .then(|| {
+                lower
+                    .split_once("c++")?
+                    .1
+                    .split_whitespace()
+                    .next()
+                    .filter(|year| year.starts_with("20"))
+                    .map(str::to_string)
+            })
+            .flatten();
+        let architecture = lower
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find_map(|token| match token {
+                "x64" | "amd64" => Some("x64"),
+                "x86" => Some("x86"),
+                "arm64" => Some("arm64"),
+                _ => None,
+            })
+            .map(str::to_string);
+
+        Some(Redistributable {
+            kind,
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            year,
+            architecture,
+        })
+    }
+
+    /// The redistributables in a software list, sorted by kind, then name.
+    pub fn collect(software: &[SoftwareDto]) -> Vec<Self> {
+        let mut found: Vec<Self> = software
+            .iter()
+            .filter_map(|sw| Self::classify(&sw.name, sw.version.as_deref()))
+            .collect();
+        found.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.name.cmp(&b.name)));
+        found.dedup();
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let vc2008 = Redistributable::classify(
+            "Microsoft Visual C++ 2008 Redistributable - x86 9.0.30729.6161",
+            None,
+        )
+        .unwrap();
+        assert_eq!(vc2008.year.as_deref(), Some("2008"));
+        assert_eq!(vc2008.architecture.as_deref(), Some("x86"));
+        let vc2005 =
+            Redistributable::classify("Microsoft Visual C++ 2005 Redistributable", None).unwrap();
+        assert_eq!(vc2005.architecture, None);
+        // The bundle's MSI parts are not listed twice
+        assert_eq!(
+            Redistributable::classify("Microsoft Visual C++ 2019 X64 Minimum Runtime", None),
+            None
+        );
+
+        let opc = Redistributable::classify(
+            "OPC Core Components Redistributable (x64) 3.00.108",
+            Some("3.00.108"),
+        )
+        .unwrap();
+        assert_eq!(opc.kind, RedistributableKind::OpcCoreComponents);
+        assert_eq!(opc.year, None);
+        assert_eq!(opc.architecture.as_deref(), Some("x64"));
+
+        let desktop =
+            Redistributable::classify("Microsoft Windows Desktop Runtime - 6.0.25 (x64)", None)
+                .unwrap();
+        assert_eq!(desktop.kind, RedistributableKind::DesktopRuntime);
+        let dotnet =
+            Redistributable::classify("Microsoft .NET Runtime - 8.0.0 (arm64)", None).unwrap();
+        assert_eq!(dotnet.kind, RedistributableKind::DotNetRuntime);
+        assert_eq!(dotnet.architecture.as_deref(), Some("arm64"));
+        assert_eq!(
+            Redistributable::classify("K-Lite Codec Pack 17.9.0 Basic", None).map(|r| r.kind),
+            Some(RedistributableKind::Codec)
+        );
+        assert_eq!(
+            Redistributable::classify("Notepad++ (64-bit x64)", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_collect_sorts() {
+        let dto = |name: &str| SoftwareDto {
+            name: name.into(),
+            version: None,
+            vendor: None,
+            install_date: None,
+        };
+        let found = Redistributable::collect(&[
+            dto("OPC Core Components Redistributable (x86) 3.00.108"),
+            dto("7-Zip 23.01 (x64)"),
+            dto("Microsoft Visual C++ 2013 Redistributable (x86) - 12.0.40664"),
+            dto("Microsoft Visual C++ 2010  x64 Redistributable - 10.0.40219"),
+        ]);
+        let names: Vec<(RedistributableKind, Option<&str>)> =
+            found.iter().map(|r| (r.kind, r.year.as_deref())).collect();
+        assert_eq!(
+            names,
+            [
+                (RedistributableKind::VcRedist, Some("2010")),
+                (RedistributableKind::VcRedist, Some("2013")),
+                (RedistributableKind::OpcCoreComponents, None),
+            ]
+        );
+    }
+}
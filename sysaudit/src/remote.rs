@@ -1,14 +1,41 @@
+mod agent;
+mod client_cert;
+mod credentials;
+#[cfg(feature = "local")]
+mod dcom;
+mod errors;
 pub mod payload;
+pub(crate) mod progress;
+mod retry;
+mod sspi;
 pub mod transport;
+pub mod wsman;
+
+pub use crate::options::Sections;
+pub use agent::{AgentUpload, ExecutionMode};
+pub use client_cert::ClientCertificate;
+pub use credentials::{CredentialSource, ResolvedCredential};
+#[cfg(feature = "local")]
+pub use dcom::WmiRemoteScanner;
+pub use progress::{EventSender, ScanEvent, ScanStage};
+pub use retry::RetryPolicy;
+pub use transport::AuthMethod;
 
 use bon::Builder;
 use secrecy::SecretString;
-use std::time::Duration;
-use sysaudit_common::SysauditReport;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use crate::remote::payload::WINRM_PAYLOAD;
+use crate::metadata::{ScanMetadata, ScannerKind, current_user};
+use crate::metrics::{ScanMetrics, TransferMetrics};
+use crate::options::ScanOptions;
+use crate::redistributables::Redistributable;
+use crate::remote::payload::{decode_output, powershell_command, render_payload};
+use crate::remote::progress::emit;
 use crate::remote::transport::{HttpWinrmTransport, WinrmTransport};
+use crate::report::AuditReport;
 use crate::scanner::{ScanError, Scanner};
+use crate::score::AuditScore;
 
 /// Collects system data from a remote Windows machine via WinRM.
 ///
@@ -25,23 +52,105 @@ use crate::scanner::{ScanError, Scanner};
 ///     .password(SecretString::from("hunter2"))
 ///     .build();
 ///
-/// let report = scanner.scan().await?;
+/// let report = scanner.audit().await?;
 /// println!("Host: {}", report.system.host_name);
 /// # Ok(())
 /// # }
 /// ```
+///
+/// On a domain-joined machine, Kerberos can reuse the current logon:
+///
+/// ```no_run
+/// use sysaudit::{AuthMethod, RemoteScanner, Scanner};
+///
+/// # async fn example() -> Result<(), sysaudit::ScanError> {
+/// let report = RemoteScanner::builder()
+///     .host("hmi01.plant.example.com")
+///     .auth(AuthMethod::Kerberos)
+///     .build()
+///     .scan()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A quick health check can skip the slow collectors:
+///
+/// ```no_run
+/// use sysaudit::{AuthMethod, RemoteScanner, Scanner, Sections};
+///
+/// # async fn example() -> Result<(), sysaudit::ScanError> {
+/// let report = RemoteScanner::builder()
+///     .host("hmi01.plant.example.com")
+///     .auth(AuthMethod::Kerberos)
+///     .options(Sections::system_only().updates(true))
+///     .build()
+///     .audit()
+///     .await?;
+/// println!("{} updates", report.updates.len());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Without a password in code, fetch it from the Credential Manager:
+///
+/// ```no_run
+/// use sysaudit::{AuthMethod, CredentialSource, RemoteScanner, Scanner};
+///
+/// # async fn example() -> Result<(), sysaudit::ScanError> {
+/// let report = RemoteScanner::builder()
+///     .host("hmi01.plant.example.com")
+///     .auth(AuthMethod::Negotiate)
+///     .credential_source(CredentialSource::CredentialManager("sysaudit/plant".into()))
+///     .build()
+///     .audit()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Where PowerShell is locked down, push the native agent instead:
+///
+/// ```no_run
+/// use sysaudit::{AuthMethod, ExecutionMode, RemoteScanner, Scanner};
+///
+/// # async fn example() -> Result<(), sysaudit::ScanError> {
+/// let report = RemoteScanner::builder()
+///     .host("hmi01.plant.example.com")
+///     .auth(AuthMethod::Kerberos)
+///     .mode(ExecutionMode::agent(r"C:\tools\sysaudit-agent.exe"))
+///     .build()
+///     .audit()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Builder)]
 pub struct RemoteScanner {
     /// Target hostname or IP address.
     #[builder(into)]
     host: String,
 
-    /// Username for WinRM authentication.
+    /// Username for WinRM authentication. Leave unset with an SSPI
+    /// [`AuthMethod`] to use the current logon credentials.
     #[builder(into)]
-    username: String,
+    username: Option<String>,
 
     /// Password (secured in memory).
-    password: SecretString,
+    password: Option<SecretString>,
+
+    /// Where to fetch the password when none is set: an environment
+    /// variable, the Windows Credential Manager or a console prompt.
+    credential_source: Option<CredentialSource>,
+
+    /// Authentication method (default: Basic).
+    #[builder(default)]
+    auth: AuthMethod,
+
+    /// Service principal name override for Kerberos/Negotiate
+    /// (default: `HTTP/<host>`).
+    #[builder(into)]
+    spn: Option<String>,
 
     /// WinRM port (default: 5985 for HTTP, 5986 for HTTPS).
     #[builder(default = 5985)]
@@ -55,51 +164,202 @@ pub struct RemoteScanner {
     #[builder(default = false)]
     skip_cert_verify: bool,
 
-    /// Timeout for the entire scan operation.
+    /// PEM bundle of additional trusted CA certificates for HTTPS.
+    #[builder(into)]
+    ca_bundle: Option<PathBuf>,
+
+    /// Client certificate (`.pfx` file and passphrase) for WinRM
+    /// certificate-mapping authentication over HTTPS, replacing the
+    /// username and password.
+    #[builder(with = |path: impl Into<PathBuf>, passphrase: impl Into<SecretString>| {
+        ClientCertificate::new(path, passphrase)
+    })]
+    client_cert: Option<ClientCertificate>,
+
+    /// Timeout for each scan attempt.
     #[builder(default = Duration::from_secs(30))]
     timeout: Duration,
+
+    /// Retry policy for network failures and timeouts (default: 3 attempts).
+    #[builder(default)]
+    retry: RetryPolicy,
+
+    /// What to collect (default: software, industrial and updates).
+    /// Accepts [`ScanOptions`] or plain [`Sections`]; agent mode passes the
+    /// sections on to the agent.
+    #[builder(into, default)]
+    options: ScanOptions,
+
+    /// Run the PowerShell payload or push the native agent
+    /// (default: payload).
+    #[builder(default)]
+    mode: ExecutionMode,
+
+    /// Channel receiving progress events while the scan runs.
+    events: Option<EventSender>,
+}
+
+impl<S: remote_scanner_builder::State> RemoteScannerBuilder<S> {
+    /// Report sections to collect.
+    #[deprecated(note = "use `options`, which also takes `Sections`")]
+    pub fn sections(
+        self,
+        sections: Sections,
+    ) -> RemoteScannerBuilder<remote_scanner_builder::SetOptions<S>>
+    where
+        S::Options: remote_scanner_builder::IsUnset,
+    {
+        self.options(sections)
+    }
 }
 
 impl Scanner for RemoteScanner {
-    async fn scan(&self) -> Result<SysauditReport, ScanError> {
-        let transport = HttpWinrmTransport::new(
-            self.host.clone(),
-            self.port,
-            self.use_https,
-            self.skip_cert_verify,
-            self.username.clone(),
-            self.password.clone(),
-            self.timeout,
-        )?;
-
-        // Encode the payload in Base64 (UTF-16LE) for WinRM execution
-        // WinRM expects PowerShell commands to be encoded this way.
-
-        let utf16_bytes: Vec<u8> = WINRM_PAYLOAD
-            .encode_utf16()
-            .flat_map(|u| u.to_le_bytes())
-            .collect();
-        use base64::{Engine as _, engine::general_purpose::STANDARD};
-        let b64_payload = STANDARD.encode(&utf16_bytes);
-
-        // Command to run the encoded payload without profile to speed it up
-        let command = format!(
-            "powershell -NonInteractive -NoProfile -EncodedCommand {}",
-            b64_payload
-        );
+    async fn audit(&self) -> Result<AuditReport, ScanError> {
+        let started = Instant::now();
+        let (username, password) = self.credentials().await?;
+        // SSPI without a user name connects as the calling account.
+        let user = username.clone().or_else(current_user);
+        let transport = HttpWinrmTransport::builder()
+            .host(self.host.clone())
+            .port(self.port)
+            .use_https(self.use_https)
+            .skip_cert_verify(self.skip_cert_verify)
+            .maybe_ca_bundle(self.ca_bundle.clone())
+            .maybe_client_cert(self.client_cert.clone())
+            .maybe_username(username)
+            .maybe_password(password)
+            .auth(self.auth)
+            .maybe_spn(self.spn.clone())
+            .timeout(self.timeout)
+            .retry(self.retry)
+            .maybe_events(self.events.clone())
+            .build()?;
 
-        RemoteScanner::scan_with_transport(transport, &command).await
+        let (mut report, payload_bytes) = match &self.mode {
+            ExecutionMode::Payload => {
+                let command = powershell_command();
+                let script = render_payload(self.options.selected());
+                let report = RemoteScanner::scan_with_transport(
+                    &self.host,
+                    &transport,
+                    &command,
+                    &script,
+                    self.events.as_ref(),
+                )
+                .await?;
+                (report, (command.len() + script.len()) as u64)
+            }
+            ExecutionMode::Agent { binary, upload } => {
+                let stdout = agent::run(
+                    &self.host,
+                    &transport,
+                    binary,
+                    *upload,
+                    self.options.selected(),
+                    self.timeout,
+                )
+                .await?;
+                emit(self.events.as_ref(), &self.host, ScanStage::Parsing);
+                let size = std::fs::metadata(binary).map_or(0, |m| m.len());
+                (RemoteScanner::parse_output(&self.host, &stdout)?, size)
+            }
+        };
+        report.score = Some(AuditScore::compute_with(
+            &report,
+            &self.options.effective_score_weights(),
+        ));
+        report.metadata =
+            Some(ScanMetadata::new(ScannerKind::Remote, started, &self.options).user(user));
+        self.options.apply_asset_id(&mut report);
+        self.options.redact(&mut report);
+
+        // Agents report their own collector timings.
+        let metrics = report
+            .metrics
+            .take()
+            .unwrap_or_else(|| ScanMetrics::new().total(started.elapsed()));
+        let transfer = TransferMetrics {
+            payload_bytes,
+            report_bytes: serde_json::to_vec(&report).map_or(0, |json| json.len() as u64),
+            ..transport.transfer()
+        };
+        tracing::info!(
+            host = %self.host,
+            bytes_sent = transfer.bytes_sent,
+            bytes_received = transfer.bytes_received,
+            requests = transfer.requests,
+            "Remote scan transfer"
+        );
+        report.metrics = Some(metrics.transfer(transfer));
+        Ok(report)
     }
 }
 
 impl RemoteScanner {
+    /// Present `client_cert`, sharing its decoded form with the caller's
+    /// other scanners (the builder setter takes a path and decodes anew).
+    pub(crate) fn with_client_cert(mut self, client_cert: Option<ClientCertificate>) -> Self {
+        self.client_cert = client_cert;
+        self
+    }
+
+    /// Username and password, resolving the credential source if no
+    /// password was given.
+    async fn credentials(&self) -> Result<(Option<String>, Option<SecretString>), ScanError> {
+        let Some(source) = self
+            .credential_source
+            .clone()
+            .filter(|_| self.password.is_none())
+        else {
+            return Ok((self.username.clone(), self.password.clone()));
+        };
+
+        let username = self.username.clone();
+        let resolved = tokio::task::spawn_blocking(move || source.resolve(username.as_deref()))
+            .await
+            .map_err(|e| ScanError::Local(e.to_string()))?
+            .map_err(|e| ScanError::Local(format!("credentials for {}: {}", self.host, e)))?;
+        Ok((resolved.username, Some(resolved.password)))
+    }
+
     /// Internal method to allow passing a mocked transport in tests.
     async fn scan_with_transport<T: WinrmTransport>(
-        transport: T,
+        host: &str,
+        transport: &T,
         command: &str,
-    ) -> Result<SysauditReport, ScanError> {
-        let json_stdout = transport.execute(command).await?;
-        let report: SysauditReport = serde_json::from_str(&json_stdout)?;
+        script: &str,
+        events: Option<&EventSender>,
+    ) -> Result<AuditReport, ScanError> {
+        let stdout = transport.execute_script(command, script).await?;
+        emit(events, host, ScanStage::Parsing);
+        RemoteScanner::parse_output(host, &stdout)
+    }
+
+    /// Parse payload or agent stdout into a report.
+    fn parse_output(host: &str, stdout: &str) -> Result<AuditReport, ScanError> {
+        if stdout.trim().is_empty() {
+            return Err(ScanError::RemoteExecution {
+                host: host.to_string(),
+                message: "payload produced no output".to_string(),
+            });
+        }
+        let json = decode_output(stdout).map_err(|message| ScanError::RemoteExecution {
+            host: host.to_string(),
+            message,
+        })?;
+        // Agents may run a different release; upgrade older documents and
+        // refuse newer ones rather than misreading them.
+        let mut report = AuditReport::from_json_versioned(&json).map_err(|e| match e {
+            crate::Error::Json(e) => ScanError::Deserialization(e),
+            e => ScanError::RemoteExecution {
+                host: host.to_string(),
+                message: e.to_string(),
+            },
+        })?;
+        // The payload leaves classification to this side.
+        if report.redistributables.is_empty() {
+            report.redistributables = Redistributable::collect(&report.software);
+        }
         Ok(report)
     }
 }
@@ -109,7 +369,7 @@ mod tests {
     use super::*;
     use crate::remote::transport::MockWinrmTransport;
     use chrono::Utc;
-    use sysaudit_common::SystemInfoDto;
+    use sysaudit_common::{SysauditReport, SystemInfoDto};
 
     #[tokio::test]
     async fn test_remote_scanner_success() {
@@ -135,30 +395,98 @@ mod tests {
         let response_json = serde_json::to_string(&mock_report).unwrap();
 
         mock_transport
-            .expect_execute()
-            .with(mockall::predicate::str::contains("powershell"))
+            .expect_execute_script()
+            .with(
+                mockall::predicate::str::contains("powershell"),
+                mockall::predicate::str::contains("Get-HardwareInfo"),
+            )
             .times(1)
-            .returning(move |_| Ok(response_json.clone()));
+            .returning(move |_, _| Ok(response_json.clone()));
 
-        let result = RemoteScanner::scan_with_transport(mock_transport, "powershell mock").await;
+        let (events, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let result = RemoteScanner::scan_with_transport(
+            "test",
+            &mock_transport,
+            "powershell mock",
+            "Get-HardwareInfo",
+            Some(&events),
+        )
+        .await;
 
         assert!(result.is_ok());
         let report = result.unwrap();
         assert_eq!(report.system.host_name, "MOCK-PC");
+        assert_eq!(receiver.try_recv().unwrap().stage, ScanStage::Parsing);
+    }
+
+    #[test]
+    fn test_parse_output_keeps_payload_warnings() {
+        let report = SysauditReport {
+            system: SystemInfoDto {
+                os_name: "Mock OS".to_string(),
+                os_version: "10.0".to_string(),
+                host_name: "MOCK-PC".to_string(),
+                cpu_info: "Mock CPU".to_string(),
+                cpu_physical_cores: None,
+                memory_total_bytes: 0,
+                memory_used_bytes: 0,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            },
+            software: vec![],
+            industrial: vec![],
+            timestamp: Utc::now(),
+        };
+        let mut json = serde_json::to_value(&report).unwrap();
+        json["warnings"] = serde_json::json!([{
+            "collector": "event_logs",
+            "message": "payload truncated: event_logs is nested deeper than the JSON depth limit"
+        }]);
+
+        let report = RemoteScanner::parse_output("test", &json.to_string()).unwrap();
+        assert_eq!(report.schema_version, crate::REPORT_SCHEMA_VERSION);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].collector, crate::Collector::EventLogs);
+        assert!(report.warnings[0].message.starts_with("payload truncated"));
+    }
+
+    #[test]
+    fn test_parse_output_rejects_newer_schema() {
+        let json = format!(
+            r#"{{"schema_version":{},"system":{{}},"software":[],"industrial":[],"timestamp":"2024-01-01T00:00:00Z"}}"#,
+            crate::REPORT_SCHEMA_VERSION + 1
+        );
+        match RemoteScanner::parse_output("test", &json).unwrap_err() {
+            ScanError::RemoteExecution { message, .. } => {
+                assert!(message.contains("newer than supported"), "{}", message)
+            }
+            e => panic!("Expected RemoteExecution error, got {:?}", e),
+        }
     }
 
     #[tokio::test]
     async fn test_remote_scanner_execution_error() {
         let mut mock_transport = MockWinrmTransport::new();
 
-        mock_transport.expect_execute().times(1).returning(|_| {
-            Err(ScanError::RemoteExecution {
-                host: "test".to_string(),
-                message: "execution failed".to_string(),
-            })
-        });
+        mock_transport
+            .expect_execute_script()
+            .times(1)
+            .returning(|_, _| {
+                Err(ScanError::RemoteExecution {
+                    host: "test".to_string(),
+                    message: "execution failed".to_string(),
+                })
+            });
 
-        let result = RemoteScanner::scan_with_transport(mock_transport, "powershell mock").await;
+        let result = RemoteScanner::scan_with_transport(
+            "test",
+            &mock_transport,
+            "powershell mock",
+            "",
+            None,
+        )
+        .await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -172,11 +500,18 @@ mod tests {
         let mut mock_transport = MockWinrmTransport::new();
 
         mock_transport
-            .expect_execute()
+            .expect_execute_script()
             .times(1)
-            .returning(|_| Ok("{ invalid_json ]".to_string()));
+            .returning(|_, _| Ok("{ invalid_json ]".to_string()));
 
-        let result = RemoteScanner::scan_with_transport(mock_transport, "powershell mock").await;
+        let result = RemoteScanner::scan_with_transport(
+            "test",
+            &mock_transport,
+            "powershell mock",
+            "",
+            None,
+        )
+        .await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
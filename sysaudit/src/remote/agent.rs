@@ -0,0 +1,270 @@
+//! Push-binary execution: copy `sysaudit-agent.exe` to the target, run it
+//! and remove it again.
+//!
+//! Sites that lock PowerShell down (ConstrainedLanguage, AMSI rules) block
+//! the script payload; the native agent collects the same report with the
+//! local collectors instead.
+//!
+//! Every step runs through `cmd /c` rather than PowerShell, so agent mode
+//! works under ConstrainedLanguage too: a session upload echoes Base64
+//! chunks into numbered files, joins and `certutil -decode`s them and
+//! checks the result's SHA-256.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::options::Sections;
+use crate::remote::transport::WinrmTransport;
+use crate::scanner::ScanError;
+
+/// Time the agent run needs beyond its collectors: process start,
+/// serialization and streaming the report back.
+const RUN_MARGIN: Duration = Duration::from_secs(10);
+
+/// Raw bytes per upload command when copying over the WinRM session
+/// (8000 Base64 characters, below cmd's 8191-character line limit).
+const UPLOAD_CHUNK_BYTES: usize = 6000;
+
+/// How [`RemoteScanner`](crate::RemoteScanner) collects the report.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ExecutionMode {
+    /// Run the PowerShell payload (the default).
+    #[default]
+    Payload,
+    /// Copy a statically linked `sysaudit-agent.exe` to the target, run
+    /// `sysaudit-agent --json` and delete it afterwards.
+    Agent {
+        /// Local path of the agent binary
+        binary: PathBuf,
+        /// How the binary is copied
+        upload: AgentUpload,
+    },
+}
+
+impl ExecutionMode {
+    /// Agent mode with the binary copied over the `ADMIN$` share.
+    pub fn agent(binary: impl Into<PathBuf>) -> Self {
+        ExecutionMode::Agent {
+            binary: binary.into(),
+            upload: AgentUpload::AdminShare,
+        }
+    }
+}
+
+/// How the agent binary reaches the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AgentUpload {
+    /// Copy to `\\host\ADMIN$\Temp` over SMB with the calling process's
+    /// credentials (fast; needs 445/tcp and admin rights on the share).
+    #[default]
+    AdminShare,
+    /// Stream the binary in Base64 chunks over the WinRM session (slower,
+    /// but needs nothing beyond the WinRM listener).
+    Session,
+}
+
+/// Copy the agent, run it for `sections` and clean up, returning its
+/// stdout.
+///
+/// The agent's collectors get `timeout` (the transport's timeout) less
+/// [`RUN_MARGIN`], so a hung collector leaves its section empty instead of
+/// the whole run timing out.
+///
+/// The binary is deleted even when the run fails; a failed cleanup is
+/// logged rather than masking the scan result.
+pub(crate) async fn run<T: WinrmTransport>(
+    host: &str,
+    transport: &T,
+    binary: &Path,
+    upload: AgentUpload,
+    sections: Sections,
+    timeout: Duration,
+) -> Result<String, ScanError> {
+    let name = format!("sysaudit-agent-{}.exe", uuid::Uuid::new_v4().simple());
+    let upload_error = |message: String| ScanError::RemoteExecution {
+        host: host.to_string(),
+        message,
+    };
+
+    tracing::info!(host, binary = %binary.display(), ?upload, "Copying sysaudit agent");
+    match upload {
+        AgentUpload::AdminShare => {
+            let source = binary.to_path_buf();
+            let target = admin_share_path(host, &name);
+            tokio::task::spawn_blocking(move || std::fs::copy(&source, &target))
+                .await
+                .map_err(|e| upload_error(e.to_string()))?
+                .map_err(|e| upload_error(format!("copy to ADMIN$ failed: {}", e)))?;
+        }
+        AgentUpload::Session => {
+            let bytes = tokio::fs::read(binary)
+                .await
+                .map_err(|e| upload_error(format!("cannot read {}: {}", binary.display(), e)))?;
+            for command in upload_commands(&name, &bytes) {
+                if let Err(e) = transport.execute(&command).await {
+                    cleanup(host, transport, &name).await;
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    tracing::info!(host, agent = %name, "Running sysaudit agent");
+    let collector_timeout = timeout
+        .saturating_sub(RUN_MARGIN)
+        .max(Duration::from_secs(1));
+    let result = transport
+        .execute(&run_command(&name, sections, collector_timeout))
+        .await;
+    cleanup(host, transport, &name).await;
+    result
+}
+
+/// UNC path of the agent on the target's `ADMIN$` share.
+fn admin_share_path(host: &str, name: &str) -> PathBuf {
+    PathBuf::from(format!(r"\\{}\ADMIN$\Temp\{}", host, name))
+}
+
+/// Path of the agent on the target, expanded by `cmd`.
+fn remote_path(name: &str) -> String {
+    format!(r"%SystemRoot%\Temp\{}", name)
+}
+
+/// Command running the agent for `sections`, giving each collector
+/// `collector_timeout`.
+fn run_command(name: &str, sections: Sections, collector_timeout: Duration) -> String {
+    let mut names = vec!["system"];
+    names.extend(sections.names());
+    format!(
+        "cmd /c {} --json --sections {} --collector-timeout {}",
+        remote_path(name),
+        names.join(","),
+        collector_timeout.as_secs()
+    )
+}
+
+/// Commands writing `bytes` to `%SystemRoot%\Temp\<name>`: one `echo` per
+/// Base64 chunk into its own numbered part file, then one command joining
+/// the parts in order, `certutil -decode`-ing them and checking the
+/// SHA-256 of the result.
+///
+/// Every command overwrites its output, so a retried command cannot
+/// duplicate a chunk.
+fn upload_commands(name: &str, bytes: &[u8]) -> Vec<String> {
+    let path = remote_path(name);
+    let chunks = bytes.chunks(UPLOAD_CHUNK_BYTES);
+    let count = chunks.len();
+    // The redirection goes first: `echo ...9>file` would redirect handle 9.
+    let mut commands: Vec<String> = chunks
+        .enumerate()
+        .map(|(i, chunk)| {
+            format!(
+                "cmd /c >{}.{}.part echo {}",
+                path,
+                i,
+                STANDARD.encode(chunk)
+            )
+        })
+        .collect();
+    // Bare names after `cd`: `%i` followed by `%SystemRoot%` would be
+    // parsed as one variable reference.
+    commands.push(format!(
+        "cmd /c cd /d %SystemRoot%\\Temp \
+         && (for /l %i in (0,1,{last}) do @type {name}.%i.part) >{name}.b64 \
+         && certutil -f -decode {name}.b64 {name} >nul \
+         && certutil -hashfile {name} SHA256 | findstr /i /x {hash} >nul \
+         && del /f /q {name}.b64 {name}.*.part",
+        last = count.saturating_sub(1),
+        name = name,
+        hash = hex(&Sha256::digest(bytes)),
+    ));
+    commands
+}
+
+/// Lowercase hex, as `certutil -hashfile` prints it.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+async fn cleanup<T: WinrmTransport>(host: &str, transport: &T, name: &str) {
+    let path = remote_path(name);
+    let command = format!(
+        "cmd /c del /f /q {path} {path}.b64 {path}.*.part 2>nul",
+        path = path
+    );
+    if let Err(e) = transport.execute(&command).await {
+        tracing::warn!(host, agent = %name, error = %e, "Could not remove sysaudit agent");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::transport::MockWinrmTransport;
+
+    #[test]
+    fn test_upload_commands_chunking() {
+        let bytes = vec![0x4d_u8; UPLOAD_CHUNK_BYTES * 2 + 10];
+        let commands = upload_commands("agent.exe", &bytes);
+
+        // three chunks and the join/decode/verify
+        assert_eq!(commands.len(), 4);
+        assert!(commands[0].starts_with(r"cmd /c >%SystemRoot%\Temp\agent.exe.0.part echo "));
+        assert!(commands[1].contains(r"agent.exe.1.part echo "));
+        assert!(commands.iter().all(|c| !c.contains(">>")));
+        assert!(commands[2].ends_with(&STANDARD.encode([0x4d_u8; 10])));
+        assert!(
+            commands[3].starts_with(r"cmd /c cd /d %SystemRoot%\Temp && (for /l %i in (0,1,2) do")
+        );
+        assert!(commands[3].contains("certutil -f -decode"));
+        assert!(commands[3].contains(&format!("findstr /i /x {} ", hex(&Sha256::digest(&bytes)))));
+        assert!(commands.iter().all(|c| c.len() < 8191));
+        assert!(commands.iter().all(|c| !c.contains("powershell")));
+        assert_eq!(
+            admin_share_path("hmi01", "agent.exe"),
+            PathBuf::from(r"\\hmi01\ADMIN$\Temp\agent.exe")
+        );
+    }
+
+    #[test]
+    fn test_run_command_passes_sections() {
+        let timeout = Duration::from_secs(20);
+        assert_eq!(
+            run_command("agent.exe", Sections::new(), timeout),
+            r"cmd /c %SystemRoot%\Temp\agent.exe --json --sections system,software,industrial --collector-timeout 20"
+        );
+        assert!(
+            run_command("agent.exe", Sections::system_only(), timeout)
+                .contains("--sections system --")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_upload_runs_and_cleans_up() {
+        let binary = std::env::temp_dir().join("sysaudit-agent-test.exe");
+        std::fs::write(&binary, b"MZ agent").unwrap();
+
+        // one upload chunk, the join/decode/verify, the run and the cleanup
+        let mut transport = MockWinrmTransport::new();
+        transport
+            .expect_execute()
+            .times(4)
+            .withf(|command| command.starts_with("cmd /c "))
+            .returning(|_| Ok("{}".to_string()));
+
+        let stdout = run(
+            "hmi01",
+            &transport,
+            &binary,
+            AgentUpload::Session,
+            Sections::new(),
+            Duration::from_secs(30),
+        )
+        .await
+        .unwrap();
+        assert_eq!(stdout, "{}");
+        std::fs::remove_file(&binary).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Client certificates for WinRM certificate-mapping authentication.
+//!
+//! Hardened sites map a certificate to a local account on the target
+//! (`New-Item WSMan:\localhost\ClientCertificate ...`) and turn password
+//! authentication off. The scanner then presents the certificate in the
+//! TLS handshake and asks the listener for the HTTPS mutual security
+//! profile instead of sending credentials.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use p12_keystore::KeyStore;
+use reqwest::Identity;
+use secrecy::{ExposeSecret, SecretString};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::scanner::ScanError;
+
+/// `Authorization` value selecting the WS-Man HTTPS mutual security profile.
+pub(crate) const MUTUAL_AUTH_PROFILE: &str =
+    "http://schemas.dmtf.org/wbem/wsman/1/wsman/secprofile/https/mutual";
+
+/// A client certificate and its private key: a PKCS#12 (`.pfx`/`.p12`)
+/// file with its passphrase, or a PEM file holding an unencrypted key and
+/// the certificate chain (the passphrase is then ignored).
+///
+/// The file is read and decoded once, on first use; clones share the
+/// result, so a fleet run decodes it once for all hosts.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::{ClientCertificate, RemoteScanner, Scanner};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let report = RemoteScanner::builder()
+///     .host("hmi01.plant.local")
+///     .port(5986)
+///     .use_https(true)
+///     .client_cert("C:\\certs\\audit.pfx", "pfx passphrase")
+///     .build()
+///     .audit()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClientCertificate {
+    path: PathBuf,
+    passphrase: SecretString,
+    identity: Arc<OnceLock<Result<Identity, String>>>,
+}
+
+impl fmt::Debug for ClientCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientCertificate")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ClientCertificate {
+    /// Certificate file and the passphrase protecting it.
+    pub fn new(path: impl Into<PathBuf>, passphrase: impl Into<SecretString>) -> Self {
+        ClientCertificate {
+            path: path.into(),
+            passphrase: passphrase.into(),
+            identity: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Path of the certificate file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the certificate.
+    ///
+    /// An unreadable file, a damaged archive or a wrong passphrase is a
+    /// local error: no host would accept the scan, so it is not retried.
+    pub(crate) fn identity(&self) -> Result<Identity, ScanError> {
+        self.identity
+            .get_or_init(|| self.load())
+            .clone()
+            .map_err(ScanError::Local)
+    }
+
+    fn load(&self) -> Result<Identity, String> {
+        let data = std::fs::read(&self.path).map_err(|e| {
+            format!(
+                "Failed to read client certificate {}: {}",
+                self.path.display(),
+                e
+            )
+        })?;
+
+        let pem = if data.trim_ascii_start().starts_with(b"-----BEGIN") {
+            Ok(data)
+        } else {
+            pkcs12_to_pem(&data, self.passphrase.expose_secret()).map(String::into_bytes)
+        };
+        pem.and_then(|pem| Identity::from_pem(&pem).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Invalid client certificate {}: {}", self.path.display(), e))
+    }
+}
+
+/// The private key and certificate chain of a PKCS#12 archive, as PEM.
+fn pkcs12_to_pem(data: &[u8], passphrase: &str) -> Result<String, String> {
+    let keystore = KeyStore::from_pkcs12(data, passphrase).map_err(|e| e.to_string())?;
+    let (_, chain) = keystore
+        .private_key_chain()
+        .ok_or_else(|| "no private key in the archive".to_string())?;
+
+    let mut pem = pem_block("PRIVATE KEY", chain.key());
+    for certificate in chain.chain() {
+        pem.push_str(&pem_block("CERTIFICATE", certificate.as_der()));
+    }
+    Ok(pem)
+}
+
+/// One PEM block with 64-column base64 lines.
+fn pem_block(label: &str, der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let mut block = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        block.push_str(&String::from_utf8_lossy(line));
+        block.push('\n');
+    }
+    block.push_str(&format!("-----END {}-----\n", label));
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pem_block() {
+        let block = pem_block("CERTIFICATE", &[0u8; 60]);
+        let lines: Vec<&str> = block.lines().collect();
+        assert_eq!(lines[0], "-----BEGIN CERTIFICATE-----");
+        assert_eq!(lines[1].len(), 64);
+        assert_eq!(lines[2].len(), 16);
+        assert_eq!(lines[3], "-----END CERTIFICATE-----");
+    }
+
+    #[test]
+    fn test_unreadable_certificate() {
+        let certificate = ClientCertificate::new("does-not-exist.pfx", "secret");
+        let error = certificate.identity().unwrap_err();
+        assert!(matches!(error, ScanError::Local(_)));
+        assert!(error.to_string().contains("does-not-exist.pfx"));
+
+        let path = std::env::temp_dir().join(format!("sysaudit-client-{}.pfx", std::process::id()));
+        std::fs::write(&path, b"not a pkcs12 archive").unwrap();
+        let certificate = ClientCertificate::new(&path, "secret");
+        let error = certificate.identity().unwrap_err();
+        assert!(matches!(error, ScanError::Local(_)));
+        assert!(error.to_string().contains("Invalid client certificate"));
+
+        // Clones reuse the first result instead of reading the file again
+        std::fs::remove_file(&path).unwrap();
+        let error = certificate.clone().identity().unwrap_err();
+        assert!(error.to_string().contains("Invalid client certificate"));
+    }
+}
@@ -0,0 +1,230 @@
+//! Where remote scans get their password from.
+//!
+//! Keeps passwords out of code and command lines: read them from an
+//! environment variable, a generic credential in the Windows Credential
+//! Manager (`cmdkey /generic:<target> /user:<user> /pass`), or an
+//! interactive console prompt.
+
+use secrecy::SecretString;
+use std::io::{BufRead, Write};
+
+use crate::Error;
+
+/// A source for the remote account's password (and optionally its name).
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::CredentialSource;
+///
+/// let credential = CredentialSource::parse("credman:sysaudit/plant")
+///     .unwrap()
+///     .resolve(None)
+///     .unwrap();
+/// println!("scanning as {:?}", credential.username);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// Password from an environment variable
+    Env(String),
+    /// Generic credential stored under this target name; supplies the
+    /// username too
+    CredentialManager(String),
+    /// Ask on the console (input is not echoed)
+    Prompt,
+}
+
+/// A resolved account.
+#[derive(Debug, Clone)]
+pub struct ResolvedCredential {
+    /// Account name, when the source or caller provided one
+    pub username: Option<String>,
+    /// The password
+    pub password: SecretString,
+}
+
+impl CredentialSource {
+    /// Parse `env:<VAR>`, `credman:<target>` or `prompt`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for any other form.
+    pub fn parse(spec: &str) -> Result<Self, Error> {
+        let spec = spec.trim();
+        if spec.eq_ignore_ascii_case("prompt") {
+            return Ok(CredentialSource::Prompt);
+        }
+        match spec.split_once(':') {
+            Some((kind, name)) if !name.is_empty() => match kind.to_lowercase().as_str() {
+                "env" => Ok(CredentialSource::Env(name.to_string())),
+                "credman" => Ok(CredentialSource::CredentialManager(name.to_string())),
+                _ => Err(invalid_spec(spec)),
+            },
+            _ => Err(invalid_spec(spec)),
+        }
+    }
+
+    /// Fetch the credential. `username` is used when the source has none
+    /// of its own (and shown in the prompt).
+    ///
+    /// Blocks on the Credential Manager or the console; call it once before
+    /// starting scans rather than per host.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if the variable or stored credential does
+    /// not exist, or [`Error::Io`] if the console cannot be read.
+    pub fn resolve(&self, username: Option<&str>) -> Result<ResolvedCredential, Error> {
+        match self {
+            CredentialSource::Env(var) => {
+                let password = std::env::var(var).map_err(|_| {
+                    Error::General(format!("environment variable {} is not set", var))
+                })?;
+                Ok(ResolvedCredential {
+                    username: username.map(String::from),
+                    password: SecretString::from(password),
+                })
+            }
+            CredentialSource::CredentialManager(target) => {
+                let (stored_user, password) = read_generic_credential(target)?;
+                Ok(ResolvedCredential {
+                    username: stored_user.or_else(|| username.map(String::from)),
+                    password,
+                })
+            }
+            CredentialSource::Prompt => {
+                let username = match username {
+                    Some(user) => user.to_string(),
+                    None => prompt_line("Username: ")?,
+                };
+                let password = prompt_password(&format!("Password for {}: ", username))?;
+                Ok(ResolvedCredential {
+                    username: Some(username),
+                    password,
+                })
+            }
+        }
+    }
+}
+
+fn invalid_spec(spec: &str) -> Error {
+    Error::General(format!(
+        "invalid credential source '{}' (use env:<VAR>, credman:<target> or prompt)",
+        spec
+    ))
+}
+
+/// Read a generic credential's user name and UTF-16 password blob.
+fn read_generic_credential(target: &str) -> Result<(Option<String>, SecretString), Error> {
+    use windows::Win32::Security::Credentials::{
+        CRED_TYPE_GENERIC, CREDENTIALW, CredFree, CredReadW,
+    };
+    use windows::core::HSTRING;
+
+    let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+    // SAFETY: `credential` receives a buffer allocated by CredReadW, which
+    // is only read while valid and released with CredFree below.
+    unsafe {
+        CredReadW(
+            &HSTRING::from(target),
+            CRED_TYPE_GENERIC,
+            None,
+            &mut credential,
+        )
+        .map_err(|e| {
+            Error::General(format!(
+                "credential '{}' not found in Credential Manager: {}",
+                target, e
+            ))
+        })?;
+
+        let stored = &*credential;
+        let username = if stored.UserName.is_null() {
+            None
+        } else {
+            stored.UserName.to_string().ok().filter(|u| !u.is_empty())
+        };
+        let blob =
+            std::slice::from_raw_parts(stored.CredentialBlob, stored.CredentialBlobSize as usize);
+        let utf16: Vec<u16> = blob
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        let password = String::from_utf16_lossy(&utf16);
+        CredFree(credential as *const _);
+
+        Ok((username, SecretString::from(password)))
+    }
+}
+
+fn prompt_line(prompt: &str) -> Result<String, Error> {
+    let mut stderr = std::io::stderr();
+    write!(stderr, "{}", prompt)?;
+    stderr.flush()?;
+
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Prompt with console echo switched off.
+fn prompt_password(prompt: &str) -> Result<SecretString, Error> {
+    use windows::Win32::System::Console::{
+        CONSOLE_MODE, ENABLE_ECHO_INPUT, GetConsoleMode, GetStdHandle, STD_INPUT_HANDLE,
+        SetConsoleMode,
+    };
+
+    // SAFETY: plain console API calls on the process's own stdin handle.
+    let restore = unsafe {
+        GetStdHandle(STD_INPUT_HANDLE).ok().and_then(|handle| {
+            let mut mode = CONSOLE_MODE::default();
+            GetConsoleMode(handle, &mut mode).ok()?;
+            SetConsoleMode(handle, mode & !ENABLE_ECHO_INPUT).ok()?;
+            Some((handle, mode))
+        })
+    };
+
+    let password = prompt_line(prompt);
+
+    if let Some((handle, mode)) = restore {
+        // SAFETY: restores the mode read above on the same handle.
+        let _ = unsafe { SetConsoleMode(handle, mode) };
+        eprintln!();
+    }
+
+    Ok(SecretString::from(password?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::ExposeSecret;
+
+    #[test]
+    fn test_parse_and_resolve_env() {
+        assert_eq!(
+            CredentialSource::parse("credman:sysaudit/plant").unwrap(),
+            CredentialSource::CredentialManager("sysaudit/plant".to_string())
+        );
+        assert_eq!(
+            CredentialSource::parse("PROMPT").unwrap(),
+            CredentialSource::Prompt
+        );
+        assert!(CredentialSource::parse("env:").is_err());
+        assert!(CredentialSource::parse("vault:x").is_err());
+
+        // SAFETY: no other test reads or writes this variable.
+        unsafe { std::env::set_var("SYSAUDIT_TEST_CREDENTIAL_PW", "secret") };
+        let credential = CredentialSource::parse("env:SYSAUDIT_TEST_CREDENTIAL_PW")
+            .unwrap()
+            .resolve(Some("PLANT\\audit"))
+            .unwrap();
+        assert_eq!(credential.username.as_deref(), Some("PLANT\\audit"));
+        assert_eq!(credential.password.expose_secret(), "secret");
+        assert!(
+            CredentialSource::Env("SYSAUDIT_TEST_CREDENTIAL_UNSET".into())
+                .resolve(None)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,684 @@
+//! Agentless remote collection over DCOM/WMI.
+//!
+//! Queries `Win32_*` classes and reads the Uninstall keys through the
+//! `StdRegProv` provider, so nothing is executed on the target. The
+//! connection uses the credentials of the calling process; run under an
+//! account with remote WMI rights (e.g. `runas /netonly`).
+
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use wmi::{COMLibrary, WMIConnection, WMIError};
+
+use crate::autopilot::MDM_NAMESPACE;
+use crate::containers::Hive;
+use crate::exclusions::{
+    AvExclusions, DEFENDER_NAMESPACE, SECURITY_CENTER_NAMESPACE, query_antivirus,
+};
+use crate::industrial::{classify_software, merge_industrial};
+use crate::licensing::EDITION_KEY;
+use crate::metadata::{ScanMetadata, ScannerKind};
+use crate::options::{Collector, ScanOptions, Sections};
+use crate::report::{AuditReport, ScanWarning};
+use crate::scanner::{ScanError, Scanner};
+use crate::score::AuditScore;
+use crate::security::BITLOCKER_NAMESPACE;
+use crate::software::build_software;
+use crate::system::{install_time, machine_sid};
+use crate::update_health::{CCM_NAMESPACE, CCM_SCHEDULER_NAMESPACE, UPDATE_SERVICES};
+use crate::{
+    AssetFingerprint, Autopilot, Containers, Error, EventLogs, HostIdentity, IndustrialScanner,
+    IndustrialSoftware, Licensing, NetworkInterface, RegistrySource, SecurityPosture, Software,
+    SystemInfo, UpdateHealth, WindowsService, WindowsUpdate,
+};
+
+/// `HKEY_LOCAL_MACHINE` as passed to `StdRegProv` methods.
+const HKEY_LOCAL_MACHINE: u32 = 0x8000_0002;
+
+/// `HKEY_USERS` as passed to `StdRegProv` methods.
+const HKEY_USERS: u32 = 0x8000_0003;
+
+const UNINSTALL_KEYS: &[(&str, RegistrySource)] = &[
+    (
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+        RegistrySource::LocalMachine64,
+    ),
+    (
+        r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+        RegistrySource::LocalMachine32,
+    ),
+];
+
+/// Collects system data from a remote Windows machine over DCOM/WMI.
+///
+/// Unlike [`RemoteScanner`](crate::RemoteScanner) this needs no WinRM
+/// listener and runs no PowerShell on the target; only the RPC endpoint
+/// mapper (135/tcp) and the dynamic RPC ports must be reachable.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::{Scanner, WmiRemoteScanner};
+///
+/// # async fn example() -> Result<(), sysaudit::ScanError> {
+/// let report = WmiRemoteScanner::builder()
+///     .host("hmi01.plant.example.com")
+///     .build()
+///     .audit()
+///     .await?;
+/// println!("{} packages", report.software.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Builder)]
+pub struct WmiRemoteScanner {
+    /// Target hostname or IP address.
+    #[builder(into)]
+    host: String,
+
+    /// Timeout for the entire scan operation.
+    ///
+    /// WMI calls over DCOM cannot be interrupted: after a timeout the scan
+    /// returns, but its thread stays blocked until the remote call returns or
+    /// DCOM gives up on the host, so a fleet of unresponsive hosts can keep
+    /// many blocking-pool threads busy.
+    #[builder(default = Duration::from_secs(120))]
+    timeout: Duration,
+
+    /// What to collect (default: software, industrial and updates).
+    #[builder(into, default)]
+    options: ScanOptions,
+}
+
+impl<S: wmi_remote_scanner_builder::State> WmiRemoteScannerBuilder<S> {
+    /// Report sections to collect.
+    #[deprecated(note = "use `options`, which also takes `Sections`")]
+    pub fn sections(
+        self,
+        sections: Sections,
+    ) -> WmiRemoteScannerBuilder<wmi_remote_scanner_builder::SetOptions<S>>
+    where
+        S::Options: wmi_remote_scanner_builder::IsUnset,
+    {
+        self.options(sections)
+    }
+}
+
+impl Scanner for WmiRemoteScanner {
+    async fn audit(&self) -> Result<AuditReport, ScanError> {
+        let started = Instant::now();
+        // WMI calls block on COM; keep them off the async runtime.
+        let host = self.host.clone();
+        let sections = self.options.selected();
+        let task = tokio::task::spawn_blocking(move || collect(&host, sections));
+
+        match tokio::time::timeout(self.timeout, task).await {
+            Ok(Ok(result)) => result.map(|mut report| {
+                report.score = Some(AuditScore::compute_with(
+                    &report,
+                    &self.options.effective_score_weights(),
+                ));
+                // DCOM connects as the calling process.
+                report.metadata = Some(ScanMetadata::new(
+                    ScannerKind::Remote,
+                    started,
+                    &self.options,
+                ));
+                self.options.apply_asset_id(&mut report);
+                self.options.redact(&mut report);
+                report
+            }),
+            Ok(Err(e)) => Err(ScanError::RemoteExecution {
+                host: self.host.clone(),
+                message: e.to_string(),
+            }),
+            Err(_) => {
+                tracing::warn!(host = %self.host, "DCOM scan timed out; abandoning its thread");
+                Err(ScanError::Timeout(self.timeout))
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Win32_OperatingSystem")]
+#[serde(rename_all = "PascalCase")]
+struct Win32OperatingSystem {
+    caption: Option<String>,
+    version: Option<String>,
+    #[serde(rename = "CSName")]
+    cs_name: Option<String>,
+    free_physical_memory: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Win32_UserAccount")]
+struct Win32UserAccount {
+    #[serde(rename = "SID")]
+    sid: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Win32_ComputerSystem")]
+#[serde(rename_all = "PascalCase")]
+struct Win32ComputerSystem {
+    manufacturer: Option<String>,
+    model: Option<String>,
+    domain: Option<String>,
+    part_of_domain: Option<bool>,
+    total_physical_memory: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Win32_BIOS")]
+#[serde(rename_all = "PascalCase")]
+struct Win32Bios {
+    serial_number: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Win32_Processor")]
+#[serde(rename_all = "PascalCase")]
+struct Win32Processor {
+    name: Option<String>,
+    number_of_cores: Option<u32>,
+    number_of_logical_processors: Option<u32>,
+    max_clock_speed: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Win32_NetworkAdapterConfiguration")]
+#[serde(rename_all = "PascalCase")]
+struct Win32NetworkAdapterConfiguration {
+    description: Option<String>,
+    #[serde(rename = "IPAddress")]
+    ip_address: Option<Vec<String>>,
+    #[serde(rename = "IPSubnet")]
+    ip_subnet: Option<Vec<String>>,
+    #[serde(rename = "DefaultIPGateway")]
+    default_ip_gateway: Option<Vec<String>>,
+    #[serde(rename = "MACAddress")]
+    mac_address: Option<String>,
+}
+
+/// Marker for static `StdRegProv` method calls.
+#[derive(Deserialize)]
+struct StdRegProv;
+
+#[derive(Serialize)]
+struct KeyIn<'a> {
+    #[serde(rename = "hDefKey")]
+    h_def_key: u32,
+    #[serde(rename = "sSubKeyName")]
+    sub_key_name: &'a str,
+}
+
+#[derive(Serialize)]
+struct ValueIn<'a> {
+    #[serde(rename = "hDefKey")]
+    h_def_key: u32,
+    #[serde(rename = "sSubKeyName")]
+    sub_key_name: &'a str,
+    #[serde(rename = "sValueName")]
+    value_name: &'a str,
+}
+
+#[derive(Deserialize)]
+struct EnumKeyOut {
+    #[serde(rename = "ReturnValue")]
+    return_value: u32,
+    #[serde(rename = "sNames")]
+    names: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct StringValueOut {
+    #[serde(rename = "ReturnValue")]
+    return_value: u32,
+    #[serde(rename = "sValue")]
+    value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DwordValueOut {
+    #[serde(rename = "ReturnValue")]
+    return_value: u32,
+    #[serde(rename = "uValue")]
+    value: Option<u32>,
+}
+
+fn collect(host: &str, sections: Sections) -> Result<AuditReport, ScanError> {
+    let connection_error = |e: WMIError| ScanError::RemoteConnection {
+        host: host.to_string(),
+        message: e.to_string(),
+    };
+    let query_error = |e: WMIError| ScanError::RemoteExecution {
+        host: host.to_string(),
+        message: e.to_string(),
+    };
+
+    tracing::info!(host, "Connecting to remote WMI");
+    let com_con = COMLibrary::new().map_err(connection_error)?;
+    let namespace = format!(r"\\{}\root\cimv2", host);
+    let wmi_con =
+        WMIConnection::with_namespace_path(&namespace, com_con).map_err(connection_error)?;
+
+    let system = system_info(&wmi_con).map_err(query_error)?;
+    // Industrial detection classifies the Uninstall entries.
+    let mut software = if sections.has_software() || sections.has_industrial() {
+        software(&wmi_con)
+    } else {
+        Vec::new()
+    };
+    let industrial: Vec<IndustrialSoftware> = if sections.has_industrial() {
+        let industrial_scanner = IndustrialScanner::default();
+        merge_industrial(classify_software(&software, industrial_scanner.vendors()))
+    } else {
+        Vec::new()
+    };
+    if !sections.has_software() {
+        software.clear();
+    }
+    let mut warnings = Vec::new();
+    let updates = if sections.has_updates() {
+        WindowsUpdate::query(&wmi_con).unwrap_or_else(|e| {
+            tracing::warn!(host, error = %e, "Could not query remote Windows Updates");
+            warnings.push(ScanWarning::new(
+                Collector::Updates,
+                format!("query failed: {}", e),
+            ));
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+
+    let mut report = AuditReport::from_local(system, software, industrial, updates);
+    if sections.has_services() {
+        report.services = WindowsService::query(&wmi_con).unwrap_or_else(|e| {
+            tracing::warn!(host, error = %e, "Could not query remote Windows services");
+            warnings.push(ScanWarning::new(
+                Collector::Services,
+                format!("query failed: {}", e),
+            ));
+            Vec::new()
+        });
+    }
+    if sections.has_security() {
+        // The password policy is only available to local scans.
+        let mut posture =
+            SecurityPosture::from_registry(|path, value| get_dword(&wmi_con, path, value));
+        // BitLocker lives in its own namespace, which needs a second
+        // connection.
+        let namespace = format!(r"\\{}\{}", host, BITLOCKER_NAMESPACE);
+        let bitlocker = WMIConnection::with_namespace_path(&namespace, com_con)
+            .map_err(Error::from)
+            .and_then(|con| SecurityPosture::query_bitlocker(&con));
+        match bitlocker {
+            Ok(bitlocker) => posture.bitlocker = bitlocker,
+            Err(e) => {
+                tracing::warn!(host, error = %e, "Could not query remote BitLocker status");
+                warnings.push(ScanWarning::new(
+                    Collector::Security,
+                    format!("BitLocker query failed: {}", e),
+                ));
+            }
+        }
+        let namespace = format!(r"\\{}\{}", host, DEFENDER_NAMESPACE);
+        let exclusions = WMIConnection::with_namespace_path(&namespace, com_con)
+            .map_err(Error::from)
+            .and_then(|con| AvExclusions::query(&con));
+        match exclusions {
+            Ok(exclusions) => posture.defender_exclusions = exclusions,
+            Err(e) => {
+                tracing::warn!(host, error = %e, "Could not read remote Defender exclusions");
+                warnings.push(ScanWarning::new(
+                    Collector::Security,
+                    format!("Defender exclusions query failed: {}", e),
+                ));
+            }
+        }
+        let namespace = format!(r"\\{}\{}", host, SECURITY_CENTER_NAMESPACE);
+        posture.antivirus = WMIConnection::with_namespace_path(&namespace, com_con)
+            .map_err(Error::from)
+            .and_then(|con| query_antivirus(&con))
+            .unwrap_or_default();
+        report.security = Some(posture);
+    }
+    if sections.has_update_health() {
+        let mut health = UpdateHealth::from_registry(
+            |path, value| get_dword(&wmi_con, path, value),
+            |path, value| get_string(&wmi_con, path, value),
+        );
+        let mut messages = Vec::new();
+        match WindowsService::query_named(&wmi_con, UPDATE_SERVICES) {
+            Ok(services) => health.set_services(services),
+            Err(e) => messages.push(format!("service query failed: {}", e)),
+        }
+        if let Some(sccm) = &mut health.sccm {
+            // The client's namespaces need their own connections.
+            let connect = |namespace: &str| {
+                WMIConnection::with_namespace_path(&format!(r"\\{}\{}", host, namespace), com_con)
+                    .map_err(Error::from)
+            };
+            messages.extend(sccm.query(connect(CCM_NAMESPACE), connect(CCM_SCHEDULER_NAMESPACE)));
+        }
+        for message in messages {
+            tracing::warn!(host, message, "Could not read remote update agent health");
+            warnings.push(ScanWarning::new(Collector::UpdateHealth, message));
+        }
+        report.update_health = Some(health);
+    }
+    if sections.has_licensing() {
+        let edition = get_string(&wmi_con, EDITION_KEY, "EditionID").filter(|s| !s.is_empty());
+        match Licensing::query(&wmi_con, edition) {
+            Ok(licensing) => report.licensing = Some(licensing),
+            Err(e) => {
+                tracing::warn!(host, error = %e, "Could not query remote licensing status");
+                warnings.push(ScanWarning::new(
+                    Collector::Licensing,
+                    format!("query failed: {}", e),
+                ));
+            }
+        }
+    }
+    if sections.has_event_logs() {
+        report.event_logs = Some(EventLogs::from_registry(
+            |path, value| get_dword(&wmi_con, path, value),
+            |path, value| get_string(&wmi_con, path, value).filter(|s| !s.is_empty()),
+        ));
+    }
+    if sections.has_autopilot() {
+        let autopilot =
+            WMIConnection::with_namespace_path(&format!(r"\\{}\{}", host, MDM_NAMESPACE), com_con)
+                .map_err(Error::from)
+                .and_then(|mdm| Autopilot::query(&wmi_con, &mdm));
+        match autopilot {
+            Ok(autopilot) => report.autopilot = Some(autopilot),
+            Err(e) => {
+                tracing::warn!(host, error = %e, "Could not query remote Autopilot data");
+                warnings.push(ScanWarning::new(
+                    Collector::Autopilot,
+                    format!("query failed: {}", e),
+                ));
+            }
+        }
+    }
+    if sections.has_containers() {
+        let root = |hive| match hive {
+            Hive::LocalMachine => HKEY_LOCAL_MACHINE,
+            Hive::Users => HKEY_USERS,
+        };
+        report.containers = Some(Containers::from_registry(
+            |hive, path| enum_keys_in(&wmi_con, root(hive), path).unwrap_or_default(),
+            |hive, path, value| get_string_in(&wmi_con, root(hive), path, value),
+            |hive, path, value| get_dword_in(&wmi_con, root(hive), path, value),
+        ));
+    }
+    report.warnings = warnings;
+    Ok(report)
+}
+
+fn system_info(wmi_con: &WMIConnection) -> Result<SystemInfo, WMIError> {
+    let os = wmi_con
+        .query::<Win32OperatingSystem>()?
+        .into_iter()
+        .next()
+        .ok_or(WMIError::ResultEmpty)?;
+    let computer = wmi_con
+        .query::<Win32ComputerSystem>()?
+        .into_iter()
+        .next()
+        .ok_or(WMIError::ResultEmpty)?;
+    let processors = wmi_con.query::<Win32Processor>()?;
+    let adapters: Vec<Win32NetworkAdapterConfiguration> = wmi_con.raw_query(
+        "SELECT Description, IPAddress, IPSubnet, DefaultIPGateway, MACAddress \
+         FROM Win32_NetworkAdapterConfiguration WHERE IPEnabled = TRUE",
+    )?;
+
+    let cpu = processors.first();
+    let sum_cores = |f: fn(&Win32Processor) -> Option<u32>| {
+        let cores: u32 = processors.iter().filter_map(f).sum();
+        (cores > 0).then_some(cores as usize)
+    };
+
+    let memory_total = computer.total_physical_memory.unwrap_or(0);
+    let memory_free = os.free_physical_memory.unwrap_or(0) * 1024;
+    let computer_name = os.cs_name.unwrap_or_else(|| "Unknown".to_string());
+    let fingerprint = AssetFingerprint {
+        machine_guid: get_string(wmi_con, r"SOFTWARE\Microsoft\Cryptography", "MachineGuid"),
+        bios_serial: wmi_con
+            .query::<Win32Bios>()
+            .ok()
+            .and_then(|bios| bios.into_iter().next())
+            .and_then(|bios| bios.serial_number),
+        mac_addresses: adapters
+            .iter()
+            .filter_map(|adapter| adapter.mac_address.clone())
+            .collect(),
+    };
+    let domain = computer
+        .domain
+        .filter(|_| computer.part_of_domain.unwrap_or(false));
+    // The suffix search list is not read remotely.
+    let mut identity = HostIdentity::new(&computer_name, domain.as_deref(), None);
+    identity.machine_sid = wmi_con
+        .raw_query::<Win32UserAccount>(
+            "SELECT SID FROM Win32_UserAccount WHERE LocalAccount = TRUE",
+        )
+        .ok()
+        .and_then(|accounts| {
+            accounts
+                .iter()
+                .filter_map(|account| account.sid.as_deref())
+                .find_map(machine_sid)
+        });
+    identity.installed_on = get_dword(
+        wmi_con,
+        r"SOFTWARE\Microsoft\Windows NT\CurrentVersion",
+        "InstallDate",
+    )
+    .and_then(install_time);
+
+    Ok(SystemInfo {
+        os_name: os.caption.unwrap_or_else(|| "Unknown".to_string()),
+        os_version: os.version.unwrap_or_else(|| "Unknown".to_string()),
+        build_number: build_number(wmi_con).unwrap_or_default(),
+        identity,
+        fingerprint,
+        computer_name,
+        domain,
+        cpu_info: cpu
+            .and_then(|c| c.name.as_deref())
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_string(),
+        network_interfaces: adapters.into_iter().flat_map(network_interfaces).collect(),
+        manufacturer: computer.manufacturer,
+        model: computer.model,
+        cpu_cores_physical: sum_cores(|c| c.number_of_cores),
+        cpu_cores_logical: sum_cores(|c| c.number_of_logical_processors),
+        cpu_frequency_mhz: cpu.and_then(|c| c.max_clock_speed).unwrap_or(0) as u64,
+        memory_total,
+        memory_used: memory_total.saturating_sub(memory_free),
+        memory_free,
+    })
+}
+
+/// One interface entry per IP address bound to the adapter.
+fn network_interfaces(adapter: Win32NetworkAdapterConfiguration) -> Vec<NetworkInterface> {
+    let subnets = adapter.ip_subnet.unwrap_or_default();
+    let gateway = adapter
+        .default_ip_gateway
+        .and_then(|g| g.into_iter().next());
+    let name = adapter.description.unwrap_or_default();
+
+    adapter
+        .ip_address
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, ip)| {
+            let ip_address: IpAddr = ip.parse().ok()?;
+            Some(NetworkInterface {
+                name: name.clone(),
+                ip_address,
+                subnet_mask: subnets.get(i).cloned(),
+                gateway: gateway.clone().filter(|g| {
+                    g.parse::<IpAddr>()
+                        .is_ok_and(|g| g.is_ipv4() == ip_address.is_ipv4())
+                }),
+                mac_address: adapter.mac_address.clone(),
+                additional_addresses: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn build_number(wmi_con: &WMIConnection) -> Option<String> {
+    const KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion";
+    let current_build = get_string(wmi_con, KEY, "CurrentBuild")?;
+    let ubr = get_dword(wmi_con, KEY, "UBR").unwrap_or(0);
+
+    if ubr > 0 {
+        Some(format!("{}.{}", current_build, ubr))
+    } else {
+        Some(current_build)
+    }
+}
+
+fn software(wmi_con: &WMIConnection) -> Vec<Software> {
+    let mut result = Vec::new();
+
+    for (path, source) in UNINSTALL_KEYS {
+        let names = match enum_keys(wmi_con, path) {
+            Ok(names) => names,
+            Err(e) => {
+                tracing::debug!(path, error = %e, "Could not enumerate remote Uninstall key");
+                continue;
+            }
+        };
+
+        for name in names {
+            let key = format!(r"{}\{}", path, name);
+            let mut values: HashMap<&str, String> = HashMap::new();
+            for value in [
+                "DisplayName",
+                "DisplayVersion",
+                "Publisher",
+                "InstallLocation",
+                "InstallDate",
+            ] {
+                if let Some(v) = get_string(wmi_con, &key, value) {
+                    values.insert(value, v);
+                }
+            }
+
+            let Some(display_name) = values.remove("DisplayName") else {
+                continue;
+            };
+            if let Some(software) = build_software(
+                display_name,
+                values.remove("DisplayVersion"),
+                values.remove("Publisher"),
+                values.remove("InstallLocation"),
+                values.remove("InstallDate"),
+                // StdRegProv does not expose key write times
+                None,
+                *source,
+            ) {
+                result.push(software);
+            }
+        }
+    }
+
+    result.sort_by_cached_key(|sw| sw.name.to_lowercase());
+    result
+}
+
+fn enum_keys(wmi_con: &WMIConnection, path: &str) -> Result<Vec<String>, WMIError> {
+    enum_keys_in(wmi_con, HKEY_LOCAL_MACHINE, path)
+}
+
+fn enum_keys_in(wmi_con: &WMIConnection, hive: u32, path: &str) -> Result<Vec<String>, WMIError> {
+    let out: EnumKeyOut = wmi_con.exec_class_method::<StdRegProv, EnumKeyOut>(
+        "EnumKey",
+        KeyIn {
+            h_def_key: hive,
+            sub_key_name: path,
+        },
+    )?;
+    if out.return_value != 0 {
+        return Ok(Vec::new());
+    }
+    Ok(out.names.unwrap_or_default())
+}
+
+fn get_string(wmi_con: &WMIConnection, path: &str, value: &str) -> Option<String> {
+    get_string_in(wmi_con, HKEY_LOCAL_MACHINE, path, value)
+}
+
+fn get_string_in(wmi_con: &WMIConnection, hive: u32, path: &str, value: &str) -> Option<String> {
+    wmi_con
+        .exec_class_method::<StdRegProv, StringValueOut>(
+            "GetStringValue",
+            ValueIn {
+                h_def_key: hive,
+                sub_key_name: path,
+                value_name: value,
+            },
+        )
+        .ok()
+        .filter(|out| out.return_value == 0)
+        .and_then(|out| out.value)
+        .filter(|s| !s.is_empty())
+}
+
+fn get_dword(wmi_con: &WMIConnection, path: &str, value: &str) -> Option<u32> {
+    get_dword_in(wmi_con, HKEY_LOCAL_MACHINE, path, value)
+}
+
+fn get_dword_in(wmi_con: &WMIConnection, hive: u32, path: &str, value: &str) -> Option<u32> {
+    wmi_con
+        .exec_class_method::<StdRegProv, DwordValueOut>(
+            "GetDWORDValue",
+            ValueIn {
+                h_def_key: hive,
+                sub_key_name: path,
+                value_name: value,
+            },
+        )
+        .ok()
+        .filter(|out| out.return_value == 0)
+        .and_then(|out| out.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_interfaces_per_address() {
+        let adapter = Win32NetworkAdapterConfiguration {
+            description: Some("Intel(R) Ethernet".to_string()),
+            ip_address: Some(vec![
+                "10.0.0.5".to_string(),
+                "fe80::1".to_string(),
+                "bogus".to_string(),
+            ]),
+            ip_subnet: Some(vec!["255.255.255.0".to_string(), "64".to_string()]),
+            default_ip_gateway: Some(vec!["10.0.0.1".to_string()]),
+            mac_address: Some("AC:B4:80:D6:59:1D".to_string()),
+        };
+
+        let interfaces = network_interfaces(adapter);
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[0].subnet_mask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(interfaces[0].gateway.as_deref(), Some("10.0.0.1"));
+        assert_eq!(interfaces[1].subnet_mask.as_deref(), Some("64"));
+        assert_eq!(interfaces[1].gateway, None);
+        assert_eq!(interfaces[1].name, "Intel(R) Ethernet");
+    }
+}
@@ -0,0 +1,210 @@
+//! Turning a failed remote command into a useful [`ScanError`].
+//!
+//! PowerShell reports errors on stderr as CLIXML (`#< CLIXML` followed by
+//! serialized `<S S="Error">` records). The records are decoded to plain
+//! text and matched against well-known failures so callers get the cause
+//! and a hint instead of a wall of XML.
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::scanner::ScanError;
+
+/// Marker PowerShell writes before a serialized error stream.
+const CLIXML_HEADER: &str = "#< CLIXML";
+
+const EXECUTION_POLICY_HINT: &str = "PowerShell on the target refuses unsigned scripts; use \
+     ExecutionMode::agent (--agent) or the wmi transport";
+const ACCESS_DENIED_HINT: &str = "the account needs local administrator or Remote Management \
+     Users membership plus WMI and registry read rights";
+const MODULE_MISSING_HINT: &str = "a cmdlet the payload uses is unavailable on this Windows \
+     version; use ExecutionMode::agent (--agent) or the wmi transport";
+
+/// Decode a stderr stream into plain text, expanding CLIXML error records.
+pub(crate) fn decode_stderr(stderr: &[u8]) -> String {
+    let text = String::from_utf8_lossy(stderr);
+    let Some(xml) = text.trim_start().strip_prefix(CLIXML_HEADER) else {
+        return text.trim().to_string();
+    };
+
+    let mut reader = Reader::from_str(xml);
+    let mut in_error = false;
+    let mut errors = String::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(start)) => {
+                in_error = start.local_name().as_ref() == b"S"
+                    && start.attributes().flatten().any(|attr| {
+                        attr.key.local_name().as_ref() == b"S" && attr.value.as_ref() == b"Error"
+                    });
+            }
+            Ok(Event::Text(chunk)) if in_error => {
+                if let Ok(chunk) = chunk.unescape() {
+                    errors.push_str(&chunk);
+                }
+            }
+            Ok(Event::End(_)) => in_error = false,
+            Ok(Event::Eof) => break,
+            // Truncated or malformed: keep what was decoded so far.
+            Err(_) if !errors.is_empty() => break,
+            Err(_) => return text.trim().to_string(),
+            _ => {}
+        }
+    }
+
+    unescape_clixml(&errors)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Expand CLIXML `_xHHHH_` character escapes (e.g. `_x000D__x000A_`).
+fn unescape_clixml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("_x") {
+        out.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let decoded = candidate
+            .get(2..6)
+            .filter(|_| candidate.as_bytes().get(6) == Some(&b'_'))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32);
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &candidate[7..];
+            }
+            None => {
+                out.push_str("_x");
+                rest = &candidate[2..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Map a failed command's exit code and decoded stderr to the matching
+/// error.
+pub(crate) fn classify(host: &str, exit_code: i32, stderr: &str) -> ScanError {
+    let lower = stderr.to_lowercase();
+    let host = host.to_string();
+    let message = first_line(stderr, exit_code);
+
+    if [
+        "execution policy",
+        "running scripts is disabled",
+        "pssecurityexception",
+        "language mode",
+        "malicious content",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+    {
+        ScanError::ExecutionPolicy {
+            host,
+            message,
+            hint: EXECUTION_POLICY_HINT.to_string(),
+        }
+    } else if [
+        "access is denied",
+        "unauthorizedaccessexception",
+        "0x80070005",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+    {
+        ScanError::AccessDenied {
+            host,
+            message,
+            hint: ACCESS_DENIED_HINT.to_string(),
+        }
+    } else if [
+        "is not recognized as the name of a cmdlet",
+        "commandnotfoundexception",
+        "no valid module file",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+        || (lower.contains("module") && lower.contains("could not be loaded"))
+    {
+        ScanError::ModuleMissing {
+            host,
+            message,
+            hint: MODULE_MISSING_HINT.to_string(),
+        }
+    } else {
+        let message = if stderr.is_empty() {
+            message
+        } else {
+            format!("command exited with code {}: {}", exit_code, stderr)
+        };
+        ScanError::RemoteExecution { host, message }
+    }
+}
+
+/// The first line of an error record, which carries the actual message.
+fn first_line(stderr: &str, exit_code: i32) -> String {
+    stderr
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(String::from)
+        .unwrap_or_else(|| format!("command exited with code {}", exit_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_clixml_stderr() {
+        let stderr = concat!(
+            "#< CLIXML\r\n",
+            r#"<Objs Version="1.1.0.1" xmlns="http://schemas.microsoft.com/powershell/2004/04">"#,
+            r#"<Obj S="progress" RefId="0"><TN RefId="0"><T>System.Management.Automation.PSCustomObject</T></TN></Obj>"#,
+            r#"<S S="Error">File C:\x.ps1 cannot be loaded because running scripts is disabled on this system._x000D__x000A_</S>"#,
+            r#"<S S="Error">    + FullyQualifiedErrorId : UnauthorizedAccess_x000D__x000A_</S>"#,
+            r#"</Objs>"#
+        );
+
+        let decoded = decode_stderr(stderr.as_bytes());
+        assert_eq!(
+            decoded,
+            "File C:\\x.ps1 cannot be loaded because running scripts is disabled on this system.\n\
+             + FullyQualifiedErrorId : UnauthorizedAccess"
+        );
+        assert_eq!(decode_stderr(b"  plain failure \r\n"), "plain failure");
+        assert_eq!(unescape_clixml("a_x0009_b_xZZ"), "a\tb_xZZ");
+    }
+
+    #[test]
+    fn test_classify() {
+        let error = classify(
+            "hmi01",
+            1,
+            "File C:\\x.ps1 cannot be loaded because running scripts is disabled on this system.",
+        );
+        assert!(matches!(error, ScanError::ExecutionPolicy { ref host, .. } if host == "hmi01"));
+
+        let error = classify("hmi01", 1, "Get-CimInstance : Access is denied.");
+        assert!(matches!(error, ScanError::AccessDenied { .. }));
+        assert_eq!(error.kind(), crate::ScanErrorKind::Auth);
+
+        let error = classify(
+            "hmi01",
+            1,
+            "Get-NetAdapter : The term 'Get-NetAdapter' is not recognized as the name of a cmdlet",
+        );
+        assert!(matches!(error, ScanError::ModuleMissing { .. }));
+
+        let error = classify("hmi01", 3, "something else");
+        assert_eq!(
+            error.to_string(),
+            "remote execution error on hmi01: command exited with code 3: something else"
+        );
+    }
+}
@@ -1,18 +1,94 @@
 //! PowerShell payload executed remotely via WinRM.
+//!
+//! WinRM truncates very large stdout streams, so the payload gzips its JSON,
+//! Base64-encodes it and writes it as numbered lines of at most
+//! [`CHUNK_SIZE`] characters:
+//!
+//! ```text
+//! SYSAUDIT-CHUNK 1/3 H4sIAAAAAAAEAO1d...
+//! ```
+//!
+//! [`decode_output`] reassembles and decompresses them; [`encode_output`]
+//! writes the same format for `sysaudit-agent`.
+//!
+//! [`WINRM_PAYLOAD`] is a template: [`render_payload`] fills in the
+//! [`Sections`] to collect, so quick health checks can skip the slow
+//! collectors, and the chunk size.
 
-/// The PowerShell script that collects system, software, and industrial data.
-/// It outputs a JSON string matching the `SysauditReport` structure.
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{Read, Write};
+
+use crate::options::Sections;
+
+/// Marker starting every chunk line.
+pub const CHUNK_PREFIX: &str = "SYSAUDIT-CHUNK";
+
+/// Base64 characters per chunk line.
+pub const CHUNK_SIZE: usize = 8000;
+
+/// Most chunks [`decode_output`] accepts (about 500 MB of Base64), so a
+/// corrupt or hostile chunk total cannot force a huge allocation.
+const MAX_CHUNKS: usize = 65_536;
+
+/// Placeholder in [`WINRM_PAYLOAD`] replaced with the section list.
+const SECTIONS_PLACEHOLDER: &str = "__SYSAUDIT_SECTIONS__";
+
+/// Placeholder in [`WINRM_PAYLOAD`] replaced with [`CHUNK_SIZE`].
+const CHUNK_SIZE_PLACEHOLDER: &str = "__SYSAUDIT_CHUNK_SIZE__";
+
+/// Render [`WINRM_PAYLOAD`] for the given sections.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::Sections;
+/// use sysaudit::remote::payload::render_payload;
+///
+/// let script = render_payload(Sections::system_only());
+/// assert!(script.contains("Get-HardwareInfo"));
+/// ```
+pub fn render_payload(sections: Sections) -> String {
+    WINRM_PAYLOAD
+        .replace(SECTIONS_PLACEHOLDER, &sections.names().join(","))
+        .replace(CHUNK_SIZE_PLACEHOLDER, &CHUNK_SIZE.to_string())
+}
+
+/// PowerShell bootstrap reading the payload from stdin and running it.
+const STDIN_BOOTSTRAP: &str = "$script = $input | Out-String; & ([ScriptBlock]::Create($script))";
+
+/// Command line running a script fed over stdin (see
+/// [`WinrmTransport::execute_script`](crate::remote::transport::WinrmTransport::execute_script)).
+///
+/// The full payload would exceed Windows' 32,767-character command line
+/// limit as an `-EncodedCommand`, so only a short bootstrap is passed,
+/// Base64-encoded as UTF-16LE, as PowerShell expects. It runs without
+/// loading a profile.
+pub(crate) fn powershell_command() -> String {
+    let utf16_bytes: Vec<u8> = STDIN_BOOTSTRAP
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    format!(
+        "powershell -NonInteractive -NoProfile -EncodedCommand {}",
+        STANDARD.encode(&utf16_bytes)
+    )
+}
+
+/// The PowerShell script template that collects system information and the
+/// selected sections (see [`render_payload`]). It outputs a JSON string
+/// matching the [`AuditReport`](crate::AuditReport) structure, gzipped and
+/// split into [`CHUNK_PREFIX`] lines.
 pub const WINRM_PAYLOAD: &str = r#"
 $ErrorActionPreference = "Stop"
+$Sections = "__SYSAUDIT_SECTIONS__" -split ","
 
 function Get-HardwareInfo {
     $os = Get-CimInstance Win32_OperatingSystem
     $cs = Get-CimInstance Win32_ComputerSystem
     $cpu = Get-CimInstance Win32_Processor | Select-Object -First 1
-    
-    # Calculate Build Number + UBR
-    $regCurrentVersion = Get-ItemProperty "HKLM:\SOFTWARE\Microsoft\Windows NT\CurrentVersion"
-    $build = if ($null -ne $regCurrentVersion.UBR) { "$($regCurrentVersion.CurrentBuild).$($regCurrentVersion.UBR)" } else { $regCurrentVersion.CurrentBuild }
 
     # Calculate Used RAM
     $totalRam = $cs.TotalPhysicalMemory
@@ -50,6 +126,55 @@ function Get-HardwareInfo {
     return $system
 }
 
+function Get-OsBuild {
+    # Build Number + UBR
+    $regCurrentVersion = Get-ItemProperty "HKLM:\SOFTWARE\Microsoft\Windows NT\CurrentVersion"
+    if ($null -ne $regCurrentVersion.UBR) { "$($regCurrentVersion.CurrentBuild).$($regCurrentVersion.UBR)" } else { $regCurrentVersion.CurrentBuild }
+}
+
+function Get-AssetId {
+    # Same sources, order and hash as AssetFingerprint::asset_id in asset.rs
+    $placeholders = @("0", "00000000", "0123456789", "123456789", "DEFAULT STRING", "NONE",
+        "NOT APPLICABLE", "NOT SPECIFIED", "O.E.M.", "SYSTEM SERIAL NUMBER", "TO BE FILLED BY O.E.M.")
+    $key = $null
+    $bios = Get-CimInstance Win32_BIOS -ErrorAction SilentlyContinue | Select-Object -First 1
+    $serial = "$($bios.SerialNumber)".Trim().ToUpperInvariant()
+    if ($serial -and $placeholders -notcontains $serial) { $key = "bios:$serial" }
+    if (-not $key) {
+        $macs = @(Get-NetAdapter -ErrorAction SilentlyContinue |
+            Where-Object { $_.MacAddress } |
+            ForEach-Object { $_.MacAddress.ToUpperInvariant().Replace("-", ":") } |
+            Where-Object { $_ -ne "00:00:00:00:00:00" -and ([Convert]::ToByte($_.Substring(0, 2), 16) -band 2) -eq 0 } |
+            Sort-Object -Unique)
+        if ($macs.Count -gt 0) { $key = "mac:" + ($macs -join ",") }
+    }
+    if (-not $key) {
+        $crypto = Get-ItemProperty "HKLM:\SOFTWARE\Microsoft\Cryptography" -ErrorAction SilentlyContinue
+        $guid = "$($crypto.MachineGuid)".Trim().Trim("{", "}").ToLowerInvariant()
+        if ($guid) { $key = "guid:$guid" }
+    }
+    if (-not $key) { return $null }
+    $sha = [System.Security.Cryptography.SHA256]::Create()
+    $digest = $sha.ComputeHash([System.Text.Encoding]::UTF8.GetBytes($key))
+    $hex = -join ($digest[0..15] | ForEach-Object { $_.ToString("x2") })
+    return "v1-{0}-{1}-{2}-{3}-{4}" -f $hex.Substring(0, 8), $hex.Substring(8, 4), $hex.Substring(12, 4), $hex.Substring(16, 4), $hex.Substring(20, 12)
+}
+
+function Get-HostIdentity {
+    # Machine SID (a local account's SID without its RID) and install time,
+    # which give away hosts cloned from one image
+    $identity = [ordered]@{}
+    $account = Get-CimInstance Win32_UserAccount -Filter "LocalAccount = TRUE" -ErrorAction SilentlyContinue |
+        Where-Object { $_.SID -match '^S-1-5-21-\d+-\d+-\d+-\d+$' } | Select-Object -First 1
+    if ($account) { $identity.machine_sid = $account.SID.ToUpperInvariant() -replace '-\d+$', '' }
+    $installDate = (Get-ItemProperty "HKLM:\SOFTWARE\Microsoft\Windows NT\CurrentVersion" -ErrorAction SilentlyContinue).InstallDate
+    if ($installDate) {
+        $identity.installed_on = [DateTimeOffset]::FromUnixTimeSeconds([int64]$installDate).UtcDateTime.ToString("yyyy-MM-ddTHH:mm:ssZ")
+    }
+    if ($identity.Count -eq 0) { return $null }
+    return $identity
+}
+
 function Get-InstalledSoftware {
     $software = @()
     $paths = @(
@@ -79,34 +204,724 @@ function Get-InstalledSoftware {
     return $software
 }
 
+function Get-IndustrialVendor([string]$Name) {
+    # Same patterns as classify_industrial in industrial.rs
+    $n = $Name.ToLowerInvariant()
+    if ($n.Contains("citect") -or ($n.Contains("aveva") -and $n.Contains("scada"))) { return "Citect" }
+    if ($n.Contains("digifort")) { return "Digifort" }
+    if ($n.Contains("abb") -and ($n.Contains("automation") -or $n.Contains("builder"))) { return "ABB" }
+    if ($n.Contains("rockwell") -or $n.Contains("allen-bradley") -or $n.Contains("studio 5000")) { return "Rockwell" }
+    if ($n.Contains("simatic") -or $n.Contains("tia portal") -or $n.Contains("wincc")) { return "Siemens" }
+    if ($n.Contains("schneider") -and $n.Contains("electric")) { return "Schneider Electric" }
+    if ($n.Contains("pc worx") -or $n.Contains("plcnext engineer")) { return "Phoenix Contact" }
+    if ($n.Contains("e!cockpit") -or ($n.Contains("wago") -and $n.Contains("codesys"))) { return "WAGO" }
+    if ($n.Contains("festo automation suite")) { return "Festo" }
+    if ($n.Contains("moxa") -or $n.StartsWith("nport")) { return "Moxa" }
+    if ($n.Contains("anybus") -or $n.Contains("ixxat")) { return "HMS" }
+    if ($n.Contains("opc router")) { return "inray" }
+    if ($n.Contains("datahub") -and ($n.Contains("cogent") -or $n.Contains("skkynet"))) { return "Skkynet" }
+    if ($n.Contains("kepware") -or $n.Contains("kepserverex") -or $n.Contains("linkmaster")) { return "Kepware" }
+    if ($n.Contains("hivemq")) { return "HiveMQ" }
+    if ($n.Contains("mosquitto")) { return "Mosquitto" }
+    if ($n.Contains("node-red")) { return "Node-RED" }
+    return $null
+}
+
+function New-IndustrialEntry($Vendor, $Product, $Version, $InstallPath) {
+    return [ordered]@{
+        vendor = $Vendor
+        product = $Product
+        version = if ($Version) { $Version.ToString() } else { $null }
+        install_path = if ($InstallPath) { $InstallPath.ToString() } else { $null }
+    }
+}
+
 function Get-IndustrialSoftware {
-    # Stubbed implementation based on LocalScanner logic
+    # Mirrors IndustrialScanner::scan: vendor registry keys, then the
+    # Uninstall keys matched by name
     $industrial = @()
-    
+
+    # Citect / AVEVA Plant SCADA
+    $citectPath = "HKLM:\SOFTWARE\WOW6432Node\Citect\SCADA Installs"
+    if (Test-Path $citectPath) {
+        Get-ChildItem $citectPath -ErrorAction SilentlyContinue | ForEach-Object {
+            $iniPath = (Get-ItemProperty $_.PSPath -ErrorAction SilentlyContinue).DefaultINIPath
+            $industrial += New-IndustrialEntry "Citect" "AVEVA Plant SCADA $($_.PSChildName)" $_.PSChildName $iniPath
+        }
+    }
+
+    # Digifort
+    if ((Test-Path "HKLM:\SOFTWARE\Digifort") -or (Test-Path "HKCU:\Software\Digifort")) {
+        $industrial += New-IndustrialEntry "Digifort" "Digifort VMS" $null $null
+    }
+
     # Rockwell
     $rockwellPath = "HKLM:\SOFTWARE\WOW6432Node\Rockwell Software"
     if (Test-Path $rockwellPath) {
         Get-ChildItem $rockwellPath -ErrorAction SilentlyContinue | ForEach-Object {
-            $industrial += [ordered]@{
-                vendor = "Rockwell"
-                product = $_.PSChildName
+            $industrial += New-IndustrialEntry "Rockwell" $_.PSChildName $null $null
+        }
+    }
+
+    # Schneider Electric
+    $schneiderPath = "HKCU:\Software\Schneider Electric"
+    if (Test-Path $schneiderPath) {
+        Get-ChildItem $schneiderPath -ErrorAction SilentlyContinue | ForEach-Object {
+            $industrial += New-IndustrialEntry "Schneider Electric" $_.PSChildName $null $null
+        }
+    }
+
+    # Moxa NPort Real COM ports, as in virtual_com_port
+    $serialComm = Get-Item "HKLM:\HARDWARE\DEVICEMAP\SERIALCOMM" -ErrorAction SilentlyContinue
+    if ($serialComm) {
+        foreach ($device in $serialComm.GetValueNames()) {
+            $d = $device.ToLowerInvariant()
+            if ($d.StartsWith("\device\npdrv") -or $d.StartsWith("\device\npser")) {
+                $port = "$($serialComm.GetValue($device))".Trim()
+                $industrial += New-IndustrialEntry "Moxa" "NPort Real COM $port ($device)" $null $null
+            }
+        }
+    }
+
+    # Node-RED installed with npm into each user's profile
+    Get-ChildItem "$env:SystemDrive\Users" -Directory -ErrorAction SilentlyContinue | ForEach-Object {
+        $nodeRed = Join-Path $_.FullName "AppData\Roaming\npm\node_modules\node-red"
+        $package = Get-Content (Join-Path $nodeRed "package.json") -Raw -ErrorAction SilentlyContinue | ConvertFrom-Json -ErrorAction SilentlyContinue
+        if ($package -and $package.name -eq "node-red") {
+            $industrial += New-IndustrialEntry "Node-RED" "Node-RED" $package.version $nodeRed
+        }
+    }
+
+    # ABB, Siemens, Phoenix Contact, WAGO, Festo and the rest register only in the Uninstall keys
+    $paths = @(
+        "HKLM:\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\*",
+        "HKLM:\SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall\*"
+    )
+    foreach ($path in $paths) {
+        $keys = Get-ItemProperty $path -ErrorAction SilentlyContinue | Where-Object DisplayName -ne $null
+        foreach ($key in $keys) {
+            $vendor = Get-IndustrialVendor $key.DisplayName
+            if ($null -ne $vendor) {
+                $industrial += New-IndustrialEntry $vendor $key.DisplayName $key.DisplayVersion $key.InstallLocation
+            }
+        }
+    }
+
+    # Merge detections of the same install (mirrors merge_industrial):
+    # side-by-side versions stay separate, duplicates fill in each other's
+    # version and install path
+    $unique = @()
+    foreach ($entry in $industrial) {
+        $match = $unique | Where-Object { Test-SameInstall $_ $entry } | Select-Object -First 1
+        if ($null -eq $match) {
+            $unique += $entry
+            continue
+        }
+        if ($null -eq $match.version) { $match.version = $entry.version }
+        if ($null -eq $match.install_path) { $match.install_path = $entry.install_path }
+    }
+    return @($unique | Sort-Object -CaseSensitive { $_.product }, { $_.version })
+}
+
+function Get-ProductKey([string]$Product, [string]$Version) {
+    $arch = @('x64', 'x86', '64', '32', 'bit', '64bit', '32bit')
+    $tokens = $Product.ToLowerInvariant() -split '[^\p{L}\p{N}.]+' |
+        Where-Object { $_ -and ($arch -notcontains $_) -and ($_ -ne $Version) }
+    return ($tokens -join ' ')
+}
+
+function Test-SameInstall($A, $B) {
+    if ($A.vendor -ne $B.vendor) { return $false }
+    if ((Get-ProductKey $A.product $A.version) -cne (Get-ProductKey $B.product $B.version)) { return $false }
+    if ($A.version -and $B.version -and ($A.version -ne $B.version)) { return $false }
+    if ($A.install_path -and $B.install_path -and
+        ($A.install_path.TrimEnd('\', '/') -ne $B.install_path.TrimEnd('\', '/'))) { return $false }
+    return $true
+}
+
+function Get-Updates {
+    $updates = @()
+    Get-HotFix -ErrorAction SilentlyContinue | Where-Object { $_.HotFixID } | ForEach-Object {
+        $updates += [ordered]@{
+            hotfix_id = $_.HotFixID
+            description = if ($_.Description) { $_.Description } else { $null }
+            installed_on = if ($null -ne $_.InstalledOn) { $_.InstalledOn.ToString("yyyy-MM-dd") } else { $null }
+            installed_by = if ($_.InstalledBy) { $_.InstalledBy } else { $null }
+        }
+    }
+    return $updates
+}
+
+function Get-Services {
+    $services = @()
+    Get-CimInstance Win32_Service -ErrorAction SilentlyContinue | Where-Object { $_.Name } | ForEach-Object {
+        $services += [ordered]@{
+            name = $_.Name
+            display_name = if ($_.DisplayName) { $_.DisplayName } else { $null }
+            state = if ($_.State) { $_.State } else { $null }
+            start_mode = if ($_.StartMode) { $_.StartMode } else { $null }
+            account = if ($_.StartName) { $_.StartName } else { $null }
+            path = if ($_.PathName) { $_.PathName } else { $null }
+        }
+    }
+    return $services
+}
+
+function Get-UpdateHealth {
+    # Mirrors UpdateHealth::collect
+    function Get-ServiceEntry($Name) {
+        $s = Get-CimInstance Win32_Service -Filter "Name = '$Name'" -ErrorAction SilentlyContinue
+        if (-not $s) { return $null }
+        return [ordered]@{
+            name = $s.Name
+            display_name = if ($s.DisplayName) { $s.DisplayName } else { $null }
+            state = if ($s.State) { $s.State } else { $null }
+            start_mode = if ($s.StartMode) { $s.StartMode } else { $null }
+            account = if ($s.StartName) { $s.StartName } else { $null }
+            path = if ($s.PathName) { $s.PathName } else { $null }
+        }
+    }
+    function Get-Value($Key, $Name) {
+        $value = (Get-ItemProperty -Path "HKLM:\$Key" -Name $Name -ErrorAction SilentlyContinue).$Name
+        if ($null -eq $value -or "$value" -eq "") { return $null }
+        return $value
+    }
+    $wu = "SOFTWARE\Policies\Microsoft\Windows\WindowsUpdate"
+    $useWsus = Get-Value "$wu\AU" "UseWUServer"
+    $noAuto = Get-Value "$wu\AU" "NoAutoUpdate"
+    $sccm = $null
+    $client = "SOFTWARE\Microsoft\SMS\Mobile Client"
+    $version = Get-Value $client "ProductVersion"
+    $site = Get-Value $client "AssignedSiteCode"
+    if ($null -ne $version -or $null -ne $site) {
+        $mp = Get-CimInstance -Namespace "root/ccm" -ClassName SMS_Authority -ErrorAction SilentlyContinue | Select-Object -First 1
+        $policy = Get-CimInstance -Namespace "root/ccm/Scheduler" -ClassName CCM_Scheduler_History -Filter "ScheduleID = '{00000000-0000-0000-0000-000000000021}' AND UserSID = 'Machine'" -ErrorAction SilentlyContinue |
+            Sort-Object LastTriggerTime -Descending | Select-Object -First 1
+        $sccm = [ordered]@{
+            version = $version
+            site_code = $site
+            management_point = if ($mp -and $mp.CurrentManagementPoint) { $mp.CurrentManagementPoint } else { $null }
+            last_policy_evaluation = if ($policy -and $policy.LastTriggerTime) { $policy.LastTriggerTime.ToUniversalTime().ToString("yyyy-MM-ddTHH:mm:ssZ") } else { $null }
+        }
+    }
+    return [ordered]@{
+        windows_update = Get-ServiceEntry "wuauserv"
+        bits = Get-ServiceEntry "BITS"
+        wsus_server = if ($useWsus -eq 1) { Get-Value $wu "WUServer" } else { $null }
+        auto_update_disabled = if ($null -eq $noAuto) { $null } else { $noAuto -ne 0 }
+        sccm = $sccm
+    }
+}
+
+function Get-Licensing {
+    # Mirrors Licensing::collect; Windows 7 has no ProductKeyChannel
+    $products = @(Get-CimInstance SoftwareLicensingProduct -Filter "PartialProductKey IS NOT NULL" -ErrorAction SilentlyContinue)
+    $licenses = @()
+    foreach ($p in $products) {
+        $keyChannel = if ($p.PSObject.Properties["ProductKeyChannel"] -and $p.ProductKeyChannel) { $p.ProductKeyChannel } else { $null }
+        $channelText = $keyChannel
+        if (-not $channelText -and $p.Description -match ',\s*([^,]+) channel$') { $channelText = $Matches[1] }
+        $channel = $null
+        if ($channelText) {
+            $upper = $channelText.ToUpperInvariant()
+            if ($upper.StartsWith("RETAIL")) { $channel = "retail" }
+            elseif ($upper.StartsWith("OEM")) { $channel = "oem" }
+            elseif ($upper.StartsWith("VOLUME")) { $channel = "volume" }
+        }
+        $statuses = @("unlicensed", "licensed", "initial_grace", "additional_grace", "non_genuine_grace", "notification", "extended_grace")
+        $code = [int]$p.LicenseStatus
+        $licenses += [ordered]@{
+            name = "$($p.Name)"
+            description = if ($p.Description) { $p.Description } else { $null }
+            application_id = if ($p.ApplicationID) { $p.ApplicationID } else { $null }
+            channel = $channel
+            key_channel = $keyChannel
+            status = if ($code -ge 0 -and $code -lt $statuses.Count) { $statuses[$code] } else { "unknown" }
+            partial_product_key = if ($p.PartialProductKey) { $p.PartialProductKey } else { $null }
+        }
+    }
+    $edition = (Get-ItemProperty "HKLM:\SOFTWARE\Microsoft\Windows NT\CurrentVersion" -ErrorAction SilentlyContinue).EditionID
+    return [ordered]@{
+        edition = if ($edition) { $edition } else { $null }
+        licenses = @($licenses)
+    }
+}
+
+function Get-EventLogs {
+    # Mirrors EventLogs::from_registry; Group Policy wins over the log's own key
+    function Get-Value($Key, $Name) {
+        $value = (Get-ItemProperty -Path "HKLM:\$Key" -Name $Name -ErrorAction SilentlyContinue).$Name
+        if ($null -eq $value -or "$value" -eq "") { return $null }
+        return $value
+    }
+    $logs = @()
+    foreach ($name in @("Application", "Security", "Setup", "System")) {
+        $local = if ($name -eq "Setup") { "SOFTWARE\Microsoft\Windows\CurrentVersion\WINEVT\Channels\$name" } else { "SYSTEM\CurrentControlSet\Services\EventLog\$name" }
+        $policy = "SOFTWARE\Policies\Microsoft\Windows\EventLog\$name"
+        $policySize = Get-Value $policy "MaxSize"
+        $policyRetain = Get-Value $policy "Retention"
+        $policyArchive = Get-Value $policy "AutoBackupLogFiles"
+        $size = if ($null -ne $policySize) { [int64]$policySize } else { $bytes = Get-Value $local "MaxSize"; if ($null -ne $bytes) { [int64][Math]::Floor([uint32]$bytes / 1024) } else { $null } }
+        # A DWORD of 0xFFFFFFFF reads back as -1
+        $retain = if ($null -ne $policyRetain) { "$policyRetain".Trim() -eq "1" } else { $r = Get-Value $local "Retention"; if ($null -ne $r) { $r -eq -1 } else { $null } }
+        $archive = if ($null -ne $policyArchive) { "$policyArchive".Trim() -eq "1" } else { $a = Get-Value $local "AutoBackupLogFiles"; if ($null -ne $a) { $a -ne 0 } else { $null } }
+        $retention = if ($archive -eq $true) { "archive" } elseif ($retain -eq $true) { "do_not_overwrite" } elseif ($retain -eq $false) { "overwrite" } else { $null }
+        $logs += [ordered]@{
+            name = $name
+            max_size_kb = $size
+            retention = $retention
+            policy = ($null -ne $policySize) -or ($null -ne $policyRetain) -or ($null -ne $policyArchive)
+        }
+    }
+    $managers = @()
+    $key = "SOFTWARE\Policies\Microsoft\Windows\EventLog\EventForwarding\SubscriptionManager"
+    for ($i = 1; ; $i++) {
+        $entry = Get-Value $key "$i"
+        if ($null -eq $entry) { break }
+        $server = "$entry".Trim()
+        foreach ($part in "$entry".Split(",")) {
+            $pair = $part.Split("=", 2)
+            if ($pair.Count -eq 2 -and $pair[0].Trim() -eq "Server") { $server = $pair[1].Trim(); break }
+        }
+        if ($server) { $managers += $server }
+    }
+    return [ordered]@{
+        logs = @($logs)
+        subscription_managers = @($managers)
+    }
+}
+
+function Get-Autopilot {
+    # Mirrors Autopilot::query; the MDM bridge needs administrator rights
+    function Get-Text($Value) {
+        if ($null -eq $Value -or "$Value".Trim() -eq "") { return $null }
+        return "$Value".Trim()
+    }
+    $bios = Get-CimInstance Win32_BIOS -ErrorAction SilentlyContinue | Select-Object -First 1
+    $computer = Get-CimInstance Win32_ComputerSystem -ErrorAction SilentlyContinue | Select-Object -First 1
+    $detail = Get-CimInstance -Namespace "root/cimv2/mdm/dmmap" -ClassName MDM_DevDetail_Ext01 -Filter "InstanceID='Ext' AND ParentID='./DevDetail'" -ErrorAction SilentlyContinue | Select-Object -First 1
+    return [ordered]@{
+        serial_number = Get-Text $bios.SerialNumber
+        manufacturer = Get-Text $computer.Manufacturer
+        model = Get-Text $computer.Model
+        hardware_hash = Get-Text $detail.DeviceHardwareData
+    }
+}
+
+function Get-Containers {
+    # Mirrors Containers::from_registry; other users' hives need administrator rights
+    function Get-ServiceBinary($Name) {
+        $service = Get-ItemProperty -Path "HKLM:\SYSTEM\CurrentControlSet\Services\$Name" -ErrorAction SilentlyContinue
+        if ($null -eq $service -or $null -eq $service.Start) { return $null }
+        $image = "$($service.ImagePath)".Trim()
+        if ($image.StartsWith('"')) { return $image.Substring(1).Split('"')[0] }
+        return ($image -split " -", 2)[0].Trim()
+    }
+    $packageVersion = $null
+    $runtimes = @()
+    foreach ($entry in @(Get-ChildItem "HKLM:\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall" -ErrorAction SilentlyContinue)) {
+        $p = Get-ItemProperty -Path $entry.PSPath -ErrorAction SilentlyContinue
+        if (-not $p.DisplayName) { continue }
+        $version = if ($p.DisplayVersion) { "$($p.DisplayVersion)" } else { $null }
+        if ("$($p.DisplayName)".Trim() -eq "Windows Subsystem for Linux") { $packageVersion = $version }
+        elseif ("$($p.DisplayName)" -like "Docker Desktop*") {
+            $runtimes += [ordered]@{
+                kind = "docker_desktop"
+                version = $version
+                path = if ($p.InstallLocation) { "$($p.InstallLocation)" } else { $null }
+            }
+        }
+    }
+    foreach ($runtime in @(@("docker", "docker_engine"), @("containerd", "containerd"))) {
+        $binary = Get-ServiceBinary $runtime[0]
+        if ($null -ne $binary) {
+            $runtimes += [ordered]@{
+                kind = $runtime[1]
                 version = $null
-                install_path = $null
+                path = if ($binary) { $binary } else { $null }
             }
         }
     }
-    
-    return $industrial
+    $distributions = @()
+    foreach ($user in @(Get-ChildItem "Registry::HKEY_USERS" -ErrorAction SilentlyContinue)) {
+        $sid = $user.PSChildName
+        if (-not $sid.StartsWith("S-1-5-21-") -or $sid.EndsWith("_Classes")) { continue }
+        $lxss = "Registry::HKEY_USERS\$sid\Software\Microsoft\Windows\CurrentVersion\Lxss"
+        $default = (Get-ItemProperty -Path $lxss -ErrorAction SilentlyContinue).DefaultDistribution
+        foreach ($key in @(Get-ChildItem $lxss -ErrorAction SilentlyContinue)) {
+            $p = Get-ItemProperty -Path $key.PSPath -ErrorAction SilentlyContinue
+            if (-not $p.DistributionName) { continue }
+            $base = if ($p.BasePath) { "$($p.BasePath)" } else { $null }
+            if ($base -and $base.StartsWith("\\?\")) { $base = $base.Substring(4) }
+            $distributions += [ordered]@{
+                name = "$($p.DistributionName)"
+                wsl_version = if ($null -ne $p.Version) { [int]$p.Version } else { $null }
+                default = ($null -ne $default) -and ($default -eq $key.PSChildName)
+                base_path = $base
+                user_sid = $sid
+            }
+        }
+    }
+    $inbox = $null -ne (Get-ServiceBinary "LxssManager")
+    $packaged = ($null -ne $packageVersion) -or ($null -ne (Get-ServiceBinary "WSLService"))
+    $wsl = $null
+    if ($inbox -or $packaged -or $distributions.Count -gt 0) {
+        $wsl = [ordered]@{
+            inbox = $inbox
+            package_version = $packageVersion
+            distributions = @($distributions)
+        }
+    }
+    return [ordered]@{
+        wsl = $wsl
+        runtimes = @($runtimes)
+    }
+}
+
+function Get-SecurityPosture {
+    # Mirrors SecurityPosture::collect; BitLocker needs administrator rights
+    $volumes = @()
+    Get-CimInstance -Namespace "root/cimv2/Security/MicrosoftVolumeEncryption" -ClassName Win32_EncryptableVolume -ErrorAction SilentlyContinue | ForEach-Object {
+        $volumes += [ordered]@{
+            drive = if ($_.DriveLetter) { $_.DriveLetter } else { $_.DeviceID }
+            protected = switch ($_.ProtectionStatus) { 0 { $false } 1 { $true } default { $null } }
+        }
+    }
+    # Group Policy values win over local ones; the password policy is only collected locally
+    function Get-Dword($Keys, $Name) {
+        foreach ($key in $Keys) {
+            $value = (Get-ItemProperty -Path "HKLM:\$key" -Name $Name -ErrorAction SilentlyContinue).$Name
+            if ($null -ne $value) { return [int64]$value }
+        }
+        return $null
+    }
+    function Test-Driver($Name) {
+        $start = Get-Dword @("SYSTEM\CurrentControlSet\Services\$Name") "Start"
+        return ($null -ne $start -and $start -ne 4)
+    }
+    $uac = "SOFTWARE\Microsoft\Windows\CurrentVersion\Policies\System"
+    $rdpPolicy = "SOFTWARE\Policies\Microsoft\Windows NT\Terminal Services"
+    $enableLua = Get-Dword @($uac) "EnableLUA"
+    $denyRdp = Get-Dword @($rdpPolicy, "SYSTEM\CurrentControlSet\Control\Terminal Server") "fDenyTSConnections"
+    $nla = Get-Dword @($rdpPolicy, "SYSTEM\CurrentControlSet\Control\Terminal Server\WinStations\RDP-Tcp") "UserAuthentication"
+    $firewall = @()
+    foreach ($fw in @(@("domain", "DomainProfile", "DomainProfile"), @("private", "PrivateProfile", "StandardProfile"), @("public", "PublicProfile", "PublicProfile"))) {
+        $enabled = Get-Dword @("SOFTWARE\Policies\Microsoft\WindowsFirewall\$($fw[1])", "SYSTEM\CurrentControlSet\Services\SharedAccess\Parameters\FirewallPolicy\$($fw[2])") "EnableFirewall"
+        $firewall += [ordered]@{
+            name = $fw[0]
+            enabled = if ($null -eq $enabled) { $null } else { $enabled -ne 0 }
+        }
+    }
+    # Defender answers "N/A: Must be an administrator" to other accounts
+    $mp = Get-CimInstance -Namespace "root/Microsoft/Windows/Defender" -ClassName MSFT_MpPreference -ErrorAction SilentlyContinue
+    $exclusions = $null
+    if ($mp -and -not (@($mp.ExclusionPath) -like "N/A*")) {
+        $exclusions = [ordered]@{
+            paths = @($mp.ExclusionPath | Where-Object { $_ })
+            extensions = @($mp.ExclusionExtension | Where-Object { $_ })
+            processes = @($mp.ExclusionProcess | Where-Object { $_ })
+        }
+    }
+    # Security Center exists on workstations only
+    $antivirus = @(Get-CimInstance -Namespace "root/SecurityCenter2" -ClassName AntiVirusProduct -ErrorAction SilentlyContinue | ForEach-Object { $_.displayName } | Sort-Object -Unique)
+    return [ordered]@{
+        system_drive = $env:SystemDrive
+        bitlocker = @($volumes)
+        uac_enabled = if ($null -eq $enableLua) { $null } else { $enableLua -ne 0 }
+        uac_admin_prompt = Get-Dword @($uac) "ConsentPromptBehaviorAdmin"
+        smb1_server = (Get-Dword @("SYSTEM\CurrentControlSet\Services\LanmanServer\Parameters") "SMB1") -ne 0 -and (Test-Driver "srv")
+        smb1_client = Test-Driver "mrxsmb10"
+        firewall = @($firewall)
+        password_policy = $null
+        rdp_enabled = if ($null -eq $denyRdp) { $null } else { $denyRdp -eq 0 }
+        rdp_nla = if ($null -eq $nla) { $null } else { $nla -ne 0 }
+        defender_exclusions = $exclusions
+        antivirus = $antivirus
+    }
 }
 
-# Assemble Final Structure
+# Assemble Final Structure; skipped sections stay empty
 $report = [ordered]@{
     system = Get-HardwareInfo
-    software = Get-InstalledSoftware
-    industrial = Get-IndustrialSoftware
+    os_build = Get-OsBuild
+    asset_id = Get-AssetId
+    identity = Get-HostIdentity
+    software = @()
+    industrial = @()
+    updates = @()
+    services = @()
     timestamp = (Get-Date).ToUniversalTime().ToString("yyyy-MM-ddTHH:mm:ssZ")
 }
+# @() keeps single-item results as arrays
+if ($Sections -contains "software") { $report.software = @(Get-InstalledSoftware) }
+if ($Sections -contains "industrial") { $report.industrial = @(Get-IndustrialSoftware) }
+if ($Sections -contains "updates") { $report.updates = @(Get-Updates) }
+if ($Sections -contains "services") { $report.services = @(Get-Services) }
+if ($Sections -contains "security") { $report.security = Get-SecurityPosture }
+if ($Sections -contains "update_health") { $report.update_health = Get-UpdateHealth }
+if ($Sections -contains "licensing") { $report.licensing = Get-Licensing }
+if ($Sections -contains "event_logs") { $report.event_logs = Get-EventLogs }
+if ($Sections -contains "autopilot") { $report.autopilot = Get-Autopilot }
+if ($Sections -contains "containers") { $report.containers = Get-Containers }
+
+# Convert to JSON; sections nested deeper than -Depth are cut short, so
+# name them in the report's warnings
+$json = $report | ConvertTo-Json -Depth 6 -Compress -WarningAction SilentlyContinue -WarningVariable truncated
+if ($truncated) {
+    $warnings = @(foreach ($key in @($report.Keys)) {
+        $null = ConvertTo-Json -InputObject $report[$key] -Depth 5 -Compress -WarningAction SilentlyContinue -WarningVariable sectionTruncated
+        if ($sectionTruncated) {
+            [ordered]@{
+                collector = if ($key -in "os_build", "asset_id", "identity") { "system" } else { $key }
+                message = "payload truncated: $key is nested deeper than the JSON depth limit"
+            }
+        }
+    })
+    $report.warnings = $warnings
+    $json = $report | ConvertTo-Json -Depth 6 -Compress -WarningAction SilentlyContinue
+}
+
+# Gzip + Base64 and emit in numbered chunks; WinRM truncates long output
+$bytes = [System.Text.Encoding]::UTF8.GetBytes($json)
+$buffer = New-Object System.IO.MemoryStream
+$gzip = New-Object System.IO.Compression.GZipStream($buffer, [System.IO.Compression.CompressionMode]::Compress)
+$gzip.Write($bytes, 0, $bytes.Length)
+$gzip.Close()
+$encoded = [Convert]::ToBase64String($buffer.ToArray())
 
-# Convert to JSON with maximum depth to prevent truncation
-$report | ConvertTo-Json -Depth 5 -Compress
+$chunkSize = __SYSAUDIT_CHUNK_SIZE__
+$total = [Math]::Max(1, [Math]::Ceiling($encoded.Length / $chunkSize))
+for ($i = 0; $i -lt $total; $i++) {
+    $start = $i * $chunkSize
+    $length = [Math]::Min($chunkSize, $encoded.Length - $start)
+    Write-Output ("SYSAUDIT-CHUNK {0}/{1} {2}" -f ($i + 1), $total, $encoded.Substring($start, $length))
+}
 "#;
+
+/// Encode `json` as the payload does: gzipped, Base64-encoded and split
+/// into [`CHUNK_PREFIX`] lines of at most [`CHUNK_SIZE`] characters.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::remote::payload::{decode_output, encode_output};
+///
+/// let stdout = encode_output("{\"software\":[]}").unwrap();
+/// assert!(stdout.starts_with("SYSAUDIT-CHUNK 1/1 "));
+/// assert_eq!(decode_output(&stdout).unwrap(), "{\"software\":[]}");
+/// ```
+///
+/// # Errors
+///
+/// Returns a message if compression fails.
+pub fn encode_output(json: &str) -> Result<String, String> {
+    Ok(encode_chunks(json, CHUNK_SIZE)?.join("\n"))
+}
+
+/// Chunk lines of `json` with `chunk_size` Base64 characters each.
+fn encode_chunks(json: &str, chunk_size: usize) -> Result<Vec<String>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(json.as_bytes())
+        .map_err(|e| format!("gzip failed: {}", e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("gzip failed: {}", e))?;
+    let encoded = STANDARD.encode(compressed);
+    // Base64 is ASCII, so every chunk is valid UTF-8.
+    let parts: Vec<String> = encoded
+        .as_bytes()
+        .chunks(chunk_size)
+        .map(|c| String::from_utf8_lossy(c).into_owned())
+        .collect();
+    Ok(parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| format!("{} {}/{} {}", CHUNK_PREFIX, i + 1, parts.len(), part))
+        .collect())
+}
+
+/// Reassemble the payload's JSON from its stdout.
+///
+/// Chunks may arrive in any order and interleaved with other output lines.
+/// Output without any chunk lines is returned unchanged, so plain JSON from
+/// older payloads still parses.
+///
+/// # Errors
+///
+/// Returns a message if chunks are missing or duplicated, claim more than
+/// the supported number of chunks, or the data is not valid
+/// Base64/gzip/UTF-8.
+pub fn decode_output(stdout: &str) -> Result<String, String> {
+    let mut chunks: Vec<Option<&str>> = Vec::new();
+    let mut seen_any = false;
+
+    for line in stdout.lines() {
+        let Some(rest) = line.trim().strip_prefix(CHUNK_PREFIX) else {
+            continue;
+        };
+        let (position, data) = rest
+            .trim_start()
+            .split_once(' ')
+            .ok_or_else(|| format!("malformed chunk line: {}", line))?;
+        let (index, total) = position
+            .split_once('/')
+            .and_then(|(i, t)| Some((i.parse::<usize>().ok()?, t.parse::<usize>().ok()?)))
+            .filter(|(i, t)| *i >= 1 && i <= t)
+            .ok_or_else(|| format!("malformed chunk position: {}", position))?;
+        if total > MAX_CHUNKS {
+            return Err(format!(
+                "chunk {} claims {} chunks, more than the limit of {}",
+                index, total, MAX_CHUNKS
+            ));
+        }
+
+        if !seen_any {
+            chunks.resize(total, None);
+            seen_any = true;
+        } else if total != chunks.len() {
+            return Err(format!(
+                "chunk {} claims {} chunks, expected {}",
+                index,
+                total,
+                chunks.len()
+            ));
+        }
+        if chunks[index - 1].replace(data.trim()).is_some() {
+            return Err(format!("duplicate chunk {}", index));
+        }
+    }
+
+    if !seen_any {
+        return Ok(stdout.to_string());
+    }
+
+    let missing: Vec<String> = chunks
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_none())
+        .map(|(i, _)| (i + 1).to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "missing output chunks {} of {}",
+            missing.join(", "),
+            chunks.len()
+        ));
+    }
+
+    let encoded: String = chunks.into_iter().flatten().collect();
+    let compressed = STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("invalid Base64 output: {}", e))?;
+    let mut json = String::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut json)
+        .map_err(|e| format!("invalid gzip output: {}", e))?;
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_chunks_out_of_order() {
+        let json = format!("{{\"software\":[{}]}}", ["\"x\""; 500].join(","));
+        let mut lines = encode_chunks(&json, 40).unwrap();
+        assert!(lines.len() > 2);
+        lines.reverse();
+        let stdout = format!("WARNING: noise\r\n{}\r\n", lines.join("\r\n"));
+
+        assert_eq!(decode_output(&stdout).unwrap(), json);
+    }
+
+    #[test]
+    fn test_encode_output_round_trip() {
+        let json = format!("{{\"software\":[{}]}}", ["\"x\""; 5000].join(","));
+        let stdout = encode_output(&json).unwrap();
+        assert!(stdout.lines().all(|l| l.len() <= CHUNK_SIZE + 32));
+        assert_eq!(decode_output(&stdout).unwrap(), json);
+    }
+
+    #[test]
+    fn test_decode_missing_chunk() {
+        let mut lines = encode_chunks(&"y".repeat(2000), 16).unwrap();
+        lines.remove(1);
+        let err = decode_output(&lines.join("\n")).unwrap_err();
+        assert!(err.contains("missing output chunks 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_decode_rejects_huge_total() {
+        let stdout = format!("{} 1/{} AAAA", CHUNK_PREFIX, usize::MAX);
+        let err = decode_output(&stdout).unwrap_err();
+        assert!(err.contains("more than the limit"), "{}", err);
+    }
+
+    #[test]
+    fn test_render_payload_sections() {
+        let script = render_payload(Sections::new());
+        assert!(script.contains("$Sections = \"software,industrial\" -split"));
+        assert!(!script.contains(SECTIONS_PLACEHOLDER));
+        assert!(script.contains(&format!("$chunkSize = {}\n", CHUNK_SIZE)));
+
+        let script = render_payload(Sections::system_only());
+        assert!(script.contains("$Sections = \"\" -split"));
+
+        assert_eq!(Sections::parse("all").unwrap(), Sections::all());
+        assert_eq!(
+            Sections::parse("system, updates").unwrap(),
+            Sections::system_only().updates(true)
+        );
+        assert!(Sections::parse("software,registry").is_err());
+    }
+
+    #[test]
+    fn test_payload_knows_every_vendor() {
+        use crate::Vendor;
+
+        for vendor in [
+            Vendor::Citect,
+            Vendor::Digifort,
+            Vendor::ABB,
+            Vendor::Rockwell,
+            Vendor::Siemens,
+            Vendor::SchneiderElectric,
+            Vendor::PhoenixContact,
+            Vendor::Wago,
+            Vendor::Festo,
+            Vendor::Moxa,
+            Vendor::Hms,
+            Vendor::Inray,
+            Vendor::Skkynet,
+            Vendor::Kepware,
+            Vendor::HiveMq,
+            Vendor::Mosquitto,
+            Vendor::NodeRed,
+        ] {
+            let name = format!("\"{}\"", vendor);
+            assert!(WINRM_PAYLOAD.contains(&name), "payload misses {}", vendor);
+        }
+    }
+
+    #[test]
+    fn test_payload_reports_truncation() {
+        assert!(WINRM_PAYLOAD.contains("-WarningVariable truncated"));
+        assert!(WINRM_PAYLOAD.contains("$report.warnings = $warnings"));
+        assert!(WINRM_PAYLOAD.contains("payload truncated: "));
+    }
+
+    #[test]
+    fn test_powershell_command_fits_command_line() {
+        // As an -EncodedCommand the payload alone would overflow the
+        // limit; it goes over stdin instead.
+        assert!(render_payload(Sections::all()).len() > 16_000);
+        assert!(powershell_command().len() < 32_767);
+    }
+
+    #[test]
+    fn test_plain_json_passthrough() {
+        assert_eq!(decode_output("{\"a\":1}").unwrap(), "{\"a\":1}");
+    }
+}
@@ -0,0 +1,105 @@
+//! Live status events from remote scans.
+//!
+//! Pass an [`EventSender`] to [`RemoteScanner`](crate::RemoteScanner) or
+//! [`FleetScanner`](crate::fleet::FleetScanner) and read [`ScanEvent`]s from
+//! the matching receiver while the scan runs.
+
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Sending half of a progress channel
+/// (`tokio::sync::mpsc::unbounded_channel`).
+pub type EventSender = UnboundedSender<ScanEvent>;
+
+/// A status change of one host's scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanEvent {
+    /// Target hostname or IP address
+    pub host: String,
+    /// What happened
+    pub stage: ScanStage,
+}
+
+/// Stages of a remote scan, in the order they normally occur.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScanStage {
+    /// Opening the connection (once per attempt)
+    Connecting,
+    /// Credentials accepted and a remote shell opened
+    Authenticated,
+    /// The payload or agent is running
+    Executing,
+    /// Output received so far
+    Received {
+        /// Total stdout bytes of the running command
+        bytes: usize,
+    },
+    /// Decoding the output into a report
+    Parsing,
+    /// A transient failure; the attempt is repeated after `delay`
+    Retrying {
+        /// Attempt that failed (1 for the first)
+        attempt: u32,
+        /// Wait before the next attempt
+        delay: Duration,
+    },
+    /// The host is finished (fleet scans only)
+    Done {
+        /// Whether a report was collected
+        ok: bool,
+    },
+}
+
+impl std::fmt::Display for ScanEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.host)?;
+        match &self.stage {
+            ScanStage::Connecting => write!(f, "connecting"),
+            ScanStage::Authenticated => write!(f, "authenticated"),
+            ScanStage::Executing => write!(f, "executing"),
+            ScanStage::Received { bytes } => write!(f, "received {} bytes", bytes),
+            ScanStage::Parsing => write!(f, "parsing"),
+            ScanStage::Retrying { attempt, delay } => {
+                write!(f, "attempt {} failed, retrying in {:?}", attempt, delay)
+            }
+            ScanStage::Done { ok: true } => write!(f, "done"),
+            ScanStage::Done { ok: false } => write!(f, "failed"),
+        }
+    }
+}
+
+/// Send `stage` for `host` if anyone is listening.
+///
+/// A closed receiver is ignored: progress is advisory and must never fail
+/// a scan.
+pub(crate) fn emit(events: Option<&EventSender>, host: &str, stage: ScanStage) {
+    if let Some(events) = events {
+        let _ = events.send(ScanEvent {
+            host: host.to_string(),
+            stage,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emit_and_display() {
+        let (events, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        emit(Some(&events), "hmi01", ScanStage::Received { bytes: 512 });
+        emit(None, "hmi01", ScanStage::Parsing);
+        drop(events);
+
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.to_string(), "hmi01: received 512 bytes");
+        assert!(receiver.try_recv().is_err());
+
+        // A dropped receiver is not an error.
+        let (events, receiver) = tokio::sync::mpsc::unbounded_channel();
+        drop(receiver);
+        emit(Some(&events), "hmi01", ScanStage::Connecting);
+    }
+}
@@ -0,0 +1,104 @@
+//! Retry with exponential backoff for remote scans.
+
+use std::time::Duration;
+
+/// How often, and how patiently, a failed remote scan is retried.
+///
+/// Only transient failures (see [`ScanError::is_transient`]) are retried;
+/// rejected credentials or a failing command fail immediately. The delay
+/// before retry `n` is `initial_backoff * 2^(n-1)`, capped at
+/// `max_backoff`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use sysaudit::RetryPolicy;
+///
+/// let policy = RetryPolicy::new()
+///     .max_attempts(4)
+///     .initial_backoff(Duration::from_secs(5));
+/// assert_eq!(policy.backoff(3), Duration::from_secs(20));
+/// ```
+///
+/// [`ScanError::is_transient`]: crate::ScanError::is_transient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Three attempts, backing off 2s then 4s (capped at 60s).
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+
+    /// A single attempt, no retries.
+    pub fn none() -> Self {
+        Self::new().max_attempts(1)
+    }
+
+    /// Total attempts including the first (at least 1).
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry.
+    pub fn initial_backoff(mut self, delay: Duration) -> Self {
+        self.initial_backoff = delay;
+        self
+    }
+
+    /// Upper bound for any single delay.
+    pub fn max_backoff(mut self, delay: Duration) -> Self {
+        self.max_backoff = delay;
+        self
+    }
+
+    /// Total attempts including the first.
+    pub fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Delay before retry number `retry` (1 for the first retry).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy::new()
+            .max_attempts(10)
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(10));
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(4), Duration::from_secs(8));
+        assert_eq!(policy.backoff(5), Duration::from_secs(10));
+        assert_eq!(policy.backoff(40), Duration::from_secs(10));
+        assert_eq!(RetryPolicy::none().attempts(), 1);
+        assert_eq!(RetryPolicy::new().max_attempts(0).attempts(), 1);
+    }
+}
@@ -0,0 +1,366 @@
+//! SSPI security context for WinRM authentication and message sealing.
+//!
+//! Drives the `Negotiate`/`NTLM`/`Kerberos` handshake token by token and, once the
+//! context is established, wraps request bodies in the
+//! `application/HTTP-SPNEGO-session-encrypted` multipart format WinRM
+//! requires for authenticated traffic over plain HTTP.
+
+use crate::scanner::ScanError;
+use secrecy::zeroize::Zeroize;
+use secrecy::{ExposeSecret, SecretString};
+use windows::Win32::Foundation::{
+    SEC_E_OK, SEC_I_COMPLETE_AND_CONTINUE, SEC_I_COMPLETE_NEEDED, SEC_I_CONTINUE_NEEDED,
+};
+use windows::Win32::Security::Authentication::Identity::{
+    AcquireCredentialsHandleW, CompleteAuthToken, DecryptMessage, DeleteSecurityContext,
+    EncryptMessage, FreeCredentialsHandle, ISC_REQ_CONFIDENTIALITY, ISC_REQ_FLAGS,
+    ISC_REQ_INTEGRITY, ISC_REQ_MUTUAL_AUTH, ISC_REQ_REPLAY_DETECT, ISC_REQ_SEQUENCE_DETECT,
+    InitializeSecurityContextW, QueryContextAttributesW, SECBUFFER_DATA, SECBUFFER_PADDING,
+    SECBUFFER_TOKEN, SECBUFFER_VERSION, SECPKG_ATTR_SIZES, SECPKG_CRED_OUTBOUND,
+    SECURITY_NATIVE_DREP, SecBuffer, SecBufferDesc, SecPkgContext_Sizes,
+};
+use windows::Win32::Security::Credentials::SecHandle;
+use windows::Win32::System::Rpc::{SEC_WINNT_AUTH_IDENTITY_UNICODE, SEC_WINNT_AUTH_IDENTITY_W};
+use windows::core::{HSTRING, PCWSTR};
+
+/// MIME boundary used by WinRM for sealed messages.
+const BOUNDARY: &str = "Encrypted Boundary";
+
+/// Content type of a sealed request or response.
+pub const ENCRYPTED_CONTENT_TYPE: &str = "multipart/encrypted;protocol=\"application/HTTP-SPNEGO-session-encrypted\";boundary=\"Encrypted Boundary\"";
+
+/// Largest token any package produces (Kerberos with large PACs).
+const MAX_TOKEN_SIZE: usize = 65_536;
+
+/// An SSPI client context for one WinRM connection.
+pub struct SspiContext {
+    credentials: SecHandle,
+    context: Option<SecHandle>,
+    target: Vec<u16>,
+    sizes: SecPkgContext_Sizes,
+    host: String,
+    user: String,
+}
+
+impl SspiContext {
+    /// Acquire credentials for `package` (`"NTLM"`, `"Negotiate"` or `"Kerberos"`).
+    ///
+    /// With `None` the current logon session's credentials are used and no
+    /// password leaves the process. A user name may be `DOMAIN\user`,
+    /// `user@domain` or a bare user name. `target` is the service principal
+    /// name, e.g. `HTTP/server.example.com`.
+    pub fn new(
+        package: &str,
+        credentials: Option<(&str, &SecretString)>,
+        target: &str,
+        host: &str,
+    ) -> Result<Self, ScanError> {
+        let account = credentials.map_or_else(
+            || "(current user)".to_string(),
+            |(username, _)| username.to_string(),
+        );
+        let (domain, user, password) = match credentials {
+            Some((username, password)) => {
+                let (domain, user) = username.split_once('\\').unwrap_or(("", username));
+                (domain, user, password.expose_secret())
+            }
+            None => ("", "", ""),
+        };
+        let mut user: Vec<u16> = user.encode_utf16().collect();
+        let mut domain: Vec<u16> = domain.encode_utf16().collect();
+        let mut secret: Vec<u16> = password.encode_utf16().collect();
+
+        let identity = SEC_WINNT_AUTH_IDENTITY_W {
+            User: user.as_mut_ptr(),
+            UserLength: user.len() as u32,
+            Domain: domain.as_mut_ptr(),
+            DomainLength: domain.len() as u32,
+            Password: secret.as_mut_ptr(),
+            PasswordLength: secret.len() as u32,
+            Flags: SEC_WINNT_AUTH_IDENTITY_UNICODE,
+        };
+        let auth_data = credentials
+            .is_some()
+            .then_some(&identity as *const _ as *const _);
+
+        let mut credentials = SecHandle::default();
+        // SAFETY: `identity` and the buffers it points to live until the
+        // call returns (a null identity selects the logon session); `credentials` is a valid out pointer.
+        let result = unsafe {
+            AcquireCredentialsHandleW(
+                PCWSTR::null(),
+                &HSTRING::from(package),
+                SECPKG_CRED_OUTBOUND,
+                None,
+                auth_data,
+                None,
+                None,
+                &mut credentials,
+                None,
+            )
+        };
+        secret.zeroize();
+        result.map_err(|e| ScanError::RemoteConnection {
+            host: host.to_string(),
+            message: format!("cannot acquire {} credentials: {}", package, e),
+        })?;
+
+        Ok(SspiContext {
+            credentials,
+            context: None,
+            target: target.encode_utf16().chain(std::iter::once(0)).collect(),
+            sizes: SecPkgContext_Sizes::default(),
+            host: host.to_string(),
+            user: account,
+        })
+    }
+
+    /// Feed the server's token (none for the first step) and return the
+    /// next client token, plus whether the context is now established.
+    pub fn step(&mut self, input: Option<&[u8]>) -> Result<(Vec<u8>, bool), ScanError> {
+        let mut input = input.map(<[u8]>::to_vec).unwrap_or_default();
+        let mut input_buffer = SecBuffer {
+            cbBuffer: input.len() as u32,
+            BufferType: SECBUFFER_TOKEN,
+            pvBuffer: input.as_mut_ptr().cast(),
+        };
+        let input_desc = SecBufferDesc {
+            ulVersion: SECBUFFER_VERSION,
+            cBuffers: 1,
+            pBuffers: &mut input_buffer,
+        };
+
+        let mut output = vec![0u8; MAX_TOKEN_SIZE];
+        let mut output_buffer = SecBuffer {
+            cbBuffer: output.len() as u32,
+            BufferType: SECBUFFER_TOKEN,
+            pvBuffer: output.as_mut_ptr().cast(),
+        };
+        let mut output_desc = SecBufferDesc {
+            ulVersion: SECBUFFER_VERSION,
+            cBuffers: 1,
+            pBuffers: &mut output_buffer,
+        };
+
+        let flags: ISC_REQ_FLAGS = ISC_REQ_CONFIDENTIALITY
+            | ISC_REQ_INTEGRITY
+            | ISC_REQ_MUTUAL_AUTH
+            | ISC_REQ_REPLAY_DETECT
+            | ISC_REQ_SEQUENCE_DETECT;
+        let mut new_context = self.context.unwrap_or_default();
+        let mut attributes = 0u32;
+
+        // SAFETY: every pointer refers to a local that outlives the call;
+        // the input descriptor is only passed once a context exists.
+        let status = unsafe {
+            InitializeSecurityContextW(
+                Some(&self.credentials as *const _),
+                self.context.as_ref().map(|c| c as *const _),
+                Some(self.target.as_ptr()),
+                flags,
+                0,
+                SECURITY_NATIVE_DREP,
+                self.context.is_some().then_some(&input_desc as *const _),
+                0,
+                Some(&mut new_context as *mut _),
+                Some(&mut output_desc as *mut _),
+                &mut attributes,
+                None,
+            )
+        };
+        let done = status == SEC_E_OK || status == SEC_I_COMPLETE_NEEDED;
+        if !done && status != SEC_I_CONTINUE_NEEDED && status != SEC_I_COMPLETE_AND_CONTINUE {
+            return Err(self.auth_error(windows::core::Error::from(status)));
+        }
+        self.context = Some(new_context);
+
+        if status == SEC_I_COMPLETE_NEEDED || status == SEC_I_COMPLETE_AND_CONTINUE {
+            // SAFETY: the context was just initialized and `output_desc`
+            // holds the token it produced.
+            unsafe { CompleteAuthToken(&new_context, &output_desc) }
+                .map_err(|e| self.auth_error(e))?;
+        }
+
+        if done {
+            // SAFETY: the context is established and `self.sizes` is the
+            // structure SECPKG_ATTR_SIZES expects.
+            unsafe {
+                QueryContextAttributesW(
+                    &new_context,
+                    SECPKG_ATTR_SIZES,
+                    &mut self.sizes as *mut _ as *mut _,
+                )
+            }
+            .map_err(|e| self.auth_error(e))?;
+        }
+
+        output.truncate(output_buffer.cbBuffer as usize);
+        Ok((output, done))
+    }
+
+    /// Seal a SOAP envelope into a multipart/encrypted body.
+    pub fn encrypt(&self, message: &[u8]) -> Result<Vec<u8>, ScanError> {
+        let context = self.established()?;
+        let mut token = vec![0u8; self.sizes.cbSecurityTrailer as usize];
+        let mut data = message.to_vec();
+        let mut padding = vec![0u8; self.sizes.cbBlockSize as usize];
+        let mut buffers = [
+            SecBuffer {
+                cbBuffer: token.len() as u32,
+                BufferType: SECBUFFER_TOKEN,
+                pvBuffer: token.as_mut_ptr().cast(),
+            },
+            SecBuffer {
+                cbBuffer: data.len() as u32,
+                BufferType: SECBUFFER_DATA,
+                pvBuffer: data.as_mut_ptr().cast(),
+            },
+            SecBuffer {
+                cbBuffer: padding.len() as u32,
+                BufferType: SECBUFFER_PADDING,
+                pvBuffer: padding.as_mut_ptr().cast(),
+            },
+        ];
+        let desc = SecBufferDesc {
+            ulVersion: SECBUFFER_VERSION,
+            cBuffers: buffers.len() as u32,
+            pBuffers: buffers.as_mut_ptr(),
+        };
+
+        // SAFETY: the buffers point at locals sized per the context's
+        // SECPKG_ATTR_SIZES and live until the call returns.
+        unsafe { EncryptMessage(&context, 0, &desc, 0) }
+            .ok()
+            .map_err(|e| self.seal_error(e))?;
+
+        token.truncate(buffers[0].cbBuffer as usize);
+        data.truncate(buffers[1].cbBuffer as usize);
+        padding.truncate(buffers[2].cbBuffer as usize);
+
+        let mut body = format!(
+            "--{b}\r\n\tContent-Type: application/HTTP-SPNEGO-session-encrypted\r\n\
+             \tOriginalContent: type=application/soap+xml;charset=UTF-8;Length={len}\r\n\
+             --{b}\r\n\tContent-Type: application/octet-stream\r\n",
+            b = BOUNDARY,
+            len = message.len()
+        )
+        .into_bytes();
+        body.extend((token.len() as u32).to_le_bytes());
+        body.extend(token);
+        body.extend(data);
+        body.extend(padding);
+        body.extend(format!("--{}--\r\n", BOUNDARY).into_bytes());
+        Ok(body)
+    }
+
+    /// Unseal a multipart/encrypted response body.
+    pub fn decrypt(&self, body: &[u8]) -> Result<Vec<u8>, ScanError> {
+        let context = self.established()?;
+        let (mut token, mut data) =
+            split_sealed(body).ok_or_else(|| self.seal_error("malformed encrypted response"))?;
+        let mut buffers = [
+            SecBuffer {
+                cbBuffer: token.len() as u32,
+                BufferType: SECBUFFER_TOKEN,
+                pvBuffer: token.as_mut_ptr().cast(),
+            },
+            SecBuffer {
+                cbBuffer: data.len() as u32,
+                BufferType: SECBUFFER_DATA,
+                pvBuffer: data.as_mut_ptr().cast(),
+            },
+        ];
+        let desc = SecBufferDesc {
+            ulVersion: SECBUFFER_VERSION,
+            cBuffers: buffers.len() as u32,
+            pBuffers: buffers.as_mut_ptr(),
+        };
+
+        // SAFETY: the buffers point at owned copies of the response parts
+        // and are decrypted in place.
+        unsafe { DecryptMessage(&context, &desc, 0, None) }
+            .ok()
+            .map_err(|e| self.seal_error(e))?;
+
+        data.truncate(buffers[1].cbBuffer as usize);
+        Ok(data)
+    }
+
+    fn established(&self) -> Result<SecHandle, ScanError> {
+        match self.context {
+            Some(context) if self.sizes.cbMaxToken > 0 => Ok(context),
+            _ => Err(self.seal_error("security context is not established")),
+        }
+    }
+
+    fn auth_error(&self, e: windows::core::Error) -> ScanError {
+        tracing::debug!(host = %self.host, error = %e, "SSPI authentication failed");
+        self.rejected()
+    }
+
+    fn seal_error(&self, e: impl std::fmt::Display) -> ScanError {
+        tracing::debug!(host = %self.host, error = %e, "Message encryption failed");
+        self.rejected()
+    }
+
+    /// A failed handshake or a context that cannot seal means the
+    /// credentials were not accepted; retrying would not help.
+    fn rejected(&self) -> ScanError {
+        ScanError::RemoteAuth {
+            host: self.host.clone(),
+            user: self.user.clone(),
+        }
+    }
+}
+
+impl Drop for SspiContext {
+    fn drop(&mut self) {
+        // SAFETY: both handles were created by SSPI and are released once.
+        unsafe {
+            if let Some(context) = self.context.take() {
+                let _ = DeleteSecurityContext(&context);
+            }
+            let _ = FreeCredentialsHandle(&self.credentials);
+        }
+    }
+}
+
+/// Split a sealed body into its signature token and encrypted payload.
+fn split_sealed(body: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let marker = b"Content-Type: application/octet-stream\r\n";
+    let start = find(body, marker)? + marker.len();
+    let end = find(&body[start..], format!("--{}--", BOUNDARY).as_bytes())? + start;
+    let part = &body[start..end];
+
+    let len = u32::from_le_bytes(part.get(..4)?.try_into().ok()?) as usize;
+    let token = part.get(4..4 + len)?.to_vec();
+    let data = part.get(4 + len..)?.to_vec();
+    Some((token, data))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sealed() {
+        let mut body = b"--Encrypted Boundary\r\n\tContent-Type: application/HTTP-SPNEGO-session-encrypted\r\n\
+            \tOriginalContent: type=application/soap+xml;charset=UTF-8;Length=3\r\n\
+            --Encrypted Boundary\r\n\tContent-Type: application/octet-stream\r\n"
+            .to_vec();
+        body.extend(2u32.to_le_bytes());
+        body.extend(b"SGabc");
+        body.extend(b"--Encrypted Boundary--\r\n");
+
+        let (token, data) = split_sealed(&body).unwrap();
+        assert_eq!(token, b"SG");
+        assert_eq!(data, b"abc");
+        assert!(split_sealed(b"garbage").is_none());
+    }
+}
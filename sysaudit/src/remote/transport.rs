@@ -1,87 +1,605 @@
+use crate::metrics::TransferMetrics;
+use crate::remote::client_cert::{ClientCertificate, MUTUAL_AUTH_PROFILE};
+use crate::remote::errors;
+use crate::remote::progress::{self, EventSender, ScanStage};
+use crate::remote::retry::RetryPolicy;
+use crate::remote::sspi::{ENCRYPTED_CONTENT_TYPE, SspiContext};
+use crate::remote::wsman;
 use crate::scanner::ScanError;
 use async_trait::async_trait;
-use reqwest::Client;
-use secrecy::SecretString;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use bon::bon;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE};
+use reqwest::{Certificate, Client, RequestBuilder, Response, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
 use std::time::Duration;
 
+/// Upper bound for a single WS-Man operation (each Receive long-polls this long).
+const MAX_OPERATION_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Raw stdin bytes per Send request, well below the listener's default
+/// 500 KB envelope limit once Base64-encoded.
+const STDIN_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Content type of a plain SOAP request.
+const SOAP_CONTENT_TYPE: &str = "application/soap+xml;charset=UTF-8";
+
+/// How the transport authenticates to the WinRM listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuthMethod {
+    /// HTTP Basic with a local account. A stock listener only accepts it
+    /// over HTTPS, or over HTTP with `AllowUnencrypted` set.
+    #[default]
+    Basic,
+    /// NTLM through SSPI; works with domain and local accounts
+    Ntlm,
+    /// SPNEGO through SSPI: Kerberos when available, NTLM otherwise
+    Negotiate,
+    /// Kerberos through SSPI. Needs the host's FQDN (or an SPN override);
+    /// without explicit credentials the current logon ticket is used.
+    Kerberos,
+}
+
+impl AuthMethod {
+    /// Parse a method name (`basic`, `ntlm`, `negotiate`, `kerberos`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`](crate::Error::General) for an unknown name.
+    pub fn parse(name: &str) -> Result<Self, crate::Error> {
+        match name.trim().to_lowercase().as_str() {
+            "basic" => Ok(AuthMethod::Basic),
+            "ntlm" => Ok(AuthMethod::Ntlm),
+            "negotiate" => Ok(AuthMethod::Negotiate),
+            "kerberos" => Ok(AuthMethod::Kerberos),
+            other => Err(crate::Error::General(format!(
+                "unsupported auth method '{}' (use basic, ntlm, negotiate or kerberos)",
+                other
+            ))),
+        }
+    }
+
+    /// SSPI package name, `None` for Basic.
+    fn package(self) -> Option<&'static str> {
+        match self {
+            AuthMethod::Basic => None,
+            AuthMethod::Ntlm => Some("NTLM"),
+            AuthMethod::Negotiate => Some("Negotiate"),
+            AuthMethod::Kerberos => Some("Kerberos"),
+        }
+    }
+}
+
 /// Abstraction over the WinRM HTTP transport for testability.
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait WinrmTransport: Send + Sync {
     /// Execute a PowerShell command on the remote host and return the JSON stdout.
     async fn execute(&self, command: &str) -> Result<String, ScanError>;
+
+    /// Execute `command`, writing `script` to its stdin, and return its
+    /// stdout. Keeps large scripts off the command line, which Windows
+    /// limits to 32,767 characters.
+    async fn execute_script(&self, command: &str, script: &str) -> Result<String, ScanError>;
 }
 
 /// A real HTTP-based WinRM transport implementing WS-Man Protocol.
-#[allow(dead_code)]
+///
+/// With [`AuthMethod::Ntlm`] or [`AuthMethod::Negotiate`] the connection is
+/// authenticated once through SSPI; over plain HTTP every message is then
+/// sealed with the session key, as WinRM requires by default. With a
+/// client certificate the connection is authenticated by the TLS handshake
+/// instead, and no credentials are sent.
 pub struct HttpWinrmTransport {
     host: String,
     port: u16,
     use_https: bool,
-    skip_cert_verify: bool,
-    username: String,
-    password: SecretString,
+    username: Option<String>,
+    password: Option<SecretString>,
+    auth: AuthMethod,
+    certificate_auth: bool,
+    spn: Option<String>,
     timeout: Duration,
+    retry: RetryPolicy,
+    events: Option<EventSender>,
     client: Client,
+    session: Mutex<Option<SspiContext>>,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    requests: AtomicU64,
 }
 
+#[bon]
 impl HttpWinrmTransport {
     /// Create a new HTTP WinRM transport.
     ///
+    /// `ca_bundle` is a PEM file of extra trusted roots (e.g. an enterprise
+    /// or plant PKI); `skip_cert_verify` disables certificate checks
+    /// entirely and should be limited to self-signed lab listeners.
+    /// `client_cert` switches to certificate-mapping authentication, which
+    /// needs HTTPS; `username`, `password` and `auth` are then ignored.
+    /// `timeout` bounds each attempt; `retry` controls how transient
+    /// failures are retried. Progress goes to `events` when set.
+    ///
     /// # Errors
     ///
-    /// Returns [`ScanError::RemoteConnection`] if the HTTP client cannot be built.
+    /// Returns [`ScanError::RemoteConnection`] if the CA bundle cannot be
+    /// read, a client certificate is given without HTTPS, or the HTTP client
+    /// cannot be built, and [`ScanError::Local`] if the client certificate
+    /// cannot be loaded.
+    #[builder]
     pub fn new(
         host: String,
         port: u16,
-        use_https: bool,
-        skip_cert_verify: bool,
-        username: String,
-        password: SecretString,
+        #[builder(default)] use_https: bool,
+        #[builder(default)] skip_cert_verify: bool,
+        ca_bundle: Option<PathBuf>,
+        client_cert: Option<ClientCertificate>,
+        username: Option<String>,
+        password: Option<SecretString>,
+        #[builder(default)] auth: AuthMethod,
+        spn: Option<String>,
         timeout: Duration,
+        #[builder(default)] retry: RetryPolicy,
+        events: Option<EventSender>,
     ) -> Result<Self, ScanError> {
-        let client = Client::builder()
-            .timeout(timeout)
-            // .danger_accept_invalid_certs(skip_cert_verify) // reqwest rustls api
-            .build()
-            .map_err(|e| ScanError::RemoteConnection {
+        let mut builder = Client::builder().timeout(timeout);
+        if let Some(path) = &ca_bundle {
+            let pem = std::fs::read(path).map_err(|e| ScanError::RemoteConnection {
                 host: host.clone(),
-                message: format!("Failed to build HTTP client: {}", e),
+                message: format!("Failed to read CA bundle {}: {}", path.display(), e),
             })?;
+            let certificates =
+                Certificate::from_pem_bundle(&pem).map_err(|e| ScanError::RemoteConnection {
+                    host: host.clone(),
+                    message: format!("Invalid CA bundle {}: {}", path.display(), e),
+                })?;
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(certificate) = &client_cert {
+            if !use_https {
+                return Err(ScanError::RemoteConnection {
+                    host: host.clone(),
+                    message: "client certificate authentication requires HTTPS".to_string(),
+                });
+            }
+            builder = builder.identity(certificate.identity()?);
+        }
+        if skip_cert_verify {
+            tracing::warn!(host = %host, "TLS certificate verification disabled");
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        let client = builder.build().map_err(|e| ScanError::RemoteConnection {
+            host: host.clone(),
+            message: format!("Failed to build HTTP client: {}", e),
+        })?;
 
         Ok(Self {
             host,
             port,
             use_https,
-            skip_cert_verify,
             username,
             password,
+            auth,
+            certificate_auth: client_cert.is_some(),
+            spn,
             timeout,
+            retry,
+            events,
             client,
+            session: Mutex::new(None),
+            bytes_sent: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            requests: AtomicU64::new(0),
         })
     }
 }
 
-#[async_trait]
-impl WinrmTransport for HttpWinrmTransport {
-    async fn execute(&self, _command: &str) -> Result<String, ScanError> {
-        // Here we would implement the actual WS-Management protocol over HTTP/HTTPS:
-        // 1. Create a WinRM Shell
-        // 2. Execute command
-        // 3. Receive output
-        // 4. Close shell
-        // Since implementing full WS-Man in raw Rust is hundreds of lines of SOAP XML,
-        // we'll stub this for the architecture step, and we may need the `winrm` crate
-        // to do the heavy lifting later.
-
-        // For the sake of this design step, let's pretend we execute `command` and get JSON string.
+impl HttpWinrmTransport {
+    /// Bytes and requests exchanged with the host so far.
+    pub fn transfer(&self) -> TransferMetrics {
+        TransferMetrics {
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            requests: self.requests.load(Ordering::Relaxed),
+            ..TransferMetrics::default()
+        }
+    }
+
+    fn url(&self) -> String {
         let scheme = if self.use_https { "https" } else { "http" };
-        let _url = format!("{}://{}:{}/wsman", scheme, self.host, self.port);
+        format!("{}://{}:{}/wsman", scheme, self.host, self.port)
+    }
+
+    /// Per-request WS-Man OperationTimeout; kept below the HTTP timeout so
+    /// the server answers (with a timeout fault) before the client gives up.
+    fn operation_timeout(&self) -> Duration {
+        (self.timeout / 2).clamp(Duration::from_secs(1), MAX_OPERATION_TIMEOUT)
+    }
+
+    fn emit(&self, stage: ScanStage) {
+        progress::emit(self.events.as_ref(), &self.host, stage);
+    }
+
+    fn session(&self) -> MutexGuard<'_, Option<SspiContext>> {
+        self.session.lock().unwrap_or_else(|e| e.into_inner())
+    }
 
-        // Placeholder return wrapping simulated error to satisfy compiler
-        Err(ScanError::RemoteExecution {
+    fn connection_error(&self, e: reqwest::Error) -> ScanError {
+        if e.is_timeout() {
+            ScanError::Timeout(self.timeout)
+        } else {
+            ScanError::RemoteConnection {
+                host: self.host.clone(),
+                message: e.to_string(),
+            }
+        }
+    }
+
+    fn auth_error(&self) -> ScanError {
+        let user = if self.certificate_auth {
+            "(client certificate)".to_string()
+        } else {
+            self.username
+                .clone()
+                .unwrap_or_else(|| "(current user)".to_string())
+        };
+        ScanError::RemoteAuth {
             host: self.host.clone(),
-            message: "WS-Man protocol implementation pending.".to_string(),
+            user,
+        }
+    }
+
+    /// Send a request, counting it and its body.
+    async fn dispatch(&self, request: RequestBuilder) -> Result<Response, ScanError> {
+        let request = request.build().map_err(|e| self.connection_error(e))?;
+        let size = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map_or(0, <[u8]>::len);
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(size as u64, Ordering::Relaxed);
+        self.client
+            .execute(request)
+            .await
+            .map_err(|e| self.connection_error(e))
+    }
+
+    fn count_received(&self, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Run the SSPI handshake on the pooled connection and keep the context.
+    async fn authenticate(&self, package: &str) -> Result<(), ScanError> {
+        let spn = self
+            .spn
+            .clone()
+            .unwrap_or_else(|| format!("HTTP/{}", self.host));
+        let credentials = match (&self.username, &self.password) {
+            (Some(username), Some(password)) => Some((username.as_str(), password)),
+            // Falling back to the logon session would authenticate as
+            // someone other than the user asked for.
+            (Some(_), None) => {
+                tracing::warn!(host = %self.host, "A username was given without a password");
+                return Err(self.auth_error());
+            }
+            (None, _) => None,
+        };
+        let mut context = SspiContext::new(package, credentials, &spn, &self.host)?;
+
+        let mut input: Option<Vec<u8>> = None;
+        loop {
+            let (token, done) = context.step(input.as_deref())?;
+            let response = self
+                .dispatch(
+                    self.client
+                        .post(self.url())
+                        .header(
+                            AUTHORIZATION,
+                            format!("Negotiate {}", STANDARD.encode(&token)),
+                        )
+                        .header(CONTENT_TYPE, SOAP_CONTENT_TYPE)
+                        .body(Vec::new()),
+                )
+                .await?;
+            // The handshake responses are not read; their length is declared.
+            self.count_received(response.content_length().unwrap_or(0) as usize);
+
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Negotiate "))
+                .and_then(|v| STANDARD.decode(v.trim()).ok());
+
+            match (response.status(), challenge) {
+                (StatusCode::UNAUTHORIZED, Some(challenge)) if !done => input = Some(challenge),
+                (StatusCode::UNAUTHORIZED, _) => return Err(self.auth_error()),
+                // Mutual authentication: the final server token completes the context.
+                (_, Some(challenge)) if !done => {
+                    context.step(Some(&challenge))?;
+                    break;
+                }
+                _ => break,
+            }
+        }
+
+        tracing::debug!(host = %self.host, package, "WinRM connection authenticated");
+        *self.session() = Some(context);
+        Ok(())
+    }
+
+    /// POST one envelope and return the response body.
+    ///
+    /// SOAP faults are returned as `Err(Ok(fault))` so callers can react to
+    /// specific fault codes; other failures are `Err(Err(ScanError))`.
+    async fn send(&self, envelope: String) -> Result<String, Result<wsman::Fault, ScanError>> {
+        let mut request = self.client.post(self.url());
+        match (self.certificate_auth, self.auth.package()) {
+            // The TLS handshake presented the certificate already.
+            (true, _) => {
+                request = request
+                    .header(AUTHORIZATION, MUTUAL_AUTH_PROFILE)
+                    .header(CONTENT_TYPE, SOAP_CONTENT_TYPE)
+                    .body(envelope);
+            }
+            (false, None) => {
+                let (Some(username), Some(password)) = (&self.username, &self.password) else {
+                    return Err(Err(self.auth_error()));
+                };
+                request = request
+                    .basic_auth(username, Some(password.expose_secret()))
+                    .header(CONTENT_TYPE, SOAP_CONTENT_TYPE)
+                    .body(envelope);
+            }
+            (false, Some(package)) => {
+                let authenticated = self.session().is_some();
+                if !authenticated {
+                    self.authenticate(package).await.map_err(Err)?;
+                }
+                if self.use_https {
+                    request = request
+                        .header(CONTENT_TYPE, SOAP_CONTENT_TYPE)
+                        .body(envelope);
+                } else {
+                    let sealed = match self.session().as_ref() {
+                        Some(context) => context.encrypt(envelope.as_bytes()),
+                        None => Err(self.auth_error()),
+                    }
+                    .map_err(Err)?;
+                    request = request
+                        .header(CONTENT_TYPE, ENCRYPTED_CONTENT_TYPE)
+                        .body(sealed);
+                }
+            }
+        }
+
+        let response = self.dispatch(request).await.map_err(Err)?;
+
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED {
+            // The connection (and with it the security context) is gone.
+            *self.session() = None;
+            return Err(Err(self.auth_error()));
+        }
+        let encrypted = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("multipart/encrypted"));
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| Err(self.connection_error(e)))?;
+        self.count_received(bytes.len());
+        let body = if encrypted {
+            let plain = match self.session().as_ref() {
+                Some(context) => context.decrypt(&bytes),
+                None => Err(self.auth_error()),
+            }
+            .map_err(Err)?;
+            String::from_utf8_lossy(&plain).into_owned()
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        if status.is_success() {
+            return Ok(body);
+        }
+        match wsman::parse_fault(&body) {
+            Some(fault) => Err(Ok(fault)),
+            None => Err(Err(ScanError::RemoteConnection {
+                host: self.host.clone(),
+                message: format!("WinRM returned HTTP {}", status),
+            })),
+        }
+    }
+
+    /// Like [`send`](Self::send), with faults turned into errors.
+    async fn call(&self, envelope: String) -> Result<String, ScanError> {
+        self.send(envelope).await.map_err(|e| match e {
+            Ok(fault) => self.execution_error(fault.message),
+            Err(e) => e,
         })
     }
+
+    fn execution_error(&self, message: impl Into<String>) -> ScanError {
+        ScanError::RemoteExecution {
+            host: self.host.clone(),
+            message: message.into(),
+        }
+    }
+
+    /// Run a command in an open shell, feed it `stdin` and collect its
+    /// stdout.
+    async fn run(
+        &self,
+        shell_id: &str,
+        command: &str,
+        stdin: Option<&str>,
+    ) -> Result<String, ScanError> {
+        let url = self.url();
+        let timeout = self.operation_timeout();
+        let (program, arguments) = command.split_once(' ').unwrap_or((command, ""));
+
+        let response = self
+            .call(wsman::command(
+                &url,
+                timeout,
+                shell_id,
+                program,
+                arguments,
+                stdin.is_some(),
+            ))
+            .await?;
+        let command_id = wsman::parse_command_id(&response).map_err(|e| self.execution_error(e))?;
+        if let Some(stdin) = stdin {
+            // An empty script still needs one Send to close the stream.
+            let mut chunks: Vec<&[u8]> = stdin.as_bytes().chunks(STDIN_CHUNK_BYTES).collect();
+            if chunks.is_empty() {
+                chunks.push(&[]);
+            }
+            for (i, chunk) in chunks.iter().enumerate() {
+                let end = i + 1 == chunks.len();
+                self.call(wsman::send(
+                    &url,
+                    timeout,
+                    shell_id,
+                    &command_id,
+                    chunk,
+                    end,
+                ))
+                .await?;
+            }
+        }
+        self.emit(ScanStage::Executing);
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let exit_code = loop {
+            let response = match self
+                .send(wsman::receive(&url, timeout, shell_id, &command_id))
+                .await
+            {
+                Ok(response) => response,
+                // No output yet: poll again.
+                Err(Ok(fault)) if fault.code.as_deref() == Some(wsman::FAULT_OPERATION_TIMEOUT) => {
+                    continue;
+                }
+                Err(Ok(fault)) => return Err(self.execution_error(fault.message)),
+                Err(Err(e)) => return Err(e),
+            };
+            let output = wsman::parse_receive(&response).map_err(|e| self.execution_error(e))?;
+            if !output.stdout.is_empty() {
+                self.emit(ScanStage::Received {
+                    bytes: stdout.len() + output.stdout.len(),
+                });
+            }
+            stdout.extend(output.stdout);
+            stderr.extend(output.stderr);
+            if output.done {
+                break output.exit_code.unwrap_or(0);
+            }
+        };
+
+        if let Err(e) = self
+            .call(wsman::signal_terminate(
+                &url,
+                timeout,
+                shell_id,
+                &command_id,
+            ))
+            .await
+        {
+            tracing::debug!(host = %self.host, error = %e, "Failed to terminate remote command");
+        }
+
+        // A script that dies before writing anything can still exit 0, so
+        // errors with no output count as a failure too.
+        let stderr = errors::decode_stderr(&stderr);
+        if exit_code != 0 || (stdout.is_empty() && !stderr.is_empty()) {
+            return Err(errors::classify(&self.host, exit_code, &stderr));
+        }
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+
+    /// One attempt of [`execute`](WinrmTransport::execute).
+    async fn execute_once(&self, command: &str, stdin: Option<&str>) -> Result<String, ScanError> {
+        let url = self.url();
+        let timeout = self.operation_timeout();
+
+        self.emit(ScanStage::Connecting);
+        let response = self.call(wsman::create_shell(&url, timeout)).await?;
+        let shell_id = wsman::parse_shell_id(&response).map_err(|e| self.execution_error(e))?;
+        tracing::debug!(host = %self.host, shell_id = %shell_id, "Remote shell created");
+        self.emit(ScanStage::Authenticated);
+
+        let result = tokio::time::timeout(self.timeout, self.run(&shell_id, command, stdin))
+            .await
+            .unwrap_or(Err(ScanError::Timeout(self.timeout)));
+
+        if let Err(e) = self
+            .call(wsman::delete_shell(&url, timeout, &shell_id))
+            .await
+        {
+            tracing::warn!(host = %self.host, error = %e, "Failed to delete remote shell");
+        }
+
+        result
+    }
+
+    /// Run [`execute_once`](Self::execute_once), retrying transient
+    /// failures according to the transport's [`RetryPolicy`].
+    async fn execute_retrying(
+        &self,
+        command: &str,
+        stdin: Option<&str>,
+    ) -> Result<String, ScanError> {
+        let mut attempt = 1;
+        loop {
+            match self.execute_once(command, stdin).await {
+                Err(e) if e.is_transient() && attempt < self.retry.attempts() => {
+                    let delay = self.retry.backoff(attempt);
+                    tracing::warn!(
+                        host = %self.host,
+                        attempt,
+                        error = %e,
+                        "WinRM attempt failed, retrying in {:?}",
+                        delay
+                    );
+                    self.emit(ScanStage::Retrying { attempt, delay });
+                    // The pooled connection, and its security context, may be gone.
+                    *self.session() = None;
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl WinrmTransport for HttpWinrmTransport {
+    /// Run `command` in a new remote shell over WS-Management.
+    ///
+    /// Creates a shell, runs the command, streams its output until it
+    /// exits and always deletes the shell afterwards. Each attempt is
+    /// bounded by the transport timeout; network failures and timeouts are
+    /// retried according to the transport's [`RetryPolicy`].
+    async fn execute(&self, command: &str) -> Result<String, ScanError> {
+        self.execute_retrying(command, None).await
+    }
+
+    /// Like [`execute`](WinrmTransport::execute), sending `script` over the
+    /// command's stdin stream in [`STDIN_CHUNK_BYTES`] pieces.
+    async fn execute_script(&self, command: &str, script: &str) -> Result<String, ScanError> {
+        self.execute_retrying(command, Some(script)).await
+    }
 }
@@ -0,0 +1,465 @@
+//! WS-Management (WinRM) SOAP messages for the Windows Remote Shell.
+//!
+//! Builds the request envelopes for the shell lifecycle — Create, Command,
+//! Send, Receive, Signal and Delete — and parses the parts of the responses the
+//! transport needs: shell and command ids, output streams, exit code and
+//! SOAP faults.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::time::Duration;
+
+/// Resource URI of the `cmd` remote shell.
+const SHELL_URI: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/cmd";
+
+const ACTION_CREATE: &str = "http://schemas.xmlsoap.org/ws/2004/09/transfer/Create";
+const ACTION_DELETE: &str = "http://schemas.xmlsoap.org/ws/2004/09/transfer/Delete";
+const ACTION_COMMAND: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Command";
+const ACTION_SEND: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Send";
+const ACTION_RECEIVE: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Receive";
+const ACTION_SIGNAL: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Signal";
+
+const SIGNAL_TERMINATE: &str =
+    "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/signal/terminate";
+
+/// `CommandState` value reported once the command has exited.
+const STATE_DONE: &str =
+    "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/CommandState/Done";
+
+/// WSManFault code returned when a Receive saw no output within the
+/// operation timeout. Not an error: the client simply polls again.
+pub const FAULT_OPERATION_TIMEOUT: &str = "2150858793";
+
+/// Maximum envelope size we accept in responses.
+const MAX_ENVELOPE_SIZE: u32 = 512_000;
+
+/// Envelope for one WS-Man request.
+fn envelope(
+    url: &str,
+    action: &str,
+    timeout: Duration,
+    shell_id: Option<&str>,
+    options: &[(&str, &str)],
+    body: &str,
+) -> String {
+    let selectors = shell_id
+        .map(|id| {
+            format!(
+                "<wsman:SelectorSet><wsman:Selector Name=\"ShellId\">{}</wsman:Selector></wsman:SelectorSet>",
+                escape(id)
+            )
+        })
+        .unwrap_or_default();
+    let options = if options.is_empty() {
+        String::new()
+    } else {
+        let options: String = options
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "<wsman:Option Name=\"{}\">{}</wsman:Option>",
+                    name,
+                    escape(value)
+                )
+            })
+            .collect();
+        format!("<wsman:OptionSet>{}</wsman:OptionSet>", options)
+    };
+
+    format!(
+        concat!(
+            "<s:Envelope xmlns:s=\"http://www.w3.org/2003/05/soap-envelope\"",
+            " xmlns:wsa=\"http://schemas.xmlsoap.org/ws/2004/08/addressing\"",
+            " xmlns:wsman=\"http://schemas.dmtf.org/wbem/wsman/1/wsman.xsd\"",
+            " xmlns:rsp=\"http://schemas.microsoft.com/wbem/wsman/1/windows/shell\">",
+            "<s:Header>",
+            "<wsa:To>{url}</wsa:To>",
+            "<wsman:ResourceURI s:mustUnderstand=\"true\">{resource}</wsman:ResourceURI>",
+            "<wsa:ReplyTo><wsa:Address s:mustUnderstand=\"true\">",
+            "http://schemas.xmlsoap.org/ws/2004/08/addressing/role/anonymous",
+            "</wsa:Address></wsa:ReplyTo>",
+            "<wsa:Action s:mustUnderstand=\"true\">{action}</wsa:Action>",
+            "<wsman:MaxEnvelopeSize s:mustUnderstand=\"true\">{max}</wsman:MaxEnvelopeSize>",
+            "<wsa:MessageID>uuid:{id}</wsa:MessageID>",
+            "<wsman:Locale xml:lang=\"en-US\" s:mustUnderstand=\"false\"/>",
+            "<wsman:OperationTimeout>PT{timeout}S</wsman:OperationTimeout>",
+            "{selectors}{options}",
+            "</s:Header>",
+            "<s:Body>{body}</s:Body>",
+            "</s:Envelope>"
+        ),
+        url = escape(url),
+        resource = SHELL_URI,
+        action = action,
+        max = MAX_ENVELOPE_SIZE,
+        id = uuid::Uuid::new_v4().to_string().to_uppercase(),
+        timeout = timeout.as_secs().max(1),
+        selectors = selectors,
+        options = options,
+        body = body,
+    )
+}
+
+/// Create a shell with stdin/stdout/stderr streams.
+pub fn create_shell(url: &str, timeout: Duration) -> String {
+    envelope(
+        url,
+        ACTION_CREATE,
+        timeout,
+        None,
+        &[("WINRS_NOPROFILE", "TRUE"), ("WINRS_CODEPAGE", "65001")],
+        "<rsp:Shell><rsp:InputStreams>stdin</rsp:InputStreams>\
+         <rsp:OutputStreams>stdout stderr</rsp:OutputStreams></rsp:Shell>",
+    )
+}
+
+/// Start `command` with `arguments` in a shell.
+///
+/// The command runs without an intermediate `cmd.exe`, so the command line
+/// may exceed cmd's 8191 character limit. With `piped_stdin` its stdin is a
+/// pipe fed by [`send`] rather than a console.
+pub fn command(
+    url: &str,
+    timeout: Duration,
+    shell_id: &str,
+    command: &str,
+    arguments: &str,
+    piped_stdin: bool,
+) -> String {
+    let arguments = if arguments.is_empty() {
+        String::new()
+    } else {
+        format!("<rsp:Arguments>{}</rsp:Arguments>", escape(arguments))
+    };
+    envelope(
+        url,
+        ACTION_COMMAND,
+        timeout,
+        Some(shell_id),
+        &[
+            (
+                "WINRS_CONSOLEMODE_STDIN",
+                if piped_stdin { "FALSE" } else { "TRUE" },
+            ),
+            ("WINRS_SKIP_CMD_SHELL", "TRUE"),
+        ],
+        &format!(
+            "<rsp:CommandLine><rsp:Command>{}</rsp:Command>{}</rsp:CommandLine>",
+            escape(command),
+            arguments
+        ),
+    )
+}
+
+/// Write `data` to a command's stdin; `end` closes the stream.
+pub fn send(
+    url: &str,
+    timeout: Duration,
+    shell_id: &str,
+    command_id: &str,
+    data: &[u8],
+    end: bool,
+) -> String {
+    envelope(
+        url,
+        ACTION_SEND,
+        timeout,
+        Some(shell_id),
+        &[],
+        &format!(
+            "<rsp:Send><rsp:Stream Name=\"stdin\" CommandId=\"{}\"{}>{}</rsp:Stream></rsp:Send>",
+            escape(command_id),
+            if end { " End=\"true\"" } else { "" },
+            STANDARD.encode(data)
+        ),
+    )
+}
+
+/// Poll a command for stdout and stderr.
+pub fn receive(url: &str, timeout: Duration, shell_id: &str, command_id: &str) -> String {
+    envelope(
+        url,
+        ACTION_RECEIVE,
+        timeout,
+        Some(shell_id),
+        &[],
+        &format!(
+            "<rsp:Receive><rsp:DesiredStream CommandId=\"{}\">stdout stderr</rsp:DesiredStream></rsp:Receive>",
+            escape(command_id)
+        ),
+    )
+}
+
+/// Terminate a command so its resources are released.
+pub fn signal_terminate(url: &str, timeout: Duration, shell_id: &str, command_id: &str) -> String {
+    envelope(
+        url,
+        ACTION_SIGNAL,
+        timeout,
+        Some(shell_id),
+        &[],
+        &format!(
+            "<rsp:Signal CommandId=\"{}\"><rsp:Code>{}</rsp:Code></rsp:Signal>",
+            escape(command_id),
+            SIGNAL_TERMINATE
+        ),
+    )
+}
+
+/// Delete a shell.
+pub fn delete_shell(url: &str, timeout: Duration, shell_id: &str) -> String {
+    envelope(url, ACTION_DELETE, timeout, Some(shell_id), &[], "")
+}
+
+/// Output collected by one Receive response.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReceiveOutput {
+    /// Decoded stdout chunk
+    pub stdout: Vec<u8>,
+    /// Decoded stderr chunk
+    pub stderr: Vec<u8>,
+    /// Exit code, set once the command is done
+    pub exit_code: Option<i32>,
+    /// Whether the command has finished
+    pub done: bool,
+}
+
+/// A SOAP / WSManFault.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Fault {
+    /// WSManFault code (a Win32/WinRM error number) if present
+    pub code: Option<String>,
+    /// Human-readable reason
+    pub message: String,
+}
+
+/// One element from a response: local name, attributes and direct text.
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Flatten a response into its elements, in document order.
+fn elements(xml: &str) -> Result<Vec<Element>, String> {
+    fn open(start: &BytesStart<'_>) -> Result<Element, String> {
+        let mut attributes = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| e.to_string())?;
+            let value = attr.unescape_value().map_err(|e| e.to_string())?;
+            attributes.push((
+                String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned(),
+                value.into_owned(),
+            ));
+        }
+        Ok(Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+            attributes,
+            text: String::new(),
+        })
+    }
+
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut elements = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(start) => {
+                stack.push(elements.len());
+                elements.push(open(&start)?);
+            }
+            Event::Empty(start) => elements.push(open(&start)?),
+            Event::Text(text) => {
+                if let Some(&i) = stack.last() {
+                    let text = text.unescape().map_err(|e| e.to_string())?;
+                    elements[i].text.push_str(&text);
+                }
+            }
+            Event::End(_) => {
+                stack.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(elements)
+}
+
+/// Extract the shell id from a Create response.
+pub fn parse_shell_id(xml: &str) -> Result<String, String> {
+    let elements = elements(xml)?;
+    elements
+        .iter()
+        .find(|e| e.name == "ShellId")
+        .or_else(|| {
+            elements
+                .iter()
+                .find(|e| e.name == "Selector" && e.attribute("Name") == Some("ShellId"))
+        })
+        .map(|e| e.text.clone())
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| "no ShellId in Create response".to_string())
+}
+
+/// Extract the command id from a Command response.
+pub fn parse_command_id(xml: &str) -> Result<String, String> {
+    elements(xml)?
+        .into_iter()
+        .find(|e| e.name == "CommandId")
+        .map(|e| e.text)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| "no CommandId in Command response".to_string())
+}
+
+/// Decode the streams and command state of a Receive response.
+pub fn parse_receive(xml: &str) -> Result<ReceiveOutput, String> {
+    let mut output = ReceiveOutput::default();
+    for element in elements(xml)? {
+        match element.name.as_str() {
+            "Stream" if !element.text.is_empty() => {
+                let bytes = STANDARD
+                    .decode(element.text.as_bytes())
+                    .map_err(|e| format!("invalid stream data: {}", e))?;
+                match element.attribute("Name") {
+                    Some("stdout") => output.stdout.extend(bytes),
+                    Some("stderr") => output.stderr.extend(bytes),
+                    _ => {}
+                }
+            }
+            "CommandState" => {
+                output.done = element.attribute("State") == Some(STATE_DONE);
+            }
+            "ExitCode" => output.exit_code = element.text.trim().parse().ok(),
+            _ => {}
+        }
+    }
+    Ok(output)
+}
+
+/// Extract a fault from an error response, if it is one.
+pub fn parse_fault(xml: &str) -> Option<Fault> {
+    let elements = elements(xml).ok()?;
+    let fault = elements.iter().position(|e| e.name == "Fault")?;
+    let elements = &elements[fault..];
+
+    let code = elements
+        .iter()
+        .find(|e| e.name == "WSManFault")
+        .and_then(|e| e.attribute("Code"))
+        .map(str::to_string);
+    let message = elements
+        .iter()
+        .find(|e| e.name == "Message" && !e.text.is_empty())
+        .or_else(|| elements.iter().find(|e| e.name == "Text"))
+        .map(|e| e.text.trim().to_string())
+        .unwrap_or_else(|| "unknown WS-Management fault".to_string());
+    Some(Fault { code, message })
+}
+
+/// Escape XML special characters.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "http://host:5985/wsman";
+    const SHELL: &str = "11111111-2222-3333-4444-555555555555";
+
+    #[test]
+    fn test_command_envelope() {
+        let xml = command(
+            URL,
+            Duration::from_secs(20),
+            SHELL,
+            "powershell",
+            "-C \"a<b\"",
+            false,
+        );
+        assert!(xml.contains("<wsa:Action s:mustUnderstand=\"true\">http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Command</wsa:Action>"));
+        assert!(xml.contains(&format!(
+            "<wsman:Selector Name=\"ShellId\">{}</wsman:Selector>",
+            SHELL
+        )));
+        assert!(xml.contains("<wsman:Option Name=\"WINRS_SKIP_CMD_SHELL\">TRUE</wsman:Option>"));
+        assert!(xml.contains("<rsp:Arguments>-C &quot;a&lt;b&quot;</rsp:Arguments>"));
+        assert!(xml.contains("<wsman:OperationTimeout>PT20S</wsman:OperationTimeout>"));
+    }
+
+    #[test]
+    fn test_send_envelope() {
+        let xml = send(URL, Duration::from_secs(20), SHELL, "CMD-1", b"hi", true);
+        assert!(xml.contains("<wsa:Action s:mustUnderstand=\"true\">http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Send</wsa:Action>"));
+        assert!(xml.contains(
+            "<rsp:Stream Name=\"stdin\" CommandId=\"CMD-1\" End=\"true\">aGk=</rsp:Stream>"
+        ));
+
+        let xml = send(URL, Duration::from_secs(20), SHELL, "CMD-1", b"hi", false);
+        assert!(!xml.contains("End="));
+    }
+
+    #[test]
+    fn test_parse_shell_and_command_id() {
+        let create = format!(
+            "<s:Envelope xmlns:s=\"http://www.w3.org/2003/05/soap-envelope\" xmlns:rsp=\"x\"><s:Body>\
+             <rsp:Shell><rsp:ShellId>{}</rsp:ShellId></rsp:Shell></s:Body></s:Envelope>",
+            SHELL
+        );
+        assert_eq!(parse_shell_id(&create).unwrap(), SHELL);
+
+        let cmd = "<s:Envelope xmlns:s=\"a\" xmlns:rsp=\"b\"><s:Body><rsp:CommandResponse>\
+                   <rsp:CommandId>ABC</rsp:CommandId></rsp:CommandResponse></s:Body></s:Envelope>";
+        assert_eq!(parse_command_id(cmd).unwrap(), "ABC");
+        assert!(parse_command_id("<a/>").is_err());
+    }
+
+    #[test]
+    fn test_parse_receive() {
+        let xml = format!(
+            "<s:Envelope xmlns:s=\"a\" xmlns:rsp=\"b\"><s:Body><rsp:ReceiveResponse>\
+             <rsp:Stream Name=\"stdout\" CommandId=\"C\">{}</rsp:Stream>\
+             <rsp:Stream Name=\"stdout\" CommandId=\"C\">{}</rsp:Stream>\
+             <rsp:Stream Name=\"stderr\" CommandId=\"C\" End=\"true\"/>\
+             <rsp:CommandState CommandId=\"C\" State=\"{}\"><rsp:ExitCode>0</rsp:ExitCode></rsp:CommandState>\
+             </rsp:ReceiveResponse></s:Body></s:Envelope>",
+            STANDARD.encode("{\"a\":"),
+            STANDARD.encode("1}"),
+            STATE_DONE
+        );
+        let output = parse_receive(&xml).unwrap();
+        assert_eq!(output.stdout, b"{\"a\":1}");
+        assert!(output.stderr.is_empty());
+        assert!(output.done);
+        assert_eq!(output.exit_code, Some(0));
+    }
+
+    #[test]
+    fn test_parse_fault() {
+        let xml = "<s:Envelope xmlns:s=\"a\" xmlns:f=\"b\"><s:Body><s:Fault>\
+                   <s:Reason><s:Text xml:lang=\"en-US\">The WS-Management service cannot complete the operation within the time specified in OperationTimeout.</s:Text></s:Reason>\
+                   <s:Detail><f:WSManFault Code=\"2150858793\" Machine=\"host\"><f:Message>timed out</f:Message></f:WSManFault></s:Detail>\
+                   </s:Fault></s:Body></s:Envelope>";
+        let fault = parse_fault(xml).unwrap();
+        assert_eq!(fault.code.as_deref(), Some(FAULT_OPERATION_TIMEOUT));
+        assert_eq!(fault.message, "timed out");
+        assert!(parse_fault("<s:Envelope xmlns:s=\"a\"/>").is_none());
+    }
+}
@@ -0,0 +1,686 @@
+//! Full audit report model.
+//!
+//! [`SysauditReport`] is the wire DTO shared with `sysaudit-common` and the
+//! remote payload. [`AuditReport`] carries the same sections plus the data
+//! only this library collects (Windows updates and services), and serializes to a JSON
+//! superset of the DTO so either side can read the other.
+//!
+//! Stored reports carry a `schema_version`:
+//!
+//! | Version | Changes |
+//! |:---|:---|
+//! | 1 | Original DTO layout (no `schema_version` field) |
+//! | 2 | Adds `schema_version` and `updates` |
+//! | 3 | Adds `services` |
+//! | 4 | Adds `warnings` |
+//! | 5 | Adds `security` |
+//! | 6 | Adds `score` |
+//! | 7 | Adds `extensions` |
+//! | 8 | Adds `metrics` |
+//! | 9 | Adds `elevated` |
+//! | 10 | Adds `metadata` |
+//! | 11 | Adds `identity` |
+//! | 12 | Adds `os_build` |
+//! | 13 | Adds `update_health` |
+//! | 14 | Adds `asset_id` |
+//! | 15 | Adds `licensing` |
+//! | 16 | Adds `redistributables` |
+//! | 17 | Adds `event_logs` |
+//! | 18 | Adds `autopilot` |
+//! | 19 | Adds `containers` |
+//!
+//! [`AuditReport::from_json_versioned`] upgrades older documents step by
+//! step, so reports written by any earlier release stay readable. [`diff`]
+//! lists what changed between two reports of the same host, and
+//! [`AuditReport::merge`] combines partial captures of one host.
+
+mod diff;
+mod merge;
+
+pub use diff::{ChangeKind, FieldChange, ItemChange, ReportDiff, diff};
+pub use merge::{MergeConflict, MergeResult, SectionSource};
+
+use crate::metadata::ScanMetadata;
+use crate::metrics::ScanMetrics;
+use crate::options::Collector;
+use crate::redistributables::Redistributable;
+use crate::score::AuditScore;
+use crate::{
+    Autopilot, Containers, Error, EventLogs, HostIdentity, IndustrialSoftware, Licensing,
+    RuntimeState, SecurityPosture, Software, SystemInfo, UpdateHealth, WindowsService,
+    WindowsUpdate,
+};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use sysaudit_common::{
+    IndustrialSoftwareDto, IpVersion, NetworkInterfaceDto, SoftwareDto, SysauditReport,
+    SystemInfoDto,
+};
+
+/// Current report schema version.
+pub const REPORT_SCHEMA_VERSION: u32 = 19;
+
+/// Complete audit report for a single host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// Schema version of the document this report was read from
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+    /// System information
+    pub system: SystemInfoDto,
+    /// Installed software
+    pub software: Vec<SoftwareDto>,
+    /// Detected industrial software
+    pub industrial: Vec<IndustrialSoftwareDto>,
+    /// Installed Windows updates
+    #[serde(default)]
+    pub updates: Vec<WindowsUpdate>,
+    /// Installed Windows services
+    #[serde(default)]
+    pub services: Vec<WindowsService>,
+    /// Security settings, if collected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<SecurityPosture>,
+    /// Windows Update agent health, if collected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_health: Option<UpdateHealth>,
+    /// Windows edition, license channels and activation, if collected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub licensing: Option<Licensing>,
+    /// Event log sizes, retention and forwarding, if collected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_logs: Option<EventLogs>,
+    /// Autopilot registration data, if collected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autopilot: Option<Autopilot>,
+    /// WSL distributions and container runtimes, if collected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub containers: Option<Containers>,
+    /// Visual C++, OPC Core Components and .NET runtimes among the
+    /// installed software
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redistributables: Vec<Redistributable>,
+    /// Non-fatal problems during collection; a section with a warning may
+    /// be incomplete or empty
+    #[serde(default)]
+    pub warnings: Vec<ScanWarning>,
+    /// Executive score, set by the scanners
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<AuditScore>,
+    /// Sections added by custom collectors, keyed by section name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, serde_json::Value>,
+    /// Per-collector timings and counts, set by local scans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<ScanMetrics>,
+    /// Whether the scan ran with administrator rights, if known; sections
+    /// it could not fully read carry a warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevated: Option<bool>,
+    /// Tool version, scanner, user, options and duration of the scan,
+    /// set by the scanners
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ScanMetadata>,
+    /// FQDN and DNS suffixes of the host, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<HostIdentity>,
+    /// OS build with update build revision (e.g. "22631.3007"), when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_build: Option<String>,
+    /// Stable hardware-derived identifier of the host, see
+    /// [`asset`](crate::asset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_id: Option<String>,
+    /// Time the report was produced
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A collector that failed or gave up without failing the scan.
+///
+/// Tells "no updates installed" apart from "the update query failed".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanWarning {
+    /// The collector whose section is affected
+    pub collector: Collector,
+    /// What went wrong
+    pub message: String,
+}
+
+impl ScanWarning {
+    /// Warning for `collector`.
+    pub fn new(collector: Collector, message: impl Into<String>) -> Self {
+        ScanWarning {
+            collector,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ScanWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.collector, self.message)
+    }
+}
+
+impl AuditReport {
+    /// Build a report from locally collected data.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{AuditReport, IndustrialScanner, SoftwareScanner, SystemInfo, WindowsUpdate};
+    ///
+    /// let software = SoftwareScanner::new().scan().unwrap();
+    /// let industrial = IndustrialScanner::all_vendors().classify_from(&software).unwrap();
+    /// let report = AuditReport::from_local(
+    ///     SystemInfo::collect().unwrap(),
+    ///     software,
+    ///     industrial,
+    ///     WindowsUpdate::collect_all(),
+    /// );
+    /// println!("Host: {}", report.system.host_name);
+    /// ```
+    pub fn from_local(
+        system: SystemInfo,
+        software: Vec<Software>,
+        industrial: Vec<IndustrialSoftware>,
+        updates: Vec<WindowsUpdate>,
+    ) -> Self {
+        let identity = Some(system.identity.clone()).filter(|i| *i != HostIdentity::default());
+        let os_build = Some(system.build_number.clone()).filter(|b| !b.is_empty());
+        let asset_id = system.fingerprint.asset_id();
+        let software: Vec<SoftwareDto> = software.into_iter().map(Into::into).collect();
+        AuditReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            system: system.into(),
+            redistributables: Redistributable::collect(&software),
+            software,
+            industrial: industrial.into_iter().map(Into::into).collect(),
+            updates,
+            services: Vec::new(),
+            security: None,
+            update_health: None,
+            licensing: None,
+            event_logs: None,
+            autopilot: None,
+            containers: None,
+            warnings: Vec::new(),
+            score: None,
+            extensions: BTreeMap::new(),
+            metrics: None,
+            elevated: None,
+            metadata: None,
+            identity,
+            os_build,
+            asset_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Load a stored report of any supported schema version.
+    ///
+    /// Older documents are upgraded to [`REPORT_SCHEMA_VERSION`]; documents
+    /// without a `schema_version` are treated as version 1.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::AuditReport;
+    ///
+    /// let json = std::fs::read_to_string("report-2023.json").unwrap();
+    /// let report = AuditReport::from_json_versioned(&json).unwrap();
+    /// println!("{} updates", report.updates.len());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`] if the document is not a valid report, or
+    /// [`Error::General`] if it was written by a newer, unsupported version.
+    pub fn from_json_versioned(json: &str) -> Result<Self, Error> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        let mut version = match value.get("schema_version") {
+            Some(v) => u32::deserialize(v)?,
+            None => legacy_schema_version(),
+        };
+
+        if version > REPORT_SCHEMA_VERSION {
+            return Err(Error::General(format!(
+                "report schema version {} is newer than supported version {}",
+                version, REPORT_SCHEMA_VERSION
+            )));
+        }
+
+        let derive_redistributables = version < 16;
+        while version < REPORT_SCHEMA_VERSION {
+            upgrade(&mut value, version)?;
+            version += 1;
+        }
+
+        let mut report: AuditReport = serde_json::from_value(value)?;
+        report.schema_version = REPORT_SCHEMA_VERSION;
+        if derive_redistributables {
+            report.redistributables = Redistributable::collect(&report.software);
+        }
+        Ok(report)
+    }
+
+    /// A custom collector's section decoded as `T`, or `None` if the report
+    /// has no such section.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::AuditReport;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct AssetTag {
+    ///     tag: String,
+    /// }
+    ///
+    /// let json = std::fs::read_to_string("report.json").unwrap();
+    /// let report = AuditReport::from_json_versioned(&json).unwrap();
+    /// if let Some(asset) = report.extension::<AssetTag>("asset_tag").unwrap() {
+    ///     println!("Asset {}", asset.tag);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`] if the section does not match `T`.
+    pub fn extension<T: DeserializeOwned>(&self, section: &str) -> Result<Option<T>, Error> {
+        self.extensions
+            .get(section)
+            .map(|value| T::deserialize(value).map_err(Error::from))
+            .transpose()
+    }
+
+    /// Name that identifies the host across domains: its FQDN when known,
+    /// otherwise the NetBIOS host name.
+    pub fn host_id(&self) -> &str {
+        self.identity
+            .as_ref()
+            .and_then(|identity| identity.fqdn.as_deref())
+            .unwrap_or(&self.system.host_name)
+    }
+
+    /// Whether a detected industrial product runs as a service, from the
+    /// report's services. `None` when the product has no install path or the
+    /// services section was not collected.
+    pub fn runtime_state(&self, sw: &IndustrialSoftwareDto) -> Option<RuntimeState> {
+        RuntimeState::of(sw.install_path.as_deref()?, &self.services)
+    }
+}
+
+/// Version assumed for documents without a `schema_version` field.
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+/// Upgrade a report document from `version` to `version + 1` in place.
+fn upgrade(value: &mut serde_json::Value, version: u32) -> Result<(), Error> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| Error::General("report is not a JSON object".to_string()))?;
+
+    if version == 1 {
+        // v2: Windows updates section
+        object
+            .entry("updates")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    }
+    if version == 2 {
+        // v3: Windows services section
+        object
+            .entry("services")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    }
+    if version == 3 {
+        // v4: collection warnings
+        object
+            .entry("warnings")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    }
+    // v5: optional security section, absent in older documents
+    // v6: optional score, absent in older documents
+    // v7: optional extensions, absent in older documents
+    // v8: optional metrics, absent in older documents
+    // v9: optional elevated flag, absent in older documents
+    // v10: optional metadata, absent in older documents
+    // v11: optional host identity, absent in older documents
+    // v12: optional OS build, absent in older documents
+    // v13: optional update agent health, absent in older documents
+    // v14: optional asset ID, absent in older documents
+    // v15: optional licensing, absent in older documents
+    // v16: redistributables, derived from the software list on load
+    // v17: optional event log configuration, absent in older documents
+    // v18: optional Autopilot registration data, absent in older documents
+    // v19: optional WSL and container runtimes, absent in older documents
+
+    object.insert("schema_version".to_string(), (version + 1).into());
+    Ok(())
+}
+
+#[cfg(test)]
+impl AuditReport {
+    /// Report of `system` with every section empty, for tests to fill in
+    /// with struct update syntax.
+    pub(crate) fn empty(system: SystemInfoDto) -> Self {
+        AuditReport::from(SysauditReport {
+            system,
+            software: Vec::new(),
+            industrial: Vec::new(),
+            timestamp: Utc::now(),
+        })
+    }
+}
+
+/// Host section shared by the formatter and exporter tests.
+#[cfg(test)]
+pub(crate) fn test_system() -> SystemInfoDto {
+    SystemInfoDto {
+        os_name: "Windows 11 Pro".into(),
+        os_version: "23H2".into(),
+        host_name: "TEST-PC".into(),
+        cpu_info: "Intel i7".into(),
+        cpu_physical_cores: Some(8),
+        memory_total_bytes: 1000,
+        memory_used_bytes: 500,
+        manufacturer: None,
+        model: None,
+        network_interfaces: vec![],
+    }
+}
+
+impl From<SysauditReport> for AuditReport {
+    fn from(report: SysauditReport) -> Self {
+        AuditReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            system: report.system,
+            redistributables: Redistributable::collect(&report.software),
+            software: report.software,
+            industrial: report.industrial,
+            updates: Vec::new(),
+            services: Vec::new(),
+            security: None,
+            update_health: None,
+            licensing: None,
+            event_logs: None,
+            autopilot: None,
+            containers: None,
+            warnings: Vec::new(),
+            score: None,
+            extensions: BTreeMap::new(),
+            metrics: None,
+            elevated: None,
+            metadata: None,
+            identity: None,
+            os_build: None,
+            asset_id: None,
+            timestamp: report.timestamp,
+        }
+    }
+}
+
+impl From<AuditReport> for SysauditReport {
+    fn from(report: AuditReport) -> Self {
+        SysauditReport {
+            system: report.system,
+            software: report.software,
+            industrial: report.industrial,
+            timestamp: report.timestamp,
+        }
+    }
+}
+
+impl From<SystemInfo> for SystemInfoDto {
+    fn from(info: SystemInfo) -> Self {
+        SystemInfoDto {
+            os_name: info.os_name,
+            os_version: info.os_version,
+            host_name: info.computer_name,
+            cpu_info: info.cpu_info,
+            cpu_physical_cores: info.cpu_cores_physical.map(|c| c as u32),
+            memory_total_bytes: info.memory_total,
+            memory_used_bytes: info.memory_used,
+            manufacturer: info.manufacturer,
+            model: info.model,
+            // Reports keep one entry per address, grouped or not.
+            network_interfaces: info
+                .network_interfaces
+                .iter()
+                .flat_map(|iface| {
+                    iface.ip_addresses().map(|ip| {
+                        let ip_version = if ip.is_ipv4() {
+                            IpVersion::IPv4
+                        } else {
+                            IpVersion::IPv6
+                        };
+
+                        NetworkInterfaceDto {
+                            name: iface.name.clone(),
+                            ip_address: ip.to_string(),
+                            ip_version,
+                            mac_address: iface.mac_address.clone(),
+                        }
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Software> for SoftwareDto {
+    fn from(sw: Software) -> Self {
+        let install_date = sw
+            .install_date
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc());
+        SoftwareDto {
+            name: sw.name,
+            version: sw.version,
+            vendor: sw.publisher,
+            install_date,
+        }
+    }
+}
+
+impl From<IndustrialSoftware> for IndustrialSoftwareDto {
+    fn from(sw: IndustrialSoftware) -> Self {
+        IndustrialSoftwareDto {
+            vendor: sw.vendor.to_string(),
+            product: sw.product,
+            version: sw.version,
+            install_path: sw.install_path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RegistrySource, Vendor};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_software_dto_conversion() {
+        let sw = Software {
+            name: "TestApp".into(),
+            version: Some("1.0".into()),
+            publisher: Some("Acme".into()),
+            install_date: NaiveDate::from_ymd_opt(2024, 1, 15),
+            install_date_source: crate::InstallDateSource::Exact,
+            install_location: None,
+            source: RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
+        };
+
+        let dto = SoftwareDto::from(sw);
+        assert_eq!(dto.name, "TestApp");
+        assert_eq!(dto.vendor.as_deref(), Some("Acme"));
+        assert_eq!(
+            dto.install_date.map(|d| d.date_naive()),
+            NaiveDate::from_ymd_opt(2024, 1, 15)
+        );
+    }
+
+    #[test]
+    fn test_industrial_dto_conversion() {
+        let sw = IndustrialSoftware {
+            vendor: Vendor::SchneiderElectric,
+            product: "EcoStruxure".into(),
+            version: None,
+            install_path: None,
+            runtime_state: None,
+        };
+
+        let dto = IndustrialSoftwareDto::from(sw);
+        assert_eq!(dto.vendor, "Schneider Electric");
+        assert_eq!(dto.product, "EcoStruxure");
+    }
+
+    #[test]
+    fn test_report_reads_dto_json() {
+        let json = r#"{
+            "system": {
+                "os_name": "Windows 11 Pro",
+                "os_version": "23H2",
+                "host_name": "TEST-PC",
+                "cpu_info": "Intel i7",
+                "cpu_physical_cores": 8,
+                "memory_total_bytes": 1000,
+                "memory_used_bytes": 500,
+                "manufacturer": null,
+                "model": null,
+                "network_interfaces": []
+            },
+            "software": [],
+            "industrial": [],
+            "timestamp": "2024-01-15T00:00:00Z"
+        }"#;
+
+        let report: AuditReport = serde_json::from_str(json).unwrap();
+        assert_eq!(report.system.host_name, "TEST-PC");
+        assert!(report.updates.is_empty());
+    }
+
+    #[test]
+    fn test_from_json_versioned_upgrades_v1() {
+        let json = r#"{
+            "system": {
+                "os_name": "Windows 10 Pro",
+                "os_version": "22H2",
+                "host_name": "OLD-PC",
+                "cpu_info": "Intel i5",
+                "cpu_physical_cores": null,
+                "memory_total_bytes": 1000,
+                "memory_used_bytes": 500,
+                "manufacturer": null,
+                "model": null,
+                "network_interfaces": []
+            },
+            "software": [{
+                "name": "Microsoft Visual C++ 2010  x86 Redistributable - 10.0.40219",
+                "version": "10.0.40219",
+                "vendor": "Microsoft Corporation",
+                "install_date": null
+            }],
+            "industrial": [],
+            "timestamp": "2021-06-01T00:00:00Z"
+        }"#;
+
+        let report = AuditReport::from_json_versioned(json).unwrap();
+        assert_eq!(report.schema_version, REPORT_SCHEMA_VERSION);
+        assert_eq!(report.system.host_name, "OLD-PC");
+        assert!(report.updates.is_empty());
+        assert!(report.services.is_empty());
+        assert!(report.warnings.is_empty());
+        assert!(report.security.is_none());
+        assert!(report.extensions.is_empty());
+        assert_eq!(report.redistributables.len(), 1);
+        assert_eq!(report.redistributables[0].year.as_deref(), Some("2010"));
+    }
+
+    #[test]
+    fn test_extension_typed() {
+        let mut report: AuditReport = serde_json::from_str(
+            r#"{
+                "system": {
+                    "os_name": "Windows 10 Pro",
+                    "os_version": "22H2",
+                    "host_name": "HMI01",
+                    "cpu_info": "Intel i5",
+                    "cpu_physical_cores": null,
+                    "memory_total_bytes": 1000,
+                    "memory_used_bytes": 500,
+                    "manufacturer": null,
+                    "model": null,
+                    "network_interfaces": []
+                },
+                "software": [],
+                "industrial": [],
+                "extensions": { "asset_tag": { "tag": "PLC-0042" } },
+                "timestamp": "2024-06-01T00:00:00Z"
+            }"#,
+        )
+        .unwrap();
+
+        #[derive(Deserialize)]
+        struct AssetTag {
+            tag: String,
+        }
+        let asset: Option<AssetTag> = report.extension("asset_tag").unwrap();
+        assert_eq!(asset.unwrap().tag, "PLC-0042");
+        assert!(report.extension::<AssetTag>("missing").unwrap().is_none());
+        report
+            .extensions
+            .insert("asset_tag".into(), serde_json::json!(42));
+        assert!(report.extension::<AssetTag>("asset_tag").is_err());
+    }
+
+    #[test]
+    fn test_host_id_prefers_fqdn() {
+        let mut report: AuditReport = serde_json::from_str(
+            r#"{
+                "system": {
+                    "os_name": "Windows 10 Pro",
+                    "os_version": "22H2",
+                    "host_name": "HMI01",
+                    "cpu_info": "Intel i5",
+                    "cpu_physical_cores": null,
+                    "memory_total_bytes": 1000,
+                    "memory_used_bytes": 500,
+                    "manufacturer": null,
+                    "model": null,
+                    "network_interfaces": []
+                },
+                "software": [],
+                "industrial": [],
+                "timestamp": "2024-06-01T00:00:00Z"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(report.host_id(), "HMI01");
+
+        report.identity = Some(HostIdentity::new("HMI01", Some("Plant.Example.com"), None));
+        assert_eq!(report.host_id(), "hmi01.plant.example.com");
+    }
+
+    #[test]
+    fn test_from_json_versioned_rejects_newer() {
+        let json = format!(r#"{{"schema_version": {}}}"#, REPORT_SCHEMA_VERSION + 1);
+        let err = AuditReport::from_json_versioned(&json).unwrap_err();
+        assert!(err.to_string().contains("newer than supported"));
+    }
+
+    #[test]
+    fn test_from_json_versioned_rejects_non_integer_version() {
+        for version in [r#""15""#, "2.5", "-1", "null"] {
+            let json = format!(r#"{{"schema_version": {}}}"#, version);
+            let err = AuditReport::from_json_versioned(&json).unwrap_err();
+            assert!(matches!(err, Error::Json(_)), "{}: {}", version, err);
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Changes between two reports of the same host.
+//!
+//! Software is matched by name, industrial products by vendor and product,
+//! updates by hotfix ID. A package installed in several versions (e.g. in
+//! both the 32- and 64-bit hives) is compared by its full version list.
+
+use super::AuditReport;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use sysaudit_common::IndustrialSoftwareDto;
+
+/// How an item differs between the old and new report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Only in the new report
+    Added,
+    /// Only in the old report
+    Removed,
+    /// In both, with a different version
+    Changed,
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// An added, removed or upgraded package or update.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemChange {
+    /// What happened
+    pub kind: ChangeKind,
+    /// Software name, `Vendor Product` or hotfix ID
+    pub name: String,
+    /// Version in the old report
+    pub old_version: Option<String>,
+    /// Version in the new report
+    pub new_version: Option<String>,
+}
+
+/// A system field with a different value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Field name, as in the report's `system` section
+    pub field: String,
+    /// Old value
+    pub old: Option<String>,
+    /// New value
+    pub new: Option<String>,
+}
+
+/// Differences between two reports, see [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportDiff {
+    /// Host name in the new report
+    pub host: String,
+    /// Time the old report was produced
+    pub old_timestamp: DateTime<Utc>,
+    /// Time the new report was produced
+    pub new_timestamp: DateTime<Utc>,
+    /// Changed system fields
+    pub system: Vec<FieldChange>,
+    /// Software changes, sorted by name
+    pub software: Vec<ItemChange>,
+    /// Industrial software changes, sorted by name
+    pub industrial: Vec<ItemChange>,
+    /// Installed and removed updates, sorted by hotfix ID
+    pub updates: Vec<ItemChange>,
+}
+
+impl ReportDiff {
+    /// Whether the reports are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.system.is_empty()
+            && self.software.is_empty()
+            && self.industrial.is_empty()
+            && self.updates.is_empty()
+    }
+
+    /// Whether software or industrial products were added, removed or
+    /// changed. System and update changes alone do not count.
+    pub fn inventory_changed(&self) -> bool {
+        !self.software.is_empty() || !self.industrial.is_empty()
+    }
+}
+
+/// Compare two reports of the same host.
+///
+/// Memory in use and the scan time change on every scan and are not
+/// reported as system changes.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::AuditReport;
+/// use sysaudit::report::diff;
+///
+/// let old = AuditReport::from_json_versioned(&std::fs::read_to_string("2024-05.json").unwrap()).unwrap();
+/// let new = AuditReport::from_json_versioned(&std::fs::read_to_string("2024-06.json").unwrap()).unwrap();
+/// for change in diff(&old, &new).software {
+///     println!("{} {}", change.kind, change.name);
+/// }
+/// ```
+pub fn diff(old: &AuditReport, new: &AuditReport) -> ReportDiff {
+    ReportDiff {
+        host: new.system.host_name.clone(),
+        old_timestamp: old.timestamp,
+        new_timestamp: new.timestamp,
+        system: system_changes(old, new),
+        software: item_changes(
+            versions(
+                old.software
+                    .iter()
+                    .map(|sw| (sw.name.clone(), sw.version.clone())),
+            ),
+            versions(
+                new.software
+                    .iter()
+                    .map(|sw| (sw.name.clone(), sw.version.clone())),
+            ),
+        ),
+        industrial: item_changes(
+            versions(old.industrial.iter().map(industrial_entry)),
+            versions(new.industrial.iter().map(industrial_entry)),
+        ),
+        updates: item_changes(
+            versions(old.updates.iter().map(|u| (u.hotfix_id.clone(), None))),
+            versions(new.updates.iter().map(|u| (u.hotfix_id.clone(), None))),
+        ),
+    }
+}
+
+/// `Vendor Product` and version of an industrial detection.
+fn industrial_entry(sw: &IndustrialSoftwareDto) -> (String, Option<String>) {
+    (format!("{} {}", sw.vendor, sw.product), sw.version.clone())
+}
+
+/// Versions installed per name.
+fn versions(
+    entries: impl Iterator<Item = (String, Option<String>)>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut map: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (name, version) in entries {
+        let versions = map.entry(name).or_default();
+        if let Some(version) = version {
+            versions.insert(version);
+        }
+    }
+    map
+}
+
+/// Compare two name → versions maps.
+fn item_changes(
+    old: BTreeMap<String, BTreeSet<String>>,
+    new: BTreeMap<String, BTreeSet<String>>,
+) -> Vec<ItemChange> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let kind = match (old.get(name), new.get(name)) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(a), Some(b)) if a != b => ChangeKind::Changed,
+                _ => return None,
+            };
+            Some(ItemChange {
+                kind,
+                name: name.clone(),
+                old_version: old.get(name).and_then(joined),
+                new_version: new.get(name).and_then(joined),
+            })
+        })
+        .collect()
+}
+
+/// Versions as one string, `None` when none are recorded.
+fn joined(versions: &BTreeSet<String>) -> Option<String> {
+    (!versions.is_empty()).then(|| versions.iter().cloned().collect::<Vec<_>>().join(", "))
+}
+
+/// Compared system fields of a report, by name.
+pub(super) fn system_fields(report: &AuditReport) -> [(&'static str, Option<String>); 9] {
+    let system = &report.system;
+    let mut addresses: Vec<String> = system
+        .network_interfaces
+        .iter()
+        .map(|iface| format!("{} {}", iface.name, iface.ip_address))
+        .collect();
+    addresses.sort();
+    [
+        ("os_name", Some(system.os_name.clone())),
+        ("os_version", Some(system.os_version.clone())),
+        ("host_name", Some(system.host_name.clone())),
+        ("cpu_info", Some(system.cpu_info.clone())),
+        (
+            "cpu_physical_cores",
+            system.cpu_physical_cores.map(|c| c.to_string()),
+        ),
+        (
+            "memory_total_bytes",
+            Some(system.memory_total_bytes.to_string()),
+        ),
+        ("manufacturer", system.manufacturer.clone()),
+        ("model", system.model.clone()),
+        (
+            "network_interfaces",
+            (!addresses.is_empty()).then(|| addresses.join(", ")),
+        ),
+    ]
+}
+
+/// System fields that differ between the reports.
+fn system_changes(old: &AuditReport, new: &AuditReport) -> Vec<FieldChange> {
+    system_fields(old)
+        .into_iter()
+        .zip(system_fields(new))
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|((field, old), (_, new))| FieldChange {
+            field: field.to_string(),
+            old,
+            new,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sysaudit_common::{SoftwareDto, SystemInfoDto};
+
+    fn report(os_version: &str, software: &[(&str, &str)]) -> AuditReport {
+        AuditReport {
+            schema_version: crate::REPORT_SCHEMA_VERSION,
+            software: software
+                .iter()
+                .map(|(name, version)| SoftwareDto {
+                    name: name.to_string(),
+                    version: Some(version.to_string()),
+                    vendor: None,
+                    install_date: None,
+                })
+                .collect(),
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "AVEVA".into(),
+                product: "Citect SCADA".into(),
+                version: Some("8.1".into()),
+                install_path: None,
+            }],
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Pro".into(),
+                os_version: os_version.into(),
+                host_name: "HMI01".into(),
+                cpu_info: "Intel i5".into(),
+                cpu_physical_cores: Some(4),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        }
+    }
+
+    #[test]
+    fn test_diff_reports() {
+        let old = report("22H2", &[("7-Zip", "22.01"), ("Notepad++", "8.5")]);
+        let new = report("22H2", &[("7-Zip", "23.01"), ("Putty", "0.80")]);
+
+        let changes = diff(&old, &new);
+        assert!(changes.system.is_empty());
+        assert!(changes.industrial.is_empty());
+        assert_eq!(
+            changes
+                .software
+                .iter()
+                .map(|c| (c.kind, c.name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (ChangeKind::Changed, "7-Zip"),
+                (ChangeKind::Removed, "Notepad++"),
+                (ChangeKind::Added, "Putty"),
+            ]
+        );
+        assert_eq!(changes.software[0].old_version.as_deref(), Some("22.01"));
+        assert_eq!(changes.software[0].new_version.as_deref(), Some("23.01"));
+
+        let upgraded = diff(&old, &report("21H2", &[]));
+        assert_eq!(upgraded.system.len(), 1);
+        assert_eq!(upgraded.system[0].field, "os_version");
+        assert!(changes.inventory_changed());
+        let os_only = report("21H2", &[("7-Zip", "22.01"), ("Notepad++", "8.5")]);
+        assert!(!diff(&old, &os_only).inventory_changed());
+        assert!(diff(&old, &old).is_empty());
+    }
+}
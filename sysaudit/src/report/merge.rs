@@ -0,0 +1,345 @@
+//! Combining partial reports of one host.
+//!
+//! Field work often captures a machine more than once: a basic remote scan,
+//! then a local scan with more sections. [`AuditReport::merge`] builds one
+//! report from such captures:
+//!
+//! - Every section comes from the newest report that has it. A list section
+//!   counts as present when it is non-empty; lists are never combined, so
+//!   software removed between captures does not come back.
+//! - System information comes from the newest report, with the hardware
+//!   details it lacks filled in from older ones. Values the reports
+//!   disagree on are listed as [`MergeConflict`]s.
+//! - Warnings follow their section: a warning is kept when its section was
+//!   taken from the report that raised it.
+//! - Reports belong to one host when their asset IDs match, or, where a
+//!   report has none, their host names do.
+//! - The score is recomputed for the merged report.
+
+use super::{AuditReport, REPORT_SCHEMA_VERSION, diff::system_fields};
+use crate::Error;
+use crate::options::Collector;
+use crate::score::AuditScore;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Report a section of the merged report was taken from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionSource {
+    /// Section
+    pub collector: Collector,
+    /// Position of the report in the merged slice
+    pub index: usize,
+    /// Time that report was produced
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A system field the merged reports disagree on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// Field name, as in the report's `system` section
+    pub field: String,
+    /// Value in the merged report, from the newest report
+    pub kept: Option<String>,
+    /// Other values found in older reports
+    pub discarded: Vec<String>,
+}
+
+/// Outcome of [`AuditReport::merge`].
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// The combined report
+    pub report: AuditReport,
+    /// Where each section came from
+    pub sources: Vec<SectionSource>,
+    /// System fields with differing values
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl AuditReport {
+    /// Combine partial reports of the same host, newest data first.
+    ///
+    /// See the [module documentation](self) for the rules.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::AuditReport;
+    ///
+    /// let read = |path: &str| {
+    ///     AuditReport::from_json_versioned(&std::fs::read_to_string(path).unwrap()).unwrap()
+    /// };
+    /// let merged = AuditReport::merge(&[read("remote.json"), read("local.json")]).unwrap();
+    /// for conflict in &merged.conflicts {
+    ///     println!("{}: kept {:?}", conflict.field, conflict.kept);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if `reports` is empty or the reports
+    /// describe different hosts.
+    pub fn merge(reports: &[AuditReport]) -> Result<MergeResult, Error> {
+        let mut order: Vec<usize> = (0..reports.len()).collect();
+        order.sort_by_key(|&i| reports[i].timestamp);
+        let Some(&newest) = order.last() else {
+            return Err(Error::General("no reports to merge".to_string()));
+        };
+        let base = &reports[newest];
+        // The same asset under a new name (after reimaging) is one host
+        let same_host = |r: &AuditReport| match (&r.asset_id, &base.asset_id) {
+            (Some(a), Some(b)) => a == b,
+            _ => r
+                .system
+                .host_name
+                .eq_ignore_ascii_case(&base.system.host_name),
+        };
+        if let Some(other) = reports.iter().find(|r| !same_host(r)) {
+            return Err(Error::General(format!(
+                "cannot merge reports of {} and {}",
+                base.system.host_name, other.system.host_name
+            )));
+        }
+
+        // Newest report for which `has` holds
+        let latest = |has: &dyn Fn(&AuditReport) -> bool| {
+            order.iter().rev().copied().find(|&i| has(&reports[i]))
+        };
+        // A section no report has comes from the newest one, with its
+        // warnings explaining why
+        let mut sources = vec![(Collector::System, newest)];
+        let mut pick = |collector: Collector, has: &dyn Fn(&AuditReport) -> bool| {
+            let index = latest(has).unwrap_or(newest);
+            sources.push((collector, index));
+            index
+        };
+        let software = pick(Collector::Software, &|r| !r.software.is_empty());
+        let industrial = pick(Collector::Industrial, &|r| !r.industrial.is_empty());
+        let updates = pick(Collector::Updates, &|r| !r.updates.is_empty());
+        let services = pick(Collector::Services, &|r| !r.services.is_empty());
+        let security = pick(Collector::Security, &|r| r.security.is_some());
+        let update_health = pick(Collector::UpdateHealth, &|r| r.update_health.is_some());
+        let licensing = pick(Collector::Licensing, &|r| r.licensing.is_some());
+        let event_logs = pick(Collector::EventLogs, &|r| r.event_logs.is_some());
+        let autopilot = pick(Collector::Autopilot, &|r| r.autopilot.is_some());
+        let containers = pick(Collector::Containers, &|r| r.containers.is_some());
+
+        let mut report = AuditReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            system: base.system.clone(),
+            software: reports[software].software.clone(),
+            industrial: reports[industrial].industrial.clone(),
+            updates: reports[updates].updates.clone(),
+            services: reports[services].services.clone(),
+            security: reports[security].security.clone(),
+            update_health: reports[update_health].update_health.clone(),
+            licensing: reports[licensing].licensing.clone(),
+            event_logs: reports[event_logs].event_logs.clone(),
+            autopilot: reports[autopilot].autopilot.clone(),
+            containers: reports[containers].containers.clone(),
+            redistributables: reports[software].redistributables.clone(),
+            warnings: Vec::new(),
+            score: None,
+            extensions: Default::default(),
+            metrics: latest(&|r| r.metrics.is_some()).and_then(|i| reports[i].metrics.clone()),
+            elevated: latest(&|r| r.elevated.is_some()).and_then(|i| reports[i].elevated),
+            metadata: latest(&|r| r.metadata.is_some()).and_then(|i| reports[i].metadata.clone()),
+            identity: latest(&|r| r.identity.is_some()).and_then(|i| reports[i].identity.clone()),
+            os_build: latest(&|r| r.os_build.is_some()).and_then(|i| reports[i].os_build.clone()),
+            asset_id: latest(&|r| r.asset_id.is_some()).and_then(|i| reports[i].asset_id.clone()),
+            timestamp: base.timestamp,
+        };
+
+        // Newest first, so each gap takes the most recent value
+        for &i in order.iter().rev() {
+            let older = &reports[i];
+            let system = &mut report.system;
+            if system.cpu_physical_cores.is_none() {
+                system.cpu_physical_cores = older.system.cpu_physical_cores;
+            }
+            if system.manufacturer.is_none() {
+                system.manufacturer = older.system.manufacturer.clone();
+            }
+            if system.model.is_none() {
+                system.model = older.system.model.clone();
+            }
+            if system.network_interfaces.is_empty() {
+                system.network_interfaces = older.system.network_interfaces.clone();
+            }
+            // Newer custom sections replace older ones of the same name
+            for (name, section) in &older.extensions {
+                report
+                    .extensions
+                    .entry(name.clone())
+                    .or_insert_with(|| section.clone());
+            }
+        }
+
+        // Warnings of the sections taken from each report; custom collector
+        // warnings from the newest report
+        sources.push((Collector::Custom, newest));
+        for &(collector, index) in &sources {
+            report.warnings.extend(
+                reports[index]
+                    .warnings
+                    .iter()
+                    .filter(|w| w.collector == collector)
+                    .cloned(),
+            );
+        }
+        sources.pop();
+
+        if reports.iter().any(|r| r.score.is_some()) {
+            report.score = Some(AuditScore::compute(&report));
+        }
+
+        let fields: Vec<_> = order
+            .iter()
+            .rev()
+            .map(|&i| system_fields(&reports[i]))
+            .collect();
+        let conflicts = system_fields(&report)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(n, (field, kept))| {
+                let mut discarded: Vec<String> = Vec::new();
+                for (_, value) in fields.iter().map(|f| &f[n]) {
+                    let Some(value) = value else { continue };
+                    if Some(value) != kept.as_ref() && !discarded.contains(value) {
+                        discarded.push(value.clone());
+                    }
+                }
+                (!discarded.is_empty()).then(|| MergeConflict {
+                    field: field.to_string(),
+                    kept,
+                    discarded,
+                })
+            })
+            .collect();
+
+        Ok(MergeResult {
+            sources: sources
+                .into_iter()
+                .map(|(collector, index)| SectionSource {
+                    collector,
+                    index,
+                    timestamp: reports[index].timestamp,
+                })
+                .collect(),
+            report,
+            conflicts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScanWarning;
+    use chrono::TimeZone;
+    use sysaudit_common::{SoftwareDto, SystemInfoDto};
+
+    fn report(hour: u32, os_version: &str) -> AuditReport {
+        AuditReport {
+            timestamp: Utc.with_ymd_and_hms(2024, 6, 1, hour, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Pro".into(),
+                os_version: os_version.into(),
+                host_name: "HMI01".into(),
+                cpu_info: "Intel i5".into(),
+                cpu_physical_cores: None,
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        }
+    }
+
+    #[test]
+    fn test_merge_takes_newest_sections() {
+        // Basic remote scan with software, later local scan with updates
+        let mut remote = report(9, "10.0.19045");
+        remote.system.manufacturer = Some("Siemens".into());
+        remote.software = vec![SoftwareDto {
+            name: "Citect SCADA".into(),
+            version: Some("8.0".into()),
+            vendor: None,
+            install_date: None,
+        }];
+        remote.warnings = vec![ScanWarning::new(Collector::Updates, "access denied")];
+        let mut local = report(11, "10.0.19045.4651");
+        local.updates = vec![crate::WindowsUpdate {
+            hotfix_id: "KB5040427".into(),
+            description: None,
+            installed_on: None,
+            installed_by: None,
+        }];
+
+        let merged = AuditReport::merge(&[local, remote]).unwrap();
+        let report = &merged.report;
+        assert_eq!(report.software.len(), 1);
+        assert_eq!(report.updates.len(), 1);
+        assert_eq!(report.system.os_version, "10.0.19045.4651");
+        assert_eq!(report.system.manufacturer.as_deref(), Some("Siemens"));
+        // The updates came from the local scan, so the remote warning goes
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.timestamp.to_string(), "2024-06-01 11:00:00 UTC");
+        assert!(merged.sources.contains(&SectionSource {
+            collector: Collector::Updates,
+            index: 0,
+            timestamp: report.timestamp,
+        }));
+        assert!(merged.sources.contains(&SectionSource {
+            collector: Collector::Software,
+            index: 1,
+            timestamp: Utc.with_ymd_and_hms(2024, 6, 1, 9, 0, 0).unwrap(),
+        }));
+        assert_eq!(
+            merged.conflicts,
+            vec![MergeConflict {
+                field: "os_version".into(),
+                kept: Some("10.0.19045.4651".into()),
+                discarded: vec!["10.0.19045".into()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_fills_gaps_from_newest() {
+        let mut oldest = report(8, "10.0.19045");
+        oldest.system.model = Some("IPC427D".into());
+        oldest.system.cpu_physical_cores = Some(2);
+        oldest
+            .extensions
+            .insert("plc".into(), serde_json::json!({"firmware": "2.8"}));
+        let mut middle = report(9, "10.0.19045");
+        middle.system.model = Some("IPC427E".into());
+        middle
+            .extensions
+            .insert("plc".into(), serde_json::json!({"firmware": "2.9"}));
+        let newest = report(10, "10.0.19045");
+
+        let merged = AuditReport::merge(&[middle, newest, oldest]).unwrap();
+        let report = &merged.report;
+        assert_eq!(report.system.model.as_deref(), Some("IPC427E"));
+        assert_eq!(report.system.cpu_physical_cores, Some(2));
+        assert_eq!(report.extensions["plc"]["firmware"], "2.9");
+    }
+
+    #[test]
+    fn test_merge_rejects_other_hosts() {
+        let mut other = report(10, "10.0.19045");
+        other.system.host_name = "ENG02".into();
+        assert!(AuditReport::merge(&[report(9, "10.0.19045"), other.clone()]).is_err());
+        assert!(AuditReport::merge(&[]).is_err());
+
+        // Renamed after reimaging, same hardware
+        let mut before = report(9, "10.0.19045");
+        before.asset_id = Some("v1-a".into());
+        other.asset_id = Some("v1-a".into());
+        let merged = AuditReport::merge(&[before, other]).unwrap();
+        assert_eq!(merged.report.system.host_name, "ENG02");
+    }
+}
@@ -1,5 +1,19 @@
+use crate::report::{AuditReport, ScanWarning};
+use crate::{
+    Autopilot, Containers, EventLogs, Licensing, SecurityPosture, UpdateHealth, WindowsService,
+    WindowsUpdate,
+};
+use futures_core::Stream;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use std::time::Duration;
-use sysaudit_common::SysauditReport;
+use sysaudit_common::{IndustrialSoftwareDto, SoftwareDto, SysauditReport, SystemInfoDto};
+#[cfg(all(windows, feature = "local"))]
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 /// Unified error type for all scanning strategies.
 #[derive(thiserror::Error, Debug)]
@@ -21,6 +35,31 @@ pub enum ScanError {
     #[error("remote execution error on {host}: {message}")]
     RemoteExecution { host: String, message: String },
 
+    /// PowerShell on the remote host refused to run the payload
+    /// (execution policy, ConstrainedLanguage mode or AMSI).
+    #[error("PowerShell blocked the payload on {host}: {message} (hint: {hint})")]
+    ExecutionPolicy {
+        host: String,
+        message: String,
+        hint: String,
+    },
+
+    /// The remote account lacks rights for a query or registry read.
+    #[error("access denied on {host}: {message} (hint: {hint})")]
+    AccessDenied {
+        host: String,
+        message: String,
+        hint: String,
+    },
+
+    /// A cmdlet or module the payload needs is missing on the remote host.
+    #[error("missing PowerShell command or module on {host}: {message} (hint: {hint})")]
+    ModuleMissing {
+        host: String,
+        message: String,
+        hint: String,
+    },
+
     /// Response deserialization failure.
     #[error("deserialization error: {0}")]
     Deserialization(#[from] serde_json::Error),
@@ -28,11 +67,77 @@ pub enum ScanError {
     /// Operation timed out.
     #[error("operation timed out after {0:?}")]
     Timeout(Duration),
+
+    /// The caller cancelled the scan.
+    #[error("scan cancelled")]
+    Cancelled,
+}
+
+/// Broad class of a [`ScanError`], for grouping failures across hosts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ScanErrorKind {
+    /// Local collection failed
+    Local,
+    /// Host unreachable or the connection dropped
+    Network,
+    /// Credentials rejected or lacking rights
+    Auth,
+    /// The remote command or query failed
+    Execution,
+    /// The response could not be parsed
+    Data,
+    /// The scan did not finish in time
+    Timeout,
+    /// The caller cancelled the scan
+    Cancelled,
+}
+
+impl std::fmt::Display for ScanErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanErrorKind::Local => write!(f, "local"),
+            ScanErrorKind::Network => write!(f, "network"),
+            ScanErrorKind::Auth => write!(f, "auth"),
+            ScanErrorKind::Execution => write!(f, "execution"),
+            ScanErrorKind::Data => write!(f, "data"),
+            ScanErrorKind::Timeout => write!(f, "timeout"),
+            ScanErrorKind::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl ScanError {
+    /// Broad class of this error.
+    pub fn kind(&self) -> ScanErrorKind {
+        match self {
+            ScanError::Local(_) => ScanErrorKind::Local,
+            ScanError::RemoteConnection { .. } => ScanErrorKind::Network,
+            ScanError::RemoteAuth { .. } => ScanErrorKind::Auth,
+            ScanError::RemoteExecution { .. }
+            | ScanError::ExecutionPolicy { .. }
+            | ScanError::ModuleMissing { .. } => ScanErrorKind::Execution,
+            ScanError::AccessDenied { .. } => ScanErrorKind::Auth,
+            ScanError::Deserialization(_) => ScanErrorKind::Data,
+            ScanError::Timeout(_) => ScanErrorKind::Timeout,
+            ScanError::Cancelled => ScanErrorKind::Cancelled,
+        }
+    }
+
+    /// Whether retrying the same operation may succeed (network failures
+    /// and timeouts).
+    pub fn is_transient(&self) -> bool {
+        matches!(self.kind(), ScanErrorKind::Network | ScanErrorKind::Timeout)
+    }
 }
 
 impl From<crate::Error> for ScanError {
     fn from(err: crate::Error) -> Self {
-        ScanError::Local(err.to_string())
+        match err {
+            crate::Error::Cancelled => ScanError::Cancelled,
+            err => ScanError::Local(err.to_string()),
+        }
     }
 }
 
@@ -45,5 +150,382 @@ pub trait Scanner: Send + Sync {
     /// # Errors
     ///
     /// Returns [`ScanError`] if collection fails for any reason.
-    fn scan(&self) -> impl std::future::Future<Output = Result<SysauditReport, ScanError>> + Send;
+    fn audit(&self) -> impl std::future::Future<Output = Result<AuditReport, ScanError>> + Send;
+
+    /// Execute a full system audit and return the shared
+    /// [`SysauditReport`] DTO, without the sections only [`AuditReport`]
+    /// carries.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScanError`] if collection fails for any reason.
+    fn scan(&self) -> impl std::future::Future<Output = Result<SysauditReport, ScanError>> + Send {
+        async move { self.audit().await.map(SysauditReport::from) }
+    }
+
+    /// Execute a full system audit that stops once `cancel` is triggered.
+    ///
+    /// The default implementation drops the [`audit`](Scanner::audit) future
+    /// on cancellation; scanners with blocking collectors override it so the
+    /// collectors stop as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{CancellationToken, LocalScanner, ScanError, Scanner};
+    ///
+    /// # async fn example() -> Result<(), ScanError> {
+    /// let cancel = CancellationToken::new();
+    /// let abort = cancel.clone(); // e.g. moved into a GUI's Cancel button
+    /// # abort.cancel();
+    /// match LocalScanner::new().scan_with_cancel(&cancel).await {
+    ///     Err(ScanError::Cancelled) => println!("scan aborted"),
+    ///     result => println!("{} programs", result?.software.len()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScanError::Cancelled`] if `cancel` fires before the scan
+    /// completes, or any error [`audit`](Scanner::audit) returns.
+    fn scan_with_cancel(
+        &self,
+        cancel: &CancellationToken,
+    ) -> impl std::future::Future<Output = Result<AuditReport, ScanError>> + Send {
+        async move {
+            cancel
+                .run_until_cancelled(self.audit())
+                .await
+                .unwrap_or(Err(ScanError::Cancelled))
+        }
+    }
+
+    /// Execute a full system audit, yielding items as they are collected
+    /// instead of one [`AuditReport`].
+    ///
+    /// The default implementation waits for [`audit`](Scanner::audit) and
+    /// then yields the report's items; [`LocalScanner`](crate::LocalScanner)
+    /// yields software entries as registry keys are read. No
+    /// [`AuditScore`](crate::AuditScore) is computed, as that needs the
+    /// full report.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, ScanError, ScanItem, Scanner};
+    ///
+    /// # async fn example() -> Result<(), ScanError> {
+    /// let scanner = LocalScanner::new();
+    /// let mut items = scanner.scan_stream();
+    /// while let Some(item) = items.next().await {
+    ///     if let ScanItem::Software(sw) = item? {
+    ///         println!("{}", sw.name); // e.g. insert into a database
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn scan_stream(&self) -> ScanStream<'_> {
+        ScanStream::from_scan(self.audit())
+    }
+}
+
+/// Blocking counterpart of [`Scanner`], for callers without an async
+/// runtime (GUI event handlers, service wrappers).
+///
+/// Implemented for every [`Scanner`]: each call runs the scan to completion
+/// on a private single-threaded tokio runtime.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::{BlockingScanner, LocalScanner};
+///
+/// let report = LocalScanner::new().scan_blocking().unwrap();
+/// println!("{} programs", report.software.len());
+/// ```
+pub trait BlockingScanner {
+    /// Execute a full system audit, blocking the calling thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScanError`] if collection fails, or
+    /// [`ScanError::Local`] if called from within an async runtime, where
+    /// [`Scanner::audit`] should be awaited instead.
+    fn scan_blocking(&self) -> Result<AuditReport, ScanError>;
+
+    /// [`scan_blocking`](BlockingScanner::scan_blocking) that stops once
+    /// `cancel` is triggered, e.g. from another thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScanError::Cancelled`] if `cancel` fires before the scan
+    /// completes, or any error [`scan_blocking`](BlockingScanner::scan_blocking)
+    /// returns.
+    fn scan_blocking_with_cancel(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<AuditReport, ScanError>;
+}
+
+impl<S: Scanner> BlockingScanner for S {
+    fn scan_blocking(&self) -> Result<AuditReport, ScanError> {
+        block_on(self.audit())?
+    }
+
+    fn scan_blocking_with_cancel(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<AuditReport, ScanError> {
+        block_on(self.scan_with_cancel(cancel))?
+    }
+}
+
+/// Drive `future` to completion on a fresh single-threaded runtime.
+fn block_on<F: Future>(future: F) -> Result<F::Output, ScanError> {
+    // Nesting runtimes panics; report it as an error instead.
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(ScanError::Local(
+            "blocking scan called from within an async runtime; await audit() instead".into(),
+        ));
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| ScanError::Local(format!("could not start runtime: {}", e)))?;
+    Ok(runtime.block_on(future))
+}
+
+/// One collected item of a streamed scan, see [`Scanner::scan_stream`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ScanItem {
+    /// Host information, always the first item
+    System(Box<SystemInfoDto>),
+    /// One installed program
+    Software(SoftwareDto),
+    /// One detected industrial application
+    Industrial(IndustrialSoftwareDto),
+    /// One installed update
+    Update(WindowsUpdate),
+    /// One Windows service
+    Service(WindowsService),
+    /// Security configuration
+    Security(Box<SecurityPosture>),
+    /// Windows Update agent health
+    UpdateHealth(Box<UpdateHealth>),
+    /// Windows edition, licenses and activation status
+    Licensing(Box<Licensing>),
+    /// Event log sizes, retention and forwarding
+    EventLogs(Box<EventLogs>),
+    /// Autopilot registration data
+    Autopilot(Box<Autopilot>),
+    /// WSL distributions and container runtimes
+    Containers(Box<Containers>),
+    /// A custom collector's section and its key
+    Extension(String, serde_json::Value),
+    /// A collector that returned partial or no data
+    Warning(ScanWarning),
+}
+
+impl ScanItem {
+    /// The items of a complete report, in collection order.
+    fn from_report(report: AuditReport) -> impl Iterator<Item = ScanItem> {
+        std::iter::once(ScanItem::System(Box::new(report.system)))
+            .chain(report.software.into_iter().map(ScanItem::Software))
+            .chain(report.industrial.into_iter().map(ScanItem::Industrial))
+            .chain(report.updates.into_iter().map(ScanItem::Update))
+            .chain(report.services.into_iter().map(ScanItem::Service))
+            .chain(
+                report
+                    .security
+                    .map(|security| ScanItem::Security(Box::new(security))),
+            )
+            .chain(
+                report
+                    .update_health
+                    .map(|health| ScanItem::UpdateHealth(Box::new(health))),
+            )
+            .chain(
+                report
+                    .licensing
+                    .map(|licensing| ScanItem::Licensing(Box::new(licensing))),
+            )
+            .chain(
+                report
+                    .event_logs
+                    .map(|logs| ScanItem::EventLogs(Box::new(logs))),
+            )
+            .chain(
+                report
+                    .autopilot
+                    .map(|autopilot| ScanItem::Autopilot(Box::new(autopilot))),
+            )
+            .chain(
+                report
+                    .containers
+                    .map(|containers| ScanItem::Containers(Box::new(containers))),
+            )
+            .chain(
+                report
+                    .extensions
+                    .into_iter()
+                    .map(|(section, value)| ScanItem::Extension(section, value)),
+            )
+            .chain(report.warnings.into_iter().map(ScanItem::Warning))
+    }
+}
+
+/// Items of a streamed scan, returned by [`Scanner::scan_stream`].
+///
+/// Implements [`Stream`]; [`next`](ScanStream::next) reads it without a
+/// stream combinator crate. Dropping the stream stops the scan.
+pub struct ScanStream<'a> {
+    state: StreamState<'a>,
+}
+
+enum StreamState<'a> {
+    /// Waiting for a whole report
+    Scanning(Pin<Box<dyn Future<Output = Result<AuditReport, ScanError>> + Send + 'a>>),
+    /// Items left of a whole report
+    Items(VecDeque<Result<ScanItem, ScanError>>),
+    /// Items sent by a collector thread as they are read
+    #[cfg(all(windows, feature = "local"))]
+    Channel(mpsc::Receiver<Result<ScanItem, ScanError>>),
+}
+
+impl<'a> ScanStream<'a> {
+    /// Stream the items of the report `scan` resolves to.
+    pub(crate) fn from_scan(
+        scan: impl Future<Output = Result<AuditReport, ScanError>> + Send + 'a,
+    ) -> Self {
+        ScanStream {
+            state: StreamState::Scanning(Box::pin(scan)),
+        }
+    }
+
+    /// Stream items as a collector sends them; the stream ends when every
+    /// sender is dropped.
+    #[cfg(all(windows, feature = "local"))]
+    pub(crate) fn from_channel(items: mpsc::Receiver<Result<ScanItem, ScanError>>) -> Self {
+        ScanStream {
+            state: StreamState::Channel(items),
+        }
+    }
+
+    /// A stream yielding only `err`.
+    #[cfg(all(windows, feature = "local"))]
+    pub(crate) fn from_error(err: ScanError) -> Self {
+        ScanStream {
+            state: StreamState::Items(VecDeque::from([Err(err)])),
+        }
+    }
+
+    /// The next item, or `None` once the scan is complete.
+    pub async fn next(&mut self) -> Option<Result<ScanItem, ScanError>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl Stream for ScanStream<'_> {
+    type Item = Result<ScanItem, ScanError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                StreamState::Scanning(scan) => {
+                    let items = match std::task::ready!(scan.as_mut().poll(cx)) {
+                        Ok(report) => ScanItem::from_report(report).map(Ok).collect(),
+                        Err(e) => VecDeque::from([Err(e)]),
+                    };
+                    this.state = StreamState::Items(items);
+                }
+                StreamState::Items(items) => return Poll::Ready(items.pop_front()),
+                #[cfg(all(windows, feature = "local"))]
+                StreamState::Channel(items) => return items.poll_recv(cx),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scan_stream_from_report() {
+        let report = AuditReport {
+            schema_version: crate::REPORT_SCHEMA_VERSION,
+            software: vec![SoftwareDto {
+                name: "7-Zip".into(),
+                version: Some("23.01".into()),
+                vendor: None,
+                install_date: None,
+            }],
+            warnings: vec![ScanWarning::new(crate::Collector::Updates, "query failed")],
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 10 Pro".into(),
+                os_version: "10.0.19045".into(),
+                host_name: "HMI01".into(),
+                cpu_info: "Intel i5".into(),
+                cpu_physical_cores: Some(4),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        };
+
+        let mut items = ScanStream::from_scan(async { Ok(report) });
+        assert!(
+            matches!(items.next().await, Some(Ok(ScanItem::System(s))) if s.host_name == "HMI01")
+        );
+        assert!(
+            matches!(items.next().await, Some(Ok(ScanItem::Software(sw))) if sw.name == "7-Zip")
+        );
+        assert!(matches!(items.next().await, Some(Ok(ScanItem::Warning(_)))));
+        assert!(items.next().await.is_none());
+    }
+
+    struct TimedOut;
+
+    impl Scanner for TimedOut {
+        async fn audit(&self) -> Result<AuditReport, ScanError> {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            Err(ScanError::Timeout(Duration::from_millis(1)))
+        }
+    }
+
+    #[test]
+    fn test_scan_blocking_without_runtime() {
+        assert!(matches!(
+            TimedOut.scan_blocking(),
+            Err(ScanError::Timeout(_))
+        ));
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        assert!(matches!(
+            TimedOut.scan_blocking_with_cancel(&cancel),
+            Err(ScanError::Cancelled)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_scan_blocking_inside_runtime_errors() {
+        assert!(matches!(TimedOut.scan_blocking(), Err(ScanError::Local(_))));
+    }
+
+    #[tokio::test]
+    async fn test_scan_stream_error() {
+        let mut items = ScanStream::from_scan(async { Err(ScanError::Cancelled) });
+        assert!(matches!(
+            items.next().await,
+            Some(Err(ScanError::Cancelled))
+        ));
+        assert!(items.next().await.is_none());
+    }
 }
@@ -0,0 +1,233 @@
+//! Configuration of unattended, recurring scans.
+//!
+//! `sysaudit service` runs as a Windows service that scans the host on a
+//! fixed interval and ships each report to the sinks of a
+//! [`ScheduleConfig`], usually loaded from YAML:
+//!
+//! ```yaml
+//! interval: 6h
+//! skip: industrial
+//! watch_changes: true
+//! output_dir: '\\fileserver\audits\plant3'
+//! history_db: 'C:\ProgramData\sysaudit\history.db'
+//! elastic:
+//!   url: https://es.plant.local:9200
+//!   index: sysaudit
+//!   api_key_env: SYSAUDIT_ELASTIC_API_KEY
+//! syslog:
+//!   address: collector.plant.local:6514
+//!   protocol: tls
+//! gelf:
+//!   address: graylog.plant.local:12201
+//!   items: true
+//! ```
+//!
+//! With `watch_changes`, installations and removals also trigger a scan
+//! as they happen, see [`ChangeWatcher`](crate::watch::ChangeWatcher).
+//!
+//! Reports written to `output_dir` (a local directory or a file share) are
+//! named like those of `sysaudit watch`, see [`ReportHistory`](crate::watch::ReportHistory).
+//! Secrets are never part of the file: the Elasticsearch API key is read
+//! from the environment variable named by `api_key_env`.
+
+use crate::Error;
+use crate::options::ScanOptions;
+use crate::watch::parse_interval;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "yaml")]
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Scan interval and report sinks of a scheduled scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// Time between scans (e.g. `30m`, `6h`, `1d`), see [`parse_interval`]
+    #[serde(default = "default_interval")]
+    pub interval: String,
+    /// Sections to collect (e.g. `system,software`); all when absent
+    #[serde(default)]
+    pub only: Option<String>,
+    /// Sections to leave out
+    #[serde(default)]
+    pub skip: Option<String>,
+    /// Also scan as soon as software is installed or removed, instead of
+    /// only every `interval`
+    #[serde(default)]
+    pub watch_changes: bool,
+    /// Directory or file share each report is written to as JSON
+    #[serde(default)]
+    pub output_dir: Option<PathBuf>,
+    /// SQLite database each scan is recorded in for `sysaudit history`
+    /// trends
+    #[serde(default)]
+    pub history_db: Option<PathBuf>,
+    /// Elasticsearch/OpenSearch cluster to index reports into
+    #[serde(default)]
+    pub elastic: Option<ElasticTarget>,
+    /// Syslog collector to forward the summary and findings to
+    #[serde(default)]
+    pub syslog: Option<SyslogTarget>,
+    /// Graylog GELF input to send the summary, findings and optionally
+    /// every entry to
+    #[serde(default)]
+    pub gelf: Option<GelfTarget>,
+}
+
+/// Elasticsearch settings of a [`ScheduleConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ElasticTarget {
+    /// Cluster URL
+    pub url: String,
+    /// Index name
+    #[serde(default = "default_index")]
+    pub index: String,
+    /// Environment variable holding the API key
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Index one document per entry instead of one per report
+    #[serde(default)]
+    pub items: bool,
+}
+
+/// Syslog settings of a [`ScheduleConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyslogTarget {
+    /// Collector `host:port`
+    pub address: String,
+    /// Transport: `udp`, `tcp` or `tls`
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+}
+
+/// Graylog settings of a [`ScheduleConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GelfTarget {
+    /// GELF input `host:port`
+    pub address: String,
+    /// Transport: `udp`, `tcp` or `tls`
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+    /// Also send one message per system, software, industrial and update
+    /// entry
+    #[serde(default)]
+    pub items: bool,
+}
+
+fn default_interval() -> String {
+    "1h".to_string()
+}
+
+fn default_index() -> String {
+    "sysaudit".to_string()
+}
+
+fn default_protocol() -> String {
+    "udp".to_string()
+}
+
+impl ScheduleConfig {
+    /// Parse a configuration from YAML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Yaml`] if the document is not a valid
+    /// configuration, or [`Error::General`] if the interval or sections
+    /// are invalid or no sink is configured.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        let config: ScheduleConfig = serde_yaml::from_str(yaml)?;
+        config.interval()?;
+        config.scan_options()?;
+        if config.output_dir.is_none()
+            && config.history_db.is_none()
+            && config.elastic.is_none()
+            && config.syslog.is_none()
+            && config.gelf.is_none()
+        {
+            return Err(Error::General(
+                "schedule configures no sink: set output_dir, history_db, elastic, syslog or gelf"
+                    .to_string(),
+            ));
+        }
+        Ok(config)
+    }
+
+    /// Load a configuration from a YAML file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::schedule::ScheduleConfig;
+    ///
+    /// let config = ScheduleConfig::load(r"C:\ProgramData\sysaudit\service.yaml").unwrap();
+    /// println!("Scanning every {:?}", config.interval().unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, otherwise as
+    /// [`from_yaml`](Self::from_yaml).
+    #[cfg(feature = "yaml")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_yaml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Time between scans.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if `interval` is not a valid interval.
+    pub fn interval(&self) -> Result<Duration, Error> {
+        parse_interval(&self.interval)
+    }
+
+    /// Scan options selecting the configured sections.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown section name.
+    pub fn scan_options(&self) -> Result<ScanOptions, Error> {
+        let mut options = ScanOptions::new();
+        if let Some(only) = &self.only {
+            options = options.only(only)?;
+        }
+        if let Some(skip) = &self.skip {
+            options = options.skip(skip)?;
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_yaml() {
+        let config = ScheduleConfig::from_yaml(
+            "interval: 6h\nskip: industrial\nelastic:\n  url: https://es:9200\n",
+        )
+        .unwrap();
+        assert_eq!(config.interval().unwrap(), Duration::from_secs(6 * 3600));
+        assert!(!config.watch_changes);
+        assert!(!config.scan_options().unwrap().selected().has_industrial());
+        let elastic = config.elastic.unwrap();
+        assert_eq!(elastic.index, "sysaudit");
+        assert!(!elastic.items);
+
+        let config =
+            ScheduleConfig::from_yaml("gelf:\n  address: graylog:12201\n  items: true\n").unwrap();
+        let gelf = config.gelf.unwrap();
+        assert_eq!(gelf.protocol, "udp");
+        assert!(gelf.items);
+
+        assert!(ScheduleConfig::from_yaml("interval: 1h\n").is_err());
+        assert!(ScheduleConfig::from_yaml("interval: 1w\noutput_dir: audits\n").is_err());
+        assert!(ScheduleConfig::from_yaml("output_dir: audits\nsinks: []\n").is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! Audit scoring for executive summaries.
+//!
+//! An [`AuditScore`] condenses a report into one 0-100 number: a weighted
+//! average of per-domain scores (patching, obsolescence, hardening and
+//! industrial risk). Domains without data (no security section, no
+//! industrial software) are left out rather than counted as failures.
+//! Higher is better.
+
+use crate::compliance::{Policy, RuleStatus};
+use crate::findings::STALE_PATCH_DAYS;
+use crate::lifecycle::{OsSupport, SoftwareLifecycle, SupportStatus};
+use crate::options::Collector;
+use crate::{AuditReport, Error};
+use serde::{Deserialize, Serialize};
+
+/// Update age (days) up to which patching scores full marks.
+const PATCH_GRACE_DAYS: i64 = 30;
+
+/// Update age (days) at which patching scores zero.
+const PATCH_ZERO_DAYS: i64 = 180;
+
+/// A scored domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreDomain {
+    /// Age of the latest Windows update
+    Patching,
+    /// Operating system and software past vendor end of life
+    Obsolescence,
+    /// CIS hardening checks (needs the security section)
+    Hardening,
+    /// Exposure of hosts running industrial software
+    Industrial,
+}
+
+impl std::fmt::Display for ScoreDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScoreDomain::Patching => write!(f, "Patching"),
+            ScoreDomain::Obsolescence => write!(f, "Obsolescence"),
+            ScoreDomain::Hardening => write!(f, "Hardening"),
+            ScoreDomain::Industrial => write!(f, "Industrial risk"),
+        }
+    }
+}
+
+/// Relative weight of each domain in the overall score.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::score::ScoreWeights;
+///
+/// let weights = ScoreWeights::parse("patching=40, industrial=0").unwrap();
+/// assert_eq!(weights.patching, 40);
+/// assert_eq!(weights.hardening, ScoreWeights::default().hardening);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreWeights {
+    /// Weight of [`ScoreDomain::Patching`]
+    pub patching: u32,
+    /// Weight of [`ScoreDomain::Obsolescence`]
+    pub obsolescence: u32,
+    /// Weight of [`ScoreDomain::Hardening`]
+    pub hardening: u32,
+    /// Weight of [`ScoreDomain::Industrial`]
+    pub industrial: u32,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl ScoreWeights {
+    /// Patching 30, obsolescence 25, hardening 30, industrial 15.
+    pub const DEFAULT: ScoreWeights = ScoreWeights {
+        patching: 30,
+        obsolescence: 25,
+        hardening: 30,
+        industrial: 15,
+    };
+
+    /// Override defaults from a comma-separated `domain=weight` list.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] for an unknown domain or a weight that
+    /// is not a whole number.
+    pub fn parse(list: &str) -> Result<Self, Error> {
+        let mut weights = ScoreWeights::default();
+        for pair in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (domain, weight) = pair
+                .split_once('=')
+                .ok_or_else(|| Error::General(format!("expected domain=weight, got '{}'", pair)))?;
+            let weight: u32 = weight
+                .trim()
+                .parse()
+                .map_err(|_| Error::General(format!("invalid weight in '{}'", pair)))?;
+            match domain.trim().to_lowercase().as_str() {
+                "patching" => weights.patching = weight,
+                "obsolescence" => weights.obsolescence = weight,
+                "hardening" => weights.hardening = weight,
+                "industrial" => weights.industrial = weight,
+                other => {
+                    return Err(Error::General(format!(
+                        "unknown score domain '{}' (available: patching, obsolescence, hardening, industrial)",
+                        other
+                    )));
+                }
+            }
+        }
+        Ok(weights)
+    }
+
+    /// Weight of `domain`.
+    pub fn weight(&self, domain: ScoreDomain) -> u32 {
+        match domain {
+            ScoreDomain::Patching => self.patching,
+            ScoreDomain::Obsolescence => self.obsolescence,
+            ScoreDomain::Hardening => self.hardening,
+            ScoreDomain::Industrial => self.industrial,
+        }
+    }
+}
+
+/// Score of one domain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainScore {
+    /// The domain
+    pub domain: ScoreDomain,
+    /// 0-100, `None` when the report has no data for the domain
+    pub score: Option<u8>,
+    /// Weight used for the overall score
+    pub weight: u32,
+    /// What lowered the score
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasons: Vec<String>,
+}
+
+/// Overall and per-domain score of a report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditScore {
+    /// Weighted average of the scored domains (0-100), `None` when no
+    /// domain could be scored
+    pub overall: Option<u8>,
+    /// One entry per domain
+    pub domains: Vec<DomainScore>,
+    /// Change of `overall` since the previous report, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trend: Option<i16>,
+}
+
+impl AuditScore {
+    /// Score a report with the default weights.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{LocalScanner, Scanner, score::AuditScore};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = LocalScanner::new().audit().await?;
+    /// let score = AuditScore::compute(&report);
+    /// println!("{} ({})", score.overall.unwrap_or(0), score.grade().unwrap_or('-'));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compute(report: &AuditReport) -> Self {
+        Self::compute_with(report, &ScoreWeights::default())
+    }
+
+    /// Score a report with custom weights.
+    pub fn compute_with(report: &AuditReport, weights: &ScoreWeights) -> Self {
+        let domains: Vec<DomainScore> = [
+            (ScoreDomain::Patching, patching(report)),
+            (ScoreDomain::Obsolescence, obsolescence(report)),
+            (ScoreDomain::Hardening, hardening(report)),
+            (ScoreDomain::Industrial, industrial(report)),
+        ]
+        .into_iter()
+        .map(|(domain, (score, reasons))| DomainScore {
+            domain,
+            score,
+            weight: weights.weight(domain),
+            reasons,
+        })
+        .collect();
+
+        let (points, total) = domains
+            .iter()
+            .filter_map(|d| d.score.map(|s| (u64::from(s), u64::from(d.weight))))
+            .fold((0, 0), |(points, total), (score, weight)| {
+                (points + score * weight, total + weight)
+            });
+        let overall = (total > 0).then(|| ((points + total / 2) / total) as u8);
+
+        AuditScore {
+            overall,
+            domains,
+            trend: None,
+        }
+    }
+
+    /// Record the change since `previous`, a score of an earlier report of
+    /// the same host.
+    pub fn with_previous(mut self, previous: &AuditScore) -> Self {
+        self.trend = self
+            .overall
+            .zip(previous.overall)
+            .map(|(now, before)| i16::from(now) - i16::from(before));
+        self
+    }
+
+    /// Letter grade of the overall score: A (90+), B (80+), C (70+),
+    /// D (60+) or F.
+    pub fn grade(&self) -> Option<char> {
+        self.overall.map(|score| match score {
+            90.. => 'A',
+            80..=89 => 'B',
+            70..=79 => 'C',
+            60..=69 => 'D',
+            _ => 'F',
+        })
+    }
+
+    /// Score of `domain`, if it was scored.
+    pub fn domain(&self, domain: ScoreDomain) -> Option<u8> {
+        self.domains
+            .iter()
+            .find(|d| d.domain == domain)
+            .and_then(|d| d.score)
+    }
+}
+
+/// Full marks up to [`PATCH_GRACE_DAYS`], falling linearly to zero at
+/// [`PATCH_ZERO_DAYS`].
+fn patching(report: &AuditReport) -> (Option<u8>, Vec<String>) {
+    let today = report.timestamp.date_naive();
+    match report.updates.iter().filter_map(|u| u.installed_on).max() {
+        Some(latest) => {
+            let age = (today - latest).num_days();
+            let score = if age <= PATCH_GRACE_DAYS {
+                100
+            } else {
+                let left = (PATCH_ZERO_DAYS - age).max(0);
+                (left * 100 / (PATCH_ZERO_DAYS - PATCH_GRACE_DAYS)) as u8
+            };
+            let reasons = if age > PATCH_GRACE_DAYS {
+                vec![format!("latest update installed {} days ago", age)]
+            } else {
+                Vec::new()
+            };
+            (Some(score), reasons)
+        }
+        // The query failed: no evidence either way.
+        None if report
+            .warnings
+            .iter()
+            .any(|w| w.collector == Collector::Updates) =>
+        {
+            (None, Vec::new())
+        }
+        None => (Some(0), vec!["no installed updates recorded".to_string()]),
+    }
+}
+
+/// Penalties for an out-of-support OS and end-of-life software.
+fn obsolescence(report: &AuditReport) -> (Option<u8>, Vec<String>) {
+    let today = report.timestamp.date_naive();
+    let mut reasons = Vec::new();
+    let mut penalty = 0u32;
+
+    match OsSupport::evaluate(&report.system.os_name, &report.system.os_version, today) {
+        OsSupport::EndOfLife(end) => {
+            penalty += 60;
+            reasons.push(format!("operating system out of support since {}", end));
+        }
+        OsSupport::EndingSoon(end) => {
+            penalty += 15;
+            reasons.push(format!("operating system support ends {}", end));
+        }
+        OsSupport::Supported(_) | OsSupport::Unknown => {}
+    }
+
+    let lifecycle = SoftwareLifecycle::bundled();
+    let (mut eol, mut ending) = (0u32, 0u32);
+    for sw in &report.software {
+        match lifecycle
+            .lookup(&sw.name, sw.version.as_deref(), today)
+            .map(|s| s.status)
+        {
+            Some(SupportStatus::EndOfLife) => eol += 1,
+            Some(SupportStatus::EndingSoon) => ending += 1,
+            _ => {}
+        }
+    }
+    if eol > 0 {
+        penalty += (eol * 10).min(40);
+        reasons.push(format!("{} end-of-life software package(s)", eol));
+    }
+    if ending > 0 {
+        penalty += (ending * 3).min(10);
+        reasons.push(format!("{} software package(s) near end of life", ending));
+    }
+
+    (Some(100u32.saturating_sub(penalty) as u8), reasons)
+}
+
+/// The CIS pack score, when the security section was collected.
+fn hardening(report: &AuditReport) -> (Option<u8>, Vec<String>) {
+    if report.security.is_none() {
+        return (None, Vec::new());
+    }
+    let compliance = Policy::cis().evaluate(report);
+    let failed = compliance
+        .results
+        .iter()
+        .filter(|r| r.status == RuleStatus::Fail)
+        .count();
+    let reasons = if failed > 0 {
+        vec![format!("{} CIS check(s) failed", failed)]
+    } else {
+        Vec::new()
+    };
+    (compliance.score, reasons)
+}
+
+/// Penalties for exposure of a host running industrial software; not
+/// scored on hosts without any.
+fn industrial(report: &AuditReport) -> (Option<u8>, Vec<String>) {
+    if report.industrial.is_empty() {
+        return (None, Vec::new());
+    }
+    let today = report.timestamp.date_naive();
+    let mut reasons = Vec::new();
+    let mut penalty = 0u32;
+
+    if OsSupport::evaluate(&report.system.os_name, &report.system.os_version, today)
+        .is_end_of_life()
+    {
+        penalty += 40;
+        reasons.push("industrial software on an unsupported OS".to_string());
+    }
+    let latest = report.updates.iter().filter_map(|u| u.installed_on).max();
+    if latest.is_none_or(|date| (today - date).num_days() > STALE_PATCH_DAYS) {
+        penalty += 20;
+        reasons.push(format!("no updates in the last {} days", STALE_PATCH_DAYS));
+    }
+    if let Some(security) = &report.security {
+        if security.smb1_server == Some(true) {
+            penalty += 15;
+            reasons.push("SMBv1 server enabled".to_string());
+        }
+        if security.rdp_enabled == Some(true) && security.rdp_nla == Some(false) {
+            penalty += 15;
+            reasons.push("Remote Desktop without NLA".to_string());
+        }
+        if security.firewall.iter().any(|p| p.enabled == Some(false)) {
+            penalty += 10;
+            reasons.push("a firewall profile is off".to_string());
+        }
+    }
+
+    (Some(100u32.saturating_sub(penalty) as u8), reasons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SecurityPosture, WindowsUpdate};
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use sysaudit_common::{IndustrialSoftwareDto, SoftwareDto, SystemInfoDto};
+
+    fn report() -> AuditReport {
+        AuditReport {
+            software: vec![SoftwareDto {
+                name: "Python 2.7.18".into(),
+                version: Some("2.7.18150".into()),
+                vendor: None,
+                install_date: None,
+            }],
+            industrial: vec![IndustrialSoftwareDto {
+                vendor: "Rockwell".into(),
+                product: "FactoryTalk View".into(),
+                version: Some("13.0".into()),
+                install_path: None,
+            }],
+            updates: vec![WindowsUpdate {
+                hotfix_id: "KB4534310".into(),
+                description: None,
+                installed_on: NaiveDate::from_ymd_opt(2024, 3, 2),
+                installed_by: None,
+            }],
+            timestamp: Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(),
+            ..AuditReport::empty(SystemInfoDto {
+                os_name: "Windows 7 Professional".into(),
+                os_version: "6.1.7601".into(),
+                host_name: "HMI-01".into(),
+                cpu_info: "Intel Atom".into(),
+                cpu_physical_cores: Some(2),
+                memory_total_bytes: 1000,
+                memory_used_bytes: 500,
+                manufacturer: None,
+                model: None,
+                network_interfaces: vec![],
+            })
+        }
+    }
+
+    #[test]
+    fn test_compute_domains() {
+        let score = AuditScore::compute(&report());
+        // 91 days since the last update: 89/150 of the way to zero.
+        assert_eq!(score.domain(ScoreDomain::Patching), Some(59));
+        // Windows 7 (-60) and Python 2.7 (-10).
+        assert_eq!(score.domain(ScoreDomain::Obsolescence), Some(30));
+        assert_eq!(score.domain(ScoreDomain::Hardening), None);
+        // Unsupported OS (-40), stale updates (-20).
+        assert_eq!(score.domain(ScoreDomain::Industrial), Some(40));
+        // (59*30 + 30*25 + 40*15) / 70
+        assert_eq!(score.overall, Some(45));
+        assert_eq!(score.grade(), Some('F'));
+
+        let previous = AuditScore::compute_with(
+            &report(),
+            &ScoreWeights::parse("obsolescence=0,industrial=0").unwrap(),
+        );
+        assert_eq!(previous.overall, Some(59));
+        assert_eq!(score.with_previous(&previous).trend, Some(-14));
+    }
+
+    #[test]
+    fn test_hardening_and_weights() {
+        let mut report = report();
+        report.security = Some(SecurityPosture::default());
+        let score = AuditScore::compute(&report);
+        assert!(score.domains.iter().all(|d| d.weight > 0));
+        // Nothing reported in the posture: every CIS rule is unknown.
+        assert_eq!(score.domain(ScoreDomain::Hardening), None);
+
+        assert!(ScoreWeights::parse("uptime=5").is_err());
+        assert!(ScoreWeights::parse("patching").is_err());
+    }
+}
@@ -0,0 +1,423 @@
+//! Security posture module.
+//!
+//! Provides read-only access to security settings that compliance rules
+//! check: BitLocker protection of each volume (via WMI), UAC, SMBv1,
+//! Windows Firewall and Remote Desktop settings (via the registry), the
+//! local password and lockout policy (via `NetUserModalsGet`) and antivirus
+//! exclusions (see [`exclusions`](crate::exclusions)).
+//!
+//! Registry settings prefer the Group Policy value over the local one, as
+//! Windows does.
+
+#[cfg(all(windows, feature = "local"))]
+use crate::WmiContext;
+use crate::exclusions::AvExclusions;
+#[cfg(all(windows, feature = "local"))]
+use crate::exclusions::{DEFENDER_NAMESPACE, SECURITY_CENTER_NAMESPACE, query_antivirus};
+use serde::{Deserialize, Serialize};
+#[cfg(all(windows, feature = "local"))]
+use windows::Win32::NetworkManagement::NetManagement::{
+    NetApiBufferFree, NetUserModalsGet, TIMEQ_FOREVER, USER_MODALS_INFO_0, USER_MODALS_INFO_3,
+};
+#[cfg(all(windows, feature = "local"))]
+use windows::core::PCWSTR;
+#[cfg(all(windows, feature = "local"))]
+use windows_registry::LOCAL_MACHINE;
+#[cfg(all(windows, feature = "local"))]
+use wmi::WMIConnection;
+
+/// WMI namespace of `Win32_EncryptableVolume`.
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const BITLOCKER_NAMESPACE: &str = r"root\cimv2\Security\MicrosoftVolumeEncryption";
+
+/// Security settings of a host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityPosture {
+    /// Drive Windows is installed on (e.g., "C:")
+    #[serde(default)]
+    pub system_drive: Option<String>,
+    /// BitLocker status per volume; empty when BitLocker is not available
+    #[serde(default)]
+    pub bitlocker: Vec<BitLockerVolume>,
+    /// Whether User Account Control is on (`EnableLUA`)
+    #[serde(default)]
+    pub uac_enabled: Option<bool>,
+    /// UAC elevation prompt for administrators
+    /// (`ConsentPromptBehaviorAdmin`: 0 elevate silently, 1/2 credentials or
+    /// consent on the secure desktop, 5 consent for non-Windows binaries)
+    #[serde(default)]
+    pub uac_admin_prompt: Option<u32>,
+    /// Whether the SMBv1 server is enabled
+    #[serde(default)]
+    pub smb1_server: Option<bool>,
+    /// Whether the SMBv1 client driver is enabled
+    #[serde(default)]
+    pub smb1_client: Option<bool>,
+    /// Windows Firewall state per profile
+    #[serde(default)]
+    pub firewall: Vec<FirewallProfile>,
+    /// Local password and lockout policy
+    #[serde(default)]
+    pub password_policy: Option<PasswordPolicy>,
+    /// Whether Remote Desktop connections are allowed
+    #[serde(default)]
+    pub rdp_enabled: Option<bool>,
+    /// Whether Remote Desktop requires Network Level Authentication
+    #[serde(default)]
+    pub rdp_nla: Option<bool>,
+    /// Microsoft Defender exclusions; `None` when Defender is not present
+    /// or they could not be read
+    #[serde(default)]
+    pub defender_exclusions: Option<AvExclusions>,
+    /// Antivirus products registered with Security Center (workstations
+    /// only)
+    #[serde(default)]
+    pub antivirus: Vec<String>,
+}
+
+/// BitLocker status of one volume.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitLockerVolume {
+    /// Drive letter (e.g., "C:"), or the volume ID when unmounted
+    pub drive: String,
+    /// Whether protection is on; `None` when the volume is locked and the
+    /// status cannot be read
+    pub protected: Option<bool>,
+}
+
+/// Windows Firewall state of one profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallProfile {
+    /// Profile name: "domain", "private" or "public"
+    pub name: String,
+    /// Whether the firewall is on; `None` when the setting cannot be read
+    pub enabled: Option<bool>,
+}
+
+/// Password and account lockout policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    /// Minimum password length
+    pub min_length: u32,
+    /// Maximum password age in days; `None` when passwords never expire
+    pub max_age_days: Option<u32>,
+    /// Minimum password age in days
+    pub min_age_days: u32,
+    /// Number of remembered passwords
+    pub history: u32,
+    /// Failed logons before lockout; 0 when accounts never lock out
+    pub lockout_threshold: u32,
+}
+
+/// Firewall profiles: name, Group Policy key and local key.
+#[cfg(any(all(windows, feature = "local"), test))]
+const FIREWALL_PROFILES: &[(&str, &str, &str)] = &[
+    (
+        "domain",
+        r"SOFTWARE\Policies\Microsoft\WindowsFirewall\DomainProfile",
+        r"SYSTEM\CurrentControlSet\Services\SharedAccess\Parameters\FirewallPolicy\DomainProfile",
+    ),
+    (
+        "private",
+        r"SOFTWARE\Policies\Microsoft\WindowsFirewall\PrivateProfile",
+        r"SYSTEM\CurrentControlSet\Services\SharedAccess\Parameters\FirewallPolicy\StandardProfile",
+    ),
+    (
+        "public",
+        r"SOFTWARE\Policies\Microsoft\WindowsFirewall\PublicProfile",
+        r"SYSTEM\CurrentControlSet\Services\SharedAccess\Parameters\FirewallPolicy\PublicProfile",
+    ),
+];
+
+#[cfg(any(all(windows, feature = "local"), test))]
+const UAC_KEY: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\Policies\System";
+#[cfg(any(all(windows, feature = "local"), test))]
+const SMB_SERVER_KEY: &str = r"SYSTEM\CurrentControlSet\Services\LanmanServer\Parameters";
+/// SMBv1 server and client drivers.
+#[cfg(any(all(windows, feature = "local"), test))]
+const SMB1_SERVER_DRIVER: &str = r"SYSTEM\CurrentControlSet\Services\srv";
+#[cfg(any(all(windows, feature = "local"), test))]
+const SMB1_CLIENT_DRIVER: &str = r"SYSTEM\CurrentControlSet\Services\mrxsmb10";
+#[cfg(any(all(windows, feature = "local"), test))]
+const RDP_POLICY_KEY: &str = r"SOFTWARE\Policies\Microsoft\Windows NT\Terminal Services";
+#[cfg(any(all(windows, feature = "local"), test))]
+const RDP_KEY: &str = r"SYSTEM\CurrentControlSet\Control\Terminal Server";
+#[cfg(any(all(windows, feature = "local"), test))]
+const RDP_TCP_KEY: &str = r"SYSTEM\CurrentControlSet\Control\Terminal Server\WinStations\RDP-Tcp";
+
+/// `Start` value of a disabled driver.
+#[cfg(any(all(windows, feature = "local"), test))]
+const SERVICE_DISABLED: u32 = 4;
+
+/// WMI result struct for Win32_EncryptableVolume.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Win32EncryptableVolume {
+    #[serde(rename = "DeviceID")]
+    device_id: Option<String>,
+    drive_letter: Option<String>,
+    protection_status: Option<u32>,
+}
+
+impl SecurityPosture {
+    /// Registry-based settings, read through `read(key, value)` which
+    /// returns a `REG_DWORD` under `HKEY_LOCAL_MACHINE`. Shared by the local
+    /// and the remote (`StdRegProv`) collectors.
+    #[cfg(any(all(windows, feature = "local"), test))]
+    pub(crate) fn from_registry(read: impl Fn(&str, &str) -> Option<u32>) -> Self {
+        // Group Policy wins over the local setting.
+        let setting = |policy: &str, local: &str, value: &str| {
+            read(policy, value).or_else(|| read(local, value))
+        };
+        let driver_enabled = |key: &str| read(key, "Start").is_some_and(|s| s != SERVICE_DISABLED);
+
+        SecurityPosture {
+            uac_enabled: read(UAC_KEY, "EnableLUA").map(|v| v != 0),
+            uac_admin_prompt: read(UAC_KEY, "ConsentPromptBehaviorAdmin"),
+            // Windows 10 1709 and later drop the SMBv1 drivers unless the
+            // feature is installed; `SMB1=0` turns the server off.
+            smb1_server: Some(
+                read(SMB_SERVER_KEY, "SMB1") != Some(0) && driver_enabled(SMB1_SERVER_DRIVER),
+            ),
+            smb1_client: Some(driver_enabled(SMB1_CLIENT_DRIVER)),
+            firewall: FIREWALL_PROFILES
+                .iter()
+                .map(|(name, policy, local)| FirewallProfile {
+                    name: name.to_string(),
+                    enabled: setting(policy, local, "EnableFirewall").map(|v| v != 0),
+                })
+                .collect(),
+            rdp_enabled: setting(RDP_POLICY_KEY, RDP_KEY, "fDenyTSConnections").map(|v| v == 0),
+            rdp_nla: setting(RDP_POLICY_KEY, RDP_TCP_KEY, "UserAuthentication").map(|v| v != 0),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the system drive (or, if unknown, every volume) is
+    /// protected by BitLocker.
+    pub fn bitlocker_enabled(&self) -> bool {
+        let system_volume = self.system_drive.as_deref().and_then(|drive| {
+            self.bitlocker
+                .iter()
+                .find(|v| v.drive.eq_ignore_ascii_case(drive))
+        });
+        match system_volume {
+            Some(volume) => volume.protected == Some(true),
+            None => {
+                !self.bitlocker.is_empty()
+                    && self.bitlocker.iter().all(|v| v.protected == Some(true))
+            }
+        }
+    }
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl SecurityPosture {
+    /// Collect the security settings (READ-ONLY).
+    ///
+    /// Reading BitLocker status and Defender exclusions requires
+    /// administrator rights; settings that cannot be read are left empty.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SecurityPosture;
+    ///
+    /// let posture = SecurityPosture::collect().unwrap();
+    /// println!("BitLocker: {}", posture.bitlocker_enabled());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if COM cannot be
+    /// initialized.
+    pub fn collect() -> Result<Self, crate::Error> {
+        Self::collect_with_warnings().map(|(posture, _)| posture)
+    }
+
+    /// [`collect`](Self::collect), also describing the settings that could
+    /// not be read.
+    ///
+    /// # Errors
+    ///
+    /// As [`collect`](Self::collect).
+    #[tracing::instrument(fields(warnings = tracing::field::Empty))]
+    pub fn collect_with_warnings() -> Result<(Self, Vec<String>), crate::Error> {
+        WmiContext::with_current(Self::collect_with_wmi)
+    }
+
+    /// [`collect_with_warnings`](Self::collect_with_warnings) over an open
+    /// WMI context.
+    ///
+    /// # Errors
+    ///
+    /// Never fails once the context is open; unreadable settings are
+    /// reported as warnings.
+    pub fn collect_with_wmi(wmi: &WmiContext) -> Result<(Self, Vec<String>), crate::Error> {
+        tracing::info!("Collecting security settings");
+        let mut warnings = Vec::new();
+        let mut posture = SecurityPosture::from_registry(|path, value| {
+            LOCAL_MACHINE.open(path).ok()?.get_u32(value).ok()
+        });
+        posture.system_drive = std::env::var("SystemDrive").ok();
+
+        match wmi
+            .namespace(BITLOCKER_NAMESPACE)
+            .and_then(|con| Self::query_bitlocker(&con))
+        {
+            Ok(bitlocker) => posture.bitlocker = bitlocker,
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not query BitLocker status");
+                warnings.push(format!("BitLocker query failed: {}", e));
+            }
+        }
+        match wmi
+            .namespace(DEFENDER_NAMESPACE)
+            .and_then(|con| AvExclusions::query(&con))
+        {
+            Ok(exclusions) => posture.defender_exclusions = exclusions,
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not read Defender exclusions");
+                warnings.push(format!("Defender exclusions query failed: {}", e));
+            }
+        }
+        // Windows Server has no Security Center.
+        posture.antivirus = wmi
+            .namespace(SECURITY_CENTER_NAMESPACE)
+            .and_then(|con| query_antivirus(&con))
+            .unwrap_or_default();
+        match query_password_policy() {
+            Ok(policy) => posture.password_policy = Some(policy),
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not read the password policy");
+                warnings.push(format!("password policy query failed: {}", e));
+            }
+        }
+        tracing::Span::current().record("warnings", warnings.len());
+        Ok((posture, warnings))
+    }
+
+    /// Query `Win32_EncryptableVolume` on an open (local or remote) WMI
+    /// connection to [`BITLOCKER_NAMESPACE`].
+    pub(crate) fn query_bitlocker(
+        wmi_con: &WMIConnection,
+    ) -> Result<Vec<BitLockerVolume>, crate::Error> {
+        let results: Vec<Win32EncryptableVolume> = wmi_con.query()?;
+
+        Ok(results
+            .into_iter()
+            .filter_map(|r| {
+                let drive = r.drive_letter.filter(|s| !s.is_empty()).or(r.device_id)?;
+                Some(BitLockerVolume {
+                    drive,
+                    protected: protection(r.protection_status),
+                })
+            })
+            .collect())
+    }
+}
+
+/// Local password and lockout policy from `NetUserModalsGet`.
+#[cfg(all(windows, feature = "local"))]
+fn query_password_policy() -> Result<PasswordPolicy, crate::Error> {
+    let password: USER_MODALS_INFO_0 = user_modals(0)?;
+    let lockout: USER_MODALS_INFO_3 = user_modals(3)?;
+    let days = |seconds: u32| seconds / 86_400;
+    Ok(PasswordPolicy {
+        min_length: password.usrmod0_min_passwd_len,
+        max_age_days: (password.usrmod0_max_passwd_age != TIMEQ_FOREVER)
+            .then(|| days(password.usrmod0_max_passwd_age)),
+        min_age_days: days(password.usrmod0_min_passwd_age),
+        history: password.usrmod0_password_hist_len,
+        lockout_threshold: lockout.usrmod3_lockout_threshold,
+    })
+}
+
+/// Read `USER_MODALS_INFO_<level>` of the local machine; `T` must be that
+/// struct.
+#[cfg(all(windows, feature = "local"))]
+fn user_modals<T: Copy>(level: u32) -> Result<T, crate::Error> {
+    let mut buffer: *mut u8 = std::ptr::null_mut();
+    // SAFETY: a null server name means the local machine and `buffer` is a
+    // valid out pointer.
+    let status = unsafe { NetUserModalsGet(PCWSTR::null(), level, &mut buffer) };
+    if status != 0 || buffer.is_null() {
+        return Err(crate::Error::General(format!(
+            "NetUserModalsGet level {} failed with status {}",
+            level, status
+        )));
+    }
+    // SAFETY: on success `buffer` holds a USER_MODALS_INFO_<level>, which
+    // the caller's `T` matches; it is copied out before being freed.
+    let info = unsafe { std::ptr::read_unaligned(buffer as *const T) };
+    // SAFETY: `buffer` was allocated by NetUserModalsGet above.
+    unsafe { NetApiBufferFree(Some(buffer as *const std::ffi::c_void)) };
+    Ok(info)
+}
+
+/// Map `ProtectionStatus` (0 off, 1 on, 2 unknown).
+#[cfg(any(all(windows, feature = "local"), test))]
+fn protection(status: Option<u32>) -> Option<bool> {
+    match status {
+        Some(0) => Some(false),
+        Some(1) => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitlocker_enabled() {
+        let volume = |drive: &str, status| BitLockerVolume {
+            drive: drive.to_string(),
+            protected: protection(status),
+        };
+        let mut posture = SecurityPosture {
+            system_drive: Some("C:".to_string()),
+            bitlocker: vec![volume("C:", Some(1)), volume("D:", Some(0))],
+            ..Default::default()
+        };
+        assert!(posture.bitlocker_enabled());
+        posture.system_drive = None;
+        assert!(!posture.bitlocker_enabled());
+
+        let posture = SecurityPosture {
+            system_drive: Some("C:".to_string()),
+            bitlocker: vec![volume("C:", Some(2))],
+            ..Default::default()
+        };
+        assert!(!posture.bitlocker_enabled());
+        assert!(!SecurityPosture::default().bitlocker_enabled());
+    }
+
+    #[test]
+    fn test_from_registry_prefers_policy() {
+        let values = std::collections::HashMap::from([
+            ((UAC_KEY, "EnableLUA"), 1),
+            ((UAC_KEY, "ConsentPromptBehaviorAdmin"), 5),
+            ((SMB_SERVER_KEY, "SMB1"), 0),
+            ((SMB1_SERVER_DRIVER, "Start"), 3),
+            ((SMB1_CLIENT_DRIVER, "Start"), 3),
+            ((FIREWALL_PROFILES[0].1, "EnableFirewall"), 0),
+            ((FIREWALL_PROFILES[0].2, "EnableFirewall"), 1),
+            ((FIREWALL_PROFILES[2].2, "EnableFirewall"), 1),
+            ((RDP_KEY, "fDenyTSConnections"), 0),
+            ((RDP_TCP_KEY, "UserAuthentication"), 1),
+        ]);
+        let posture =
+            SecurityPosture::from_registry(|key, value| values.get(&(key, value)).copied());
+
+        assert_eq!(posture.uac_enabled, Some(true));
+        assert_eq!(posture.uac_admin_prompt, Some(5));
+        assert_eq!(posture.smb1_server, Some(false));
+        assert_eq!(posture.smb1_client, Some(true));
+        let firewall: Vec<Option<bool>> = posture.firewall.iter().map(|p| p.enabled).collect();
+        assert_eq!(firewall, vec![Some(false), None, Some(true)]);
+        assert_eq!(posture.rdp_enabled, Some(true));
+        assert_eq!(posture.rdp_nla, Some(true));
+    }
+}
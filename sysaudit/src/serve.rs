@@ -0,0 +1,341 @@
+//! Pull-based collection: serve this host's report over HTTPS.
+//!
+//! For networks where a central scanner may not open WinRM connections into
+//! the OT zone, each host runs [`ReportServer`] and the collector pulls
+//! `GET /report` instead. Every endpoint except `/health` requires
+//! `Authorization: Bearer <token>`.
+//!
+//! | Path | Body |
+//! |:---|:---|
+//! | `/report` | Full [`AuditReport`] |
+//! | `/system` | System information |
+//! | `/software` | Installed software |
+//! | `/industrial` | Industrial software |
+//! | `/updates` | Windows updates |
+//! | `/health` | `{"status":"ok"}` (no authentication) |
+
+use bon::Builder;
+use secrecy::{ExposeSecret, SecretString};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server, SslConfig};
+
+use crate::options::ScanOptions;
+use crate::report::AuditReport;
+use crate::scanner::{BlockingScanner, ScanError};
+use crate::{Error, LocalScanner};
+
+/// Resource served at a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Endpoint {
+    Health,
+    Report,
+    System,
+    Software,
+    Industrial,
+    Updates,
+}
+
+impl Endpoint {
+    fn from_path(path: &str) -> Option<Self> {
+        let path = path.split('?').next().unwrap_or(path).trim_end_matches('/');
+        match path {
+            "/health" => Some(Endpoint::Health),
+            "/report" | "" => Some(Endpoint::Report),
+            "/system" => Some(Endpoint::System),
+            "/software" => Some(Endpoint::Software),
+            "/industrial" => Some(Endpoint::Industrial),
+            "/updates" => Some(Endpoint::Updates),
+            _ => None,
+        }
+    }
+}
+
+/// HTTPS endpoint serving the local audit report.
+///
+/// Reports are collected on demand and reused for `cache_ttl`; a zero TTL
+/// scans on every request. Concurrent requests share one scan.
+///
+/// # Example
+///
+/// ```no_run
+/// use secrecy::SecretString;
+/// use std::time::Duration;
+/// use sysaudit::serve::ReportServer;
+///
+/// ReportServer::builder()
+///     .listen("0.0.0.0:8443")
+///     .cert("server.pem")
+///     .key("server.key")
+///     .token(SecretString::from("s3cret-token"))
+///     .cache_ttl(Duration::from_secs(600))
+///     .build()
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Builder)]
+pub struct ReportServer {
+    /// Address to listen on, e.g. `0.0.0.0:8443`.
+    #[builder(into)]
+    listen: String,
+
+    /// PEM certificate chain for TLS.
+    #[builder(into)]
+    cert: PathBuf,
+
+    /// PEM private key for TLS.
+    #[builder(into)]
+    key: PathBuf,
+
+    /// Bearer token clients must present.
+    token: SecretString,
+
+    /// How long a collected report is reused (default: 5 minutes).
+    #[builder(default = Duration::from_secs(300))]
+    cache_ttl: Duration,
+
+    /// Threads handling requests (default: 4).
+    #[builder(default = 4)]
+    workers: usize,
+
+    /// Collection settings; with [`ScanOptions::cache`] sections are also
+    /// reused from disk across restarts.
+    #[builder(default)]
+    options: ScanOptions,
+}
+
+/// The last collected report and when it was collected. `scan` is held
+/// while a report is collected, so requests arriving meanwhile wait for
+/// that scan instead of starting their own, while requests served from a
+/// fresh report never wait.
+#[derive(Default)]
+struct Cache {
+    report: Mutex<Option<(Arc<AuditReport>, Instant)>>,
+    scan: Mutex<()>,
+}
+
+impl ReportServer {
+    /// Listen and serve until the process exits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the certificate or key cannot be read, or
+    /// [`Error::General`] if the token is empty or the listener cannot be
+    /// started.
+    pub fn run(self) -> Result<(), Error> {
+        check_token(&self.token)?;
+        let cache = Cache::default();
+        let (listen, cert, key, workers) = (
+            self.listen.clone(),
+            self.cert.clone(),
+            self.key.clone(),
+            self.workers,
+        );
+        serve_https(&listen, &cert, &key, workers, move |request| {
+            self.handle(request, &cache)
+        })
+    }
+
+    fn handle(&self, request: Request, cache: &Cache) {
+        let url = request.url().to_string();
+        let response = match (request.method(), Endpoint::from_path(&url)) {
+            (Method::Get, Some(Endpoint::Health)) => json_response(200, r#"{"status":"ok"}"#),
+            (Method::Get, Some(endpoint)) => {
+                let bearer = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Authorization"))
+                    .map(|h| h.value.as_str().to_string());
+                if authorized(bearer.as_deref(), &self.token) {
+                    self.respond(endpoint, cache)
+                } else {
+                    tracing::warn!(
+                        remote = ?request.remote_addr(),
+                        path = %url,
+                        "Rejected unauthenticated request"
+                    );
+                    json_response(401, r#"{"error":"unauthorized"}"#)
+                }
+            }
+            (_, Some(_)) => json_response(405, r#"{"error":"method not allowed"}"#),
+            (_, None) => json_response(404, r#"{"error":"not found"}"#),
+        };
+
+        if let Err(e) = request.respond(response) {
+            tracing::debug!(error = %e, "Failed to send response");
+        }
+    }
+
+    fn respond(&self, endpoint: Endpoint, cache: &Cache) -> Response<std::io::Cursor<Vec<u8>>> {
+        let report = match self.report(cache) {
+            Ok(report) => report,
+            Err(e) => {
+                tracing::error!(error = %e, "Audit failed");
+                let body = serde_json::json!({ "error": e.to_string() });
+                return json_response(500, &body.to_string());
+            }
+        };
+
+        let body = match endpoint {
+            Endpoint::Report | Endpoint::Health => serde_json::to_string(&*report),
+            Endpoint::System => serde_json::to_string(&report.system),
+            Endpoint::Software => serde_json::to_string(&report.software),
+            Endpoint::Industrial => serde_json::to_string(&report.industrial),
+            Endpoint::Updates => serde_json::to_string(&report.updates),
+        };
+        match body {
+            Ok(body) => json_response(200, &body),
+            Err(e) => json_response(
+                500,
+                &serde_json::json!({ "error": e.to_string() }).to_string(),
+            ),
+        }
+    }
+
+    /// The cached report if it is still fresh, otherwise a new one.
+    fn report(&self, cache: &Cache) -> Result<Arc<AuditReport>, ScanError> {
+        if let Some(report) = self.fresh(&lock(&cache.report)) {
+            return Ok(report);
+        }
+        let _scan = lock(&cache.scan);
+        // Another request may have finished a scan while this one waited.
+        if let Some(report) = self.fresh(&lock(&cache.report)) {
+            return Ok(report);
+        }
+        tracing::info!("Collecting report for request");
+        let report = Arc::new(LocalScanner::with_options(self.options.clone()).scan_blocking()?);
+        *lock(&cache.report) = Some((Arc::clone(&report), Instant::now()));
+        Ok(report)
+    }
+
+    fn fresh(&self, cached: &Option<(Arc<AuditReport>, Instant)>) -> Option<Arc<AuditReport>> {
+        cached
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < self.cache_ttl)
+            .map(|(report, _)| Arc::clone(report))
+    }
+}
+
+/// Listen on `listen` over HTTPS and pass each request to `handle` on
+/// `workers` threads until the process exits.
+///
+/// # Errors
+///
+/// Returns [`Error::Io`] if the certificate or key cannot be read, or
+/// [`Error::General`] if the listener cannot be started.
+pub fn serve_https<F>(
+    listen: &str,
+    cert: &Path,
+    key: &Path,
+    workers: usize,
+    handle: F,
+) -> Result<(), Error>
+where
+    F: Fn(Request) + Send + Sync + 'static,
+{
+    let ssl = SslConfig {
+        certificate: std::fs::read(cert)?,
+        private_key: std::fs::read(key)?,
+    };
+    let server = Server::https(listen, ssl)
+        .map_err(|e| Error::General(format!("cannot listen on {}: {}", listen, e)))?;
+    tracing::info!(listen = %listen, "Listening on HTTPS");
+
+    let server = Arc::new(server);
+    let handle = Arc::new(handle);
+    let workers: Vec<_> = (0..workers.max(1))
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let handle = Arc::clone(&handle);
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(request);
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+/// Lock a mutex, ignoring poisoning: a panicked request leaves no partial
+/// state behind.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Refuse to start with an empty or blank token, which every
+/// `Authorization: Bearer ` header would match.
+///
+/// # Errors
+///
+/// Returns [`Error::General`] if the token is empty or whitespace.
+pub fn check_token(token: &SecretString) -> Result<(), Error> {
+    if token.expose_secret().trim().is_empty() {
+        return Err(Error::General(
+            "the bearer token must not be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check an `Authorization` header against the token in constant time.
+///
+/// Shared by every HTTP endpoint that takes a bearer token.
+pub fn authorized(header: Option<&str>, token: &SecretString) -> bool {
+    let Some(presented) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let expected = token.expose_secret().trim().as_bytes();
+    let presented = presented.trim().as_bytes();
+    !expected.is_empty()
+        && presented.len() == expected.len()
+        && presented
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// A response with `body` as `application/json`.
+///
+/// Shared by every HTTP endpoint that answers in JSON.
+pub fn json_response(status: u16, body: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let response = Response::from_string(body).with_status_code(status);
+    match Header::from_bytes("Content-Type", "application/json") {
+        Ok(header) => response.with_header(header),
+        Err(()) => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_routing() {
+        assert_eq!(Endpoint::from_path("/report"), Some(Endpoint::Report));
+        assert_eq!(Endpoint::from_path("/software/"), Some(Endpoint::Software));
+        assert_eq!(Endpoint::from_path("/updates?x=1"), Some(Endpoint::Updates));
+        assert_eq!(Endpoint::from_path("/"), Some(Endpoint::Report));
+        assert_eq!(Endpoint::from_path("/admin"), None);
+    }
+
+    #[test]
+    fn test_authorized() {
+        let token = SecretString::from("s3cret");
+        assert!(authorized(Some("Bearer s3cret"), &token));
+        assert!(!authorized(Some("Bearer s3cre"), &token));
+        assert!(!authorized(Some("Basic s3cret"), &token));
+        assert!(!authorized(None, &token));
+
+        let blank = SecretString::from(" ");
+        assert!(!authorized(Some("Bearer "), &blank));
+        assert!(check_token(&blank).is_err());
+        assert!(check_token(&token).is_ok());
+    }
+}
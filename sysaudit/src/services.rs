@@ -0,0 +1,121 @@
+//! Windows services module.
+//!
+//! Provides read-only access to installed Windows services via WMI.
+
+#[cfg(all(windows, feature = "local"))]
+use crate::WmiContext;
+use serde::{Deserialize, Serialize};
+#[cfg(all(windows, feature = "local"))]
+use wmi::WMIConnection;
+
+/// Installed Windows service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowsService {
+    /// Service name (e.g., "W3SVC")
+    pub name: String,
+    /// Display name
+    pub display_name: Option<String>,
+    /// Current state (e.g., "Running", "Stopped")
+    pub state: Option<String>,
+    /// Start mode (e.g., "Auto", "Manual", "Disabled")
+    pub start_mode: Option<String>,
+    /// Account the service runs as
+    pub account: Option<String>,
+    /// Command line of the service binary
+    pub path: Option<String>,
+}
+
+/// WMI result struct for Win32_Service.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Win32Service {
+    name: Option<String>,
+    display_name: Option<String>,
+    state: Option<String>,
+    start_mode: Option<String>,
+    start_name: Option<String>,
+    path_name: Option<String>,
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl WindowsService {
+    /// Collect all installed services (READ-ONLY).
+    ///
+    /// Returns empty vec if WMI query fails (graceful degradation).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::WindowsService;
+    ///
+    /// for service in WindowsService::collect_all() {
+    ///     println!("{}: {:?}", service.name, service.state);
+    /// }
+    /// ```
+    pub fn collect_all() -> Vec<Self> {
+        tracing::info!("Collecting Windows services");
+        match Self::try_collect() {
+            Ok(services) => {
+                tracing::debug!("Found {} services", services.len());
+                services
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not query Windows services");
+                Vec::new()
+            }
+        }
+    }
+
+    #[tracing::instrument(fields(items = tracing::field::Empty))]
+    pub(crate) fn try_collect() -> Result<Vec<Self>, crate::Error> {
+        let services = WmiContext::with_current(Self::collect_with_wmi)?;
+        tracing::Span::current().record("items", services.len());
+        Ok(services)
+    }
+
+    /// Installed services, queried over an open WMI context.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if the query fails.
+    pub fn collect_with_wmi(wmi: &WmiContext) -> Result<Vec<Self>, crate::Error> {
+        Self::query(wmi.cimv2()?)
+    }
+
+    /// Query `Win32_Service` on an open (local or remote) WMI connection.
+    pub(crate) fn query(wmi_con: &WMIConnection) -> Result<Vec<Self>, crate::Error> {
+        let results: Vec<Win32Service> = wmi_con.query()?;
+        Ok(results.into_iter().filter_map(Self::from_wmi).collect())
+    }
+
+    /// Query only the services with these names.
+    pub(crate) fn query_named(
+        wmi_con: &WMIConnection,
+        names: &[&str],
+    ) -> Result<Vec<Self>, crate::Error> {
+        let filter = names
+            .iter()
+            .map(|name| format!("Name = '{}'", name))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let results: Vec<Win32Service> = wmi_con.raw_query(format!(
+            "SELECT Name, DisplayName, State, StartMode, StartName, PathName \
+             FROM Win32_Service WHERE {}",
+            filter
+        ))?;
+        Ok(results.into_iter().filter_map(Self::from_wmi).collect())
+    }
+
+    fn from_wmi(r: Win32Service) -> Option<Self> {
+        let name = r.name.filter(|s| !s.trim().is_empty())?;
+        Some(WindowsService {
+            name,
+            display_name: r.display_name.filter(|s| !s.is_empty()),
+            state: r.state.filter(|s| !s.is_empty()),
+            start_mode: r.start_mode.filter(|s| !s.is_empty()),
+            account: r.start_name.filter(|s| !s.is_empty()),
+            path: r.path_name.filter(|s| !s.is_empty()),
+        })
+    }
+}
@@ -0,0 +1,264 @@
+//! Correlation of installed cumulative updates with the OS build.
+//!
+//! Each monthly latest cumulative update (LCU) moves a release to a known
+//! update build revision (UBR). A host whose build says it reached that
+//! revision but whose update list lacks the matching KB, or that lists a
+//! KB for a later revision than its build, was likely restored from an
+//! old image or failed servicing.
+//!
+//! The bundled table covers the monthly security releases of current
+//! client and server builds; revisions outside it are reported as
+//! [`ServicingStatus::Unknown`] rather than guessed.
+
+use crate::WindowsUpdate;
+use chrono::NaiveDate;
+
+/// A date as `(year, month, day)`.
+type Ymd = (i32, u32, u32);
+
+/// `(build, UBR, KB, release date)` of monthly cumulative updates.
+const CUMULATIVE_UPDATES: &[(u32, u32, &str, Ymd)] = &[
+    // Windows Server 2019 / Windows 10 1809
+    (17763, 5329, "KB5034127", (2024, 1, 9)),
+    (17763, 5458, "KB5034768", (2024, 2, 13)),
+    (17763, 5576, "KB5035849", (2024, 3, 12)),
+    (17763, 5696, "KB5036896", (2024, 4, 9)),
+    (17763, 5820, "KB5037765", (2024, 5, 14)),
+    (17763, 5936, "KB5039217", (2024, 6, 11)),
+    (17763, 6054, "KB5040430", (2024, 7, 9)),
+    (17763, 6189, "KB5041578", (2024, 8, 13)),
+    (17763, 6293, "KB5043050", (2024, 9, 10)),
+    (17763, 6414, "KB5044277", (2024, 10, 8)),
+    (17763, 6532, "KB5046615", (2024, 11, 12)),
+    (17763, 6659, "KB5048661", (2024, 12, 10)),
+    // Windows 10 21H2 / 22H2 share their cumulative updates
+    (19044, 3930, "KB5034122", (2024, 1, 9)),
+    (19044, 4046, "KB5034763", (2024, 2, 13)),
+    (19044, 4170, "KB5035845", (2024, 3, 12)),
+    (19044, 4291, "KB5036892", (2024, 4, 9)),
+    (19044, 4412, "KB5037768", (2024, 5, 14)),
+    (19044, 4529, "KB5039211", (2024, 6, 11)),
+    (19044, 4651, "KB5040427", (2024, 7, 9)),
+    (19044, 4780, "KB5041580", (2024, 8, 13)),
+    (19044, 4894, "KB5043064", (2024, 9, 10)),
+    (19044, 5011, "KB5044273", (2024, 10, 8)),
+    (19044, 5131, "KB5046613", (2024, 11, 12)),
+    (19044, 5247, "KB5048652", (2024, 12, 10)),
+    (19045, 3930, "KB5034122", (2024, 1, 9)),
+    (19045, 4046, "KB5034763", (2024, 2, 13)),
+    (19045, 4170, "KB5035845", (2024, 3, 12)),
+    (19045, 4291, "KB5036892", (2024, 4, 9)),
+    (19045, 4412, "KB5037768", (2024, 5, 14)),
+    (19045, 4529, "KB5039211", (2024, 6, 11)),
+    (19045, 4651, "KB5040427", (2024, 7, 9)),
+    (19045, 4780, "KB5041580", (2024, 8, 13)),
+    (19045, 4894, "KB5043064", (2024, 9, 10)),
+    (19045, 5011, "KB5044273", (2024, 10, 8)),
+    (19045, 5131, "KB5046613", (2024, 11, 12)),
+    (19045, 5247, "KB5048652", (2024, 12, 10)),
+    // Windows 11 22H2 / 23H2 share their cumulative updates
+    (22621, 3007, "KB5034123", (2024, 1, 9)),
+    (22621, 3155, "KB5034765", (2024, 2, 13)),
+    (22621, 3296, "KB5035853", (2024, 3, 12)),
+    (22621, 3447, "KB5036893", (2024, 4, 9)),
+    (22621, 3593, "KB5037771", (2024, 5, 14)),
+    (22621, 3737, "KB5039212", (2024, 6, 11)),
+    (22621, 3880, "KB5040442", (2024, 7, 9)),
+    (22621, 4037, "KB5041585", (2024, 8, 13)),
+    (22621, 4169, "KB5043076", (2024, 9, 10)),
+    (22621, 4317, "KB5044285", (2024, 10, 8)),
+    (22631, 3007, "KB5034123", (2024, 1, 9)),
+    (22631, 3155, "KB5034765", (2024, 2, 13)),
+    (22631, 3296, "KB5035853", (2024, 3, 12)),
+    (22631, 3447, "KB5036893", (2024, 4, 9)),
+    (22631, 3593, "KB5037771", (2024, 5, 14)),
+    (22631, 3737, "KB5039212", (2024, 6, 11)),
+    (22631, 3880, "KB5040442", (2024, 7, 9)),
+    (22631, 4037, "KB5041585", (2024, 8, 13)),
+    (22631, 4169, "KB5043076", (2024, 9, 10)),
+    (22631, 4317, "KB5044285", (2024, 10, 8)),
+    (22631, 4460, "KB5046633", (2024, 11, 12)),
+    (22631, 4602, "KB5048685", (2024, 12, 10)),
+    // Windows 11 24H2 / Windows Server 2025
+    (26100, 1742, "KB5043080", (2024, 9, 10)),
+    (26100, 2033, "KB5044284", (2024, 10, 8)),
+    (26100, 2314, "KB5046617", (2024, 11, 12)),
+    (26100, 2605, "KB5048667", (2024, 12, 10)),
+];
+
+/// How a host's installed updates line up with its OS build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServicingStatus {
+    /// The cumulative update for the build's revision is installed
+    Consistent {
+        /// KB of that update
+        kb: String,
+    },
+    /// The build reports a revision whose cumulative update is not
+    /// installed
+    MissingUpdate {
+        /// Build with UBR (e.g. "22631.3880")
+        build: String,
+        /// KB that produces that revision
+        kb: String,
+        /// Release date of that KB
+        released: NaiveDate,
+    },
+    /// A cumulative update for a later revision is installed, but the
+    /// build never reached it
+    BuildBehind {
+        /// Build with UBR (e.g. "22631.3007")
+        build: String,
+        /// Installed KB for the later revision
+        kb: String,
+        /// Revision that KB moves the build to
+        expected_ubr: u32,
+    },
+    /// The build or revision is not in the bundled table
+    Unknown,
+}
+
+impl ServicingStatus {
+    /// Correlate a build with UBR (`22631.3880`, `10.0.22631.3880`) with
+    /// the installed updates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sysaudit::servicing::ServicingStatus;
+    ///
+    /// let status = ServicingStatus::evaluate("22631.3880", &[]);
+    /// assert!(status.is_inconsistent());
+    /// ```
+    pub fn evaluate(build: &str, updates: &[WindowsUpdate]) -> Self {
+        let Some((number, ubr)) = parse_build(build) else {
+            return ServicingStatus::Unknown;
+        };
+        let installed = |kb: &str| {
+            updates
+                .iter()
+                .any(|u| u.hotfix_id.trim().eq_ignore_ascii_case(kb))
+        };
+        let releases = CUMULATIVE_UPDATES.iter().filter(|(b, ..)| *b == number);
+
+        // Latest installed LCU that the build has not caught up with
+        let ahead = releases
+            .clone()
+            .filter(|(_, u, kb, _)| *u > ubr && installed(kb))
+            .max_by_key(|(_, u, ..)| *u);
+        if let Some((_, expected_ubr, kb, _)) = ahead {
+            return ServicingStatus::BuildBehind {
+                build: format!("{}.{}", number, ubr),
+                kb: kb.to_string(),
+                expected_ubr: *expected_ubr,
+            };
+        }
+
+        // Previews and out-of-band releases land between the monthly
+        // revisions; only an exact match names the expected KB.
+        match releases.clone().find(|(_, u, ..)| *u == ubr) {
+            Some((_, _, kb, _)) if installed(kb) => {
+                ServicingStatus::Consistent { kb: kb.to_string() }
+            }
+            Some((_, _, kb, (y, m, d))) => match NaiveDate::from_ymd_opt(*y, *m, *d) {
+                Some(released) => ServicingStatus::MissingUpdate {
+                    build: format!("{}.{}", number, ubr),
+                    kb: kb.to_string(),
+                    released,
+                },
+                None => ServicingStatus::Unknown,
+            },
+            None => ServicingStatus::Unknown,
+        }
+    }
+
+    /// Whether the build and the installed updates disagree.
+    pub fn is_inconsistent(&self) -> bool {
+        matches!(
+            self,
+            ServicingStatus::MissingUpdate { .. } | ServicingStatus::BuildBehind { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for ServicingStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServicingStatus::Consistent { kb } => write!(f, "Consistent ({})", kb),
+            ServicingStatus::MissingUpdate { build, kb, .. } => {
+                write!(f, "Build {} but {} is not installed", build, kb)
+            }
+            ServicingStatus::BuildBehind {
+                build,
+                kb,
+                expected_ubr,
+            } => write!(
+                f,
+                "{} is installed but build {} is below revision {}",
+                kb, build, expected_ubr
+            ),
+            ServicingStatus::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// NT build and UBR from the last two numbers of a version string.
+fn parse_build(build: &str) -> Option<(u32, u32)> {
+    let mut parts = build.trim().rsplit('.');
+    let ubr = parts.next()?.parse().ok()?;
+    let number: u32 = parts.next()?.parse().ok()?;
+    (7600..100_000).contains(&number).then_some((number, ubr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(kb: &str) -> WindowsUpdate {
+        WindowsUpdate {
+            hotfix_id: kb.into(),
+            description: Some("Security Update".into()),
+            installed_on: None,
+            installed_by: None,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_servicing() {
+        assert_eq!(
+            ServicingStatus::evaluate("22631.3880", &[update("KB5040442")]),
+            ServicingStatus::Consistent {
+                kb: "KB5040442".into()
+            }
+        );
+        assert_eq!(
+            ServicingStatus::evaluate("10.0.19045.4651", &[update("KB5034122")]),
+            ServicingStatus::MissingUpdate {
+                build: "19045.4651".into(),
+                kb: "KB5040427".into(),
+                released: NaiveDate::from_ymd_opt(2024, 7, 9).unwrap(),
+            }
+        );
+        assert_eq!(
+            ServicingStatus::evaluate("22631.3007", &[update("kb5040442")]),
+            ServicingStatus::BuildBehind {
+                build: "22631.3007".into(),
+                kb: "KB5040442".into(),
+                expected_ubr: 3880,
+            }
+        );
+        // Preview revision, unknown build, no UBR
+        assert_eq!(
+            ServicingStatus::evaluate("22631.3958", &[]),
+            ServicingStatus::Unknown
+        );
+        assert_eq!(
+            ServicingStatus::evaluate("9999.1", &[]),
+            ServicingStatus::Unknown
+        );
+        assert_eq!(
+            ServicingStatus::evaluate("22631", &[]),
+            ServicingStatus::Unknown
+        );
+    }
+}
@@ -2,11 +2,29 @@
 //!
 //! Provides read-only access to installed software from Windows Registry.
 
-use crate::Error;
+use crate::lifecycle::{SoftwareLifecycle, SupportStatus};
+#[cfg(all(windows, feature = "local"))]
+use crate::{Error, cancel};
 use chrono::NaiveDate;
+#[cfg(all(windows, feature = "local"))]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
+#[cfg(all(windows, feature = "local"))]
 use windows_registry::{CURRENT_USER, Key, LOCAL_MACHINE};
+#[cfg(all(windows, feature = "local"))]
+use windows_result::HRESULT;
+
+/// Win32 `ERROR_FILE_NOT_FOUND`, returned when a registry key is absent.
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const ERROR_FILE_NOT_FOUND: u32 = 2;
+
+#[cfg(all(windows, feature = "local"))]
+const UNINSTALL_KEY: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall";
+#[cfg(all(windows, feature = "local"))]
+const UNINSTALL_KEY_32: &str = r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall";
 
 /// Registry source for software entry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +47,29 @@ impl std::fmt::Display for RegistrySource {
     }
 }
 
+/// Where a [`Software::install_date`] comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallDateSource {
+    /// The entry's `InstallDate` value
+    Exact,
+    /// Last-write time of the Uninstall key, when `InstallDate` is absent
+    KeyWriteTime,
+    /// No date known
+    #[default]
+    Unknown,
+}
+
+impl std::fmt::Display for InstallDateSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallDateSource::Exact => write!(f, "exact"),
+            InstallDateSource::KeyWriteTime => write!(f, "key write time"),
+            InstallDateSource::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
 /// Installed software entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Software {
@@ -40,16 +81,27 @@ pub struct Software {
     pub publisher: Option<String>,
     /// Installation date
     pub install_date: Option<NaiveDate>,
+    /// Where the installation date comes from
+    #[serde(default)]
+    pub install_date_source: InstallDateSource,
     /// Installation location
     pub install_location: Option<PathBuf>,
     /// Registry source
     pub source: RegistrySource,
+    /// Vendor support status, for products in the lifecycle dataset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub support_status: Option<SupportStatus>,
+    /// Vendor end-of-support date, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eol: Option<NaiveDate>,
 }
 
 /// Scanner for installed software.
 pub struct SoftwareScanner {
     include_user_installs: bool,
     include_32bit: bool,
+    cancel: Option<CancellationToken>,
+    lifecycle: Option<SoftwareLifecycle>,
 }
 
 impl Default for SoftwareScanner {
@@ -64,6 +116,8 @@ impl SoftwareScanner {
         SoftwareScanner {
             include_user_installs: true,
             include_32bit: true,
+            cancel: None,
+            lifecycle: None,
         }
     }
 
@@ -79,6 +133,22 @@ impl SoftwareScanner {
         self
     }
 
+    /// Stop between registry keys once `cancel` is triggered.
+    pub fn cancel_token(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Evaluate end of life against `lifecycle` instead of the bundled
+    /// dataset (see [`SoftwareLifecycle::load`]).
+    pub fn lifecycle(mut self, lifecycle: SoftwareLifecycle) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl SoftwareScanner {
     /// Scan for installed software (READ-ONLY).
     ///
     /// # Example
@@ -95,46 +165,109 @@ impl SoftwareScanner {
     ///
     /// # Errors
     ///
-    /// Returns [`Error`] if the Windows registry cannot be opened.
+    /// Returns [`Error`] if the Windows registry cannot be opened, or
+    /// [`Error::Cancelled`] if the scan was cancelled.
     pub fn scan(&self) -> Result<Vec<Software>, Error> {
+        self.scan_with_warnings().map(|(software, _)| software)
+    }
+
+    /// [`scan`](Self::scan), also describing Uninstall keys that exist but
+    /// could not be read.
+    #[tracing::instrument(skip(self), fields(items = tracing::field::Empty))]
+    pub(crate) fn scan_with_warnings(&self) -> Result<(Vec<Software>, Vec<String>), Error> {
         tracing::info!("Starting software scan");
         let mut result = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (root, path, source) in self.hives() {
+            match self.scan_key(root, path, source) {
+                Ok(software) => result.extend(software),
+                // No per-user installs, or no 32-bit view: nothing to report.
+                Err(Error::Registry(e))
+                    if e.code() == HRESULT::from_win32(ERROR_FILE_NOT_FOUND) => {}
+                Err(e) => {
+                    tracing::warn!(?source, error = %e, "Could not read Uninstall key");
+                    warnings.push(format!("could not read {:?} Uninstall key: {}", source, e));
+                }
+            }
+        }
 
-        // HKLM 64-bit
-        if let Ok(software) = self.scan_key(
-            LOCAL_MACHINE,
-            r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
-            RegistrySource::LocalMachine64,
-        ) {
-            result.extend(software);
+        cancel::check(self.cancel.as_ref())?;
+
+        // Sort by name, lowercasing each name once rather than per
+        // comparison.
+        result.sort_by_cached_key(|sw| sw.name.to_lowercase());
+
+        annotate_support(
+            &mut result,
+            self.lifecycle
+                .as_ref()
+                .unwrap_or_else(SoftwareLifecycle::bundled),
+            chrono::Utc::now().date_naive(),
+        );
+
+        tracing::Span::current().record("items", result.len());
+        Ok((result, warnings))
+    }
+
+    /// Iterate installed software as registry keys are read, without
+    /// collecting or sorting the full list.
+    ///
+    /// Entries arrive in registry order, one hive after another, already
+    /// annotated with their support status. An Uninstall key that exists but
+    /// cannot be read yields an error and iteration moves on to the next
+    /// hive; once the scan is cancelled, [`Error::Cancelled`] is yielded
+    /// and iteration ends.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SoftwareScanner;
+    ///
+    /// for sw in SoftwareScanner::new().scan_iter() {
+    ///     match sw {
+    ///         Ok(sw) => println!("{}", sw.name),
+    ///         Err(e) => eprintln!("{}", e),
+    ///     }
+    /// }
+    /// ```
+    pub fn scan_iter(&self) -> SoftwareIter<'_> {
+        SoftwareIter {
+            scanner: self,
+            hives: self.hives().into_iter(),
+            current: None,
+            today: chrono::Utc::now().date_naive(),
+            done: false,
         }
+    }
 
-        // HKLM 32-bit (WOW6432Node)
-        if self.include_32bit {
-            if let Ok(software) = self.scan_key(
+    /// Uninstall keys to read, per the scanner's settings.
+    fn hives(&self) -> Vec<(&'static Key, &'static str, RegistrySource)> {
+        let hives = [
+            (
+                true,
+                LOCAL_MACHINE,
+                UNINSTALL_KEY,
+                RegistrySource::LocalMachine64,
+            ),
+            (
+                self.include_32bit,
                 LOCAL_MACHINE,
-                r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+                UNINSTALL_KEY_32,
                 RegistrySource::LocalMachine32,
-            ) {
-                result.extend(software);
-            }
-        }
-
-        // HKCU
-        if self.include_user_installs {
-            if let Ok(software) = self.scan_key(
+            ),
+            (
+                self.include_user_installs,
                 CURRENT_USER,
-                r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+                UNINSTALL_KEY,
                 RegistrySource::CurrentUser,
-            ) {
-                result.extend(software);
-            }
-        }
-
-        // Sort by name
-        result.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-
-        Ok(result)
+            ),
+        ];
+        hives
+            .into_iter()
+            .filter(|(enabled, ..)| *enabled)
+            .map(|(_, root, path, source)| (root, path, source))
+            .collect()
     }
 
     fn scan_key(
@@ -144,17 +277,28 @@ impl SoftwareScanner {
         source: RegistrySource,
     ) -> Result<Vec<Software>, Error> {
         let key = root.open(path)?;
-        let mut result = Vec::new();
-
-        for subkey_name in key.keys()? {
-            if let Ok(subkey) = key.open(&subkey_name) {
-                if let Some(software) = self.parse_software_key(&subkey, source) {
-                    result.push(software);
+        // Enumerate first, so the parallel pass knows its length.
+        let subkey_names: Vec<String> = key.keys()?.collect();
+
+        // Thousands of entries on engineering workstations and terminal
+        // servers: read them in parallel, each into its own slot of a
+        // vector sized up front.
+        let mut parsed = Vec::with_capacity(subkey_names.len());
+        subkey_names
+            .par_iter()
+            .map(|subkey_name| {
+                if cancel::is_cancelled(self.cancel.as_ref()) {
+                    return None;
                 }
-            }
-        }
-
-        Ok(result)
+                let subkey = key.open(subkey_name).ok()?;
+                self.parse_software_key(&subkey, source)
+            })
+            .collect_into_vec(&mut parsed);
+
+        // Keys without a DisplayName (patches, components) leave gaps.
+        let mut software = Vec::with_capacity(parsed.iter().filter(|p| p.is_some()).count());
+        software.extend(parsed.into_iter().flatten());
+        Ok(software)
     }
 
     fn parse_software_key(&self, key: &Key, source: RegistrySource) -> Option<Software> {
@@ -170,18 +314,147 @@ impl SoftwareScanner {
             publisher,
             install_location,
             install_date_str,
+            key_write_date(key),
             source,
         )
     }
 }
 
+/// Lazy iterator over installed software, see
+/// [`SoftwareScanner::scan_iter`].
+#[cfg(all(windows, feature = "local"))]
+pub struct SoftwareIter<'a> {
+    scanner: &'a SoftwareScanner,
+    hives: std::vec::IntoIter<(&'static Key, &'static str, RegistrySource)>,
+    /// Open Uninstall key and its remaining subkey names
+    current: Option<(Key, std::vec::IntoIter<String>, RegistrySource)>,
+    today: NaiveDate,
+    done: bool,
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl SoftwareIter<'_> {
+    /// Open the next enabled hive's Uninstall key, skipping absent ones.
+    fn open_next_hive(&mut self) -> Option<Result<(), Error>> {
+        let (root, path, source) = self.hives.next()?;
+        let opened = root
+            .open(path)
+            .and_then(|key| Ok((key.keys()?.collect::<Vec<_>>(), key)));
+        Some(match opened {
+            Ok((names, key)) => {
+                self.current = Some((key, names.into_iter(), source));
+                Ok(())
+            }
+            Err(e) if e.code() == HRESULT::from_win32(ERROR_FILE_NOT_FOUND) => Ok(()),
+            Err(e) => {
+                tracing::warn!(?source, error = %e, "Could not read Uninstall key");
+                Err(Error::General(format!(
+                    "could not read {:?} Uninstall key: {}",
+                    source, e
+                )))
+            }
+        })
+    }
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl Iterator for SoftwareIter<'_> {
+    type Item = Result<Software, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if cancel::is_cancelled(self.scanner.cancel.as_ref()) {
+                self.done = true;
+                return Some(Err(Error::Cancelled));
+            }
+            let Some((key, names, source)) = self.current.as_mut() else {
+                match self.open_next_hive() {
+                    Some(Ok(())) => continue,
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => {
+                        self.done = true;
+                        return None;
+                    }
+                }
+            };
+            let Some(name) = names.next() else {
+                self.current = None;
+                continue;
+            };
+            let Some(mut sw) = key
+                .open(&name)
+                .ok()
+                .and_then(|subkey| self.scanner.parse_software_key(&subkey, *source))
+            else {
+                continue;
+            };
+            annotate_support(
+                std::slice::from_mut(&mut sw),
+                self.scanner
+                    .lifecycle
+                    .as_ref()
+                    .unwrap_or_else(SoftwareLifecycle::bundled),
+                self.today,
+            );
+            return Some(Ok(sw));
+        }
+    }
+}
+
+/// Day the key was last written, from `RegQueryInfoKeyW`.
+#[cfg(all(windows, feature = "local"))]
+fn key_write_date(key: &Key) -> Option<NaiveDate> {
+    use windows::Win32::Foundation::{ERROR_SUCCESS, FILETIME};
+    use windows::Win32::System::Registry::{HKEY, RegQueryInfoKeyW};
+
+    let mut written = FILETIME::default();
+    // SAFETY: the handle is open for the lifetime of `key`, and only the
+    // last-write time is requested, into a valid FILETIME.
+    let status = unsafe {
+        RegQueryInfoKeyW(
+            HKEY(key.as_raw()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(&mut written),
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+    filetime_date((u64::from(written.dwHighDateTime) << 32) | u64::from(written.dwLowDateTime))
+}
+
+/// Date of a FILETIME (100 ns intervals since 1601-01-01 UTC).
+#[cfg(any(all(windows, feature = "local"), test))]
+fn filetime_date(filetime: u64) -> Option<NaiveDate> {
+    const UNIX_EPOCH_OFFSET_SECS: i64 = 11_644_473_600;
+    let secs = i64::try_from(filetime / 10_000_000).ok()? - UNIX_EPOCH_OFFSET_SECS;
+    chrono::DateTime::from_timestamp(secs, 0).map(|t| t.date_naive())
+}
+
 /// Pure construction logic for software entry (fully testable).
-fn build_software(
+///
+/// `key_written` is used as the install date when `install_date_str` is
+/// missing or unparsable.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn build_software(
     name: String,
     version: Option<String>,
     publisher: Option<String>,
     install_location: Option<String>,
     install_date_str: Option<String>,
+    key_written: Option<NaiveDate>,
     source: RegistrySource,
 ) -> Option<Software> {
     if name.trim().is_empty() {
@@ -191,19 +464,45 @@ fn build_software(
     let install_location = install_location
         .filter(|s| !s.is_empty())
         .map(PathBuf::from);
-    let install_date = install_date_str.and_then(|s| parse_install_date(&s));
+    let (install_date, install_date_source) =
+        match install_date_str.and_then(|s| parse_install_date(&s)) {
+            Some(date) => (Some(date), InstallDateSource::Exact),
+            None => match key_written {
+                Some(date) => (Some(date), InstallDateSource::KeyWriteTime),
+                None => (None, InstallDateSource::Unknown),
+            },
+        };
 
     Some(Software {
         name,
         version,
         publisher,
         install_date,
+        install_date_source,
         install_location,
         source,
+        support_status: None,
+        eol: None,
     })
 }
 
+/// Fill in the support status and end-of-life date of each entry.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn annotate_support(
+    software: &mut [Software],
+    lifecycle: &SoftwareLifecycle,
+    on: NaiveDate,
+) {
+    for sw in software {
+        if let Some(support) = lifecycle.lookup(&sw.name, sw.version.as_deref(), on) {
+            sw.support_status = Some(support.status);
+            sw.eol = support.eol;
+        }
+    }
+}
+
 /// Parse install date from registry format (YYYYMMDD).
+#[cfg(any(all(windows, feature = "local"), test))]
 fn parse_install_date(s: &str) -> Option<NaiveDate> {
     if s.len() != 8 {
         return None;
@@ -216,6 +515,30 @@ fn parse_install_date(s: &str) -> Option<NaiveDate> {
     NaiveDate::from_ymd_opt(year, month, day)
 }
 
+/// Compare dotted versions numerically segment by segment
+/// ("10.2" > "9.12", "1.0" == "1.0.0").
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    let segments = |v: &str| -> Vec<String> {
+        v.split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|s| !s.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (segments(a), segments(b));
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).map_or("0", String::as_str);
+        let y = b.get(i).map_or("0", String::as_str);
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,6 +578,7 @@ mod tests {
             Some("Acme".into()),
             Some(r"C:\Acme".into()),
             Some("20240115".into()),
+            None,
             RegistrySource::LocalMachine64,
         );
         let sw = sw.unwrap();
@@ -274,6 +598,7 @@ mod tests {
             None,
             None,
             None,
+            None,
             RegistrySource::CurrentUser,
         );
         assert!(sw.is_none());
@@ -287,6 +612,7 @@ mod tests {
             None,
             None,
             None,
+            None,
             RegistrySource::LocalMachine32,
         );
         assert!(sw.is_none());
@@ -300,11 +626,36 @@ mod tests {
             None,
             Some("".into()), // empty string
             None,
+            None,
             RegistrySource::LocalMachine64,
         );
         assert!(sw.unwrap().install_location.is_none());
     }
 
+    #[test]
+    fn test_annotate_support() {
+        let mut software: Vec<Software> =
+            [("Python 3.7.9 (64-bit)", "3.7.9150.0"), ("Test App", "2.0")]
+                .into_iter()
+                .filter_map(|(name, version)| {
+                    build_software(
+                        name.into(),
+                        Some(version.into()),
+                        None,
+                        None,
+                        None,
+                        None,
+                        RegistrySource::LocalMachine64,
+                    )
+                })
+                .collect();
+        let on = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        annotate_support(&mut software, SoftwareLifecycle::bundled(), on);
+        assert_eq!(software[0].support_status, Some(SupportStatus::EndOfLife));
+        assert_eq!(software[0].eol, NaiveDate::from_ymd_opt(2023, 6, 27));
+        assert_eq!(software[1].support_status, None);
+    }
+
     #[test]
     fn test_build_software_invalid_date_ignored() {
         let sw = build_software(
@@ -313,8 +664,44 @@ mod tests {
             None,
             None,
             Some("not-a-date".into()),
+            None,
             RegistrySource::LocalMachine64,
         );
         assert!(sw.unwrap().install_date.is_none());
     }
+
+    #[test]
+    fn test_build_software_key_write_time_fallback() {
+        let written = NaiveDate::from_ymd_opt(2023, 11, 2);
+        let build = |date: Option<&str>, written| {
+            build_software(
+                "App".into(),
+                None,
+                None,
+                None,
+                date.map(Into::into),
+                written,
+                RegistrySource::LocalMachine64,
+            )
+            .unwrap()
+        };
+
+        let exact = build(Some("20240115"), written);
+        assert_eq!(exact.install_date, NaiveDate::from_ymd_opt(2024, 1, 15));
+        assert_eq!(exact.install_date_source, InstallDateSource::Exact);
+
+        let fallback = build(Some("not-a-date"), written);
+        assert_eq!(fallback.install_date, written);
+        assert_eq!(
+            fallback.install_date_source,
+            InstallDateSource::KeyWriteTime
+        );
+
+        let unknown = build(None, None);
+        assert_eq!(unknown.install_date, None);
+        assert_eq!(unknown.install_date_source, InstallDateSource::Unknown);
+
+        // 2023-11-02 12:00:00 UTC
+        assert_eq!(filetime_date(133_434_000_000_000_000), written);
+    }
 }
@@ -2,10 +2,16 @@
 //!
 //! Provides read-only access to OS, CPU, and network information.
 
-use crate::Error;
+use crate::AssetFingerprint;
+#[cfg(all(windows, feature = "local"))]
+use crate::{Error, WmiContext};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::net::IpAddr;
+#[cfg(all(windows, feature = "local"))]
 use sysinfo::System;
+#[cfg(all(windows, feature = "local"))]
 use windows_registry::LOCAL_MACHINE;
 
 /// Network interface information.
@@ -21,6 +27,341 @@ pub struct NetworkInterface {
     pub gateway: Option<String>,
     /// MAC address
     pub mac_address: Option<String>,
+    /// The interface's other addresses, when collected with
+    /// [`NetworkOptions::group_by_interface`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_addresses: Vec<InterfaceAddress>,
+}
+
+impl NetworkInterface {
+    /// Every address of the interface, the primary one first.
+    pub fn ip_addresses(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        std::iter::once(self.ip_address).chain(
+            self.additional_addresses
+                .iter()
+                .map(|address| address.ip_address),
+        )
+    }
+
+    /// The interface's IPv4 addresses.
+    pub fn ipv4_addresses(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        self.ip_addresses().filter(IpAddr::is_ipv4)
+    }
+
+    /// The interface's IPv6 addresses.
+    pub fn ipv6_addresses(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        self.ip_addresses().filter(IpAddr::is_ipv6)
+    }
+
+    /// Prefix length of the primary address, read from `subnet_mask` as
+    /// written locally (`/24`) or by WMI (`24`, `255.255.255.0`).
+    pub fn prefix(&self) -> Option<u8> {
+        let mask = self.subnet_mask.as_deref()?.trim_start_matches('/');
+        if let Ok(prefix) = mask.parse::<u8>() {
+            return Some(prefix);
+        }
+        let mask = u32::from(mask.parse::<std::net::Ipv4Addr>().ok()?);
+        (mask.leading_ones() + mask.trailing_zeros() == 32).then(|| mask.leading_ones() as u8)
+    }
+
+    /// Every address with its prefix (`192.168.1.10/24`), the primary one
+    /// first.
+    pub fn address_list(&self) -> Vec<String> {
+        let primary = match self.prefix() {
+            Some(prefix) => format!("{}/{}", self.ip_address, prefix),
+            None => self.ip_address.to_string(),
+        };
+        std::iter::once(primary)
+            .chain(self.additional_addresses.iter().map(ToString::to_string))
+            .collect()
+    }
+}
+
+/// An address of a network interface with its prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceAddress {
+    /// IP address
+    pub ip_address: IpAddr,
+    /// Prefix length (e.g., 24)
+    pub prefix: u8,
+}
+
+impl fmt::Display for InterfaceAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.ip_address, self.prefix)
+    }
+}
+
+/// Which adapters and addresses [`SystemInfo::collect_with`] reports.
+///
+/// By default every address of every adapter is listed, one entry per
+/// address. [`compact`](Self::compact) drops link-local, loopback and
+/// disconnected adapters and lists each adapter once.
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::NetworkOptions;
+///
+/// let options = NetworkOptions::compact().ipv6(false);
+/// assert!(options.is_grouped());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkOptions {
+    link_local: bool,
+    loopback: bool,
+    disconnected: bool,
+    ipv4: bool,
+    ipv6: bool,
+    group_by_interface: bool,
+    reverse_dns: bool,
+}
+
+impl Default for NetworkOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetworkOptions {
+    /// Every address of every adapter, one entry per address.
+    pub fn new() -> Self {
+        NetworkOptions {
+            link_local: true,
+            loopback: true,
+            disconnected: true,
+            ipv4: true,
+            ipv6: true,
+            group_by_interface: false,
+            reverse_dns: false,
+        }
+    }
+
+    /// Routable addresses of connected adapters, one entry per adapter.
+    pub fn compact() -> Self {
+        Self::new()
+            .link_local(false)
+            .loopback(false)
+            .disconnected(false)
+            .group_by_interface(true)
+    }
+
+    /// Include link-local addresses (IPv4 APIPA `169.254.0.0/16`, IPv6
+    /// `fe80::/10`).
+    pub fn link_local(mut self, include: bool) -> Self {
+        self.link_local = include;
+        self
+    }
+
+    /// Include loopback addresses.
+    pub fn loopback(mut self, include: bool) -> Self {
+        self.loopback = include;
+        self
+    }
+
+    /// Include adapters that have not sent or received anything since
+    /// boot (unplugged or disabled).
+    pub fn disconnected(mut self, include: bool) -> Self {
+        self.disconnected = include;
+        self
+    }
+
+    /// Include IPv4 addresses.
+    pub fn ipv4(mut self, include: bool) -> Self {
+        self.ipv4 = include;
+        self
+    }
+
+    /// Include IPv6 addresses.
+    pub fn ipv6(mut self, include: bool) -> Self {
+        self.ipv6 = include;
+        self
+    }
+
+    /// List each adapter once, with its other addresses in
+    /// [`NetworkInterface::additional_addresses`] (IPv4 first).
+    pub fn group_by_interface(mut self, group: bool) -> Self {
+        self.group_by_interface = group;
+        self
+    }
+
+    /// Look up the reverse-DNS name of each reported address into
+    /// [`HostIdentity::reverse_dns`]. Off by default: every lookup can wait
+    /// for a DNS timeout.
+    pub fn reverse_dns(mut self, lookup: bool) -> Self {
+        self.reverse_dns = lookup;
+        self
+    }
+
+    /// Whether adapters are listed once each.
+    pub fn is_grouped(&self) -> bool {
+        self.group_by_interface
+    }
+
+    /// Whether reported addresses are looked up in reverse DNS.
+    pub fn has_reverse_dns(&self) -> bool {
+        self.reverse_dns
+    }
+
+    #[cfg(any(all(windows, feature = "local"), test))]
+    fn keeps(&self, ip: IpAddr) -> bool {
+        let link_local = match ip {
+            IpAddr::V4(ip) => ip.is_link_local(),
+            IpAddr::V6(ip) => ip.is_unicast_link_local(),
+        };
+        (self.link_local || !link_local)
+            && (self.loopback || !ip.is_loopback())
+            && if ip.is_ipv4() { self.ipv4 } else { self.ipv6 }
+    }
+}
+
+/// An adapter as the OS reports it, before [`NetworkOptions`] apply.
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) struct Adapter {
+    pub(crate) name: String,
+    pub(crate) mac_address: Option<String>,
+    pub(crate) connected: bool,
+    pub(crate) addresses: Vec<InterfaceAddress>,
+}
+
+/// Filter and shape adapters into interface entries (fully testable).
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn select_interfaces(
+    adapters: Vec<Adapter>,
+    options: &NetworkOptions,
+) -> Vec<NetworkInterface> {
+    let entry = |adapter: &Adapter, address: InterfaceAddress| NetworkInterface {
+        name: adapter.name.clone(),
+        ip_address: address.ip_address,
+        subnet_mask: Some(format!("/{}", address.prefix)),
+        gateway: None, // Would need additional API calls
+        mac_address: adapter.mac_address.clone(),
+        additional_addresses: Vec::new(),
+    };
+
+    let mut interfaces = Vec::new();
+    for adapter in adapters
+        .iter()
+        .filter(|adapter| options.disconnected || adapter.connected)
+    {
+        let mut addresses: Vec<InterfaceAddress> = adapter
+            .addresses
+            .iter()
+            .copied()
+            .filter(|address| options.keeps(address.ip_address))
+            .collect();
+        if options.group_by_interface {
+            addresses.sort_by_key(|address| address.ip_address.is_ipv6());
+            let mut rest = addresses.into_iter();
+            if let Some(primary) = rest.next() {
+                let mut iface = entry(adapter, primary);
+                iface.additional_addresses = rest.collect();
+                interfaces.push(iface);
+            }
+        } else {
+            interfaces.extend(addresses.into_iter().map(|address| entry(adapter, address)));
+        }
+    }
+    interfaces
+}
+
+/// DNS identity of a host, which tells apart machines that share a
+/// NetBIOS name across domains, and the machine SID and installation time
+/// that give away hosts cloned from one image.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostIdentity {
+    /// Fully qualified domain name (e.g., "hmi01.plant.example.com")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fqdn: Option<String>,
+    /// Primary DNS suffix (e.g., "plant.example.com")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_suffix: Option<String>,
+    /// DNS suffix search list, in lookup order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns_search_list: Vec<String>,
+    /// Reverse-DNS names of the host's own addresses, when requested with
+    /// [`NetworkOptions::reverse_dns`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reverse_dns: Vec<ReverseDns>,
+    /// Machine SID (e.g., "S-1-5-21-1004336348-1177238915-682003330"),
+    /// the prefix of every local account's SID; images cloned without
+    /// Sysprep share it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_sid: Option<String>,
+    /// Time Windows was installed; cloned images keep the golden image's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_on: Option<DateTime<Utc>>,
+}
+
+/// Reverse-DNS (PTR) name of one of the host's addresses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReverseDns {
+    /// IP address
+    pub ip_address: IpAddr,
+    /// Name the address resolves to
+    pub name: String,
+}
+
+impl HostIdentity {
+    /// Identity of `host_name` under the primary suffix `dns_suffix`, with
+    /// the search list as stored in the registry (comma- or
+    /// space-separated).
+    #[cfg(any(all(windows, feature = "local"), test))]
+    pub(crate) fn new(
+        host_name: &str,
+        dns_suffix: Option<&str>,
+        search_list: Option<&str>,
+    ) -> Self {
+        let dns_suffix = dns_suffix
+            .map(|s| s.trim().trim_matches('.').to_lowercase())
+            .filter(|s| !s.is_empty());
+        let host = host_name.trim().trim_end_matches('.').to_lowercase();
+        // Without a suffix (workgroup hosts) there is no FQDN.
+        let fqdn = if host.contains('.') {
+            Some(host)
+        } else {
+            dns_suffix
+                .as_ref()
+                .filter(|_| !host.is_empty())
+                .map(|suffix| format!("{}.{}", host, suffix))
+        };
+        HostIdentity {
+            fqdn,
+            dns_suffix,
+            dns_search_list: search_list
+                .unwrap_or_default()
+                .split([',', ' '])
+                .map(|s| s.trim().trim_matches('.').to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            reverse_dns: Vec::new(),
+            machine_sid: None,
+            installed_on: None,
+        }
+    }
+}
+
+/// Machine SID from the SID of a local account: the account's SID without
+/// its relative ID. `None` for anything but a local account SID
+/// (`S-1-5-21-a-b-c-rid`).
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn machine_sid(account_sid: &str) -> Option<String> {
+    let account_sid = account_sid.trim().to_uppercase();
+    let (machine, rid) = account_sid.rsplit_once('-')?;
+    let parts: Vec<&str> = machine.split('-').collect();
+    let numeric = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    (parts.len() == 7
+        && parts[..4] == ["S", "1", "5", "21"]
+        && parts[4..].iter().all(|p| numeric(p))
+        && numeric(rid))
+    .then(|| machine.to_string())
+}
+
+/// Installation time from the `InstallDate` registry value (seconds since
+/// the Unix epoch).
+#[cfg(any(all(windows, feature = "local"), test))]
+pub(crate) fn install_time(seconds: u32) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(i64::from(seconds), 0).filter(|_| seconds > 0)
 }
 
 /// System information collected from the local machine.
@@ -36,6 +377,13 @@ pub struct SystemInfo {
     pub computer_name: String,
     /// Domain name if joined
     pub domain: Option<String>,
+    /// FQDN, DNS suffixes and reverse-DNS names
+    #[serde(default)]
+    pub identity: HostIdentity,
+    /// Machine GUID, BIOS serial and MAC addresses behind the report's
+    /// asset ID
+    #[serde(default)]
+    pub fingerprint: AssetFingerprint,
     /// CPU brand string (renamed from cpu_brand)
     pub cpu_info: String,
     /// Network interfaces with IP, mask, gateway
@@ -60,6 +408,7 @@ pub struct SystemInfo {
     pub memory_free: u64,
 }
 
+#[cfg(all(windows, feature = "local"))]
 impl SystemInfo {
     /// Collect system information (READ-ONLY).
     ///
@@ -77,6 +426,46 @@ impl SystemInfo {
     /// Returns [`Error`] if the Windows registry cannot be opened or read.
     #[tracing::instrument]
     pub fn collect() -> Result<Self, Error> {
+        Self::collect_with(NetworkOptions::new())
+    }
+
+    /// [`collect`](Self::collect), reporting network adapters as `network`
+    /// selects.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::{NetworkOptions, SystemInfo};
+    ///
+    /// let info = SystemInfo::collect_with(NetworkOptions::compact()).unwrap();
+    /// for iface in &info.network_interfaces {
+    ///     println!("{}: {}", iface.name, iface.address_list().join(", "));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As [`collect`](Self::collect).
+    pub fn collect_with(network: NetworkOptions) -> Result<Self, Error> {
+        match WmiContext::with_current(|wmi| Self::collect_from(Some(wmi), &network)) {
+            Err(Error::Wmi(e)) => {
+                tracing::warn!(error = %e, "COM init failed for system model info");
+                Self::collect_from(None, &network)
+            }
+            result => result,
+        }
+    }
+
+    /// [`collect`](Self::collect) over an open WMI context.
+    ///
+    /// # Errors
+    ///
+    /// As [`collect`](Self::collect).
+    pub fn collect_with_wmi(wmi: &WmiContext) -> Result<Self, Error> {
+        Self::collect_from(Some(wmi), &NetworkOptions::new())
+    }
+
+    fn collect_from(wmi: Option<&WmiContext>, network: &NetworkOptions) -> Result<Self, Error> {
         tracing::info!("Starting system information collection");
         let mut sys = System::new_all();
         sys.refresh_all();
@@ -110,10 +499,31 @@ impl SystemInfo {
         let memory_free = sys.free_memory();
 
         // Get Manufacturer/Model via WMI
-        let (manufacturer, model) = Self::get_system_model_info();
+        let (manufacturer, model) = wmi.map(Self::get_system_model_info).unwrap_or_default();
+
+        // Get network interfaces; the fingerprint takes every adapter's MAC,
+        // whatever the network options select
+        let adapters = Self::get_adapters();
+        let fingerprint = AssetFingerprint {
+            machine_guid: LOCAL_MACHINE
+                .open(r"SOFTWARE\Microsoft\Cryptography")
+                .and_then(|key| key.get_string("MachineGuid"))
+                .ok(),
+            bios_serial: wmi.and_then(Self::get_bios_serial),
+            mac_addresses: adapters
+                .iter()
+                .filter_map(|adapter| adapter.mac_address.clone())
+                .collect(),
+        };
+        let network_interfaces = select_interfaces(adapters, network);
 
-        // Get network interfaces
-        let network_interfaces = Self::get_network_interfaces();
+        // Get FQDN and DNS suffixes from registry, reverse DNS on request
+        let mut identity = Self::get_identity(&computer_name);
+        if network.has_reverse_dns() {
+            identity.reverse_dns = reverse_dns(&network_interfaces);
+        }
+        identity.machine_sid = wmi.and_then(Self::get_machine_sid);
+        identity.installed_on = Self::get_install_time();
 
         Ok(SystemInfo {
             os_name,
@@ -121,6 +531,8 @@ impl SystemInfo {
             build_number,
             computer_name,
             domain,
+            identity,
+            fingerprint,
             cpu_info,
             network_interfaces,
             manufacturer,
@@ -134,9 +546,8 @@ impl SystemInfo {
         })
     }
 
-    fn get_system_model_info() -> (Option<String>, Option<String>) {
+    fn get_system_model_info(wmi: &WmiContext) -> (Option<String>, Option<String>) {
         use serde::Deserialize;
-        use wmi::{COMLibrary, WMIConnection};
 
         #[derive(Deserialize)]
         #[serde(rename = "Win32_ComputerSystem")]
@@ -146,15 +557,7 @@ impl SystemInfo {
             model: Option<String>,
         }
 
-        let com_con = match COMLibrary::new() {
-            Ok(c) => c,
-            Err(e) => {
-                tracing::warn!(error = %e, "COM init failed for system model info");
-                return (None, None);
-            }
-        };
-
-        let wmi_con = match WMIConnection::new(com_con) {
+        let wmi_con = match wmi.cimv2() {
             Ok(c) => c,
             Err(e) => {
                 tracing::warn!(error = %e, "WMI connection failed for system model info");
@@ -177,6 +580,71 @@ impl SystemInfo {
         }
     }
 
+    fn get_bios_serial(wmi: &WmiContext) -> Option<String> {
+        use serde::Deserialize;
+
+        #[derive(Deserialize)]
+        #[serde(rename = "Win32_BIOS")]
+        #[serde(rename_all = "PascalCase")]
+        struct Win32Bios {
+            serial_number: Option<String>,
+        }
+
+        let serial = wmi
+            .cimv2()
+            .and_then(|wmi_con| wmi_con.query::<Win32Bios>().map_err(Error::from))
+            .map(|results| {
+                results
+                    .into_iter()
+                    .next()
+                    .and_then(|bios| bios.serial_number)
+            });
+        match serial {
+            Ok(serial) => serial,
+            Err(e) => {
+                tracing::warn!(error = %e, "WMI query failed for BIOS serial");
+                None
+            }
+        }
+    }
+
+    fn get_machine_sid(wmi: &WmiContext) -> Option<String> {
+        use serde::Deserialize;
+
+        #[derive(Deserialize)]
+        #[serde(rename = "Win32_UserAccount")]
+        struct Win32UserAccount {
+            #[serde(rename = "SID")]
+            sid: Option<String>,
+        }
+
+        let accounts = wmi.cimv2().and_then(|wmi_con| {
+            wmi_con
+                .raw_query::<Win32UserAccount>(
+                    "SELECT SID FROM Win32_UserAccount WHERE LocalAccount = TRUE",
+                )
+                .map_err(Error::from)
+        });
+        match accounts {
+            Ok(accounts) => accounts
+                .iter()
+                .filter_map(|account| account.sid.as_deref())
+                .find_map(machine_sid),
+            Err(e) => {
+                tracing::warn!(error = %e, "WMI query failed for machine SID");
+                None
+            }
+        }
+    }
+
+    fn get_install_time() -> Option<DateTime<Utc>> {
+        LOCAL_MACHINE
+            .open(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion")
+            .and_then(|key| key.get_u32("InstallDate"))
+            .ok()
+            .and_then(install_time)
+    }
+
     fn get_build_number() -> Result<String, Error> {
         let key = LOCAL_MACHINE.open(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion")?;
 
@@ -197,33 +665,117 @@ impl SystemInfo {
         key.get_string("Domain").ok().filter(|s| !s.is_empty())
     }
 
-    fn get_network_interfaces() -> Vec<NetworkInterface> {
+    fn get_identity(computer_name: &str) -> HostIdentity {
+        let Ok(key) = LOCAL_MACHINE.open(r"SYSTEM\CurrentControlSet\Services\Tcpip\Parameters")
+        else {
+            return HostIdentity::new(computer_name, None, None);
+        };
+        let value = |name| key.get_string(name).ok().filter(|s| !s.is_empty());
+        let host_name = value("Hostname").unwrap_or_else(|| computer_name.to_string());
+        // "NV Domain" is the configured primary suffix; "Domain" and
+        // "DhcpDomain" what is in effect when none is configured.
+        let suffix = value("NV Domain")
+            .or_else(|| value("Domain"))
+            .or_else(|| value("DhcpDomain"));
+        HostIdentity::new(
+            &host_name,
+            suffix.as_deref(),
+            value("SearchList").as_deref(),
+        )
+    }
+
+    fn get_adapters() -> Vec<Adapter> {
         use sysinfo::Networks;
 
         let networks = Networks::new_with_refreshed_list();
-        let mut interfaces = Vec::new();
-
-        for (name, network) in &networks {
-            for ip in network.ip_networks() {
+        networks
+            .iter()
+            .map(|(name, network)| {
                 // Format MAC address as hex (e.g., AC:B4:80:D6:59:1D)
                 let mac = network.mac_address();
                 let mac_str = format!(
                     "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
                     mac.0[0], mac.0[1], mac.0[2], mac.0[3], mac.0[4], mac.0[5]
                 );
-
-                interfaces.push(NetworkInterface {
+                Adapter {
                     name: name.clone(),
-                    ip_address: ip.addr,
-                    subnet_mask: Some(format!("/{}", ip.prefix)),
-                    gateway: None, // Would need additional API calls
                     mac_address: Some(mac_str),
-                });
-            }
+                    connected: network.total_received() > 0 || network.total_transmitted() > 0,
+                    addresses: network
+                        .ip_networks()
+                        .iter()
+                        .map(|ip| InterfaceAddress {
+                            ip_address: ip.addr,
+                            prefix: ip.prefix,
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reverse-DNS names of the interfaces' routable addresses.
+#[cfg(all(windows, feature = "local"))]
+fn reverse_dns(interfaces: &[NetworkInterface]) -> Vec<ReverseDns> {
+    use windows::Win32::Networking::WinSock::{WSACleanup, WSADATA, WSAStartup};
+
+    let mut data = WSADATA::default();
+    // SAFETY: `data` is a valid out pointer; every successful WSAStartup is
+    // paired with the WSACleanup below.
+    if unsafe { WSAStartup(0x0202, &mut data) } != 0 {
+        tracing::warn!("Winsock init failed, skipping reverse DNS");
+        return Vec::new();
+    }
+    let routable = NetworkOptions::new().link_local(false).loopback(false);
+    let mut names = Vec::new();
+    for ip in interfaces.iter().flat_map(NetworkInterface::ip_addresses) {
+        if !routable.keeps(ip) || names.iter().any(|r: &ReverseDns| r.ip_address == ip) {
+            continue;
+        }
+        if let Some(name) = reverse_lookup(ip) {
+            names.push(ReverseDns {
+                ip_address: ip,
+                name: name.trim_end_matches('.').to_lowercase(),
+            });
         }
+    }
+    // SAFETY: balances the successful WSAStartup above.
+    unsafe { WSACleanup() };
+    names
+}
 
-        interfaces
+/// PTR name of `ip`, from `GetNameInfoW`.
+#[cfg(all(windows, feature = "local"))]
+fn reverse_lookup(ip: IpAddr) -> Option<String> {
+    use std::net::SocketAddr;
+    use windows::Win32::Networking::WinSock::{
+        GetNameInfoW, NI_MAXHOST, NI_NAMEREQD, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6, SOCKADDR_INET,
+    };
+
+    let address = SOCKADDR_INET::from(SocketAddr::new(ip, 0));
+    let length = if ip.is_ipv4() {
+        std::mem::size_of::<SOCKADDR_IN>()
+    } else {
+        std::mem::size_of::<SOCKADDR_IN6>()
+    };
+    let mut host = [0u16; NI_MAXHOST as usize];
+    // SAFETY: `address` holds a SOCKADDR_IN or SOCKADDR_IN6 of `length`
+    // bytes, and the host buffer is passed with its length.
+    let status = unsafe {
+        GetNameInfoW(
+            &address as *const SOCKADDR_INET as *const SOCKADDR,
+            length as i32,
+            Some(&mut host),
+            None,
+            NI_NAMEREQD as i32,
+        )
+    };
+    if status != 0 {
+        return None;
     }
+    let end = host.iter().position(|&c| c == 0).unwrap_or(host.len());
+    Some(String::from_utf16_lossy(&host[..end])).filter(|name| !name.is_empty())
 }
 
 #[cfg(test)]
@@ -231,6 +783,123 @@ mod tests {
     use super::*;
 
     #[test]
+    fn test_host_identity() {
+        let joined = HostIdentity::new(
+            "HMI01",
+            Some("Plant.Example.com."),
+            Some("plant.example.com, corp.example.com"),
+        );
+        assert_eq!(joined.fqdn.as_deref(), Some("hmi01.plant.example.com"));
+        assert_eq!(joined.dns_suffix.as_deref(), Some("plant.example.com"));
+        assert_eq!(
+            joined.dns_search_list,
+            ["plant.example.com", "corp.example.com"]
+        );
+
+        let workgroup = HostIdentity::new("HMI01", Some(""), None);
+        assert_eq!(workgroup.fqdn, None);
+        assert_eq!(workgroup.dns_suffix, None);
+        assert!(workgroup.dns_search_list.is_empty());
+    }
+
+    #[test]
+    fn test_machine_sid() {
+        assert_eq!(
+            machine_sid("S-1-5-21-1004336348-1177238915-682003330-500").as_deref(),
+            Some("S-1-5-21-1004336348-1177238915-682003330")
+        );
+        // Well-known and domain-less SIDs carry no machine SID
+        assert_eq!(machine_sid("S-1-5-18"), None);
+        assert_eq!(machine_sid("S-1-5-21-1004336348-1177238915-500"), None);
+        assert_eq!(machine_sid("S-1-5-21-1004336348-x-682003330-500"), None);
+
+        assert_eq!(
+            install_time(1_700_000_000).map(|t| t.to_rfc3339()),
+            Some("2023-11-14T22:13:20+00:00".to_string())
+        );
+        assert_eq!(install_time(0), None);
+    }
+
+    #[test]
+    fn test_select_interfaces() {
+        let address = |ip: &str, prefix| InterfaceAddress {
+            ip_address: ip.parse().unwrap(),
+            prefix,
+        };
+        let adapters = || {
+            vec![
+                Adapter {
+                    name: "Plant LAN".into(),
+                    mac_address: Some("00:1B:1B:00:00:01".into()),
+                    connected: true,
+                    addresses: vec![
+                        address("fe80::1", 64),
+                        address("2001:db8::10", 64),
+                        address("192.168.1.10", 24),
+                        address("10.0.0.10", 8),
+                    ],
+                },
+                Adapter {
+                    name: "Loopback Pseudo-Interface 1".into(),
+                    mac_address: None,
+                    connected: true,
+                    addresses: vec![address("127.0.0.1", 8), address("::1", 128)],
+                },
+                Adapter {
+                    name: "Ethernet 2".into(),
+                    mac_address: None,
+                    connected: false,
+                    addresses: vec![address("169.254.12.1", 16)],
+                },
+            ]
+        };
+
+        let all = select_interfaces(adapters(), &NetworkOptions::new());
+        assert_eq!(all.len(), 7);
+        assert!(
+            all.iter()
+                .all(|iface| iface.additional_addresses.is_empty())
+        );
+
+        let compact = select_interfaces(adapters(), &NetworkOptions::compact());
+        assert_eq!(compact.len(), 1);
+        let lan = &compact[0];
+        assert_eq!(lan.ip_address.to_string(), "192.168.1.10");
+        assert_eq!(
+            lan.address_list(),
+            ["192.168.1.10/24", "10.0.0.10/8", "2001:db8::10/64"]
+        );
+        assert_eq!(lan.ipv4_addresses().count(), 2);
+        assert_eq!(lan.ipv6_addresses().count(), 1);
+
+        let ipv6 = select_interfaces(adapters(), &NetworkOptions::compact().ipv4(false));
+        assert_eq!(ipv6[0].ip_address.to_string(), "2001:db8::10");
+        assert!(ipv6[0].additional_addresses.is_empty());
+    }
+
+    #[test]
+    fn test_interface_prefix() {
+        let iface = |mask: Option<&str>| NetworkInterface {
+            name: "Plant LAN".into(),
+            ip_address: "192.168.1.10".parse().unwrap(),
+            subnet_mask: mask.map(Into::into),
+            gateway: None,
+            mac_address: None,
+            additional_addresses: Vec::new(),
+        };
+        assert_eq!(iface(Some("/24")).prefix(), Some(24));
+        assert_eq!(iface(Some("64")).prefix(), Some(64));
+        assert_eq!(iface(Some("255.255.255.0")).prefix(), Some(24));
+        assert_eq!(iface(Some("255.0.255.0")).prefix(), None);
+        assert_eq!(iface(None).prefix(), None);
+        assert_eq!(
+            iface(Some("255.255.0.0")).address_list(),
+            ["192.168.1.10/16"]
+        );
+    }
+
+    #[test]
+    #[cfg(all(windows, feature = "local"))]
     fn test_collect_system_info() {
         let info = SystemInfo::collect().expect("Should collect system info");
 
@@ -248,6 +917,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(all(windows, feature = "local"))]
     fn test_network_interfaces_have_valid_mac() {
         let info = SystemInfo::collect().expect("Should collect system info");
 
@@ -261,6 +931,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(all(windows, feature = "local"))]
     fn test_build_number_format() {
         let info = SystemInfo::collect().expect("Should collect system info");
 
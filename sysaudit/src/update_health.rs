@@ -0,0 +1,353 @@
+//! Windows Update agent health module.
+//!
+//! Reports the state of what patching depends on: the Windows Update
+//! service (`wuauserv`), BITS, the WSUS server and automatic-update policy,
+//! and the Configuration Manager (SCCM/MECM) client when it is installed.
+//! "Why isn't this host patching" triage starts here.
+
+use crate::WindowsService;
+#[cfg(all(windows, feature = "local"))]
+use crate::WmiContext;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(all(windows, feature = "local"))]
+use windows_registry::LOCAL_MACHINE;
+#[cfg(all(windows, feature = "local"))]
+use wmi::{WMIConnection, WMIDateTime};
+
+/// Days without a machine policy evaluation before the SCCM client is
+/// reported as stale.
+pub const STALE_POLICY_DAYS: i64 = 7;
+
+/// WMI namespaces of the Configuration Manager client.
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const CCM_NAMESPACE: &str = r"root\ccm";
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const CCM_SCHEDULER_NAMESPACE: &str = r"root\ccm\Scheduler";
+
+/// Services patching depends on.
+#[cfg(all(windows, feature = "local"))]
+pub(crate) const UPDATE_SERVICES: &[&str] = &["wuauserv", "BITS"];
+
+#[cfg(any(all(windows, feature = "local"), test))]
+const WU_POLICY_KEY: &str = r"SOFTWARE\Policies\Microsoft\Windows\WindowsUpdate";
+#[cfg(any(all(windows, feature = "local"), test))]
+const WU_AU_POLICY_KEY: &str = r"SOFTWARE\Policies\Microsoft\Windows\WindowsUpdate\AU";
+#[cfg(any(all(windows, feature = "local"), test))]
+const SCCM_CLIENT_KEY: &str = r"SOFTWARE\Microsoft\SMS\Mobile Client";
+
+/// Schedule ID of the Machine Policy Retrieval & Evaluation Cycle.
+#[cfg(all(windows, feature = "local"))]
+const MACHINE_POLICY_SCHEDULE: &str = "{00000000-0000-0000-0000-000000000021}";
+
+/// Health of the Windows Update agents of a host.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateHealth {
+    /// Windows Update service (`wuauserv`), if installed
+    #[serde(default)]
+    pub windows_update: Option<WindowsService>,
+    /// Background Intelligent Transfer Service, if installed
+    #[serde(default)]
+    pub bits: Option<WindowsService>,
+    /// WSUS server set by policy (`WUServer` with `UseWUServer=1`)
+    #[serde(default)]
+    pub wsus_server: Option<String>,
+    /// Whether automatic updates are turned off by policy (`NoAutoUpdate`)
+    #[serde(default)]
+    pub auto_update_disabled: Option<bool>,
+    /// Configuration Manager client, if installed
+    #[serde(default)]
+    pub sccm: Option<SccmClient>,
+}
+
+/// Configuration Manager (SCCM/MECM) client state.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SccmClient {
+    /// Client version (e.g., "5.00.9128.1000")
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Assigned site code (e.g., "PS1")
+    #[serde(default)]
+    pub site_code: Option<String>,
+    /// Management point the client talks to
+    #[serde(default)]
+    pub management_point: Option<String>,
+    /// Last machine policy retrieval and evaluation
+    #[serde(default)]
+    pub last_policy_evaluation: Option<DateTime<Utc>>,
+}
+
+/// WMI result struct for SMS_Authority.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct SmsAuthority {
+    current_management_point: Option<String>,
+}
+
+/// WMI result struct for CCM_Scheduler_History.
+#[cfg(all(windows, feature = "local"))]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct CcmSchedulerHistory {
+    last_trigger_time: Option<WMIDateTime>,
+}
+
+impl UpdateHealth {
+    /// Policy and SCCM client settings, read through `dword(key, value)`
+    /// and `string(key, value)` under `HKEY_LOCAL_MACHINE`. Shared by the
+    /// local and the remote (`StdRegProv`) collectors.
+    #[cfg(any(all(windows, feature = "local"), test))]
+    pub(crate) fn from_registry(
+        dword: impl Fn(&str, &str) -> Option<u32>,
+        string: impl Fn(&str, &str) -> Option<String>,
+    ) -> Self {
+        let wsus_server = string(WU_POLICY_KEY, "WUServer")
+            .filter(|_| dword(WU_AU_POLICY_KEY, "UseWUServer") == Some(1));
+        let version = string(SCCM_CLIENT_KEY, "ProductVersion");
+        let site_code = string(SCCM_CLIENT_KEY, "AssignedSiteCode");
+        let sccm = (version.is_some() || site_code.is_some()).then(|| SccmClient {
+            version,
+            site_code,
+            ..Default::default()
+        });
+
+        UpdateHealth {
+            wsus_server,
+            auto_update_disabled: dword(WU_AU_POLICY_KEY, "NoAutoUpdate").map(|v| v != 0),
+            sccm,
+            ..Default::default()
+        }
+    }
+
+    /// Set the service entries from a [`UPDATE_SERVICES`] query.
+    #[cfg(any(all(windows, feature = "local"), test))]
+    pub(crate) fn set_services(&mut self, services: Vec<WindowsService>) {
+        for service in services {
+            if service.name.eq_ignore_ascii_case("wuauserv") {
+                self.windows_update = Some(service);
+            } else if service.name.eq_ignore_ascii_case("BITS") {
+                self.bits = Some(service);
+            }
+        }
+    }
+
+    /// What keeps the host from patching, as of `now`; empty when
+    /// nothing is known to be wrong.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::Utc;
+    /// use sysaudit::UpdateHealth;
+    ///
+    /// let health = UpdateHealth {
+    ///     auto_update_disabled: Some(true),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(health.issues(Utc::now()).len(), 1);
+    /// ```
+    pub fn issues(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut issues = Vec::new();
+        for (name, service) in [
+            ("Windows Update (wuauserv)", &self.windows_update),
+            ("BITS", &self.bits),
+        ] {
+            let disabled = service.as_ref().is_some_and(|s| {
+                s.start_mode
+                    .as_deref()
+                    .is_some_and(|mode| mode.eq_ignore_ascii_case("Disabled"))
+            });
+            if disabled {
+                issues.push(format!("{} service is disabled", name));
+            }
+        }
+        if self.auto_update_disabled == Some(true) {
+            issues.push("Automatic updates are turned off by policy".to_string());
+        }
+        if let Some(sccm) = &self.sccm {
+            if sccm.site_code.is_none() {
+                issues.push("SCCM client is not assigned to a site".to_string());
+            }
+            match sccm.last_policy_evaluation {
+                None => issues.push("SCCM client has no recorded policy evaluation".to_string()),
+                Some(at) if (now - at).num_days() > STALE_POLICY_DAYS => issues.push(format!(
+                    "SCCM client last evaluated machine policy {} days ago",
+                    (now - at).num_days()
+                )),
+                Some(_) => {}
+            }
+        }
+        issues
+    }
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl UpdateHealth {
+    /// Collect the update agent health (READ-ONLY).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::UpdateHealth;
+    ///
+    /// let health = UpdateHealth::collect().unwrap();
+    /// println!("WSUS: {:?}", health.wsus_server);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if COM cannot be
+    /// initialized.
+    pub fn collect() -> Result<Self, crate::Error> {
+        Self::collect_with_warnings().map(|(health, _)| health)
+    }
+
+    /// [`collect`](Self::collect), also describing what could not be read.
+    ///
+    /// # Errors
+    ///
+    /// As [`collect`](Self::collect).
+    #[tracing::instrument(fields(warnings = tracing::field::Empty))]
+    pub fn collect_with_warnings() -> Result<(Self, Vec<String>), crate::Error> {
+        WmiContext::with_current(Self::collect_with_wmi)
+    }
+
+    /// [`collect_with_warnings`](Self::collect_with_warnings) over an open
+    /// WMI context.
+    ///
+    /// # Errors
+    ///
+    /// Never fails once the context is open; unreadable settings are
+    /// reported as warnings.
+    pub fn collect_with_wmi(wmi: &WmiContext) -> Result<(Self, Vec<String>), crate::Error> {
+        tracing::info!("Collecting update agent health");
+        let mut warnings = Vec::new();
+        let mut health = UpdateHealth::from_registry(
+            |path, value| LOCAL_MACHINE.open(path).ok()?.get_u32(value).ok(),
+            |path, value| {
+                LOCAL_MACHINE
+                    .open(path)
+                    .ok()?
+                    .get_string(value)
+                    .ok()
+                    .filter(|s| !s.is_empty())
+            },
+        );
+
+        match wmi
+            .cimv2()
+            .and_then(|con| WindowsService::query_named(con, UPDATE_SERVICES))
+        {
+            Ok(services) => health.set_services(services),
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not query update services");
+                warnings.push(format!("service query failed: {}", e));
+            }
+        }
+        if let Some(sccm) = &mut health.sccm {
+            let ccm = wmi.namespace(CCM_NAMESPACE);
+            let scheduler = wmi.namespace(CCM_SCHEDULER_NAMESPACE);
+            warnings.extend(sccm.query(ccm, scheduler));
+        }
+        tracing::Span::current().record("warnings", warnings.len());
+        Ok((health, warnings))
+    }
+}
+
+#[cfg(all(windows, feature = "local"))]
+impl SccmClient {
+    /// Fill in the management point and last policy evaluation from the
+    /// client's WMI namespaces, returning what could not be read.
+    pub(crate) fn query(
+        &mut self,
+        ccm: Result<WMIConnection, crate::Error>,
+        scheduler: Result<WMIConnection, crate::Error>,
+    ) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let authority = ccm.and_then(|con| {
+            Ok(con.raw_query::<SmsAuthority>("SELECT CurrentManagementPoint FROM SMS_Authority")?)
+        });
+        match authority {
+            Ok(authority) => {
+                self.management_point = authority
+                    .into_iter()
+                    .find_map(|a| a.current_management_point.filter(|s| !s.is_empty()));
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not query the SCCM management point");
+                warnings.push(format!("SCCM management point query failed: {}", e));
+            }
+        }
+        let history = scheduler.and_then(|con| {
+            Ok(con.raw_query::<CcmSchedulerHistory>(format!(
+                "SELECT LastTriggerTime FROM CCM_Scheduler_History \
+                 WHERE ScheduleID = '{}' AND UserSID = 'Machine'",
+                MACHINE_POLICY_SCHEDULE
+            ))?)
+        });
+        match history {
+            Ok(history) => {
+                self.last_policy_evaluation = history
+                    .into_iter()
+                    .filter_map(|h| h.last_trigger_time)
+                    .map(|t| t.0.with_timezone(&Utc))
+                    .max();
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not query the SCCM policy schedule");
+                warnings.push(format!("SCCM policy schedule query failed: {}", e));
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_from_registry_and_issues() {
+        let health = UpdateHealth::from_registry(
+            |path, value| match (path, value) {
+                (WU_AU_POLICY_KEY, "UseWUServer") => Some(1),
+                (WU_AU_POLICY_KEY, "NoAutoUpdate") => Some(0),
+                _ => None,
+            },
+            |path, value| match (path, value) {
+                (WU_POLICY_KEY, "WUServer") => Some("http://wsus.plant.local:8530".into()),
+                (SCCM_CLIENT_KEY, "ProductVersion") => Some("5.00.9128.1000".into()),
+                _ => None,
+            },
+        );
+        assert_eq!(
+            health.wsus_server.as_deref(),
+            Some("http://wsus.plant.local:8530")
+        );
+        assert_eq!(health.auto_update_disabled, Some(false));
+        let sccm = health.sccm.clone().unwrap();
+        assert_eq!(sccm.version.as_deref(), Some("5.00.9128.1000"));
+
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let mut health = health;
+        health.set_services(vec![WindowsService {
+            name: "wuauserv".into(),
+            display_name: None,
+            state: Some("Stopped".into()),
+            start_mode: Some("Disabled".into()),
+            account: None,
+            path: None,
+        }]);
+        health.sccm = Some(SccmClient {
+            site_code: Some("PS1".into()),
+            last_policy_evaluation: Some(Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()),
+            ..sccm
+        });
+        let issues = health.issues(now);
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert!(issues[0].contains("wuauserv"));
+        assert!(issues[1].contains("31 days"));
+    }
+}
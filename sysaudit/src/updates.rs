@@ -2,9 +2,12 @@
 //!
 //! Provides read-only access to installed Windows Updates via WMI.
 
+#[cfg(all(windows, feature = "local"))]
+use crate::WmiContext;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use wmi::{COMLibrary, WMIConnection};
+#[cfg(all(windows, feature = "local"))]
+use wmi::WMIConnection;
 
 /// Windows Update / Hotfix entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,7 +22,136 @@ pub struct WindowsUpdate {
     pub installed_by: Option<String>,
 }
 
+/// Kind of an update, from its `Win32_QuickFixEngineering` description
+/// (`Security Update`, `Update`, `Hotfix`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateKind {
+    /// Described as a security update
+    Security,
+    /// Any other update, hotfix or service pack
+    Other,
+}
+
+impl UpdateKind {
+    /// Parse `security` or `other` (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "security" => Some(UpdateKind::Security),
+            "other" => Some(UpdateKind::Other),
+            _ => None,
+        }
+    }
+}
+
+impl WindowsUpdate {
+    /// Whether this is a security update or another kind.
+    pub fn kind(&self) -> UpdateKind {
+        let security = self
+            .description
+            .as_deref()
+            .is_some_and(|d| d.to_ascii_lowercase().contains("security"));
+        if security {
+            UpdateKind::Security
+        } else {
+            UpdateKind::Other
+        }
+    }
+}
+
+/// Selection of installed updates by date, KB number and kind.
+///
+/// An empty filter keeps every update; each criterion that is set must
+/// match.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateFilter {
+    since: Option<NaiveDate>,
+    kbs: Vec<String>,
+    kind: Option<UpdateKind>,
+}
+
+impl UpdateFilter {
+    /// A filter that keeps every update.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep updates installed on or after `date`. Updates without an
+    /// install date are dropped.
+    pub fn since(mut self, date: NaiveDate) -> Self {
+        self.since = Some(date);
+        self
+    }
+
+    /// Keep the update with this KB number (`KB5034441` or `5034441`).
+    /// Repeated calls keep any of the given KBs.
+    pub fn kb(mut self, id: &str) -> Self {
+        self.kbs.push(normalize_kb(id));
+        self
+    }
+
+    /// Keep only updates of this kind.
+    pub fn kind(mut self, kind: UpdateKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Whether `update` passes the filter.
+    pub fn matches(&self, update: &WindowsUpdate) -> bool {
+        let too_old = self
+            .since
+            .is_some_and(|since| update.installed_on.is_none_or(|date| date < since));
+        if too_old {
+            return false;
+        }
+        if !self.kbs.is_empty() && !self.kbs.contains(&normalize_kb(&update.hotfix_id)) {
+            return false;
+        }
+        self.kind.is_none_or(|kind| update.kind() == kind)
+    }
+
+    /// Keep the updates that pass the filter.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::WindowsUpdate;
+    /// use sysaudit::updates::{UpdateFilter, UpdateKind, parse_date};
+    ///
+    /// let filter = UpdateFilter::new()
+    ///     .since(parse_date("2024-01-01").unwrap())
+    ///     .kind(UpdateKind::Security);
+    /// for update in filter.apply(WindowsUpdate::collect_all()) {
+    ///     println!("{} {:?}", update.hotfix_id, update.installed_on);
+    /// }
+    /// ```
+    pub fn apply(&self, mut updates: Vec<WindowsUpdate>) -> Vec<WindowsUpdate> {
+        updates.retain(|update| self.matches(update));
+        updates
+    }
+}
+
+/// `KB5034441`, `kb5034441` and `5034441` all become `KB5034441`.
+fn normalize_kb(id: &str) -> String {
+    let id = id.trim();
+    let number = match id.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("kb") => &id[2..],
+        _ => id,
+    };
+    format!("KB{}", number.to_ascii_uppercase())
+}
+
+/// Parse a `YYYY-MM-DD` date for [`UpdateFilter::since`].
+///
+/// # Errors
+///
+/// Returns [`crate::Error::General`] if `text` is not a valid date.
+pub fn parse_date(text: &str) -> Result<NaiveDate, crate::Error> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+        .map_err(|_| crate::Error::General(format!("invalid date '{}': use YYYY-MM-DD", text)))
+}
+
 /// WMI result struct for Win32_QuickFixEngineering.
+#[cfg(all(windows, feature = "local"))]
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 struct Win32QuickFixEngineering {
@@ -30,10 +162,13 @@ struct Win32QuickFixEngineering {
     installed_by: Option<String>,
 }
 
+#[cfg(all(windows, feature = "local"))]
 impl WindowsUpdate {
     /// Collect all installed Windows Updates (READ-ONLY).
     ///
-    /// Returns empty vec if WMI query fails (graceful degradation).
+    /// Returns empty vec if WMI query fails (graceful degradation); use
+    /// [`try_collect_all`](Self::try_collect_all) to tell a failed query
+    /// from a host without updates.
     ///
     /// # Example
     ///
@@ -47,7 +182,7 @@ impl WindowsUpdate {
     /// ```
     pub fn collect_all() -> Vec<Self> {
         tracing::info!("Collecting all Windows Updates");
-        match Self::try_collect() {
+        match Self::try_collect_all() {
             Ok(updates) => {
                 tracing::debug!("Found {} updates", updates.len());
                 updates
@@ -59,10 +194,43 @@ impl WindowsUpdate {
         }
     }
 
-    fn try_collect() -> Result<Vec<Self>, crate::Error> {
-        let com_con = COMLibrary::new()?;
-        let wmi_con = WMIConnection::new(com_con)?;
+    /// Collect all installed Windows Updates, failing if WMI cannot be
+    /// queried.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::WindowsUpdate;
+    ///
+    /// match WindowsUpdate::try_collect_all() {
+    ///     Ok(updates) => println!("{} updates installed", updates.len()),
+    ///     Err(e) => eprintln!("Windows Updates unavailable: {}", e),
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if COM cannot be
+    /// initialized or the query fails.
+    #[tracing::instrument(fields(items = tracing::field::Empty))]
+    pub fn try_collect_all() -> Result<Vec<Self>, crate::Error> {
+        let updates = WmiContext::with_current(Self::collect_with_wmi)?;
+        tracing::Span::current().record("items", updates.len());
+        Ok(updates)
+    }
+
+    /// Installed updates, queried over an open WMI context.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`](crate::Error::Wmi) if the query fails.
+    pub fn collect_with_wmi(wmi: &WmiContext) -> Result<Vec<Self>, crate::Error> {
+        Self::query(wmi.cimv2()?)
+    }
 
+    /// Query `Win32_QuickFixEngineering` on an open (local or remote)
+    /// WMI connection.
+    pub(crate) fn query(wmi_con: &WMIConnection) -> Result<Vec<Self>, crate::Error> {
         let results: Vec<Win32QuickFixEngineering> = wmi_con.query()?;
 
         let updates = results
@@ -94,6 +262,7 @@ impl WindowsUpdate {
 }
 
 /// Parse WMI date format (various formats possible).
+#[cfg(any(all(windows, feature = "local"), test))]
 fn parse_wmi_date(s: &str) -> Option<NaiveDate> {
     // Try common formats
     // MM/DD/YYYY
@@ -153,4 +322,35 @@ mod tests {
     fn test_parse_wmi_date_compact_non_numeric() {
         assert_eq!(parse_wmi_date("ABCDEFGH"), None);
     }
+
+    #[test]
+    fn test_update_filter() {
+        let update = |id: &str, description: &str, date: Option<(i32, u32, u32)>| WindowsUpdate {
+            hotfix_id: id.into(),
+            description: Some(description.into()),
+            installed_on: date.and_then(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d)),
+            installed_by: None,
+        };
+        let updates = vec![
+            update("KB5034441", "Security Update", Some((2024, 1, 10))),
+            update("KB5033052", "Update", Some((2023, 12, 12))),
+            update("KB4589208", "Update", None),
+        ];
+
+        let since = UpdateFilter::new().since(parse_date("2024-01-01").unwrap());
+        assert_eq!(since.apply(updates.clone()).len(), 1);
+
+        let kb = UpdateFilter::new().kb("5033052").kb("kb4589208");
+        let ids: Vec<_> = kb
+            .apply(updates.clone())
+            .into_iter()
+            .map(|u| u.hotfix_id)
+            .collect();
+        assert_eq!(ids, ["KB5033052", "KB4589208"]);
+
+        let other = UpdateFilter::new().kind(UpdateKind::Other);
+        assert_eq!(other.apply(updates.clone()).len(), 2);
+        assert_eq!(UpdateFilter::new().apply(updates).len(), 3);
+        assert!(parse_date("01/10/2024").is_err());
+    }
 }
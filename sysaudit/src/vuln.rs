@@ -0,0 +1,822 @@
+//! Known-vulnerability matching against an offline dataset.
+//!
+//! A [`VulnDatabase`] is loaded from a local snapshot, so matching works on
+//! air-gapped networks:
+//!
+//! - an NVD CVE API 2.0 JSON feed (`{"vulnerabilities": [...]}`),
+//! - OSV records: one record, a JSON array of records, JSON lines, or a
+//!   directory of `.json` files (e.g. an extracted OSV `all.zip`).
+//!
+//! Installed software is matched by a CPE generated from its name and
+//! publisher (see [`cpe`]): the product must match the advisory's product,
+//! and the vendor must match when both are known. Matching is heuristic;
+//! treat results as leads, not as a verified vulnerability assessment.
+
+use crate::findings::{Finding, Severity};
+use crate::software::compare_versions;
+use crate::{Error, Software};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Publisher words dropped when deriving a CPE vendor.
+const COMPANY_SUFFIXES: &[&str] = &[
+    "inc",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "ltd",
+    "limited",
+    "llc",
+    "gmbh",
+    "ag",
+    "sa",
+    "bv",
+    "team",
+    "foundation",
+    "software",
+    "technologies",
+];
+
+/// Name words dropped when deriving a CPE product.
+const ARCH_WORDS: &[&str] = &["x64", "x86", "64-bit", "32-bit", "amd64", "arm64"];
+
+/// A known vulnerability affecting an installed package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vulnerability {
+    /// CVE ID when known, otherwise the advisory ID (e.g. "GHSA-...")
+    pub id: String,
+    /// Other IDs of the same vulnerability
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// One-line description
+    pub summary: Option<String>,
+    /// CVSS base score (0.0-10.0)
+    pub cvss: Option<f32>,
+    /// Severity derived from the CVSS score; medium when unscored
+    pub severity: Severity,
+}
+
+/// An installed package and the vulnerabilities matched to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VulnerableSoftware {
+    /// The installed package
+    #[serde(flatten)]
+    pub software: Software,
+    /// Matched vulnerabilities, highest CVSS first
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+impl VulnerableSoftware {
+    /// One finding per matched vulnerability.
+    pub fn findings(&self) -> Vec<Finding> {
+        let version = self.software.version.clone().unwrap_or_default();
+        self.vulnerabilities
+            .iter()
+            .map(|vuln| Finding {
+                id: "vulnerable-software".to_string(),
+                category: "vulnerability".to_string(),
+                severity: vuln.severity,
+                title: format!("{} in {} {}", vuln.id, self.software.name, version),
+                attributes: vec![
+                    ("cve".to_string(), vuln.id.clone()),
+                    ("software".to_string(), self.software.name.clone()),
+                    ("version".to_string(), version.clone()),
+                    (
+                        "cvss".to_string(),
+                        vuln.cvss.map(|s| format!("{:.1}", s)).unwrap_or_default(),
+                    ),
+                    (
+                        "summary".to_string(),
+                        vuln.summary.clone().unwrap_or_default(),
+                    ),
+                ],
+            })
+            .collect()
+    }
+}
+
+/// Offline vulnerability dataset, indexed by product.
+#[derive(Debug, Clone, Default)]
+pub struct VulnDatabase {
+    advisories: Vec<Advisory>,
+    /// Normalized product name → (advisory, affected entry) indices
+    index: HashMap<String, Vec<(usize, usize)>>,
+}
+
+/// One advisory from the dataset.
+#[derive(Debug, Clone)]
+struct Advisory {
+    vulnerability: Vulnerability,
+    affected: Vec<Affected>,
+}
+
+/// A product an advisory affects.
+#[derive(Debug, Clone)]
+struct Affected {
+    vendor: Option<String>,
+    product: String,
+    /// Exact affected versions
+    versions: Vec<String>,
+    ranges: Vec<VersionRange>,
+}
+
+/// Affected versions between two optional bounds.
+#[derive(Debug, Clone, Default)]
+struct VersionRange {
+    /// Lower bound and whether it is inclusive
+    start: Option<(String, bool)>,
+    /// Upper bound and whether it is inclusive
+    end: Option<(String, bool)>,
+}
+
+impl VersionRange {
+    fn contains(&self, version: &str) -> bool {
+        let above = self.start.as_ref().is_none_or(|(start, inclusive)| {
+            match compare_versions(version, start) {
+                Ordering::Greater => true,
+                Ordering::Equal => *inclusive,
+                Ordering::Less => false,
+            }
+        });
+        let below =
+            self.end
+                .as_ref()
+                .is_none_or(|(end, inclusive)| match compare_versions(version, end) {
+                    Ordering::Less => true,
+                    Ordering::Equal => *inclusive,
+                    Ordering::Greater => false,
+                });
+        above && below
+    }
+}
+
+impl VulnDatabase {
+    /// Load a dataset from a file or a directory of `.json` files.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::SoftwareScanner;
+    /// use sysaudit::vuln::VulnDatabase;
+    ///
+    /// let db = VulnDatabase::load("nvdcve-2.0-modified.json").unwrap();
+    /// for entry in db.annotate(SoftwareScanner::new().scan().unwrap()) {
+    ///     for vuln in &entry.vulnerabilities {
+    ///         println!("{} {}: {}", entry.software.name, vuln.id, vuln.cvss.unwrap_or(0.0));
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the path cannot be read, or
+    /// [`Error::General`] naming the file that is not NVD or OSV JSON.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut db = VulnDatabase::default();
+        if path.is_dir() {
+            let mut files: Vec<_> = std::fs::read_dir(path)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<_, _>>()?;
+            files.retain(|p| p.extension().is_some_and(|ext| ext == "json"));
+            files.sort();
+            for file in files {
+                db.add_file(&file)?;
+            }
+        } else {
+            db.add_file(path)?;
+        }
+        tracing::info!(
+            advisories = db.advisories.len(),
+            "Loaded vulnerability dataset"
+        );
+        Ok(db)
+    }
+
+    /// Parse a dataset held in memory (NVD feed or OSV JSON/JSON lines).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::General`] if the text is neither format.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let mut db = VulnDatabase::default();
+        db.add(json)?;
+        Ok(db)
+    }
+
+    /// Number of advisories loaded.
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    /// Whether no advisories are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Vulnerabilities affecting `version` of the package `name` published
+    /// by `publisher`. Without a version nothing matches.
+    pub fn lookup(
+        &self,
+        name: &str,
+        publisher: Option<&str>,
+        version: Option<&str>,
+    ) -> Vec<Vulnerability> {
+        let Some(version) = version.filter(|v| !v.is_empty()) else {
+            return Vec::new();
+        };
+        let product = product_name(name, publisher);
+        // The vendor of e.g. "7-Zip" is its own first word, not "Igor Pavlov".
+        let vendors: Vec<String> = publisher
+            .map(vendor_name)
+            .into_iter()
+            .chain(name.split_whitespace().next().map(normalize))
+            .collect();
+
+        let mut found: Vec<Vulnerability> = Vec::new();
+        for &(advisory, affected) in self.index.get(&product).into_iter().flatten() {
+            let advisory = &self.advisories[advisory];
+            let affected = &advisory.affected[affected];
+            let vendor_ok =
+                publisher.is_none() || affected.vendor.as_ref().is_none_or(|v| vendors.contains(v));
+            let version_ok = affected
+                .versions
+                .iter()
+                .any(|v| compare_versions(version, v) == Ordering::Equal)
+                || affected.ranges.iter().any(|r| r.contains(version));
+            if vendor_ok && version_ok && !found.iter().any(|f| f.id == advisory.vulnerability.id) {
+                found.push(advisory.vulnerability.clone());
+            }
+        }
+        found.sort_by(|a, b| b.cvss.partial_cmp(&a.cvss).unwrap_or(Ordering::Equal));
+        found
+    }
+
+    /// Match every package, keeping those with at least one vulnerability.
+    pub fn annotate(&self, software: Vec<Software>) -> Vec<VulnerableSoftware> {
+        software
+            .into_iter()
+            .filter_map(|sw| {
+                let vulnerabilities =
+                    self.lookup(&sw.name, sw.publisher.as_deref(), sw.version.as_deref());
+                (!vulnerabilities.is_empty()).then_some(VulnerableSoftware {
+                    software: sw,
+                    vulnerabilities,
+                })
+            })
+            .collect()
+    }
+
+    fn add_file(&mut self, path: &Path) -> Result<(), Error> {
+        let json = std::fs::read_to_string(path)?;
+        self.add(&json).map_err(|e| {
+            Error::General(format!(
+                "cannot read vulnerability data {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    fn add(&mut self, json: &str) -> Result<(), Error> {
+        let advisories = match serde_json::from_str::<serde_json::Value>(json) {
+            Ok(serde_json::Value::Array(records)) => records
+                .into_iter()
+                .map(osv_advisory)
+                .collect::<Result<Vec<_>, _>>()?,
+            Ok(value) if value.get("vulnerabilities").is_some() => {
+                let feed: NvdFeed = serde_json::from_value(value)?;
+                feed.vulnerabilities
+                    .into_iter()
+                    .map(|item| nvd_advisory(item.cve))
+                    .collect()
+            }
+            Ok(value) if value.get("id").is_some() => vec![osv_advisory(value)?],
+            // JSON lines: one OSV record per line
+            Err(_) if json.lines().any(|l| l.trim_start().starts_with('{')) => json
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| {
+                    serde_json::from_str(l)
+                        .map_err(Error::from)
+                        .and_then(osv_advisory)
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => {
+                return Err(Error::General(
+                    "expected an NVD 2.0 feed or OSV records".to_string(),
+                ));
+            }
+        };
+        for advisory in advisories {
+            let i = self.advisories.len();
+            for (j, affected) in advisory.affected.iter().enumerate() {
+                self.index
+                    .entry(affected.product.clone())
+                    .or_default()
+                    .push((i, j));
+            }
+            self.advisories.push(advisory);
+        }
+        Ok(())
+    }
+}
+
+/// CPE 2.3 name generated for an installed package, e.g.
+/// `cpe:2.3:a:mozilla:firefox:115.0:*:*:*:*:*:*:*`.
+pub fn cpe(name: &str, publisher: Option<&str>, version: Option<&str>) -> String {
+    format!(
+        "cpe:2.3:a:{}:{}:{}:*:*:*:*:*:*:*",
+        publisher
+            .map(vendor_name)
+            .unwrap_or_else(|| "*".to_string()),
+        product_name(name, publisher),
+        version.map(normalize).unwrap_or_else(|| "*".to_string())
+    )
+}
+
+/// CPE-style token: lowercase, words joined by `_`.
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '.' | '+' | '_'))
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// CPE vendor from a publisher ("Mozilla Corporation" → "mozilla").
+fn vendor_name(publisher: &str) -> String {
+    let words: Vec<String> = publisher
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(normalize)
+        .filter(|w| !w.is_empty() && !COMPANY_SUFFIXES.contains(&w.trim_end_matches('.')))
+        .collect();
+    words.join("_")
+}
+
+/// CPE product from a display name, without version, architecture and
+/// vendor prefix ("Mozilla Firefox (x64 en-US)" → "firefox").
+fn product_name(name: &str, publisher: Option<&str>) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .map(normalize)
+        .filter(|w| !w.is_empty() && !ARCH_WORDS.contains(&w.as_str()))
+        .filter(|w| !is_version(w) && !is_locale(w))
+        .collect();
+    let product = words.join("_");
+    let vendor = publisher.map(vendor_name).unwrap_or_default();
+    match product.strip_prefix(&format!("{}_", vendor)) {
+        Some(rest) if !vendor.is_empty() && !rest.is_empty() => rest.to_string(),
+        _ => product,
+    }
+}
+
+/// Version numbers such as "22.01" that installers append to names.
+fn is_version(word: &str) -> bool {
+    word.contains('.') && word.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Locale tags such as "en-us" that installers append to names.
+fn is_locale(word: &str) -> bool {
+    let mut parts = word.split('-');
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(a), Some(b), None) if a.len() == 2 && b.len() == 2
+    )
+}
+
+/// Severity of a CVSS base score, using the CVSS qualitative ratings.
+fn severity(cvss: Option<f32>) -> Severity {
+    match cvss {
+        Some(score) if score >= 9.0 => Severity::Critical,
+        Some(score) if score >= 7.0 => Severity::High,
+        Some(score) if score >= 4.0 => Severity::Medium,
+        Some(score) if score > 0.0 => Severity::Low,
+        Some(_) => Severity::Info,
+        None => Severity::Medium,
+    }
+}
+
+/// NVD CVE API 2.0 feed.
+#[derive(Deserialize)]
+struct NvdFeed {
+    vulnerabilities: Vec<NvdItem>,
+}
+
+#[derive(Deserialize)]
+struct NvdItem {
+    cve: NvdCve,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NvdCve {
+    id: String,
+    #[serde(default)]
+    descriptions: Vec<NvdDescription>,
+    #[serde(default)]
+    metrics: HashMap<String, Vec<NvdMetric>>,
+    #[serde(default)]
+    configurations: Vec<NvdConfiguration>,
+}
+
+#[derive(Deserialize)]
+struct NvdDescription {
+    lang: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NvdMetric {
+    cvss_data: NvdCvssData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NvdCvssData {
+    base_score: f32,
+}
+
+#[derive(Deserialize)]
+struct NvdConfiguration {
+    #[serde(default)]
+    nodes: Vec<NvdNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NvdNode {
+    #[serde(default)]
+    cpe_match: Vec<NvdCpeMatch>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NvdCpeMatch {
+    vulnerable: bool,
+    criteria: String,
+    version_start_including: Option<String>,
+    version_start_excluding: Option<String>,
+    version_end_including: Option<String>,
+    version_end_excluding: Option<String>,
+}
+
+fn nvd_advisory(cve: NvdCve) -> Advisory {
+    // Newest CVSS version first
+    let cvss = [
+        "cvssMetricV40",
+        "cvssMetricV31",
+        "cvssMetricV30",
+        "cvssMetricV2",
+    ]
+    .iter()
+    .find_map(|key| cve.metrics.get(*key).and_then(|m| m.first()))
+    .map(|m| m.cvss_data.base_score);
+    let summary = cve
+        .descriptions
+        .iter()
+        .find(|d| d.lang == "en")
+        .map(|d| d.value.clone());
+    let affected = cve
+        .configurations
+        .iter()
+        .flat_map(|c| &c.nodes)
+        .flat_map(|n| &n.cpe_match)
+        .filter(|m| m.vulnerable)
+        .filter_map(|m| {
+            let fields = cpe_fields(&m.criteria);
+            // Only applications (part "a")
+            if fields.get(2).map(String::as_str) != Some("a") {
+                return None;
+            }
+            let vendor = normalize(fields.get(3)?);
+            let product = normalize(fields.get(4)?);
+            if product.is_empty() {
+                return None;
+            }
+            let version = fields.get(5).cloned().unwrap_or_default();
+            let bound = |incl: &Option<String>, excl: &Option<String>| {
+                incl.clone()
+                    .map(|v| (v, true))
+                    .or_else(|| excl.clone().map(|v| (v, false)))
+            };
+            let range = VersionRange {
+                start: bound(&m.version_start_including, &m.version_start_excluding),
+                end: bound(&m.version_end_including, &m.version_end_excluding),
+            };
+            // "*" and "-" cover every version within the bounds
+            let (versions, ranges) = match version.as_str() {
+                "*" | "-" | "" => (Vec::new(), vec![range]),
+                _ => (vec![version], Vec::new()),
+            };
+            Some(Affected {
+                // "*" normalizes to ""
+                vendor: (!vendor.is_empty()).then_some(vendor),
+                product,
+                versions,
+                ranges,
+            })
+        })
+        .collect();
+    Advisory {
+        vulnerability: Vulnerability {
+            id: cve.id,
+            aliases: Vec::new(),
+            summary,
+            cvss,
+            severity: severity(cvss),
+        },
+        affected,
+    }
+}
+
+/// Fields of a CPE 2.3 formatted string, unescaped.
+fn cpe_fields(cpe: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = cpe.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let (Some(next), Some(field)) = (chars.next(), fields.last_mut()) {
+                    field.push(next);
+                }
+            }
+            ':' => fields.push(String::new()),
+            _ => {
+                if let Some(field) = fields.last_mut() {
+                    field.push(c);
+                }
+            }
+        }
+    }
+    fields
+}
+
+/// OSV record (https://ossf.github.io/osv-schema/).
+#[derive(Deserialize)]
+struct OsvRecord {
+    id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    summary: Option<String>,
+    details: Option<String>,
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+}
+
+#[derive(Deserialize)]
+struct OsvSeverity {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Deserialize)]
+struct OsvAffected {
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvPackage {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<HashMap<String, String>>,
+}
+
+fn osv_advisory(value: serde_json::Value) -> Result<Advisory, Error> {
+    let record: OsvRecord = serde_json::from_value(value)?;
+    let cvss = record
+        .severity
+        .iter()
+        .filter(|s| s.kind == "CVSS_V3")
+        .find_map(|s| cvss3_base_score(&s.score));
+    // Report the CVE ID when the advisory has one
+    let (id, aliases) = match record.aliases.iter().position(|a| a.starts_with("CVE-")) {
+        Some(i) if !record.id.starts_with("CVE-") => {
+            let mut aliases = record.aliases.clone();
+            let cve = aliases.remove(i);
+            aliases.insert(0, record.id.clone());
+            (cve, aliases)
+        }
+        _ => (record.id.clone(), record.aliases.clone()),
+    };
+    let affected = record
+        .affected
+        .iter()
+        .filter_map(|a| {
+            let package = a.package.as_ref()?;
+            Some(Affected {
+                vendor: None,
+                product: product_name(&package.name, None),
+                versions: a.versions.clone(),
+                ranges: a
+                    .ranges
+                    .iter()
+                    .filter(|r| r.kind != "GIT")
+                    .flat_map(|r| osv_ranges(&r.events))
+                    .collect(),
+            })
+        })
+        .collect();
+    Ok(Advisory {
+        vulnerability: Vulnerability {
+            id,
+            aliases,
+            summary: record.summary.or(record.details),
+            cvss,
+            severity: severity(cvss),
+        },
+        affected,
+    })
+}
+
+/// Version ranges from OSV `introduced`/`fixed`/`last_affected` events.
+fn osv_ranges(events: &[HashMap<String, String>]) -> Vec<VersionRange> {
+    let mut ranges = Vec::new();
+    let mut current: Option<VersionRange> = None;
+    for event in events {
+        if let Some(introduced) = event.get("introduced") {
+            let start = (introduced != "0").then(|| (introduced.clone(), true));
+            current = Some(VersionRange { start, end: None });
+        } else if let Some(range) = current.as_mut() {
+            if let Some(fixed) = event.get("fixed") {
+                range.end = Some((fixed.clone(), false));
+            } else if let Some(last) = event.get("last_affected") {
+                range.end = Some((last.clone(), true));
+            }
+            if range.end.is_some() {
+                ranges.extend(current.take());
+            }
+        }
+    }
+    // An open range: every version since `introduced`
+    ranges.extend(current);
+    ranges
+}
+
+/// CVSS 3.x base score from a vector such as
+/// `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+fn cvss3_base_score(vector: &str) -> Option<f32> {
+    let metrics: HashMap<&str, &str> = vector
+        .split('/')
+        .skip(1)
+        .filter_map(|m| m.split_once(':'))
+        .collect();
+    let metric = |name: &str| metrics.get(name).copied();
+    let changed = metric("S")? == "C";
+    let av = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (metric("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |name: &str| match metric(name) {
+        Some("H") => Some(0.56),
+        Some("L") => Some(0.22),
+        Some("N") => Some(0.0),
+        _ => None,
+    };
+    let iss: f64 = 1.0 - (1.0 - cia("C")?) * (1.0 - cia("I")?) * (1.0 - cia("A")?);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powi(15)
+    } else {
+        6.42 * iss
+    };
+    let exploitability = 8.22 * av * ac * pr * ui;
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let score = if changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+    Some(round_up(score) as f32)
+}
+
+/// CVSS 3.1 `Roundup`: smallest one-decimal number not below `value`.
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        ((scaled / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegistrySource;
+
+    fn software(name: &str, publisher: &str, version: &str) -> Software {
+        Software {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            publisher: Some(publisher.to_string()),
+            install_date: None,
+            install_date_source: crate::InstallDateSource::Unknown,
+            install_location: None,
+            source: RegistrySource::LocalMachine64,
+            support_status: None,
+            eol: None,
+        }
+    }
+
+    #[test]
+    fn test_nvd_feed_matching() {
+        let db = VulnDatabase::from_json(
+            r#"{"vulnerabilities": [{"cve": {
+                "id": "CVE-2023-31102",
+                "descriptions": [{"lang": "en", "value": "7-Zip integer underflow"}],
+                "metrics": {"cvssMetricV31": [{"cvssData": {"baseScore": 7.8}}]},
+                "configurations": [{"nodes": [{"cpeMatch": [{
+                    "vulnerable": true,
+                    "criteria": "cpe:2.3:a:7-zip:7-zip:*:*:*:*:*:*:*:*",
+                    "versionEndExcluding": "23.00"
+                }]}]}]
+            }}]}"#,
+        )
+        .unwrap();
+        assert_eq!(db.len(), 1);
+
+        let found = db.annotate(vec![
+            software("7-Zip 22.01 (x64)", "Igor Pavlov", "22.01"),
+            software("7-Zip 23.01 (x64)", "Igor Pavlov", "23.01"),
+        ]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].vulnerabilities[0].id, "CVE-2023-31102");
+        assert_eq!(found[0].vulnerabilities[0].severity, Severity::High);
+        assert_eq!(
+            found[0].findings()[0].title,
+            "CVE-2023-31102 in 7-Zip 22.01 (x64) 22.01"
+        );
+        assert_eq!(
+            cpe(
+                "Mozilla Firefox (x64 en-US)",
+                Some("Mozilla"),
+                Some("115.0")
+            ),
+            "cpe:2.3:a:mozilla:firefox:115.0:*:*:*:*:*:*:*"
+        );
+    }
+
+    #[test]
+    fn test_osv_records() {
+        let db = VulnDatabase::from_json(
+            r#"{"id": "GHSA-xxxx", "aliases": ["CVE-2024-0001"], "summary": "RCE",
+                "severity": [{"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"}],
+                "affected": [{"package": {"name": "Notepad++"},
+                    "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "8.0"}, {"fixed": "8.5.3"}]}]}]}"#,
+        )
+        .unwrap();
+
+        let vulns = db.lookup("Notepad++ (64-bit x64)", None, Some("8.5.2"));
+        assert_eq!(vulns.len(), 1);
+        assert_eq!(vulns[0].id, "CVE-2024-0001");
+        assert_eq!(vulns[0].aliases, vec!["GHSA-xxxx".to_string()]);
+        assert_eq!(vulns[0].cvss, Some(9.8));
+        assert!(db.lookup("Notepad++", None, Some("8.5.10")).is_empty());
+        assert!(VulnDatabase::from_json("not json").is_err());
+    }
+}
@@ -0,0 +1,207 @@
+//! Continuous monitoring: timestamped report history and scan intervals.
+//!
+//! A [`ReportHistory`] keeps every report of a watched host as
+//! `sysaudit_<host>_<YYYYMMDD_HHMMSS>.json` in one directory, next to a
+//! `.diff.json` for each scan that changed the inventory. Comparing a new
+//! scan with [`ReportHistory::latest`] tells whether software or
+//! industrial products changed since the previous run. Between runs, a
+//! [`ChangeWatcher`] reports installations and removals as they happen, so
+//! the next scan need not wait for the interval.
+
+mod changes;
+
+#[cfg(all(windows, feature = "local"))]
+pub use changes::ChangeWatcher;
+pub use changes::{ChangeTrigger, SETTLE_TIME, installer_action};
+
+use crate::Error;
+use crate::output::{file_safe, report_name};
+use crate::report::{AuditReport, ReportDiff};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Directory of timestamped reports.
+#[derive(Debug, Clone)]
+pub struct ReportHistory {
+    dir: PathBuf,
+}
+
+impl ReportHistory {
+    /// History rooted at `dir`. The directory is created on first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ReportHistory { dir: dir.into() }
+    }
+
+    /// Directory the reports are kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Save `report` under its host and timestamp. Returns the file written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be written.
+    pub fn save(&self, report: &AuditReport) -> Result<PathBuf, Error> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.json", report_name(report)));
+        std::fs::write(&path, serde_json::to_string_pretty(report)?)?;
+        Ok(path)
+    }
+
+    /// Save the changes found by a scan, named after the newer report.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be written.
+    pub fn save_diff(&self, report: &AuditReport, diff: &ReportDiff) -> Result<PathBuf, Error> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.diff.json", report_name(report)));
+        std::fs::write(&path, serde_json::to_string_pretty(diff)?)?;
+        Ok(path)
+    }
+
+    /// Most recent stored report of `host`, upgraded to the current schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the directory cannot be read, or
+    /// [`Error::Json`] if the newest file is not a valid report.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sysaudit::report::diff;
+    /// use sysaudit::watch::ReportHistory;
+    /// use sysaudit::{LocalScanner, Scanner};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let history = ReportHistory::new("history");
+    /// let report = LocalScanner::new().audit().await?;
+    /// if let Some(previous) = history.latest(&report.system.host_name)? {
+    ///     if diff(&previous, &report).inventory_changed() {
+    ///         println!("Inventory changed since {}", previous.timestamp);
+    ///     }
+    /// }
+    /// history.save(&report)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn latest(&self, host: &str) -> Result<Option<AuditReport>, Error> {
+        match self.list(host)?.pop() {
+            Some(path) => Ok(Some(AuditReport::from_json_versioned(
+                &std::fs::read_to_string(path)?,
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stored report files of `host`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the directory exists but cannot be read.
+    pub fn list(&self, host: &str) -> Result<Vec<PathBuf>, Error> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let prefix = format!("sysaudit_{}_", file_safe(host));
+        let mut reports = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            // `<YYYYMMDD_HHMMSS>.json` after the prefix, so host `A` does
+            // not pick up the reports of host `A_B`.
+            let matches = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix(prefix.as_str()))
+                .and_then(|rest| rest.strip_suffix(".json"))
+                .is_some_and(|stamp| {
+                    stamp.len() == 15 && stamp.chars().all(|c| c.is_ascii_digit() || c == '_')
+                });
+            if matches {
+                reports.push(path);
+            }
+        }
+        // Timestamps in the names sort chronologically.
+        reports.sort();
+        Ok(reports)
+    }
+}
+
+/// Parse a scan interval such as `90s`, `15m`, `1h` or `1d`. A bare
+/// number is taken as seconds.
+///
+/// # Errors
+///
+/// Returns [`Error::General`] for an unknown unit or a zero interval.
+pub fn parse_interval(text: &str) -> Result<Duration, Error> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let invalid = || {
+        Error::General(format!(
+            "invalid interval '{}': use a number with s, m, h or d (e.g. 15m, 1h)",
+            text
+        ))
+    };
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "" | "s" => number,
+        "m" => number.saturating_mul(60),
+        "h" => number.saturating_mul(3600),
+        "d" => number.saturating_mul(86_400),
+        _ => return Err(invalid()),
+    };
+    if seconds == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_interval("15m").unwrap(), Duration::from_secs(900));
+        assert_eq!(parse_interval("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_interval("2d").unwrap(), Duration::from_secs(172_800));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("1w").is_err());
+        assert!(parse_interval("h").is_err());
+    }
+
+    #[test]
+    fn test_history_latest() {
+        let dir = std::env::temp_dir().join(format!("sysaudit-history-{}", std::process::id()));
+        let history = ReportHistory::new(&dir);
+        assert!(history.latest("SCADA01").unwrap().is_none());
+
+        let mut report =
+            AuditReport::from_json_versioned(include_str!("../tests/fixtures/report.json"))
+                .unwrap();
+        report.system.host_name = "SCADA01".into();
+        for hour in [9, 11, 10] {
+            report.timestamp = Utc.with_ymd_and_hms(2024, 6, 1, hour, 0, 0).unwrap();
+            history.save(&report).unwrap();
+        }
+        let changes = crate::report::diff(&report, &report);
+        history.save_diff(&report, &changes).unwrap();
+        let mut other = report.clone();
+        other.system.host_name = "SCADA01_B".into();
+        other.timestamp = Utc.with_ymd_and_hms(2024, 6, 2, 0, 0, 0).unwrap();
+        history.save(&other).unwrap();
+
+        assert_eq!(history.list("SCADA01").unwrap().len(), 3);
+        let latest = history.latest("SCADA01").unwrap().unwrap();
+        assert_eq!(latest.timestamp.format("%H").to_string(), "11");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
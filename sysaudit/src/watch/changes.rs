@@ -0,0 +1,403 @@
+//! Change notifications that trigger an early rescan.
+//!
+//! A full scan every few minutes is too heavy for a production HMI, and an
+//! hourly one misses what was installed in between until the next round.
+//! A [`ChangeWatcher`] instead waits on the operating system: it asks for
+//! registry-change notifications on the Uninstall keys (both registry views
+//! and the current user's) and subscribes to the `MsiInstaller` events of
+//! the Application log, and reports each as a [`ChangeTrigger`]. Waiting
+//! costs nothing; the periodic scan stays as the fallback for changes
+//! neither source sees.
+//!
+//! An installation touches many keys and logs several events within
+//! seconds, so callers wait for [`SETTLE_TIME`] without a notification
+//! before rescanning.
+
+use std::time::Duration;
+
+/// Quiet time after a change before rescanning, so one installation
+/// causes one scan.
+pub const SETTLE_TIME: Duration = Duration::from_secs(30);
+
+/// `MsiInstaller` event IDs that mean a product was installed, removed or
+/// changed, with what happened.
+const INSTALLER_EVENTS: &[(u16, &str)] = &[
+    (1033, "installed"),
+    (1034, "removed"),
+    (1035, "reconfigured"),
+    (1036, "updated"),
+    (11707, "installed"),
+    (11724, "removed"),
+    (11728, "reconfigured"),
+];
+
+/// Why a watched host should be rescanned before its next interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeTrigger {
+    /// A watched Uninstall key or one of its subkeys changed
+    Registry(String),
+    /// Windows Installer logged an installation, removal or
+    /// reconfiguration
+    Installer {
+        /// `MsiInstaller` event ID
+        event_code: u16,
+        /// Product name, when the event gives it
+        product: Option<String>,
+    },
+}
+
+impl std::fmt::Display for ChangeTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeTrigger::Registry(key) => write!(f, "{} changed", key),
+            ChangeTrigger::Installer {
+                event_code,
+                product,
+            } => write!(
+                f,
+                "{} {} (MsiInstaller event {})",
+                product.as_deref().unwrap_or("A product"),
+                installer_action(*event_code).unwrap_or("changed"),
+                event_code
+            ),
+        }
+    }
+}
+
+/// What an `MsiInstaller` event ID reports, or `None` for events that do
+/// not change the installed software (e.g. 1040, a transaction start).
+///
+/// # Example
+///
+/// ```
+/// use sysaudit::watch::installer_action;
+///
+/// assert_eq!(installer_action(11724), Some("removed"));
+/// assert_eq!(installer_action(1040), None);
+/// ```
+pub fn installer_action(event_code: u16) -> Option<&'static str> {
+    INSTALLER_EVENTS
+        .iter()
+        .find(|(code, _)| *code == event_code)
+        .map(|(_, action)| *action)
+}
+
+/// Product name from an `MsiInstaller` event's insertion strings: the
+/// first string as is for 1033-1036, or the name within "Product: <name>
+/// -- Installation completed successfully." for 117xx.
+#[cfg(any(all(windows, feature = "local"), test))]
+fn installer_product(strings: &[String]) -> Option<String> {
+    let first = strings.first()?.trim();
+    let name = match first.strip_prefix("Product:") {
+        Some(rest) => rest.split(" -- ").next().unwrap_or(rest).trim(),
+        None => first,
+    };
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(all(windows, feature = "local"))]
+pub use watcher::ChangeWatcher;
+
+#[cfg(all(windows, feature = "local"))]
+mod watcher {
+    use super::{ChangeTrigger, installer_action, installer_product};
+    use crate::{Error, WmiContext};
+    use serde::Deserialize;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use windows::Win32::Foundation::{CloseHandle, ERROR_SUCCESS, HANDLE, WAIT_OBJECT_0};
+    use windows::Win32::System::Registry::{
+        HKEY, REG_NOTIFY_CHANGE_LAST_SET, REG_NOTIFY_CHANGE_NAME, RegNotifyChangeKeyValue,
+    };
+    use windows::Win32::System::Threading::{
+        CreateEventW, INFINITE, SetEvent, WaitForMultipleObjects,
+    };
+    use windows::core::PCWSTR;
+    use windows_registry::{CURRENT_USER, Key, LOCAL_MACHINE};
+
+    /// Uninstall keys to watch, as (hive, path).
+    const WATCHED_KEYS: &[(&str, &str)] = &[
+        (
+            "HKLM",
+            r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+        ),
+        (
+            "HKLM",
+            r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+        ),
+        (
+            "HKCU",
+            r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+        ),
+    ];
+
+    /// New `MsiInstaller` entries in the Application log. The event log
+    /// provider raises these itself, so no `WITHIN` polling is needed.
+    const INSTALLER_QUERY: &str = "SELECT * FROM __InstanceCreationEvent \
+                                   WHERE TargetInstance ISA 'Win32_NTLogEvent' \
+                                   AND TargetInstance.Logfile = 'Application' \
+                                   AND TargetInstance.SourceName = 'MsiInstaller'";
+
+    type Notify = Arc<dyn Fn(ChangeTrigger) -> bool + Send + Sync>;
+
+    #[derive(Deserialize)]
+    #[serde(rename = "__InstanceCreationEvent", rename_all = "PascalCase")]
+    struct InstanceCreationEvent {
+        target_instance: NtLogEvent,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "Win32_NTLogEvent", rename_all = "PascalCase")]
+    struct NtLogEvent {
+        event_code: Option<u16>,
+        #[serde(default)]
+        insertion_strings: Option<Vec<String>>,
+    }
+
+    /// A Win32 event object, closed on drop.
+    struct Event(HANDLE);
+
+    // SAFETY: event handles may be waited on and signalled from any thread.
+    unsafe impl Send for Event {}
+    // SAFETY: as above; `SetEvent` and waits need no exclusive access.
+    unsafe impl Sync for Event {}
+
+    impl Event {
+        fn new() -> Result<Self, Error> {
+            // SAFETY: an unnamed auto-reset event with default security.
+            let handle = unsafe { CreateEventW(None, false, false, PCWSTR::null()) }
+                .map_err(|e| Error::General(format!("could not create an event: {}", e)))?;
+            Ok(Event(handle))
+        }
+    }
+
+    impl Drop for Event {
+        fn drop(&mut self) {
+            // SAFETY: the handle was created by `Event::new` and is closed
+            // exactly once.
+            let _ = unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    /// Waits for software changes on background threads and passes each
+    /// to a callback.
+    ///
+    /// The registry thread stops when the watcher is dropped. The installer
+    /// subscription blocks inside WMI and ends at the first event after
+    /// that, without passing it on. Both also end once the callback returns
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::sync::mpsc;
+    /// use sysaudit::watch::ChangeWatcher;
+    ///
+    /// let (tx, changes) = mpsc::channel();
+    /// let _watcher = ChangeWatcher::start(move |trigger| tx.send(trigger).is_ok()).unwrap();
+    /// for trigger in changes {
+    ///     println!("{}", trigger);
+    /// }
+    /// ```
+    pub struct ChangeWatcher {
+        stop: Arc<Event>,
+        stopped: Arc<AtomicBool>,
+    }
+
+    impl ChangeWatcher {
+        /// Start watching. `notify` receives each change and returns
+        /// whether to keep watching.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`Error::General`] if no Uninstall key can be watched, or
+        /// [`Error::Io`] if a watcher thread cannot be started. A failed
+        /// `MsiInstaller` subscription is logged, and registry notifications go on alone.
+        pub fn start(
+            notify: impl Fn(ChangeTrigger) -> bool + Send + Sync + 'static,
+        ) -> Result<Self, Error> {
+            let notify: Notify = Arc::new(notify);
+            let stop = Arc::new(Event::new()?);
+
+            // Keys are opened and armed on the thread that waits on them:
+            // a notification ends with the thread that requested it.
+            let (ready, armed) = std::sync::mpsc::channel();
+            {
+                let stop = Arc::clone(&stop);
+                let notify = Arc::clone(&notify);
+                std::thread::Builder::new()
+                    .name("sysaudit-registry-watch".into())
+                    .spawn(move || watch_registry(&stop, &notify, &ready))?;
+            }
+            armed
+                .recv()
+                .map_err(|_| Error::General("registry watcher exited".to_string()))??;
+
+            let stopped = Arc::new(AtomicBool::new(false));
+            {
+                let stopped = Arc::clone(&stopped);
+                std::thread::Builder::new()
+                    .name("sysaudit-installer-watch".into())
+                    .spawn(move || {
+                        if let Err(e) = watch_installer(&notify, &stopped) {
+                            tracing::warn!(error = %e, "Windows Installer events are not watched");
+                        }
+                    })?;
+            }
+            Ok(ChangeWatcher { stop, stopped })
+        }
+    }
+
+    impl Drop for ChangeWatcher {
+        fn drop(&mut self) {
+            self.stopped.store(true, Ordering::Relaxed);
+            // SAFETY: the event stays open until the last `Arc` is dropped.
+            let _ = unsafe { SetEvent(self.stop.0) };
+        }
+    }
+
+    /// Ask for the next change below `key`, signalling `event`.
+    fn arm(key: &Key, event: &Event) -> Result<(), Error> {
+        // SAFETY: the key and the event stay open while the notification
+        // is pending; both outlive the waiting thread's loop.
+        let status = unsafe {
+            RegNotifyChangeKeyValue(
+                HKEY(key.as_raw()),
+                true,
+                REG_NOTIFY_CHANGE_NAME | REG_NOTIFY_CHANGE_LAST_SET,
+                Some(event.0),
+                true,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return Err(Error::General(format!(
+                "registry notification failed: {:?}",
+                status
+            )));
+        }
+        Ok(())
+    }
+
+    /// Open and arm the Uninstall keys, report the outcome on `ready`,
+    /// then pass each change to `notify` until `stop` is signalled.
+    fn watch_registry(
+        stop: &Event,
+        notify: &Notify,
+        ready: &std::sync::mpsc::Sender<Result<(), Error>>,
+    ) {
+        let mut watched = Vec::new();
+        for (root, path) in WATCHED_KEYS {
+            let hive = if *root == "HKLM" {
+                LOCAL_MACHINE
+            } else {
+                CURRENT_USER
+            };
+            let opened = hive
+                .open(path)
+                .map_err(Error::from)
+                .and_then(|key| Ok((key, Event::new()?)))
+                .and_then(|(key, event)| arm(&key, &event).map(|()| (key, event)));
+            match opened {
+                Ok((key, event)) => watched.push((format!(r"{}\{}", root, path), key, event)),
+                // The 32-bit view is missing on 32-bit Windows.
+                Err(e) => tracing::debug!(key = path, error = %e, "Uninstall key not watched"),
+            }
+        }
+        if watched.is_empty() {
+            let _ = ready.send(Err(Error::General(
+                "no Uninstall key could be watched".to_string(),
+            )));
+            return;
+        }
+        let _ = ready.send(Ok(()));
+        tracing::info!(keys = watched.len(), "Watching Uninstall keys for changes");
+
+        let handles: Vec<HANDLE> = std::iter::once(stop.0)
+            .chain(watched.iter().map(|(_, _, event)| event.0))
+            .collect();
+        loop {
+            // SAFETY: every handle is an open event for the whole loop.
+            let signalled = unsafe { WaitForMultipleObjects(&handles, false, INFINITE) };
+            let index = signalled.0.wrapping_sub(WAIT_OBJECT_0.0) as usize;
+            // Index 0 is `stop`; anything past the keys is a failed wait.
+            let Some((name, key, event)) = index.checked_sub(1).and_then(|i| watched.get(i)) else {
+                break;
+            };
+            if !notify(ChangeTrigger::Registry(name.clone())) {
+                break;
+            }
+            if let Err(e) = arm(key, event) {
+                tracing::warn!(key = %name, error = %e, "Stopped watching Uninstall key");
+                break;
+            }
+        }
+        tracing::debug!("Registry watcher stopped");
+    }
+
+    /// Pass each software-changing `MsiInstaller` event to `notify` until
+    /// `stopped` is set.
+    fn watch_installer(notify: &Notify, stopped: &AtomicBool) -> Result<(), Error> {
+        let wmi = WmiContext::new()?;
+        let events = wmi
+            .cimv2()?
+            .raw_notification::<InstanceCreationEvent>(INSTALLER_QUERY)?;
+        tracing::info!("Watching Windows Installer events");
+        for event in events {
+            if stopped.load(Ordering::Relaxed) {
+                break;
+            }
+            let event = event?.target_instance;
+            let Some(code) = event.event_code.filter(|&c| installer_action(c).is_some()) else {
+                continue;
+            };
+            let trigger = ChangeTrigger::Installer {
+                event_code: code,
+                product: installer_product(event.insertion_strings.as_deref().unwrap_or_default()),
+            };
+            if !notify(trigger) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installer_product() {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            installer_product(&strings(&[
+                "Product: FactoryTalk Linx -- Installation completed successfully."
+            ]))
+            .as_deref(),
+            Some("FactoryTalk Linx")
+        );
+        assert_eq!(
+            installer_product(&strings(&["CodeMeter Runtime Kit v8.10a", "8.10.6245.500"]))
+                .as_deref(),
+            Some("CodeMeter Runtime Kit v8.10a")
+        );
+        assert_eq!(installer_product(&strings(&[" "])), None);
+        assert_eq!(installer_product(&[]), None);
+    }
+
+    #[test]
+    fn test_trigger_display() {
+        let trigger = ChangeTrigger::Installer {
+            event_code: 11707,
+            product: Some("RSLinx Classic".into()),
+        };
+        assert_eq!(
+            trigger.to_string(),
+            "RSLinx Classic installed (MsiInstaller event 11707)"
+        );
+        assert_eq!(
+            ChangeTrigger::Registry(r"HKLM\SOFTWARE\Test".into()).to_string(),
+            r"HKLM\SOFTWARE\Test changed"
+        );
+    }
+}
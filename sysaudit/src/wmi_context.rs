@@ -0,0 +1,117 @@
+//! Shared WMI connection for the local collectors.
+//!
+//! Each WMI-based collector used to initialize COM and connect to
+//! `root\cimv2` on its own. A [`WmiContext`] does both once and hands the
+//! same connection to every collector that runs on its thread: pass one to
+//! the `*_with_wmi` collectors, or let the plain collectors pick up the
+//! calling thread's context through [`WmiContext::with_current`].
+//!
+//! COM objects belong to the thread (apartment) that created them, so a
+//! context is neither `Send` nor `Sync`.
+
+use crate::Error;
+use std::cell::OnceCell;
+use wmi::{COMLibrary, WMIConnection, WMIError};
+
+/// `CoInitializeEx` result when the thread already joined another apartment.
+const RPC_E_CHANGED_MODE: i32 = 0x8001_0106_u32 as i32;
+
+thread_local! {
+    static CURRENT: OnceCell<WmiContext> = const { OnceCell::new() };
+}
+
+/// An initialized COM library and a lazily opened `root\cimv2` connection.
+///
+/// # Example
+///
+/// ```no_run
+/// use sysaudit::{SystemInfo, WindowsService, WindowsUpdate, WmiContext};
+///
+/// let wmi = WmiContext::new().unwrap();
+/// let system = SystemInfo::collect_with_wmi(&wmi).unwrap();
+/// let updates = WindowsUpdate::collect_with_wmi(&wmi).unwrap();
+/// let services = WindowsService::collect_with_wmi(&wmi).unwrap();
+/// println!("{}: {} updates, {} services", system.computer_name, updates.len(), services.len());
+/// ```
+pub struct WmiContext {
+    com: COMLibrary,
+    cimv2: OnceCell<WMIConnection>,
+}
+
+impl WmiContext {
+    /// Initialize COM on this thread.
+    ///
+    /// If the host application already initialized the thread in another
+    /// apartment (a GUI thread in an STA, say), its initialization is
+    /// reused instead of failing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`] if COM cannot be initialized.
+    pub fn new() -> Result<Self, Error> {
+        let com = match COMLibrary::new() {
+            Ok(com) => com,
+            Err(WMIError::HResultError { hres }) if hres == RPC_E_CHANGED_MODE => {
+                tracing::debug!("COM already initialized by the host, reusing it");
+                // SAFETY: RPC_E_CHANGED_MODE means COM is initialized on this
+                // thread, in the host's apartment, for as long as it runs.
+                unsafe { COMLibrary::assume_initialized() }
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self::from_com(com))
+    }
+
+    /// Use a COM library the caller initialized, e.g. with
+    /// `COMLibrary::assume_initialized()` on a thread the host set up.
+    pub fn from_com(com: COMLibrary) -> Self {
+        WmiContext {
+            com,
+            cimv2: OnceCell::new(),
+        }
+    }
+
+    /// The `root\cimv2` connection, opened on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`] if the connection cannot be opened.
+    pub fn cimv2(&self) -> Result<&WMIConnection, Error> {
+        if let Some(con) = self.cimv2.get() {
+            return Ok(con);
+        }
+        let con = WMIConnection::new(self.com)?;
+        Ok(self.cimv2.get_or_init(|| con))
+    }
+
+    /// A connection to another namespace, such as BitLocker's.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`] if the connection cannot be opened.
+    pub fn namespace(&self, path: &str) -> Result<WMIConnection, Error> {
+        Ok(WMIConnection::with_namespace_path(path, self.com)?)
+    }
+
+    /// Run `f` with this thread's context, creating it on first use.
+    ///
+    /// Sequential collectors on one thread share a connection this way, and
+    /// the scanner's worker threads keep theirs across scans.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Wmi`] if COM cannot be initialized, or what `f`
+    /// returns.
+    pub fn with_current<T>(f: impl FnOnce(&WmiContext) -> Result<T, Error>) -> Result<T, Error> {
+        CURRENT.with(|current| {
+            let wmi = match current.get() {
+                Some(wmi) => wmi,
+                None => {
+                    let wmi = WmiContext::new()?;
+                    current.get_or_init(|| wmi)
+                }
+            };
+            f(wmi)
+        })
+    }
+}